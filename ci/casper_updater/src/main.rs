@@ -250,6 +250,9 @@ fn main() {
     let json_rpc = Package::cargo("json_rpc", &*regex_data::json_rpc::DEPENDENT_FILES);
     json_rpc.update();
 
+    let light_client = Package::cargo("light_client", &*regex_data::light_client::DEPENDENT_FILES);
+    light_client.update();
+
     let node = Package::cargo("node", &*regex_data::node::DEPENDENT_FILES);
     node.update();
 
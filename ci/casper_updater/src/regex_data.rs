@@ -37,6 +37,11 @@ pub mod types {
                 Regex::new(r#"(?m)(^casper-types = \{[^\}]*version = )"(?:[^"]+)"#).unwrap(),
                 replacement,
             ),
+            DependentFile::new(
+                "light_client/Cargo.toml",
+                Regex::new(r#"(?m)(^casper-types = \{[^\}]*version = )"(?:[^"]+)"#).unwrap(),
+                replacement,
+            ),
             DependentFile::new(
                 "node/Cargo.toml",
                 Regex::new(r#"(?m)(^casper-types = \{[^\}]*version = )"(?:[^"]+)"#).unwrap(),
@@ -79,6 +84,11 @@ pub mod hashing {
                 Regex::new(r#"(?m)(^casper-hashing = \{[^\}]*version = )"(?:[^"]+)"#).unwrap(),
                 replacement,
             ),
+            DependentFile::new(
+                "light_client/Cargo.toml",
+                Regex::new(r#"(?m)(^casper-hashing = \{[^\}]*version = )"(?:[^"]+)"#).unwrap(),
+                replacement,
+            ),
             DependentFile::new(
                 "node/Cargo.toml",
                 Regex::new(r#"(?m)(^casper-hashing = \{[^\}]*version = )"(?:[^"]+)"#).unwrap(),
@@ -112,6 +122,12 @@ pub mod execution_engine {
                         .unwrap(),
                     replacement,
                 ),
+                DependentFile::new(
+                    "light_client/Cargo.toml",
+                    Regex::new(r#"(?m)(^casper-execution-engine = \{[^\}]*version = )"(?:[^"]+)"#)
+                        .unwrap(),
+                    replacement,
+                ),
                 DependentFile::new(
                     "node/Cargo.toml",
                     Regex::new(r#"(?m)(^casper-execution-engine = \{[^\}]*version = )"(?:[^"]+)"#)
@@ -186,6 +202,28 @@ pub mod json_rpc {
     });
 }
 
+pub mod light_client {
+    use super::*;
+
+    pub static DEPENDENT_FILES: Lazy<Vec<DependentFile>> = Lazy::new(|| {
+        vec![
+            DependentFile::new(
+                "light_client/Cargo.toml",
+                MANIFEST_VERSION_REGEX.clone(),
+                replacement,
+            ),
+            DependentFile::new(
+                "light_client/src/lib.rs",
+                Regex::new(
+                    r#"(?m)(#!\[doc\(html_root_url = "https://docs.rs/casper-light-client)/(?:[^"]+)"#,
+                )
+                .unwrap(),
+                replacement_with_slash,
+            ),
+        ]
+    });
+}
+
 pub mod node {
     use super::*;
 
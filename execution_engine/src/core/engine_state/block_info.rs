@@ -0,0 +1,61 @@
+//! Information about the block in which deploys are executed.
+use casper_hashing::Digest;
+use casper_types::{BlockTime, EraId};
+
+/// Properties of the block in which deploys are being executed, made available to contracts via
+/// host functions.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BlockInfo {
+    state_root_hash: Digest,
+    block_time: BlockTime,
+    block_height: u64,
+    era_id: EraId,
+    parent_block_hash: Digest,
+}
+
+impl BlockInfo {
+    /// Creates a new `BlockInfo`.
+    pub fn new(
+        state_root_hash: Digest,
+        block_time: BlockTime,
+        block_height: u64,
+        era_id: EraId,
+        parent_block_hash: Digest,
+    ) -> Self {
+        BlockInfo {
+            state_root_hash,
+            block_time,
+            block_height,
+            era_id,
+            parent_block_hash,
+        }
+    }
+
+    /// Returns the state root hash of the global state on top of which the deploy is executed.
+    ///
+    /// This is the deploy's pre-state, which includes the effects of all preceding deploys in the
+    /// same block, rather than the pre-state of the block as a whole.
+    pub fn state_root_hash(&self) -> Digest {
+        self.state_root_hash
+    }
+
+    /// Returns the block time.
+    pub fn block_time(&self) -> BlockTime {
+        self.block_time
+    }
+
+    /// Returns the height of the block.
+    pub fn block_height(&self) -> u64 {
+        self.block_height
+    }
+
+    /// Returns the era in which the block was created.
+    pub fn era_id(&self) -> EraId {
+        self.era_id
+    }
+
+    /// Returns the hash of the parent of the block.
+    pub fn parent_block_hash(&self) -> Digest {
+        self.parent_block_hash
+    }
+}
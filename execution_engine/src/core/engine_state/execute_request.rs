@@ -2,9 +2,9 @@
 use std::mem;
 
 use casper_hashing::Digest;
-use casper_types::{ProtocolVersion, PublicKey, SecretKey};
+use casper_types::{BlockTime, EraId, ProtocolVersion, PublicKey, SecretKey};
 
-use super::deploy_item::DeployItem;
+use super::{block_info::BlockInfo, deploy_item::DeployItem};
 
 /// Represents an execution request that can contain multiple deploys.
 #[derive(Debug)]
@@ -19,16 +19,26 @@ pub struct ExecuteRequest {
     pub protocol_version: ProtocolVersion,
     /// The owner of the node that proposed the block containing this request.
     pub proposer: PublicKey,
+    /// Height of the block containing this request.
+    pub block_height: u64,
+    /// Era in which the block containing this request was created.
+    pub era_id: EraId,
+    /// Hash of the parent of the block containing this request.
+    pub parent_block_hash: Digest,
 }
 
 impl ExecuteRequest {
     /// Creates new execute request.
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         parent_state_hash: Digest,
         block_time: u64,
         deploys: Vec<DeployItem>,
         protocol_version: ProtocolVersion,
         proposer: PublicKey,
+        block_height: u64,
+        era_id: EraId,
+        parent_block_hash: Digest,
     ) -> Self {
         Self {
             parent_state_hash,
@@ -36,6 +46,9 @@ impl ExecuteRequest {
             deploys,
             protocol_version,
             proposer,
+            block_height,
+            era_id,
+            parent_block_hash,
         }
     }
 
@@ -48,6 +61,17 @@ impl ExecuteRequest {
     pub fn deploys(&self) -> &Vec<DeployItem> {
         &self.deploys
     }
+
+    /// Returns the properties of the block containing this request.
+    pub fn block_info(&self) -> BlockInfo {
+        BlockInfo::new(
+            self.parent_state_hash,
+            BlockTime::new(self.block_time),
+            self.block_height,
+            self.era_id,
+            self.parent_block_hash,
+        )
+    }
 }
 
 impl Default for ExecuteRequest {
@@ -61,6 +85,9 @@ impl Default for ExecuteRequest {
             deploys: vec![],
             protocol_version: Default::default(),
             proposer,
+            block_height: 0,
+            era_id: EraId::default(),
+            parent_block_hash: Digest::default(),
         }
     }
 }
@@ -3,7 +3,8 @@
 use std::collections::VecDeque;
 
 use casper_types::{
-    bytesrepr::FromBytes, CLTyped, CLValue, Gas, Key, Motes, StoredValue, TransferAddr,
+    bytesrepr::FromBytes, CLTyped, CLValue, ContractMessage, Gas, Key, Motes, StoredValue,
+    TransferAddr,
 };
 
 use super::error;
@@ -58,6 +59,8 @@ pub enum ExecutionResult {
         cost: Gas,
         /// Journal of execution.
         execution_journal: ExecutionJournal,
+        /// List of messages emitted by contracts during execution.
+        messages: Vec<ContractMessage>,
     },
 }
 
@@ -67,6 +70,7 @@ impl Default for ExecutionResult {
             execution_journal: Default::default(),
             transfers: Default::default(),
             cost: Default::default(),
+            messages: Default::default(),
         }
     }
 }
@@ -144,6 +148,16 @@ impl ExecutionResult {
         }
     }
 
+    /// Returns list of emitted messages.
+    ///
+    /// Messages are discarded on failure, so this is always empty for the failure variant.
+    pub fn messages(&self) -> &[ContractMessage] {
+        match self {
+            ExecutionResult::Failure { .. } => &[],
+            ExecutionResult::Success { messages, .. } => messages,
+        }
+    }
+
     /// The journal of transforms regardless of variant.
     pub fn execution_journal(&self) -> &ExecutionJournal {
         match self {
@@ -176,11 +190,13 @@ impl ExecutionResult {
             ExecutionResult::Success {
                 transfers,
                 execution_journal,
+                messages,
                 ..
             } => ExecutionResult::Success {
                 transfers,
                 cost,
                 execution_journal,
+                messages,
             },
         }
     }
@@ -205,11 +221,13 @@ impl ExecutionResult {
             ExecutionResult::Success {
                 cost,
                 execution_journal,
+                messages,
                 ..
             } => ExecutionResult::Success {
                 transfers,
                 cost,
                 execution_journal,
+                messages,
             },
         }
     }
@@ -235,10 +253,12 @@ impl ExecutionResult {
                 transfers,
                 cost,
                 execution_journal: _,
+                messages,
             } => ExecutionResult::Success {
                 transfers,
                 cost,
                 execution_journal,
+                messages,
             },
         }
     }
@@ -349,10 +369,12 @@ impl From<&ExecutionResult> for casper_types::ExecutionResult {
                 transfers,
                 cost,
                 execution_journal,
+                messages,
             } => casper_types::ExecutionResult::Success {
                 effect: execution_journal.into(),
                 transfers: transfers.clone(),
                 cost: cost.value(),
+                messages: messages.clone(),
             },
             ExecutionResult::Failure {
                 error,
@@ -376,10 +398,12 @@ impl From<ExecutionResult> for casper_types::ExecutionResult {
                 transfers,
                 cost,
                 execution_journal,
+                messages,
             } => casper_types::ExecutionResult::Success {
                 effect: execution_journal.into(),
                 transfers,
                 cost: cost.value(),
+                messages,
             },
             ExecutionResult::Failure {
                 error,
@@ -486,11 +510,13 @@ impl ExecutionResultBuilder {
         let mut transfers = self.transfers();
         let cost = self.total_cost();
 
-        let mut journal = match self.payment_execution_result {
+        let (mut journal, mut messages) = match self.payment_execution_result {
             Some(result @ ExecutionResult::Failure { .. }) => return Ok(result),
             Some(ExecutionResult::Success {
-                execution_journal, ..
-            }) => execution_journal,
+                execution_journal,
+                messages,
+                ..
+            }) => (execution_journal, messages),
             None => return Err(ExecutionResultBuilderError::MissingPaymentExecutionResult),
         };
 
@@ -507,8 +533,13 @@ impl ExecutionResultBuilder {
                 transfers = session_transfers;
             }
             Some(ExecutionResult::Success {
-                execution_journal, ..
-            }) => journal.extend(execution_journal.into_iter()),
+                execution_journal,
+                messages: session_messages,
+                ..
+            }) => {
+                journal.extend(execution_journal.into_iter());
+                messages.extend(session_messages);
+            }
             None => return Err(ExecutionResultBuilderError::MissingSessionExecutionResult),
         };
 
@@ -530,6 +561,7 @@ impl ExecutionResultBuilder {
                 transfers,
                 cost,
                 execution_journal: journal,
+                messages,
             }),
             Some(error) => Ok(ExecutionResult::Failure {
                 error,
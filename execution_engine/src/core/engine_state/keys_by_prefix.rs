@@ -0,0 +1,89 @@
+//! Support for enumerating global state entries by key prefix.
+use casper_hashing::Digest;
+use casper_types::{Key, KeyTag, StoredValue};
+
+/// Identifies the set of global state entries to be enumerated.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeyPrefix {
+    /// All entries whose keys have the given tag, e.g. all `Key::Bid`s.
+    Tag(KeyTag),
+}
+
+impl KeyPrefix {
+    /// Returns the prefix of the serialized keys of all the entries identified by `self`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            KeyPrefix::Tag(key_tag) => vec![*key_tag as u8],
+        }
+    }
+}
+
+/// Represents a request to enumerate a page of global state entries by key prefix.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GetKeysByPrefixRequest {
+    state_hash: Digest,
+    key_prefix: KeyPrefix,
+    start_after: Option<Key>,
+    limit: usize,
+    include_values: bool,
+}
+
+impl GetKeysByPrefixRequest {
+    /// Creates new request.  A `limit` of zero is treated as one, so that every request makes
+    /// progress.
+    pub fn new(
+        state_hash: Digest,
+        key_prefix: KeyPrefix,
+        start_after: Option<Key>,
+        limit: usize,
+        include_values: bool,
+    ) -> Self {
+        GetKeysByPrefixRequest {
+            state_hash,
+            key_prefix,
+            start_after,
+            limit: limit.max(1),
+            include_values,
+        }
+    }
+
+    /// Returns state root hash.
+    pub fn state_hash(&self) -> Digest {
+        self.state_hash
+    }
+
+    /// Returns the prefix identifying the entries to be enumerated.
+    pub fn key_prefix(&self) -> &KeyPrefix {
+        &self.key_prefix
+    }
+
+    /// Returns the key after which enumeration starts, if any.
+    pub fn start_after(&self) -> Option<&Key> {
+        self.start_after.as_ref()
+    }
+
+    /// Returns the maximum number of entries to be returned.
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// Returns whether the values of the entries are to be returned along with their keys.
+    pub fn include_values(&self) -> bool {
+        self.include_values
+    }
+}
+
+/// Represents a result of a `get_keys_by_prefix` request.
+#[derive(Debug)]
+pub enum GetKeysByPrefixResult {
+    /// Invalid state root hash.
+    RootNotFound,
+    /// Contains a page of matching entries, in ascending order of their serialized keys.
+    Success {
+        /// The keys of the matching entries, with their values if requested.
+        entries: Vec<(Key, Option<StoredValue>)>,
+        /// The key after which a subsequent request should start, or `None` if there are no
+        /// further entries.
+        continue_after: Option<Key>,
+    },
+}
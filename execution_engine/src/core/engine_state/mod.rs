@@ -1,5 +1,6 @@
 //!  This module contains all the execution related code.
 pub mod balance;
+pub mod block_info;
 pub mod chainspec_registry;
 pub mod deploy_item;
 pub mod engine_config;
@@ -11,6 +12,7 @@ pub mod execution_effect;
 pub mod execution_result;
 pub mod genesis;
 pub mod get_bids;
+pub mod keys_by_prefix;
 pub mod op;
 pub mod query;
 pub mod run_genesis_request;
@@ -47,12 +49,13 @@ use casper_types::{
         mint::{self, ROUND_SEIGNIORAGE_RATE_KEY},
         AUCTION, HANDLE_PAYMENT, MINT, STANDARD_PAYMENT,
     },
-    AccessRights, ApiError, BlockTime, CLValue, ContractHash, DeployHash, DeployInfo, Gas, Key,
-    KeyTag, Motes, Phase, ProtocolVersion, PublicKey, RuntimeArgs, StoredValue, URef, U512,
+    AccessRights, ApiError, CLValue, ContractHash, DeployHash, DeployInfo, Gas, Key, KeyTag, Motes,
+    Phase, ProtocolVersion, PublicKey, RuntimeArgs, StoredValue, URef, U512,
 };
 
 pub use self::{
     balance::{BalanceRequest, BalanceResult},
+    block_info::BlockInfo,
     chainspec_registry::ChainspecRegistry,
     deploy_item::DeployItem,
     engine_config::{EngineConfig, DEFAULT_MAX_QUERY_DEPTH, DEFAULT_MAX_RUNTIME_CALL_STACK_HEIGHT},
@@ -64,6 +67,7 @@ pub use self::{
     execution_result::{ExecutionResult, ForcedTransferResult},
     genesis::{ExecConfig, GenesisAccount, GenesisConfig, GenesisSuccess},
     get_bids::{GetBidsRequest, GetBidsResult},
+    keys_by_prefix::{GetKeysByPrefixRequest, GetKeysByPrefixResult, KeyPrefix},
     query::{QueryRequest, QueryResult},
     run_genesis_request::RunGenesisRequest,
     step::{RewardItem, SlashItem, StepError, StepRequest, StepSuccess},
@@ -138,6 +142,42 @@ impl EngineState<LmdbGlobalState> {
         Ok(())
     }
 
+    /// Enumerates a page of the global state entries identified by the request's key prefix.
+    ///
+    /// The values of the entries are only read if the request includes them.
+    pub fn get_keys_by_prefix(
+        &self,
+        correlation_id: CorrelationId,
+        request: GetKeysByPrefixRequest,
+    ) -> Result<GetKeysByPrefixResult, Error> {
+        let mut entries = Vec::new();
+        let mut continue_after = None;
+
+        let root_found = self.state.visit_entries_with_prefix(
+            correlation_id,
+            request.state_hash(),
+            &request.key_prefix().to_bytes(),
+            request.start_after(),
+            request.include_values(),
+            |key, maybe_value| {
+                if entries.len() >= request.limit() {
+                    continue_after = entries.last().map(|(key, _)| *key);
+                    return false;
+                }
+                entries.push((key, maybe_value));
+                true
+            },
+        )?;
+
+        if !root_found {
+            return Ok(GetKeysByPrefixResult::RootNotFound);
+        }
+        Ok(GetKeysByPrefixResult::Success {
+            entries,
+            continue_after,
+        })
+    }
+
     /// Provide a local cached-only version of engine-state.
     pub fn get_scratch_engine_state(&self) -> EngineState<ScratchGlobalState> {
         EngineState {
@@ -152,9 +192,32 @@ impl EngineState<LmdbGlobalState> {
         state_root_hash: Digest,
         scratch_global_state: ScratchGlobalState,
     ) -> Result<Digest, Error> {
-        let stored_values = scratch_global_state.into_inner();
+        let (stored_values, keys_to_prune) = scratch_global_state.into_inner();
         self.state
-            .put_stored_values(CorrelationId::new(), state_root_hash, stored_values)
+            .put_stored_values(
+                CorrelationId::new(),
+                state_root_hash,
+                stored_values,
+                keys_to_prune,
+            )
+            .map_err(Into::into)
+    }
+
+    /// Returns the state root hash which writing state cached in an
+    /// EngineState<ScratchEngineState> to LMDB would result in, without writing it.
+    pub fn scratch_state_root_hash(
+        &self,
+        state_root_hash: Digest,
+        scratch_global_state: ScratchGlobalState,
+    ) -> Result<Digest, Error> {
+        let (stored_values, keys_to_prune) = scratch_global_state.into_inner();
+        self.state
+            .compute_stored_values_root(
+                CorrelationId::new(),
+                state_root_hash,
+                stored_values,
+                keys_to_prune,
+            )
             .map_err(Into::into)
     }
 }
@@ -507,7 +570,7 @@ where
                     &executor,
                     exec_request.protocol_version,
                     exec_request.parent_state_hash,
-                    BlockTime::new(exec_request.block_time),
+                    exec_request.block_info(),
                     deploy_item,
                     exec_request.proposer.clone(),
                 ),
@@ -516,7 +579,7 @@ where
                     &executor,
                     exec_request.protocol_version,
                     exec_request.parent_state_hash,
-                    BlockTime::new(exec_request.block_time),
+                    exec_request.block_info(),
                     deploy_item,
                     exec_request.proposer.clone(),
                 ),
@@ -595,7 +658,7 @@ where
         executor: &Executor,
         protocol_version: ProtocolVersion,
         prestate_hash: Digest,
-        blocktime: BlockTime,
+        block_info: BlockInfo,
         deploy_item: DeployItem,
         proposer: PublicKey,
     ) -> Result<ExecutionResult, Error> {
@@ -749,7 +812,7 @@ where
                             RuntimeArgs::new(), // mint create takes no arguments
                             &account,
                             authorization_keys.clone(),
-                            blocktime,
+                            block_info,
                             deploy_item.deploy_hash,
                             gas_limit,
                             protocol_version,
@@ -838,7 +901,7 @@ where
                     RuntimeArgs::default(),
                     &account,
                     authorization_keys.clone(),
-                    blocktime,
+                    block_info,
                     deploy_item.deploy_hash,
                     gas_limit,
                     protocol_version,
@@ -881,7 +944,7 @@ where
                     runtime_args,
                     &account,
                     authorization_keys.clone(),
-                    blocktime,
+                    block_info,
                     deploy_item.deploy_hash,
                     gas_limit,
                     protocol_version,
@@ -967,7 +1030,7 @@ where
                 runtime_args,
                 &account,
                 authorization_keys.clone(),
-                blocktime,
+                block_info,
                 deploy_item.deploy_hash,
                 gas_limit,
                 protocol_version,
@@ -1031,7 +1094,7 @@ where
                     handle_payment_args,
                     &system_account,
                     authorization_keys,
-                    blocktime,
+                    block_info,
                     deploy_item.deploy_hash,
                     gas_limit,
                     protocol_version,
@@ -1096,7 +1159,7 @@ where
         executor: &Executor,
         protocol_version: ProtocolVersion,
         prestate_hash: Digest,
-        blocktime: BlockTime,
+        block_info: BlockInfo,
         deploy_item: DeployItem,
         proposer: PublicKey,
     ) -> Result<ExecutionResult, Error> {
@@ -1240,7 +1303,7 @@ where
                     &mut payment_named_keys,
                     payment_access_rights,
                     authorization_keys.clone(),
-                    blocktime,
+                    block_info,
                     deploy_hash,
                     payment_gas_limit,
                     protocol_version,
@@ -1270,7 +1333,7 @@ where
                     &mut payment_named_keys,
                     payment_access_rights,
                     authorization_keys.clone(),
-                    blocktime,
+                    block_info,
                     deploy_hash,
                     payment_gas_limit,
                     protocol_version,
@@ -1465,7 +1528,7 @@ where
                 &mut session_named_keys,
                 session_access_rights,
                 authorization_keys.clone(),
-                blocktime,
+                block_info,
                 deploy_hash,
                 session_gas_limit,
                 protocol_version,
@@ -1607,7 +1670,7 @@ where
                     handle_payment_args,
                     &system_account,
                     authorization_keys,
-                    blocktime,
+                    block_info,
                     deploy_hash,
                     gas_limit,
                     protocol_version,
@@ -1861,7 +1924,7 @@ where
             reward_args,
             &virtual_system_account,
             authorization_keys.clone(),
-            BlockTime::default(),
+            BlockInfo::default(),
             deploy_hash,
             gas_limit,
             step_request.protocol_version,
@@ -1895,7 +1958,7 @@ where
                     slash_args,
                     &virtual_system_account,
                     authorization_keys.clone(),
-                    BlockTime::default(),
+                    BlockInfo::default(),
                     deploy_hash,
                     gas_limit,
                     step_request.protocol_version,
@@ -1934,7 +1997,7 @@ where
             run_auction_args,
             &virtual_system_account,
             authorization_keys,
-            BlockTime::default(),
+            BlockInfo::default(),
             deploy_hash,
             gas_limit,
             step_request.protocol_version,
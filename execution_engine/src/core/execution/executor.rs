@@ -5,15 +5,15 @@ use casper_types::{
     bytesrepr::FromBytes,
     contracts::NamedKeys,
     system::{auction, handle_payment, mint, AUCTION, HANDLE_PAYMENT, MINT},
-    BlockTime, CLTyped, ContextAccessRights, DeployHash, EntryPointType, Gas, Key, Phase,
-    ProtocolVersion, RuntimeArgs, StoredValue, U512,
+    CLTyped, ContextAccessRights, DeployHash, EntryPointType, Gas, Key, Phase, ProtocolVersion,
+    RuntimeArgs, StoredValue, U512,
 };
 
 use crate::{
     core::{
         engine_state::{
-            executable_deploy_item::ExecutionKind, execution_result::ExecutionResult, EngineConfig,
-            ExecError,
+            executable_deploy_item::ExecutionKind, execution_result::ExecutionResult, BlockInfo,
+            EngineConfig, ExecError,
         },
         execution::{address_generator::AddressGenerator, Error},
         runtime::{Runtime, RuntimeStack},
@@ -56,7 +56,7 @@ impl Executor {
         named_keys: &mut NamedKeys,
         access_rights: ContextAccessRights,
         authorization_keys: BTreeSet<AccountHash>,
-        blocktime: BlockTime,
+        block_info: BlockInfo,
         deploy_hash: DeployHash,
         gas_limit: Gas,
         protocol_version: ProtocolVersion,
@@ -89,7 +89,7 @@ impl Executor {
             Key::from(account.account_hash()),
             account,
             authorization_keys,
-            blocktime,
+            block_info,
             deploy_hash,
             gas_limit,
             address_generator,
@@ -122,6 +122,7 @@ impl Executor {
                 execution_journal: runtime.context().execution_journal(),
                 transfers: runtime.context().transfers().to_owned(),
                 cost: runtime.context().gas_counter(),
+                messages: runtime.context().messages().to_owned(),
             },
             Err(error) => ExecutionResult::Failure {
                 error: error.into(),
@@ -142,7 +143,7 @@ impl Executor {
         payment_named_keys: &mut NamedKeys,
         access_rights: ContextAccessRights,
         authorization_keys: BTreeSet<AccountHash>,
-        blocktime: BlockTime,
+        block_info: BlockInfo,
         deploy_hash: DeployHash,
         payment_gas_limit: Gas,
         protocol_version: ProtocolVersion,
@@ -175,7 +176,7 @@ impl Executor {
             payment_base_key,
             account,
             authorization_keys,
-            blocktime,
+            block_info,
             deploy_hash,
             payment_gas_limit,
             address_generator,
@@ -197,6 +198,7 @@ impl Executor {
                 execution_journal: runtime.context().execution_journal(),
                 transfers: runtime.context().transfers().to_owned(),
                 cost: runtime.context().gas_counter(),
+                messages: runtime.context().messages().to_owned(),
             },
             Err(error) => ExecutionResult::Failure {
                 execution_journal,
@@ -216,7 +218,7 @@ impl Executor {
         runtime_args: RuntimeArgs,
         account: &Account,
         authorization_keys: BTreeSet<AccountHash>,
-        blocktime: BlockTime,
+        block_info: BlockInfo,
         deploy_hash: DeployHash,
         gas_limit: Gas,
         protocol_version: ProtocolVersion,
@@ -294,7 +296,7 @@ impl Executor {
             base_key,
             account,
             authorization_keys,
-            blocktime,
+            block_info,
             deploy_hash,
             gas_limit,
             address_generator,
@@ -321,6 +323,7 @@ impl Executor {
                     execution_journal: runtime.context().execution_journal(),
                     transfers: runtime.context().transfers().to_owned(),
                     cost: runtime.context().gas_counter(),
+                    messages: runtime.context().messages().to_owned(),
                 }
                 .take_with_ret(ret),
                 Err(error) => ExecutionResult::Failure {
@@ -352,7 +355,7 @@ impl Executor {
         base_key: Key,
         account: &'a Account,
         authorization_keys: BTreeSet<AccountHash>,
-        blocktime: BlockTime,
+        block_info: BlockInfo,
         deploy_hash: DeployHash,
         gas_limit: Gas,
         address_generator: Rc<RefCell<AddressGenerator>>,
@@ -378,7 +381,7 @@ impl Executor {
             authorization_keys,
             account,
             base_key,
-            blocktime,
+            block_info,
             deploy_hash,
             gas_limit,
             gas_counter,
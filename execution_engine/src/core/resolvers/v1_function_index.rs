@@ -59,6 +59,15 @@ pub(crate) enum FunctionIndex {
     LoadAuthorizationKeys,
     RandomBytes,
     DictionaryReadFuncIndex,
+    EmitMessageIndex,
+    VerifySignatureIndex,
+    GetBlockHeightIndex,
+    GetEraIdIndex,
+    GetProtocolVersionIndex,
+    GetParentBlockHashIndex,
+    GetStateRootHashIndex,
+    RemoveURefIndex,
+    RemoveDictionaryItemIndex,
 }
 
 impl From<FunctionIndex> for usize {
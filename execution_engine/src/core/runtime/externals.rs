@@ -1060,6 +1060,133 @@ where
 
                 Ok(Some(RuntimeValue::I32(0)))
             }
+
+            FunctionIndex::EmitMessageIndex => {
+                // args(0) = pointer to the serialized topic in Wasm memory
+                // args(1) = size of the serialized topic
+                // args(2) = pointer to the serialized payload `CLValue` in Wasm memory
+                // args(3) = size of the serialized payload
+                let (topic_ptr, topic_size, payload_ptr, payload_size) = Args::parse(args)?;
+                self.charge_host_function_call(
+                    &host_function_costs.emit_message,
+                    [topic_ptr, topic_size, payload_ptr, payload_size],
+                )?;
+                let result = self.emit_message(topic_ptr, topic_size, payload_ptr, payload_size)?;
+                Ok(Some(RuntimeValue::I32(api_error::i32_from(result))))
+            }
+
+            FunctionIndex::VerifySignatureIndex => {
+                // args(0) = pointer to the message in Wasm memory
+                // args(1) = size of the message
+                // args(2) = pointer to the serialized `Signature` in Wasm memory
+                // args(3) = size of the serialized `Signature`
+                // args(4) = pointer to the serialized `PublicKey` in Wasm memory
+                // args(5) = size of the serialized `PublicKey`
+                let (
+                    message_ptr,
+                    message_size,
+                    signature_ptr,
+                    signature_size,
+                    public_key_ptr,
+                    public_key_size,
+                ) = Args::parse(args)?;
+                self.charge_host_function_call(
+                    &host_function_costs.verify_signature,
+                    [
+                        message_ptr,
+                        message_size,
+                        signature_ptr,
+                        signature_size,
+                        public_key_ptr,
+                        public_key_size,
+                    ],
+                )?;
+                let result = self.verify_signature(
+                    message_ptr,
+                    message_size,
+                    signature_ptr,
+                    signature_size,
+                    public_key_ptr,
+                    public_key_size,
+                )?;
+                Ok(Some(RuntimeValue::I32(api_error::i32_from(result))))
+            }
+
+            FunctionIndex::GetBlockHeightIndex => {
+                // args(0) = pointer to Wasm memory where to write.
+                let (dest_ptr,) = Args::parse(args)?;
+                self.charge_host_function_call(&host_function_costs.get_block_height, [dest_ptr])?;
+                self.get_block_height(dest_ptr)?;
+                Ok(None)
+            }
+
+            FunctionIndex::GetEraIdIndex => {
+                // args(0) = pointer to Wasm memory where to write.
+                let (dest_ptr,) = Args::parse(args)?;
+                self.charge_host_function_call(&host_function_costs.get_era_id, [dest_ptr])?;
+                self.get_era_id(dest_ptr)?;
+                Ok(None)
+            }
+
+            FunctionIndex::GetProtocolVersionIndex => {
+                // args(0) = pointer to Wasm memory where to write.
+                let (dest_ptr,) = Args::parse(args)?;
+                self.charge_host_function_call(
+                    &host_function_costs.get_protocol_version,
+                    [dest_ptr],
+                )?;
+                self.get_protocol_version(dest_ptr)?;
+                Ok(None)
+            }
+
+            FunctionIndex::GetParentBlockHashIndex => {
+                // args(0) = pointer to Wasm memory where to write.
+                let (dest_ptr,) = Args::parse(args)?;
+                self.charge_host_function_call(
+                    &host_function_costs.get_parent_block_hash,
+                    [dest_ptr],
+                )?;
+                self.get_parent_block_hash(dest_ptr)?;
+                Ok(None)
+            }
+
+            FunctionIndex::GetStateRootHashIndex => {
+                // args(0) = pointer to Wasm memory where to write.
+                let (dest_ptr,) = Args::parse(args)?;
+                self.charge_host_function_call(
+                    &host_function_costs.get_state_root_hash,
+                    [dest_ptr],
+                )?;
+                self.get_state_root_hash(dest_ptr)?;
+                Ok(None)
+            }
+
+            FunctionIndex::RemoveURefIndex => {
+                // args(0) = pointer to uref in Wasm memory
+                // args(1) = size of uref in Wasm memory
+                let (uref_ptr, uref_size) = Args::parse(args)?;
+                self.charge_host_function_call(
+                    &host_function_costs.remove_uref,
+                    [uref_ptr, uref_size],
+                )?;
+                let ret = self.remove_uref(uref_ptr, uref_size)?;
+                Ok(Some(RuntimeValue::I32(api_error::i32_from(ret))))
+            }
+
+            FunctionIndex::RemoveDictionaryItemIndex => {
+                // args(0) = pointer to uref in Wasm memory
+                // args(1) = size of uref in Wasm memory
+                // args(2) = pointer to key bytes pointer in Wasm memory
+                // args(3) = pointer to key bytes size in Wasm memory
+                let (uref_ptr, uref_size, key_bytes_ptr, key_bytes_size) = Args::parse(args)?;
+                self.charge_host_function_call(
+                    &host_function_costs.remove_dictionary_item,
+                    [uref_ptr, uref_size, key_bytes_ptr, key_bytes_size],
+                )?;
+                let ret =
+                    self.dictionary_remove(uref_ptr, uref_size, key_bytes_ptr, key_bytes_size)?;
+                Ok(Some(RuntimeValue::I32(api_error::i32_from(ret))))
+            }
         }
     }
 }
@@ -28,6 +28,7 @@ use casper_types::{
         DisabledVersions, EntryPoint, EntryPointAccess, EntryPoints, Group, Groups, NamedKeys,
         DEFAULT_ENTRY_POINT_NAME,
     },
+    crypto,
     system::{
         self,
         auction::{self, EraInfo},
@@ -36,8 +37,9 @@ use casper_types::{
     },
     AccessRights, ApiError, CLTyped, CLValue, ContextAccessRights, ContractHash,
     ContractPackageHash, ContractVersionKey, ContractWasm, DeployHash, EntryPointType, EraId, Gas,
-    GrantedAccess, Key, NamedArg, Parameter, Phase, PublicKey, RuntimeArgs, StoredValue, Transfer,
-    TransferResult, TransferredTo, URef, DICTIONARY_ITEM_KEY_MAX_LENGTH, U512,
+    GrantedAccess, Key, NamedArg, Parameter, Phase, PublicKey, RuntimeArgs, Signature, StoredValue,
+    Transfer, TransferResult, TransferredTo, URef, CONTRACT_MESSAGE_TOPIC_MAX_LENGTH,
+    DICTIONARY_ITEM_KEY_MAX_LENGTH, U512,
 };
 
 use crate::{
@@ -403,6 +405,45 @@ where
             .map_err(|e| Error::Interpreter(e.into()).into())
     }
 
+    /// Writes the height of the current block to dest_ptr in Wasm memory.
+    fn get_block_height(&self, dest_ptr: u32) -> Result<(), Trap> {
+        let block_height = self.context.block_info().block_height();
+        self.write_block_info_value(dest_ptr, block_height)
+    }
+
+    /// Writes the era id of the current block to dest_ptr in Wasm memory.
+    fn get_era_id(&self, dest_ptr: u32) -> Result<(), Trap> {
+        let era_id = self.context.block_info().era_id();
+        self.write_block_info_value(dest_ptr, era_id)
+    }
+
+    /// Writes the current protocol version to dest_ptr in Wasm memory.
+    fn get_protocol_version(&self, dest_ptr: u32) -> Result<(), Trap> {
+        let protocol_version = self.context.protocol_version();
+        self.write_block_info_value(dest_ptr, protocol_version)
+    }
+
+    /// Writes the hash of the parent of the current block to dest_ptr in Wasm memory.
+    fn get_parent_block_hash(&self, dest_ptr: u32) -> Result<(), Trap> {
+        let parent_block_hash = self.context.block_info().parent_block_hash();
+        self.write_block_info_value(dest_ptr, parent_block_hash)
+    }
+
+    /// Writes the state root hash on top of which the deploy is executed to dest_ptr in Wasm
+    /// memory.
+    fn get_state_root_hash(&self, dest_ptr: u32) -> Result<(), Trap> {
+        let state_root_hash = self.context.block_info().state_root_hash();
+        self.write_block_info_value(dest_ptr, state_root_hash)
+    }
+
+    /// Writes a fixed-size property of the current block to dest_ptr in Wasm memory.
+    fn write_block_info_value<T: ToBytes>(&self, dest_ptr: u32, value: T) -> Result<(), Trap> {
+        let bytes = value.into_bytes().map_err(Error::BytesRepr)?;
+        self.try_get_memory()?
+            .set(dest_ptr, &bytes)
+            .map_err(|e| Error::Interpreter(e.into()).into())
+    }
+
     /// Load the uref known by the given name into the Wasm memory
     fn load_call_stack(
         &mut self,
@@ -1368,6 +1409,10 @@ where
             let transfers = self.context.transfers_mut();
             *transfers = runtime.context.transfers().to_owned();
         }
+        {
+            let messages = self.context.messages_mut();
+            *messages = runtime.context.messages().to_owned();
+        }
 
         let error = match result {
             Err(error) => error,
@@ -1801,6 +1846,21 @@ where
             .map_err(Into::into)
     }
 
+    /// Removes the value stored under the [`URef`] (read by de-serializing the bytes in wasm
+    /// memory from offset `uref_ptr` to `uref_ptr + uref_size`) from the global state.
+    ///
+    /// Returns [`ApiError::PermissionDenied`] if the caller may not write to the given `URef`.
+    fn remove_uref(&mut self, uref_ptr: u32, uref_size: u32) -> Result<Result<(), ApiError>, Trap> {
+        let uref: URef = self.t_from_mem(uref_ptr, uref_size)?;
+        match self.context.prune_gs(Key::from(uref)) {
+            Ok(()) => Ok(Ok(())),
+            Err(Error::InvalidAccess { .. }) | Err(Error::ForgedReference(_)) => {
+                Ok(Err(ApiError::PermissionDenied))
+            }
+            Err(error) => Err(error.into()),
+        }
+    }
+
     /// Records a transfer.
     fn record_transfer(
         &mut self,
@@ -2456,6 +2516,54 @@ where
         Ok(Ok(()))
     }
 
+    /// Records a message with the given topic and payload, to be published once the deploy has
+    /// been executed.
+    fn emit_message(
+        &mut self,
+        topic_ptr: u32,
+        topic_size: u32,
+        payload_ptr: u32,
+        payload_size: u32,
+    ) -> Result<Result<(), ApiError>, Trap> {
+        let topic = self.string_from_mem(topic_ptr, topic_size)?;
+        if topic.is_empty() || topic.len() > CONTRACT_MESSAGE_TOPIC_MAX_LENGTH {
+            return Ok(Err(ApiError::InvalidArgument));
+        }
+        let payload = self.cl_value_from_mem(payload_ptr, payload_size)?;
+        self.context.emit_message(topic, payload);
+        Ok(Ok(()))
+    }
+
+    /// Verifies a serialized `Signature` of the given message against a serialized `PublicKey`.
+    ///
+    /// Malformed signature or public key bytes are reported to the caller as an error rather than
+    /// trapping, as they might originate from untrusted input such as runtime arguments.
+    fn verify_signature(
+        &mut self,
+        message_ptr: u32,
+        message_size: u32,
+        signature_ptr: u32,
+        signature_size: u32,
+        public_key_ptr: u32,
+        public_key_size: u32,
+    ) -> Result<Result<(), ApiError>, Trap> {
+        let message = self.bytes_from_mem(message_ptr, message_size as usize)?;
+        let signature_bytes = self.bytes_from_mem(signature_ptr, signature_size as usize)?;
+        let signature: Signature = match bytesrepr::deserialize(signature_bytes) {
+            Ok(signature) => signature,
+            Err(error) => return Ok(Err(error.into())),
+        };
+        let public_key_bytes = self.bytes_from_mem(public_key_ptr, public_key_size as usize)?;
+        let public_key: PublicKey = match bytesrepr::deserialize(public_key_bytes) {
+            Ok(public_key) => public_key,
+            Err(error) => return Ok(Err(error.into())),
+        };
+        Ok(
+            crypto::verify(message, &signature, &public_key)
+                .map_err(|_| ApiError::InvalidSignature),
+        )
+    }
+
     #[cfg(feature = "test-support")]
     fn print(&mut self, text_ptr: u32, text_size: u32) -> Result<(), Trap> {
         let text = self.string_from_mem(text_ptr, text_size)?;
@@ -2851,6 +2959,34 @@ where
         Ok(Ok(()))
     }
 
+    /// Removes a dictionary item from the dictionary referenced by the given uref.
+    fn dictionary_remove(
+        &mut self,
+        uref_ptr: u32,
+        uref_size: u32,
+        key_ptr: u32,
+        key_size: u32,
+    ) -> Result<Result<(), ApiError>, Trap> {
+        let uref: URef = self.t_from_mem(uref_ptr, uref_size)?;
+        let dictionary_item_key_bytes = self.bytes_from_mem(key_ptr, key_size as usize)?;
+        if dictionary_item_key_bytes.len() > DICTIONARY_ITEM_KEY_MAX_LENGTH {
+            return Ok(Err(ApiError::DictionaryItemKeyExceedsLength));
+        }
+        let dictionary_item_key = if let Ok(item_key) = String::from_utf8(dictionary_item_key_bytes)
+        {
+            item_key
+        } else {
+            return Ok(Err(ApiError::InvalidDictionaryItemKey));
+        };
+        match self.context.dictionary_remove(uref, &dictionary_item_key) {
+            Ok(()) => Ok(Ok(())),
+            Err(Error::InvalidAccess { .. }) | Err(Error::ForgedReference(_)) => {
+                Ok(Err(ApiError::PermissionDenied))
+            }
+            Err(error) => Err(error.into()),
+        }
+    }
+
     /// Checks if immediate caller is a system contract or account.
     ///
     /// For cases where call stack is only the session code, then this method returns `true` if the
@@ -18,14 +18,17 @@ use casper_types::{
     contracts::NamedKeys,
     system::auction::EraInfo,
     AccessRights, BlockTime, CLType, CLValue, ContextAccessRights, Contract, ContractHash,
-    ContractPackage, ContractPackageHash, DeployHash, DeployInfo, EntryPointAccess, EntryPointType,
-    Gas, GrantedAccess, Key, KeyTag, Phase, ProtocolVersion, PublicKey, RuntimeArgs, StoredValue,
-    Transfer, TransferAddr, URef, URefAddr, DICTIONARY_ITEM_KEY_MAX_LENGTH, KEY_HASH_LENGTH, U512,
+    ContractMessage, ContractPackage, ContractPackageHash, DeployHash, DeployInfo,
+    EntryPointAccess, EntryPointType, Gas, GrantedAccess, Key, KeyTag, Phase, ProtocolVersion,
+    PublicKey, RuntimeArgs, StoredValue, Transfer, TransferAddr, URef, URefAddr,
+    DICTIONARY_ITEM_KEY_MAX_LENGTH, KEY_HASH_LENGTH, U512,
 };
 
 use crate::{
     core::{
-        engine_state::{execution_effect::ExecutionEffect, EngineConfig, SystemContractRegistry},
+        engine_state::{
+            execution_effect::ExecutionEffect, BlockInfo, EngineConfig, SystemContractRegistry,
+        },
         execution::{AddressGenerator, Error},
         runtime_context::dictionary::DictionaryValue,
         tracking_copy::{AddResult, TrackingCopy, TrackingCopyExt},
@@ -92,7 +95,7 @@ pub struct RuntimeContext<'a, R> {
     // Key pointing to the entity we are currently running
     //(could point at an account or contract in the global state)
     base_key: Key,
-    blocktime: BlockTime,
+    block_info: BlockInfo,
     deploy_hash: DeployHash,
     gas_limit: Gas,
     gas_counter: Gas,
@@ -103,6 +106,7 @@ pub struct RuntimeContext<'a, R> {
     engine_config: EngineConfig,
     entry_point_type: EntryPointType,
     transfers: Vec<TransferAddr>,
+    messages: Vec<ContractMessage>,
     remaining_spending_limit: U512,
 }
 
@@ -124,7 +128,7 @@ where
         authorization_keys: BTreeSet<AccountHash>,
         account: &'a Account,
         base_key: Key,
-        blocktime: BlockTime,
+        block_info: BlockInfo,
         deploy_hash: DeployHash,
         gas_limit: Gas,
         gas_counter: Gas,
@@ -144,7 +148,7 @@ where
             args: runtime_args,
             account,
             authorization_keys,
-            blocktime,
+            block_info,
             deploy_hash,
             base_key,
             gas_limit,
@@ -155,6 +159,7 @@ where
             phase,
             engine_config,
             transfers,
+            messages: Vec::new(),
             remaining_spending_limit,
         }
     }
@@ -173,7 +178,7 @@ where
         let tracking_copy = self.state();
         let authorization_keys = self.authorization_keys.clone();
         let account = self.account;
-        let blocktime = self.blocktime;
+        let block_info = self.block_info;
         let deploy_hash = self.deploy_hash;
         let gas_limit = self.gas_limit;
         let gas_counter = self.gas_counter;
@@ -183,6 +188,7 @@ where
         let phase = self.phase;
         let engine_config = self.engine_config;
         let transfers = self.transfers.clone();
+        let messages = self.messages.clone();
         let remaining_spending_limit = self.remaining_spending_limit();
 
         RuntimeContext {
@@ -193,7 +199,7 @@ where
             args: runtime_args,
             account,
             authorization_keys,
-            blocktime,
+            block_info,
             deploy_hash,
             base_key,
             gas_limit,
@@ -204,6 +210,7 @@ where
             phase,
             engine_config,
             transfers,
+            messages,
             remaining_spending_limit,
         }
     }
@@ -348,7 +355,12 @@ where
 
     /// Returns the block time.
     pub fn get_blocktime(&self) -> BlockTime {
-        self.blocktime
+        self.block_info.block_time()
+    }
+
+    /// Returns the properties of the block in which the deploy is executed.
+    pub fn block_info(&self) -> BlockInfo {
+        self.block_info
     }
 
     /// Returns the deploy hash.
@@ -655,6 +667,22 @@ where
         &mut self.transfers
     }
 
+    /// Returns list of messages emitted so far.
+    pub fn messages(&self) -> &Vec<ContractMessage> {
+        &self.messages
+    }
+
+    /// Returns mutable list of messages emitted so far.
+    pub fn messages_mut(&mut self) -> &mut Vec<ContractMessage> {
+        &mut self.messages
+    }
+
+    /// Records a message emitted by the entity we are currently running under the given topic.
+    pub fn emit_message(&mut self, topic: String, payload: CLValue) {
+        let message = ContractMessage::new(self.base_key, topic, payload);
+        self.messages.push(message);
+    }
+
     fn validate_cl_value(&self, cl_value: &CLValue) -> Result<(), Error> {
         match cl_value.cl_type() {
             CLType::Bool
@@ -946,6 +974,16 @@ where
         self.metered_write_gs_unsafe(key, stored_value)
     }
 
+    /// Removes the value stored under `key` from global state.
+    ///
+    /// This method performs full validation of the key to be removed.
+    pub(crate) fn prune_gs(&mut self, key: Key) -> Result<(), Error> {
+        self.validate_writeable(&key)?;
+        self.validate_key(&key)?;
+        self.tracking_copy.borrow_mut().prune(key);
+        Ok(())
+    }
+
     /// Adds data to a global state key and charges for bytes stored.
     ///
     /// This method performs full validation of the key to be written.
@@ -1258,6 +1296,26 @@ where
         Ok(())
     }
 
+    /// Removes a dictionary item key from a dictionary referenced by a `uref`.
+    pub fn dictionary_remove(
+        &mut self,
+        seed_uref: URef,
+        dictionary_item_key: &str,
+    ) -> Result<(), Error> {
+        let dictionary_item_key_bytes = dictionary_item_key.as_bytes();
+
+        if dictionary_item_key_bytes.len() > DICTIONARY_ITEM_KEY_MAX_LENGTH {
+            return Err(Error::DictionaryItemKeyExceedsLength);
+        }
+
+        self.validate_writeable(&seed_uref.into())?;
+        self.validate_uref(&seed_uref)?;
+
+        let dictionary_key = Key::dictionary(seed_uref, dictionary_item_key_bytes);
+        self.tracking_copy.borrow_mut().prune(dictionary_key);
+        Ok(())
+    }
+
     /// Gets system contract by name.
     pub(crate) fn get_system_contract(&self, name: &str) -> Result<ContractHash, Error> {
         let registry = self.system_contract_registry()?;
@@ -137,6 +137,7 @@ pub struct TrackingCopyCache<M> {
     muts_cached: HashMap<Key, StoredValue>,
     key_tag_reads_cached: LinkedHashMap<KeyTag, BTreeSet<Key>>,
     key_tag_muts_cached: HashMap<KeyTag, BTreeSet<Key>>,
+    prunes_cached: HashSet<Key>,
     meter: M,
 }
 
@@ -153,6 +154,7 @@ impl<M: Meter<Key, StoredValue>> TrackingCopyCache<M> {
             muts_cached: HashMap::new(),
             key_tag_reads_cached: LinkedHashMap::new(),
             key_tag_muts_cached: HashMap::new(),
+            prunes_cached: HashSet::new(),
             meter,
         }
     }
@@ -191,6 +193,7 @@ impl<M: Meter<Key, StoredValue>> TrackingCopyCache<M> {
 
     /// Inserts `key` and `value` pair to Write/Add cache.
     pub fn insert_write(&mut self, key: Key, value: StoredValue) {
+        self.prunes_cached.remove(&key);
         self.muts_cached.insert(key, value);
 
         let key_set = self
@@ -201,8 +204,26 @@ impl<M: Meter<Key, StoredValue>> TrackingCopyCache<M> {
         key_set.insert(key);
     }
 
+    /// Marks `key` as pruned, discarding any value cached under it.
+    pub fn insert_prune(&mut self, key: Key) {
+        self.muts_cached.remove(&key);
+        if let Some(key_set) = self.key_tag_muts_cached.get_mut(&key.tag()) {
+            key_set.remove(&key);
+        }
+        self.prunes_cached.insert(key);
+    }
+
+    /// Returns `true` if `key` has been pruned.
+    pub fn is_pruned(&self, key: &Key) -> bool {
+        self.prunes_cached.contains(key)
+    }
+
     /// Gets value from `key` in the cache.
     pub fn get(&mut self, key: &Key) -> Option<&StoredValue> {
+        if self.is_pruned(key) {
+            return None;
+        }
+
         if let Some(value) = self.muts_cached.get(key) {
             return Some(value);
         };
@@ -295,6 +316,9 @@ impl<R: StateReader<Key, StoredValue>> TrackingCopy<R> {
         correlation_id: CorrelationId,
         key: &Key,
     ) -> Result<Option<StoredValue>, R::Error> {
+        if self.cache.is_pruned(key) {
+            return Ok(None);
+        }
         if let Some(value) = self.cache.get(key) {
             return Ok(Some(value.to_owned()));
         }
@@ -327,6 +351,7 @@ impl<R: StateReader<Key, StoredValue>> TrackingCopy<R> {
         if let Some(keys) = self.cache.get_key_tag_muts_cached(key_tag) {
             ret.extend(keys)
         }
+        ret.retain(|key| !self.cache.is_pruned(key));
         Ok(ret)
     }
 
@@ -353,6 +378,14 @@ impl<R: StateReader<Key, StoredValue>> TrackingCopy<R> {
         self.journal.push((normalized_key, Transform::Write(value)));
     }
 
+    /// Prunes `key`, removing it from the state. Note that the removal is only cached, and the
+    /// global state itself remains unmodified.
+    pub fn prune(&mut self, key: Key) {
+        let normalized_key = key.normalize();
+        self.cache.insert_prune(normalized_key);
+        self.journal.push((normalized_key, Transform::Prune));
+    }
+
     /// Ok(None) represents missing key to which we want to "add" some value.
     /// Ok(Some(unit)) represents successful operation.
     /// Err(error) is reserved for unexpected errors when accessing global
@@ -577,6 +610,9 @@ impl<R: StateReader<Key, StoredValue>> StateReader<Key, StoredValue> for &Tracki
         correlation_id: CorrelationId,
         key: &Key,
     ) -> Result<Option<StoredValue>, Self::Error> {
+        if self.cache.is_pruned(key) {
+            return Ok(None);
+        }
         if let Some(value) = self.cache.muts_cached.get(key) {
             return Ok(Some(value.to_owned()));
         }
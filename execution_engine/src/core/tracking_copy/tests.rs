@@ -304,6 +304,41 @@ fn tracking_copy_aw() {
     );
 }
 
+#[test]
+fn tracking_copy_prune() {
+    let correlation_id = CorrelationId::new();
+    let counter = Rc::new(Cell::new(0));
+    let db = CountingDb::new(Rc::clone(&counter));
+    let mut tc = TrackingCopy::new(db);
+    let k = Key::Hash([0u8; 32]);
+
+    // pruning hides the value from subsequent reads without querying the DB
+    tc.prune(k);
+    assert_eq!(tc.read(correlation_id, &k).unwrap(), None);
+    assert_eq!(counter.get(), 0);
+    assert_eq!(
+        tc.journal,
+        ExecutionJournal::new(vec![(k, Transform::Prune)])
+    );
+
+    // adding to a pruned key fails
+    let value = StoredValue::CLValue(CLValue::from_t(3_i32).unwrap());
+    let add = tc.add(correlation_id, k, value.clone());
+    assert_matches!(add, Ok(AddResult::KeyNotFound(_)));
+
+    // writing after pruning makes the key readable again
+    tc.write(k, value.clone());
+    assert_eq!(tc.read(correlation_id, &k).unwrap(), Some(value.clone()));
+    assert_eq!(
+        tc.journal,
+        ExecutionJournal::new(vec![
+            (k, Transform::Prune),
+            (k, Transform::Write(value)),
+            (k, Transform::Identity)
+        ])
+    );
+}
+
 proptest! {
     #[test]
     fn query_empty_path(k in key_arb(), missing_key in key_arb(), v in stored_value_arb()) {
@@ -286,6 +286,24 @@ pub struct HostFunctionCosts {
     pub blake2b: HostFunction<[Cost; 4]>,
     /// Cost of calling the `next address` host function.
     pub random_bytes: HostFunction<[Cost; 2]>,
+    /// Cost of calling the `emit_message` host function.
+    pub emit_message: HostFunction<[Cost; 4]>,
+    /// Cost of calling the `verify_signature` host function.
+    pub verify_signature: HostFunction<[Cost; 6]>,
+    /// Cost of calling the `get_block_height` host function.
+    pub get_block_height: HostFunction<[Cost; 1]>,
+    /// Cost of calling the `get_era_id` host function.
+    pub get_era_id: HostFunction<[Cost; 1]>,
+    /// Cost of calling the `get_protocol_version` host function.
+    pub get_protocol_version: HostFunction<[Cost; 1]>,
+    /// Cost of calling the `get_parent_block_hash` host function.
+    pub get_parent_block_hash: HostFunction<[Cost; 1]>,
+    /// Cost of calling the `get_state_root_hash` host function.
+    pub get_state_root_hash: HostFunction<[Cost; 1]>,
+    /// Cost of calling the `remove_uref` host function.
+    pub remove_uref: HostFunction<[Cost; 2]>,
+    /// Cost of calling the `remove_dictionary_item` host function.
+    pub remove_dictionary_item: HostFunction<[Cost; 4]>,
 }
 
 impl Default for HostFunctionCosts {
@@ -404,6 +422,23 @@ impl Default for HostFunctionCosts {
             ),
             blake2b: HostFunction::default(),
             random_bytes: HostFunction::default(),
+            emit_message: HostFunction::default(),
+            verify_signature: HostFunction::default(),
+            get_block_height: HostFunction::fixed(DEFAULT_GET_BLOCKTIME_COST),
+            get_era_id: HostFunction::fixed(DEFAULT_GET_BLOCKTIME_COST),
+            get_protocol_version: HostFunction::fixed(DEFAULT_GET_BLOCKTIME_COST),
+            get_parent_block_hash: HostFunction::fixed(DEFAULT_GET_BLOCKTIME_COST),
+            get_state_root_hash: HostFunction::fixed(DEFAULT_GET_BLOCKTIME_COST),
+            remove_uref: HostFunction::fixed(DEFAULT_WRITE_COST),
+            remove_dictionary_item: HostFunction::new(
+                DEFAULT_DICTIONARY_PUT_COST,
+                [
+                    NOT_USED,
+                    NOT_USED,
+                    DEFAULT_DICTIONARY_PUT_KEY_BYTES_SIZE_WEIGHT,
+                    NOT_USED,
+                ],
+            ),
         }
     }
 }
@@ -454,6 +489,15 @@ impl ToBytes for HostFunctionCosts {
         ret.append(&mut self.print.to_bytes()?);
         ret.append(&mut self.blake2b.to_bytes()?);
         ret.append(&mut self.random_bytes.to_bytes()?);
+        ret.append(&mut self.emit_message.to_bytes()?);
+        ret.append(&mut self.verify_signature.to_bytes()?);
+        ret.append(&mut self.get_block_height.to_bytes()?);
+        ret.append(&mut self.get_era_id.to_bytes()?);
+        ret.append(&mut self.get_protocol_version.to_bytes()?);
+        ret.append(&mut self.get_parent_block_hash.to_bytes()?);
+        ret.append(&mut self.get_state_root_hash.to_bytes()?);
+        ret.append(&mut self.remove_uref.to_bytes()?);
+        ret.append(&mut self.remove_dictionary_item.to_bytes()?);
         Ok(ret)
     }
 
@@ -501,6 +545,15 @@ impl ToBytes for HostFunctionCosts {
             + self.print.serialized_length()
             + self.blake2b.serialized_length()
             + self.random_bytes.serialized_length()
+            + self.emit_message.serialized_length()
+            + self.verify_signature.serialized_length()
+            + self.get_block_height.serialized_length()
+            + self.get_era_id.serialized_length()
+            + self.get_protocol_version.serialized_length()
+            + self.get_parent_block_hash.serialized_length()
+            + self.get_state_root_hash.serialized_length()
+            + self.remove_uref.serialized_length()
+            + self.remove_dictionary_item.serialized_length()
     }
 }
 
@@ -549,6 +602,15 @@ impl FromBytes for HostFunctionCosts {
         let (print, rem) = FromBytes::from_bytes(rem)?;
         let (blake2b, rem) = FromBytes::from_bytes(rem)?;
         let (random_bytes, rem) = FromBytes::from_bytes(rem)?;
+        let (emit_message, rem) = FromBytes::from_bytes(rem)?;
+        let (verify_signature, rem) = FromBytes::from_bytes(rem)?;
+        let (get_block_height, rem) = FromBytes::from_bytes(rem)?;
+        let (get_era_id, rem) = FromBytes::from_bytes(rem)?;
+        let (get_protocol_version, rem) = FromBytes::from_bytes(rem)?;
+        let (get_parent_block_hash, rem) = FromBytes::from_bytes(rem)?;
+        let (get_state_root_hash, rem) = FromBytes::from_bytes(rem)?;
+        let (remove_uref, rem) = FromBytes::from_bytes(rem)?;
+        let (remove_dictionary_item, rem) = FromBytes::from_bytes(rem)?;
         Ok((
             HostFunctionCosts {
                 read_value,
@@ -594,6 +656,15 @@ impl FromBytes for HostFunctionCosts {
                 print,
                 blake2b,
                 random_bytes,
+                emit_message,
+                verify_signature,
+                get_block_height,
+                get_era_id,
+                get_protocol_version,
+                get_parent_block_hash,
+                get_state_root_hash,
+                remove_uref,
+                remove_dictionary_item,
             },
             rem,
         ))
@@ -646,6 +717,15 @@ impl Distribution<HostFunctionCosts> for Standard {
             print: rng.gen(),
             blake2b: rng.gen(),
             random_bytes: rng.gen(),
+            emit_message: rng.gen(),
+            verify_signature: rng.gen(),
+            get_block_height: rng.gen(),
+            get_era_id: rng.gen(),
+            get_protocol_version: rng.gen(),
+            get_parent_block_hash: rng.gen(),
+            get_state_root_hash: rng.gen(),
+            remove_uref: rng.gen(),
+            remove_dictionary_item: rng.gen(),
         }
     }
 }
@@ -706,6 +786,15 @@ pub mod gens {
             print in host_function_cost_arb(),
             blake2b in host_function_cost_arb(),
             random_bytes in host_function_cost_arb(),
+            emit_message in host_function_cost_arb(),
+            verify_signature in host_function_cost_arb(),
+            get_block_height in host_function_cost_arb(),
+            get_era_id in host_function_cost_arb(),
+            get_protocol_version in host_function_cost_arb(),
+            get_parent_block_hash in host_function_cost_arb(),
+            get_state_root_hash in host_function_cost_arb(),
+            remove_uref in host_function_cost_arb(),
+            remove_dictionary_item in host_function_cost_arb(),
         ) -> HostFunctionCosts {
             HostFunctionCosts {
                 read_value,
@@ -751,6 +840,15 @@ pub mod gens {
                 print,
                 blake2b,
                 random_bytes,
+                emit_message,
+                verify_signature,
+                get_block_height,
+                get_era_id,
+                get_protocol_version,
+                get_parent_block_hash,
+                get_state_root_hash,
+                remove_uref,
+                remove_dictionary_item,
             }
         }
     }
@@ -89,6 +89,11 @@ pub enum Transform {
     /// Represents the case where applying a transform would cause an error.
     #[data_size(skip)]
     Failure(Error),
+    /// Removes the value from the global state.
+    ///
+    /// The value remains reachable from previous state root hashes, but is absent from the state
+    /// root hash produced by committing this transform.
+    Prune,
 }
 
 macro_rules! from_try_from_impl {
@@ -233,6 +238,11 @@ impl Transform {
                 }
             },
             Transform::Failure(error) => Err(error),
+            Transform::Prune => {
+                let expected = "Write or Add transform".to_string();
+                let found = "Prune".to_string();
+                Err(StoredValueTypeMismatch::new(expected, found).into())
+            }
         }
     }
 }
@@ -275,6 +285,7 @@ impl Add for Transform {
             (a @ Transform::Failure(_), _) => a,
             (_, b @ Transform::Failure(_)) => b,
             (_, b @ Transform::Write(_)) => b,
+            (_, b @ Transform::Prune) => b,
             (Transform::Write(v), b) => {
                 // second transform changes value being written
                 match b.apply(v) {
@@ -307,6 +318,10 @@ impl Add for Transform {
             (Transform::AddUInt128(i), b) => wrapped_transform_addition(i, b, "U128"),
             (Transform::AddUInt256(i), b) => wrapped_transform_addition(i, b, "U256"),
             (Transform::AddUInt512(i), b) => wrapped_transform_addition(i, b, "U512"),
+            // Only a subsequent write can follow a prune, as the value no longer exists.
+            (Transform::Prune, b) => Transform::Failure(
+                StoredValueTypeMismatch::new("Prune".to_owned(), format!("{:?}", b)).into(),
+            ),
             (Transform::AddKeys(mut ks1), b) => match b {
                 Transform::AddKeys(mut ks2) => {
                     ks1.append(&mut ks2);
@@ -389,6 +404,7 @@ impl From<&Transform> for casper_types::Transform {
                     .collect(),
             ),
             Transform::Failure(error) => casper_types::Transform::Failure(error.to_string()),
+            Transform::Prune => casper_types::Transform::Prune,
         }
     }
 }
@@ -405,6 +421,7 @@ pub mod gens {
     pub fn transform_arb() -> impl Strategy<Value = Transform> {
         prop_oneof![
             Just(Transform::Identity),
+            Just(Transform::Prune),
             stored_value_arb().prop_map(Transform::Write),
             any::<i32>().prop_map(Transform::AddInt32),
             any::<u64>().prop_map(Transform::AddUInt64),
@@ -868,4 +885,25 @@ mod tests {
         assert_eq!(ZERO_U512, add(MAX_U512, ONE_U512));
         assert_eq!(MAX_U512 - 1, add(MAX_U512, MAX_U512));
     }
+
+    #[test]
+    fn prune_should_compose() {
+        let value = StoredValue::CLValue(CLValue::from_t(ONE_I32).expect("should create CLValue"));
+
+        assert_eq!(
+            Transform::Write(value.clone()) + Transform::Prune,
+            Transform::Prune
+        );
+        assert_eq!(Transform::AddInt32(1) + Transform::Prune, Transform::Prune);
+        assert_eq!(Transform::Prune + Transform::Identity, Transform::Prune);
+        assert_eq!(
+            Transform::Prune + Transform::Write(value.clone()),
+            Transform::Write(value.clone())
+        );
+        assert!(matches!(
+            Transform::Prune + Transform::AddInt32(1),
+            Transform::Failure(_)
+        ));
+        assert!(Transform::Prune.apply(value).is_err());
+    }
 }
@@ -1,11 +1,19 @@
 use std::{
     collections::{HashMap, HashSet},
+    convert::TryFrom,
+    mem,
     ops::Deref,
-    sync::{Arc, RwLock},
+    path::Path,
+    sync::{Arc, Mutex, RwLock},
 };
 
 use casper_hashing::{ChunkWithProof, Digest};
-use casper_types::{bytesrepr::Bytes, Key, StoredValue};
+use casper_types::{
+    bytesrepr::{self, Bytes, ToBytes},
+    Key, StoredValue,
+};
+use lmdb::{Cursor, Database, DatabaseFlags, WriteFlags};
+use lmdb_sys::MDB_SET_RANGE;
 use tracing::trace;
 
 use crate::{
@@ -13,8 +21,8 @@ use crate::{
     storage::{
         error,
         global_state::{
-            commit, put_stored_values, scratch::ScratchGlobalState, CommitProvider, StateProvider,
-            StateReader,
+            commit, put_stored_values, scratch::ScratchGlobalState, CommitError, CommitProvider,
+            StateProvider, StateReader,
         },
         store::Store,
         transaction_source::{lmdb::LmdbEnvironment, Transaction, TransactionSource},
@@ -25,8 +33,8 @@ use crate::{
         trie_store::{
             lmdb::{LmdbTrieStore, ScratchTrieStore},
             operations::{
-                descendant_trie_keys, keys_with_prefix, missing_trie_keys, put_trie, read,
-                read_with_proof, ReadResult,
+                descendant_trie_keys, entries_with_prefix, keys_with_prefix, missing_trie_keys,
+                put_trie, read, read_with_proof, ReadResult,
             },
         },
     },
@@ -42,6 +50,97 @@ pub struct LmdbGlobalState {
     /// Empty root hash used for a new trie.
     pub(crate) empty_root_hash: Digest,
     digests_without_missing_descendants: RwLock<HashSet<Digest>>,
+    /// The keys of the tries written since the in-place trie garbage collection in progress, if
+    /// any, started.  Tries reachable from these are treated as live by the garbage collection.
+    trie_gc_writes: Mutex<Option<Vec<Digest>>>,
+}
+
+/// Number of trie entries swept between progress reports, and between commits to the destination
+/// store, during an offline trie garbage collection.
+const TRIE_GC_BATCH_SIZE: usize = 100_000;
+
+/// Name of the database holding the marked trie keys of a trie garbage collection.
+const TRIE_GC_MARKS_DB_NAME: &str = "TRIE_GC_MARKS";
+
+/// The set of trie keys marked as reachable during a trie garbage collection.
+///
+/// The marks are held in their own LMDB environment rather than in memory, as the number of tries
+/// reachable from a state root hash of a live network is too large to hold in a `HashSet`.
+pub struct TrieGcMarks {
+    environment: LmdbEnvironment,
+    db: Database,
+}
+
+impl TrieGcMarks {
+    /// Creates a set of marks backed by an LMDB environment in the directory `path`.
+    ///
+    /// Any marks left there by a previous garbage collection are discarded when the next one
+    /// starts, so the same directory can be reused.
+    pub fn new<P: AsRef<Path>>(path: P, max_db_size: usize) -> Result<Self, error::Error> {
+        let environment = LmdbEnvironment::new(path, max_db_size, 1, true)?;
+        let db = environment
+            .env()
+            .create_db(Some(TRIE_GC_MARKS_DB_NAME), DatabaseFlags::empty())?;
+        Ok(TrieGcMarks { environment, db })
+    }
+
+    /// Returns `true` if `trie_key` has been marked, as read through `txn`.
+    fn contains<T: lmdb::Transaction>(
+        &self,
+        txn: &T,
+        trie_key: &[u8],
+    ) -> Result<bool, error::Error> {
+        match txn.get(self.db, &trie_key) {
+            Ok(_) => Ok(true),
+            Err(lmdb::Error::NotFound) => Ok(false),
+            Err(error) => Err(error.into()),
+        }
+    }
+}
+
+/// Progress of an offline trie garbage collection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrieGcProgress {
+    /// All tries reachable from a retained state root hash have been marked.
+    Marked {
+        /// The state root hash which was traversed.
+        state_root_hash: Digest,
+        /// The number of distinct tries marked so far.
+        marked: usize,
+    },
+    /// A batch of entries of the source trie store has been swept.
+    Swept {
+        /// The number of entries examined so far.
+        scanned: usize,
+        /// The number of examined entries which are retained.
+        retained: usize,
+    },
+}
+
+/// Summary of an offline trie garbage collection.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct TrieGcSummary {
+    /// The number of entries in the source trie store.
+    pub total_tries: usize,
+    /// The size in bytes of all keys and values in the source trie store.
+    pub total_bytes: u64,
+    /// The number of entries reachable from the retained state root hashes.
+    pub retained_tries: usize,
+    /// The size in bytes of the keys and values of the retained entries.
+    pub retained_bytes: u64,
+}
+
+impl TrieGcSummary {
+    /// Returns the number of entries which are not reachable from the retained state root hashes.
+    pub fn removed_tries(&self) -> usize {
+        self.total_tries - self.retained_tries
+    }
+
+    /// Returns the size in bytes of the entries which are not reachable from the retained state
+    /// root hashes.
+    pub fn removed_bytes(&self) -> u64 {
+        self.total_bytes - self.retained_bytes
+    }
 }
 
 /// Represents a "view" of global state at a particular root hash.
@@ -83,6 +182,7 @@ impl LmdbGlobalState {
             trie_store,
             empty_root_hash,
             digests_without_missing_descendants: Default::default(),
+            trie_gc_writes: Default::default(),
         }
     }
 
@@ -95,12 +195,13 @@ impl LmdbGlobalState {
         )
     }
 
-    /// Write stored values to LMDB.
+    /// Write stored values to LMDB and remove the pruned keys.
     pub fn put_stored_values(
         &self,
         correlation_id: CorrelationId,
         prestate_hash: Digest,
         stored_values: HashMap<Key, StoredValue>,
+        keys_to_prune: HashSet<Key>,
     ) -> Result<Digest, error::Error> {
         let scratch_trie = self.get_scratch_store();
         let new_state_root = put_stored_values::<_, _, error::Error>(
@@ -109,9 +210,49 @@ impl LmdbGlobalState {
             correlation_id,
             prestate_hash,
             stored_values,
+            keys_to_prune,
         )?;
-        scratch_trie.write_root_to_db(new_state_root)?;
-        Ok(new_state_root)
+        self.write_tries(|| {
+            scratch_trie.write_root_to_db(new_state_root)?;
+            Ok(new_state_root)
+        })
+    }
+
+    /// Computes the state root hash which `put_stored_values` would return, without writing any
+    /// tries to the trie store.  The new tries are held in memory only until this returns.
+    pub fn compute_stored_values_root(
+        &self,
+        correlation_id: CorrelationId,
+        prestate_hash: Digest,
+        stored_values: HashMap<Key, StoredValue>,
+        keys_to_prune: HashSet<Key>,
+    ) -> Result<Digest, error::Error> {
+        let scratch_trie = self.get_scratch_store();
+        put_stored_values::<_, _, error::Error>(
+            &scratch_trie,
+            &scratch_trie,
+            correlation_id,
+            prestate_hash,
+            stored_values,
+            keys_to_prune,
+        )
+    }
+
+    /// Runs `write`, which writes tries to the trie store and returns the key of the trie from
+    /// which all of them are reachable, recording that key as live for any in-place trie garbage
+    /// collection in progress.
+    fn write_tries(
+        &self,
+        write: impl FnOnce() -> Result<Digest, error::Error>,
+    ) -> Result<Digest, error::Error> {
+        // The lock is held while writing so the garbage collection cannot delete a rewritten trie
+        // between it being written and being recorded.
+        let mut trie_gc_writes = self.trie_gc_writes.lock().expect("trie gc writes lock");
+        let trie_key = write()?;
+        if let Some(trie_gc_writes) = trie_gc_writes.as_mut() {
+            trie_gc_writes.push(trie_key);
+        }
+        Ok(trie_key)
     }
 
     /// Gets a scratch trie store.
@@ -130,6 +271,390 @@ impl LmdbGlobalState {
     pub fn trie_store(&self) -> &LmdbTrieStore {
         &self.trie_store
     }
+
+    /// Visits the entries under `state_root_hash` whose serialized keys start with `prefix`, in
+    /// ascending order of their serialized keys, starting after `start_after`.  The values are
+    /// only read if `read_values` is `true`, and are passed to `visitor` as `None` otherwise.
+    ///
+    /// The subtries holding only keys at or before `start_after` are not traversed, so resuming
+    /// from a key deep into the matching entries doesn't revisit the preceding ones.
+    /// Visiting stops early once `visitor` returns `false`.  Returns `Ok(false)` if
+    /// `state_root_hash` is not present in the trie store.
+    pub fn visit_entries_with_prefix(
+        &self,
+        correlation_id: CorrelationId,
+        state_root_hash: Digest,
+        prefix: &[u8],
+        start_after: Option<&Key>,
+        read_values: bool,
+        mut visitor: impl FnMut(Key, Option<StoredValue>) -> bool,
+    ) -> Result<bool, error::Error> {
+        let txn = self.environment.create_read_txn()?;
+        let maybe_root: Option<Trie<Key, StoredValue>> =
+            self.trie_store.get(&txn, &state_root_hash)?;
+        if maybe_root.is_none() {
+            return Ok(false);
+        }
+        let start_after_bytes = start_after.map(Key::to_bytes).transpose()?;
+
+        let entries_iter = entries_with_prefix::<Key, StoredValue, _, _>(
+            correlation_id,
+            &txn,
+            self.trie_store.deref(),
+            &state_root_hash,
+            prefix,
+            start_after_bytes,
+            read_values,
+        );
+        for result in entries_iter {
+            let (key, value) = result?;
+            if !visitor(key, value) {
+                break;
+            }
+        }
+        txn.commit()?;
+        Ok(true)
+    }
+
+    /// Estimates the effect of garbage collecting every trie which is not reachable from any of
+    /// `state_root_hashes`, without modifying any store other than `marks`.
+    pub fn estimate_trie_gc(
+        &self,
+        state_root_hashes: &[Digest],
+        marks: &TrieGcMarks,
+        mut progress: impl FnMut(TrieGcProgress),
+    ) -> Result<TrieGcSummary, error::Error> {
+        self.mark_reachable_tries(state_root_hashes, marks, &mut progress)?;
+        self.sweep_tries(marks, None, &mut progress)
+    }
+
+    /// Garbage collects every trie which is not reachable from any of `state_root_hashes` by
+    /// copying only the reachable tries into `destination`, which is expected to be backed by a
+    /// new environment.  This global state is left unmodified.
+    pub fn compact_tries_into(
+        &self,
+        state_root_hashes: &[Digest],
+        marks: &TrieGcMarks,
+        destination: &LmdbGlobalState,
+        mut progress: impl FnMut(TrieGcProgress),
+    ) -> Result<TrieGcSummary, error::Error> {
+        self.mark_reachable_tries(state_root_hashes, marks, &mut progress)?;
+        let summary = self.sweep_tries(marks, Some(destination), &mut progress)?;
+        destination.environment.sync()?;
+        Ok(summary)
+    }
+
+    /// Starts an in-place trie garbage collection, to be run by `prune_unreachable_tries`: every
+    /// trie reachable from a trie written from now on is retained by it.
+    ///
+    /// This must be called before anything not reachable from the state root hashes to be
+    /// retained is written, as a trie rewritten before then may be deleted.  Calling it again
+    /// before the garbage collection ends has no effect.
+    pub fn begin_trie_gc(&self) {
+        self.trie_gc_writes
+            .lock()
+            .expect("trie gc writes lock")
+            .get_or_insert_with(Vec::new);
+    }
+
+    /// Stops recording the tries written for an in-place trie garbage collection, e.g. if it is
+    /// abandoned after `begin_trie_gc`.
+    pub fn end_trie_gc(&self) {
+        *self.trie_gc_writes.lock().expect("trie gc writes lock") = None;
+    }
+
+    /// Garbage collects, in place, every trie which is not reachable from any of
+    /// `state_root_hashes` or from the empty root hash.
+    ///
+    /// New tries may be written to this global state while this is running: every trie reachable
+    /// from a trie written after the garbage collection started is retained, even if it is not
+    /// reachable from `state_root_hashes`.  The garbage collection starts with the call to
+    /// `begin_trie_gc` if there was one, or else with this call.  Only one garbage collection may
+    /// run at a time.
+    pub fn prune_unreachable_tries(
+        &self,
+        state_root_hashes: &[Digest],
+        marks: &TrieGcMarks,
+        mut progress: impl FnMut(TrieGcProgress),
+    ) -> Result<TrieGcSummary, error::Error> {
+        // Start recording writes before the retained tries are marked, so that any trie written
+        // after the mark phase's snapshot is taken is also recorded.
+        self.begin_trie_gc();
+        let result =
+            self.mark_and_delete_unreachable_tries(state_root_hashes, marks, &mut progress);
+        self.end_trie_gc();
+        result
+    }
+
+    fn mark_and_delete_unreachable_tries(
+        &self,
+        state_root_hashes: &[Digest],
+        marks: &TrieGcMarks,
+        progress: &mut impl FnMut(TrieGcProgress),
+    ) -> Result<TrieGcSummary, error::Error> {
+        let mut retained = state_root_hashes.to_vec();
+        retained.push(self.empty_root_hash);
+        self.mark_reachable_tries(&retained, marks, progress)?;
+
+        let mut summary = TrieGcSummary::default();
+        let (mut removed_tries, mut removed_bytes) = (0, 0);
+        let mut maybe_last_scanned: Option<Digest> = None;
+        loop {
+            // Each batch is collected in its own snapshot, as the write transaction deleting it
+            // cannot be opened alongside a read transaction.
+            let mut unmarked = Vec::new();
+            {
+                let txn = self.environment.create_read_txn()?;
+                let marks_txn = marks.environment.create_read_txn()?;
+                let mut cursor = lmdb::Transaction::open_ro_cursor(&txn, self.trie_store.get_db())?;
+                // `iter_from` panics if no key is greater than or equal to the given one, which is
+                // the case if the last scanned trie was the greatest and has since been deleted.
+                let maybe_iter = match maybe_last_scanned {
+                    Some(last_scanned) => {
+                        match cursor.get(Some(last_scanned.as_ref()), None, MDB_SET_RANGE) {
+                            Ok(_) => Some(cursor.iter_from(last_scanned)),
+                            Err(lmdb::Error::NotFound) => None,
+                            Err(error) => return Err(error.into()),
+                        }
+                    }
+                    None => Some(cursor.iter_start()),
+                };
+                for (key_bytes, value_bytes) in maybe_iter.into_iter().flatten() {
+                    let trie_key =
+                        Digest::try_from(key_bytes).map_err(|_| bytesrepr::Error::Formatting)?;
+                    if maybe_last_scanned == Some(trie_key) {
+                        continue;
+                    }
+                    maybe_last_scanned = Some(trie_key);
+                    let entry_bytes = (key_bytes.len() + value_bytes.len()) as u64;
+                    summary.total_tries += 1;
+                    summary.total_bytes += entry_bytes;
+                    if !marks.contains(&marks_txn, key_bytes)? {
+                        unmarked.push((trie_key, entry_bytes));
+                        if unmarked.len() == TRIE_GC_BATCH_SIZE {
+                            break;
+                        }
+                    }
+                }
+                drop(cursor);
+                txn.commit()?;
+                marks_txn.commit()?;
+            }
+
+            let exhausted = unmarked.len() < TRIE_GC_BATCH_SIZE;
+            let (deleted_tries, deleted_bytes) = self.delete_unmarked_tries(&unmarked, marks)?;
+            removed_tries += deleted_tries;
+            removed_bytes += deleted_bytes;
+            summary.retained_tries = summary.total_tries - removed_tries;
+            summary.retained_bytes = summary.total_bytes - removed_bytes;
+            progress(TrieGcProgress::Swept {
+                scanned: summary.total_tries,
+                retained: summary.retained_tries,
+            });
+            if exhausted {
+                break;
+            }
+        }
+        self.environment.sync()?;
+        Ok(summary)
+    }
+
+    /// Deletes those of `unmarked` which are still not marked once every trie reachable from the
+    /// tries written since the garbage collection started has been marked, returning the number
+    /// and total size of the deleted tries.
+    fn delete_unmarked_tries(
+        &self,
+        unmarked: &[(Digest, u64)],
+        marks: &TrieGcMarks,
+    ) -> Result<(usize, u64), error::Error> {
+        // Writers are held off until the batch is deleted, so that no trie can be rewritten after
+        // the recorded writes are marked.
+        let mut trie_gc_writes = self.trie_gc_writes.lock().expect("trie gc writes lock");
+        let mut txn = self.environment.create_read_write_txn()?;
+        let mut marks_txn = marks.environment.create_read_write_txn()?;
+        if let Some(trie_gc_writes) = trie_gc_writes.as_mut() {
+            let mut trie_keys_to_visit = mem::take(trie_gc_writes);
+            self.mark_descendants(
+                &txn,
+                marks,
+                &mut marks_txn,
+                &mut trie_keys_to_visit,
+                usize::MAX,
+            )?;
+        }
+
+        let mut deleted = HashSet::new();
+        let mut deleted_bytes = 0;
+        for (trie_key, entry_bytes) in unmarked {
+            if marks.contains(&marks_txn, trie_key.as_ref())? {
+                continue;
+            }
+            txn.del(self.trie_store.get_db(), trie_key, None)?;
+            deleted.insert(*trie_key);
+            deleted_bytes += entry_bytes;
+        }
+        marks_txn.commit()?;
+        txn.commit()?;
+
+        self.digests_without_missing_descendants
+            .write()
+            .expect("digest cache write lock")
+            .retain(|digest| !deleted.contains(digest));
+        Ok((deleted.len(), deleted_bytes))
+    }
+
+    /// Marks every trie reachable from any of `state_root_hashes` in `marks`, discarding any
+    /// previous marks.
+    fn mark_reachable_tries(
+        &self,
+        state_root_hashes: &[Digest],
+        marks: &TrieGcMarks,
+        progress: &mut impl FnMut(TrieGcProgress),
+    ) -> Result<(), error::Error> {
+        let txn = self.environment.create_read_txn()?;
+        let mut marks_txn = marks.environment.create_read_write_txn()?;
+        marks_txn.clear_db(marks.db)?;
+        marks_txn.commit()?;
+
+        let mut marked = 0;
+        for state_root_hash in state_root_hashes {
+            let maybe_root: Option<Trie<Key, StoredValue>> =
+                self.trie_store.get(&txn, state_root_hash)?;
+            if maybe_root.is_none() {
+                return Err(CommitError::RootNotFound(*state_root_hash).into());
+            }
+
+            let mut trie_keys_to_visit = vec![*state_root_hash];
+            while !trie_keys_to_visit.is_empty() {
+                let mut marks_txn = marks.environment.create_read_write_txn()?;
+                marked += self.mark_descendants(
+                    &txn,
+                    marks,
+                    &mut marks_txn,
+                    &mut trie_keys_to_visit,
+                    TRIE_GC_BATCH_SIZE,
+                )?;
+                marks_txn.commit()?;
+            }
+            progress(TrieGcProgress::Marked {
+                state_root_hash: *state_root_hash,
+                marked,
+            });
+        }
+        txn.commit()?;
+        Ok(())
+    }
+
+    /// Marks the tries in `trie_keys_to_visit` and all of their descendants, as read through
+    /// `txn`, stopping early once `limit` tries have been marked.  Returns the number of tries
+    /// marked, leaving any not yet visited in `trie_keys_to_visit`.
+    ///
+    /// The traversal is depth-first, so only the pending siblings along the current path are held
+    /// in memory.  Tries already marked are not traversed again, so shared subtries are only
+    /// visited once.
+    fn mark_descendants<T: lmdb::Transaction>(
+        &self,
+        txn: &T,
+        marks: &TrieGcMarks,
+        marks_txn: &mut lmdb::RwTransaction,
+        trie_keys_to_visit: &mut Vec<Digest>,
+        limit: usize,
+    ) -> Result<usize, error::Error> {
+        let mut marked = 0;
+        while marked < limit {
+            let trie_key = match trie_keys_to_visit.pop() {
+                Some(trie_key) => trie_key,
+                None => break,
+            };
+            if marks.contains(&*marks_txn, trie_key.as_ref())? {
+                continue;
+            }
+            let trie_bytes = match txn.get(self.trie_store.get_db(), &trie_key) {
+                Ok(trie_bytes) => trie_bytes,
+                Err(lmdb::Error::NotFound) => continue,
+                Err(error) => return Err(error.into()),
+            };
+            marks_txn.put(marks.db, &trie_key, &[], WriteFlags::empty())?;
+            marked += 1;
+
+            // Leaves have no descendants, so don't bother deserializing them.
+            if trie_bytes.first() == Some(&Trie::<Key, StoredValue>::LEAF_TAG) {
+                continue;
+            }
+            match bytesrepr::deserialize_from_slice::<_, Trie<Key, StoredValue>>(trie_bytes)? {
+                Trie::Leaf { .. } => {}
+                Trie::Node { pointer_block } => trie_keys_to_visit.extend(
+                    pointer_block
+                        .as_indexed_pointers()
+                        .map(|(_, pointer)| pointer.into_hash()),
+                ),
+                Trie::Extension { pointer, .. } => trie_keys_to_visit.push(pointer.into_hash()),
+            }
+        }
+        Ok(marked)
+    }
+
+    /// Scans every entry of the trie store, copying those in `marks` to `destination` if given.
+    fn sweep_tries(
+        &self,
+        marks: &TrieGcMarks,
+        destination: Option<&LmdbGlobalState>,
+        progress: &mut impl FnMut(TrieGcProgress),
+    ) -> Result<TrieGcSummary, error::Error> {
+        let mut summary = TrieGcSummary::default();
+        let txn = self.environment.create_read_txn()?;
+        let marks_txn = marks.environment.create_read_txn()?;
+        let mut cursor = lmdb::Transaction::open_ro_cursor(&txn, self.trie_store.get_db())?;
+        let mut maybe_destination_txn = destination
+            .map(|destination| destination.environment.create_read_write_txn())
+            .transpose()?;
+
+        for (key_bytes, value_bytes) in cursor.iter() {
+            let entry_bytes = (key_bytes.len() + value_bytes.len()) as u64;
+            summary.total_tries += 1;
+            summary.total_bytes += entry_bytes;
+
+            if marks.contains(&marks_txn, key_bytes)? {
+                summary.retained_tries += 1;
+                summary.retained_bytes += entry_bytes;
+                if let (Some(destination), Some(destination_txn)) =
+                    (destination, maybe_destination_txn.as_mut())
+                {
+                    destination_txn.put(
+                        destination.trie_store.get_db(),
+                        &key_bytes,
+                        &value_bytes,
+                        WriteFlags::empty(),
+                    )?;
+                }
+            }
+
+            if summary.total_tries % TRIE_GC_BATCH_SIZE == 0 {
+                if let (Some(destination), Some(destination_txn)) =
+                    (destination, maybe_destination_txn.take())
+                {
+                    destination_txn.commit()?;
+                    maybe_destination_txn = Some(destination.environment.create_read_write_txn()?);
+                }
+                progress(TrieGcProgress::Swept {
+                    scanned: summary.total_tries,
+                    retained: summary.retained_tries,
+                });
+            }
+        }
+
+        if let Some(destination_txn) = maybe_destination_txn {
+            destination_txn.commit()?;
+        }
+        drop(cursor);
+        txn.commit()?;
+        marks_txn.commit()?;
+        progress(TrieGcProgress::Swept {
+            scanned: summary.total_tries,
+            retained: summary.retained_tries,
+        });
+        Ok(summary)
+    }
 }
 
 impl StateReader<Key, StoredValue> for LmdbGlobalStateView {
@@ -215,14 +740,15 @@ impl CommitProvider for LmdbGlobalState {
         prestate_hash: Digest,
         effects: AdditiveMap<Key, Transform>,
     ) -> Result<Digest, Self::Error> {
-        commit::<LmdbEnvironment, LmdbTrieStore, _, Self::Error>(
-            &self.environment,
-            &self.trie_store,
-            correlation_id,
-            prestate_hash,
-            effects,
-        )
-        .map_err(Into::into)
+        self.write_tries(|| {
+            commit::<LmdbEnvironment, LmdbTrieStore, _, Self::Error>(
+                &self.environment,
+                &self.trie_store,
+                correlation_id,
+                prestate_hash,
+                effects,
+            )
+        })
     }
 }
 
@@ -289,16 +815,18 @@ impl StateProvider for LmdbGlobalState {
     }
 
     fn put_trie(&self, correlation_id: CorrelationId, trie: &[u8]) -> Result<Digest, Self::Error> {
-        let mut txn = self.environment.create_read_write_txn()?;
-        let trie_hash = put_trie::<
-            Key,
-            StoredValue,
-            lmdb::RwTransaction,
-            LmdbTrieStore,
-            Self::Error,
-        >(correlation_id, &mut txn, &self.trie_store, trie)?;
-        txn.commit()?;
-        Ok(trie_hash)
+        self.write_tries(|| {
+            let mut txn = self.environment.create_read_write_txn()?;
+            let trie_hash = put_trie::<
+                Key,
+                StoredValue,
+                lmdb::RwTransaction,
+                LmdbTrieStore,
+                Self::Error,
+            >(correlation_id, &mut txn, &self.trie_store, trie)?;
+            txn.commit()?;
+            Ok(trie_hash)
+        })
     }
 
     /// Finds all of the keys of missing descendant `Trie<K,V>` values.
@@ -375,7 +903,7 @@ impl StateProvider for LmdbGlobalState {
 #[cfg(test)]
 mod tests {
     use lmdb::DatabaseFlags;
-    use tempfile::tempdir;
+    use tempfile::{tempdir, TempDir};
 
     use casper_hashing::Digest;
     use casper_types::{account::AccountHash, bytesrepr, CLValue};
@@ -485,6 +1013,12 @@ mod tests {
         (ret, current_root)
     }
 
+    fn create_test_marks() -> (TempDir, TrieGcMarks) {
+        let temp_dir = tempdir().unwrap();
+        let marks = TrieGcMarks::new(temp_dir.path(), DEFAULT_TEST_MAX_DB_SIZE).unwrap();
+        (temp_dir, marks)
+    }
+
     #[test]
     fn reads_from_a_checkout_return_expected_values() {
         let correlation_id = CorrelationId::new();
@@ -664,4 +1198,244 @@ mod tests {
         };
         next_hash
     }
+
+    #[test]
+    fn compact_tries_retains_only_reachable_tries() {
+        let correlation_id = CorrelationId::new();
+        let test_pairs_updated = create_test_pairs_updated();
+
+        let (state, root_hash) = create_test_state(create_test_pairs);
+
+        let effects: AdditiveMap<Key, Transform> = {
+            let mut tmp = AdditiveMap::new();
+            for TestPair { key, value } in &test_pairs_updated {
+                tmp.insert(*key, Transform::Write(value.to_owned()));
+            }
+            tmp
+        };
+
+        let updated_hash = state.commit(correlation_id, root_hash, effects).unwrap();
+
+        let (_marks_dir, marks) = create_test_marks();
+        let estimate = state
+            .estimate_trie_gc(&[updated_hash], &marks, |_| ())
+            .unwrap();
+        assert!(estimate.removed_tries() > 0);
+        assert!(estimate.removed_bytes() > 0);
+
+        let temp_dir = tempdir().unwrap();
+        let environment = Arc::new(
+            LmdbEnvironment::new(
+                &temp_dir.path(),
+                DEFAULT_TEST_MAX_DB_SIZE,
+                DEFAULT_TEST_MAX_READERS,
+                true,
+            )
+            .unwrap(),
+        );
+        let trie_store =
+            Arc::new(LmdbTrieStore::new(&environment, None, DatabaseFlags::empty()).unwrap());
+        let destination = LmdbGlobalState::empty(environment, trie_store).unwrap();
+
+        let mut reports = Vec::new();
+        let summary = state
+            .compact_tries_into(&[updated_hash], &marks, &destination, |report| {
+                reports.push(report)
+            })
+            .unwrap();
+        assert_eq!(summary, estimate);
+        assert_eq!(
+            reports.first(),
+            Some(&TrieGcProgress::Marked {
+                state_root_hash: updated_hash,
+                marked: summary.retained_tries,
+            })
+        );
+        assert_eq!(
+            reports.last(),
+            Some(&TrieGcProgress::Swept {
+                scanned: summary.total_tries,
+                retained: summary.retained_tries,
+            })
+        );
+
+        let updated_checkout = destination.checkout(updated_hash).unwrap().unwrap();
+        for TestPair { key, value } in test_pairs_updated.iter().cloned() {
+            assert_eq!(
+                Some(value),
+                updated_checkout.read(correlation_id, &key).unwrap()
+            );
+        }
+        assert!(destination.checkout(root_hash).unwrap().is_none());
+    }
+
+    #[test]
+    fn prune_unreachable_tries_removes_only_unreachable_tries() {
+        let correlation_id = CorrelationId::new();
+        let test_pairs_updated = create_test_pairs_updated();
+
+        let (state, root_hash) = create_test_state(create_test_pairs);
+
+        let effects: AdditiveMap<Key, Transform> = {
+            let mut tmp = AdditiveMap::new();
+            for TestPair { key, value } in &test_pairs_updated {
+                tmp.insert(*key, Transform::Write(value.to_owned()));
+            }
+            tmp
+        };
+
+        let updated_hash = state.commit(correlation_id, root_hash, effects).unwrap();
+
+        let (_marks_dir, marks) = create_test_marks();
+        let estimate = state
+            .estimate_trie_gc(&[updated_hash], &marks, |_| ())
+            .unwrap();
+        let summary = state
+            .prune_unreachable_tries(&[updated_hash], &marks, |_| ())
+            .unwrap();
+        assert_eq!(summary.total_tries, estimate.total_tries);
+        assert!(summary.removed_tries() > 0);
+
+        let after = state
+            .estimate_trie_gc(&[updated_hash], &marks, |_| ())
+            .unwrap();
+        assert_eq!(
+            after.removed_tries(),
+            1,
+            "only the empty root should remain unreachable"
+        );
+        assert_eq!(after.total_tries, summary.retained_tries);
+
+        let updated_checkout = state.checkout(updated_hash).unwrap().unwrap();
+        for TestPair { key, value } in test_pairs_updated.iter().cloned() {
+            assert_eq!(
+                Some(value),
+                updated_checkout.read(correlation_id, &key).unwrap()
+            );
+        }
+        assert!(state.checkout(root_hash).unwrap().is_none());
+        assert!(state.checkout(state.empty_root_hash).unwrap().is_some());
+    }
+
+    #[test]
+    fn prune_unreachable_tries_retains_tries_written_after_begin() {
+        let correlation_id = CorrelationId::new();
+        let test_pairs = create_test_pairs();
+        let test_pairs_updated = create_test_pairs_updated();
+
+        let (state, root_hash) = create_test_state(create_test_pairs);
+        let effects = |pairs: &[TestPair]| {
+            let mut tmp = AdditiveMap::new();
+            for TestPair { key, value } in pairs {
+                tmp.insert(*key, Transform::Write(value.to_owned()));
+            }
+            tmp
+        };
+        let updated_hash = state
+            .commit(correlation_id, root_hash, effects(&test_pairs_updated))
+            .unwrap();
+
+        // A block committed after the garbage collection is scheduled, but before it marks the
+        // retained tries, rewrites leaves which are only reachable from the pruned state root.
+        state.begin_trie_gc();
+        let reverted_hash = state
+            .commit(correlation_id, updated_hash, effects(&test_pairs))
+            .unwrap();
+
+        let (_marks_dir, marks) = create_test_marks();
+        state
+            .prune_unreachable_tries(&[updated_hash], &marks, |_| ())
+            .unwrap();
+
+        let reverted_checkout = state.checkout(reverted_hash).unwrap().unwrap();
+        for TestPair { key, value } in test_pairs.iter().cloned() {
+            assert_eq!(
+                Some(value),
+                reverted_checkout.read(correlation_id, &key).unwrap()
+            );
+        }
+        assert!(state
+            .missing_trie_keys(correlation_id, vec![reverted_hash])
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn trie_gc_should_fail_for_unknown_state_root_hash() {
+        let (state, _) = create_test_state(create_test_pairs);
+        let unknown_hash = Digest::hash(b"unknown");
+        let (_marks_dir, marks) = create_test_marks();
+        let result = state.estimate_trie_gc(&[unknown_hash], &marks, |_| ());
+        assert!(matches!(
+            result,
+            Err(error::Error::CommitError(CommitError::RootNotFound(hash))) if hash == unknown_hash
+        ));
+    }
+
+    #[test]
+    fn visit_entries_with_prefix_returns_entries_after_start_key_in_order() {
+        let correlation_id = CorrelationId::new();
+        let test_pairs_updated = create_test_pairs_updated();
+
+        let (state, root_hash) = create_test_state(create_test_pairs);
+
+        let effects: AdditiveMap<Key, Transform> = {
+            let mut tmp = AdditiveMap::new();
+            for TestPair { key, value } in &test_pairs_updated {
+                tmp.insert(*key, Transform::Write(value.to_owned()));
+            }
+            tmp.insert(
+                Key::Hash([1u8; 32]),
+                Transform::Write(StoredValue::CLValue(CLValue::from_t(()).unwrap())),
+            );
+            tmp
+        };
+        let updated_hash = state.commit(correlation_id, root_hash, effects).unwrap();
+        let prefix = [casper_types::KeyTag::Account as u8];
+
+        let mut visited = Vec::new();
+        let root_found = state
+            .visit_entries_with_prefix(
+                correlation_id,
+                updated_hash,
+                &prefix,
+                None,
+                true,
+                |key, value| {
+                    visited.push((key, value));
+                    true
+                },
+            )
+            .unwrap();
+        assert!(root_found);
+        let expected: Vec<(Key, Option<StoredValue>)> = test_pairs_updated
+            .iter()
+            .map(|TestPair { key, value }| (*key, Some(value.clone())))
+            .collect();
+        assert_eq!(visited, expected);
+
+        let mut visited = Vec::new();
+        state
+            .visit_entries_with_prefix(
+                correlation_id,
+                updated_hash,
+                &prefix,
+                Some(&test_pairs_updated[0].key),
+                false,
+                |key, value| {
+                    visited.push((key, value));
+                    false
+                },
+            )
+            .unwrap();
+        assert_eq!(visited, vec![(test_pairs_updated[1].key, None)]);
+
+        let unknown_hash = Digest::hash(b"unknown");
+        let root_found = state
+            .visit_entries_with_prefix(correlation_id, unknown_hash, &prefix, None, true, |_, _| {
+                true
+            })
+            .unwrap();
+        assert!(!root_found);
+    }
 }
@@ -9,7 +9,10 @@ pub mod lmdb;
 /// Lmdb implementation of global state with cache.
 pub mod scratch;
 
-use std::{collections::HashMap, hash::BuildHasher};
+use std::{
+    collections::{HashMap, HashSet},
+    hash::BuildHasher,
+};
 
 use tracing::error;
 
@@ -23,10 +26,10 @@ use crate::{
         transform::{self, Transform},
     },
     storage::{
-        transaction_source::{Transaction, TransactionSource},
+        transaction_source::{Readable, Transaction, TransactionSource, Writable},
         trie::{merkle_proof::TrieMerkleProof, Trie, TrieOrChunk, TrieOrChunkId},
         trie_store::{
-            operations::{read, write, ReadResult, WriteResult},
+            operations::{delete, read, write, DeleteResult, ReadResult, WriteResult},
             TrieStore,
         },
     },
@@ -136,6 +139,7 @@ pub fn put_stored_values<'a, R, S, E>(
     correlation_id: CorrelationId,
     prestate_hash: Digest,
     stored_values: HashMap<Key, StoredValue>,
+    keys_to_prune: HashSet<Key>,
 ) -> Result<Digest, E>
 where
     R: TransactionSource<'a, Handle = S::Handle>,
@@ -163,10 +167,38 @@ where
             }
         }
     }
+    for key in keys_to_prune.iter() {
+        state_root = prune::<_, _, E>(correlation_id, &mut txn, store, state_root, key)?;
+    }
     txn.commit()?;
     Ok(state_root)
 }
 
+/// Removes `key` from the trie at `state_root`, returning the resulting root.  Removing a key which
+/// does not exist leaves the state root unchanged.
+fn prune<T, S, E>(
+    correlation_id: CorrelationId,
+    txn: &mut T,
+    store: &S,
+    state_root: Digest,
+    key: &Key,
+) -> Result<Digest, E>
+where
+    T: Readable<Handle = S::Handle> + Writable<Handle = S::Handle>,
+    S: TrieStore<Key, StoredValue>,
+    S::Error: From<T::Error>,
+    E: From<S::Error> + From<bytesrepr::Error> + From<CommitError>,
+{
+    match delete::<_, StoredValue, _, _, E>(correlation_id, txn, store, &state_root, key)? {
+        DeleteResult::Deleted(root_hash) => Ok(root_hash),
+        DeleteResult::DoesNotExist => Ok(state_root),
+        DeleteResult::RootNotFound => {
+            error!(?state_root, ?key, "Error pruning key");
+            Err(CommitError::WriteRootNotFound(state_root).into())
+        }
+    }
+}
+
 /// Commit `effects` to the store.
 pub fn commit<'a, R, S, H, E>(
     environment: &'a R,
@@ -192,6 +224,11 @@ where
     };
 
     for (key, transform) in effects.into_iter() {
+        if let Transform::Prune = transform {
+            state_root = prune::<_, _, E>(correlation_id, &mut txn, store, state_root, &key)?;
+            continue;
+        }
+
         let read_result = read::<_, _, _, _, E>(correlation_id, &txn, store, &state_root, &key)?;
 
         let value = match (read_result, transform) {
@@ -1,5 +1,5 @@
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     mem,
     ops::Deref,
     sync::{Arc, RwLock},
@@ -31,16 +31,19 @@ type SharedCache = Arc<RwLock<Cache>>;
 
 struct Cache {
     cached_values: HashMap<Key, (bool, StoredValue)>,
+    pruned: HashSet<Key>,
 }
 
 impl Cache {
     fn new() -> Self {
         Cache {
             cached_values: HashMap::new(),
+            pruned: HashSet::new(),
         }
     }
 
     fn insert_write(&mut self, key: Key, value: StoredValue) {
+        self.pruned.remove(&key);
         self.cached_values.insert(key, (true, value));
     }
 
@@ -48,17 +51,28 @@ impl Cache {
         self.cached_values.entry(key).or_insert((false, value));
     }
 
+    fn insert_prune(&mut self, key: Key) {
+        self.cached_values.remove(&key);
+        self.pruned.insert(key);
+    }
+
+    fn is_pruned(&self, key: &Key) -> bool {
+        self.pruned.contains(key)
+    }
+
     fn get(&self, key: &Key) -> Option<&StoredValue> {
         self.cached_values.get(key).map(|(_dirty, value)| value)
     }
 
     /// Consumes self and returns only written values as values that were only read must be filtered
-    /// out to prevent unnecessary writes.
-    fn into_dirty_writes(self) -> HashMap<Key, StoredValue> {
-        self.cached_values
+    /// out to prevent unnecessary writes, along with the keys which were pruned.
+    fn into_dirty_writes(self) -> (HashMap<Key, StoredValue>, HashSet<Key>) {
+        let dirty_writes = self
+            .cached_values
             .into_iter()
             .filter_map(|(key, (dirty, value))| if dirty { Some((key, value)) } else { None })
-            .collect()
+            .collect();
+        (dirty_writes, self.pruned)
     }
 }
 
@@ -102,8 +116,8 @@ impl ScratchGlobalState {
         }
     }
 
-    /// Consume self and return inner cache.
-    pub fn into_inner(self) -> HashMap<Key, StoredValue> {
+    /// Consume self and return inner cache, split into the written values and the pruned keys.
+    pub fn into_inner(self) -> (HashMap<Key, StoredValue>, HashSet<Key>) {
         let cache = mem::replace(&mut *self.cache.write().unwrap(), Cache::new());
         cache.into_dirty_writes()
     }
@@ -117,8 +131,14 @@ impl StateReader<Key, StoredValue> for ScratchGlobalStateView {
         correlation_id: CorrelationId,
         key: &Key,
     ) -> Result<Option<StoredValue>, Self::Error> {
-        if let Some(value) = self.cache.read().unwrap().get(key) {
-            return Ok(Some(value.clone()));
+        {
+            let cache = self.cache.read().unwrap();
+            if cache.is_pruned(key) {
+                return Ok(None);
+            }
+            if let Some(value) = cache.get(key) {
+                return Ok(Some(value.clone()));
+            }
         }
         let txn = self.environment.create_read_txn()?;
         let ret = match read::<Key, StoredValue, lmdb::RoTransaction, LmdbTrieStore, Self::Error>(
@@ -180,8 +200,10 @@ impl StateReader<Key, StoredValue> for ScratchGlobalStateView {
             prefix,
         );
         let mut ret = Vec::new();
+        let cache = self.cache.read().unwrap();
         for result in keys_iter {
             match result {
+                Ok(key) if cache.is_pruned(&key) => (),
                 Ok(key) => ret.push(key),
                 Err(error) => return Err(error),
             }
@@ -201,9 +223,22 @@ impl CommitProvider for ScratchGlobalState {
         effects: AdditiveMap<Key, Transform>,
     ) -> Result<Digest, Self::Error> {
         for (key, transform) in effects.into_iter() {
+            if let Transform::Prune = transform {
+                self.cache.write().unwrap().insert_prune(key);
+                continue;
+            }
+
             let cached_value = self.cache.read().unwrap().get(&key).cloned();
             let value = match (cached_value, transform) {
                 (None, Transform::Write(new_value)) => new_value,
+                (None, transform) if self.cache.read().unwrap().is_pruned(&key) => {
+                    error!(
+                        ?key,
+                        ?transform,
+                        "Key pruned while attempting to apply transform"
+                    );
+                    return Err(CommitError::KeyNotFound(key).into());
+                }
                 (None, transform) => {
                     // It might be the case that for `Add*` operations we don't have the previous
                     // value in cache yet.
@@ -512,7 +547,7 @@ mod tests {
             .keys_with_prefix(correlation_id, &[])
             .unwrap();
 
-        let stored_values = scratch.into_inner();
+        let (stored_values, _) = scratch.into_inner();
         assert_eq!(all_keys.len(), stored_values.len());
 
         for key in all_keys {
@@ -534,6 +569,39 @@ mod tests {
         }
     }
 
+    #[test]
+    fn compute_stored_values_root_does_not_write_tries() {
+        let correlation_id = CorrelationId::new();
+        let test_pairs_updated = create_test_pairs_updated();
+
+        let TestState { state, root_hash } = create_test_state();
+
+        let scratch = state.create_scratch();
+
+        let effects: AdditiveMap<Key, Transform> = {
+            let mut tmp = AdditiveMap::new();
+            for TestPair { key, value } in &test_pairs_updated {
+                tmp.insert(*key, Transform::Write(value.to_owned()));
+            }
+            tmp
+        };
+
+        scratch
+            .commit(correlation_id, root_hash, effects.clone())
+            .unwrap();
+        let (stored_values, keys_to_prune) = scratch.into_inner();
+        let computed_hash = state
+            .compute_stored_values_root(correlation_id, root_hash, stored_values, keys_to_prune)
+            .unwrap();
+        assert!(
+            state.checkout(computed_hash).unwrap().is_none(),
+            "computing the root should not write it to the trie store"
+        );
+
+        let lmdb_hash = state.commit(correlation_id, root_hash, effects).unwrap();
+        assert_eq!(computed_hash, lmdb_hash);
+    }
+
     #[test]
     fn commit_updates_state_with_add() {
         let correlation_id = CorrelationId::new();
@@ -638,4 +706,53 @@ mod tests {
                 .unwrap()
         );
     }
+
+    #[test]
+    fn commit_prunes_key() {
+        let correlation_id = CorrelationId::new();
+        let test_pairs = create_test_pairs();
+        let pruned_key = test_pairs[0].key;
+
+        let TestState { state, root_hash } = create_test_state();
+
+        let scratch = state.create_scratch();
+
+        let effects: AdditiveMap<Key, Transform> = {
+            let mut tmp = AdditiveMap::new();
+            tmp.insert(pruned_key, Transform::Prune);
+            tmp
+        };
+
+        scratch
+            .commit(correlation_id, root_hash, effects.clone())
+            .unwrap();
+
+        let scratch_checkout = scratch.checkout(root_hash).unwrap().unwrap();
+        assert_eq!(
+            None,
+            scratch_checkout.read(correlation_id, &pruned_key).unwrap()
+        );
+        assert_eq!(
+            vec![test_pairs[1].key],
+            scratch_checkout
+                .keys_with_prefix(correlation_id, &[])
+                .unwrap()
+        );
+
+        let lmdb_hash = state.commit(correlation_id, root_hash, effects).unwrap();
+        let updated_checkout = state.checkout(lmdb_hash).unwrap().unwrap();
+        assert_eq!(
+            None,
+            updated_checkout.read(correlation_id, &pruned_key).unwrap()
+        );
+
+        let (stored_values, keys_to_prune) = scratch.into_inner();
+        assert!(stored_values.is_empty());
+        assert!(keys_to_prune.contains(&pruned_key));
+
+        let written_hash = state
+            .put_stored_values(correlation_id, root_hash, stored_values, keys_to_prune)
+            .unwrap();
+        assert_eq!(written_hash, lmdb_hash);
+    }
 }
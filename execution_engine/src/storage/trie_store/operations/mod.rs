@@ -502,8 +502,9 @@ pub enum DeleteResult {
     RootNotFound,
 }
 
-#[allow(unused)]
-fn delete<K, V, T, S, E>(
+/// Removes the value stored under `key_to_delete` from the trie at the given root, collapsing any
+/// nodes and extensions made redundant by the removal.
+pub fn delete<K, V, T, S, E>(
     correlation_id: CorrelationId,
     txn: &mut T,
     store: &S,
@@ -1077,21 +1078,95 @@ enum KeysIteratorState<K, V, S: TrieStore<K, V>> {
     Failed,
 }
 
+/// A trie visited by an [`EntriesIterator`], with the value of a leaf left unread if the iterator
+/// doesn't return values.
+enum VisitedTrie<K, V> {
+    Leaf {
+        key: K,
+        value: Option<V>,
+    },
+    Node {
+        pointer_block: Box<PointerBlock>,
+    },
+    Extension {
+        affix: bytesrepr::Bytes,
+        pointer: Pointer,
+    },
+}
+
+impl<K, V> From<Trie<K, V>> for VisitedTrie<K, V> {
+    fn from(trie: Trie<K, V>) -> Self {
+        match trie {
+            Trie::Leaf { key, value } => VisitedTrie::Leaf {
+                key,
+                value: Some(value),
+            },
+            Trie::Node { pointer_block } => VisitedTrie::Node { pointer_block },
+            Trie::Extension { affix, pointer } => VisitedTrie::Extension { affix, pointer },
+        }
+    }
+}
+
 struct VisitedTrieNode<K, V> {
-    trie: Trie<K, V>,
+    trie: VisitedTrie<K, V>,
     maybe_index: Option<usize>,
     path: Vec<u8>,
 }
 
-pub struct KeysIterator<'a, 'b, K, V, T, S: TrieStore<K, V>> {
+/// Returns the index of the first child of a node at `path` which may hold keys after
+/// `start_after`, or `None` if every key under `path` is at or before `start_after`.
+fn first_index_after(path: &[u8], start_after: &[u8]) -> Option<usize> {
+    let len = cmp::min(path.len(), start_after.len());
+    match path[..len].cmp(&start_after[..len]) {
+        cmp::Ordering::Less => None,
+        cmp::Ordering::Greater => Some(0),
+        cmp::Ordering::Equal => Some(start_after.get(len).map_or(0, |index| *index as usize)),
+    }
+}
+
+pub struct EntriesIterator<'a, 'b, K, V, T, S: TrieStore<K, V>> {
     initial_descend: VecDeque<u8>,
+    start_after: Option<Vec<u8>>,
     visited: Vec<VisitedTrieNode<K, V>>,
+    read_values: bool,
     store: &'a S,
     txn: &'b T,
     state: KeysIteratorState<K, V, S>,
 }
 
-impl<'a, 'b, K, V, T, S> Iterator for KeysIterator<'a, 'b, K, V, T, S>
+impl<'a, 'b, K, V, T, S> EntriesIterator<'a, 'b, K, V, T, S>
+where
+    K: FromBytes,
+    V: FromBytes,
+    T: Readable<Handle = S::Handle>,
+    S: TrieStore<K, V>,
+    S::Error: From<T::Error> + From<bytesrepr::Error>,
+{
+    /// Reads the trie `pointer` points to, deserializing only the key of a leaf unless values are
+    /// returned.
+    fn read(&self, pointer: &Pointer) -> Result<Option<VisitedTrie<K, V>>, S::Error> {
+        match pointer {
+            Pointer::LeafPointer(trie_key) if !self.read_values => {
+                match self.store.get_raw(self.txn, trie_key)? {
+                    Some(trie_bytes) => match trie_bytes.split_first() {
+                        Some((&Trie::<K, V>::LEAF_TAG, rem)) => {
+                            let (key, _) = K::from_bytes(rem)?;
+                            Ok(Some(VisitedTrie::Leaf { key, value: None }))
+                        }
+                        _ => Err(bytesrepr::Error::Formatting.into()),
+                    },
+                    None => Ok(None),
+                }
+            }
+            _ => Ok(self
+                .store
+                .get(self.txn, pointer.hash())?
+                .map(VisitedTrie::from)),
+        }
+    }
+}
+
+impl<'a, 'b, K, V, T, S> Iterator for EntriesIterator<'a, 'b, K, V, T, S>
 where
     K: ToBytes + FromBytes + Clone + Eq + std::fmt::Debug,
     V: ToBytes + FromBytes + Clone + Eq + std::fmt::Debug,
@@ -1099,7 +1174,7 @@ where
     S: TrieStore<K, V>,
     S::Error: From<T::Error> + From<bytesrepr::Error>,
 {
-    type Item = Result<K, S::Error>;
+    type Item = Result<(K, Option<V>), S::Error>;
 
     fn next(&mut self) -> Option<Self::Item> {
         match mem::replace(&mut self.state, KeysIteratorState::Ok) {
@@ -1118,10 +1193,10 @@ where
             mut path,
         }) = self.visited.pop()
         {
-            let mut maybe_next_trie: Option<Trie<K, V>> = None;
+            let mut maybe_next_trie: Option<VisitedTrie<K, V>> = None;
 
             match trie {
-                Trie::Leaf { key, .. } => {
+                VisitedTrie::Leaf { key, value } => {
                     let key_bytes = match key.to_bytes() {
                         Ok(bytes) => bytes,
                         Err(e) => {
@@ -1132,23 +1207,36 @@ where
                     debug_assert!(key_bytes.starts_with(&path));
                     // only return the leaf if it matches the initial descend path
                     path.extend(&self.initial_descend);
-                    if key_bytes.starts_with(&path) {
-                        return Some(Ok(key));
+                    let is_after_start = self
+                        .start_after
+                        .as_ref()
+                        .map_or(true, |start_after| key_bytes > *start_after);
+                    if key_bytes.starts_with(&path) && is_after_start {
+                        return Some(Ok((key, value)));
                     }
                 }
-                Trie::Node { ref pointer_block } => {
+                VisitedTrie::Node { ref pointer_block } => {
                     // if we are still initially descending (and initial_descend is not empty), take
                     // the first index we should descend to, otherwise take maybe_index from the
                     // visited stack
-                    let mut index: usize = self
-                        .initial_descend
-                        .front()
-                        .map(|i| *i as usize)
-                        .or(maybe_index)
-                        .unwrap_or_default();
+                    let mut index: usize = match self.initial_descend.front() {
+                        Some(i) => *i as usize,
+                        None => match (maybe_index, self.start_after.as_ref()) {
+                            (Some(index), _) => index,
+                            // on the first visit after the initial descend, skip the children
+                            // holding only keys at or before `start_after`
+                            (None, Some(start_after)) => {
+                                match first_index_after(&path, start_after) {
+                                    Some(index) => index,
+                                    None => continue,
+                                }
+                            }
+                            (None, None) => 0,
+                        },
+                    };
                     while index < RADIX {
                         if let Some(ref pointer) = pointer_block[index] {
-                            maybe_next_trie = match self.store.get(self.txn, pointer.hash()) {
+                            maybe_next_trie = match self.read(pointer) {
                                 Ok(trie) => trie,
                                 Err(e) => {
                                     self.state = KeysIteratorState::Failed;
@@ -1175,7 +1263,7 @@ where
                         index += 1;
                     }
                 }
-                Trie::Extension { affix, pointer } => {
+                VisitedTrie::Extension { affix, pointer } => {
                     let descend_len = cmp::min(self.initial_descend.len(), affix.len());
                     let check_prefix = self
                         .initial_descend
@@ -1185,16 +1273,21 @@ where
                     // matches the descend path
                     // if we are not, the check_prefix will be empty, so we will enter the if
                     // anyway
-                    if affix.starts_with(&check_prefix) {
-                        maybe_next_trie = match self.store.get(self.txn, pointer.hash()) {
+                    path.extend(affix.iter());
+                    let is_after_start = self.start_after.as_ref().map_or(true, |start_after| {
+                        first_index_after(&path, start_after).is_some()
+                    });
+                    if affix.starts_with(&check_prefix) && is_after_start {
+                        maybe_next_trie = match self.read(&pointer) {
                             Ok(trie) => trie,
                             Err(e) => {
                                 self.state = KeysIteratorState::Failed;
                                 return Some(Err(e));
                             }
                         };
-                        debug_assert!({ matches!(&maybe_next_trie, Some(Trie::Node { .. })) });
-                        path.extend(affix);
+                        debug_assert!({
+                            matches!(&maybe_next_trie, Some(VisitedTrie::Node { .. }))
+                        });
                     }
                 }
             }
@@ -1231,16 +1324,66 @@ where
     keys_with_prefix(correlation_id, txn, store, root, &[])
 }
 
+pub struct KeysIterator<'a, 'b, K, V, T, S: TrieStore<K, V>>(EntriesIterator<'a, 'b, K, V, T, S>);
+
+impl<'a, 'b, K, V, T, S> Iterator for KeysIterator<'a, 'b, K, V, T, S>
+where
+    K: ToBytes + FromBytes + Clone + Eq + std::fmt::Debug,
+    V: ToBytes + FromBytes + Clone + Eq + std::fmt::Debug,
+    T: Readable<Handle = S::Handle>,
+    S: TrieStore<K, V>,
+    S::Error: From<T::Error> + From<bytesrepr::Error>,
+{
+    type Item = Result<K, S::Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|result| result.map(|(key, _)| key))
+    }
+}
+
 /// Returns the iterator over the keys in the subtrie matching `prefix`.
 ///
 /// The root should be the apex of the trie.
 pub fn keys_with_prefix<'a, 'b, K, V, T, S>(
-    _correlation_id: CorrelationId,
+    correlation_id: CorrelationId,
     txn: &'b T,
     store: &'a S,
     root: &Digest,
     prefix: &[u8],
 ) -> KeysIterator<'a, 'b, K, V, T, S>
+where
+    K: ToBytes + FromBytes + Clone + Eq + std::fmt::Debug,
+    V: ToBytes + FromBytes + Clone + Eq + std::fmt::Debug,
+    T: Readable<Handle = S::Handle>,
+    S: TrieStore<K, V>,
+    S::Error: From<T::Error>,
+{
+    KeysIterator(entries_with_prefix(
+        correlation_id,
+        txn,
+        store,
+        root,
+        prefix,
+        None,
+        false,
+    ))
+}
+
+/// Returns the iterator over the entries in the subtrie matching `prefix`, in ascending order of
+/// their serialized keys, starting after the serialized key `start_after` if given.
+///
+/// Subtries holding only keys at or before `start_after` are not traversed.  The values of the
+/// entries are only deserialized and returned if `read_values` is `true`, and are `None`
+/// otherwise.  The root should be the apex of the trie.
+pub fn entries_with_prefix<'a, 'b, K, V, T, S>(
+    _correlation_id: CorrelationId,
+    txn: &'b T,
+    store: &'a S,
+    root: &Digest,
+    prefix: &[u8],
+    start_after: Option<Vec<u8>>,
+    read_values: bool,
+) -> EntriesIterator<'a, 'b, K, V, T, S>
 where
     K: ToBytes + FromBytes + Clone + Eq + std::fmt::Debug,
     V: ToBytes + FromBytes + Clone + Eq + std::fmt::Debug,
@@ -1253,7 +1396,7 @@ where
         Err(e) => (vec![], KeysIteratorState::ReturnError(e)),
         Ok(Some(current_root)) => (
             vec![VisitedTrieNode {
-                trie: current_root,
+                trie: current_root.into(),
                 maybe_index: None,
                 path: vec![],
             }],
@@ -1261,9 +1404,11 @@ where
         ),
     };
 
-    KeysIterator {
+    EntriesIterator {
         initial_descend: prefix.iter().cloned().collect(),
+        start_after,
         visited,
+        read_values,
         store,
         txn,
         state: init_state,
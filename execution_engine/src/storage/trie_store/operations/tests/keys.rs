@@ -314,3 +314,88 @@ mod keys_with_prefix_iterator {
         test_prefix(&[0, 0, 0, 0, 0, 0, 1]); // 1 leaf
     }
 }
+
+mod entries_with_prefix_iterator {
+    use crate::{
+        shared::newtypes::CorrelationId,
+        storage::{
+            transaction_source::TransactionSource,
+            trie::Trie,
+            trie_store::operations::{
+                self,
+                tests::{create_6_leaf_trie, InMemoryTestContext, TestKey, TestValue, TEST_LEAVES},
+            },
+        },
+    };
+
+    fn expected_entries(prefix: &[u8], start_after: Option<&[u8]>) -> Vec<(TestKey, TestValue)> {
+        let mut tmp = TEST_LEAVES
+            .iter()
+            .filter_map(|leaf| match leaf {
+                Trie::Leaf { key, value } => Some((*key, *value)),
+                _ => None,
+            })
+            .filter(|(key, _)| key.0.starts_with(prefix))
+            .filter(|(key, _)| start_after.map_or(true, |start_after| key.0[..] > *start_after))
+            .collect::<Vec<_>>();
+        tmp.sort_by_key(|(key, _)| *key);
+        tmp
+    }
+
+    fn test_prefix_and_start_after(prefix: &[u8], start_after: Option<&[u8]>, read_values: bool) {
+        let correlation_id = CorrelationId::new();
+        let (root_hash, tries) = create_6_leaf_trie().expect("should create a trie");
+        let context = InMemoryTestContext::new(&tries).expect("should create a new context");
+        let txn = context
+            .environment
+            .create_read_txn()
+            .expect("should create a read txn");
+        let expected = expected_entries(prefix, start_after)
+            .into_iter()
+            .map(|(key, value)| (key, Some(value).filter(|_| read_values)))
+            .collect::<Vec<_>>();
+        // The entries should be returned in ascending order without sorting.
+        let actual = operations::entries_with_prefix::<TestKey, TestValue, _, _>(
+            correlation_id,
+            &txn,
+            &context.store,
+            &root_hash,
+            prefix,
+            start_after.map(<[u8]>::to_vec),
+            read_values,
+        )
+        .collect::<Result<Vec<_>, _>>()
+        .expect("should iterate entries");
+        assert_eq!(
+            expected, actual,
+            "prefix {:?}, start after {:?}, read values {}",
+            prefix, start_after, read_values
+        );
+    }
+
+    #[test]
+    fn test_start_after() {
+        let prefixes: [&[u8]; 4] = [&[], &[0], &[0, 0], &[0, 0, 0, 0]];
+        let start_afters: [&[u8]; 11] = [
+            &[],
+            &[0],
+            &[0, 0, 0, 0, 0, 0, 0],
+            &[0, 0, 0, 0, 0, 0, 1],
+            &[0, 0, 0, 0, 0, 1],
+            &[0, 0, 0, 0, 0, 255, 0],
+            &[0, 0, 0, 1],
+            &[0, 0, 1],
+            &[0, 0, 2, 0, 0, 0, 0],
+            &[0, 1, 0, 0, 0, 0, 0],
+            &[1],
+        ];
+        for prefix in prefixes {
+            for read_values in [true, false] {
+                test_prefix_and_start_after(prefix, None, read_values);
+                for start_after in start_afters {
+                    test_prefix_and_start_after(prefix, Some(start_after), read_values);
+                }
+            }
+        }
+    }
+}
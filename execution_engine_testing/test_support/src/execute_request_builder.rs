@@ -5,8 +5,9 @@ use rand::Rng;
 use casper_execution_engine::core::engine_state::{
     deploy_item::DeployItem, execute_request::ExecuteRequest,
 };
+use casper_hashing::Digest;
 use casper_types::{
-    account::AccountHash, runtime_args, ContractHash, ContractPackageHash, ContractVersion,
+    account::AccountHash, runtime_args, ContractHash, ContractPackageHash, ContractVersion, EraId,
     ProtocolVersion, RuntimeArgs,
 };
 
@@ -61,6 +62,24 @@ impl ExecuteRequestBuilder {
         self
     }
 
+    /// Sets the block height of the [`ExecuteRequest`].
+    pub fn with_block_height(mut self, block_height: u64) -> Self {
+        self.execute_request.block_height = block_height;
+        self
+    }
+
+    /// Sets the era id of the [`ExecuteRequest`].
+    pub fn with_era_id(mut self, era_id: EraId) -> Self {
+        self.execute_request.era_id = era_id;
+        self
+    }
+
+    /// Sets the parent block hash of the [`ExecuteRequest`].
+    pub fn with_parent_block_hash(mut self, parent_block_hash: Digest) -> Self {
+        self.execute_request.parent_block_hash = parent_block_hash;
+        self
+    }
+
     /// Consumes self and returns an [`ExecuteRequest`].
     pub fn build(self) -> ExecuteRequest {
         self.execute_request
@@ -230,6 +230,9 @@ pub fn transfer_to_account_multiple_native_transfers(
             exec_request.deploys.clone(),
             exec_request.protocol_version,
             exec_request.proposer.clone(),
+            exec_request.block_height,
+            exec_request.era_id,
+            exec_request.parent_block_hash,
         );
         if use_scratch {
             builder.scratch_exec_and_commit(request).expect_success();
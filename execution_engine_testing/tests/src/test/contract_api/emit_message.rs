@@ -0,0 +1,74 @@
+use casper_engine_test_support::{
+    ExecuteRequestBuilder, InMemoryWasmTestBuilder, DEFAULT_ACCOUNT_ADDR,
+    PRODUCTION_RUN_GENESIS_REQUEST,
+};
+use casper_execution_engine::core::{engine_state::Error, execution};
+use casper_types::{
+    runtime_args, ApiError, CLValue, ContractMessage, Key, RuntimeArgs,
+    CONTRACT_MESSAGE_TOPIC_MAX_LENGTH,
+};
+
+const EMIT_MESSAGE_WASM: &str = "emit_message.wasm";
+const ARG_TOPIC: &str = "topic";
+const ARG_VALUE: &str = "value";
+const TOPIC: &str = "transfer";
+const VALUE: u64 = 42;
+
+fn exec_emit_message(builder: &mut InMemoryWasmTestBuilder, topic: &str) {
+    let exec_request = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        EMIT_MESSAGE_WASM,
+        runtime_args! {
+            ARG_TOPIC => topic.to_string(),
+            ARG_VALUE => VALUE,
+        },
+    )
+    .build();
+    builder.exec(exec_request).commit();
+}
+
+#[ignore]
+#[test]
+fn should_record_emitted_message() {
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder.run_genesis(&PRODUCTION_RUN_GENESIS_REQUEST);
+
+    exec_emit_message(&mut builder, TOPIC);
+    builder.expect_success();
+
+    let exec_results = builder
+        .get_last_exec_results()
+        .expect("should have exec results");
+    let expected_message = ContractMessage::new(
+        Key::Account(*DEFAULT_ACCOUNT_ADDR),
+        TOPIC.to_string(),
+        CLValue::from_t(VALUE).unwrap(),
+    );
+    assert_eq!(exec_results[0].messages(), &[expected_message]);
+}
+
+#[ignore]
+#[test]
+fn should_fail_to_emit_message_with_invalid_topic() {
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder.run_genesis(&PRODUCTION_RUN_GENESIS_REQUEST);
+
+    let too_long_topic = "a".repeat(CONTRACT_MESSAGE_TOPIC_MAX_LENGTH + 1);
+    for topic in ["", too_long_topic.as_str()] {
+        exec_emit_message(&mut builder, topic);
+
+        let error = builder.get_error().expect("should have error");
+        assert!(
+            matches!(
+                error,
+                Error::Exec(execution::Error::Revert(ApiError::InvalidArgument))
+            ),
+            "{:?}",
+            error
+        );
+        let exec_results = builder
+            .get_last_exec_results()
+            .expect("should have exec results");
+        assert!(exec_results[0].messages().is_empty());
+    }
+}
@@ -0,0 +1,70 @@
+use casper_engine_test_support::{
+    ExecuteRequestBuilder, InMemoryWasmTestBuilder, DEFAULT_ACCOUNT_ADDR, DEFAULT_PROTOCOL_VERSION,
+    PRODUCTION_RUN_GENESIS_REQUEST,
+};
+use casper_hashing::Digest;
+use casper_types::{
+    bytesrepr::FromBytes, runtime_args, CLTyped, EraId, Key, RuntimeArgs, BLAKE2B_DIGEST_LENGTH,
+};
+
+const CONTRACT_GET_BLOCK_INFO: &str = "get_block_info.wasm";
+const BLOCK_HEIGHT_KEY: &str = "block_height";
+const ERA_ID_KEY: &str = "era_id";
+const PROTOCOL_VERSION_KEY: &str = "protocol_version";
+const PARENT_BLOCK_HASH_KEY: &str = "parent_block_hash";
+const STATE_ROOT_HASH_KEY: &str = "state_root_hash";
+
+const BLOCK_HEIGHT: u64 = 42;
+const ERA_ID: EraId = EraId::new(7);
+const PARENT_BLOCK_HASH: [u8; BLAKE2B_DIGEST_LENGTH] = [3; BLAKE2B_DIGEST_LENGTH];
+
+fn get_stored_value<T: CLTyped + FromBytes>(builder: &InMemoryWasmTestBuilder, name: &str) -> T {
+    builder
+        .query(
+            None,
+            Key::Account(*DEFAULT_ACCOUNT_ADDR),
+            &[name.to_string()],
+        )
+        .expect("should have value")
+        .as_cl_value()
+        .cloned()
+        .expect("should be CLValue")
+        .into_t()
+        .expect("should have correct type")
+}
+
+#[ignore]
+#[test]
+fn should_run_get_block_info_contract() {
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder.run_genesis(&PRODUCTION_RUN_GENESIS_REQUEST);
+    let pre_state_hash = builder.get_post_state_hash();
+
+    let exec_request = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        CONTRACT_GET_BLOCK_INFO,
+        runtime_args! {},
+    )
+    .with_block_height(BLOCK_HEIGHT)
+    .with_era_id(ERA_ID)
+    .with_parent_block_hash(Digest::from(PARENT_BLOCK_HASH))
+    .build();
+    builder.exec(exec_request).commit().expect_success();
+
+    let block_height: u64 = get_stored_value(&builder, BLOCK_HEIGHT_KEY);
+    assert_eq!(block_height, BLOCK_HEIGHT);
+
+    let era_id: EraId = get_stored_value(&builder, ERA_ID_KEY);
+    assert_eq!(era_id, ERA_ID);
+
+    let protocol_version: String = get_stored_value(&builder, PROTOCOL_VERSION_KEY);
+    assert_eq!(protocol_version, DEFAULT_PROTOCOL_VERSION.to_string());
+
+    let parent_block_hash: [u8; BLAKE2B_DIGEST_LENGTH] =
+        get_stored_value(&builder, PARENT_BLOCK_HASH_KEY);
+    assert_eq!(parent_block_hash, PARENT_BLOCK_HASH);
+
+    let state_root_hash: [u8; BLAKE2B_DIGEST_LENGTH] =
+        get_stored_value(&builder, STATE_ROOT_HASH_KEY);
+    assert_eq!(Digest::from(state_root_hash), pre_state_hash);
+}
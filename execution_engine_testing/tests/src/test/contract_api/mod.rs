@@ -1,7 +1,9 @@
 mod account;
 mod create_purse;
 mod dictionary;
+mod emit_message;
 mod get_arg;
+mod get_block_info;
 mod get_blocktime;
 mod get_call_stack;
 mod get_caller;
@@ -12,6 +14,7 @@ mod main_purse;
 mod mint_purse;
 mod multisig_authorization;
 mod named_dictionaries;
+mod remove_stored_value;
 mod revert;
 mod runtime;
 mod subcall;
@@ -19,3 +22,4 @@ mod transfer;
 mod transfer_cached;
 mod transfer_purse_to_account;
 mod transfer_purse_to_purse;
+mod verify_signature;
@@ -0,0 +1,86 @@
+use casper_engine_test_support::{
+    ExecuteRequestBuilder, InMemoryWasmTestBuilder, DEFAULT_ACCOUNT_ADDR,
+    PRODUCTION_RUN_GENESIS_REQUEST,
+};
+use casper_execution_engine::{
+    core::{engine_state::Error, execution},
+    shared::transform::Transform,
+};
+use casper_types::{runtime_args, ApiError, Key, RuntimeArgs, URef};
+
+const REMOVE_STORED_VALUE_WASM: &str = "remove_stored_value.wasm";
+const ARG_ACTION: &str = "action";
+const ACTION_STORE: &str = "store";
+const ACTION_REMOVE: &str = "remove";
+const ACTION_REMOVE_READ_ONLY: &str = "remove_read_only";
+const UREF_NAME: &str = "value";
+const DICTIONARY_NAME: &str = "dictionary";
+const DICTIONARY_ITEM_KEY: &str = "item";
+
+fn exec_remove_stored_value(builder: &mut InMemoryWasmTestBuilder, action: &str) {
+    let exec_request = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        REMOVE_STORED_VALUE_WASM,
+        runtime_args! {
+            ARG_ACTION => action.to_string(),
+        },
+    )
+    .build();
+    builder.exec(exec_request).commit();
+}
+
+fn get_named_uref(builder: &InMemoryWasmTestBuilder, name: &str) -> URef {
+    builder
+        .get_expected_account(*DEFAULT_ACCOUNT_ADDR)
+        .named_keys()
+        .get(name)
+        .and_then(Key::as_uref)
+        .copied()
+        .expect("should have named uref")
+}
+
+#[ignore]
+#[test]
+fn should_remove_uref_and_dictionary_item() {
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder.run_genesis(&PRODUCTION_RUN_GENESIS_REQUEST);
+
+    exec_remove_stored_value(&mut builder, ACTION_STORE);
+    builder.expect_success();
+
+    let value_key = Key::from(get_named_uref(&builder, UREF_NAME).remove_access_rights());
+    let dictionary_uref = get_named_uref(&builder, DICTIONARY_NAME);
+    let dictionary_key = Key::dictionary(dictionary_uref, DICTIONARY_ITEM_KEY.as_bytes());
+    assert!(builder.query(None, value_key, &[]).is_ok());
+    assert!(builder.query(None, dictionary_key, &[]).is_ok());
+
+    exec_remove_stored_value(&mut builder, ACTION_REMOVE);
+    builder.expect_success();
+
+    assert!(builder.query(None, value_key, &[]).is_err());
+    assert!(builder.query(None, dictionary_key, &[]).is_err());
+
+    let transforms = builder.get_transforms();
+    let transforms = transforms.last().expect("should have transforms");
+    assert_eq!(transforms.get(&value_key), Some(&Transform::Prune));
+    assert_eq!(transforms.get(&dictionary_key), Some(&Transform::Prune));
+}
+
+#[ignore]
+#[test]
+fn should_not_remove_read_only_uref() {
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder.run_genesis(&PRODUCTION_RUN_GENESIS_REQUEST);
+
+    exec_remove_stored_value(&mut builder, ACTION_REMOVE_READ_ONLY);
+
+    let error = builder.get_error().expect("should have error");
+    assert!(
+        matches!(
+            error,
+            Error::Exec(execution::Error::Revert(ApiError::PermissionDenied))
+        ),
+        "{:?}",
+        error
+    );
+}
@@ -0,0 +1,99 @@
+use casper_engine_test_support::{
+    ExecuteRequestBuilder, InMemoryWasmTestBuilder, DEFAULT_ACCOUNT_ADDR,
+    PRODUCTION_RUN_GENESIS_REQUEST,
+};
+use casper_execution_engine::core::{engine_state::Error, execution};
+use casper_types::{
+    bytesrepr::{Bytes, ToBytes},
+    crypto, runtime_args, ApiError, PublicKey, RuntimeArgs, SecretKey, Signature,
+};
+
+const VERIFY_SIGNATURE_WASM: &str = "verify_signature.wasm";
+const ARG_MESSAGE: &str = "message";
+const ARG_SIGNATURE: &str = "signature";
+const ARG_PUBLIC_KEY: &str = "public_key";
+const MESSAGE: &[u8] = b"message to be signed";
+
+fn key_pairs() -> Vec<(SecretKey, PublicKey)> {
+    let ed25519_secret_key = SecretKey::ed25519_from_bytes([1; SecretKey::ED25519_LENGTH]).unwrap();
+    let secp256k1_secret_key =
+        SecretKey::secp256k1_from_bytes([2; SecretKey::SECP256K1_LENGTH]).unwrap();
+    vec![ed25519_secret_key, secp256k1_secret_key]
+        .into_iter()
+        .map(|secret_key| {
+            let public_key = PublicKey::from(&secret_key);
+            (secret_key, public_key)
+        })
+        .collect()
+}
+
+fn exec_verify_signature(
+    builder: &mut InMemoryWasmTestBuilder,
+    message: &[u8],
+    signature: &Signature,
+    public_key: &PublicKey,
+) {
+    let exec_request = ExecuteRequestBuilder::standard(
+        *DEFAULT_ACCOUNT_ADDR,
+        VERIFY_SIGNATURE_WASM,
+        runtime_args! {
+            ARG_MESSAGE => Bytes::from(message.to_vec()),
+            ARG_SIGNATURE => Bytes::from(signature.to_bytes().unwrap()),
+            ARG_PUBLIC_KEY => public_key.clone(),
+        },
+    )
+    .build();
+    builder.exec(exec_request).commit();
+}
+
+fn assert_invalid_signature(builder: &InMemoryWasmTestBuilder) {
+    let error = builder.get_error().expect("should have error");
+    assert!(
+        matches!(
+            error,
+            Error::Exec(execution::Error::Revert(ApiError::InvalidSignature))
+        ),
+        "{:?}",
+        error
+    );
+}
+
+#[ignore]
+#[test]
+fn should_verify_valid_signature() {
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder.run_genesis(&PRODUCTION_RUN_GENESIS_REQUEST);
+
+    for (secret_key, public_key) in key_pairs() {
+        let signature = crypto::sign(MESSAGE, &secret_key, &public_key);
+        exec_verify_signature(&mut builder, MESSAGE, &signature, &public_key);
+        builder.expect_success();
+    }
+}
+
+#[ignore]
+#[test]
+fn should_fail_to_verify_signature_of_different_message() {
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder.run_genesis(&PRODUCTION_RUN_GENESIS_REQUEST);
+
+    for (secret_key, public_key) in key_pairs() {
+        let signature = crypto::sign(MESSAGE, &secret_key, &public_key);
+        exec_verify_signature(&mut builder, b"another message", &signature, &public_key);
+        assert_invalid_signature(&builder);
+    }
+}
+
+#[ignore]
+#[test]
+fn should_fail_to_verify_signature_with_mismatched_public_key() {
+    let mut builder = InMemoryWasmTestBuilder::default();
+    builder.run_genesis(&PRODUCTION_RUN_GENESIS_REQUEST);
+
+    let key_pairs = key_pairs();
+    let (ed25519_secret_key, ed25519_public_key) = &key_pairs[0];
+    let (_, secp256k1_public_key) = &key_pairs[1];
+    let signature = crypto::sign(MESSAGE, ed25519_secret_key, ed25519_public_key);
+    exec_verify_signature(&mut builder, MESSAGE, &signature, secp256k1_public_key);
+    assert_invalid_signature(&builder);
+}
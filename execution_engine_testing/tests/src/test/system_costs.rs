@@ -979,6 +979,15 @@ fn should_verify_wasm_add_bid_wasm_cost_is_not_recursive() {
         print: HostFunction::fixed(0),
         blake2b: HostFunction::fixed(0),
         random_bytes: HostFunction::fixed(0),
+        emit_message: HostFunction::fixed(0),
+        verify_signature: HostFunction::fixed(0),
+        get_block_height: HostFunction::fixed(0),
+        get_era_id: HostFunction::fixed(0),
+        get_protocol_version: HostFunction::fixed(0),
+        get_parent_block_hash: HostFunction::fixed(0),
+        get_state_root_hash: HostFunction::fixed(0),
+        remove_uref: HostFunction::fixed(0),
+        remove_dictionary_item: HostFunction::fixed(0),
     };
 
     let new_wasm_config = WasmConfig::new(
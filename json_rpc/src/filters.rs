@@ -7,7 +7,9 @@
 mod tests;
 
 use bytes::Bytes;
+use futures::future;
 use http::{header::CONTENT_TYPE, HeaderMap, StatusCode};
+use serde::Serialize;
 use serde_json::{json, Map, Value};
 use tracing::{debug, trace, warn};
 use warp::{
@@ -66,60 +68,223 @@ pub fn base_filter<P: AsRef<str>>(path: P, max_body_bytes: u32) -> BoxedFilter<(
         .boxed()
 }
 
-/// Handles parsing a JSON-RPC request from the given HTTP body, executing it using the appropriate
-/// handler, and providing a JSON-RPC response (which could be a success or failure).
+/// The body of a JSON-RPC reply: either a single response, or an array of responses to a batch.
+#[derive(Serialize)]
+#[serde(untagged)]
+pub(crate) enum ResponseBody {
+    Single(Response),
+    Batch(Vec<Response>),
+}
+
+/// Returns `true` if the first non-whitespace character of the body is `[`, i.e. the body should be
+/// parsed as a batch of requests rather than as a single request.
+fn is_batch(body: &[u8]) -> bool {
+    body.iter().find(|byte| !byte.is_ascii_whitespace()) == Some(&b'[')
+}
+
+/// Validates the given unvalidated request and, if valid, executes it using the appropriate
+/// handler.
+///
+/// Returns an `Err(Rejection)` if the request is a Notification as per the JSON-RPC specification.
+async fn handle_request(
+    unvalidated_request: Map<String, Value>,
+    handlers: &RequestHandlers,
+    allow_unknown_fields: bool,
+) -> Result<Response, Rejection> {
+    match Request::new(unvalidated_request, allow_unknown_fields) {
+        Ok(request) => Ok(handlers.handle_request(request).await),
+        Err(ErrorOrRejection::Error { id, error }) => {
+            debug!(?error, "got an invalid request");
+            Ok(Response::new_failure(id, error))
+        }
+        Err(ErrorOrRejection::Rejection(rejection)) => {
+            debug!(?rejection, "rejecting an invalid request");
+            Err(rejection)
+        }
+    }
+}
+
+/// Handles a batch of JSON-RPC requests, executing all valid entries concurrently.
+///
+/// The returned responses are in the same order as the corresponding entries in the batch.  Entries
+/// which are not valid requests each yield a `Response::Failure`, while entries which are
+/// Notifications (i.e. have no "id" field) are not executed and yield no response.
+///
+/// Returns an `Err(Rejection)` only if every entry in the batch is a Notification, since in that
+/// case there are no JSON-RPC responses to send to the client.
+async fn handle_batch(
+    batch: Vec<Value>,
+    handlers: RequestHandlers,
+    allow_unknown_fields: bool,
+    max_batch_size: u32,
+) -> Result<ResponseBody, Rejection> {
+    if max_batch_size == 0 {
+        debug!("got a batch request, but batches are disabled");
+        let error = Error::new(
+            ReservedErrorCode::InvalidRequest,
+            "Batch requests are not supported by this server",
+        );
+        return Ok(ResponseBody::Single(Response::new_failure(
+            Value::Null,
+            error,
+        )));
+    }
+
+    if batch.is_empty() {
+        debug!("got an empty batch request");
+        let error = Error::new(ReservedErrorCode::InvalidRequest, "Empty batch");
+        return Ok(ResponseBody::Single(Response::new_failure(
+            Value::Null,
+            error,
+        )));
+    }
+
+    if batch.len() > max_batch_size as usize {
+        debug!(batch_size = batch.len(), "got an oversized batch request");
+        let error = Error::new(
+            ReservedErrorCode::InvalidRequest,
+            format!(
+                "Batch of {} requests exceeds the maximum allowed of {}",
+                batch.len(),
+                max_batch_size
+            ),
+        );
+        return Ok(ResponseBody::Single(Response::new_failure(
+            Value::Null,
+            error,
+        )));
+    }
+
+    let mut last_rejection = None;
+    let entry_futures = batch.into_iter().map(|entry| {
+        let handlers = &handlers;
+        async move {
+            match entry {
+                Value::Object(unvalidated_request) => {
+                    handle_request(unvalidated_request, handlers, allow_unknown_fields).await
+                }
+                _ => {
+                    debug!("got a batch entry which is not a json object");
+                    let error = Error::new(
+                        ReservedErrorCode::InvalidRequest,
+                        "Expected each batch entry to be a JSON-RPC request object",
+                    );
+                    Ok(Response::new_failure(Value::Null, error))
+                }
+            }
+        }
+    });
+    let responses: Vec<Response> = future::join_all(entry_futures)
+        .await
+        .into_iter()
+        .filter_map(|result| match result {
+            Ok(response) => Some(response),
+            Err(rejection) => {
+                last_rejection = Some(rejection);
+                None
+            }
+        })
+        .collect();
+
+    match last_rejection {
+        Some(rejection) if responses.is_empty() => Err(rejection),
+        _ => Ok(ResponseBody::Batch(responses)),
+    }
+}
+
+/// Handles parsing a JSON-RPC request or batch of requests from the given HTTP body, executing
+/// them using the appropriate handlers, and providing a JSON-RPC response or array of responses
+/// (each of which could be a success or failure).
 ///
 /// Returns an `Err(Rejection)` only if the request is a Notification as per the JSON-RPC
-/// specification, i.e. the request doesn't contain an "id" field.  In this case, no JSON-RPC
-/// response is sent to the client.
+/// specification, i.e. the request doesn't contain an "id" field, or if the body is a batch
+/// consisting solely of Notifications.  In this case, no JSON-RPC response is sent to the client.
 ///
 /// If `allow_unknown_fields` is `false`, requests with unknown fields will cause the server to
 /// respond with an error.
-async fn handle_body(
+///
+/// Batches with more than `max_batch_size` entries are responded to with a single error.  If
+/// `max_batch_size` is 0, all batches are responded to in this way.
+pub(crate) async fn handle_body(
     body: Bytes,
     handlers: RequestHandlers,
     allow_unknown_fields: bool,
-) -> Result<Response, Rejection> {
-    let response = match serde_json::from_slice::<Map<String, Value>>(&*body) {
-        Ok(unvalidated_request) => match Request::new(unvalidated_request, allow_unknown_fields) {
-            Ok(request) => handlers.handle_request(request).await,
-            Err(ErrorOrRejection::Error { id, error }) => {
-                debug!(?error, "got an invalid request");
-                Response::new_failure(id, error)
+    max_batch_size: u32,
+) -> Result<ResponseBody, Rejection> {
+    if is_batch(&body) {
+        return match serde_json::from_slice::<Vec<Value>>(&body) {
+            Ok(batch) => handle_batch(batch, handlers, allow_unknown_fields, max_batch_size).await,
+            Err(error) => {
+                debug!(%error, "got bad json");
+                let error = Error::new(ReservedErrorCode::ParseError, error.to_string());
+                Ok(ResponseBody::Single(Response::new_failure(
+                    Value::Null,
+                    error,
+                )))
             }
-            Err(ErrorOrRejection::Rejection(rejection)) => {
-                debug!(?rejection, "rejecting an invalid request");
-                return Err(rejection);
-            }
-        },
+        };
+    }
+
+    let response = match serde_json::from_slice::<Map<String, Value>>(&body) {
+        Ok(unvalidated_request) => {
+            handle_request(unvalidated_request, &handlers, allow_unknown_fields).await?
+        }
         Err(error) => {
             debug!(%error, "got bad json");
             let error = Error::new(ReservedErrorCode::ParseError, error.to_string());
             Response::new_failure(Value::Null, error)
         }
     };
-    Ok(response)
+    Ok(ResponseBody::Single(response))
 }
 
 /// Returns a boxed warp filter which handles parsing a JSON-RPC request from the given HTTP body,
 /// executing it using the appropriate handler, and providing a reply.
 ///
 /// The reply will normally be built from a JSON-RPC response (which could be a success or failure).
+/// If the HTTP body is a JSON array, it is handled as a batch as per the JSON-RPC specification,
+/// and the reply will be built from an array of JSON-RPC responses, in the same order as the
+/// requests in the batch.
 ///
 /// However, the reply could be built from a [`Rejection`] if the request is a Notification as per
 /// the JSON-RPC specification, i.e. the request doesn't contain an "id" field.  In this case, no
-/// JSON-RPC response is sent to the client, only an HTTP response.
+/// JSON-RPC response is sent to the client, only an HTTP response.  Notifications within a batch
+/// are skipped, and only if every entry in the batch is a Notification is the reply built from a
+/// [`Rejection`].
 ///
 /// If `allow_unknown_fields` is `false`, requests with unknown fields will cause the server to
 /// respond with an error.
+///
+/// `max_batch_size` sets an upper limit for the number of requests in a single batch.  If it is 0,
+/// batch requests are not supported and will be responded to with an error.
 pub fn main_filter(
     handlers: RequestHandlers,
     allow_unknown_fields: bool,
+    max_batch_size: u32,
+) -> BoxedFilter<(WithStatus<reply::Json>,)> {
+    main_filter_with_body_source(
+        body::bytes().boxed(),
+        handlers,
+        allow_unknown_fields,
+        max_batch_size,
+    )
+}
+
+/// Returns a boxed warp filter which behaves as [`main_filter`], except that the HTTP body is
+/// extracted by `body_source` rather than being read directly.
+///
+/// This allows the body to be inspected, and the request possibly rejected, before the JSON-RPC
+/// request is handled, e.g. in order to rate limit requests according to the methods they call.
+pub fn main_filter_with_body_source(
+    body_source: BoxedFilter<(Bytes,)>,
+    handlers: RequestHandlers,
+    allow_unknown_fields: bool,
+    max_batch_size: u32,
 ) -> BoxedFilter<(WithStatus<reply::Json>,)> {
-    body::bytes()
+    body_source
         .and_then(move |body| {
             let handlers = handlers.clone();
-            async move { handle_body(body, handlers, allow_unknown_fields).await }
+            async move { handle_body(body, handlers, allow_unknown_fields, max_batch_size).await }
         })
         .map(|response| reply::with_status(reply::json(&response), StatusCode::OK))
         .boxed()
@@ -16,6 +16,7 @@ use crate::{
 
 const GET_GOOD_THING: &str = "get good thing";
 const GET_BAD_THING: &str = "get bad thing";
+const MAX_BATCH_SIZE: u32 = 3;
 
 #[derive(PartialEq, Eq, Serialize, Deserialize, Debug)]
 struct GoodThing {
@@ -49,13 +50,18 @@ async fn from_http_response(response: http::Response<hyper::Body>) -> Response {
     serde_json::from_slice(&body_bytes).unwrap()
 }
 
+async fn batch_from_http_response(response: http::Response<hyper::Body>) -> Vec<Response> {
+    let body_bytes = hyper::body::to_bytes(response.into_body()).await.unwrap();
+    serde_json::from_slice(&body_bytes).unwrap()
+}
+
 fn main_filter_with_recovery() -> BoxedFilter<(impl Reply,)> {
     let mut handlers = RequestHandlersBuilder::new();
     handlers.register_handler(GET_GOOD_THING, Arc::new(get_good_thing));
     handlers.register_handler(GET_BAD_THING, Arc::new(get_bad_thing));
     let handlers = handlers.build();
 
-    main_filter(handlers, false)
+    main_filter(handlers, false, MAX_BATCH_SIZE)
         .recover(handle_rejection)
         .boxed()
 }
@@ -318,3 +324,227 @@ async fn should_handle_invalid_json() {
         )
     );
 }
+
+#[tokio::test]
+async fn should_handle_batch_in_order() {
+    let _ = env_logger::try_init();
+
+    let filter = main_filter_with_recovery();
+
+    // Each entry should get handled independently, with the responses returned in the same order as
+    // the requests.
+    let http_response = warp::test::request()
+        .body(
+            r#"[
+                {"jsonrpc":"2.0","id":"a","method":"get good thing","params":["one"]},
+                {"jsonrpc":"2.0","id":"b","method":"not registered"},
+                {"jsonrpc":"2.0","id":"c","method":"get good thing","params":["three"]}
+            ]"#,
+        )
+        .filter(&filter)
+        .await
+        .unwrap()
+        .into_response();
+
+    assert_eq!(http_response.status(), StatusCode::OK);
+    let rpc_responses = batch_from_http_response(http_response).await;
+    assert_eq!(rpc_responses.len(), 3);
+
+    assert_eq!(rpc_responses[0].id(), "a");
+    assert_eq!(
+        rpc_responses[0].result(),
+        Some(GoodThing {
+            good_thing: "one".to_string()
+        })
+    );
+
+    assert_eq!(rpc_responses[1].id(), "b");
+    assert_eq!(
+        rpc_responses[1].error().unwrap(),
+        &Error::new(
+            ReservedErrorCode::MethodNotFound,
+            "'not registered' is not a supported json-rpc method on this server"
+        )
+    );
+
+    assert_eq!(rpc_responses[2].id(), "c");
+    assert_eq!(
+        rpc_responses[2].result(),
+        Some(GoodThing {
+            good_thing: "three".to_string()
+        })
+    );
+}
+
+#[tokio::test]
+async fn should_handle_batch_with_invalid_entries() {
+    let _ = env_logger::try_init();
+
+    let filter = main_filter_with_recovery();
+
+    // Invalid entries should each yield a Response::Failure, while the notification (no ID) should
+    // yield no response at all.
+    let http_response = warp::test::request()
+        .body(
+            r#"[
+                1,
+                {"jsonrpc":"2.0","method":"get good thing","params":["one"]},
+                {"jsonrpc":"2.0","id":"c","method":{"not":"a string"}}
+            ]"#,
+        )
+        .filter(&filter)
+        .await
+        .unwrap()
+        .into_response();
+
+    assert_eq!(http_response.status(), StatusCode::OK);
+    let rpc_responses = batch_from_http_response(http_response).await;
+    assert_eq!(rpc_responses.len(), 2);
+
+    assert_eq!(rpc_responses[0].id(), &Value::Null);
+    assert_eq!(
+        rpc_responses[0].error().unwrap(),
+        &Error::new(
+            ReservedErrorCode::InvalidRequest,
+            "Expected each batch entry to be a JSON-RPC request object"
+        )
+    );
+
+    assert_eq!(rpc_responses[1].id(), "c");
+    assert_eq!(
+        rpc_responses[1].error().unwrap(),
+        &Error::new(
+            ReservedErrorCode::InvalidRequest,
+            "Expected 'method' to be a String"
+        )
+    );
+}
+
+#[tokio::test]
+async fn should_handle_batch_of_only_notifications() {
+    let _ = env_logger::try_init();
+
+    let filter = main_filter_with_recovery();
+
+    // This should return no JSON-RPC response, only an HTTP response (bad request) to the client as
+    // no entry has an ID.
+    let http_response = warp::test::request()
+        .body(r#"[{"jsonrpc":"2.0","method":"get good thing","params":["one"]}]"#)
+        .filter(&filter)
+        .await
+        .unwrap()
+        .into_response();
+
+    assert_eq!(http_response.status(), StatusCode::BAD_REQUEST);
+    let response_body = ResponseBodyOnRejection::from_response(http_response).await;
+    assert_eq!(
+        response_body.message,
+        "The request is missing the 'id' field"
+    );
+}
+
+#[tokio::test]
+async fn should_handle_empty_batch() {
+    let _ = env_logger::try_init();
+
+    let filter = main_filter_with_recovery();
+
+    // This should return a single Response::Failure (invalid request) rather than an array.
+    let http_response = warp::test::request()
+        .body(r#" [ ] "#)
+        .filter(&filter)
+        .await
+        .unwrap()
+        .into_response();
+
+    assert_eq!(http_response.status(), StatusCode::OK);
+    let rpc_response = from_http_response(http_response).await;
+    assert_eq!(rpc_response.id(), &Value::Null);
+    assert_eq!(
+        rpc_response.error().unwrap(),
+        &Error::new(ReservedErrorCode::InvalidRequest, "Empty batch")
+    );
+}
+
+#[tokio::test]
+async fn should_handle_oversized_batch() {
+    let _ = env_logger::try_init();
+
+    let filter = main_filter_with_recovery();
+
+    // This should return a single Response::Failure (invalid request) without executing any of the
+    // requests, as the batch has more than `MAX_BATCH_SIZE` entries.
+    let request = r#"{"jsonrpc":"2.0","id":1,"method":"get good thing","params":["one"]}"#;
+    let body = format!("[{}]", vec![request; MAX_BATCH_SIZE as usize + 1].join(","));
+    let http_response = warp::test::request()
+        .body(body)
+        .filter(&filter)
+        .await
+        .unwrap()
+        .into_response();
+
+    assert_eq!(http_response.status(), StatusCode::OK);
+    let rpc_response = from_http_response(http_response).await;
+    assert_eq!(rpc_response.id(), &Value::Null);
+    assert_eq!(
+        rpc_response.error().unwrap(),
+        &Error::new(
+            ReservedErrorCode::InvalidRequest,
+            "Batch of 4 requests exceeds the maximum allowed of 3"
+        )
+    );
+}
+
+#[tokio::test]
+async fn should_handle_batch_when_batches_disabled() {
+    let _ = env_logger::try_init();
+
+    let mut handlers = RequestHandlersBuilder::new();
+    handlers.register_handler(GET_GOOD_THING, Arc::new(get_good_thing));
+    let filter = main_filter(handlers.build(), false, 0)
+        .recover(handle_rejection)
+        .boxed();
+
+    let http_response = warp::test::request()
+        .body(r#"[{"jsonrpc":"2.0","id":"a","method":"get good thing","params":["one"]}]"#)
+        .filter(&filter)
+        .await
+        .unwrap()
+        .into_response();
+
+    assert_eq!(http_response.status(), StatusCode::OK);
+    let rpc_response = from_http_response(http_response).await;
+    assert_eq!(rpc_response.id(), &Value::Null);
+    assert_eq!(
+        rpc_response.error().unwrap(),
+        &Error::new(
+            ReservedErrorCode::InvalidRequest,
+            "Batch requests are not supported by this server"
+        )
+    );
+}
+
+#[tokio::test]
+async fn should_handle_batch_with_invalid_json() {
+    let _ = env_logger::try_init();
+
+    let filter = main_filter_with_recovery();
+
+    let http_response = warp::test::request()
+        .body(r#"[a"#)
+        .filter(&filter)
+        .await
+        .unwrap()
+        .into_response();
+
+    assert_eq!(http_response.status(), StatusCode::OK);
+    let rpc_response = from_http_response(http_response).await;
+    assert_eq!(rpc_response.id(), &Value::Null);
+    assert_eq!(
+        rpc_response.error().unwrap(),
+        &Error::new(
+            ReservedErrorCode::ParseError,
+            "expected value at line 1 column 2"
+        )
+    );
+}
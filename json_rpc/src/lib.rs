@@ -40,7 +40,14 @@
 //!     let path = "rpc";
 //!     let max_body_bytes = 1024;
 //!     let allow_unknown_fields = false;
-//!     let route = casper_json_rpc::route(path, max_body_bytes, handlers, allow_unknown_fields);
+//!     let max_batch_size = 10;
+//!     let route = casper_json_rpc::route(
+//!         path,
+//!         max_body_bytes,
+//!         handlers,
+//!         allow_unknown_fields,
+//!         max_batch_size,
+//!     );
 //!
 //!     // Convert it into a `Service` and run it.
 //!     let make_svc = hyper::service::make_service_fn(move |_| {
@@ -85,7 +92,9 @@ mod request;
 mod request_handlers;
 mod response;
 
+use bytes::Bytes;
 use http::{header::CONTENT_TYPE, Method};
+use tracing::error;
 use warp::{filters::BoxedFilter, Filter, Reply};
 
 pub use error::{Error, ErrorCodeT, ReservedErrorCode};
@@ -110,6 +119,9 @@ const JSON_RPC_VERSION: &str = "2.0";
 /// If `allow_unknown_fields` is `false`, requests with unknown fields will cause the server to
 /// respond with an error.
 ///
+/// `max_batch_size` sets an upper limit for the number of requests in a single JSON-RPC batch.  If
+/// it is 0, batch requests are not supported and will be responded to with an error.
+///
 /// Note that this is a convenience function combining the lower-level functions in [`filters`]
 /// along with [a warp CORS filter](https://docs.rs/warp/latest/warp/filters/cors/index.html) which
 ///   * allows any origin
@@ -122,9 +134,14 @@ pub fn route<P: AsRef<str>>(
     max_body_bytes: u32,
     handlers: RequestHandlers,
     allow_unknown_fields: bool,
+    max_batch_size: u32,
 ) -> BoxedFilter<(impl Reply,)> {
     filters::base_filter(path, max_body_bytes)
-        .and(filters::main_filter(handlers, allow_unknown_fields))
+        .and(filters::main_filter(
+            handlers,
+            allow_unknown_fields,
+            max_batch_size,
+        ))
         .recover(filters::handle_rejection)
         .with(
             warp::cors()
@@ -134,3 +151,73 @@ pub fn route<P: AsRef<str>>(
         )
         .boxed()
 }
+
+/// Handles a JSON-RPC request or batch of requests which has been received other than as the body
+/// of an HTTP request, e.g. as a WebSocket message.
+///
+/// Returns the JSON-encoded response (or array of responses in the case of a batch), or `None` if
+/// no JSON-RPC response should be sent to the client, i.e. if the message is a Notification or a
+/// batch consisting solely of Notifications.
+///
+/// `allow_unknown_fields` and `max_batch_size` have the same meaning as for [`route`].
+pub async fn handle_message(
+    message: Bytes,
+    handlers: RequestHandlers,
+    allow_unknown_fields: bool,
+    max_batch_size: u32,
+) -> Option<String> {
+    let response_body =
+        filters::handle_body(message, handlers, allow_unknown_fields, max_batch_size)
+            .await
+            .ok()?;
+    serde_json::to_string(&response_body)
+        .map_err(|error| error!(%error, "failed to encode json-rpc response"))
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use serde_json::Value;
+
+    use super::*;
+
+    async fn echo(params: Option<Params>) -> Result<Value, Error> {
+        Ok(params.map(Value::from).unwrap_or_default())
+    }
+
+    fn handlers() -> RequestHandlers {
+        let mut handlers = RequestHandlersBuilder::new();
+        handlers.register_handler("echo", Arc::new(echo));
+        handlers.build()
+    }
+
+    #[tokio::test]
+    async fn should_handle_message() {
+        let message = Bytes::from(r#"{"jsonrpc":"2.0","id":1,"method":"echo","params":[2]}"#);
+        let encoded_response = handle_message(message, handlers(), false, 1).await.unwrap();
+        let response: Response = serde_json::from_str(&encoded_response).unwrap();
+        assert_eq!(response.id(), 1);
+        assert_eq!(response.result(), Some(vec![2]));
+    }
+
+    #[tokio::test]
+    async fn should_handle_batch_message() {
+        let message = Bytes::from(
+            r#"[{"jsonrpc":"2.0","id":1,"method":"echo"},{"jsonrpc":"2.0","method":"echo"}]"#,
+        );
+        let encoded_response = handle_message(message, handlers(), false, 2).await.unwrap();
+        let responses: Vec<Response> = serde_json::from_str(&encoded_response).unwrap();
+        assert_eq!(responses.len(), 1);
+        assert_eq!(responses[0].id(), 1);
+    }
+
+    #[tokio::test]
+    async fn should_not_respond_to_notification_message() {
+        let message = Bytes::from(r#"{"jsonrpc":"2.0","method":"echo"}"#);
+        assert!(handle_message(message, handlers(), false, 1)
+            .await
+            .is_none());
+    }
+}
@@ -0,0 +1,448 @@
+use std::collections::BTreeMap;
+
+use num_rational::Ratio;
+use serde::{Deserialize, Serialize};
+
+use casper_hashing::Digest;
+use casper_types::{
+    bytesrepr::{self, ToBytes},
+    crypto, EraId, ProtocolVersion, PublicKey, Signature, Timestamp, U512,
+};
+
+use crate::Error;
+
+/// A validator's reward, as included in the JSON representation of an era report.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Reward {
+    /// The validator's public key.
+    pub validator: PublicKey,
+    /// The reward amount.
+    pub amount: u64,
+}
+
+/// A validator's weight, as included in the JSON representation of an era end.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ValidatorWeight {
+    /// The validator's public key.
+    pub validator: PublicKey,
+    /// The validator's weight.
+    pub weight: U512,
+}
+
+/// Equivocation, reward and inactivity information included in a switch block.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EraReport {
+    /// The set of equivocators.
+    pub equivocators: Vec<PublicKey>,
+    /// Rewards for finalization of earlier blocks.
+    pub rewards: Vec<Reward>,
+    /// Validators that haven't produced any unit during the era.
+    pub inactive_validators: Vec<PublicKey>,
+}
+
+impl ToBytes for EraReport {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut buffer = bytesrepr::allocate_buffer(self)?;
+        buffer.extend(self.equivocators.to_bytes()?);
+        buffer.extend(self.rewards_map().to_bytes()?);
+        buffer.extend(self.inactive_validators.to_bytes()?);
+        Ok(buffer)
+    }
+
+    fn serialized_length(&self) -> usize {
+        self.equivocators.serialized_length()
+            + self.rewards_map().serialized_length()
+            + self.inactive_validators.serialized_length()
+    }
+}
+
+impl EraReport {
+    fn rewards_map(&self) -> BTreeMap<PublicKey, u64> {
+        self.rewards
+            .iter()
+            .map(|reward| (reward.validator.clone(), reward.amount))
+            .collect()
+    }
+}
+
+/// Information about the end of an era, included in a switch block.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EraEnd {
+    /// Equivocation, reward and inactivity information for the era.
+    pub era_report: EraReport,
+    /// The validators for the upcoming era and their respective weights.
+    pub next_era_validator_weights: Vec<ValidatorWeight>,
+}
+
+impl ToBytes for EraEnd {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut buffer = bytesrepr::allocate_buffer(self)?;
+        buffer.extend(self.era_report.to_bytes()?);
+        buffer.extend(self.validator_weights().to_bytes()?);
+        Ok(buffer)
+    }
+
+    fn serialized_length(&self) -> usize {
+        self.era_report.serialized_length() + self.validator_weights().serialized_length()
+    }
+}
+
+impl EraEnd {
+    /// The validators for the upcoming era and their respective weights.
+    pub fn validator_weights(&self) -> BTreeMap<PublicKey, U512> {
+        self.next_era_validator_weights
+            .iter()
+            .map(|validator_weight| (validator_weight.validator.clone(), validator_weight.weight))
+            .collect()
+    }
+}
+
+/// A block header, deserializable from the `header` field of a block returned by
+/// `chain_get_block`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BlockHeader {
+    /// The parent block's hash.
+    pub parent_hash: Digest,
+    /// The root hash of the global state after executing the block.
+    pub state_root_hash: Digest,
+    /// The hash of the block's body.
+    pub body_hash: Digest,
+    /// A random bit needed for initializing a future era.
+    pub random_bit: bool,
+    /// The seed for the sequence of leaders accumulated from random bits.
+    pub accumulated_seed: Digest,
+    /// The era end, if this is a switch block.
+    pub era_end: Option<EraEnd>,
+    /// The timestamp from when the block was proposed.
+    pub timestamp: Timestamp,
+    /// The era in which the block was created.
+    pub era_id: EraId,
+    /// The height of the block.
+    pub height: u64,
+    /// The protocol version of the network from when the block was created.
+    pub protocol_version: ProtocolVersion,
+}
+
+impl BlockHeader {
+    /// Computes the hash of the block header, i.e. the block hash.
+    pub fn hash(&self) -> Result<Digest, Error> {
+        Ok(Digest::hash(self.to_bytes()?))
+    }
+
+    /// The validators for the upcoming era and their respective weights, if this is a switch
+    /// block.
+    pub fn next_era_validator_weights(&self) -> Option<BTreeMap<PublicKey, U512>> {
+        self.era_end.as_ref().map(EraEnd::validator_weights)
+    }
+}
+
+impl ToBytes for BlockHeader {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut buffer = bytesrepr::allocate_buffer(self)?;
+        buffer.extend(self.parent_hash.to_bytes()?);
+        buffer.extend(self.state_root_hash.to_bytes()?);
+        buffer.extend(self.body_hash.to_bytes()?);
+        buffer.extend(self.random_bit.to_bytes()?);
+        buffer.extend(self.accumulated_seed.to_bytes()?);
+        buffer.extend(self.era_end.to_bytes()?);
+        buffer.extend(self.timestamp.to_bytes()?);
+        buffer.extend(self.era_id.to_bytes()?);
+        buffer.extend(self.height.to_bytes()?);
+        buffer.extend(self.protocol_version.to_bytes()?);
+        Ok(buffer)
+    }
+
+    fn serialized_length(&self) -> usize {
+        self.parent_hash.serialized_length()
+            + self.state_root_hash.serialized_length()
+            + self.body_hash.serialized_length()
+            + self.random_bit.serialized_length()
+            + self.accumulated_seed.serialized_length()
+            + self.era_end.serialized_length()
+            + self.timestamp.serialized_length()
+            + self.era_id.serialized_length()
+            + self.height.serialized_length()
+            + self.protocol_version.serialized_length()
+    }
+}
+
+/// A block body, deserializable from the `body` field of a block returned by `chain_get_block`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BlockBody {
+    /// The public key of the validator which proposed the block.
+    pub proposer: PublicKey,
+    /// The hashes of the non-transfer deploys included in the block.
+    pub deploy_hashes: Vec<Digest>,
+    /// The hashes of the transfers included in the block.
+    pub transfer_hashes: Vec<Digest>,
+}
+
+impl BlockBody {
+    /// Computes the hash of the block body, which is committed to by the block header.
+    pub fn hash(&self) -> Result<Digest, Error> {
+        Ok(Digest::hash(self.to_bytes()?))
+    }
+
+    /// The hashes of the block's deploys in execution order, i.e. non-transfer deploys followed
+    /// by transfers.
+    pub fn deploy_and_transfer_hashes(&self) -> impl Iterator<Item = &Digest> {
+        self.deploy_hashes.iter().chain(self.transfer_hashes.iter())
+    }
+}
+
+impl ToBytes for BlockBody {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut buffer = bytesrepr::allocate_buffer(self)?;
+        buffer.extend(self.proposer.to_bytes()?);
+        buffer.extend(self.deploy_hashes.to_bytes()?);
+        buffer.extend(self.transfer_hashes.to_bytes()?);
+        Ok(buffer)
+    }
+
+    fn serialized_length(&self) -> usize {
+        self.proposer.serialized_length()
+            + self.deploy_hashes.serialized_length()
+            + self.transfer_hashes.serialized_length()
+    }
+}
+
+/// A finality signature, deserializable from an entry of the `proofs` field of a block returned
+/// by `chain_get_block`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BlockProof {
+    /// The public key of the signing validator.
+    pub public_key: PublicKey,
+    /// The validator's signature over the block hash and era ID.
+    pub signature: Signature,
+}
+
+/// Verifies that `header` hashes to `block_hash`.
+pub fn verify_block_header(header: &BlockHeader, block_hash: &Digest) -> Result<(), Error> {
+    let computed = header.hash()?;
+    if computed != *block_hash {
+        return Err(Error::BlockHashMismatch {
+            expected: *block_hash,
+            computed,
+        });
+    }
+    Ok(())
+}
+
+/// Verifies that `body` hashes to the body hash committed to by `header`.
+pub fn verify_block_body(header: &BlockHeader, body: &BlockBody) -> Result<(), Error> {
+    let computed = body.hash()?;
+    if computed != header.body_hash {
+        return Err(Error::BodyHashMismatch {
+            expected: header.body_hash,
+            computed,
+        });
+    }
+    Ok(())
+}
+
+/// Verifies that `proofs` are valid finality signatures for the block with hash `block_hash` in
+/// era `era_id`, and that they were made by validators in `trusted_validator_weights` holding more
+/// than the quorum of the total weight.
+///
+/// The quorum is `(fault_tolerance_fraction + 1) / 2` of the total weight, matching the node's
+/// own check.  `fault_tolerance_fraction` should be set to the `core.finality_threshold_fraction`
+/// value of the network's chainspec.
+///
+/// Duplicate proofs from the same validator are only counted once.
+pub fn verify_finality_signatures(
+    block_hash: &Digest,
+    era_id: EraId,
+    proofs: &[BlockProof],
+    trusted_validator_weights: &BTreeMap<PublicKey, U512>,
+    fault_tolerance_fraction: Ratio<u64>,
+) -> Result<(), Error> {
+    let mut signed_message = block_hash.value().to_vec();
+    signed_message.extend_from_slice(&era_id.to_le_bytes());
+
+    let mut signers = BTreeMap::new();
+    for proof in proofs {
+        let weight = trusted_validator_weights
+            .get(&proof.public_key)
+            .ok_or_else(|| Error::BogusValidator(proof.public_key.clone()))?;
+        crypto::verify(&signed_message, &proof.signature, &proof.public_key).map_err(|error| {
+            Error::InvalidSignature {
+                public_key: proof.public_key.clone(),
+                error,
+            }
+        })?;
+        signers.insert(&proof.public_key, *weight);
+    }
+
+    let signature_weight: U512 = signers.values().copied().sum();
+    let total_weight: U512 = trusted_validator_weights.values().copied().sum();
+    let quorum_fraction = (fault_tolerance_fraction + 1) / 2;
+    if signature_weight * U512::from(*quorum_fraction.denom())
+        <= total_weight * U512::from(*quorum_fraction.numer())
+    {
+        return Err(Error::InsufficientSignatureWeight {
+            signature_weight,
+            total_weight,
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use casper_types::SecretKey;
+
+    use super::*;
+
+    // The example block header from the node's `chain_get_block` RPC schema, along with its hash.
+    const BLOCK_HASH: &str = "13c2d7a68ecdd4b74bf4393c88915c836c863fc4bf11d7f2bd930a1bbccacdcb";
+    const BLOCK_HEADER_JSON: &str = r#"{
+        "parent_hash": "0707070707070707070707070707070707070707070707070707070707070707",
+        "state_root_hash": "0808080808080808080808080808080808080808080808080808080808080808",
+        "body_hash": "cd502c5393a3c8b66d6979ad7857507c9baf5a8ba16ba99c28378d3a970fff42",
+        "random_bit": true,
+        "accumulated_seed": "ac979f51525cfd979b14aa7dc0737c5154eabe0db9280eceaa8dc8d2905b20d5",
+        "era_end": {
+            "era_report": {
+                "equivocators": [
+                    "013b6a27bcceb6a42d62a3a8d02a6f0d73653215771de243a63ac048a18b59da29"
+                ],
+                "rewards": [
+                    {
+                        "validator": "018a88e3dd7409f195fd52db2d3cba5d72ca6709bf1d94121bf3748801b40f6f5c",
+                        "amount": 1000
+                    }
+                ],
+                "inactive_validators": [
+                    "018139770ea87d175f56a35466c34c7ecccb8d8a91b4ee37a25df60f5b8fc9b394"
+                ]
+            },
+            "next_era_validator_weights": [
+                {
+                    "validator": "016e7a1cdd29b0b78fd13af4c5598feff4ef2a97166e3ca6f2e4fbfccd80505bf1",
+                    "weight": "456"
+                },
+                {
+                    "validator": "018a875fff1eb38451577acd5afee405456568dd7c89e090863a0557bc7af49f17",
+                    "weight": "789"
+                },
+                {
+                    "validator": "01d9bf2148748a85c89da5aad8ee0b0fc2d105fd39d41a4c796536354f0ae2900c",
+                    "weight": "123"
+                }
+            ]
+        },
+        "timestamp": "2020-11-17T00:39:24.072Z",
+        "era_id": 1,
+        "height": 10,
+        "protocol_version": "1.0.0"
+    }"#;
+
+    // The example block body from the same schema.
+    const BLOCK_BODY_JSON: &str = r#"{
+        "proposer": "01d9bf2148748a85c89da5aad8ee0b0fc2d105fd39d41a4c796536354f0ae2900c",
+        "deploy_hashes": [],
+        "transfer_hashes": [
+            "5c9b3b099c1378aa8e4a5f07f59ff1fcdc69a83179427c7e67ae0377d94d93fa"
+        ]
+    }"#;
+
+    fn block_hash() -> Digest {
+        Digest::from_hex(BLOCK_HASH).unwrap()
+    }
+
+    fn block_header() -> BlockHeader {
+        serde_json::from_str(BLOCK_HEADER_JSON).unwrap()
+    }
+
+    fn block_body() -> BlockBody {
+        serde_json::from_str(BLOCK_BODY_JSON).unwrap()
+    }
+
+    fn sign(secret_key: &SecretKey, block_hash: &Digest, era_id: EraId) -> BlockProof {
+        let public_key = PublicKey::from(secret_key);
+        let mut message = block_hash.value().to_vec();
+        message.extend_from_slice(&era_id.to_le_bytes());
+        let signature = crypto::sign(message, secret_key, &public_key);
+        BlockProof {
+            public_key,
+            signature,
+        }
+    }
+
+    #[test]
+    fn should_verify_block_header() {
+        let mut header = block_header();
+        verify_block_header(&header, &block_hash()).unwrap();
+        assert_eq!(header.next_era_validator_weights().unwrap().len(), 3);
+
+        header.height += 1;
+        assert!(matches!(
+            verify_block_header(&header, &block_hash()),
+            Err(Error::BlockHashMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn should_verify_block_body() {
+        let header = block_header();
+        let mut body = block_body();
+        verify_block_body(&header, &body).unwrap();
+
+        body.deploy_hashes.push(Digest::hash([1]));
+        assert!(matches!(
+            verify_block_body(&header, &body),
+            Err(Error::BodyHashMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn should_verify_finality_signatures() {
+        let secret_keys: Vec<SecretKey> = (1..=4u8)
+            .map(|seed| SecretKey::ed25519_from_bytes([seed; 32]).unwrap())
+            .collect();
+        let weights: BTreeMap<PublicKey, U512> = secret_keys
+            .iter()
+            .map(|secret_key| (PublicKey::from(secret_key), U512::from(100)))
+            .collect();
+        let ftt = Ratio::new(1, 3);
+        let era_id = EraId::new(1);
+        let hash = block_hash();
+        let proofs: Vec<BlockProof> = secret_keys
+            .iter()
+            .map(|secret_key| sign(secret_key, &hash, era_id))
+            .collect();
+
+        // The quorum is 2/3 of the weight, so three of the four validators are sufficient.
+        verify_finality_signatures(&hash, era_id, &proofs[..3], &weights, ftt).unwrap();
+
+        // Two are not, even if one of them signed twice.
+        let duplicated = [proofs[0].clone(), proofs[1].clone(), proofs[1].clone()];
+        assert!(matches!(
+            verify_finality_signatures(&hash, era_id, &duplicated, &weights, ftt),
+            Err(Error::InsufficientSignatureWeight { .. })
+        ));
+
+        // A signature for a different era is invalid.
+        let wrong_era = sign(&secret_keys[3], &hash, era_id.successor());
+        let mixed = [proofs[0].clone(), proofs[1].clone(), wrong_era];
+        assert!(matches!(
+            verify_finality_signatures(&hash, era_id, &mixed, &weights, ftt),
+            Err(Error::InvalidSignature { .. })
+        ));
+
+        // A signature from outside the validator set is rejected.
+        let outsider = SecretKey::ed25519_from_bytes([5; 32]).unwrap();
+        let bogus = [sign(&outsider, &hash, era_id)];
+        assert!(matches!(
+            verify_finality_signatures(&hash, era_id, &bogus, &weights, ftt),
+            Err(Error::BogusValidator(_))
+        ));
+    }
+}
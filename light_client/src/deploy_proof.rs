@@ -0,0 +1,329 @@
+use std::convert::TryFrom;
+
+use casper_execution_engine::storage::trie::merkle_proof::TrieMerkleProof;
+use casper_hashing::{ChunkWithProof, Digest};
+use casper_types::{
+    bytesrepr::{self, FromBytes},
+    CLType, ExecutionResult, Key, StoredValue,
+};
+
+use crate::{
+    block::{verify_block_body, BlockBody, BlockHeader},
+    merkle_proof::{verify_key, verify_proof},
+    Error,
+};
+
+/// Decodes the hex-encoded `execution_results_chunks` field returned by `info_get_deploy_proof`.
+pub fn decode_execution_results_chunks(
+    execution_results_chunks: &str,
+) -> Result<Vec<ChunkWithProof>, Error> {
+    let bytes = base16::decode(execution_results_chunks)?;
+    Ok(bytesrepr::deserialize(bytes)?)
+}
+
+/// Verifies the response of `info_get_deploy_proof` against a block header, and returns the
+/// deploy's execution result.
+///
+/// `header` should itself already have been verified, e.g. with [`verify_block_header`] and
+/// [`verify_finality_signatures`].  The following are then checked:
+///   * `body` hashes to the header's body hash,
+///   * `deploy_hash` is at `execution_result_index` of the body's deploys in execution order,
+///   * `merkle_proof` proves the value under `Key::BlockEffectsRootHash` for the block's height
+///     against the header's state root hash,
+///   * `execution_results_chunks` are the leading chunks of the serialized execution results
+///     hashing to that value, and hold as many execution results as the body has deploys, up to
+///     and including the one at `execution_result_index`.
+///
+/// [`verify_block_header`]: crate::verify_block_header
+/// [`verify_finality_signatures`]: crate::verify_finality_signatures
+pub fn verify_deploy_execution(
+    header: &BlockHeader,
+    body: &BlockBody,
+    deploy_hash: &Digest,
+    execution_result_index: u64,
+    execution_results_chunks: &[ChunkWithProof],
+    merkle_proof: &TrieMerkleProof<Key, StoredValue>,
+) -> Result<ExecutionResult, Error> {
+    verify_block_body(header, body)?;
+
+    let index = usize::try_from(execution_result_index).unwrap_or(usize::MAX);
+    if body.deploy_and_transfer_hashes().nth(index) != Some(deploy_hash) {
+        return Err(Error::DeployNotInBlock {
+            deploy_hash: *deploy_hash,
+            index: execution_result_index,
+        });
+    }
+
+    verify_key(
+        Key::BlockEffectsRootHash {
+            block_height: header.height,
+        },
+        merkle_proof,
+    )?;
+    verify_proof(&header.state_root_hash, merkle_proof)?;
+    let expected = match merkle_proof.value() {
+        StoredValue::CLValue(cl_value)
+            if cl_value.cl_type() == &CLType::ByteArray(Digest::LENGTH as u32) =>
+        {
+            Digest::try_from(cl_value.inner_bytes().as_slice())
+                .map_err(|_| Error::InvalidBlockEffectsRootHashValue)?
+        }
+        _ => return Err(Error::InvalidBlockEffectsRootHashValue),
+    };
+    let serialized = verify_chunks(&expected, execution_results_chunks)?;
+
+    // The execution results are serialized as a `Vec<ExecutionResult>`, so the chunks start with
+    // their count, followed by each of them in execution order.
+    let (execution_result_count, mut remainder) = u32::from_bytes(&serialized)?;
+    let deploy_count = body.deploy_and_transfer_hashes().count();
+    if execution_result_count as usize != deploy_count {
+        return Err(Error::ExecutionResultCountMismatch {
+            deploy_count,
+            execution_result_count: execution_result_count as usize,
+        });
+    }
+    let mut execution_result = None;
+    for _ in 0..=index {
+        let (next, next_remainder) = ExecutionResult::from_bytes(remainder)?;
+        execution_result = Some(next);
+        remainder = next_remainder;
+    }
+    Ok(execution_result.expect("should have deserialized at least one execution result"))
+}
+
+/// Verifies that `chunks` are the leading chunks, in order, of data which hashes to `expected`
+/// with `Digest::hash_bytes_into_chunks_if_necessary`, and returns their concatenation.
+fn verify_chunks(expected: &Digest, chunks: &[ChunkWithProof]) -> Result<Vec<u8>, Error> {
+    let mut data = Vec::new();
+    for (index, chunk) in (0..).zip(chunks) {
+        let proof = chunk.proof();
+        if proof.index() != index || index >= proof.count() {
+            return Err(Error::InvalidExecutionResultsChunk { index });
+        }
+        // Data fitting in a single chunk is hashed directly rather than as a Merkle tree.
+        let computed = if proof.count() == 1 {
+            Digest::hash(chunk.chunk())
+        } else {
+            chunk
+                .verify()
+                .map_err(|_| Error::InvalidExecutionResultsChunk { index })?;
+            proof.root_hash()
+        };
+        if computed != *expected {
+            return Err(Error::BlockEffectsRootHashMismatch {
+                expected: *expected,
+                computed,
+            });
+        }
+        data.extend_from_slice(chunk.chunk());
+    }
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use casper_types::{
+        bytesrepr::ToBytes, CLValue, ExecutionEffect, ProtocolVersion, PublicKey, SecretKey,
+        Timestamp, U512,
+    };
+
+    use super::*;
+    use crate::test_utils::single_node_trie;
+
+    fn execution_result(cost: u64) -> ExecutionResult {
+        ExecutionResult::Success {
+            effect: ExecutionEffect::default(),
+            transfers: vec![],
+            cost: U512::from(cost),
+            messages: vec![],
+        }
+    }
+
+    struct Fixture {
+        header: BlockHeader,
+        body: BlockBody,
+        serialized_execution_results: Vec<u8>,
+        merkle_proof: TrieMerkleProof<Key, StoredValue>,
+    }
+
+    impl Fixture {
+        /// Returns the chunks from the first up to the one with index `last_chunk_index`.
+        fn chunks(&self, last_chunk_index: u64) -> Vec<ChunkWithProof> {
+            (0..=last_chunk_index)
+                .map(|index| {
+                    ChunkWithProof::new(&self.serialized_execution_results, index).unwrap()
+                })
+                .collect()
+        }
+    }
+
+    fn fixture(execution_results: Vec<ExecutionResult>) -> Fixture {
+        let secret_key = SecretKey::ed25519_from_bytes([1; 32]).unwrap();
+        let body = BlockBody {
+            proposer: PublicKey::from(&secret_key),
+            deploy_hashes: vec![Digest::hash([1]), Digest::hash([2])],
+            transfer_hashes: vec![Digest::hash([3])],
+        };
+        let height = 5;
+        let serialized_execution_results = execution_results.to_bytes().unwrap();
+        let effects_root_hash =
+            Digest::hash_bytes_into_chunks_if_necessary(&serialized_execution_results);
+        let (state_root_hash, mut proofs) = single_node_trie(vec![
+            (
+                Key::BlockEffectsRootHash {
+                    block_height: height,
+                },
+                StoredValue::CLValue(CLValue::from_t(effects_root_hash).unwrap()),
+            ),
+            (
+                Key::Hash([4; 32]),
+                StoredValue::CLValue(CLValue::from_t(1u8).unwrap()),
+            ),
+        ]);
+        let header = BlockHeader {
+            parent_hash: Digest::hash([5]),
+            state_root_hash,
+            body_hash: body.hash().unwrap(),
+            random_bit: false,
+            accumulated_seed: Digest::hash([6]),
+            era_end: None,
+            timestamp: Timestamp::from(1_000),
+            era_id: 1.into(),
+            height,
+            protocol_version: ProtocolVersion::V1_0_0,
+        };
+        Fixture {
+            header,
+            body,
+            serialized_execution_results,
+            merkle_proof: proofs.remove(0),
+        }
+    }
+
+    fn small_fixture() -> Fixture {
+        fixture(vec![
+            execution_result(1),
+            execution_result(2),
+            execution_result(3),
+        ])
+    }
+
+    fn verify(
+        fixture: &Fixture,
+        deploy_hash: &Digest,
+        index: u64,
+        chunks: &[ChunkWithProof],
+    ) -> Result<ExecutionResult, Error> {
+        verify_deploy_execution(
+            &fixture.header,
+            &fixture.body,
+            deploy_hash,
+            index,
+            chunks,
+            &fixture.merkle_proof,
+        )
+    }
+
+    #[test]
+    fn should_verify_deploy_execution() {
+        let fixture = small_fixture();
+        let chunks = fixture.chunks(0);
+        assert_eq!(
+            verify(&fixture, &Digest::hash([1]), 0, &chunks).unwrap(),
+            execution_result(1)
+        );
+        // Transfers are executed after the non-transfer deploys.
+        assert_eq!(
+            verify(&fixture, &Digest::hash([3]), 2, &chunks).unwrap(),
+            execution_result(3)
+        );
+    }
+
+    #[test]
+    fn should_verify_deploy_execution_from_leading_chunks() {
+        let large_failure = |cost| ExecutionResult::Failure {
+            effect: ExecutionEffect::default(),
+            transfers: vec![],
+            cost: U512::from(cost),
+            error_message: "e".repeat(ChunkWithProof::CHUNK_SIZE_BYTES * 2 / 3),
+        };
+        let fixture = fixture(vec![large_failure(1), large_failure(2), large_failure(3)]);
+
+        // The first execution result ends in the first chunk, which is all that is needed.
+        assert_eq!(
+            verify(&fixture, &Digest::hash([1]), 0, &fixture.chunks(0)).unwrap(),
+            large_failure(1)
+        );
+        assert_eq!(
+            verify(&fixture, &Digest::hash([2]), 1, &fixture.chunks(1)).unwrap(),
+            large_failure(2)
+        );
+        assert!(matches!(
+            verify(&fixture, &Digest::hash([2]), 1, &fixture.chunks(0)),
+            Err(Error::Bytesrepr(_))
+        ));
+        // Chunks must be provided from the first one, in order.
+        let mut chunks = fixture.chunks(1);
+        chunks.remove(0);
+        assert!(matches!(
+            verify(&fixture, &Digest::hash([2]), 1, &chunks),
+            Err(Error::InvalidExecutionResultsChunk { index: 0 })
+        ));
+    }
+
+    #[test]
+    fn should_reject_deploy_at_wrong_index() {
+        let fixture = small_fixture();
+        let chunks = fixture.chunks(0);
+        assert!(matches!(
+            verify(&fixture, &Digest::hash([1]), 1, &chunks),
+            Err(Error::DeployNotInBlock { .. })
+        ));
+        assert!(matches!(
+            verify(&fixture, &Digest::hash([1]), u64::MAX, &chunks),
+            Err(Error::DeployNotInBlock { .. })
+        ));
+    }
+
+    #[test]
+    fn should_reject_tampered_execution_results() {
+        let small = small_fixture();
+        let tampered = vec![
+            execution_result(1),
+            execution_result(20),
+            execution_result(3),
+        ]
+        .to_bytes()
+        .unwrap();
+        let chunks = vec![ChunkWithProof::new(&tampered, 0).unwrap()];
+        assert!(matches!(
+            verify(&small, &Digest::hash([1]), 0, &chunks),
+            Err(Error::BlockEffectsRootHashMismatch { .. })
+        ));
+
+        let short = fixture(vec![execution_result(1), execution_result(2)]);
+        assert!(matches!(
+            verify(&short, &Digest::hash([1]), 0, &short.chunks(0)),
+            Err(Error::ExecutionResultCountMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn should_reject_tampered_body() {
+        let mut fixture = small_fixture();
+        fixture.body.transfer_hashes[0] = Digest::hash([7]);
+        let chunks = fixture.chunks(0);
+        assert!(matches!(
+            verify(&fixture, &Digest::hash([7]), 2, &chunks),
+            Err(Error::BodyHashMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn should_decode_execution_results_chunks() {
+        let fixture = small_fixture();
+        let chunks = fixture.chunks(0);
+        let encoded = base16::encode_lower(&chunks.to_bytes().unwrap());
+        assert_eq!(decode_execution_results_chunks(&encoded).unwrap(), chunks);
+    }
+}
@@ -0,0 +1,206 @@
+use std::fmt::{self, Display, Formatter};
+
+use casper_hashing::Digest;
+use casper_types::{bytesrepr, crypto, Key, PublicKey, U512};
+
+/// Errors returned when verifying data received from a node.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// The hex-encoded proof could not be decoded.
+    FromHex(base16::DecodeError),
+    /// A value could not be (de)serialized using `bytesrepr`.
+    Bytesrepr(bytesrepr::Error),
+    /// The block header does not hash to the expected block hash.
+    BlockHashMismatch {
+        /// The expected block hash.
+        expected: Digest,
+        /// The hash computed from the block header.
+        computed: Digest,
+    },
+    /// The block body does not hash to the body hash in the block header.
+    BodyHashMismatch {
+        /// The body hash in the block header.
+        expected: Digest,
+        /// The hash computed from the block body.
+        computed: Digest,
+    },
+    /// A finality signature is not valid for the block.
+    InvalidSignature {
+        /// The public key of the signer.
+        public_key: PublicKey,
+        /// The underlying cryptographic error.
+        error: crypto::Error,
+    },
+    /// A finality signature was provided by a public key which is not in the trusted validator
+    /// set.
+    BogusValidator(PublicKey),
+    /// The finality signatures do not carry enough of the validators' weight.
+    InsufficientSignatureWeight {
+        /// The total weight of the validators which signed the block.
+        signature_weight: U512,
+        /// The total weight of all trusted validators.
+        total_weight: U512,
+    },
+    /// No Merkle proofs were provided.
+    EmptyProofs,
+    /// A Merkle proof does not resolve to the expected state root hash.
+    StateRootHashMismatch {
+        /// The expected state root hash.
+        expected: Digest,
+        /// The state root hash computed from the proof.
+        computed: Digest,
+    },
+    /// A Merkle proof is for a different key than expected.
+    UnexpectedKey {
+        /// The key expected by following the query.
+        expected: Key,
+        /// The key the proof is for.
+        found: Key,
+    },
+    /// The named key at the given path element is not present in the preceding value.
+    NamedKeyNotFound(String),
+    /// The value proven by a Merkle proof cannot be used to continue the query.
+    CannotContinueQuery {
+        /// The key of the value which cannot be traversed.
+        key: Key,
+    },
+    /// The number of Merkle proofs does not match the length of the query path.
+    PathLengthMismatch {
+        /// The number of path elements consumed while following the proofs.
+        consumed: usize,
+        /// The number of path elements provided.
+        provided: usize,
+    },
+    /// The value proven by a balance proof is not a `U512`.
+    InvalidBalanceValue,
+    /// The number of execution results does not match the number of deploys in the block body.
+    ExecutionResultCountMismatch {
+        /// The number of deploys and transfers in the block body.
+        deploy_count: usize,
+        /// The number of execution results, as serialized in the chunks provided.
+        execution_result_count: usize,
+    },
+    /// The deploy is not at the given execution result index in the block body.
+    DeployNotInBlock {
+        /// The deploy hash.
+        deploy_hash: Digest,
+        /// The claimed index of the deploy's execution result.
+        index: u64,
+    },
+    /// The value proven under `Key::BlockEffectsRootHash` is not a `Digest`.
+    InvalidBlockEffectsRootHashValue,
+    /// A chunk of the serialized execution results is out of order or has an invalid proof.
+    InvalidExecutionResultsChunk {
+        /// The position of the chunk among those provided.
+        index: u64,
+    },
+    /// A chunk of the serialized execution results does not hash to the proven block effects root
+    /// hash.
+    BlockEffectsRootHashMismatch {
+        /// The proven block effects root hash.
+        expected: Digest,
+        /// The hash computed from the chunk.
+        computed: Digest,
+    },
+}
+
+impl Display for Error {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::FromHex(error) => write!(formatter, "failed to decode hex: {}", error),
+            Error::Bytesrepr(error) => write!(formatter, "serialization error: {}", error),
+            Error::BlockHashMismatch { expected, computed } => write!(
+                formatter,
+                "block hash mismatch: expected {}, computed {}",
+                expected, computed
+            ),
+            Error::BodyHashMismatch { expected, computed } => write!(
+                formatter,
+                "block body hash mismatch: expected {}, computed {}",
+                expected, computed
+            ),
+            Error::InvalidSignature { public_key, error } => write!(
+                formatter,
+                "invalid finality signature from {}: {}",
+                public_key, error
+            ),
+            Error::BogusValidator(public_key) => write!(
+                formatter,
+                "finality signature from {} which is not a trusted validator",
+                public_key
+            ),
+            Error::InsufficientSignatureWeight {
+                signature_weight,
+                total_weight,
+            } => write!(
+                formatter,
+                "insufficient finality signature weight: {} of total {}",
+                signature_weight, total_weight
+            ),
+            Error::EmptyProofs => write!(formatter, "no merkle proofs provided"),
+            Error::StateRootHashMismatch { expected, computed } => write!(
+                formatter,
+                "state root hash mismatch: expected {}, computed {}",
+                expected, computed
+            ),
+            Error::UnexpectedKey { expected, found } => write!(
+                formatter,
+                "merkle proof for unexpected key: expected {}, found {}",
+                expected, found
+            ),
+            Error::NamedKeyNotFound(name) => {
+                write!(formatter, "named key '{}' not found", name)
+            }
+            Error::CannotContinueQuery { key } => write!(
+                formatter,
+                "value under {} cannot be used to continue the query",
+                key
+            ),
+            Error::PathLengthMismatch { consumed, provided } => write!(
+                formatter,
+                "merkle proofs consumed {} of {} path elements",
+                consumed, provided
+            ),
+            Error::InvalidBalanceValue => write!(formatter, "balance value is not a U512"),
+            Error::ExecutionResultCountMismatch {
+                deploy_count,
+                execution_result_count,
+            } => write!(
+                formatter,
+                "{} execution results provided for {} deploys in block body",
+                execution_result_count, deploy_count
+            ),
+            Error::DeployNotInBlock { deploy_hash, index } => write!(
+                formatter,
+                "deploy {} not at index {} of block body",
+                deploy_hash, index
+            ),
+            Error::InvalidBlockEffectsRootHashValue => {
+                write!(formatter, "block effects root hash value is not a digest")
+            }
+            Error::InvalidExecutionResultsChunk { index } => {
+                write!(formatter, "invalid execution results chunk at {}", index)
+            }
+            Error::BlockEffectsRootHashMismatch { expected, computed } => write!(
+                formatter,
+                "block effects root hash mismatch: expected {}, computed {}",
+                expected, computed
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<base16::DecodeError> for Error {
+    fn from(error: base16::DecodeError) -> Self {
+        Error::FromHex(error)
+    }
+}
+
+impl From<bytesrepr::Error> for Error {
+    fn from(error: bytesrepr::Error) -> Self {
+        Error::Bytesrepr(error)
+    }
+}
@@ -0,0 +1,38 @@
+//! A library for verifying Casper block headers, finality signatures and global state proofs
+//! returned by a node's JSON-RPC API, without trusting the node.
+//!
+//! A light client starting from a trusted set of validator weights can:
+//!   * check a block header against its hash with [`verify_block_header`],
+//!   * check the block's finality signatures against the validator weights with
+//!     [`verify_finality_signatures`],
+//!   * check global state values and balances against the block's state root hash with
+//!     [`verify_query_proofs`] and [`verify_balance_proof`],
+//!   * check that a deploy was executed in the block, and its execution result, with
+//!     [`verify_deploy_execution`].
+//!
+//! The validator weights for the next era are then available from a verified switch block via
+//! [`BlockHeader::next_era_validator_weights`].
+#![doc(html_root_url = "https://docs.rs/casper-light-client/0.1.0")]
+#![doc(
+    html_favicon_url = "https://raw.githubusercontent.com/CasperLabs/casper-node/master/images/CasperLabs_Logo_Favicon_RGB_50px.png",
+    html_logo_url = "https://raw.githubusercontent.com/CasperLabs/casper-node/master/images/CasperLabs_Logo_Symbol_RGB.png",
+    test(attr(forbid(warnings)))
+)]
+#![warn(missing_docs)]
+
+mod block;
+mod deploy_proof;
+mod error;
+mod merkle_proof;
+#[cfg(test)]
+mod test_utils;
+
+pub use block::{
+    verify_block_body, verify_block_header, verify_finality_signatures, BlockBody, BlockHeader,
+    BlockProof, EraEnd, EraReport, Reward, ValidatorWeight,
+};
+pub use deploy_proof::{decode_execution_results_chunks, verify_deploy_execution};
+pub use error::Error;
+pub use merkle_proof::{
+    decode_balance_proof, decode_query_proofs, verify_balance_proof, verify_query_proofs,
+};
@@ -0,0 +1,248 @@
+use casper_execution_engine::storage::trie::merkle_proof::TrieMerkleProof;
+use casper_hashing::Digest;
+use casper_types::{
+    bytesrepr::{self, FromBytes},
+    CLType, CLValue, Key, StoredValue, URef, U512,
+};
+
+use crate::Error;
+
+/// Decodes the hex-encoded `merkle_proof` field returned by `query_global_state`,
+/// `state_get_item` or `state_get_dictionary_item`.
+pub fn decode_query_proofs(
+    merkle_proof: &str,
+) -> Result<Vec<TrieMerkleProof<Key, StoredValue>>, Error> {
+    let bytes = base16::decode(merkle_proof)?;
+    Ok(bytesrepr::deserialize(bytes)?)
+}
+
+/// Decodes the hex-encoded `merkle_proof` field returned by `state_get_balance`.
+pub fn decode_balance_proof(
+    merkle_proof: &str,
+) -> Result<TrieMerkleProof<Key, StoredValue>, Error> {
+    let bytes = base16::decode(merkle_proof)?;
+    Ok(bytesrepr::deserialize(bytes)?)
+}
+
+/// Verifies that `proof` resolves to `state_root_hash`.
+pub(crate) fn verify_proof(
+    state_root_hash: &Digest,
+    proof: &TrieMerkleProof<Key, StoredValue>,
+) -> Result<(), Error> {
+    let computed = proof.compute_state_hash()?;
+    if computed != *state_root_hash {
+        return Err(Error::StateRootHashMismatch {
+            expected: *state_root_hash,
+            computed,
+        });
+    }
+    Ok(())
+}
+
+/// Verifies that `proof` is for `expected_key`.
+pub(crate) fn verify_key(
+    expected: Key,
+    proof: &TrieMerkleProof<Key, StoredValue>,
+) -> Result<(), Error> {
+    let found = *proof.key();
+    if found != expected {
+        return Err(Error::UnexpectedKey { expected, found });
+    }
+    Ok(())
+}
+
+/// Verifies the Merkle proofs of a global state query starting at `base_key` and following
+/// `path`, all against `state_root_hash`, and returns the queried value.
+///
+/// The proofs are expected in the order produced by the node: one per value visited while
+/// following the path.  Each step must be justified by the previously proven value, i.e. a named
+/// key of an account or contract, or a `CLValue` holding a `Key`.
+///
+/// As with the node's query, a value stored under a `Key::Dictionary` is returned with its
+/// dictionary wrapping removed.
+pub fn verify_query_proofs(
+    state_root_hash: &Digest,
+    proofs: &[TrieMerkleProof<Key, StoredValue>],
+    base_key: Key,
+    path: &[String],
+) -> Result<StoredValue, Error> {
+    let mut expected_key = base_key;
+    let mut names = path.iter();
+    let mut consumed = 0;
+
+    for (index, proof) in proofs.iter().enumerate() {
+        verify_key(expected_key, proof)?;
+        verify_proof(state_root_hash, proof)?;
+
+        let is_last = index + 1 == proofs.len();
+        if consumed == path.len() {
+            if !is_last {
+                return Err(Error::PathLengthMismatch {
+                    consumed,
+                    provided: path.len(),
+                });
+            }
+            return unwrap_dictionary_value(expected_key, proof.value().clone());
+        }
+        if is_last {
+            break;
+        }
+
+        expected_key = match proof.value() {
+            StoredValue::Account(account) => {
+                let name = names.next().expect("should have remaining names");
+                consumed += 1;
+                *account
+                    .named_keys()
+                    .get(name)
+                    .ok_or_else(|| Error::NamedKeyNotFound(name.clone()))?
+            }
+            StoredValue::Contract(contract) => {
+                let name = names.next().expect("should have remaining names");
+                consumed += 1;
+                *contract
+                    .named_keys()
+                    .get(name)
+                    .ok_or_else(|| Error::NamedKeyNotFound(name.clone()))?
+            }
+            StoredValue::CLValue(cl_value) if cl_value.cl_type() == &CLType::Key => {
+                bytesrepr::deserialize(cl_value.inner_bytes().clone())?
+            }
+            _ => return Err(Error::CannotContinueQuery { key: expected_key }),
+        };
+    }
+
+    if proofs.is_empty() {
+        return Err(Error::EmptyProofs);
+    }
+    Err(Error::PathLengthMismatch {
+        consumed,
+        provided: path.len(),
+    })
+}
+
+/// Strips the wrapping applied to values stored under a `Key::Dictionary`, which holds the
+/// actual `CLValue` followed by the dictionary's seed URef address and the item key.
+fn unwrap_dictionary_value(key: Key, value: StoredValue) -> Result<StoredValue, Error> {
+    match (key, value) {
+        (Key::Dictionary(_), StoredValue::CLValue(wrapped)) => {
+            let (cl_value, _remainder) = CLValue::from_bytes(wrapped.inner_bytes())?;
+            Ok(StoredValue::CLValue(cl_value))
+        }
+        (_, value) => Ok(value),
+    }
+}
+
+/// Verifies the Merkle proof of the balance of `purse` against `state_root_hash`, and returns the
+/// balance.
+pub fn verify_balance_proof(
+    state_root_hash: &Digest,
+    proof: &TrieMerkleProof<Key, StoredValue>,
+    purse: URef,
+) -> Result<U512, Error> {
+    verify_key(Key::Balance(purse.addr()), proof)?;
+    verify_proof(state_root_hash, proof)?;
+    match proof.value() {
+        StoredValue::CLValue(cl_value) if cl_value.cl_type() == &CLType::U512 => {
+            Ok(bytesrepr::deserialize(cl_value.inner_bytes().clone())?)
+        }
+        _ => Err(Error::InvalidBalanceValue),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use casper_types::{
+        account::{Account, AccountHash, ActionThresholds, AssociatedKeys, Weight},
+        bytesrepr::ToBytes,
+        contracts::NamedKeys,
+        AccessRights,
+    };
+
+    use super::*;
+    use crate::test_utils::single_node_trie;
+
+    fn account_with_named_key(name: &str, key: Key) -> (Key, StoredValue) {
+        let account_hash = AccountHash::new([1; 32]);
+        let mut named_keys = NamedKeys::new();
+        named_keys.insert(name.to_string(), key);
+        let account = Account::new(
+            account_hash,
+            named_keys,
+            URef::new([2; 32], AccessRights::READ_ADD_WRITE),
+            AssociatedKeys::new(account_hash, Weight::new(1)),
+            ActionThresholds::default(),
+        );
+        (Key::Account(account_hash), StoredValue::Account(account))
+    }
+
+    #[test]
+    fn should_verify_query_proofs_through_named_key() {
+        let target_key = Key::Hash([3; 32]);
+        let target_value = StoredValue::CLValue(CLValue::from_t(42u64).unwrap());
+        let (account_key, account_value) = account_with_named_key("answer", target_key);
+        let (root_hash, proofs) = single_node_trie(vec![
+            (account_key, account_value),
+            (target_key, target_value.clone()),
+        ]);
+        let encoded = base16::encode_lower(&proofs.to_bytes().unwrap());
+        let proofs = decode_query_proofs(&encoded).unwrap();
+
+        let path = vec!["answer".to_string()];
+        let value = verify_query_proofs(&root_hash, &proofs, account_key, &path).unwrap();
+        assert_eq!(value, target_value);
+
+        // The proofs don't justify a different base key.
+        assert!(matches!(
+            verify_query_proofs(&root_hash, &proofs, target_key, &path),
+            Err(Error::UnexpectedKey { .. })
+        ));
+
+        // The proofs don't match a different path.
+        let wrong_path = vec!["question".to_string()];
+        assert!(matches!(
+            verify_query_proofs(&root_hash, &proofs, account_key, &wrong_path),
+            Err(Error::NamedKeyNotFound(_))
+        ));
+        assert!(matches!(
+            verify_query_proofs(&root_hash, &proofs, account_key, &[]),
+            Err(Error::PathLengthMismatch { .. })
+        ));
+
+        // The proofs don't resolve to a different state root hash.
+        assert!(matches!(
+            verify_query_proofs(&Digest::hash([0]), &proofs, account_key, &path),
+            Err(Error::StateRootHashMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn should_verify_balance_proof() {
+        let purse = URef::new([4; 32], AccessRights::READ_ADD_WRITE);
+        let balance = U512::from(123_456);
+        let (root_hash, mut proofs) = single_node_trie(vec![
+            (
+                Key::Balance(purse.addr()),
+                StoredValue::CLValue(CLValue::from_t(balance).unwrap()),
+            ),
+            (
+                Key::Hash([5; 32]),
+                StoredValue::CLValue(CLValue::from_t(1u8).unwrap()),
+            ),
+        ]);
+        let proof = proofs.remove(0);
+        let encoded = base16::encode_lower(&proof.to_bytes().unwrap());
+        let proof = decode_balance_proof(&encoded).unwrap();
+
+        assert_eq!(
+            verify_balance_proof(&root_hash, &proof, purse).unwrap(),
+            balance
+        );
+
+        let other_purse = URef::new([6; 32], AccessRights::READ_ADD_WRITE);
+        assert!(matches!(
+            verify_balance_proof(&root_hash, &proof, other_purse),
+            Err(Error::UnexpectedKey { .. })
+        ));
+    }
+}
@@ -0,0 +1,39 @@
+use std::collections::VecDeque;
+
+use casper_execution_engine::storage::trie::{
+    merkle_proof::{TrieMerkleProof, TrieMerkleProofStep},
+    Pointer, Trie,
+};
+use casper_hashing::Digest;
+use casper_types::{bytesrepr::ToBytes, Key, StoredValue};
+
+/// Builds proofs for `entries` as if they were stored in a trie with a single root node,
+/// returning the root hash and a proof for each entry.
+pub(crate) fn single_node_trie(
+    entries: Vec<(Key, StoredValue)>,
+) -> (Digest, Vec<TrieMerkleProof<Key, StoredValue>>) {
+    let pointers: Vec<(u8, Pointer)> = entries
+        .iter()
+        .enumerate()
+        .map(|(index, (key, value))| {
+            let leaf = Trie::leaf(*key, value.clone());
+            (index as u8, Pointer::LeafPointer(leaf.trie_hash().unwrap()))
+        })
+        .collect();
+    let root_hash = Digest::hash(
+        Trie::<Key, StoredValue>::node(&pointers)
+            .to_bytes()
+            .unwrap(),
+    );
+    let proofs = entries
+        .into_iter()
+        .enumerate()
+        .map(|(index, (key, value))| {
+            let mut indexed_pointers_with_hole = pointers.clone();
+            indexed_pointers_with_hole.remove(index);
+            let step = TrieMerkleProofStep::node(index as u8, indexed_pointers_with_hole);
+            TrieMerkleProof::new(key, value, VecDeque::from(vec![step]))
+        })
+        .collect();
+    (root_hash, proofs)
+}
@@ -20,11 +20,12 @@ use toml::{value::Table, Value};
 use tracing::{error, info};
 
 use crate::{
+    components::consensus::SlashingProtection,
     logging,
     reactor::{initializer, joiner, participating, ReactorExit, Runner},
     setup_signal_hooks,
-    types::ExitCode,
-    utils::WithDir,
+    types::{Chainspec, ChainspecRawBytes, ExitCode},
+    utils::{Loadable, WithDir},
 };
 
 // We override the standard allocator to gather metrics and tune the allocator via th MALLOC_CONF
@@ -74,6 +75,29 @@ pub enum Cli {
         #[structopt(long)]
         new_config: PathBuf,
     },
+    /// Export the record of units and finality signatures signed by the validator.
+    ///
+    /// The exported file can be imported on a new host, so that the validator doesn't sign anything
+    /// conflicting with what it signed before.
+    ExportSlashingProtection {
+        /// Path to configuration file.
+        #[structopt(long)]
+        config: PathBuf,
+        /// Path to the interchange file to write.
+        #[structopt(long)]
+        output: PathBuf,
+    },
+    /// Import a record of units and finality signatures signed by the validator.
+    ///
+    /// The record is merged into the existing one. The node must not be running.
+    ImportSlashingProtection {
+        /// Path to configuration file.
+        #[structopt(long)]
+        config: PathBuf,
+        /// Path to the interchange file to read.
+        #[structopt(long)]
+        input: PathBuf,
+    },
 }
 
 #[derive(Debug)]
@@ -244,9 +268,45 @@ impl Cli {
                 )?;
                 Ok(ExitCode::Success as i32)
             }
+            Cli::ExportSlashingProtection { config, output } => {
+                let slashing_protection = Self::open_slashing_protection(&config)?;
+                let interchange = slashing_protection.export()?;
+                let encoded_interchange = serde_json::to_vec_pretty(&interchange)?;
+                fs::write(&output, encoded_interchange)
+                    .context("could not write slashing protection interchange file")
+                    .with_context(|| output.display().to_string())?;
+                info!(
+                    units = interchange.signed_units.len(),
+                    finality_signatures = interchange.signed_finality_signatures.len(),
+                    "exported slashing protection record"
+                );
+                Ok(ExitCode::Success as i32)
+            }
+            Cli::ImportSlashingProtection { config, input } => {
+                let slashing_protection = Self::open_slashing_protection(&config)?;
+                let encoded_interchange = fs::read(&input)
+                    .context("could not read slashing protection interchange file")
+                    .with_context(|| input.display().to_string())?;
+                slashing_protection.import(serde_json::from_slice(&encoded_interchange)?)?;
+                info!("imported slashing protection record");
+                Ok(ExitCode::Success as i32)
+            }
         }
     }
 
+    /// Opens the slashing protection database in the storage directory of the given config.
+    fn open_slashing_protection(config: &Path) -> anyhow::Result<SlashingProtection> {
+        let config = Self::init(config, vec![])?;
+        let (chainspec, _) = <(Chainspec, ChainspecRawBytes)>::from_path(config.dir())?;
+        let storage_dir = config
+            .with_dir(config.value().storage.path.clone())
+            .join(&chainspec.network_config.name);
+        fs::create_dir_all(&storage_dir)
+            .context("could not create storage directory")
+            .with_context(|| storage_dir.display().to_string())?;
+        Ok(SlashingProtection::open(&storage_dir)?)
+    }
+
     /// Parses the config file for the current version of casper-node, and initializes logging.
     fn init(
         config: &Path,
@@ -275,6 +335,7 @@ impl Cli {
 
         // Create participating config, including any overridden values.
         let participating_config: participating::Config = config_table.try_into()?;
+        participating_config.validate()?;
         logging::init_with_config(&participating_config.logging)?;
 
         Ok(WithDir::new(root, participating_config))
@@ -55,6 +55,7 @@ pub(crate) mod diagnostics_port;
 pub(crate) mod event_stream_server;
 pub(crate) mod fetcher;
 pub(crate) mod gossiper;
+pub(crate) mod graphql_server;
 pub(crate) mod linear_chain;
 pub(crate) mod rest_server;
 pub mod rpc_server;
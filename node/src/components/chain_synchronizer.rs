@@ -20,7 +20,7 @@ use crate::{
     },
     effect::{
         announcements::{
-            BlocklistAnnouncement, ChainSynchronizerAnnouncement, ControlAnnouncement,
+            ChainSynchronizerAnnouncement, ControlAnnouncement, PeerBehaviorAnnouncement,
         },
         requests::{
             ChainspecLoaderRequest, ContractRuntimeRequest, FetcherRequest,
@@ -88,7 +88,7 @@ where
         + From<FetcherRequest<Deploy>>
         + From<FetcherRequest<FinalizedApprovalsWithId>>
         + From<FetcherRequest<TrieOrChunk>>
-        + From<BlocklistAnnouncement>
+        + From<PeerBehaviorAnnouncement>
         + From<ControlAnnouncement>
         + From<MarkBlockCompletedRequest>
         + Send,
@@ -178,7 +178,7 @@ where
         + From<FetcherRequest<BlockSignatures>>
         + From<FetcherRequest<BlockHeadersBatch>>
         + From<ContractRuntimeRequest>
-        + From<BlocklistAnnouncement>
+        + From<PeerBehaviorAnnouncement>
         + From<MarkBlockCompletedRequest>
         + From<ChainSynchronizerAnnouncement>
         + Send,
@@ -273,7 +273,7 @@ where
         + From<FetcherRequest<Deploy>>
         + From<FetcherRequest<FinalizedApprovalsWithId>>
         + From<FetcherRequest<TrieOrChunk>>
-        + From<BlocklistAnnouncement>
+        + From<PeerBehaviorAnnouncement>
         + From<ControlAnnouncement>
         + From<MarkBlockCompletedRequest>
         + Send,
@@ -38,7 +38,7 @@ use crate::{
     },
     effect::{
         announcements::{
-            BlocklistAnnouncement, ChainSynchronizerAnnouncement, ControlAnnouncement,
+            ChainSynchronizerAnnouncement, ControlAnnouncement, Offense, PeerBehaviorAnnouncement,
         },
         requests::{
             ContractRuntimeRequest, FetcherRequest, MarkBlockCompletedRequest, NetworkInfoRequest,
@@ -357,7 +357,7 @@ impl CanUseSyncingNodes for Deploy {}
 impl CanUseSyncingNodes for BlockAndDeploys {}
 impl CanUseSyncingNodes for BlockHeadersBatch {}
 
-/// Gets a list of peers suitable for the fetch operation.
+/// Gets a list of peers suitable for the fetch operation, penalized peers last.
 async fn get_peers<REv>(include_syncing: bool, ctx: &ChainSyncContext<'_, REv>) -> Vec<NodeId>
 where
     REv: From<NetworkInfoRequest>,
@@ -800,7 +800,7 @@ where
     I: BlockOrHeaderWithMetadata,
     REv: From<FetcherRequest<I>>
         + From<NetworkInfoRequest>
-        + From<BlocklistAnnouncement>
+        + From<PeerBehaviorAnnouncement>
         + From<StorageRequest>
         + Send,
     Error: From<FetcherError<I>>,
@@ -871,7 +871,7 @@ where
 /// Fetches the next block or block header from the network by height.
 /// Returns `Ok(None)` if there are no more peers left.
 async fn try_fetch_block_or_block_header_by_height<REv, I>(
-    mut peers: Vec<NodeId>,
+    peers: Vec<NodeId>,
     ctx: &ChainSyncContext<'_, REv>,
     height: u64,
     parent_header: &BlockHeader,
@@ -879,11 +879,12 @@ async fn try_fetch_block_or_block_header_by_height<REv, I>(
 ) -> Result<Option<Box<I>>, Error>
 where
     I: BlockOrHeaderWithMetadata,
-    REv: From<FetcherRequest<I>> + From<BlocklistAnnouncement> + From<StorageRequest> + Send,
+    REv: From<FetcherRequest<I>> + From<PeerBehaviorAnnouncement> + From<StorageRequest> + Send,
     Error: From<FetcherError<I>>,
 {
+    let mut peers = peers.into_iter();
     Ok(loop {
-        let peer = match peers.pop() {
+        let peer = match peers.next() {
             Some(peer) => peer,
             None => return Ok(None),
         };
@@ -905,7 +906,9 @@ where
                         ?parent_header,
                         "received block with wrong parent from peer",
                     );
-                    ctx.effect_builder.announce_disconnect_from_peer(peer).await;
+                    ctx.effect_builder
+                        .announce_offense(peer, Offense::InvalidItem)
+                        .await;
                     continue;
                 }
 
@@ -919,7 +922,9 @@ where
 
                 if item.block_signatures().proofs.is_empty() {
                     warn!(?peer, ?item, "no block signatures from peer");
-                    ctx.effect_builder.announce_disconnect_from_peer(peer).await;
+                    ctx.effect_builder
+                        .announce_offense(peer, Offense::InvalidItem)
+                        .await;
                     continue;
                 }
 
@@ -934,7 +939,9 @@ where
                     }
                     Err(error @ BlockSignatureError::BogusValidator { .. }) => {
                         warn!(?error, ?peer, "bogus validator block signature from peer");
-                        ctx.effect_builder.announce_disconnect_from_peer(peer).await;
+                        ctx.effect_builder
+                            .announce_offense(peer, Offense::InvalidItem)
+                            .await;
                         continue;
                     }
                     // TODO - make this an error condition once we start using
@@ -951,7 +958,9 @@ where
                         ?peer,
                         "error validating finality signatures from peer"
                     );
-                    ctx.effect_builder.announce_disconnect_from_peer(peer).await;
+                    ctx.effect_builder
+                        .announce_offense(peer, Offense::InvalidItem)
+                        .await;
                     continue;
                 }
 
@@ -1203,7 +1212,7 @@ where
         + From<NetworkInfoRequest>
         + From<ContractRuntimeRequest>
         + From<StorageRequest>
-        + From<BlocklistAnnouncement>
+        + From<PeerBehaviorAnnouncement>
         + From<FetcherRequest<Block>>
         + From<FetcherRequest<BlockHeader>>
         + From<FetcherRequest<BlockHeaderWithMetadata>>
@@ -1282,7 +1291,7 @@ async fn fetch_block_headers_up_to_current_era<REv>(
 where
     REv: From<FetcherRequest<BlockHeaderWithMetadata>>
         + From<NetworkInfoRequest>
-        + From<BlocklistAnnouncement>
+        + From<PeerBehaviorAnnouncement>
         + From<StorageRequest>
         + Send,
 {
@@ -1416,7 +1425,7 @@ where
         + From<FetcherRequest<BlockSignatures>>
         + From<FetcherRequest<BlockHeadersBatch>>
         + From<ContractRuntimeRequest>
-        + From<BlocklistAnnouncement>
+        + From<PeerBehaviorAnnouncement>
         + From<MarkBlockCompletedRequest>
         + From<ChainSynchronizerAnnouncement>
         + Send,
@@ -1441,7 +1450,7 @@ where
     REv: From<FetcherRequest<BlockHeadersBatch>>
         + From<NetworkInfoRequest>
         + From<StorageRequest>
-        + From<BlocklistAnnouncement>,
+        + From<PeerBehaviorAnnouncement>,
 {
     let _metric = ScopeTimer::new(&ctx.metrics.chain_sync_fetch_to_genesis_duration_seconds);
     info!(
@@ -1489,7 +1498,7 @@ where
     REv: From<FetcherRequest<BlockHeadersBatch>>
         + From<NetworkInfoRequest>
         + From<StorageRequest>
-        + From<BlocklistAnnouncement>,
+        + From<PeerBehaviorAnnouncement>,
 {
     let batch_id =
         BlockHeadersBatchId::from_known(lowest_trusted_block_header, MAX_HEADERS_BATCH_SIZE);
@@ -1518,7 +1527,9 @@ where
                             ?batch_id,
                             "block headers batch failed validation. Trying next peer..."
                         );
-                        ctx.effect_builder.announce_disconnect_from_peer(peer).await;
+                        ctx.effect_builder
+                            .announce_offense(peer, Offense::InvalidItem)
+                            .await;
                     }
                 }
             }
@@ -1538,7 +1549,7 @@ where
         + From<FetcherRequest<BlockSignatures>>
         + From<NetworkInfoRequest>
         + From<ContractRuntimeRequest>
-        + From<BlocklistAnnouncement>
+        + From<PeerBehaviorAnnouncement>
         + From<MarkBlockCompletedRequest>
         + Send,
 {
@@ -1581,7 +1592,7 @@ where
         + From<FetcherRequest<BlockSignatures>>
         + From<NetworkInfoRequest>
         + From<ContractRuntimeRequest>
-        + From<BlocklistAnnouncement>
+        + From<PeerBehaviorAnnouncement>
         + From<MarkBlockCompletedRequest>
         + Send,
 {
@@ -1705,7 +1716,7 @@ impl BlockSignaturesCollector {
     ) -> Result<HandleSignaturesResult, Error>
     where
         REv: From<StorageRequest>
-            + From<BlocklistAnnouncement>
+            + From<PeerBehaviorAnnouncement>
             + From<ContractRuntimeRequest>
             + Send,
     {
@@ -1724,7 +1735,9 @@ impl BlockSignaturesCollector {
                 height = block_header.height(),
                 "peer sent invalid finality signatures, banning peer"
             );
-            ctx.effect_builder.announce_disconnect_from_peer(peer).await;
+            ctx.effect_builder
+                .announce_offense(peer, Offense::InvalidItem)
+                .await;
 
             // Try with next peer.
             return Ok(HandleSignaturesResult::ContinueFetching);
@@ -1785,7 +1798,7 @@ where
     REv: From<StorageRequest>
         + From<NetworkInfoRequest>
         + From<FetcherRequest<BlockSignatures>>
-        + From<BlocklistAnnouncement>
+        + From<PeerBehaviorAnnouncement>
         + From<ContractRuntimeRequest>
         + Send,
 {
@@ -1917,7 +1930,7 @@ where
         + From<FetcherRequest<Deploy>>
         + From<FetcherRequest<FinalizedApprovalsWithId>>
         + From<FetcherRequest<TrieOrChunk>>
-        + From<BlocklistAnnouncement>
+        + From<PeerBehaviorAnnouncement>
         + From<MarkBlockCompletedRequest>
         + From<ControlAnnouncement>
         + Send,
@@ -2061,7 +2074,7 @@ where
         + From<FetcherRequest<Deploy>>
         + From<NetworkInfoRequest>
         + From<ContractRuntimeRequest>
-        + From<BlocklistAnnouncement>
+        + From<PeerBehaviorAnnouncement>
         + From<StorageRequest>
         + From<MarkBlockCompletedRequest>
         + From<ControlAnnouncement>
@@ -2163,7 +2176,9 @@ where
                     "block executed with approvals from this peer doesn't match the received \
                     block; blocking peer"
                 );
-                ctx.effect_builder.announce_disconnect_from_peer(peer).await;
+                ctx.effect_builder
+                    .announce_offense(peer, Offense::InvalidItem)
+                    .await;
             }
         }
 
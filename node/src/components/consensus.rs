@@ -11,6 +11,7 @@ mod highway_core;
 pub(crate) mod error;
 mod metrics;
 mod protocols;
+pub(crate) mod signer;
 #[cfg(test)]
 mod tests;
 mod traits;
@@ -30,12 +31,13 @@ use hex_fmt::HexFmt;
 use serde::{Deserialize, Serialize};
 use tracing::info;
 
-use casper_types::{EraId, PublicKey, Timestamp};
+use casper_hashing::Digest;
+use casper_types::{EraId, PublicKey, Signature, Timestamp};
 
 use crate::{
     components::Component,
     effect::{
-        announcements::{BlocklistAnnouncement, ConsensusAnnouncement},
+        announcements::{ConsensusAnnouncement, PeerBehaviorAnnouncement},
         diagnostics_port::DumpConsensusStateRequest,
         incoming::ConsensusMessageIncoming,
         requests::{
@@ -55,6 +57,7 @@ pub(crate) use config::{ChainspecConsensusExt, Config};
 pub(crate) use consensus_protocol::{BlockContext, EraReport, ProposedBlock};
 pub(crate) use era_supervisor::{debug::EraDump, EraSupervisor};
 pub(crate) use protocols::highway::HighwayProtocol;
+pub(crate) use signer::{spawn_signing, LoadSignerError, Signer, SlashingProtection};
 pub(crate) use validator_change::ValidatorChange;
 
 #[derive(DataSize, Clone, Serialize, Deserialize)]
@@ -107,6 +110,13 @@ pub(crate) enum Event {
     Action { era_id: EraId, action_id: ActionId },
     /// We are receiving the data we require to propose a new block.
     NewBlockPayload(NewBlockPayload),
+    /// The signature of one of our consensus messages in the given era, or `None` if signing
+    /// failed.
+    Signed {
+        era_id: EraId,
+        hash: Digest,
+        signature: Option<Signature>,
+    },
     #[from]
     ConsensusRequest(ConsensusRequest),
     /// A new block has been added to the linear chain.
@@ -191,6 +201,9 @@ impl Display for Event {
                 "New proto-block for era {:?}: {:?}, {:?}",
                 era_id, block_payload, block_context
             ),
+            Event::Signed { era_id, hash, .. } => {
+                write!(f, "signature of consensus message {} in {}", hash, era_id)
+            }
             Event::ConsensusRequest(request) => write!(
                 f,
                 "A request for consensus component hash been received: {:?}",
@@ -251,8 +264,8 @@ pub(crate) trait ReactorEventT:
     + From<StorageRequest>
     + From<ContractRuntimeRequest>
     + From<ChainspecLoaderRequest>
-    + From<BlocklistAnnouncement>
-    + From<BlocklistAnnouncement>
+    + From<PeerBehaviorAnnouncement>
+    + From<PeerBehaviorAnnouncement>
 {
 }
 
@@ -268,7 +281,7 @@ impl<REv> ReactorEventT for REv where
         + From<StorageRequest>
         + From<ContractRuntimeRequest>
         + From<ChainspecLoaderRequest>
-        + From<BlocklistAnnouncement>
+        + From<PeerBehaviorAnnouncement>
 {
 }
 
@@ -300,6 +313,11 @@ where
             Event::NewBlockPayload(new_block_payload) => {
                 self.handle_new_block_payload(effect_builder, rng, new_block_payload)
             }
+            Event::Signed {
+                era_id,
+                hash,
+                signature,
+            } => self.handle_signature(effect_builder, rng, era_id, hash, signature),
             Event::BlockAdded {
                 header,
                 header_hash: _,
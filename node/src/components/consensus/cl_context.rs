@@ -2,35 +2,56 @@ use std::sync::Arc;
 
 use datasize::DataSize;
 use serde::{Deserialize, Serialize};
-use tracing::info;
+use tracing::{error, info};
 
 use casper_hashing::Digest;
-use casper_types::{crypto, PublicKey, SecretKey, Signature};
+use casper_types::{crypto, EraId, PublicKey, SecretKey, Signature};
 
 use crate::{
-    components::consensus::traits::{ConsensusValueT, Context, ValidatorSecret},
+    components::consensus::{
+        consensus_protocol::SignatureRequest,
+        signer::{LocalSigner, Signer, SigningRequest},
+        traits::{ConsensusValueT, Context, ValidatorSecret},
+    },
     types::BlockPayload,
 };
 
+/// The validator's signing key for consensus messages in a particular era.
 #[derive(DataSize)]
 pub struct Keypair {
-    secret_key: Arc<SecretKey>,
-    public_key: PublicKey,
+    #[data_size(skip)]
+    signer: Arc<dyn Signer>,
+    era_id: EraId,
+    instance_id: Digest,
 }
 
 impl Keypair {
-    pub(crate) fn new(secret_key: Arc<SecretKey>, public_key: PublicKey) -> Self {
+    pub(crate) fn new(signer: Arc<dyn Signer>, era_id: EraId, instance_id: Digest) -> Self {
         Self {
-            secret_key,
-            public_key,
+            signer,
+            era_id,
+            instance_id,
+        }
+    }
+
+    fn sign_request(&self, request: SigningRequest) -> Option<Signature> {
+        match self.signer.sign(&request) {
+            Ok(signature) => Some(signature),
+            Err(error) => {
+                error!(%error, ?request, "failed to sign consensus message");
+                None
+            }
         }
     }
 }
 
 impl From<Arc<SecretKey>> for Keypair {
     fn from(secret_key: Arc<SecretKey>) -> Self {
-        let public_key: PublicKey = secret_key.as_ref().into();
-        Self::new(secret_key, public_key)
+        Self::new(
+            Arc::new(LocalSigner::new(secret_key)),
+            EraId::default(),
+            Digest::default(),
+        )
     }
 }
 
@@ -38,8 +59,56 @@ impl ValidatorSecret for Keypair {
     type Hash = Digest;
     type Signature = Signature;
 
-    fn sign(&self, hash: &Digest) -> Signature {
-        crypto::sign(hash, self.secret_key.as_ref(), &self.public_key)
+    fn sign(&self, hash: &Digest) -> Option<Signature> {
+        self.sign_request(signing_request(
+            self.era_id,
+            self.instance_id,
+            SignatureRequest::Message { hash: *hash },
+        ))
+    }
+
+    fn sign_unit(
+        &self,
+        hash: &Digest,
+        seq_number: u64,
+        panorama_hash: &Digest,
+    ) -> Option<Signature> {
+        self.sign_request(signing_request(
+            self.era_id,
+            self.instance_id,
+            SignatureRequest::Unit {
+                hash: *hash,
+                seq_number,
+                panorama_hash: *panorama_hash,
+            },
+        ))
+    }
+
+    fn signs_asynchronously(&self) -> bool {
+        self.signer.may_block()
+    }
+}
+
+/// Returns the request to the signer for a consensus message in the given era and protocol
+/// instance.
+pub(crate) fn signing_request(
+    era_id: EraId,
+    instance_id: Digest,
+    request: SignatureRequest<ClContext>,
+) -> SigningRequest {
+    match request {
+        SignatureRequest::Unit {
+            hash,
+            seq_number,
+            panorama_hash,
+        } => SigningRequest::Unit {
+            era_id,
+            instance_id,
+            seq_number,
+            hash,
+            panorama_hash,
+        },
+        SignatureRequest::Message { hash } => SigningRequest::ConsensusMessage { era_id, hash },
     }
 }
 
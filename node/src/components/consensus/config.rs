@@ -3,14 +3,20 @@ use std::{path::Path, sync::Arc};
 use datasize::DataSize;
 use serde::Deserialize;
 
-use casper_types::{PublicKey, SecretKey};
+use casper_types::SecretKey;
 
 use crate::{
     components::consensus::{
-        era_supervisor::PAST_OPEN_ERAS, protocols::highway::config::Config as HighwayConfig, EraId,
+        era_supervisor::PAST_OPEN_ERAS,
+        protocols::highway::config::Config as HighwayConfig,
+        signer::{
+            LoadSignerError, LocalSigner, RemoteSigner, RemoteSignerConfig, Signer,
+            SlashingProtectedSigner, SlashingProtection,
+        },
+        EraId,
     },
     types::Chainspec,
-    utils::{External, LoadError, Loadable},
+    utils::External,
 };
 
 /// Consensus configuration.
@@ -18,8 +24,12 @@ use crate::{
 // Disallow unknown fields to ensure config files and command-line overrides contain valid keys.
 #[serde(deny_unknown_fields)]
 pub(crate) struct Config {
-    /// Path to secret key file.
+    /// Path to secret key file. Not needed if a remote signer is configured.
+    #[serde(default)]
     pub(crate) secret_key_path: External,
+    /// Remote signing daemon holding the secret key, used instead of `secret_key_path` if present.
+    #[serde(default)]
+    pub(crate) remote_signer: Option<RemoteSignerConfig>,
     /// Highway-specific node configuration.
     pub(crate) highway: HighwayConfig,
 }
@@ -28,21 +38,49 @@ impl Default for Config {
     fn default() -> Self {
         Config {
             secret_key_path: External::Missing,
+            remote_signer: None,
             highway: HighwayConfig::default(),
         }
     }
 }
 
 impl Config {
-    /// Loads the secret key from the configuration file and derives the public key.
-    #[allow(clippy::type_complexity)]
-    pub(crate) fn load_keys<P: AsRef<Path>>(
+    /// Returns the signer for consensus messages and finality signatures: either connects to the
+    /// remote signer, if configured, or loads the secret key from the configuration file.
+    ///
+    /// The signer refuses to sign anything conflicting with the slashing protection database in
+    /// the given storage directory.
+    pub(crate) fn load_signer<P: AsRef<Path>>(
         &self,
         root: P,
-    ) -> Result<(Arc<SecretKey>, PublicKey), LoadError<<Arc<SecretKey> as Loadable>::Error>> {
-        let secret_signing_key: Arc<SecretKey> = self.secret_key_path.clone().load(root)?;
-        let public_key: PublicKey = PublicKey::from(secret_signing_key.as_ref());
-        Ok((secret_signing_key, public_key))
+        storage_dir: &Path,
+    ) -> Result<Arc<dyn Signer>, LoadSignerError> {
+        let signer = self.load_handshake_signer(root)?;
+        let slashing_protection = SlashingProtection::open(storage_dir)?;
+        Ok(Arc::new(SlashingProtectedSigner::new(
+            signer,
+            slashing_protection,
+        )?))
+    }
+
+    /// Returns the signer for network handshakes: either connects to the remote signer, if
+    /// configured, or loads the secret key from the configuration file.
+    ///
+    /// Handshakes are not subject to slashing protection, so this signer must not be used for
+    /// consensus messages or finality signatures.
+    pub(crate) fn load_handshake_signer<P: AsRef<Path>>(
+        &self,
+        root: P,
+    ) -> Result<Arc<dyn Signer>, LoadSignerError> {
+        match &self.remote_signer {
+            Some(remote_signer_config) => {
+                Ok(Arc::new(RemoteSigner::connect(remote_signer_config, root)?))
+            }
+            None => {
+                let secret_key: Arc<SecretKey> = self.secret_key_path.clone().load(root)?;
+                Ok(Arc::new(LocalSigner::new(secret_key)))
+            }
+        }
     }
 }
 
@@ -95,6 +95,31 @@ where
     }
 }
 
+/// A request to sign one of our own consensus messages.
+#[derive(Clone, Eq, PartialEq, Debug, Hash)]
+pub(crate) enum SignatureRequest<C>
+where
+    C: Context,
+{
+    /// The hash of a new unit with the given sequence number and panorama hash.
+    Unit {
+        hash: C::Hash,
+        seq_number: u64,
+        panorama_hash: C::Hash,
+    },
+    /// The hash of any other message, e.g. a ping or an endorsement.
+    Message { hash: C::Hash },
+}
+
+impl<C: Context> SignatureRequest<C> {
+    /// The hash to be signed.
+    pub(crate) fn hash(&self) -> &C::Hash {
+        match self {
+            SignatureRequest::Unit { hash, .. } | SignatureRequest::Message { hash } => hash,
+        }
+    }
+}
+
 /// Equivocation and reward information to be included in the terminal finalized block.
 #[derive(Clone, DataSize, Debug, PartialOrd, Ord, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(bound(
@@ -201,6 +226,9 @@ pub(crate) enum ProtocolOutcome<C: Context> {
     QueueAction(ActionId),
     /// Request deploys for a new block, providing the necessary context.
     CreateNewBlock(BlockContext<C>),
+    /// Request a signature with the validator's secret, which may block and therefore must not be
+    /// done while handling an event, and call `ConsensusProtocol::handle_signature` with it.
+    RequestSignature(SignatureRequest<C>),
     /// A block was finalized.
     FinalizedBlock(FinalizedBlock<C>),
     /// Request validation of the consensus value, contained in a message received from the given
@@ -258,6 +286,15 @@ pub(crate) trait ConsensusProtocol<C: Context>: Send {
     /// Proposes a new value for consensus.
     fn propose(&mut self, proposed_block: ProposedBlock<C>, now: Timestamp) -> ProtocolOutcomes<C>;
 
+    /// Provides the signature of the given hash requested via `ProtocolOutcome::RequestSignature`,
+    /// or `None` if signing failed.
+    fn handle_signature(
+        &mut self,
+        hash: C::Hash,
+        signature: Option<C::Signature>,
+        now: Timestamp,
+    ) -> ProtocolOutcomes<C>;
+
     /// Marks the `value` as valid or invalid, based on validation requested via
     /// `ProtocolOutcome::ValidateConsensusvalue`.
     fn resolve_validity(
@@ -27,17 +27,18 @@ use rand::Rng;
 use tracing::{debug, error, info, trace, warn};
 
 use casper_hashing::Digest;
-use casper_types::{AsymmetricType, EraId, PublicKey, SecretKey, TimeDiff, Timestamp, U512};
+use casper_types::{AsymmetricType, EraId, PublicKey, Signature, TimeDiff, Timestamp, U512};
 
 use crate::{
     components::{
         consensus::{
-            cl_context::{ClContext, Keypair},
+            cl_context::{self, ClContext, Keypair},
             consensus_protocol::{
                 ConsensusProtocol, EraReport, FinalizedBlock as CpFinalizedBlock, ProposedBlock,
                 ProtocolOutcome,
             },
             metrics::Metrics,
+            signer::{self, Signer},
             validator_change::{ValidatorChange, ValidatorChanges},
             ActionId, ChainspecConsensusExt, Config, ConsensusMessage, Event, NewBlockPayload,
             ReactorEventT, ResolveValidity, TimerId,
@@ -45,14 +46,14 @@ use crate::{
         storage::Storage,
     },
     effect::{
-        announcements::ControlAnnouncement,
+        announcements::{ControlAnnouncement, Offense},
         requests::{BlockValidationRequest, ContractRuntimeRequest, StorageRequest},
         EffectBuilder, EffectExt, Effects, Responder,
     },
     fatal,
     types::{
         ActivationPoint, BlockHash, BlockHeader, Chainspec, Deploy, DeployHash,
-        DeployOrTransferHash, FinalitySignature, FinalizedApprovals, FinalizedBlock, NodeId,
+        DeployOrTransferHash, FinalizedApprovals, FinalizedBlock, NodeId,
     },
     NodeRng,
 };
@@ -104,7 +105,8 @@ pub struct EraSupervisor {
     /// Since eras at or before the most recent activation point are never instantiated, shortly
     /// after that there can temporarily be fewer than three entries in the map.
     open_eras: HashMap<EraId, Era>,
-    secret_signing_key: Arc<SecretKey>,
+    #[data_size(skip)]
+    signer: Arc<dyn Signer>,
     public_signing_key: PublicKey,
     current_era: EraId,
     chainspec: Arc<Chainspec>,
@@ -145,8 +147,7 @@ impl EraSupervisor {
     pub(crate) fn new<REv: ReactorEventT>(
         current_era: EraId,
         storage_dir: &Path,
-        secret_signing_key: Arc<SecretKey>,
-        public_signing_key: PublicKey,
+        signer: Arc<dyn Signer>,
         config: Config,
         effect_builder: EffectBuilder<REv>,
         chainspec: Arc<Chainspec>,
@@ -166,6 +167,7 @@ impl EraSupervisor {
             );
         }
         let unit_files_folder = storage_dir.join("unit_files");
+        let public_signing_key = signer.public_key().clone();
         info!(our_id = %public_signing_key, "EraSupervisor pubkey",);
         let metrics =
             Metrics::new(registry).expect("failed to set up and register consensus metrics");
@@ -174,7 +176,7 @@ impl EraSupervisor {
 
         let mut era_supervisor = Self {
             open_eras: Default::default(),
-            secret_signing_key,
+            signer,
             public_signing_key,
             current_era,
             chainspec,
@@ -333,7 +335,10 @@ impl EraSupervisor {
     ) -> Effects<Event> {
         match self.create_new_era(switch_blocks) {
             Ok((era_id, outcomes)) => {
-                self.handle_consensus_outcomes(effect_builder, rng, era_id, outcomes)
+                let mut effects =
+                    self.handle_consensus_outcomes(effect_builder, rng, era_id, outcomes);
+                effects.extend(self.prune_signing_records());
+                effects
             }
             Err(err) => fatal!(
                 effect_builder,
@@ -490,7 +495,7 @@ impl EraSupervisor {
                 info!(era = era_id.value(), %our_id, "not voting; not a validator");
             } else {
                 info!(era = era_id.value(), %our_id, "start voting");
-                let secret = Keypair::new(self.secret_signing_key.clone(), our_id.clone());
+                let secret = Keypair::new(Arc::clone(&self.signer), era_id, instance_id);
                 let unit_hash_file = self.unit_file(&instance_id);
                 outcomes.extend(self.era_mut(era_id).consensus.activate_validator(
                     our_id,
@@ -542,6 +547,29 @@ impl EraSupervisor {
         Ok((era_id, outcomes))
     }
 
+    /// Deletes the slashing protection records of eras which are no longer open.
+    fn prune_signing_records(&self) -> Effects<Event> {
+        let earliest_era_id = match self.open_eras.keys().min() {
+            Some(era_id) => *era_id,
+            None => return Effects::new(),
+        };
+        let signer = Arc::clone(&self.signer);
+        async move {
+            let result = signer::spawn_signing(signer, move |signer| {
+                signer.prune_eras_before(earliest_era_id)
+            })
+            .await;
+            if let Err(error) = result {
+                warn!(
+                    %error,
+                    era = earliest_era_id.value(),
+                    "failed to prune slashing protection records"
+                );
+            }
+        }
+        .ignore()
+    }
+
     /// Returns the path to the era's unit file.
     fn unit_file(&self, instance_id: &Digest) -> PathBuf {
         self.unit_files_folder.join(format!(
@@ -663,24 +691,46 @@ impl EraSupervisor {
         })
     }
 
+    pub(super) fn handle_signature<REv: ReactorEventT>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        rng: &mut NodeRng,
+        era_id: EraId,
+        hash: Digest,
+        signature: Option<Signature>,
+    ) -> Effects<Event> {
+        self.delegate_to_era(effect_builder, rng, era_id, move |consensus, _| {
+            consensus.handle_signature(hash, signature, Timestamp::now())
+        })
+    }
+
     pub(super) fn handle_block_added<REv: ReactorEventT>(
         &mut self,
         effect_builder: EffectBuilder<REv>,
         block_header: BlockHeader,
     ) -> Effects<Event> {
         let our_pk = self.public_signing_key.clone();
-        let our_sk = self.secret_signing_key.clone();
         let era_id = block_header.era_id();
         self.executed_block(&block_header);
         let mut effects = if self.is_validator_in(&our_pk, era_id) {
-            effect_builder
-                .announce_created_finality_signature(FinalitySignature::new(
-                    block_header.hash(),
-                    era_id,
-                    &our_sk,
-                    our_pk,
-                ))
-                .ignore()
+            let signer = Arc::clone(&self.signer);
+            let block_hash = block_header.hash();
+            let block_height = block_header.height();
+            async move {
+                match signer::spawn_signing(signer, move |signer| {
+                    signer.sign_finality_signature(block_hash, block_height, era_id)
+                })
+                .await
+                {
+                    Ok(finality_signature) => {
+                        effect_builder
+                            .announce_created_finality_signature(finality_signature)
+                            .await
+                    }
+                    Err(error) => error!(%error, %block_hash, "failed to sign block"),
+                }
+            }
+            .ignore()
         } else {
             Effects::new()
         };
@@ -869,6 +919,24 @@ impl EraSupervisor {
             ProtocolOutcome::QueueAction(action_id) => effect_builder
                 .immediately()
                 .event(move |()| Event::Action { era_id, action_id }),
+            ProtocolOutcome::RequestSignature(request) => {
+                let hash = *request.hash();
+                let instance_id = *self.era(era_id).consensus.instance_id();
+                let request = cl_context::signing_request(era_id, instance_id, request);
+                let signer = Arc::clone(&self.signer);
+                async move {
+                    let result =
+                        signer::spawn_signing(signer, move |signer| signer.sign(&request)).await;
+                    result
+                        .map_err(|error| error!(%error, %hash, "failed to sign consensus message"))
+                        .ok()
+                }
+                .event(move |signature| Event::Signed {
+                    era_id,
+                    hash,
+                    signature,
+                })
+            }
             ProtocolOutcome::CreateNewBlock(block_context) => {
                 let accusations = self
                     .iter_past(era_id, PAST_EVIDENCE_ERAS)
@@ -1084,7 +1152,7 @@ impl EraSupervisor {
         sender: NodeId,
     ) -> Effects<Event> {
         effect_builder
-            .announce_disconnect_from_peer(sender)
+            .announce_offense(sender, Offense::BadConsensusMessage)
             .ignore()
     }
 
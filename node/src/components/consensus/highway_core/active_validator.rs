@@ -1,4 +1,5 @@
 use std::{
+    collections::BTreeMap,
     fmt::{self, Debug},
     fs::{self, File},
     io::{self, Read, Write},
@@ -14,14 +15,14 @@ use casper_types::{TimeDiff, Timestamp};
 use super::{
     endorsement::{Endorsement, SignedEndorsement},
     evidence::Evidence,
-    highway::{Ping, ValidVertex, Vertex, WireUnit},
+    highway::{HashedWireUnit, Ping, ValidVertex, Vertex, WireUnit},
     state::{self, Panorama, State, Unit, Weight},
     validators::ValidatorIndex,
     ENABLE_ENDORSEMENTS,
 };
 
 use crate::components::consensus::{
-    consensus_protocol::BlockContext,
+    consensus_protocol::{BlockContext, SignatureRequest},
     highway_core::{highway::SignedWireUnit, state::Fault},
     traits::{Context, ValidatorSecret},
 };
@@ -36,12 +37,29 @@ pub(crate) enum Effect<C: Context> {
     /// `propose` needs to be called with a value for a new block with the specified block context
     /// and parent value.
     RequestNewBlock(BlockContext<C>),
+    /// `on_signature` needs to be called with the signature for the request, or with `None` if
+    /// signing failed.
+    RequestSignature(SignatureRequest<C>),
     /// This validator is faulty.
     ///
     /// When this is returned, the validator automatically deactivates.
     WeAreFaulty(Fault<C>),
 }
 
+/// One of our own vertices, waiting for its signature.
+#[derive(Clone, DataSize, Debug)]
+enum UnsignedVertex<C>
+where
+    C: Context,
+{
+    Unit(HashedWireUnit<C>),
+    Ping {
+        timestamp: Timestamp,
+        instance_id: C::InstanceId,
+    },
+    Endorsement(Endorsement<C>),
+}
+
 /// A validator that actively participates in consensus by creating new vertices.
 ///
 /// It implements the Highway schedule. The protocol proceeds in rounds, and in each round one
@@ -80,6 +98,8 @@ where
     target_ftt: Weight,
     /// If this flag is set we don't create new units and just send pings instead.
     paused: bool,
+    /// Our vertices waiting for their signatures, if the secret signs asynchronously.
+    unsigned_vertices: BTreeMap<C::Hash, UnsignedVertex<C>>,
 }
 
 impl<C: Context> Debug for ActiveValidator<C> {
@@ -126,9 +146,10 @@ impl<C: Context> ActiveValidator<C> {
             own_last_unit,
             target_ftt,
             paused: false,
+            unsigned_vertices: BTreeMap::new(),
         };
         let mut effects = av.schedule_timer(start_time, state);
-        effects.push(av.send_ping(current_time, instance_id));
+        effects.extend(av.send_ping(current_time, instance_id));
         (av, effects)
     }
 
@@ -197,7 +218,7 @@ impl<C: Context> ActiveValidator<C> {
                 return effects;
             } else if timestamp == r_id + self.witness_offset(r_len) {
                 let panorama = self.panorama_at(state, timestamp);
-                if let Some(witness_unit) =
+                if let Some(witness_effect) =
                     self.new_unit(panorama, timestamp, None, state, instance_id)
                 {
                     if self
@@ -206,7 +227,7 @@ impl<C: Context> ActiveValidator<C> {
                     {
                         info!(round_id = %r_id, "sending witness in round with no proposal");
                     }
-                    effects.push(Effect::NewVertex(ValidVertex(Vertex::Unit(witness_unit))));
+                    effects.push(witness_effect);
                     return effects;
                 }
             }
@@ -216,15 +237,31 @@ impl<C: Context> ActiveValidator<C> {
         let one_max_round_ago = timestamp.saturating_sub(state.params().max_round_length());
         if !state.has_ping(self.vidx, one_max_round_ago + 1.into()) {
             warn!(%timestamp, "too many validators offline, sending ping");
-            effects.push(self.send_ping(timestamp, instance_id));
+            effects.extend(self.send_ping(timestamp, instance_id));
         }
         effects
     }
 
-    /// Creates a Ping vertex.
-    pub(crate) fn send_ping(&self, timestamp: Timestamp, instance_id: C::InstanceId) -> Effect<C> {
-        let ping = Ping::new(self.vidx, timestamp, instance_id, &self.secret);
-        Effect::NewVertex(ValidVertex(Vertex::Ping(ping)))
+    /// Creates a Ping vertex, or returns `None` if it couldn't be signed.
+    pub(crate) fn send_ping(
+        &mut self,
+        timestamp: Timestamp,
+        instance_id: C::InstanceId,
+    ) -> Option<Effect<C>> {
+        if self.secret.signs_asynchronously() {
+            return Some(self.request_signature(UnsignedVertex::Ping {
+                timestamp,
+                instance_id,
+            }));
+        }
+        let ping = match Ping::new(self.vidx, timestamp, instance_id, &self.secret) {
+            Some(ping) => ping,
+            None => {
+                error!(%timestamp, "failed to sign ping");
+                return None;
+            }
+        };
+        Some(Effect::NewVertex(ValidVertex(Vertex::Ping(ping))))
     }
 
     /// Returns whether enough validators are online to finalize values with the target fault
@@ -258,17 +295,11 @@ impl<C: Context> ActiveValidator<C> {
         if self.should_send_confirmation(uhash, now, state) {
             let panorama = state.confirmation_panorama(self.vidx, uhash);
             if panorama.has_correct() {
-                if let Some(confirmation_unit) =
-                    self.new_unit(panorama, now, None, state, instance_id)
-                {
-                    let vv = ValidVertex(Vertex::Unit(confirmation_unit));
-                    effects.push(Effect::NewVertex(vv));
-                }
+                effects.extend(self.new_unit(panorama, now, None, state, instance_id));
             }
         };
         if self.should_endorse(uhash, state) {
-            let endorsement = self.endorse(uhash);
-            effects.extend(vec![Effect::NewVertex(ValidVertex(endorsement))]);
+            effects.extend(self.endorse(uhash));
         }
         effects
     }
@@ -291,8 +322,7 @@ impl<C: Context> ActiveValidator<C> {
                 let unit = state.unit(v);
                 unit.new_hash_obs(state, vidx)
             })
-            .map(|v| self.endorse(v))
-            .map(|endorsement| Effect::NewVertex(ValidVertex(endorsement)))
+            .filter_map(|v| self.endorse(v))
             .collect()
     }
 
@@ -314,9 +344,7 @@ impl<C: Context> ActiveValidator<C> {
         let maybe_parent_hash = state.fork_choice(&panorama);
         // If the parent is a terminal block, just create a unit without a new block.
         if maybe_parent_hash.map_or(false, |hash| state.is_terminal_block(hash)) {
-            return self
-                .new_unit(panorama, timestamp, None, state, instance_id)
-                .map(|proposal_unit| Effect::NewVertex(ValidVertex(Vertex::Unit(proposal_unit))));
+            return self.new_unit(panorama, timestamp, None, state, instance_id);
         }
         // Otherwise we need to request a new consensus value to propose.
         let ancestor_values = match maybe_parent_hash {
@@ -359,7 +387,6 @@ impl<C: Context> ActiveValidator<C> {
             return vec![];
         }
         self.new_unit(panorama, timestamp, Some(value), state, instance_id)
-            .map(|proposal_unit| Effect::NewVertex(ValidVertex(Vertex::Unit(proposal_unit))))
             .into_iter()
             .collect()
     }
@@ -408,7 +435,8 @@ impl<C: Context> ActiveValidator<C> {
         true
     }
 
-    /// Returns a new unit with the given data, and the correct sequence number.
+    /// Returns the effect for a new unit with the given data, and the correct sequence number:
+    /// either the signed unit, or a request to sign it.
     ///
     /// Returns `None` if it's not possible to create a valid unit with the given panorama.
     fn new_unit(
@@ -418,7 +446,7 @@ impl<C: Context> ActiveValidator<C> {
         value: Option<C::ConsensusValue>,
         state: &State<C>,
         instance_id: C::InstanceId,
-    ) -> Option<SignedWireUnit<C>> {
+    ) -> Option<Effect<C>> {
         if value.is_none() && !panorama.has_correct() {
             return None; // Wait for the first proposal before creating a unit without a value.
         }
@@ -426,6 +454,16 @@ impl<C: Context> ActiveValidator<C> {
             info!(?self.own_last_unit, "not voting - last own unit unknown");
             return None;
         }
+        // Until our last unit is signed and added to the state, a new one would have the same
+        // sequence number.
+        if self
+            .unsigned_vertices
+            .values()
+            .any(|unsigned| matches!(unsigned, UnsignedVertex::Unit(_)))
+        {
+            info!("not voting - last own unit not signed yet");
+            return None;
+        }
         if let Some((prop_context, _)) = self.next_proposal.take() {
             warn!(?prop_context, "canceling proposal due to unit");
         }
@@ -458,14 +496,105 @@ impl<C: Context> ActiveValidator<C> {
             endorsed,
         }
         .into_hashed();
-        let swunit = SignedWireUnit::new(hwunit, &self.secret);
+        if self.secret.signs_asynchronously() {
+            return Some(self.request_signature(UnsignedVertex::Unit(hwunit)));
+        }
+        let swunit = match SignedWireUnit::new(hwunit, &self.secret) {
+            Some(swunit) => swunit,
+            None => {
+                error!(seq_number, "failed to sign unit; canceling unit creation");
+                return None;
+            }
+        };
+        Some(self.new_own_unit(swunit))
+    }
+
+    /// Records our new signed unit in the unit file, and returns the effect to add it.
+    fn new_own_unit(&self, swunit: SignedWireUnit<C>) -> Effect<C> {
         write_last_unit(&self.unit_file, swunit.clone()).unwrap_or_else(|err| {
             panic!(
                 "should successfully write unit's hash to {:?}, got {:?}",
                 self.unit_file, err
             )
         });
-        Some(swunit)
+        Effect::NewVertex(ValidVertex(Vertex::Unit(swunit)))
+    }
+
+    /// Returns a request to sign the vertex, and keeps the vertex until the signature is provided.
+    fn request_signature(&mut self, unsigned: UnsignedVertex<C>) -> Effect<C> {
+        let request = match &unsigned {
+            UnsignedVertex::Unit(hwunit) => SignatureRequest::Unit {
+                hash: hwunit.hash(),
+                seq_number: hwunit.wire_unit().seq_number,
+                panorama_hash: hwunit.wire_unit().panorama.hash(),
+            },
+            UnsignedVertex::Ping {
+                timestamp,
+                instance_id,
+            } => SignatureRequest::Message {
+                hash: Ping::<C>::hash(self.vidx, *timestamp, *instance_id),
+            },
+            UnsignedVertex::Endorsement(endorsement) => SignatureRequest::Message {
+                hash: endorsement.hash(),
+            },
+        };
+        self.unsigned_vertices.insert(*request.hash(), unsigned);
+        Effect::RequestSignature(request)
+    }
+
+    /// Returns actions a validator needs to take upon receiving the signature for one of its
+    /// vertices, or `None` if signing it failed.
+    pub(crate) fn on_signature(
+        &mut self,
+        hash: &C::Hash,
+        signature: Option<C::Signature>,
+        state: &State<C>,
+    ) -> Vec<Effect<C>> {
+        let unsigned = match self.unsigned_vertices.remove(hash) {
+            Some(unsigned) => unsigned,
+            None => {
+                warn!(?hash, "received signature for unknown vertex");
+                return vec![];
+            }
+        };
+        let signature = match signature {
+            Some(signature) => signature,
+            None => {
+                error!(?unsigned, "failed to sign vertex; canceling its creation");
+                return vec![];
+            }
+        };
+        let vertex = match unsigned {
+            UnsignedVertex::Unit(hashed_wire_unit) => {
+                if self.is_faulty(state)
+                    || hashed_wire_unit.wire_unit().panorama[self.vidx]
+                        != state.panorama()[self.vidx]
+                {
+                    error!(
+                        ?hashed_wire_unit,
+                        "signed unit would be equivocation; canceling unit creation"
+                    );
+                    return vec![];
+                }
+                return vec![self.new_own_unit(SignedWireUnit {
+                    hashed_wire_unit,
+                    signature,
+                })];
+            }
+            UnsignedVertex::Ping {
+                timestamp,
+                instance_id,
+            } => Vertex::Ping(Ping::with_signature(
+                self.vidx,
+                timestamp,
+                instance_id,
+                signature,
+            )),
+            UnsignedVertex::Endorsement(endorsement) => {
+                Vertex::Endorsements(SignedEndorsement::new(endorsement, signature).into())
+            }
+        };
+        vec![Effect::NewVertex(ValidVertex(vertex))]
     }
 
     /// Returns a `ScheduleTimer` effect for the next time we need to be called.
@@ -559,11 +688,22 @@ impl<C: Context> ActiveValidator<C> {
                 .any(|(vidx, _)| state.is_faulty(vidx) && unit.new_hash_obs(state, vidx))
     }
 
-    /// Creates endorsement of the `vhash`.
-    fn endorse(&self, vhash: &C::Hash) -> Vertex<C> {
+    /// Creates endorsement of the `vhash`, or returns `None` if it couldn't be signed.
+    fn endorse(&mut self, vhash: &C::Hash) -> Option<Effect<C>> {
         let endorsement = Endorsement::new(*vhash, self.vidx);
-        let signature = self.secret.sign(&endorsement.hash());
-        Vertex::Endorsements(SignedEndorsement::new(endorsement, signature).into())
+        if self.secret.signs_asynchronously() {
+            return Some(self.request_signature(UnsignedVertex::Endorsement(endorsement)));
+        }
+        let signature = match self.secret.sign(&endorsement.hash()) {
+            Some(signature) => signature,
+            None => {
+                error!(?vhash, "failed to sign endorsement");
+                return None;
+            }
+        };
+        Some(Effect::NewVertex(ValidVertex(Vertex::Endorsements(
+            SignedEndorsement::new(endorsement, signature).into(),
+        ))))
     }
 
     /// Returns a panorama that is valid to use in our own unit at the given timestamp.
@@ -988,7 +1128,8 @@ mod tests {
             TEST_INSTANCE_ID,
         );
 
-        let ping = Vertex::Ping(Ping::new(ALICE, 500.into(), TEST_INSTANCE_ID, &ALICE_SEC));
+        let ping =
+            Vertex::Ping(Ping::new(ALICE, 500.into(), TEST_INSTANCE_ID, &ALICE_SEC).unwrap());
 
         // The ping is suspicious if it is newer than the latest ping (or unit) that has been added
         // to the state.
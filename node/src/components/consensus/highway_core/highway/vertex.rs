@@ -146,15 +146,19 @@ where
 }
 
 impl<C: Context> SignedWireUnit<C> {
+    /// Signs the unit, or returns `None` if the signer failed or refused to sign it.
     pub(crate) fn new(
         hashed_wire_unit: HashedWireUnit<C>,
         secret_key: &C::ValidatorSecret,
-    ) -> Self {
-        let signature = secret_key.sign(&hashed_wire_unit.hash);
-        SignedWireUnit {
+    ) -> Option<Self> {
+        let wire_unit = hashed_wire_unit.wire_unit();
+        let panorama_hash = wire_unit.panorama.hash();
+        let signature =
+            secret_key.sign_unit(&hashed_wire_unit.hash, wire_unit.seq_number, &panorama_hash)?;
+        Some(SignedWireUnit {
             hashed_wire_unit,
             signature,
-        }
+        })
     }
 
     pub(crate) fn wire_unit(&self) -> &WireUnit<C> {
@@ -335,14 +339,29 @@ where
 }
 
 impl<C: Context> Ping<C> {
-    /// Creates a new signed ping.
+    /// Creates a new signed ping, or returns `None` if the signer failed or refused to sign it.
     pub(crate) fn new(
         creator: ValidatorIndex,
         timestamp: Timestamp,
         instance_id: C::InstanceId,
         sk: &C::ValidatorSecret,
+    ) -> Option<Self> {
+        let signature = sk.sign(&Self::hash(creator, timestamp, instance_id))?;
+        Some(Self::with_signature(
+            creator,
+            timestamp,
+            instance_id,
+            signature,
+        ))
+    }
+
+    /// Creates a new ping with the given signature of its hash.
+    pub(crate) fn with_signature(
+        creator: ValidatorIndex,
+        timestamp: Timestamp,
+        instance_id: C::InstanceId,
+        signature: C::Signature,
     ) -> Self {
-        let signature = sk.sign(&Self::hash(creator, timestamp, instance_id));
         Ping {
             creator,
             timestamp,
@@ -385,7 +404,11 @@ impl<C: Context> Ping<C> {
     }
 
     /// Computes the hash of a ping, i.e. of the creator and timestamp.
-    fn hash(creator: ValidatorIndex, timestamp: Timestamp, instance_id: C::InstanceId) -> C::Hash {
+    pub(crate) fn hash(
+        creator: ValidatorIndex,
+        timestamp: Timestamp,
+        instance_id: C::InstanceId,
+    ) -> C::Hash {
         let bytes = bincode::serialize(&(creator, timestamp, instance_id)).expect("serialize Ping");
         <C as Context>::hash(&bytes)
     }
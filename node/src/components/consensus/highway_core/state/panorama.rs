@@ -106,6 +106,11 @@ impl<C: Context> Panorama<C> {
         Panorama::from(vec![Observation::None; num_validators])
     }
 
+    /// Returns the hash of the panorama, identifying it in the slashing protection record.
+    pub(crate) fn hash(&self) -> C::Hash {
+        <C as Context>::hash(&bincode::serialize(self).expect("serialize Panorama"))
+    }
+
     /// Returns `true` if there is at least one correct observation.
     pub(crate) fn has_correct(&self) -> bool {
         self.iter().any(Observation::is_correct)
@@ -264,6 +264,9 @@ impl<C: Context + 'static> HighwayProtocol<C> {
             AvEffect::RequestNewBlock(block_context) => {
                 vec![ProtocolOutcome::CreateNewBlock(block_context)]
             }
+            AvEffect::RequestSignature(request) => {
+                vec![ProtocolOutcome::RequestSignature(request)]
+            }
             AvEffect::WeAreFaulty(fault) => {
                 error!("this validator is faulty: {:?}", fault);
                 vec![ProtocolOutcome::WeAreFaulty]
@@ -967,6 +970,19 @@ where
         self.process_av_effects(effects, now)
     }
 
+    fn handle_signature(
+        &mut self,
+        hash: C::Hash,
+        signature: Option<C::Signature>,
+        now: Timestamp,
+    ) -> ProtocolOutcomes<C> {
+        let effects = self.highway.on_signature(&hash, signature, now);
+        let mut outcomes = self.process_av_effects(effects, now);
+        // Our new unit can finalize a block.
+        outcomes.extend(self.detect_finality());
+        outcomes
+    }
+
     fn resolve_validity(
         &mut self,
         proposed_block: ProposedBlock<C>,
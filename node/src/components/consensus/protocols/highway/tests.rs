@@ -1,12 +1,14 @@
 use std::{collections::BTreeSet, sync::Arc};
 
-use casper_types::{testing::TestRng, PublicKey, TimeDiff, Timestamp, U512};
+use tempfile::tempdir;
+
+use casper_types::{testing::TestRng, EraId, PublicKey, TimeDiff, Timestamp, U512};
 
 use crate::{
     components::consensus::{
-        cl_context::{ClContext, Keypair},
+        cl_context::{self, ClContext, Keypair},
         config::Config,
-        consensus_protocol::{ConsensusProtocol, ProtocolOutcome},
+        consensus_protocol::{ConsensusProtocol, ProposedBlock, ProtocolOutcome, SignatureRequest},
         highway_core::{
             highway::{SignedWireUnit, Vertex, WireUnit},
             highway_testing,
@@ -16,8 +18,9 @@ use crate::{
         },
         protocols::highway::{
             config::Config as HighwayConfig, HighwayMessage, ACTION_ID_VERTEX,
-            TIMER_ID_STANDSTILL_ALERT,
+            TIMER_ID_ACTIVE_VALIDATOR, TIMER_ID_STANDSTILL_ALERT,
         },
+        signer::{LocalSigner, Signer, SlashingProtectedSigner, SlashingProtection},
         tests::utils::{
             new_test_chainspec, ALICE_NODE_ID, ALICE_PUBLIC_KEY, ALICE_SECRET_KEY, BOB_PUBLIC_KEY,
         },
@@ -68,6 +71,7 @@ where
     let chainspec = new_test_chainspec(weights.clone());
     let config = Config {
         secret_key_path: Default::default(),
+        remote_signer: None,
         highway: HighwayConfig {
             pending_vertex_timeout: "1min".parse().unwrap(),
             standstill_timeout: Some(STANDSTILL_TIMEOUT.parse().unwrap()),
@@ -154,7 +158,7 @@ fn send_a_wire_unit_with_too_small_a_round_exp() {
     };
     let alice_keypair: Keypair = Keypair::from(Arc::clone(&*ALICE_SECRET_KEY));
     let highway_message: HighwayMessage<ClContext> = HighwayMessage::NewVertex(Vertex::Unit(
-        SignedWireUnit::new(wunit.into_hashed(), &alice_keypair),
+        SignedWireUnit::new(wunit.into_hashed(), &alice_keypair).unwrap(),
     ));
     let mut highway_protocol = new_test_highway_protocol(validators, vec![]);
     let sender = *ALICE_NODE_ID;
@@ -208,7 +212,7 @@ fn send_a_valid_wire_unit() {
     };
     let alice_keypair: Keypair = Keypair::from(Arc::clone(&*ALICE_SECRET_KEY));
     let highway_message: HighwayMessage<ClContext> = HighwayMessage::NewVertex(Vertex::Unit(
-        SignedWireUnit::new(wunit.into_hashed(), &alice_keypair),
+        SignedWireUnit::new(wunit.into_hashed(), &alice_keypair).unwrap(),
     ));
 
     let mut highway_protocol = new_test_highway_protocol(validators, vec![]);
@@ -274,7 +278,7 @@ fn detect_doppelganger() {
     };
     let alice_keypair: Keypair = Keypair::from(Arc::clone(&*ALICE_SECRET_KEY));
     let highway_message: HighwayMessage<ClContext> = HighwayMessage::NewVertex(Vertex::Unit(
-        SignedWireUnit::new(wunit.into_hashed(), &alice_keypair),
+        SignedWireUnit::new(wunit.into_hashed(), &alice_keypair).unwrap(),
     ));
     let mut highway_protocol = new_test_highway_protocol(validators, vec![]);
     // Activate ALICE as validator.
@@ -297,3 +301,68 @@ fn detect_doppelganger() {
     }
     panic!("failed to return DoppelgangerDetected effect");
 }
+
+#[test]
+fn sign_vertices_asynchronously_if_signer_may_block() {
+    let validators = vec![(ALICE_PUBLIC_KEY.clone(), 100)];
+    let mut highway_protocol = new_test_highway_protocol(validators, vec![]);
+    let instance_id = ClContext::hash(INSTANCE_ID_DATA);
+    let storage_dir = tempdir().unwrap();
+    let local_signer = Arc::new(LocalSigner::new(Arc::clone(&*ALICE_SECRET_KEY)));
+    let slashing_protection = SlashingProtection::open(storage_dir.path()).unwrap();
+    let signer: Arc<dyn Signer> =
+        Arc::new(SlashingProtectedSigner::new(local_signer, slashing_protection).unwrap());
+    let keypair = Keypair::new(Arc::clone(&signer), EraId::default(), instance_id);
+    let sign = |request: SignatureRequest<ClContext>| {
+        let request = cl_context::signing_request(EraId::default(), instance_id, request);
+        signer.sign(&request).unwrap()
+    };
+    let take_signature_request = |outcomes: &mut Vec<ProtocolOutcome<ClContext>>| {
+        let index = outcomes
+            .iter()
+            .position(|outcome| matches!(outcome, ProtocolOutcome::RequestSignature(_)))
+            .expect("expected a signature request");
+        match outcomes.remove(index) {
+            ProtocolOutcome::RequestSignature(request) => request,
+            _ => unreachable!(),
+        }
+    };
+    let is_gossip = |outcome: &ProtocolOutcome<ClContext>| {
+        matches!(outcome, ProtocolOutcome::CreatedGossipMessage(_))
+    };
+
+    // The initial ping needs to be signed first, and is dropped if signing fails.
+    let now = Timestamp::zero();
+    let mut outcomes =
+        highway_protocol.activate_validator(ALICE_PUBLIC_KEY.clone(), keypair, now, None);
+    let request = take_signature_request(&mut outcomes);
+    assert!(matches!(request, SignatureRequest::Message { .. }));
+    assert!(!outcomes.iter().any(is_gossip));
+    let outcomes = highway_protocol.handle_signature(*request.hash(), None, now);
+    assert!(!outcomes.iter().any(is_gossip));
+
+    // Alice is the only validator, so she proposes a block in the first round.
+    let outcomes = highway_protocol.handle_timer(now, TIMER_ID_ACTIVE_VALIDATOR);
+    let block_context = outcomes
+        .into_iter()
+        .find_map(|outcome| match outcome {
+            ProtocolOutcome::CreateNewBlock(block_context) => Some(block_context),
+            _ => None,
+        })
+        .expect("expected a block request");
+    let value = Arc::new(BlockPayload::new(vec![], vec![], vec![], false));
+    let mut outcomes = highway_protocol.propose(ProposedBlock::new(value, block_context), now);
+    let request = take_signature_request(&mut outcomes);
+    assert!(matches!(
+        request,
+        SignatureRequest::Unit { seq_number: 0, .. }
+    ));
+    assert!(!outcomes.iter().any(is_gossip));
+
+    // Once signed, the unit is sent, and a second signature for it is ignored.
+    let signature = sign(request.clone());
+    let outcomes = highway_protocol.handle_signature(*request.hash(), Some(signature), now);
+    assert!(outcomes.iter().any(is_gossip));
+    let outcomes = highway_protocol.handle_signature(*request.hash(), Some(signature), now);
+    assert!(outcomes.is_empty());
+}
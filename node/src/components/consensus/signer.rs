@@ -0,0 +1,208 @@
+//! Signing of consensus messages and finality signatures with the validator's secret key.
+//!
+//! The secret key is either loaded into the node process and used by a [`LocalSigner`], or held by
+//! a separate signing daemon, which the [`RemoteSigner`] talks to over a Unix domain socket or over
+//! TCP with mutual TLS. Either way, every unit and finality signature is checked against and
+//! recorded in the [`SlashingProtection`] database before it is signed.
+
+mod remote;
+mod slashing_protection;
+
+use std::{io, sync::Arc};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tokio::task::{self, JoinError};
+
+use casper_hashing::Digest;
+use casper_types::{
+    crypto::{self, ErrorExt as CryptoError},
+    EraId, PublicKey, SecretKey, Signature,
+};
+
+use crate::{
+    types::{BlockHash, FinalitySignature},
+    utils::LoadError,
+};
+
+pub(crate) use remote::{RemoteSigner, RemoteSignerConfig};
+pub(crate) use slashing_protection::{
+    SlashingProtectedSigner, SlashingProtection, SlashingProtectionError,
+};
+
+/// A message to be signed with the validator's secret key.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub(crate) enum SigningRequest {
+    /// The hash of a Highway unit. Signing two different units with the same sequence number in
+    /// the same era is an equivocation.
+    Unit {
+        era_id: EraId,
+        instance_id: Digest,
+        seq_number: u64,
+        hash: Digest,
+        panorama_hash: Digest,
+    },
+    /// The hash of any other consensus message, i.e. a ping or an endorsement.
+    ConsensusMessage { era_id: EraId, hash: Digest },
+    /// A finality signature for a block.
+    FinalitySignature {
+        era_id: EraId,
+        block_height: u64,
+        block_hash: BlockHash,
+    },
+    /// The ID of a network connection, proving to the peer that we are a validator.
+    Handshake { connection_id: Digest },
+}
+
+impl SigningRequest {
+    /// Returns the bytes to be signed.
+    pub(crate) fn bytes_to_sign(&self) -> Vec<u8> {
+        match self {
+            SigningRequest::Unit { hash, .. } | SigningRequest::ConsensusMessage { hash, .. } => {
+                hash.value().to_vec()
+            }
+            SigningRequest::Handshake { connection_id } => connection_id.value().to_vec(),
+            SigningRequest::FinalitySignature {
+                era_id, block_hash, ..
+            } => FinalitySignature::bytes_to_sign(block_hash, *era_id),
+        }
+    }
+}
+
+/// An error signing a message.
+#[derive(Debug, Error)]
+pub(crate) enum SignerError {
+    /// Invalid remote signer configuration.
+    #[error("invalid remote signer configuration: {0}")]
+    Config(String),
+    /// Error communicating with the remote signer.
+    #[error("failed to communicate with remote signer: {0}")]
+    Io(#[from] io::Error),
+    /// Error establishing a TLS connection to the remote signer.
+    #[error("TLS error connecting to remote signer: {0}")]
+    Tls(String),
+    /// The remote signer sent a malformed or unexpected response.
+    #[error("invalid response from remote signer: {0}")]
+    InvalidResponse(String),
+    /// The remote signer refused to sign.
+    #[error("remote signer refused to sign: {0}")]
+    Refused(String),
+    /// The remote signer returned a signature which doesn't verify against its public key.
+    #[error("remote signer returned an invalid signature: {0}")]
+    InvalidSignature(crypto::Error),
+    /// Signing the unit could be an equivocation.
+    #[error(
+        "refusing to sign unit with sequence number {seq_number} in era {era_id}: already signed \
+        a different unit with sequence number {last_seq_number}"
+    )]
+    DoubleSign {
+        era_id: EraId,
+        seq_number: u64,
+        last_seq_number: u64,
+    },
+    /// Signing the message would conflict with the slashing protection record.
+    #[error(transparent)]
+    SlashingProtection(#[from] SlashingProtectionError),
+    /// The signing task panicked or was cancelled.
+    #[error("signing task failed: {0}")]
+    Task(#[from] JoinError),
+}
+
+/// An error setting up the signer.
+#[derive(Debug, Error)]
+pub(crate) enum LoadSignerError {
+    /// Error loading the secret key.
+    #[error(transparent)]
+    SecretKey(#[from] LoadError<CryptoError>),
+    /// Error connecting to the remote signer.
+    #[error(transparent)]
+    RemoteSigner(#[from] SignerError),
+    /// Error opening the slashing protection database.
+    #[error(transparent)]
+    SlashingProtection(#[from] SlashingProtectionError),
+}
+
+/// Signs messages with the validator's secret key.
+pub(crate) trait Signer: Send + Sync {
+    /// Returns the validator's public key.
+    fn public_key(&self) -> &PublicKey;
+
+    /// Signs the given message.
+    fn sign(&self, request: &SigningRequest) -> Result<Signature, SignerError>;
+
+    /// Returns whether signing may block, e.g. waiting for the remote signer or writing to the
+    /// slashing protection database, so that it must not be done while handling an event.
+    fn may_block(&self) -> bool {
+        true
+    }
+
+    /// Deletes the records of eras before `earliest_era_id`, which are no longer open, from the
+    /// slashing protection database.
+    fn prune_eras_before(&self, _earliest_era_id: EraId) -> Result<(), SignerError> {
+        Ok(())
+    }
+
+    /// Creates the validator's finality signature for the given block.
+    fn sign_finality_signature(
+        &self,
+        block_hash: BlockHash,
+        block_height: u64,
+        era_id: EraId,
+    ) -> Result<FinalitySignature, SignerError> {
+        let signature = self.sign(&SigningRequest::FinalitySignature {
+            era_id,
+            block_height,
+            block_hash,
+        })?;
+        Ok(FinalitySignature {
+            block_hash,
+            era_id,
+            signature,
+            public_key: self.public_key().clone(),
+        })
+    }
+}
+
+/// A signer using a secret key loaded into the node process.
+pub(crate) struct LocalSigner {
+    secret_key: Arc<SecretKey>,
+    public_key: PublicKey,
+}
+
+impl LocalSigner {
+    pub(crate) fn new(secret_key: Arc<SecretKey>) -> Self {
+        let public_key = PublicKey::from(secret_key.as_ref());
+        LocalSigner {
+            secret_key,
+            public_key,
+        }
+    }
+}
+
+impl Signer for LocalSigner {
+    fn public_key(&self) -> &PublicKey {
+        &self.public_key
+    }
+
+    fn sign(&self, request: &SigningRequest) -> Result<Signature, SignerError> {
+        Ok(crypto::sign(
+            request.bytes_to_sign(),
+            self.secret_key.as_ref(),
+            &self.public_key,
+        ))
+    }
+
+    fn may_block(&self) -> bool {
+        false
+    }
+}
+
+/// Calls `sign` with the signer on a thread where blocking is acceptable.
+pub(crate) async fn spawn_signing<T, F>(signer: Arc<dyn Signer>, sign: F) -> Result<T, SignerError>
+where
+    T: Send + 'static,
+    F: FnOnce(&dyn Signer) -> Result<T, SignerError> + Send + 'static,
+{
+    task::spawn_blocking(move || sign(&*signer)).await?
+}
@@ -0,0 +1,501 @@
+//! A signer delegating to a separate signing daemon which holds the validator's secret key.
+//!
+//! # Protocol
+//!
+//! The node connects to the daemon either over a Unix domain socket, or over TCP secured with
+//! mutually authenticated TLS. It sends one request at a time, each answered by exactly one
+//! response. Requests and responses are JSON objects on a single line, terminated by `\n`.
+//!
+//! After connecting on startup, the node requests the validator's public key:
+//!
+//! ```text
+//! -> {"method":"public_key"}
+//! <- {"public_key":"01a1b2..."}
+//! ```
+//!
+//! Signing requests contain the kind of message to sign, and the data it is derived from (wrapped
+//! here for readability):
+//!
+//! ```text
+//! -> {"method":"sign","request":{"kind":"unit","era_id":12,"instance_id":"5e0b...",
+//!     "seq_number":7,"hash":"9a3f...","panorama_hash":"c27d..."}}
+//! <- {"signature":"01c4d5..."}
+//! ```
+//!
+//! The `kind` is one of:
+//!
+//! * `unit`, with `era_id`, `instance_id`, `seq_number`, `hash` and `panorama_hash`: a Highway
+//!   unit. The daemon signs the 32 bytes of the hash.
+//! * `consensus_message`, with `era_id` and `hash`: a ping or an endorsement. The daemon signs the
+//!   32 bytes of the hash.
+//! * `finality_signature`, with `era_id`, `block_height` and `block_hash`: a finality signature.
+//!   The daemon signs the 32 bytes of the block hash followed by the era ID as a little-endian
+//!   `u64`.
+//! * `handshake`, with `connection_id`: the ID of a network connection, proving to the peer that
+//!   the node is a validator. The daemon signs the 32 bytes of the connection ID.
+//!
+//! The signed bytes are the same as those a node holding the secret key itself signs, so they
+//! carry no indication of their kind: the hashes of units, pings, endorsements and connection IDs
+//! are all 32 bytes long. The `kind` is what separates them within this protocol, and the daemon
+//! must apply the checks below according to it. A daemon must therefore only serve the node it
+//! trusts to label its requests correctly, over a local socket or a mutually authenticated
+//! connection.
+//!
+//! Hashes, connection IDs, public keys and signatures are hex-encoded. A daemon declining a request
+//! responds with `{"error":"<reason>"}`.
+//!
+//! # Double-sign protection
+//!
+//! Signing two different units with the same sequence number in the same era is an equivocation,
+//! which gets the validator marked as faulty by its peers. Before responding to a `unit` request,
+//! the daemon must therefore durably record the era ID, sequence number and hash of the last unit
+//! it signed in each era, and it must refuse to sign a unit in that era with a lower sequence
+//! number, or with the same one but a different hash.
+//!
+//! The node keeps the same record in memory, complementing its own slashing protection database,
+//! so that it doesn't even ask the daemon to sign a conflicting unit.
+
+use std::{
+    collections::BTreeMap,
+    io::{BufRead, BufReader, Read, Write},
+    net::{TcpStream, ToSocketAddrs},
+    os::unix::net::UnixStream,
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::Duration,
+};
+
+use datasize::DataSize;
+use openssl::ssl::{SslConnector, SslFiletype, SslMethod};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use casper_hashing::Digest;
+use casper_types::{crypto, EraId, PublicKey, Signature, TimeDiff};
+
+use super::{Signer, SignerError, SigningRequest};
+
+const UNIX_ADDRESS_PREFIX: &str = "unix:";
+const TCP_ADDRESS_PREFIX: &str = "tcp:";
+
+/// Remote signer configuration.
+#[derive(DataSize, Debug, Deserialize, Clone)]
+// Disallow unknown fields to ensure config files and command-line overrides contain valid keys.
+#[serde(deny_unknown_fields)]
+pub(crate) struct RemoteSignerConfig {
+    /// Address of the signing daemon: `unix:<path>` for a Unix domain socket, or
+    /// `tcp:<host>:<port>` for TCP with mutual TLS.
+    pub(crate) address: String,
+    /// Timeout for connecting to the daemon and for each request.
+    #[serde(default = "default_request_timeout")]
+    pub(crate) request_timeout: TimeDiff,
+    /// Path to the certificate presented to the daemon. Required for TCP.
+    #[serde(default)]
+    pub(crate) certificate_path: Option<PathBuf>,
+    /// Path to the secret key of the certificate presented to the daemon. Required for TCP.
+    #[serde(default)]
+    pub(crate) secret_key_path: Option<PathBuf>,
+    /// Path to the certificate authority certificate used to verify the daemon. Required for TCP.
+    #[serde(default)]
+    pub(crate) ca_certificate_path: Option<PathBuf>,
+    /// Name expected in the daemon's certificate. Defaults to the host part of the address.
+    #[serde(default)]
+    pub(crate) server_name: Option<String>,
+}
+
+fn default_request_timeout() -> TimeDiff {
+    TimeDiff::from_seconds(5)
+}
+
+/// A request sent to the signing daemon.
+#[derive(Debug, Serialize)]
+#[serde(tag = "method", rename_all = "snake_case")]
+enum Request<'a> {
+    PublicKey,
+    Sign { request: &'a SigningRequest },
+}
+
+/// A response received from the signing daemon.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum Response {
+    PublicKey(PublicKey),
+    Signature(Signature),
+    Error(String),
+}
+
+trait Stream: Read + Write + Send {}
+
+impl<T: Read + Write + Send> Stream for T {}
+
+/// Where to reach the signing daemon.
+enum Endpoint {
+    Unix(PathBuf),
+    Tcp {
+        address: String,
+        server_name: String,
+        connector: SslConnector,
+    },
+}
+
+impl Endpoint {
+    fn new(config: &RemoteSignerConfig, root: &Path) -> Result<Self, SignerError> {
+        if let Some(path) = config.address.strip_prefix(UNIX_ADDRESS_PREFIX) {
+            return Ok(Endpoint::Unix(root.join(path)));
+        }
+        let address = config
+            .address
+            .strip_prefix(TCP_ADDRESS_PREFIX)
+            .ok_or_else(|| {
+                SignerError::Config(format!(
+                    "address must start with '{}' or '{}': {}",
+                    UNIX_ADDRESS_PREFIX, TCP_ADDRESS_PREFIX, config.address
+                ))
+            })?;
+        let required_path = |maybe_path: &Option<PathBuf>, name: &str| {
+            maybe_path
+                .as_ref()
+                .map(|path| root.join(path))
+                .ok_or_else(|| SignerError::Config(format!("{} is required for TCP", name)))
+        };
+        let certificate_path = required_path(&config.certificate_path, "certificate_path")?;
+        let secret_key_path = required_path(&config.secret_key_path, "secret_key_path")?;
+        let ca_certificate_path =
+            required_path(&config.ca_certificate_path, "ca_certificate_path")?;
+
+        let tls_error = |error: openssl::error::ErrorStack| SignerError::Tls(error.to_string());
+        let mut builder = SslConnector::builder(SslMethod::tls_client()).map_err(tls_error)?;
+        builder
+            .set_certificate_chain_file(certificate_path)
+            .map_err(tls_error)?;
+        builder
+            .set_private_key_file(secret_key_path, SslFiletype::PEM)
+            .map_err(tls_error)?;
+        builder.check_private_key().map_err(tls_error)?;
+        builder
+            .set_ca_file(ca_certificate_path)
+            .map_err(tls_error)?;
+
+        let server_name = match &config.server_name {
+            Some(server_name) => server_name.clone(),
+            None => address
+                .rsplit_once(':')
+                .map(|(host, _port)| host.to_string())
+                .ok_or_else(|| {
+                    SignerError::Config(format!("address must include a port: {}", address))
+                })?,
+        };
+        Ok(Endpoint::Tcp {
+            address: address.to_string(),
+            server_name,
+            connector: builder.build(),
+        })
+    }
+
+    fn connect(&self, timeout: Duration) -> Result<Connection, SignerError> {
+        let stream: Box<dyn Stream> = match self {
+            Endpoint::Unix(path) => {
+                let stream = UnixStream::connect(path)?;
+                stream.set_read_timeout(Some(timeout))?;
+                stream.set_write_timeout(Some(timeout))?;
+                Box::new(stream)
+            }
+            Endpoint::Tcp {
+                address,
+                server_name,
+                connector,
+            } => {
+                let socket_address = address.to_socket_addrs()?.next().ok_or_else(|| {
+                    SignerError::Config(format!("address did not resolve: {}", address))
+                })?;
+                let stream = TcpStream::connect_timeout(&socket_address, timeout)?;
+                stream.set_read_timeout(Some(timeout))?;
+                stream.set_write_timeout(Some(timeout))?;
+                let tls_stream = connector
+                    .connect(server_name, stream)
+                    .map_err(|error| SignerError::Tls(error.to_string()))?;
+                Box::new(tls_stream)
+            }
+        };
+        Ok(Connection {
+            stream: BufReader::new(stream),
+        })
+    }
+}
+
+/// An open connection to the signing daemon.
+struct Connection {
+    stream: BufReader<Box<dyn Stream>>,
+}
+
+impl Connection {
+    fn send(&mut self, request: &Request) -> Result<Response, SignerError> {
+        let mut line = serde_json::to_string(request)
+            .map_err(|error| SignerError::InvalidResponse(error.to_string()))?;
+        line.push('\n');
+        let writer = self.stream.get_mut();
+        writer.write_all(line.as_bytes())?;
+        writer.flush()?;
+
+        line.clear();
+        if self.stream.read_line(&mut line)? == 0 {
+            return Err(SignerError::InvalidResponse(
+                "connection closed by remote signer".to_string(),
+            ));
+        }
+        serde_json::from_str(&line).map_err(|error| SignerError::InvalidResponse(error.to_string()))
+    }
+}
+
+/// The mutable state of the remote signer.
+#[derive(Default)]
+struct State {
+    /// The open connection, if any. It is dropped on any error and re-established on the next
+    /// request.
+    connection: Option<Connection>,
+    /// The sequence number and hash of the last unit signed in each era.
+    last_signed_units: BTreeMap<EraId, (u64, Digest)>,
+}
+
+impl State {
+    /// Returns an error if signing the given unit could be an equivocation.
+    fn check_unit(&self, era_id: EraId, seq_number: u64, hash: &Digest) -> Result<(), SignerError> {
+        match self.last_signed_units.get(&era_id) {
+            Some((last_seq_number, last_hash))
+                if seq_number < *last_seq_number
+                    || (seq_number == *last_seq_number && hash != last_hash) =>
+            {
+                Err(SignerError::DoubleSign {
+                    era_id,
+                    seq_number,
+                    last_seq_number: *last_seq_number,
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Records the given unit as the last one signed in its era.
+    fn record_unit(&mut self, era_id: EraId, seq_number: u64, hash: Digest) {
+        self.last_signed_units.insert(era_id, (seq_number, hash));
+    }
+
+    fn send(
+        &mut self,
+        endpoint: &Endpoint,
+        timeout: Duration,
+        request: &Request,
+    ) -> Result<Response, SignerError> {
+        let mut connection = match self.connection.take() {
+            Some(connection) => connection,
+            None => endpoint.connect(timeout)?,
+        };
+        let result = connection.send(request);
+        if result.is_ok() {
+            self.connection = Some(connection);
+        }
+        result
+    }
+}
+
+/// A signer which forwards signing requests to a remote signing daemon.
+///
+/// Requests are blocking, and are sent one at a time, so they must not be sent while handling an
+/// event.
+pub(crate) struct RemoteSigner {
+    endpoint: Endpoint,
+    timeout: Duration,
+    public_key: PublicKey,
+    state: Mutex<State>,
+}
+
+impl RemoteSigner {
+    /// Connects to the signing daemon and retrieves the validator's public key from it.
+    pub(crate) fn connect<P: AsRef<Path>>(
+        config: &RemoteSignerConfig,
+        root: P,
+    ) -> Result<Self, SignerError> {
+        let endpoint = Endpoint::new(config, root.as_ref())?;
+        let timeout = Duration::from(config.request_timeout);
+        let mut state = State::default();
+        let public_key = match state.send(&endpoint, timeout, &Request::PublicKey)? {
+            Response::PublicKey(public_key) => public_key,
+            Response::Error(reason) => return Err(SignerError::Refused(reason)),
+            response => {
+                return Err(SignerError::InvalidResponse(format!(
+                    "expected public key, got {:?}",
+                    response
+                )))
+            }
+        };
+        info!(%public_key, address = %config.address, "connected to remote signer");
+        Ok(RemoteSigner {
+            endpoint,
+            timeout,
+            public_key,
+            state: Mutex::new(state),
+        })
+    }
+}
+
+impl Signer for RemoteSigner {
+    fn public_key(&self) -> &PublicKey {
+        &self.public_key
+    }
+
+    /// Sends the signing request to the daemon, blocking for up to the request timeout while
+    /// waiting for its response.
+    fn sign(&self, request: &SigningRequest) -> Result<Signature, SignerError> {
+        // The lock is held until the response has been received, so that no conflicting unit can
+        // be signed in the meantime.
+        let mut state = self.state.lock().expect("remote signer lock poisoned");
+        if let SigningRequest::Unit {
+            era_id,
+            seq_number,
+            hash,
+            ..
+        } = request
+        {
+            state.check_unit(*era_id, *seq_number, hash)?;
+        }
+
+        let signature = match state.send(&self.endpoint, self.timeout, &Request::Sign { request }) {
+            Ok(Response::Signature(signature)) => signature,
+            Ok(Response::Error(reason)) => return Err(SignerError::Refused(reason)),
+            Ok(response) => {
+                return Err(SignerError::InvalidResponse(format!(
+                    "expected signature, got {:?}",
+                    response
+                )))
+            }
+            Err(error) => {
+                warn!(%error, ?request, "failed to send request to remote signer");
+                return Err(error);
+            }
+        };
+        crypto::verify(request.bytes_to_sign(), &signature, &self.public_key)
+            .map_err(SignerError::InvalidSignature)?;
+
+        if let SigningRequest::Unit {
+            era_id,
+            seq_number,
+            hash,
+            ..
+        } = request
+        {
+            state.record_unit(*era_id, *seq_number, *hash);
+        }
+        Ok(signature)
+    }
+
+    /// Forgets about the units signed in eras which are no longer open.
+    fn prune_eras_before(&self, earliest_era_id: EraId) -> Result<(), SignerError> {
+        let mut state = self.state.lock().expect("remote signer lock poisoned");
+        state.last_signed_units = state.last_signed_units.split_off(&earliest_era_id);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{os::unix::net::UnixListener, sync::Arc, thread};
+
+    use serde_json::{json, Value};
+
+    use casper_types::SecretKey;
+
+    use super::*;
+    use crate::components::consensus::signer::LocalSigner;
+
+    /// Runs a minimal signing daemon on a Unix socket in the given directory, answering requests
+    /// of a single connection with a local signer.
+    fn run_daemon(dir: &Path) -> PathBuf {
+        let socket_path = dir.join("signer.sock");
+        let listener = UnixListener::bind(&socket_path).unwrap();
+        let secret_key = Arc::new(SecretKey::ed25519_from_bytes([7; 32]).unwrap());
+        let signer = LocalSigner::new(secret_key);
+        thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut writer = stream.try_clone().unwrap();
+            for line in BufReader::new(stream).lines() {
+                let request: Value = serde_json::from_str(&line.unwrap()).unwrap();
+                let response = match request["method"].as_str().unwrap() {
+                    "public_key" => json!({ "public_key": signer.public_key() }),
+                    "sign" => {
+                        let request: SigningRequest =
+                            serde_json::from_value(request["request"].clone()).unwrap();
+                        json!({ "signature": signer.sign(&request).unwrap() })
+                    }
+                    method => json!({ "error": format!("unknown method {}", method) }),
+                };
+                writeln!(writer, "{}", response).unwrap();
+            }
+        });
+        socket_path
+    }
+
+    fn connect(dir: &Path) -> RemoteSigner {
+        let socket_path = run_daemon(dir);
+        let config = RemoteSignerConfig {
+            address: format!("unix:{}", socket_path.display()),
+            request_timeout: default_request_timeout(),
+            certificate_path: None,
+            secret_key_path: None,
+            ca_certificate_path: None,
+            server_name: None,
+        };
+        RemoteSigner::connect(&config, dir).unwrap()
+    }
+
+    #[test]
+    fn should_sign_remotely() {
+        let dir = tempfile::tempdir().unwrap();
+        let signer = connect(dir.path());
+
+        let request = SigningRequest::ConsensusMessage {
+            era_id: EraId::new(1),
+            hash: Digest::hash(b"ping"),
+        };
+        let signature = signer.sign(&request).unwrap();
+        crypto::verify(request.bytes_to_sign(), &signature, signer.public_key()).unwrap();
+
+        let finality_signature = signer
+            .sign_finality_signature(Digest::hash(b"block").into(), 10, EraId::new(1))
+            .unwrap();
+        finality_signature.verify().unwrap();
+    }
+
+    #[test]
+    fn should_refuse_to_sign_conflicting_units() {
+        let dir = tempfile::tempdir().unwrap();
+        let signer = connect(dir.path());
+        let unit = |seq_number, data: &[u8]| SigningRequest::Unit {
+            era_id: EraId::new(3),
+            instance_id: Digest::hash(b"instance"),
+            seq_number,
+            hash: Digest::hash(data),
+            panorama_hash: Digest::hash(b"panorama"),
+        };
+
+        signer.sign(&unit(5, b"a")).unwrap();
+        // Signing the same unit again is harmless.
+        signer.sign(&unit(5, b"a")).unwrap();
+        assert!(matches!(
+            signer.sign(&unit(5, b"b")),
+            Err(SignerError::DoubleSign {
+                seq_number: 5,
+                last_seq_number: 5,
+                ..
+            })
+        ));
+        assert!(matches!(
+            signer.sign(&unit(4, b"c")),
+            Err(SignerError::DoubleSign {
+                seq_number: 4,
+                last_seq_number: 5,
+                ..
+            })
+        ));
+        signer.sign(&unit(6, b"d")).unwrap();
+    }
+}
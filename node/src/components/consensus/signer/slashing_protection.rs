@@ -0,0 +1,696 @@
+//! A durable record of everything the validator signed, protecting it against equivocation.
+//!
+//! Before any unit or finality signature is signed, it is checked against the record and written
+//! to it in the same LMDB transaction. A request conflicting with an earlier signature is refused:
+//!
+//! * A unit conflicts with a signed unit in the same Highway instance if it has the same sequence
+//!   number but a different hash, or if it has a lower sequence number than the last signed unit
+//!   and wasn't signed itself.
+//! * A finality signature conflicts with a signed one if it is for a different block at the same
+//!   height in the same era.
+//!
+//! Records of eras which are no longer open are pruned when the era supervisor closes them, as
+//! units and finality signatures in them can no longer be equivocated. The validator then refuses
+//! to sign anything in a pruned era.
+//!
+//! Since the record is written before signing, a failure to sign leaves behind a record of a
+//! signature that was never created. That is harmless: it can only cause the validator to refuse
+//! signing a conflicting message later.
+//!
+//! The record can be exported to and imported from a portable JSON [`Interchange`] file, so that
+//! it can be carried over when moving a validator to a new host or restoring it from a backup.
+
+use std::{
+    fmt::{self, Display, Formatter},
+    path::Path,
+    sync::Arc,
+};
+
+use lmdb::{
+    Cursor, Database, DatabaseFlags, Environment, EnvironmentFlags, RwTransaction, Transaction,
+    WriteFlags,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use thiserror::Error;
+
+use casper_hashing::Digest;
+use casper_types::{EraId, PublicKey, Signature};
+
+use super::{Signer, SignerError, SigningRequest};
+use crate::types::BlockHash;
+
+/// The name of the database file, in the storage directory.
+const SLASHING_PROTECTION_DB_FILENAME: &str = "slashing_protection.lmdb";
+/// The maximum size of the database: 1 GiB is enough for several million records, far more than
+/// the open eras can hold.
+const MAX_DB_SIZE: usize = 1 << 30;
+/// The number of named databases in the environment.
+const MAX_DB_COUNT: u32 = 4;
+/// The key under which the validator's public key is stored in the metadata database.
+const PUBLIC_KEY_KEY: &[u8] = b"public_key";
+/// The key under which the earliest era whose records are kept is stored in the metadata database.
+const EARLIEST_ERA_ID_KEY: &[u8] = b"earliest_era_id";
+
+/// The current version of the interchange format.
+pub(crate) const INTERCHANGE_FORMAT_VERSION: u32 = 1;
+
+/// A unit signed by the validator.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct SignedUnit {
+    pub(crate) era_id: EraId,
+    pub(crate) instance_id: Digest,
+    pub(crate) seq_number: u64,
+    pub(crate) hash: Digest,
+    pub(crate) panorama_hash: Digest,
+}
+
+impl SignedUnit {
+    /// Returns the database key: the big-endian era ID, the instance ID and the big-endian
+    /// sequence number, so that units are ordered by era, instance and sequence number.
+    fn key(&self) -> Vec<u8> {
+        let mut key = self.last_unit_key();
+        key.extend_from_slice(&self.seq_number.to_be_bytes());
+        key
+    }
+
+    /// Returns the key of the last signed unit of its instance: the big-endian era ID followed by
+    /// the instance ID.
+    fn last_unit_key(&self) -> Vec<u8> {
+        let mut key = self.era_id.value().to_be_bytes().to_vec();
+        key.extend_from_slice(&self.instance_id.value());
+        key
+    }
+}
+
+impl Display for SignedUnit {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            formatter,
+            "unit {} with sequence number {} in era {} (instance {})",
+            self.hash, self.seq_number, self.era_id, self.instance_id
+        )
+    }
+}
+
+/// A finality signature created by the validator.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct SignedFinalitySignature {
+    pub(crate) era_id: EraId,
+    pub(crate) block_height: u64,
+    pub(crate) block_hash: BlockHash,
+}
+
+impl SignedFinalitySignature {
+    /// Returns the database key: the big-endian era ID followed by the big-endian block height.
+    fn key(&self) -> Vec<u8> {
+        let mut key = self.era_id.value().to_be_bytes().to_vec();
+        key.extend_from_slice(&self.block_height.to_be_bytes());
+        key
+    }
+}
+
+impl Display for SignedFinalitySignature {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            formatter,
+            "finality signature for block {} at height {} in era {}",
+            self.block_hash, self.block_height, self.era_id
+        )
+    }
+}
+
+/// The portable interchange format of the slashing protection record.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct Interchange {
+    /// The version of the interchange format.
+    pub(crate) format_version: u32,
+    /// The public key of the validator the record belongs to.
+    pub(crate) public_key: PublicKey,
+    /// The signed units, ordered by era, instance ID and sequence number.
+    pub(crate) signed_units: Vec<SignedUnit>,
+    /// The signed finality signatures, ordered by era and block height.
+    pub(crate) signed_finality_signatures: Vec<SignedFinalitySignature>,
+}
+
+/// An error accessing the slashing protection record, or a refusal to sign.
+#[derive(Debug, Error)]
+pub(crate) enum SlashingProtectionError {
+    /// Error reading from or writing to the database.
+    #[error("slashing protection database error: {0}")]
+    Lmdb(#[from] lmdb::Error),
+    /// A corrupt record in the database.
+    #[error("corrupt slashing protection record: {0}")]
+    Corrupt(#[from] bincode::Error),
+    /// The record belongs to a different validator.
+    #[error("slashing protection record belongs to validator {stored}, not {public_key}")]
+    PublicKeyMismatch {
+        stored: Box<PublicKey>,
+        public_key: Box<PublicKey>,
+    },
+    /// The record is empty, and not associated with any validator yet.
+    #[error("slashing protection record is empty")]
+    Empty,
+    /// The interchange file has an unsupported format version.
+    #[error(
+        "unsupported slashing protection interchange format version {0}, expected {}",
+        INTERCHANGE_FORMAT_VERSION
+    )]
+    UnsupportedFormatVersion(u32),
+    /// The unit conflicts with a unit signed before.
+    #[error("refusing to sign {requested}: conflicts with signed {signed}")]
+    ConflictingUnit {
+        requested: Box<SignedUnit>,
+        signed: Box<SignedUnit>,
+    },
+    /// The finality signature conflicts with one signed before.
+    #[error("refusing to sign {requested}: conflicts with signed {signed}")]
+    ConflictingFinalitySignature {
+        requested: Box<SignedFinalitySignature>,
+        signed: Box<SignedFinalitySignature>,
+    },
+    /// The records of the era have been pruned, so conflicts can't be ruled out.
+    #[error(
+        "refusing to sign in era {era_id}: records of eras before {earliest_era_id} have been \
+        pruned"
+    )]
+    EraPruned {
+        era_id: EraId,
+        earliest_era_id: EraId,
+    },
+}
+
+/// The slashing protection record, stored in an LMDB database.
+pub(crate) struct SlashingProtection {
+    env: Environment,
+    /// All signed units, by era, instance ID and sequence number.
+    unit_db: Database,
+    /// The signed unit with the highest sequence number, by era and instance ID.
+    last_unit_db: Database,
+    /// All signed finality signatures, by era and block height.
+    finality_signature_db: Database,
+    /// The validator's public key and the earliest era whose records are kept.
+    metadata_db: Database,
+}
+
+impl SlashingProtection {
+    /// Opens the slashing protection record in the given storage directory, creating it if it
+    /// doesn't exist yet.
+    pub(crate) fn open(storage_dir: &Path) -> Result<Self, SlashingProtectionError> {
+        let env = Environment::new()
+            .set_flags(EnvironmentFlags::NO_SUB_DIR)
+            .set_max_dbs(MAX_DB_COUNT)
+            .set_map_size(MAX_DB_SIZE)
+            .open(&storage_dir.join(SLASHING_PROTECTION_DB_FILENAME))?;
+        let unit_db = env.create_db(Some("units"), DatabaseFlags::empty())?;
+        let last_unit_db = env.create_db(Some("last_units"), DatabaseFlags::empty())?;
+        let finality_signature_db =
+            env.create_db(Some("finality_signatures"), DatabaseFlags::empty())?;
+        let metadata_db = env.create_db(Some("metadata"), DatabaseFlags::empty())?;
+        Ok(SlashingProtection {
+            env,
+            unit_db,
+            last_unit_db,
+            finality_signature_db,
+            metadata_db,
+        })
+    }
+
+    /// Associates an empty record with the given validator, or checks that a non-empty one
+    /// belongs to it.
+    pub(crate) fn check_public_key(
+        &self,
+        public_key: &PublicKey,
+    ) -> Result<(), SlashingProtectionError> {
+        let mut txn = self.env.begin_rw_txn()?;
+        self.check_public_key_in(&mut txn, public_key)?;
+        txn.commit()?;
+        Ok(())
+    }
+
+    /// Checks whether the request conflicts with anything signed before, and if not, records it.
+    ///
+    /// Requests to sign pings, endorsements and handshakes are not recorded.
+    pub(crate) fn check_and_record(
+        &self,
+        request: &SigningRequest,
+    ) -> Result<(), SlashingProtectionError> {
+        let era_id = match request {
+            SigningRequest::Unit { era_id, .. }
+            | SigningRequest::FinalitySignature { era_id, .. } => *era_id,
+            SigningRequest::ConsensusMessage { .. } | SigningRequest::Handshake { .. } => {
+                return Ok(())
+            }
+        };
+        let mut txn = self.env.begin_rw_txn()?;
+        let earliest_era_id = self.earliest_era_id(&txn)?;
+        if era_id < earliest_era_id {
+            return Err(SlashingProtectionError::EraPruned {
+                era_id,
+                earliest_era_id,
+            });
+        }
+        match request {
+            SigningRequest::Unit {
+                era_id,
+                instance_id,
+                seq_number,
+                hash,
+                panorama_hash,
+            } => {
+                let unit = SignedUnit {
+                    era_id: *era_id,
+                    instance_id: *instance_id,
+                    seq_number: *seq_number,
+                    hash: *hash,
+                    panorama_hash: *panorama_hash,
+                };
+                self.check_and_record_unit(&mut txn, unit)?;
+            }
+            SigningRequest::FinalitySignature {
+                era_id,
+                block_height,
+                block_hash,
+            } => {
+                let finality_signature = SignedFinalitySignature {
+                    era_id: *era_id,
+                    block_height: *block_height,
+                    block_hash: *block_hash,
+                };
+                self.check_and_record_finality_signature(&mut txn, finality_signature)?;
+            }
+            SigningRequest::ConsensusMessage { .. } | SigningRequest::Handshake { .. } => {}
+        }
+        txn.commit()?;
+        Ok(())
+    }
+
+    /// Deletes the records of all eras before `earliest_era_id`, and refuses to sign anything in
+    /// them from now on.
+    pub(crate) fn prune_eras_before(
+        &self,
+        earliest_era_id: EraId,
+    ) -> Result<(), SlashingProtectionError> {
+        let mut txn = self.env.begin_rw_txn()?;
+        self.prune(&mut txn, earliest_era_id)?;
+        txn.commit()?;
+        Ok(())
+    }
+
+    /// Exports the whole record in the interchange format.
+    pub(crate) fn export(&self) -> Result<Interchange, SlashingProtectionError> {
+        let txn = self.env.begin_ro_txn()?;
+        let public_key =
+            get(&txn, self.metadata_db, PUBLIC_KEY_KEY)?.ok_or(SlashingProtectionError::Empty)?;
+        let signed_units = txn
+            .open_ro_cursor(self.unit_db)?
+            .iter()
+            .map(|(_, raw_value)| bincode::deserialize(raw_value))
+            .collect::<Result<_, _>>()?;
+        let signed_finality_signatures = txn
+            .open_ro_cursor(self.finality_signature_db)?
+            .iter()
+            .map(|(_, raw_value)| bincode::deserialize(raw_value))
+            .collect::<Result<_, _>>()?;
+        Ok(Interchange {
+            format_version: INTERCHANGE_FORMAT_VERSION,
+            public_key,
+            signed_units,
+            signed_finality_signatures,
+        })
+    }
+
+    /// Merges the given record into this one.
+    ///
+    /// Fails without importing anything if the interchange record belongs to a different validator
+    /// or if any of its entries conflicts with this record. Entries in eras that have already been
+    /// pruned are skipped.
+    pub(crate) fn import(&self, interchange: Interchange) -> Result<(), SlashingProtectionError> {
+        if interchange.format_version != INTERCHANGE_FORMAT_VERSION {
+            return Err(SlashingProtectionError::UnsupportedFormatVersion(
+                interchange.format_version,
+            ));
+        }
+        let mut txn = self.env.begin_rw_txn()?;
+        self.check_public_key_in(&mut txn, &interchange.public_key)?;
+        let earliest_era_id = self.earliest_era_id(&txn)?;
+        for unit in interchange.signed_units {
+            if unit.era_id >= earliest_era_id {
+                self.import_unit(&mut txn, unit)?;
+            }
+        }
+        for finality_signature in interchange.signed_finality_signatures {
+            if finality_signature.era_id >= earliest_era_id {
+                self.check_and_record_finality_signature(&mut txn, finality_signature)?;
+            }
+        }
+        txn.commit()?;
+        Ok(())
+    }
+
+    /// Returns the earliest era whose records are kept.
+    fn earliest_era_id<T: Transaction>(&self, txn: &T) -> Result<EraId, SlashingProtectionError> {
+        Ok(get(txn, self.metadata_db, EARLIEST_ERA_ID_KEY)?.unwrap_or_default())
+    }
+
+    /// Deletes the records of all eras before `earliest_era_id`.
+    fn prune(
+        &self,
+        txn: &mut RwTransaction<'_>,
+        earliest_era_id: EraId,
+    ) -> Result<(), SlashingProtectionError> {
+        if earliest_era_id <= self.earliest_era_id(&*txn)? {
+            return Ok(());
+        }
+        // All keys start with the big-endian era ID, so the records to delete come first.
+        let bound = earliest_era_id.value().to_be_bytes();
+        for db in [self.unit_db, self.last_unit_db, self.finality_signature_db] {
+            let mut cursor = txn.open_rw_cursor(db)?;
+            for (raw_key, _) in cursor.iter() {
+                if raw_key >= &bound[..] {
+                    break;
+                }
+                cursor.del(WriteFlags::empty())?;
+            }
+        }
+        put(txn, self.metadata_db, EARLIEST_ERA_ID_KEY, &earliest_era_id)
+    }
+
+    fn check_public_key_in(
+        &self,
+        txn: &mut RwTransaction<'_>,
+        public_key: &PublicKey,
+    ) -> Result<(), SlashingProtectionError> {
+        match get::<PublicKey, _>(&*txn, self.metadata_db, PUBLIC_KEY_KEY)? {
+            Some(stored) if stored == *public_key => Ok(()),
+            Some(stored) => Err(SlashingProtectionError::PublicKeyMismatch {
+                stored: Box::new(stored),
+                public_key: Box::new(public_key.clone()),
+            }),
+            None => put(txn, self.metadata_db, PUBLIC_KEY_KEY, public_key),
+        }
+    }
+
+    fn check_and_record_unit(
+        &self,
+        txn: &mut RwTransaction<'_>,
+        unit: SignedUnit,
+    ) -> Result<(), SlashingProtectionError> {
+        let key = unit.key();
+        let conflicting = match get::<SignedUnit, _>(&*txn, self.unit_db, &key)? {
+            Some(signed) if signed.hash == unit.hash => return Ok(()),
+            Some(signed) => Some(signed),
+            None => get::<SignedUnit, _>(&*txn, self.last_unit_db, &unit.last_unit_key())?
+                .filter(|last| last.seq_number > unit.seq_number),
+        };
+        if let Some(signed) = conflicting {
+            return Err(SlashingProtectionError::ConflictingUnit {
+                requested: Box::new(unit),
+                signed: Box::new(signed),
+            });
+        }
+        put(txn, self.unit_db, &key, &unit)?;
+        put(txn, self.last_unit_db, &unit.last_unit_key(), &unit)
+    }
+
+    /// Records a unit from an imported record. Unlike newly signed units, these may have a lower
+    /// sequence number than the last recorded unit.
+    fn import_unit(
+        &self,
+        txn: &mut RwTransaction<'_>,
+        unit: SignedUnit,
+    ) -> Result<(), SlashingProtectionError> {
+        let key = unit.key();
+        match get::<SignedUnit, _>(&*txn, self.unit_db, &key)? {
+            Some(signed) if signed.hash == unit.hash => return Ok(()),
+            Some(signed) => {
+                return Err(SlashingProtectionError::ConflictingUnit {
+                    requested: Box::new(unit),
+                    signed: Box::new(signed),
+                })
+            }
+            None => put(txn, self.unit_db, &key, &unit)?,
+        }
+        let last_unit_key = unit.last_unit_key();
+        match get::<SignedUnit, _>(&*txn, self.last_unit_db, &last_unit_key)? {
+            Some(last) if last.seq_number > unit.seq_number => Ok(()),
+            _ => put(txn, self.last_unit_db, &last_unit_key, &unit),
+        }
+    }
+
+    fn check_and_record_finality_signature(
+        &self,
+        txn: &mut RwTransaction<'_>,
+        finality_signature: SignedFinalitySignature,
+    ) -> Result<(), SlashingProtectionError> {
+        let key = finality_signature.key();
+        match get::<SignedFinalitySignature, _>(&*txn, self.finality_signature_db, &key)? {
+            Some(signed) if signed.block_hash == finality_signature.block_hash => Ok(()),
+            Some(signed) => Err(SlashingProtectionError::ConflictingFinalitySignature {
+                requested: Box::new(finality_signature),
+                signed: Box::new(signed),
+            }),
+            None => put(txn, self.finality_signature_db, &key, &finality_signature),
+        }
+    }
+}
+
+/// Reads and deserializes the value under the given key, if any.
+fn get<V: DeserializeOwned, T: Transaction>(
+    txn: &T,
+    db: Database,
+    key: &[u8],
+) -> Result<Option<V>, SlashingProtectionError> {
+    match txn.get(db, &key) {
+        Ok(raw_value) => Ok(Some(bincode::deserialize(raw_value)?)),
+        Err(lmdb::Error::NotFound) => Ok(None),
+        Err(error) => Err(error.into()),
+    }
+}
+
+/// Serializes and writes the value under the given key.
+fn put<V: Serialize>(
+    txn: &mut RwTransaction<'_>,
+    db: Database,
+    key: &[u8],
+    value: &V,
+) -> Result<(), SlashingProtectionError> {
+    let raw_value = bincode::serialize(value)?;
+    txn.put(db, &key, &raw_value, WriteFlags::empty())?;
+    Ok(())
+}
+
+/// A signer refusing to sign anything conflicting with its slashing protection record.
+pub(crate) struct SlashingProtectedSigner {
+    signer: Arc<dyn Signer>,
+    slashing_protection: SlashingProtection,
+}
+
+impl SlashingProtectedSigner {
+    /// Wraps the signer, associating the slashing protection record with its public key.
+    pub(crate) fn new(
+        signer: Arc<dyn Signer>,
+        slashing_protection: SlashingProtection,
+    ) -> Result<Self, SlashingProtectionError> {
+        slashing_protection.check_public_key(signer.public_key())?;
+        Ok(SlashingProtectedSigner {
+            signer,
+            slashing_protection,
+        })
+    }
+}
+
+impl Signer for SlashingProtectedSigner {
+    fn public_key(&self) -> &PublicKey {
+        self.signer.public_key()
+    }
+
+    fn sign(&self, request: &SigningRequest) -> Result<Signature, SignerError> {
+        self.slashing_protection.check_and_record(request)?;
+        self.signer.sign(request)
+    }
+
+    fn prune_eras_before(&self, earliest_era_id: EraId) -> Result<(), SignerError> {
+        self.slashing_protection
+            .prune_eras_before(earliest_era_id)?;
+        self.signer.prune_eras_before(earliest_era_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use casper_types::SecretKey;
+
+    use super::*;
+    use crate::components::consensus::signer::LocalSigner;
+
+    fn unit(seq_number: u64, data: &[u8]) -> SigningRequest {
+        unit_in_era(EraId::new(3), seq_number, data)
+    }
+
+    fn unit_in_era(era_id: EraId, seq_number: u64, data: &[u8]) -> SigningRequest {
+        SigningRequest::Unit {
+            era_id,
+            instance_id: Digest::hash(b"instance"),
+            seq_number,
+            hash: Digest::hash(data),
+            panorama_hash: Digest::hash(b"panorama"),
+        }
+    }
+
+    fn finality_signature(block_height: u64, data: &[u8]) -> SigningRequest {
+        finality_signature_in_era(EraId::new(3), block_height, data)
+    }
+
+    fn finality_signature_in_era(era_id: EraId, block_height: u64, data: &[u8]) -> SigningRequest {
+        SigningRequest::FinalitySignature {
+            era_id,
+            block_height,
+            block_hash: BlockHash::new(Digest::hash(data)),
+        }
+    }
+
+    fn new_signer(
+        secret_key: &Arc<SecretKey>,
+        storage_dir: &Path,
+    ) -> Result<SlashingProtectedSigner, SlashingProtectionError> {
+        let local_signer = Arc::new(LocalSigner::new(Arc::clone(secret_key)));
+        SlashingProtectedSigner::new(local_signer, SlashingProtection::open(storage_dir)?)
+    }
+
+    fn is_refused(result: Result<Signature, SignerError>) -> bool {
+        matches!(result, Err(SignerError::SlashingProtection(_)))
+    }
+
+    #[test]
+    fn should_refuse_conflicting_requests_across_restarts() {
+        let secret_key = Arc::new(SecretKey::ed25519_from_bytes([7; 32]).unwrap());
+        let storage_dir = tempfile::tempdir().unwrap();
+
+        let signer = new_signer(&secret_key, storage_dir.path()).unwrap();
+        signer.sign(&unit(5, b"a")).unwrap();
+        signer.sign(&unit(6, b"b")).unwrap();
+        signer.sign(&finality_signature(10, b"block")).unwrap();
+        drop(signer);
+
+        let signer = new_signer(&secret_key, storage_dir.path()).unwrap();
+        // Signing the same messages again is allowed.
+        signer.sign(&unit(5, b"a")).unwrap();
+        signer.sign(&unit(6, b"b")).unwrap();
+        signer.sign(&finality_signature(10, b"block")).unwrap();
+        // Conflicting messages are refused.
+        assert!(is_refused(signer.sign(&unit(6, b"c"))));
+        assert!(is_refused(signer.sign(&unit(4, b"d"))));
+        assert!(is_refused(signer.sign(&finality_signature(10, b"fork"))));
+        // New messages are signed.
+        signer.sign(&unit(7, b"e")).unwrap();
+        signer.sign(&finality_signature(11, b"next")).unwrap();
+
+        let other_key = Arc::new(SecretKey::ed25519_from_bytes([8; 32]).unwrap());
+        assert!(matches!(
+            new_signer(&other_key, storage_dir.path()),
+            Err(SlashingProtectionError::PublicKeyMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn should_carry_record_over_to_new_host() {
+        let secret_key = Arc::new(SecretKey::ed25519_from_bytes([7; 32]).unwrap());
+        let old_dir = tempfile::tempdir().unwrap();
+        let new_dir = tempfile::tempdir().unwrap();
+
+        let old_signer = new_signer(&secret_key, old_dir.path()).unwrap();
+        old_signer.sign(&unit(5, b"a")).unwrap();
+        old_signer.sign(&unit(6, b"b")).unwrap();
+        old_signer.sign(&finality_signature(10, b"block")).unwrap();
+        let json =
+            serde_json::to_string(&old_signer.slashing_protection.export().unwrap()).unwrap();
+
+        let new_protection = SlashingProtection::open(new_dir.path()).unwrap();
+        let interchange: Interchange = serde_json::from_str(&json).unwrap();
+        new_protection.import(interchange.clone()).unwrap();
+        assert_eq!(interchange, new_protection.export().unwrap());
+        // Importing the same record again is a no-op.
+        new_protection.import(interchange).unwrap();
+        // LMDB environments must not be opened twice in the same process.
+        drop(new_protection);
+
+        let signer = new_signer(&secret_key, new_dir.path()).unwrap();
+        assert!(is_refused(signer.sign(&unit(6, b"c"))));
+        assert!(is_refused(signer.sign(&unit(4, b"d"))));
+        assert!(is_refused(signer.sign(&finality_signature(10, b"fork"))));
+        signer.sign(&unit(7, b"e")).unwrap();
+
+        // A record conflicting with the existing one can't be imported.
+        let mut conflicting = old_signer.slashing_protection.export().unwrap();
+        conflicting.signed_units[0].hash = Digest::hash(b"f");
+        assert!(matches!(
+            signer.slashing_protection.import(conflicting),
+            Err(SlashingProtectionError::ConflictingUnit { .. })
+        ));
+    }
+
+    #[test]
+    fn should_key_records_by_era() {
+        let secret_key = Arc::new(SecretKey::ed25519_from_bytes([7; 32]).unwrap());
+        let storage_dir = tempfile::tempdir().unwrap();
+        let signer = new_signer(&secret_key, storage_dir.path()).unwrap();
+
+        signer.sign(&unit(5, b"a")).unwrap();
+        signer.sign(&finality_signature(10, b"block")).unwrap();
+        // The same instance, sequence number and height in another era don't conflict.
+        let era_id = EraId::new(4);
+        signer.sign(&unit_in_era(era_id, 5, b"b")).unwrap();
+        assert!(is_refused(signer.sign(&unit_in_era(era_id, 4, b"c"))));
+        signer
+            .sign(&finality_signature_in_era(era_id, 10, b"other"))
+            .unwrap();
+        assert!(is_refused(
+            signer.sign(&finality_signature_in_era(era_id, 10, b"fork"))
+        ));
+    }
+
+    #[test]
+    fn should_prune_eras_that_are_no_longer_open() {
+        let secret_key = Arc::new(SecretKey::ed25519_from_bytes([7; 32]).unwrap());
+        let storage_dir = tempfile::tempdir().unwrap();
+        let signer = new_signer(&secret_key, storage_dir.path()).unwrap();
+        signer.sign(&unit(5, b"a")).unwrap();
+        signer.sign(&finality_signature(10, b"block")).unwrap();
+        let old_record = signer.slashing_protection.export().unwrap();
+
+        // Signing in a much later era doesn't prune era 3 while it is still open.
+        let later_era_id = EraId::new(10);
+        signer.sign(&unit_in_era(later_era_id, 0, b"b")).unwrap();
+        assert!(is_refused(signer.sign(&unit(5, b"c"))));
+        signer.sign(&finality_signature(11, b"next")).unwrap();
+
+        // Once era 3 is closed, its records are deleted.
+        signer.prune_eras_before(EraId::new(4)).unwrap();
+        let record = signer.slashing_protection.export().unwrap();
+        assert_eq!(
+            record
+                .signed_units
+                .iter()
+                .map(|unit| unit.era_id)
+                .collect::<Vec<_>>(),
+            vec![later_era_id]
+        );
+        assert!(record.signed_finality_signatures.is_empty());
+
+        // Nothing is signed in the pruned era anymore, and importing its records is a no-op.
+        assert!(matches!(
+            signer.sign(&unit(6, b"e")),
+            Err(SignerError::SlashingProtection(
+                SlashingProtectionError::EraPruned { .. }
+            ))
+        ));
+        assert!(is_refused(signer.sign(&finality_signature(12, b"last"))));
+        signer.slashing_protection.import(old_record).unwrap();
+        assert_eq!(signer.slashing_protection.export().unwrap(), record);
+
+        // Pruning is never undone by an earlier era.
+        signer.prune_eras_before(EraId::new(2)).unwrap();
+        assert!(is_refused(signer.sign(&unit(6, b"e"))));
+    }
+}
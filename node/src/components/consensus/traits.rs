@@ -45,7 +45,30 @@ pub trait ValidatorSecret: Send + DataSize {
 
     type Signature: Eq + PartialEq + Clone + Debug + Hash + Serialize + DeserializeOwned + DataSize;
 
-    fn sign(&self, hash: &Self::Hash) -> Self::Signature;
+    /// Signs the given hash, or returns `None` if the signer failed or refused to sign it.
+    fn sign(&self, hash: &Self::Hash) -> Option<Self::Signature>;
+
+    /// Signs the hash of a new unit with the given sequence number and panorama hash, or returns
+    /// `None` if the signer failed or refused to sign it.
+    ///
+    /// Signers can use the sequence number to refuse signing two different units with the same
+    /// one, which would be an equivocation.
+    fn sign_unit(
+        &self,
+        hash: &Self::Hash,
+        _seq_number: u64,
+        _panorama_hash: &Self::Hash,
+    ) -> Option<Self::Signature> {
+        self.sign(hash)
+    }
+
+    /// Returns whether signing may block, e.g. on network or disk I/O.
+    ///
+    /// If so, `sign` and `sign_unit` are not called while handling an event: the validator
+    /// requests the signature instead, and creates the message once it is provided.
+    fn signs_asynchronously(&self) -> bool {
+        false
+    }
 }
 
 /// The collection of types the user can choose for cryptography, IDs, transactions, etc.
@@ -4,11 +4,14 @@ mod config;
 mod error;
 mod metrics;
 mod operations;
+#[cfg(test)]
+mod tests;
 mod types;
 
 use std::{
-    collections::BTreeMap,
+    collections::{BTreeMap, BTreeSet},
     fmt::{self, Debug, Display, Formatter},
+    fs,
     path::Path,
     sync::{Arc, Mutex},
     time::Instant,
@@ -21,7 +24,7 @@ use once_cell::sync::Lazy;
 use prometheus::Registry;
 use serde::Serialize;
 use thiserror::Error;
-use tracing::{debug, error, info, trace};
+use tracing::{debug, error, info, trace, warn};
 
 use casper_execution_engine::{
     core::engine_state::{
@@ -31,35 +34,44 @@ use casper_execution_engine::{
     },
     shared::{newtypes::CorrelationId, system_config::SystemConfig, wasm_config::WasmConfig},
     storage::{
-        global_state::lmdb::LmdbGlobalState,
+        global_state::lmdb::{LmdbGlobalState, TrieGcMarks},
         transaction_source::lmdb::LmdbEnvironment,
         trie::{TrieOrChunk, TrieOrChunkId},
         trie_store::lmdb::LmdbTrieStore,
     },
 };
 use casper_hashing::Digest;
-use casper_types::{bytesrepr::Bytes, ProtocolVersion, Timestamp};
+use casper_types::{
+    bytesrepr::Bytes, EraId, Key, ProtocolVersion, PublicKey, StoredValue, Timestamp,
+};
 
 use crate::{
     components::{contract_runtime::types::StepEffectAndUpcomingEraValidators, Component},
     effect::{
         announcements::{ContractRuntimeAnnouncement, ControlAnnouncement},
         incoming::{TrieDemand, TrieRequest, TrieRequestIncoming},
-        requests::{ContractRuntimeRequest, MarkBlockCompletedRequest, NetworkRequest},
+        requests::{
+            ContractRuntimeRequest, MarkBlockCompletedRequest, NetworkRequest, StorageRequest,
+        },
         EffectBuilder, EffectExt, Effects,
     },
     fatal,
     protocol::Message,
-    types::{BlockHash, BlockHeader, Chainspec, ChainspecRawBytes, Deploy, FinalizedBlock},
+    types::{
+        Block, BlockHash, BlockHeader, BlockHeadersBatchId, Chainspec, ChainspecRawBytes, Deploy,
+        FinalizedBlock,
+    },
     NodeRng,
 };
 pub(crate) use config::Config;
 pub(crate) use error::{BlockExecutionError, ConfigError};
 use metrics::Metrics;
 pub use operations::execute_finalized_block;
-pub(crate) use types::{BlockAndExecutionEffects, EraValidatorsRequest};
+pub(crate) use types::{
+    BlockAndExecutionEffects, EraValidatorsRequest, SpeculativeExecutionResults,
+};
 
-use self::operations::execute_only;
+use self::operations::{execute_only, execute_only_sequence};
 
 use super::fetcher::FetchedOrNotFound;
 
@@ -102,6 +114,9 @@ where
 
 #[derive(DataSize, Debug, Clone, Serialize)]
 /// Wrapper for speculative execution prestate.
+///
+/// Deploys are executed as if they were included in a hypothetical block following the one whose
+/// post-state they are executed on top of.
 pub struct SpeculativeExecutionState {
     /// State root on top of which to execute deploy.
     pub state_root_hash: Digest,
@@ -109,6 +124,45 @@ pub struct SpeculativeExecutionState {
     pub block_time: Timestamp,
     /// Protocol version used when creating the original block.
     pub protocol_version: ProtocolVersion,
+    /// Height of the hypothetical block.
+    pub block_height: u64,
+    /// Era of the hypothetical block.
+    pub era_id: EraId,
+    /// Hash of the parent of the hypothetical block, i.e. of the original block.
+    pub parent_hash: BlockHash,
+    /// Proposer of the hypothetical block, taken to be that of the original block.
+    pub proposer: Box<PublicKey>,
+}
+
+impl From<&Block> for SpeculativeExecutionState {
+    /// Returns the state for executing on top of the post-state of the given block, i.e. as if in
+    /// the block which follows it.
+    fn from(block: &Block) -> Self {
+        let block_header = block.header();
+        SpeculativeExecutionState {
+            state_root_hash: *block_header.state_root_hash(),
+            block_time: block_header.timestamp(),
+            protocol_version: block_header.protocol_version(),
+            block_height: block_header.height() + 1,
+            era_id: block_header.next_block_era_id(),
+            parent_hash: *block.hash(),
+            proposer: Box::new(block.body().proposer().clone()),
+        }
+    }
+}
+
+/// Overrides applied on top of the prestate of a speculative execution.
+///
+/// None of the overrides are ever committed: the state overrides are written to a scratch copy of
+/// global state which is discarded once the deploys have been executed.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct SpeculativeExecutionOverrides {
+    /// Values written to global state before executing, replacing any existing values.
+    pub state: Vec<(Key, StoredValue)>,
+    /// Block time to execute with, in place of that of the original block.
+    pub block_time: Option<Timestamp>,
+    /// Era in which to execute, in place of that of the original block.
+    pub era_id: Option<EraId>,
 }
 
 /// State to use to construct the next block in the blockchain. Includes the state root hash for the
@@ -162,6 +216,53 @@ impl ExecutionPreState {
 
 type ExecQueue = Arc<Mutex<BTreeMap<u64, (FinalizedBlock, Vec<Deploy>, Vec<Deploy>)>>>;
 
+/// Name of the directory, within the storage directory, holding the marks of reachable tries
+/// while historical global state is pruned.
+const TRIE_GC_MARKS_DIR: &str = "trie_gc_marks";
+/// Number of block headers read at a time when recording the state roots being pruned.
+const PRUNED_STATE_ROOTS_BATCH_SIZE: u64 = 1_000;
+
+/// Settings for pruning historical global state.
+#[derive(Clone)]
+struct GlobalStatePruning {
+    /// The number of most recent eras for which global state is retained.
+    retained_eras: u64,
+    /// The on-disk table in which the reachable tries are marked, locked by the pruning in
+    /// progress, if any.
+    marks: Arc<tokio::sync::Mutex<TrieGcMarks>>,
+}
+
+impl GlobalStatePruning {
+    /// Locks the marks and starts recording the tries written to global state, so that the tries
+    /// written by blocks executed before the pruning marks the retained tries are not deleted.
+    ///
+    /// Returns `None` if a previous pruning is still in progress.
+    fn schedule(self, engine_state: Arc<EngineState<LmdbGlobalState>>) -> Option<ScheduledPruning> {
+        let marks = self.marks.try_lock_owned().ok()?;
+        engine_state.get_state().begin_trie_gc();
+        Some(ScheduledPruning {
+            engine_state,
+            retained_eras: self.retained_eras,
+            marks,
+        })
+    }
+}
+
+/// A global state pruning which has started recording the tries written to global state.
+///
+/// Recording stops when it is dropped, before the marks are unlocked for the next pruning.
+struct ScheduledPruning {
+    engine_state: Arc<EngineState<LmdbGlobalState>>,
+    retained_eras: u64,
+    marks: tokio::sync::OwnedMutexGuard<TrieGcMarks>,
+}
+
+impl Drop for ScheduledPruning {
+    fn drop(&mut self) {
+        self.engine_state.get_state().end_trie_gc();
+    }
+}
+
 #[derive(Debug, From, Serialize)]
 pub(crate) enum Event {
     #[from]
@@ -198,6 +299,9 @@ pub(crate) struct ContractRuntime {
     exec_queue: ExecQueue,
     /// Cached instance of a [`SystemContractRegistry`].
     system_contract_registry: Option<SystemContractRegistry>,
+    /// Settings for pruning historical global state, if enabled.
+    #[data_size(skip)]
+    global_state_pruning: Option<GlobalStatePruning>,
 }
 
 impl Debug for ContractRuntime {
@@ -213,6 +317,7 @@ where
         + From<ControlAnnouncement>
         + From<NetworkRequest<Message>>
         + From<MarkBlockCompletedRequest>
+        + From<StorageRequest>
         + Send,
 {
     type Event = Event;
@@ -258,7 +363,7 @@ impl ContractRuntime {
         };
 
         match Message::new_get_response(&fetched_or_not_found) {
-            Ok(message) => effect_builder.send_message(sender, message).ignore(),
+            Ok(message) => effect_builder.enqueue_message(sender, message).ignore(),
             Err(error) => {
                 error!("failed to create get-response: {}", error);
                 Effects::new()
@@ -306,6 +411,7 @@ impl ContractRuntime {
             + From<ContractRuntimeAnnouncement>
             + From<ControlAnnouncement>
             + From<MarkBlockCompletedRequest>
+            + From<StorageRequest>
             + Send,
     {
         match request {
@@ -522,6 +628,7 @@ impl ContractRuntime {
                 let exec_queue = Arc::clone(&self.exec_queue);
                 let execution_pre_state = Arc::clone(&self.execution_pre_state);
                 let protocol_version = self.protocol_version;
+                let global_state_pruning = self.global_state_pruning.clone();
                 if self.execution_pre_state.lock().unwrap().next_block_height
                     == finalized_block.height()
                 {
@@ -533,6 +640,7 @@ impl ContractRuntime {
                             execution_pre_state,
                             effect_builder,
                             protocol_version,
+                            global_state_pruning,
                             finalized_block,
                             deploys,
                             transfers,
@@ -564,6 +672,26 @@ impl ContractRuntime {
                 }
                 .ignore()
             }
+            ContractRuntimeRequest::GetKeysByPrefix {
+                get_keys_by_prefix_request,
+                responder,
+            } => {
+                trace!(?get_keys_by_prefix_request, "get keys by prefix request");
+                let engine_state = Arc::clone(&self.engine_state);
+                let metrics = Arc::clone(&self.metrics);
+                async move {
+                    let correlation_id = CorrelationId::new();
+                    let start = Instant::now();
+                    let result =
+                        engine_state.get_keys_by_prefix(correlation_id, get_keys_by_prefix_request);
+                    metrics
+                        .get_keys_by_prefix
+                        .observe(start.elapsed().as_secs_f64());
+                    trace!(?result, "get keys by prefix result");
+                    responder.respond(result).await
+                }
+                .ignore()
+            }
             ContractRuntimeRequest::FindMissingDescendantTrieKeys {
                 trie_key,
                 responder,
@@ -586,12 +714,39 @@ impl ContractRuntime {
             ContractRuntimeRequest::SpeculativeDeployExecution {
                 execution_prestate,
                 deploy,
+                overrides,
+                responder,
+            } => {
+                let engine_state = Arc::clone(&self.engine_state);
+                async move {
+                    let result = run_intensive_task(move || {
+                        execute_only(
+                            engine_state.as_ref(),
+                            execution_prestate,
+                            (*deploy).into(),
+                            overrides,
+                        )
+                    })
+                    .await;
+                    responder.respond(result).await
+                }
+                .ignore()
+            }
+            ContractRuntimeRequest::SpeculativeDeploysExecution {
+                execution_prestate,
+                deploys,
+                overrides,
                 responder,
             } => {
                 let engine_state = Arc::clone(&self.engine_state);
                 async move {
                     let result = run_intensive_task(move || {
-                        execute_only(engine_state.as_ref(), execution_prestate, (*deploy).into())
+                        execute_only_sequence(
+                            engine_state.as_ref(),
+                            execution_prestate,
+                            deploys,
+                            overrides,
+                        )
                     })
                     .await;
                     responder.respond(result).await
@@ -639,6 +794,19 @@ impl ContractRuntime {
         )?);
 
         let global_state = LmdbGlobalState::empty(environment, trie_store)?;
+        let global_state_pruning = match contract_runtime_config.retained_eras() {
+            Some(retained_eras) => {
+                let marks_dir = storage_dir.join(TRIE_GC_MARKS_DIR);
+                fs::create_dir_all(&marks_dir)?;
+                let marks =
+                    TrieGcMarks::new(marks_dir, contract_runtime_config.max_global_state_size())?;
+                Some(GlobalStatePruning {
+                    retained_eras,
+                    marks: Arc::new(tokio::sync::Mutex::new(marks)),
+                })
+            }
+            None => None,
+        };
         let engine_config = EngineConfig::new(
             contract_runtime_config.max_query_depth(),
             max_associated_keys,
@@ -661,6 +829,7 @@ impl ContractRuntime {
             protocol_version,
             exec_queue: Arc::new(Mutex::new(BTreeMap::new())),
             system_contract_registry: None,
+            global_state_pruning,
         })
     }
 
@@ -771,6 +940,7 @@ impl ContractRuntime {
         execution_pre_state: Arc<Mutex<ExecutionPreState>>,
         effect_builder: EffectBuilder<REv>,
         protocol_version: ProtocolVersion,
+        global_state_pruning: Option<GlobalStatePruning>,
         finalized_block: FinalizedBlock,
         deploys: Vec<Deploy>,
         transfers: Vec<Deploy>,
@@ -779,12 +949,17 @@ impl ContractRuntime {
             + From<ContractRuntimeAnnouncement>
             + From<ControlAnnouncement>
             + From<MarkBlockCompletedRequest>
+            + From<StorageRequest>
             + Send,
     {
         let current_execution_pre_state = execution_pre_state.lock().unwrap().clone();
+        let engine_state_for_pruning = Arc::clone(&engine_state);
+        let metrics_for_pruning = Arc::clone(&metrics);
         let BlockAndExecutionEffects {
             block,
             execution_results,
+            contract_messages,
+            invoked_contracts,
             maybe_step_effect_and_upcoming_era_validators,
         } = match run_intensive_task(move || {
             execute_finalized_block(
@@ -803,6 +978,22 @@ impl ContractRuntime {
             Err(error) => return fatal!(effect_builder, "{}", error).await,
         };
 
+        // The pruning is scheduled before the next block can be executed, as its tries must be
+        // recorded for the pruning not to delete any of them.
+        let maybe_pruning = global_state_pruning
+            .filter(|_| block.header().is_switch_block())
+            .and_then(|global_state_pruning| {
+                let maybe_scheduled = global_state_pruning.schedule(engine_state_for_pruning);
+                if maybe_scheduled.is_none() {
+                    info!(
+                        era_id = %block.header().era_id(),
+                        "not pruning global state: previous pruning still in progress"
+                    );
+                }
+                maybe_scheduled
+            })
+            .map(|scheduled_pruning| (scheduled_pruning, block.header().clone()));
+
         let new_execution_pre_state = ExecutionPreState::from_block_header(block.header());
         *execution_pre_state.lock().unwrap() = new_execution_pre_state.clone();
 
@@ -810,7 +1001,12 @@ impl ContractRuntime {
 
         let block_height = block.height();
         effect_builder
-            .announce_new_linear_chain_block(block, execution_results)
+            .announce_new_linear_chain_block(
+                block,
+                execution_results,
+                contract_messages,
+                invoked_contracts,
+            )
             .await;
 
         effect_builder.mark_block_completed(block_height).await;
@@ -829,6 +1025,16 @@ impl ContractRuntime {
                 .await;
         }
 
+        // Pruning runs in the background, concurrently with the execution of subsequent blocks.
+        if let Some((scheduled_pruning, switch_block_header)) = maybe_pruning {
+            tokio::spawn(Self::prune_global_state(
+                metrics_for_pruning,
+                effect_builder,
+                scheduled_pruning,
+                switch_block_header,
+            ));
+        }
+
         // If the child is already finalized, start execution.
         let next_block = {
             // needed to help this async block impl Send (the MutexGuard lives too long)
@@ -842,6 +1048,162 @@ impl ContractRuntime {
         }
     }
 
+    /// Records the state roots of the blocks in `[lowest_pruned_height, lowest_retained_height)`
+    /// which are not among the sorted `retained_state_root_hashes`.
+    async fn record_pruned_state_roots<REv>(
+        effect_builder: EffectBuilder<REv>,
+        lowest_pruned_height: u64,
+        lowest_retained_height: u64,
+        retained_state_root_hashes: &[Digest],
+    ) where
+        REv: From<StorageRequest>,
+    {
+        let mut batch_low = lowest_pruned_height;
+        while batch_low < lowest_retained_height {
+            let batch_high = lowest_retained_height
+                .min(batch_low.saturating_add(PRUNED_STATE_ROOTS_BATCH_SIZE))
+                - 1;
+            let batch_id = BlockHeadersBatchId::new(batch_high, batch_low);
+            match effect_builder
+                .get_block_header_batch_from_storage(batch_id)
+                .await
+            {
+                Some(batch) => {
+                    let pruned_state_roots = batch
+                        .inner()
+                        .iter()
+                        .filter(|block_header| {
+                            retained_state_root_hashes
+                                .binary_search(block_header.state_root_hash())
+                                .is_err()
+                        })
+                        .map(|block_header| {
+                            (*block_header.state_root_hash(), block_header.height())
+                        })
+                        .collect();
+                    effect_builder
+                        .put_pruned_state_roots_to_storage(pruned_state_roots)
+                        .await;
+                }
+                None => {
+                    debug!(%batch_id, "not recording pruned state roots: missing block headers");
+                }
+            }
+            batch_low = batch_high + 1;
+        }
+    }
+
+    /// Records the lowest block whose state is retained, then removes every trie which is only
+    /// reachable from the state roots of blocks older than the `retained_eras` most recent eras.
+    async fn prune_global_state<REv>(
+        metrics: Arc<Metrics>,
+        effect_builder: EffectBuilder<REv>,
+        scheduled_pruning: ScheduledPruning,
+        switch_block_header: BlockHeader,
+    ) where
+        REv: From<StorageRequest> + Send,
+    {
+        // The switch block's era is the most recent one, and is always retained.
+        let lowest_retained_era_id = match switch_block_header
+            .era_id()
+            .value()
+            .checked_sub(scheduled_pruning.retained_eras.saturating_sub(1))
+        {
+            Some(era_id) if era_id > 0 => EraId::from(era_id),
+            _ => return,
+        };
+        let lowest_retained_height = match effect_builder
+            .get_switch_block_header_at_era_id_from_storage(
+                lowest_retained_era_id.saturating_sub(1),
+            )
+            .await
+        {
+            Some(previous_switch_block_header) => previous_switch_block_header.height() + 1,
+            None => {
+                warn!(
+                    era_id = %lowest_retained_era_id,
+                    "not pruning global state: missing switch block preceding lowest retained era"
+                );
+                return;
+            }
+        };
+        let previous_lowest_retained_height = effect_builder
+            .get_lowest_retained_state_height_from_storage()
+            .await;
+        if previous_lowest_retained_height.map_or(false, |previous_height| {
+            previous_height >= lowest_retained_height
+        }) {
+            return;
+        }
+
+        // Blocks below the available block range have no complete global state to retain.
+        let available_block_range = effect_builder
+            .get_available_block_range_from_storage()
+            .await;
+        let batch_id = BlockHeadersBatchId::new(
+            switch_block_header.height(),
+            lowest_retained_height.max(available_block_range.low()),
+        );
+        let state_root_hashes: Vec<Digest> = match effect_builder
+            .get_block_header_batch_from_storage(batch_id)
+            .await
+        {
+            Some(batch) => batch
+                .inner()
+                .iter()
+                .map(|block_header| *block_header.state_root_hash())
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .collect(),
+            None => {
+                warn!(%batch_id, "not pruning global state: missing retained block headers");
+                return;
+            }
+        };
+
+        // Remember which state roots are being pruned, so that queries against them can be told
+        // apart from queries against state roots which never existed.
+        let lowest_pruned_height =
+            previous_lowest_retained_height.unwrap_or_else(|| available_block_range.low());
+        Self::record_pruned_state_roots(
+            effect_builder,
+            lowest_pruned_height,
+            lowest_retained_height,
+            &state_root_hashes,
+        )
+        .await;
+        // Queries against the state about to be pruned are rejected from here on, rather than
+        // failing part way through.
+        effect_builder
+            .put_lowest_retained_state_height_to_storage(lowest_retained_height)
+            .await;
+
+        let start = Instant::now();
+        // This is not run as an intensive task, as it would hold up block execution for its whole
+        // duration.
+        let result = tokio::task::spawn_blocking(move || {
+            scheduled_pruning
+                .engine_state
+                .get_state()
+                .prune_unreachable_tries(&state_root_hashes, &scheduled_pruning.marks, |_| ())
+        })
+        .await
+        .expect("global state pruning task panicked");
+        metrics
+            .latest_prune_global_state
+            .set(start.elapsed().as_secs_f64());
+
+        match result {
+            Ok(summary) => info!(
+                lowest_retained_height,
+                removed_tries = summary.removed_tries(),
+                removed_bytes = summary.removed_bytes(),
+                "pruned global state"
+            ),
+            Err(error) => error!(%error, "failed to prune global state"),
+        }
+    }
+
     /// Reads the trie (or chunk of a trie) under the given key and index.
     pub(crate) fn get_trie(
         &self,
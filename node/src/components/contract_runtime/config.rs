@@ -31,6 +31,12 @@ pub struct Config {
     ///
     /// Defaults to `false`.
     enable_manual_sync: Option<bool>,
+    /// The number of most recent eras for which global state is retained.
+    ///
+    /// Once a switch block has been executed, tries which are only reachable from the state roots
+    /// of blocks in older eras are removed from the global state store.  Defaults to unset, in
+    /// which case historical global state is never pruned.
+    retained_eras: Option<u64>,
 }
 
 impl Config {
@@ -54,6 +60,10 @@ impl Config {
         self.enable_manual_sync
             .unwrap_or(DEFAULT_MANUAL_SYNC_ENABLED)
     }
+
+    pub(crate) fn retained_eras(&self) -> Option<u64> {
+        self.retained_eras
+    }
 }
 
 impl Default for Config {
@@ -63,6 +73,7 @@ impl Default for Config {
             max_readers: Some(DEFAULT_MAX_READERS),
             max_query_depth: Some(DEFAULT_MAX_QUERY_DEPTH),
             enable_manual_sync: Some(DEFAULT_MANUAL_SYNC_ENABLED),
+            retained_eras: None,
         }
     }
 }
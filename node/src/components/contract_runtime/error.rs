@@ -1,5 +1,7 @@
 //! Errors that the contract runtime component may raise.
 
+use std::io;
+
 use serde::Serialize;
 use thiserror::Error;
 
@@ -26,6 +28,9 @@ pub(crate) enum ConfigError {
     /// Error initializing execution engine.
     #[error("failed to initialize execution engine: {0}")]
     EngineState(#[from] EngineStateError),
+    /// Error creating the directory holding the marks of reachable tries.
+    #[error("failed to create directory for global state pruning: {0}")]
+    TrieGcMarksDir(#[from] io::Error),
 }
 
 /// An error during block execution.
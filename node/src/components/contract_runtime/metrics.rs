@@ -43,6 +43,10 @@ const GET_ERA_VALIDATORS_HELP: &str =
 const GET_BIDS_NAME: &str = "contract_runtime_get_bids";
 const GET_BIDS_HELP: &str = "time in seconds to get bids from global state";
 
+const GET_KEYS_BY_PREFIX_NAME: &str = "contract_runtime_get_keys_by_prefix";
+const GET_KEYS_BY_PREFIX_HELP: &str =
+    "time in seconds to get a page of keys by prefix from global state";
+
 const MISSING_TRIE_KEYS_NAME: &str = "contract_runtime_missing_trie_keys";
 const MISSING_TRIE_KEYS_HELP: &str = "time in seconds to get missing trie keys";
 
@@ -61,6 +65,10 @@ const EXEC_BLOCK_HELP: &str = "time in seconds to execute all deploys in a block
 const LATEST_COMMIT_STEP_NAME: &str = "contract_runtime_latest_commit_step";
 const LATEST_COMMIT_STEP_HELP: &str = "duration in seconds of latest commit step at era end";
 
+const LATEST_PRUNE_GLOBAL_STATE_NAME: &str = "contract_runtime_latest_prune_global_state";
+const LATEST_PRUNE_GLOBAL_STATE_HELP: &str =
+    "duration in seconds of latest pruning of historical global state";
+
 /// Metrics for the contract runtime component.
 #[derive(Debug)]
 pub struct Metrics {
@@ -73,12 +81,14 @@ pub struct Metrics {
     pub(super) get_validator_weights: Histogram,
     pub(super) get_era_validators: Histogram,
     pub(super) get_bids: Histogram,
+    pub(super) get_keys_by_prefix: Histogram,
     pub(super) missing_trie_keys: Histogram,
     pub(super) put_trie: Histogram,
     pub(super) get_trie: Histogram,
     pub(super) chain_height: IntGauge,
     pub(super) exec_block: Histogram,
     pub(super) latest_commit_step: Gauge,
+    pub(super) latest_prune_global_state: Gauge,
     registry: Registry,
 }
 
@@ -103,6 +113,12 @@ impl Metrics {
         let latest_commit_step = Gauge::new(LATEST_COMMIT_STEP_NAME, LATEST_COMMIT_STEP_HELP)?;
         registry.register(Box::new(latest_commit_step.clone()))?;
 
+        let latest_prune_global_state = Gauge::new(
+            LATEST_PRUNE_GLOBAL_STATE_NAME,
+            LATEST_PRUNE_GLOBAL_STATE_HELP,
+        )?;
+        registry.register(Box::new(latest_prune_global_state.clone()))?;
+
         Ok(Metrics {
             run_execute: utils::register_histogram_metric(
                 registry,
@@ -158,6 +174,12 @@ impl Metrics {
                 GET_BIDS_HELP,
                 common_buckets.clone(),
             )?,
+            get_keys_by_prefix: utils::register_histogram_metric(
+                registry,
+                GET_KEYS_BY_PREFIX_NAME,
+                GET_KEYS_BY_PREFIX_HELP,
+                common_buckets.clone(),
+            )?,
             get_trie: utils::register_histogram_metric(
                 registry,
                 GET_TRIE_NAME,
@@ -184,6 +206,7 @@ impl Metrics {
                 common_buckets,
             )?,
             latest_commit_step,
+            latest_prune_global_state,
             registry: registry.clone(),
         })
     }
@@ -200,11 +223,13 @@ impl Drop for Metrics {
         unregister_metric!(self.registry, self.get_validator_weights);
         unregister_metric!(self.registry, self.get_era_validators);
         unregister_metric!(self.registry, self.get_bids);
+        unregister_metric!(self.registry, self.get_keys_by_prefix);
         unregister_metric!(self.registry, self.missing_trie_keys);
         unregister_metric!(self.registry, self.put_trie);
         unregister_metric!(self.registry, self.get_trie);
         unregister_metric!(self.registry, self.chain_height);
         unregister_metric!(self.registry, self.exec_block);
         unregister_metric!(self.registry, self.latest_commit_step);
+        unregister_metric!(self.registry, self.latest_prune_global_state);
     }
 }
@@ -9,13 +9,16 @@ use casper_execution_engine::{
         ExecutionResult as EngineExecutionResult, GetEraValidatorsRequest, RewardItem, StepError,
         StepRequest, StepSuccess,
     },
-    shared::{additive_map::AdditiveMap, newtypes::CorrelationId, transform::Transform},
-    storage::global_state::lmdb::LmdbGlobalState,
+    shared::{
+        additive_map::AdditiveMap, execution_journal::ExecutionJournal, newtypes::CorrelationId,
+        transform::Transform,
+    },
+    storage::global_state::{lmdb::LmdbGlobalState, scratch::ScratchGlobalState},
 };
 use casper_hashing::Digest;
 use casper_types::{
-    bytesrepr::ToBytes, CLValue, DeployHash, EraId, ExecutionResult, Key, ProtocolVersion,
-    PublicKey, U512,
+    bytesrepr::ToBytes, CLValue, ContractMessage, DeployHash, EraId, ExecutionResult, Key,
+    ProtocolVersion, PublicKey, StoredValue, U512,
 };
 
 use crate::{
@@ -23,7 +26,7 @@ use crate::{
         consensus::EraReport,
         contract_runtime::{
             error::BlockExecutionError, types::StepEffectAndUpcomingEraValidators,
-            BlockAndExecutionEffects, ExecutionPreState, Metrics,
+            BlockAndExecutionEffects, ExecutionPreState, Metrics, SpeculativeExecutionResults,
         },
     },
     types::{error::BlockCreationError, Block, Deploy, DeployHeader, FinalizedBlock},
@@ -33,7 +36,7 @@ use casper_execution_engine::{
     storage::global_state::{CommitProvider, StateProvider},
 };
 
-use super::SpeculativeExecutionState;
+use super::{SpeculativeExecutionOverrides, SpeculativeExecutionState};
 
 /// Executes a finalized block.
 #[allow(clippy::too_many_arguments)]
@@ -61,6 +64,8 @@ pub fn execute_finalized_block(
     let mut state_root_hash = pre_state_root_hash;
     let mut execution_results: Vec<(_, DeployHeader, ExecutionResult)> =
         Vec::with_capacity(deploys.len() + transfers.len());
+    let mut contract_messages = Vec::new();
+    let mut invoked_contracts = Vec::new();
     // Run any deploys that must be executed
     let block_time = finalized_block.timestamp().millis();
     let start = Instant::now();
@@ -72,12 +77,16 @@ pub fn execute_finalized_block(
     for deploy in deploys.into_iter().chain(transfers) {
         let deploy_hash = *deploy.id();
         let deploy_header = deploy.header().clone();
+        let deploy_invoked_contracts = deploy.invoked_contracts();
         let execute_request = ExecuteRequest::new(
             state_root_hash,
             block_time,
             vec![DeployItem::from(deploy)],
             protocol_version,
             *finalized_block.proposer(),
+            finalized_block.height(),
+            finalized_block.era_id(),
+            *parent_hash.inner(),
         );
 
         // TODO: this is currently working coincidentally because we are passing only one
@@ -89,7 +98,7 @@ pub fn execute_finalized_block(
 
         trace!(?deploy_hash, ?result, "deploy execution result");
         // As for now a given state is expected to exist.
-        let (state_hash, execution_result) = commit_execution_effects(
+        let (state_hash, execution_result, messages) = commit_execution_effects(
             &scratch_state,
             metrics.clone(),
             state_root_hash,
@@ -97,6 +106,12 @@ pub fn execute_finalized_block(
             result,
         )?;
         execution_results.push((deploy_hash, deploy_header, execution_result));
+        if !messages.is_empty() {
+            contract_messages.push((deploy_hash, messages));
+        }
+        if !deploy_invoked_contracts.is_empty() {
+            invoked_contracts.push((deploy_hash, deploy_invoked_contracts));
+        }
         state_root_hash = state_hash;
     }
 
@@ -206,6 +221,8 @@ pub fn execute_finalized_block(
     Ok(BlockAndExecutionEffects {
         block,
         execution_results,
+        contract_messages,
+        invoked_contracts,
         maybe_step_effect_and_upcoming_era_validators,
     })
 }
@@ -217,7 +234,7 @@ fn commit_execution_effects<S>(
     state_root_hash: Digest,
     deploy_hash: DeployHash,
     execution_results: ExecutionResults,
-) -> Result<(Digest, ExecutionResult), BlockExecutionError>
+) -> Result<(Digest, ExecutionResult, Vec<ContractMessage>), BlockExecutionError>
 where
     S: StateProvider + CommitProvider,
     S::Error: Into<execution::Error>,
@@ -227,6 +244,7 @@ where
         .exactly_one()
         .map_err(|_| BlockExecutionError::MoreThanOneExecutionResult)?;
     let json_execution_result = ExecutionResult::from(&ee_execution_result);
+    let messages = ee_execution_result.messages().to_vec();
 
     let execution_effect: AdditiveMap<Key, Transform> = match ee_execution_result {
         EngineExecutionResult::Success {
@@ -255,7 +273,7 @@ where
     .into();
     let new_state_root =
         commit_transforms(engine_state, metrics, state_root_hash, execution_effect)?;
-    Ok((new_state_root, json_execution_result))
+    Ok((new_state_root, json_execution_result, messages))
 }
 
 fn commit_transforms<S>(
@@ -282,30 +300,27 @@ where
 /// Execute the transaction without commiting the effects.
 /// Intended to be used for discovery operations on read-only nodes.
 ///
+/// If any state overrides are given, they are written to a scratch copy of global state on top of
+/// which the deploy is then executed, leaving the trie store untouched.
+///
 /// Returns effects of the execution.
-pub fn execute_only<S>(
-    engine_state: &EngineState<S>,
+pub fn execute_only(
+    engine_state: &EngineState<LmdbGlobalState>,
     execution_state: SpeculativeExecutionState,
     deploy: DeployItem,
-) -> Result<Option<ExecutionResult>, engine_state::Error>
-where
-    S: StateProvider + CommitProvider,
-    S::Error: Into<execution::Error>,
-{
-    let SpeculativeExecutionState {
-        state_root_hash,
-        block_time,
-        protocol_version,
-    } = execution_state;
+    overrides: SpeculativeExecutionOverrides,
+) -> Result<Option<ExecutionResult>, engine_state::Error> {
     let deploy_hash = deploy.deploy_hash;
-    let execute_request = ExecuteRequest::new(
-        state_root_hash,
-        block_time.millis(),
-        vec![deploy],
-        protocol_version,
-        PublicKey::System,
-    );
-    let results = execute(engine_state, None, execute_request);
+    let state_root_hash = execution_state.state_root_hash;
+    let (execute_request, state_overrides) =
+        speculative_execute_request(execution_state, overrides, vec![deploy]);
+    let results = if state_overrides.is_empty() {
+        execute(engine_state, None, execute_request)
+    } else {
+        let scratch_state = engine_state.get_scratch_engine_state();
+        apply_state_overrides(&scratch_state, state_root_hash, state_overrides)?;
+        execute(&scratch_state, None, execute_request)
+    };
     results.map(|mut execution_results| {
         let len = execution_results.len();
         if len != 1 {
@@ -324,6 +339,116 @@ where
     })
 }
 
+/// Executes the deploys in order without commiting their effects, each on top of the effects of
+/// those before it, and of any state overrides.
+///
+/// The effects are cached in memory while executing, and the resulting state root hash, which is
+/// not referred to by any block, is computed from them without writing any tries to the trie store.
+pub fn execute_only_sequence(
+    engine_state: &EngineState<LmdbGlobalState>,
+    execution_state: SpeculativeExecutionState,
+    deploys: Vec<Deploy>,
+    overrides: SpeculativeExecutionOverrides,
+) -> Result<SpeculativeExecutionResults, engine_state::Error> {
+    let state_root_hash = execution_state.state_root_hash;
+    let (request_template, state_overrides) =
+        speculative_execute_request(execution_state, overrides, vec![]);
+
+    // Create a new EngineState that reads from LMDB but only caches changes in memory.
+    let scratch_state = engine_state.get_scratch_engine_state();
+    apply_state_overrides(&scratch_state, state_root_hash, state_overrides)?;
+    let mut execution_results = Vec::with_capacity(deploys.len());
+    let mut combined_effects: AdditiveMap<Key, Transform> = AdditiveMap::new();
+
+    for deploy in deploys {
+        let deploy_hash = *deploy.id();
+        let execute_request = ExecuteRequest {
+            deploys: vec![DeployItem::from(deploy)],
+            proposer: request_template.proposer.clone(),
+            ..request_template
+        };
+        let ee_execution_result = execute(&scratch_state, None, execute_request)?
+            .into_iter()
+            .exactly_one()
+            .map_err(|_| {
+                warn!(?deploy_hash, "expected exactly one execution result");
+                engine_state::Error::Deploy
+            })?;
+        let execution_result = ExecutionResult::from(&ee_execution_result);
+        let effects: AdditiveMap<Key, Transform> =
+            ee_execution_result.execution_journal().clone().into();
+        for (key, transform) in effects.iter() {
+            combined_effects.insert_add(*key, transform.clone());
+        }
+        // The scratch state does not modify the state root hash, so it need not be tracked.
+        let _ = scratch_state.apply_effect(CorrelationId::new(), state_root_hash, effects)?;
+        execution_results.push((deploy_hash, execution_result));
+    }
+
+    let post_state_hash =
+        engine_state.scratch_state_root_hash(state_root_hash, scratch_state.into_inner())?;
+    let mut combined_effects: Vec<(Key, Transform)> = combined_effects.into_iter().collect();
+    combined_effects.sort_by(|(key1, _), (key2, _)| key1.cmp(key2));
+
+    Ok(SpeculativeExecutionResults {
+        execution_results,
+        effect: ExecutionJournal::new(combined_effects).into(),
+        post_state_hash,
+    })
+}
+
+/// Returns the request for speculatively executing `deploys` with the given prestate and
+/// overrides, along with the state overrides, which are left to the caller to apply.
+fn speculative_execute_request(
+    execution_state: SpeculativeExecutionState,
+    overrides: SpeculativeExecutionOverrides,
+    deploys: Vec<DeployItem>,
+) -> (ExecuteRequest, Vec<(Key, StoredValue)>) {
+    let SpeculativeExecutionState {
+        state_root_hash,
+        block_time,
+        protocol_version,
+        block_height,
+        era_id,
+        parent_hash,
+        proposer,
+    } = execution_state;
+    let SpeculativeExecutionOverrides {
+        state: state_overrides,
+        block_time: block_time_override,
+        era_id: era_id_override,
+    } = overrides;
+    let execute_request = ExecuteRequest::new(
+        state_root_hash,
+        block_time_override.unwrap_or(block_time).millis(),
+        deploys,
+        protocol_version,
+        *proposer,
+        block_height,
+        era_id_override.unwrap_or(era_id),
+        *parent_hash.inner(),
+    );
+    (execute_request, state_overrides)
+}
+
+/// Writes `state_overrides` to the scratch state on top of `state_root_hash`.
+fn apply_state_overrides(
+    scratch_state: &EngineState<ScratchGlobalState>,
+    state_root_hash: Digest,
+    state_overrides: Vec<(Key, StoredValue)>,
+) -> Result<(), engine_state::Error> {
+    if state_overrides.is_empty() {
+        return Ok(());
+    }
+    let override_effects: AdditiveMap<Key, Transform> = state_overrides
+        .into_iter()
+        .map(|(key, stored_value)| (key, Transform::Write(stored_value)))
+        .collect();
+    // The scratch state does not modify the state root hash, so it need not be tracked.
+    let _ = scratch_state.apply_effect(CorrelationId::new(), state_root_hash, override_effects)?;
+    Ok(())
+}
+
 fn execute<S>(
     engine_state: &EngineState<S>,
     metrics: Option<Arc<Metrics>>,
@@ -0,0 +1,199 @@
+use num::Zero;
+use prometheus::Registry;
+use tempfile::TempDir;
+
+use casper_execution_engine::core::engine_state::executable_deploy_item::ExecutableDeployItem;
+use casper_types::{
+    bytesrepr::Bytes, runtime_args, system::auction::DelegationRate, testing::TestRng,
+    ExecutionResult, Motes, RuntimeArgs, SecretKey, TimeDiff, U512,
+};
+
+use super::*;
+use crate::{
+    types::{
+        chainspec::{AccountConfig, AccountsConfig, ValidatorConfig},
+        ActivationPoint,
+    },
+    utils::Loadable,
+};
+
+/// The amount transferred to the new account in the first of two chained deploys.
+const FIRST_TRANSFER_AMOUNT: u64 = 1_000_000_000_000;
+/// The amount transferred onwards by the new account in the second of two chained deploys.
+const SECOND_TRANSFER_AMOUNT: u64 = 10_000_000_000;
+
+/// A contract runtime with genesis committed for a single funded validator account.
+struct TestFixture {
+    contract_runtime: ContractRuntime,
+    /// The execution state of the block following the genesis block.
+    execution_state: SpeculativeExecutionState,
+    validator_key: SecretKey,
+    _storage_dir: TempDir,
+}
+
+impl TestFixture {
+    fn new(rng: &mut TestRng) -> Self {
+        let (mut chainspec, chainspec_raw_bytes) =
+            <(Chainspec, ChainspecRawBytes)>::from_resources("local");
+        let validator_key = SecretKey::random(rng);
+        let validator_public_key = PublicKey::from(&validator_key);
+        let validator_config =
+            ValidatorConfig::new(Motes::new(U512::from(u64::MAX)), DelegationRate::zero());
+        let account = AccountConfig::new(
+            validator_public_key.clone(),
+            Motes::new(U512::from(u64::MAX)),
+            Some(validator_config),
+        );
+        chainspec.network_config.accounts_config = AccountsConfig::new(vec![account], vec![]);
+        let genesis_timestamp = Timestamp::now();
+        chainspec.protocol_config.activation_point = ActivationPoint::Genesis(genesis_timestamp);
+
+        let storage_dir = tempfile::tempdir().expect("should create temp dir");
+        let contract_runtime = ContractRuntime::new(
+            chainspec.protocol_config.version,
+            storage_dir.path(),
+            &Config::default(),
+            chainspec.wasm_config,
+            chainspec.system_costs_config,
+            chainspec.core_config.max_associated_keys,
+            chainspec.core_config.max_runtime_call_stack_height,
+            chainspec.core_config.minimum_delegation_amount,
+            chainspec.core_config.strict_argument_checking,
+            chainspec.core_config.vesting_schedule_period.millis(),
+            &Registry::default(),
+        )
+        .expect("should create contract runtime");
+        let genesis_success = contract_runtime
+            .commit_genesis(&chainspec, &chainspec_raw_bytes)
+            .expect("should commit genesis");
+
+        let execution_state = SpeculativeExecutionState {
+            state_root_hash: genesis_success.post_state_hash,
+            block_time: genesis_timestamp,
+            protocol_version: chainspec.protocol_config.version,
+            block_height: 1,
+            era_id: EraId::new(0),
+            parent_hash: BlockHash::random(rng),
+            proposer: Box::new(validator_public_key),
+        };
+
+        TestFixture {
+            contract_runtime,
+            execution_state,
+            validator_key,
+            _storage_dir: storage_dir,
+        }
+    }
+
+    fn execute_only_sequence(
+        &self,
+        deploys: Vec<Deploy>,
+    ) -> Result<SpeculativeExecutionResults, engine_state::Error> {
+        execute_only_sequence(
+            self.contract_runtime.engine_state(),
+            self.execution_state.clone(),
+            deploys,
+            SpeculativeExecutionOverrides::default(),
+        )
+    }
+}
+
+/// Returns a native transfer of `amount` motes from the account of `secret_key` to `target`.
+fn new_transfer(secret_key: &SecretKey, target: &PublicKey, amount: u64) -> Deploy {
+    let payment = ExecutableDeployItem::ModuleBytes {
+        module_bytes: Bytes::new(),
+        args: runtime_args! { "amount" => U512::from(100_000_000_u64) },
+    };
+    let session = ExecutableDeployItem::Transfer {
+        args: runtime_args! {
+            "amount" => U512::from(amount),
+            "target" => target.clone(),
+            "id" => Some(0_u64),
+        },
+    };
+    Deploy::new(
+        Timestamp::now(),
+        TimeDiff::from_seconds(60),
+        1,
+        vec![],
+        "casper-example".to_string(),
+        payment,
+        session,
+        secret_key,
+        None,
+    )
+}
+
+#[test]
+fn should_execute_each_deploy_on_top_of_the_previous_ones() {
+    let mut rng = TestRng::new();
+    let fixture = TestFixture::new(&mut rng);
+    let new_account_key = SecretKey::random(&mut rng);
+    let new_account = PublicKey::from(&new_account_key);
+    let target = PublicKey::from(&SecretKey::random(&mut rng));
+
+    // The second deploy is sent by the account created by the first.
+    let first_deploy = new_transfer(&fixture.validator_key, &new_account, FIRST_TRANSFER_AMOUNT);
+    let second_deploy = new_transfer(&new_account_key, &target, SECOND_TRANSFER_AMOUNT);
+    let deploy_hashes = vec![*first_deploy.id(), *second_deploy.id()];
+
+    let results = fixture
+        .execute_only_sequence(vec![first_deploy, second_deploy.clone()])
+        .expect("should execute");
+    assert_eq!(
+        results
+            .execution_results
+            .iter()
+            .map(|(deploy_hash, _)| *deploy_hash)
+            .collect::<Vec<_>>(),
+        deploy_hashes
+    );
+    for (deploy_hash, execution_result) in &results.execution_results {
+        assert!(
+            matches!(execution_result, ExecutionResult::Success { .. }),
+            "{} should succeed: {:?}",
+            deploy_hash,
+            execution_result
+        );
+    }
+    assert!(!results.effect.transforms.is_empty());
+    assert_ne!(
+        results.post_state_hash,
+        fixture.execution_state.state_root_hash
+    );
+
+    // On its own, the second deploy fails, as its account doesn't exist yet.
+    let results = fixture
+        .execute_only_sequence(vec![second_deploy])
+        .expect("should execute");
+    assert!(matches!(
+        results.execution_results[0].1,
+        ExecutionResult::Failure { .. }
+    ));
+}
+
+#[test]
+fn should_compute_post_state_hash_without_storing_it() {
+    let mut rng = TestRng::new();
+    let fixture = TestFixture::new(&mut rng);
+    let target = PublicKey::from(&SecretKey::random(&mut rng));
+    let deploy = new_transfer(&fixture.validator_key, &target, FIRST_TRANSFER_AMOUNT);
+
+    let results = fixture
+        .execute_only_sequence(vec![deploy.clone()])
+        .expect("should execute");
+    let engine_state = fixture.contract_runtime.engine_state();
+    assert_eq!(
+        engine_state
+            .get_trie_full(CorrelationId::new(), results.post_state_hash)
+            .expect("should read trie store"),
+        None,
+        "the post-state root should not be written to the trie store"
+    );
+
+    // The post-state hash is that which committing the effects would result in.
+    let repeated_results = fixture
+        .execute_only_sequence(vec![deploy])
+        .expect("should execute");
+    assert_eq!(repeated_results.post_state_hash, results.post_state_hash);
+}
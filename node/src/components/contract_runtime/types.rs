@@ -6,7 +6,10 @@ use casper_execution_engine::{
     core::engine_state::GetEraValidatorsRequest, shared::execution_journal::ExecutionJournal,
 };
 use casper_hashing::Digest;
-use casper_types::{EraId, ExecutionResult, ProtocolVersion, PublicKey, U512};
+use casper_types::{
+    ContractHash, ContractMessage, EraId, ExecutionEffect, ExecutionResult, ProtocolVersion,
+    PublicKey, U512,
+};
 
 use crate::types::{Block, DeployHash, DeployHeader};
 
@@ -100,10 +103,26 @@ pub struct BlockAndExecutionEffects {
     pub block: Box<Block>,
     /// The results from executing the deploys in the block.
     pub execution_results: Vec<(DeployHash, DeployHeader, ExecutionResult)>,
+    /// The messages emitted by contracts during the successful execution of deploys in the block.
+    pub contract_messages: Vec<(DeployHash, Vec<ContractMessage>)>,
+    /// The stored contracts invoked by hash from each deploy in the block, if any.
+    pub invoked_contracts: Vec<(DeployHash, Vec<ContractHash>)>,
     /// The [`ExecutionJournal`] and the upcoming validator sets determined by the `step`
     pub maybe_step_effect_and_upcoming_era_validators: Option<StepEffectAndUpcomingEraValidators>,
 }
 
+/// The outcome of speculatively executing a sequence of deploys, each on top of the effects of
+/// those before it.
+#[derive(Clone, Debug)]
+pub struct SpeculativeExecutionResults {
+    /// The result of executing each deploy, in execution order.
+    pub execution_results: Vec<(DeployHash, ExecutionResult)>,
+    /// The combined effects of executing all of the deploys.
+    pub effect: ExecutionEffect,
+    /// The global state root hash after executing all of the deploys.  No block refers to it.
+    pub post_state_hash: Digest,
+}
+
 impl BlockAndExecutionEffects {
     /// Gets the block.
     pub fn block(&self) -> &Block {
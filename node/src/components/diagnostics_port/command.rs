@@ -86,6 +86,8 @@ pub(super) enum Action {
     },
     /// Dump the event queues.
     DumpQueues,
+    /// Dump the scores of all peers.
+    DumpPeerScores,
     /// Close connection server-side.
     Quit,
 }
@@ -117,5 +119,8 @@ mod tests {
 
         let cmd = Command::from_line("dump-queues").expect("command parsing failed");
         assert!(matches!(cmd.action, Action::DumpQueues));
+
+        let cmd = Command::from_line("dump-peer-scores").expect("command parsing failed");
+        assert!(matches!(cmd.action, Action::DumpPeerScores));
     }
 }
@@ -28,10 +28,11 @@ use super::{
     util::ShowUnixAddr,
 };
 use crate::{
-    components::consensus::EraDump,
+    components::{consensus::EraDump, small_network::PeerScore},
     effect::{
         announcements::{ControlAnnouncement, QueueDumpFormat},
         diagnostics_port::DumpConsensusStateRequest,
+        requests::NetworkInfoRequest,
         EffectBuilder,
     },
     utils::display_error,
@@ -99,6 +100,23 @@ impl Display for Session {
     }
 }
 
+/// Scores of all peers, best scored first.
+#[derive(Debug, Serialize)]
+#[serde(transparent)]
+struct PeerScoresDump(Vec<PeerScore>);
+
+impl Display for PeerScoresDump {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for (idx, peer_score) in self.0.iter().enumerate() {
+            if idx > 0 {
+                writeln!(f)?;
+            }
+            Display::fmt(peer_score, f)?;
+        }
+        Ok(())
+    }
+}
+
 /// A serializer supporting multiple format variants that writes into a file.
 pub enum FileSerializer {
     /// JSON-format serializer.
@@ -195,7 +213,10 @@ impl Session {
         line: &str,
     ) -> io::Result<bool>
     where
-        REv: From<DumpConsensusStateRequest> + From<ControlAnnouncement> + Send,
+        REv: From<DumpConsensusStateRequest>
+            + From<ControlAnnouncement>
+            + From<NetworkInfoRequest>
+            + Send,
     {
         debug!(%line, "line received");
         match Command::from_line(line) {
@@ -276,6 +297,13 @@ impl Session {
                             }
                         };
                     }
+                    Action::DumpPeerScores => {
+                        let peer_scores = effect_builder.get_peer_scores().await;
+                        self.send_outcome(writer, &Outcome::success("dumping peer scores"))
+                            .await?;
+                        self.send_to_client(writer, &PeerScoresDump(peer_scores))
+                            .await?;
+                    }
                     Action::Quit => {
                         self.send_outcome(writer, &Outcome::success("goodbye!"))
                             .await?;
@@ -393,7 +421,10 @@ async fn handler<REv>(
     mut shutdown_receiver: watch::Receiver<()>,
 ) -> io::Result<()>
 where
-    REv: From<DumpConsensusStateRequest> + From<ControlAnnouncement> + Send,
+    REv: From<DumpConsensusStateRequest>
+        + From<ControlAnnouncement>
+        + From<NetworkInfoRequest>
+        + Send,
 {
     debug!("accepted new connection on diagnostics port");
 
@@ -433,7 +464,10 @@ pub(super) async fn server<REv>(
     listener: UnixListener,
     mut shutdown_receiver: watch::Receiver<()>,
 ) where
-    REv: From<DumpConsensusStateRequest> + From<ControlAnnouncement> + Send,
+    REv: From<DumpConsensusStateRequest>
+        + From<ControlAnnouncement>
+        + From<NetworkInfoRequest>
+        + Send,
 {
     let handling_shutdown_receiver = shutdown_receiver.clone();
     let mut next_client_id: u64 = 0;
@@ -544,6 +578,7 @@ mod tests {
 
         requests: {
             DumpConsensusStateRequest -> !;
+            NetworkInfoRequest -> !;
         }
 
         announcements: {}
@@ -15,7 +15,8 @@
 //!
 //! This component uses a ring buffer for outbound events providing some robustness against
 //! unintended subscriber disconnects, if a disconnected subscriber re-subscribes before the buffer
-//! has advanced past their last received event.
+//! has advanced past their last received event.  Optionally, events are also written to an
+//! on-disk log, allowing subscribers to replay any retained events, including across node restarts.
 //!
 //! For details about the SSE model and a list of supported SSEs, see:
 //! <https://github.com/CasperLabs/ceps/blob/master/text/0009-client-api.md#rpcs>
@@ -23,6 +24,7 @@
 mod config;
 mod event;
 mod event_indexer;
+mod event_log;
 mod http_server;
 mod sse_server;
 #[cfg(test)]
@@ -31,10 +33,13 @@ mod tests;
 use std::{convert::Infallible, fmt::Debug, net::SocketAddr, path::PathBuf, sync::Arc};
 
 use datasize::DataSize;
+use futures::{future, Stream, StreamExt};
 use tokio::sync::{
+    broadcast,
     mpsc::{self, UnboundedSender},
     oneshot,
 };
+use tokio_stream::wrappers::BroadcastStream;
 use tracing::{info, warn};
 use warp::Filter;
 
@@ -50,8 +55,9 @@ use crate::{
 pub use config::Config;
 pub(crate) use event::Event;
 use event_indexer::{EventIndex, EventIndexer};
-use sse_server::ChannelsAndFilter;
-pub(crate) use sse_server::SseData;
+use event_log::EventLog;
+use sse_server::{BroadcastChannelMessage, ChannelsAndFilter};
+pub(crate) use sse_server::{EventFilter, SseData};
 
 /// This is used to define the number of events to buffer in the tokio broadcast channel to help
 /// slower clients to try to avoid missing events (See
@@ -77,6 +83,44 @@ struct InnerServer {
     sse_data_sender: UnboundedSender<(EventIndex, SseData)>,
     event_indexer: EventIndexer,
     listening_address: SocketAddr,
+    handle: EventStreamHandle,
+}
+
+/// A handle which allows other servers running in this process to subscribe to the events being
+/// sent to clients of the event stream server.
+#[derive(Clone, DataSize, Debug)]
+pub(crate) struct EventStreamHandle {
+    #[data_size(skip)]
+    event_broadcaster: broadcast::Sender<BroadcastChannelMessage>,
+    max_concurrent_subscribers: u32,
+}
+
+impl EventStreamHandle {
+    /// Returns a stream of all events broadcast by the event stream server from this point onwards,
+    /// or `None` if the server already has its maximum number of concurrent subscribers.
+    ///
+    /// The stream ends when the event stream server shuts down, or if the subscriber lags too far
+    /// behind the events being broadcast.
+    pub(crate) fn subscribe(&self) -> Option<impl Stream<Item = SseData> + Send + Unpin + 'static> {
+        if self.event_broadcaster.receiver_count() >= self.max_concurrent_subscribers as usize {
+            return None;
+        }
+
+        let stream = BroadcastStream::new(self.event_broadcaster.subscribe())
+            .take_while(|result| {
+                future::ready(matches!(
+                    result,
+                    Ok(BroadcastChannelMessage::ServerSentEvent(_))
+                ))
+            })
+            .filter_map(|result| {
+                future::ready(match result {
+                    Ok(BroadcastChannelMessage::ServerSentEvent(event)) => Some(event.data),
+                    _ => None,
+                })
+            });
+        Some(stream)
+    }
 }
 
 #[derive(DataSize, Debug)]
@@ -103,7 +147,27 @@ impl EventStreamServer {
             ListeningError::ResolveAddress(error)
         })?;
 
-        let event_indexer = EventIndexer::new(storage_path);
+        let mut event_indexer = EventIndexer::new(storage_path.clone());
+        let event_log = if config.enable_event_log {
+            match EventLog::open(
+                &storage_path,
+                event_indexer.current_index(),
+                config.event_log_max_size,
+                config.event_log_max_age,
+            ) {
+                Ok(event_log) => {
+                    // The log is authoritative, as the indexer only persists its index on shutdown.
+                    event_indexer.resume_from(event_log.retained_range().next());
+                    Some(event_log)
+                }
+                Err(error) => {
+                    warn!(%error, "failed to open sse event log, continuing without it");
+                    None
+                }
+            }
+        } else {
+            None
+        };
         let (sse_data_sender, sse_data_receiver) = mpsc::unbounded_channel();
 
         // Event stream channels and filter.
@@ -117,8 +181,14 @@ impl EventStreamServer {
         } = ChannelsAndFilter::new(
             broadcast_channel_size as usize,
             config.max_concurrent_subscribers,
+            event_log.as_ref().map(EventLog::shared_retained_range),
         );
 
+        let handle = EventStreamHandle {
+            event_broadcaster: event_broadcaster.clone(),
+            max_concurrent_subscribers: config.max_concurrent_subscribers,
+        };
+
         let (shutdown_sender, shutdown_receiver) = oneshot::channel::<()>();
 
         let (listening_address, server_with_shutdown) =
@@ -140,6 +210,7 @@ impl EventStreamServer {
             sse_data_receiver,
             event_broadcaster,
             new_subscriber_info_receiver,
+            event_log,
         ));
 
         Ok(EventStreamServer {
@@ -147,10 +218,17 @@ impl EventStreamServer {
                 sse_data_sender,
                 event_indexer,
                 listening_address,
+                handle,
             }),
         })
     }
 
+    /// Returns a handle allowing other servers to subscribe to the event stream, or `None` if the
+    /// event stream server is not enabled.
+    pub(crate) fn handle(&self) -> Option<EventStreamHandle> {
+        self.inner.as_ref().map(|server| server.handle.clone())
+    }
+
     /// Broadcasts the SSE data to all clients connected to the event stream.
     fn broadcast(&mut self, sse_data: SseData) -> Effects<Event> {
         if let Some(server) = self.inner.as_mut() {
@@ -193,6 +271,7 @@ where
                 deploy_header,
                 block_hash,
                 execution_result,
+                invoked_contracts,
             } => self.broadcast(SseData::DeployProcessed {
                 deploy_hash: Box::new(deploy_hash),
                 account: Box::new(deploy_header.account().clone()),
@@ -201,11 +280,21 @@ where
                 dependencies: deploy_header.dependencies().clone(),
                 block_hash: Box::new(block_hash),
                 execution_result,
+                invoked_contracts,
             }),
             Event::DeploysExpired(deploy_hashes) => deploy_hashes
                 .into_iter()
                 .flat_map(|deploy_hash| self.broadcast(SseData::DeployExpired { deploy_hash }))
                 .collect(),
+            Event::ContractMessage {
+                deploy_hash,
+                block_hash,
+                message,
+            } => self.broadcast(SseData::ContractMessage {
+                deploy_hash: Box::new(deploy_hash),
+                block_hash: Box::new(block_hash),
+                message,
+            }),
             Event::Fault {
                 era_id,
                 public_key,
@@ -1,6 +1,8 @@
 use datasize::DataSize;
 use serde::{Deserialize, Serialize};
 
+use casper_types::TimeDiff;
+
 /// Default binding address for the SSE HTTP server.
 ///
 /// Uses a fixed port per node, but binds on any interface.
@@ -12,6 +14,12 @@ const DEFAULT_EVENT_STREAM_BUFFER_LENGTH: u32 = 5000;
 /// Default maximum number of subscribers.
 const DEFAULT_MAX_CONCURRENT_SUBSCRIBERS: u32 = 100;
 
+/// Default maximum total size in bytes of the on-disk event log.
+const DEFAULT_EVENT_LOG_MAX_SIZE: u64 = 1_073_741_824;
+
+/// Default maximum age of events retained in the on-disk event log.
+const DEFAULT_EVENT_LOG_MAX_AGE: &str = "7days";
+
 /// SSE HTTP server configuration.
 #[derive(Clone, DataSize, Debug, Deserialize, Serialize)]
 // Disallow unknown fields to ensure config files and command-line overrides contain valid keys.
@@ -28,6 +36,16 @@ pub struct Config {
 
     /// Default maximum number of subscribers across all event streams permitted at any one time.
     pub max_concurrent_subscribers: u32,
+
+    /// Setting to enable the on-disk event log, allowing clients to replay any retained events,
+    /// including those sent before the node was restarted.
+    pub enable_event_log: bool,
+
+    /// Maximum total size in bytes of the on-disk event log.
+    pub event_log_max_size: u64,
+
+    /// Maximum age of events retained in the on-disk event log.
+    pub event_log_max_age: TimeDiff,
 }
 
 impl Config {
@@ -38,6 +56,9 @@ impl Config {
             address: DEFAULT_ADDRESS.to_string(),
             event_stream_buffer_length: DEFAULT_EVENT_STREAM_BUFFER_LENGTH,
             max_concurrent_subscribers: DEFAULT_MAX_CONCURRENT_SUBSCRIBERS,
+            enable_event_log: false,
+            event_log_max_size: DEFAULT_EVENT_LOG_MAX_SIZE,
+            event_log_max_age: DEFAULT_EVENT_LOG_MAX_AGE.parse().unwrap(),
         }
     }
 }
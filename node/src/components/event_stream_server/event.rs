@@ -1,6 +1,8 @@
 use std::fmt::{self, Display, Formatter};
 
-use casper_types::{EraId, ExecutionEffect, ExecutionResult, PublicKey, Timestamp};
+use casper_types::{
+    ContractHash, ContractMessage, EraId, ExecutionEffect, ExecutionResult, PublicKey, Timestamp,
+};
 use itertools::Itertools;
 
 use crate::types::{Block, BlockHash, Deploy, DeployHash, DeployHeader, FinalitySignature};
@@ -14,8 +16,14 @@ pub enum Event {
         deploy_header: Box<DeployHeader>,
         block_hash: BlockHash,
         execution_result: Box<ExecutionResult>,
+        invoked_contracts: Vec<ContractHash>,
     },
     DeploysExpired(Vec<DeployHash>),
+    ContractMessage {
+        deploy_hash: DeployHash,
+        block_hash: BlockHash,
+        message: Box<ContractMessage>,
+    },
     Fault {
         era_id: EraId,
         public_key: PublicKey,
@@ -45,6 +53,16 @@ impl Display for Event {
             Event::DeployProcessed { deploy_hash, .. } => {
                 write!(formatter, "deploy processed {}", deploy_hash)
             }
+            Event::ContractMessage {
+                deploy_hash,
+                message,
+                ..
+            } => write!(
+                formatter,
+                "contract message on topic {} from deploy {}",
+                message.topic(),
+                deploy_hash
+            ),
             Event::Fault {
                 era_id,
                 public_key,
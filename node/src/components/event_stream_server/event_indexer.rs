@@ -1,7 +1,7 @@
 use std::{fs, path::PathBuf};
 
 use datasize::DataSize;
-use tracing::{debug, warn};
+use tracing::{debug, info, warn};
 
 const CACHE_FILENAME: &str = "sse_index";
 
@@ -55,7 +55,14 @@ impl EventIndexer {
         index
     }
 
-    #[cfg(test)]
+    /// Sets the index to be returned by the next call to `next_index`.
+    pub(super) fn resume_from(&mut self, index: EventIndex) {
+        if index != self.index {
+            info!(old_index = %self.index, new_index = %index, "resuming sse index");
+            self.index = index;
+        }
+    }
+
     pub(super) fn current_index(&self) -> EventIndex {
         self.index
     }
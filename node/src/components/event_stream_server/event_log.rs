@@ -0,0 +1,779 @@
+//! An append-only, on-disk log of the events sent by the event stream server.
+//!
+//! The log allows clients to replay events from any retained event ID, including events sent
+//! before the node was restarted.  It is split into segment files, each holding a contiguous run of
+//! events.  The oldest segments are removed once the total size of the log exceeds its configured
+//! maximum, or once all of their events are older than the configured maximum age.
+//!
+//! Each record in a segment file is laid out as:
+//!   * event ID: `u32` little-endian
+//!   * timestamp in milliseconds: `u64` little-endian
+//!   * data length: `u32` little-endian
+//!   * data: JSON-encoded `SseData`
+//!
+//! Each record is flushed to disk before the event is sent to clients.  A replay opens the segment
+//! files it needs up front, so it is unaffected by the log subsequently pruning or clearing them.
+
+use std::{
+    collections::VecDeque,
+    fs::{self, File, OpenOptions},
+    io::{self, BufReader, ErrorKind, Read, Write},
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock},
+};
+
+use datasize::DataSize;
+use tokio::{sync::mpsc, task};
+use tracing::{debug, info, warn};
+
+use casper_types::{TimeDiff, Timestamp};
+
+use super::{
+    sse_server::{Id, ServerSentEvent},
+    SseData,
+};
+
+/// The name of the folder under the storage path holding the segment files.
+const EVENT_LOG_DIR_NAME: &str = "sse_event_log";
+/// The file extension of the segment files.
+const SEGMENT_FILE_EXTENSION: &str = "log";
+/// The target number of segment files; each segment is rotated once it reaches this fraction of
+/// the maximum total size of the log.
+const TARGET_SEGMENT_COUNT: u64 = 16;
+/// The length of the header preceding the data of each record.
+const RECORD_HEADER_LENGTH: usize = 16;
+/// The maximum number of events read from disk at a time during a replay.
+const REPLAY_BATCH_SIZE: usize = 64;
+
+/// The range of event IDs retained in the log.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, DataSize)]
+pub(super) struct RetainedRange {
+    oldest: Id,
+    count: u32,
+}
+
+impl RetainedRange {
+    /// The ID of the oldest retained event, or of the next event to be logged if the log is empty.
+    pub(super) fn oldest(&self) -> Id {
+        self.oldest
+    }
+
+    /// The ID of the next event to be logged.
+    pub(super) fn next(&self) -> Id {
+        self.oldest.wrapping_add(self.count)
+    }
+
+    /// Returns `true` if a replay can start from `id`, i.e. if it's retained or is the ID of the
+    /// next event to be logged.
+    pub(super) fn contains(&self, id: Id) -> bool {
+        id.wrapping_sub(self.oldest) <= self.count
+    }
+}
+
+/// The metadata of a single segment file.
+#[derive(Debug, DataSize)]
+struct Segment {
+    sequence: u64,
+    path: PathBuf,
+    first_id: Id,
+    count: u32,
+    size: u64,
+    newest_timestamp: Timestamp,
+}
+
+impl Segment {
+    fn next_id(&self) -> Id {
+        self.first_id.wrapping_add(self.count)
+    }
+}
+
+/// A single record read from a segment file.
+struct Record {
+    id: Id,
+    timestamp: Timestamp,
+    data: Vec<u8>,
+}
+
+impl Record {
+    fn size(&self) -> u64 {
+        (RECORD_HEADER_LENGTH + self.data.len()) as u64
+    }
+}
+
+/// Reads a single record, returning `None` if the reader is exhausted.
+fn read_record<R: Read>(reader: &mut R) -> io::Result<Option<Record>> {
+    let mut header = [0; RECORD_HEADER_LENGTH];
+    let mut header_bytes_read = 0;
+    while header_bytes_read < RECORD_HEADER_LENGTH {
+        match reader.read(&mut header[header_bytes_read..]) {
+            Ok(0) => break,
+            Ok(count) => header_bytes_read += count,
+            Err(error) if error.kind() == ErrorKind::Interrupted => continue,
+            Err(error) => return Err(error),
+        }
+    }
+    if header_bytes_read == 0 {
+        return Ok(None);
+    }
+    if header_bytes_read < RECORD_HEADER_LENGTH {
+        return Err(ErrorKind::UnexpectedEof.into());
+    }
+
+    let mut id_bytes = [0; 4];
+    id_bytes.copy_from_slice(&header[..4]);
+    let mut timestamp_bytes = [0; 8];
+    timestamp_bytes.copy_from_slice(&header[4..12]);
+    let mut length_bytes = [0; 4];
+    length_bytes.copy_from_slice(&header[12..]);
+
+    let mut data = vec![0; u32::from_le_bytes(length_bytes) as usize];
+    reader.read_exact(&mut data)?;
+
+    Ok(Some(Record {
+        id: Id::from_le_bytes(id_bytes),
+        timestamp: Timestamp::from(u64::from_le_bytes(timestamp_bytes)),
+        data,
+    }))
+}
+
+/// Encodes a single record.
+fn encode_record(id: Id, timestamp: Timestamp, data: &[u8]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(RECORD_HEADER_LENGTH + data.len());
+    bytes.extend_from_slice(&id.to_le_bytes());
+    bytes.extend_from_slice(&timestamp.millis().to_le_bytes());
+    bytes.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(data);
+    bytes
+}
+
+/// Reads the metadata of the given segment file, truncating any trailing records which are
+/// incomplete or not contiguous with the preceding ones.  Returns `None` if the segment holds no
+/// valid records.
+fn scan_segment(sequence: u64, path: PathBuf) -> io::Result<Option<Segment>> {
+    let file = File::open(&path)?;
+    let file_size = file.metadata()?.len();
+    let mut reader = BufReader::new(file);
+    let mut maybe_segment: Option<Segment> = None;
+
+    loop {
+        let record = match read_record(&mut reader) {
+            Ok(Some(record)) => record,
+            Ok(None) => break,
+            Err(error) => {
+                warn!(file = %path.display(), %error, "failed to read sse event log record");
+                break;
+            }
+        };
+        match maybe_segment.as_mut() {
+            None => {
+                maybe_segment = Some(Segment {
+                    sequence,
+                    path: path.clone(),
+                    first_id: record.id,
+                    count: 1,
+                    size: record.size(),
+                    newest_timestamp: record.timestamp,
+                })
+            }
+            Some(segment) => {
+                if record.id != segment.next_id() {
+                    warn!(
+                        file = %path.display(),
+                        expected_id = %segment.next_id(),
+                        actual_id = %record.id,
+                        "non-contiguous sse event log record"
+                    );
+                    break;
+                }
+                segment.count += 1;
+                segment.size += record.size();
+                segment.newest_timestamp = record.timestamp;
+            }
+        }
+    }
+
+    let valid_size = maybe_segment
+        .as_ref()
+        .map(|segment| segment.size)
+        .unwrap_or_default();
+    if valid_size < file_size {
+        warn!(
+            file = %path.display(),
+            %file_size,
+            %valid_size,
+            "truncating sse event log segment"
+        );
+        OpenOptions::new()
+            .write(true)
+            .open(&path)?
+            .set_len(valid_size)?;
+    }
+
+    Ok(maybe_segment)
+}
+
+/// Returns the sequence number encoded in the segment file's name, or `None` if the path is not a
+/// segment file.
+fn parse_sequence(path: &Path) -> Option<u64> {
+    if path.extension()? != SEGMENT_FILE_EXTENSION {
+        return None;
+    }
+    path.file_stem()?.to_str()?.parse().ok()
+}
+
+/// An on-disk log of events.
+#[derive(Debug, DataSize)]
+pub(super) struct EventLog {
+    dir: PathBuf,
+    max_size: u64,
+    max_age: TimeDiff,
+    segments: VecDeque<Segment>,
+    /// The open handle to the newest segment, or `None` if the next event should be written to a
+    /// new segment.
+    #[data_size(skip)]
+    current_file: Option<File>,
+    /// The range of retained event IDs, shared with the SSE server's filter.
+    #[data_size(skip)]
+    retained_range: Arc<RwLock<RetainedRange>>,
+}
+
+impl EventLog {
+    /// Opens the event log under the given storage path, creating it if required.
+    ///
+    /// `next_id` is the ID of the next event to be logged if the log is empty.  Otherwise, the log
+    /// continues from the ID following its newest event, and the event indexer should be updated
+    /// to match.
+    pub(super) fn open(
+        storage_path: &Path,
+        next_id: Id,
+        max_size: u64,
+        max_age: TimeDiff,
+    ) -> io::Result<Self> {
+        let dir = storage_path.join(EVENT_LOG_DIR_NAME);
+        fs::create_dir_all(&dir)?;
+
+        let mut sequenced_paths = vec![];
+        for entry in fs::read_dir(&dir)? {
+            let path = entry?.path();
+            match parse_sequence(&path) {
+                Some(sequence) => sequenced_paths.push((sequence, path)),
+                None => debug!(file = %path.display(), "ignoring non-segment file"),
+            }
+        }
+        sequenced_paths.sort_unstable();
+
+        let mut segments: VecDeque<Segment> = VecDeque::new();
+        for (sequence, path) in sequenced_paths {
+            let segment = match scan_segment(sequence, path.clone())? {
+                Some(segment) => segment,
+                None => {
+                    fs::remove_file(&path)?;
+                    continue;
+                }
+            };
+            if let Some(previous) = segments.back() {
+                if previous.next_id() != segment.first_id {
+                    warn!(
+                        file = %segment.path.display(),
+                        "sse event log segment not contiguous with previous: discarding older \
+                        segments"
+                    );
+                    for stale in segments.drain(..) {
+                        fs::remove_file(&stale.path)?;
+                    }
+                }
+            }
+            segments.push_back(segment);
+        }
+
+        let current_file = match segments.back() {
+            Some(last) => Some(OpenOptions::new().append(true).open(&last.path)?),
+            None => None,
+        };
+
+        let mut event_log = EventLog {
+            dir,
+            max_size,
+            max_age,
+            segments,
+            current_file,
+            retained_range: Arc::new(RwLock::new(RetainedRange {
+                oldest: next_id,
+                count: 0,
+            })),
+        };
+        event_log.prune(Timestamp::now());
+        event_log.update_retained_range(next_id);
+
+        let retained_range = event_log.retained_range();
+        info!(
+            oldest_id = %retained_range.oldest(),
+            count = %retained_range.count,
+            "opened sse event log"
+        );
+        Ok(event_log)
+    }
+
+    /// Returns the range of event IDs currently retained.
+    pub(super) fn retained_range(&self) -> RetainedRange {
+        *self.retained_range.read().unwrap()
+    }
+
+    /// Returns a shared handle to the range of event IDs retained, updated as the log changes.
+    pub(super) fn shared_retained_range(&self) -> Arc<RwLock<RetainedRange>> {
+        Arc::clone(&self.retained_range)
+    }
+
+    /// Appends the given event to the log, and removes any segments which then exceed the size or
+    /// age limits.
+    pub(super) fn append(&mut self, id: Id, data: &SseData) -> io::Result<()> {
+        self.append_at(id, data, Timestamp::now())
+    }
+
+    fn append_at(&mut self, id: Id, data: &SseData, timestamp: Timestamp) -> io::Result<()> {
+        let next_id = self.retained_range().next();
+        if id != next_id {
+            warn!(%id, expected_id = %next_id, "non-contiguous sse event: discarding event log");
+            self.clear()?;
+        }
+
+        let data = serde_json::to_vec(data).map_err(io::Error::from)?;
+        let record = encode_record(id, timestamp, &data);
+
+        let segment_max_size = self.max_size / TARGET_SEGMENT_COUNT;
+        let needs_new_segment = match (self.segments.back(), &self.current_file) {
+            (Some(last), Some(_)) => last.size >= segment_max_size,
+            _ => true,
+        };
+        if needs_new_segment {
+            let sequence = self
+                .segments
+                .back()
+                .map(|last| last.sequence + 1)
+                .unwrap_or_default();
+            let path = self
+                .dir
+                .join(format!("{:020}.{}", sequence, SEGMENT_FILE_EXTENSION));
+            self.current_file = Some(
+                OpenOptions::new()
+                    .create_new(true)
+                    .append(true)
+                    .open(&path)?,
+            );
+            // Ensure the new segment's directory entry is persisted too.
+            File::open(&self.dir)?.sync_all()?;
+            self.segments.push_back(Segment {
+                sequence,
+                path,
+                first_id: id,
+                count: 0,
+                size: 0,
+                newest_timestamp: timestamp,
+            });
+        }
+
+        // Only `Some` here, as it's set above if `None`.
+        if let Some(file) = self.current_file.as_mut() {
+            if let Err(error) = file.write_all(&record).and_then(|()| file.sync_data()) {
+                // Start a new segment for the next event, as this one may now end with a partial
+                // record.
+                self.current_file = None;
+                return Err(error);
+            }
+        }
+        if let Some(last) = self.segments.back_mut() {
+            last.count += 1;
+            last.size += record.len() as u64;
+            last.newest_timestamp = timestamp;
+        }
+
+        self.prune(timestamp);
+        self.update_retained_range(id.wrapping_add(1));
+        Ok(())
+    }
+
+    /// Returns the events to replay to a client requesting to start from `start_from`, or `None`
+    /// if `start_from` is not in the retained range.
+    ///
+    /// The segment files holding the events are opened immediately, so this performs blocking I/O.
+    pub(super) fn replay_from(&self, start_from: Id) -> io::Result<Option<Replay>> {
+        let retained_range = self.retained_range();
+        if !retained_range.contains(start_from) {
+            return Ok(None);
+        }
+
+        let mut to_skip = start_from.wrapping_sub(retained_range.oldest());
+        let mut segments = VecDeque::new();
+        for segment in &self.segments {
+            if to_skip >= segment.count {
+                to_skip -= segment.count;
+                continue;
+            }
+            segments.push_back(ReplaySegment {
+                reader: BufReader::new(File::open(&segment.path)?),
+                skip: to_skip,
+                take: segment.count - to_skip,
+            });
+            to_skip = 0;
+        }
+        Ok(Some(Replay { segments }))
+    }
+
+    /// Removes the oldest segments while the log exceeds its maximum size, or while they only
+    /// hold events older than the maximum age.
+    fn prune(&mut self, now: Timestamp) {
+        let cutoff = now.saturating_sub(self.max_age);
+        let mut total_size: u64 = self.segments.iter().map(|segment| segment.size).sum();
+        while let Some(oldest) = self.segments.front() {
+            let too_big = total_size > self.max_size && self.segments.len() > 1;
+            let too_old = oldest.newest_timestamp < cutoff;
+            if !too_big && !too_old {
+                break;
+            }
+            if let Err(error) = fs::remove_file(&oldest.path) {
+                warn!(file = %oldest.path.display(), %error, "failed to remove sse event log segment");
+                break;
+            }
+            debug!(file = %oldest.path.display(), "removed sse event log segment");
+            total_size -= oldest.size;
+            let _ = self.segments.pop_front();
+            if self.segments.is_empty() {
+                self.current_file = None;
+            }
+        }
+    }
+
+    /// Removes all segments.
+    fn clear(&mut self) -> io::Result<()> {
+        self.current_file = None;
+        while let Some(segment) = self.segments.pop_front() {
+            fs::remove_file(&segment.path)?;
+        }
+        Ok(())
+    }
+
+    fn update_retained_range(&self, next_id: Id) {
+        let retained_range = match self.segments.front() {
+            Some(oldest) => RetainedRange {
+                oldest: oldest.first_id,
+                count: self.segments.iter().map(|segment| segment.count).sum(),
+            },
+            None => RetainedRange {
+                oldest: next_id,
+                count: 0,
+            },
+        };
+        *self.retained_range.write().unwrap() = retained_range;
+    }
+}
+
+/// The portion of a single segment to be replayed.
+#[derive(Debug)]
+struct ReplaySegment {
+    reader: BufReader<File>,
+    skip: u32,
+    take: u32,
+}
+
+/// A snapshot of the events to be replayed to a client, read from disk on demand.
+#[derive(Debug)]
+pub(super) struct Replay {
+    segments: VecDeque<ReplaySegment>,
+}
+
+impl Replay {
+    /// Reads up to `max_count` further events from disk, returning an empty batch once all events
+    /// have been read.
+    ///
+    /// This performs blocking reads.
+    fn read_batch(&mut self, max_count: usize) -> io::Result<Vec<ServerSentEvent>> {
+        let mut events = vec![];
+        while events.len() < max_count {
+            let segment = match self.segments.front_mut() {
+                Some(segment) => segment,
+                None => break,
+            };
+            if segment.take == 0 {
+                let _ = self.segments.pop_front();
+                continue;
+            }
+            let record = read_record(&mut segment.reader)?.ok_or(ErrorKind::UnexpectedEof)?;
+            if segment.skip > 0 {
+                segment.skip -= 1;
+                continue;
+            }
+            segment.take -= 1;
+            let data = serde_json::from_slice(&record.data).map_err(io::Error::from)?;
+            events.push(ServerSentEvent {
+                id: Some(record.id),
+                data,
+            });
+        }
+        Ok(events)
+    }
+
+    /// Reads the events from disk in batches and sends them via `sender`, waiting for the client to
+    /// consume each event before sending the next once the channel is full.
+    ///
+    /// Returns early without error if the receiver has been dropped.
+    pub(super) async fn send_to(mut self, sender: mpsc::Sender<ServerSentEvent>) -> io::Result<()> {
+        loop {
+            let (replay, result) = task::spawn_blocking(move || {
+                let result = self.read_batch(REPLAY_BATCH_SIZE);
+                (self, result)
+            })
+            .await
+            .map_err(|error| io::Error::new(ErrorKind::Other, error))?;
+            self = replay;
+
+            let events = result?;
+            if events.is_empty() {
+                return Ok(());
+            }
+            for event in events {
+                if sender.send(event).await.is_err() {
+                    return Ok(());
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use casper_types::testing::TestRng;
+
+    use super::*;
+    use crate::logging;
+
+    const MAX_SIZE: u64 = 1024 * 1024;
+
+    fn max_age() -> TimeDiff {
+        "1day".parse().unwrap()
+    }
+
+    fn random_events(rng: &mut TestRng, first_id: Id, count: u32) -> Vec<ServerSentEvent> {
+        (0..count)
+            .map(|offset| ServerSentEvent {
+                id: Some(first_id.wrapping_add(offset)),
+                data: SseData::random_block_added(rng),
+            })
+            .collect()
+    }
+
+    fn read_all(mut replay: Replay) -> Vec<ServerSentEvent> {
+        let mut events = vec![];
+        loop {
+            let batch = replay.read_batch(REPLAY_BATCH_SIZE).unwrap();
+            if batch.is_empty() {
+                return events;
+            }
+            events.extend(batch);
+        }
+    }
+
+    fn replay(event_log: &EventLog, start_from: Id) -> Vec<ServerSentEvent> {
+        read_all(event_log.replay_from(start_from).unwrap().unwrap())
+    }
+
+    #[test]
+    fn should_replay_across_restarts() {
+        let _ = logging::init();
+        let mut rng = crate::new_rng();
+        let tempdir = tempfile::tempdir().unwrap();
+        let events = random_events(&mut rng, 0, 10);
+
+        {
+            let mut event_log = EventLog::open(tempdir.path(), 0, MAX_SIZE, max_age()).unwrap();
+            for event in &events[..5] {
+                event_log.append(event.id.unwrap(), &event.data).unwrap();
+            }
+        }
+
+        let mut event_log = EventLog::open(tempdir.path(), 5, MAX_SIZE, max_age()).unwrap();
+        assert_eq!(
+            event_log.retained_range(),
+            RetainedRange {
+                oldest: 0,
+                count: 5
+            }
+        );
+        for event in &events[5..] {
+            event_log.append(event.id.unwrap(), &event.data).unwrap();
+        }
+
+        assert_eq!(replay(&event_log, 0), events);
+        assert_eq!(replay(&event_log, 7), events[7..]);
+        assert!(replay(&event_log, 10).is_empty());
+        assert!(event_log.replay_from(11).unwrap().is_none());
+    }
+
+    #[test]
+    fn should_continue_from_newest_event() {
+        let _ = logging::init();
+        let mut rng = crate::new_rng();
+        let tempdir = tempfile::tempdir().unwrap();
+
+        {
+            let mut event_log = EventLog::open(tempdir.path(), 0, MAX_SIZE, max_age()).unwrap();
+            for event in random_events(&mut rng, 0, 5) {
+                event_log.append(event.id.unwrap(), &event.data).unwrap();
+            }
+        }
+
+        // The provided next ID is ignored, as the log is not empty.
+        let event_log = EventLog::open(tempdir.path(), 0, MAX_SIZE, max_age()).unwrap();
+        assert_eq!(
+            event_log.retained_range(),
+            RetainedRange {
+                oldest: 0,
+                count: 5
+            }
+        );
+    }
+
+    #[test]
+    fn should_prune_by_size() {
+        let _ = logging::init();
+        let mut rng = crate::new_rng();
+        let tempdir = tempfile::tempdir().unwrap();
+        let max_size = 64 * 1024;
+
+        let mut event_log = EventLog::open(tempdir.path(), 0, max_size, max_age()).unwrap();
+        let events = random_events(&mut rng, 0, 500);
+        for event in &events {
+            event_log.append(event.id.unwrap(), &event.data).unwrap();
+        }
+
+        let total_size: u64 = event_log.segments.iter().map(|segment| segment.size).sum();
+        assert!(total_size <= max_size);
+        let retained_range = event_log.retained_range();
+        assert!(retained_range.oldest() > 0);
+        assert_eq!(retained_range.next(), 500);
+        assert!(event_log.replay_from(0).unwrap().is_none());
+        assert_eq!(
+            replay(&event_log, retained_range.oldest()),
+            events[retained_range.oldest() as usize..]
+        );
+    }
+
+    #[test]
+    fn should_prune_by_age() {
+        let _ = logging::init();
+        let mut rng = crate::new_rng();
+        let tempdir = tempfile::tempdir().unwrap();
+
+        let mut event_log = EventLog::open(tempdir.path(), 0, MAX_SIZE, max_age()).unwrap();
+        let old_timestamp = Timestamp::now().saturating_sub("2days".parse().unwrap());
+        let events = random_events(&mut rng, 0, 2);
+        event_log
+            .append_at(0, &events[0].data, old_timestamp)
+            .unwrap();
+        event_log.append(1, &events[1].data).unwrap();
+
+        // Both events are in the same segment, so the old one is retained.
+        assert_eq!(event_log.retained_range().oldest(), 0);
+
+        // Once the segment only holds old events, it's removed.
+        event_log.prune(Timestamp::now().saturating_add("2days".parse().unwrap()));
+        event_log.update_retained_range(2);
+        assert_eq!(
+            event_log.retained_range(),
+            RetainedRange {
+                oldest: 2,
+                count: 0
+            }
+        );
+    }
+
+    #[test]
+    fn should_replay_with_wrapping_ids() {
+        let _ = logging::init();
+        let mut rng = crate::new_rng();
+        let tempdir = tempfile::tempdir().unwrap();
+        let first_id = Id::MAX - 2;
+        let events = random_events(&mut rng, first_id, 6);
+
+        let mut event_log = EventLog::open(tempdir.path(), first_id, MAX_SIZE, max_age()).unwrap();
+        for event in &events {
+            event_log.append(event.id.unwrap(), &event.data).unwrap();
+        }
+
+        assert_eq!(replay(&event_log, first_id), events);
+        assert_eq!(replay(&event_log, 1), events[4..]);
+        assert!(event_log.replay_from(first_id - 1).unwrap().is_none());
+    }
+
+    #[test]
+    fn should_replay_pruned_segments() {
+        let _ = logging::init();
+        let mut rng = crate::new_rng();
+        let tempdir = tempfile::tempdir().unwrap();
+        let max_size = 64 * 1024;
+        let events = random_events(&mut rng, 0, 1000);
+
+        let mut event_log = EventLog::open(tempdir.path(), 0, max_size, max_age()).unwrap();
+        for event in &events[..100] {
+            event_log.append(event.id.unwrap(), &event.data).unwrap();
+        }
+        let oldest = event_log.retained_range().oldest();
+        let replay = event_log.replay_from(oldest).unwrap().unwrap();
+
+        // Logging further events prunes the segments being replayed.
+        for event in &events[100..] {
+            event_log.append(event.id.unwrap(), &event.data).unwrap();
+        }
+        assert!(event_log.retained_range().oldest() >= 100);
+
+        assert_eq!(read_all(replay), events[oldest as usize..100]);
+    }
+
+    #[tokio::test]
+    async fn should_replay_via_bounded_channel() {
+        let _ = logging::init();
+        let mut rng = crate::new_rng();
+        let tempdir = tempfile::tempdir().unwrap();
+        let events = random_events(&mut rng, 0, 10);
+
+        let mut event_log = EventLog::open(tempdir.path(), 0, MAX_SIZE, max_age()).unwrap();
+        for event in &events {
+            event_log.append(event.id.unwrap(), &event.data).unwrap();
+        }
+
+        let (sender, mut receiver) = mpsc::channel(1);
+        let replay = event_log.replay_from(0).unwrap().unwrap();
+        let replay_task = tokio::spawn(replay.send_to(sender));
+
+        let mut received = vec![];
+        while let Some(event) = receiver.recv().await {
+            received.push(event);
+        }
+        replay_task.await.unwrap().unwrap();
+        assert_eq!(received, events);
+    }
+
+    #[test]
+    fn should_truncate_partial_record() {
+        let _ = logging::init();
+        let mut rng = crate::new_rng();
+        let tempdir = tempfile::tempdir().unwrap();
+        let events = random_events(&mut rng, 0, 3);
+
+        let segment_path = {
+            let mut event_log = EventLog::open(tempdir.path(), 0, MAX_SIZE, max_age()).unwrap();
+            for event in &events {
+                event_log.append(event.id.unwrap(), &event.data).unwrap();
+            }
+            event_log.segments.back().unwrap().path.clone()
+        };
+
+        // Simulate a crash part way through writing a further record.
+        let mut file = OpenOptions::new().append(true).open(&segment_path).unwrap();
+        file.write_all(&encode_record(3, Timestamp::now(), b"{}")[..10])
+            .unwrap();
+        drop(file);
+
+        let event_log = EventLog::open(tempdir.path(), 3, MAX_SIZE, max_age()).unwrap();
+        assert_eq!(replay(&event_log, 0), events);
+    }
+}
@@ -1,15 +1,18 @@
+use std::sync::{Arc, Mutex};
+
 use futures::{future, Future, FutureExt};
 use tokio::{
     select,
     sync::{broadcast, mpsc, oneshot},
     task,
 };
-use tracing::{info, trace};
+use tracing::{info, trace, warn};
 use wheelbuf::WheelBuf;
 
 use casper_types::ProtocolVersion;
 
 use super::{
+    event_log::EventLog,
     sse_server::{BroadcastChannelMessage, Id, NewSubscriberInfo, ServerSentEvent},
     Config, EventIndex, SseData,
 };
@@ -25,6 +28,10 @@ use super::{
 /// * `new_subscriber_info_receiver` is used to notify the server of the details of a new client
 ///   having subscribed to the event stream.  It allows the server to populate that client's stream
 ///   with the requested number of historical events.
+/// * `event_log` is the optional on-disk log of events.  If provided, each event is appended to it,
+///   and historical events are replayed to new subscribers from it rather than from the in-memory
+///   buffer.  All access to the log is performed on tokio's blocking thread pool.
+#[allow(clippy::too_many_arguments)]
 pub(super) async fn run(
     config: Config,
     api_version: ProtocolVersion,
@@ -33,8 +40,10 @@ pub(super) async fn run(
     mut data_receiver: mpsc::UnboundedReceiver<(EventIndex, SseData)>,
     broadcaster: broadcast::Sender<BroadcastChannelMessage>,
     mut new_subscriber_info_receiver: mpsc::UnboundedReceiver<NewSubscriberInfo>,
+    event_log: Option<EventLog>,
 ) {
     let server_joiner = task::spawn(server_with_shutdown);
+    let event_log = event_log.map(|event_log| Arc::new(Mutex::new(event_log)));
 
     // Initialize the index and buffer for the SSEs.
     let mut buffer = WheelBuf::new(vec![
@@ -49,14 +58,30 @@ pub(super) async fn run(
             select! {
                 maybe_new_subscriber = new_subscriber_info_receiver.recv() => {
                     if let Some(subscriber) = maybe_new_subscriber {
-                        // First send the client the `ApiVersion` event.  We don't care if this
-                        // errors - the client may have disconnected already.
-                        let _ = subscriber
-                            .initial_events_sender
-                            .send(ServerSentEvent::initial_event(api_version));
-                        // If the client supplied a "start_from" index, provide the buffered events.
-                        // If they requested more than is buffered, just provide the whole buffer.
-                        if let Some(start_index) = subscriber.start_from {
+                        // First send the client the `ApiVersion` event.
+                        let mut initial_events = vec![ServerSentEvent::initial_event(api_version)];
+                        let mut maybe_replay = None;
+                        // If the client supplied a "start_from" index and the event log is enabled,
+                        // replay the logged events from disk.  The SSE server's filter has already
+                        // rejected requests for indices not retained in the log.
+                        if let (Some(start_index), Some(event_log)) =
+                            (subscriber.start_from, event_log.as_ref())
+                        {
+                            let event_log = Arc::clone(event_log);
+                            match task::spawn_blocking(move || {
+                                event_log.lock().unwrap().replay_from(start_index)
+                            })
+                            .await
+                            .expect("sse event log task panicked")
+                            {
+                                Ok(replay) => maybe_replay = replay,
+                                Err(error) => warn!(%error, "failed to replay sse event log"),
+                            }
+                        } else if let Some(start_index) = subscriber.start_from {
+                            // Otherwise, if the client supplied a "start_from" index, provide the
+                            // buffered events.  If they requested more than is buffered, just
+                            // provide the whole buffer.
+                            //
                             // If the buffer's first event ID is in the range [0, buffer size) or
                             // (Id::MAX - buffer size, Id::MAX], then the events in the buffer are
                             // considered to have their IDs wrapping round, or that was recently the
@@ -81,10 +106,26 @@ pub(super) async fn run(
                                     event.id.unwrap() < start_index
                                 }
                             }) {
-                                // As per sending `SSE_INITIAL_EVENT`, we don't care if this errors.
-                                let _ = subscriber.initial_events_sender.send(event.clone());
+                                initial_events.push(event.clone());
                             }
                         }
+
+                        // Send the initial events from a separate task, as the channel to the
+                        // client is bounded.  We don't care if sending errors - the client may have
+                        // disconnected already.
+                        let initial_events_sender = subscriber.initial_events_sender;
+                        task::spawn(async move {
+                            for event in initial_events {
+                                if initial_events_sender.send(event).await.is_err() {
+                                    return;
+                                }
+                            }
+                            if let Some(replay) = maybe_replay {
+                                if let Err(error) = replay.send_to(initial_events_sender).await {
+                                    warn!(%error, "failed to replay sse event log");
+                                }
+                            }
+                        });
                     }
                 }
 
@@ -93,6 +134,23 @@ pub(super) async fn run(
                         Some((event_index, data)) => {
                             // Buffer the data and broadcast it to subscribed clients.
                             trace!("Event stream server received {:?}", data);
+                            let data = match event_log.as_ref() {
+                                Some(event_log) => {
+                                    let event_log = Arc::clone(event_log);
+                                    let (data, result) = task::spawn_blocking(move || {
+                                        let result =
+                                            event_log.lock().unwrap().append(event_index, &data);
+                                        (data, result)
+                                    })
+                                    .await
+                                    .expect("sse event log task panicked");
+                                    if let Err(error) = result {
+                                        warn!(%error, "failed to append to sse event log");
+                                    }
+                                    data
+                                }
+                                None => data,
+                            };
                             let event = ServerSentEvent { id: Some(event_index), data };
                             buffer.push(event.clone());
                             let message = BroadcastChannelMessage::ServerSentEvent(event);
@@ -18,9 +18,7 @@ use tokio::sync::{
     broadcast::{self, error::RecvError},
     mpsc,
 };
-use tokio_stream::wrappers::{
-    errors::BroadcastStreamRecvError, BroadcastStream, UnboundedReceiverStream,
-};
+use tokio_stream::wrappers::{errors::BroadcastStreamRecvError, BroadcastStream, ReceiverStream};
 use tracing::{debug, error, info, warn};
 use warp::{
     addr,
@@ -32,12 +30,15 @@ use warp::{
     Filter, Reply,
 };
 
+use casper_hashing::Digest;
 #[cfg(test)]
-use casper_types::testing::TestRng;
+use casper_types::{testing::TestRng, CLValue};
 use casper_types::{
-    EraId, ExecutionEffect, ExecutionResult, ProtocolVersion, PublicKey, TimeDiff, Timestamp,
+    AsymmetricType, ContractHash, ContractMessage, EraId, ExecutionEffect, ExecutionResult, Key,
+    ProtocolVersion, PublicKey, TimeDiff, Timestamp,
 };
 
+use super::event_log::RetainedRange;
 use crate::types::{BlockHash, Deploy, DeployHash, FinalitySignature, JsonBlock};
 #[cfg(test)]
 use crate::{testing, types::Block};
@@ -53,12 +54,29 @@ pub const SSE_API_DEPLOYS_PATH: &str = "deploys";
 pub const SSE_API_SIGNATURES_PATH: &str = "sigs";
 /// The URL query string field name.
 pub const QUERY_FIELD: &str = "start_from";
+/// The URL query string field name for filtering by the accounts which sent deploys.
+pub const ACCOUNT_QUERY_FIELD: &str = "account";
+/// The URL query string field name for filtering by the keys touched during execution.
+pub const KEY_QUERY_FIELD: &str = "key";
+/// The URL query string field name for filtering by the contracts invoked by hash from a deploy's
+/// payment or session code.
+pub const CONTRACT_HASH_QUERY_FIELD: &str = "contract_hash";
+/// The URL query string field name for filtering by deploy hashes.
+pub const DEPLOY_HASH_QUERY_FIELD: &str = "deploy_hash";
+/// The URL query string field name for filtering contract messages by topic.
+pub const TOPIC_QUERY_FIELD: &str = "topic";
+/// The separator between multiple values of a single query string field.
+const QUERY_VALUE_SEPARATOR: char = ',';
+/// The capacity of the channel carrying a client's initial events.  Once full, historical events
+/// are only read from the event log as the client consumes them.
+const INITIAL_EVENTS_CHANNEL_SIZE: usize = 64;
 
 /// The filter associated with `/events/main` path.
-const MAIN_FILTER: [EventFilter; 5] = [
+const MAIN_FILTER: [EventFilter; 6] = [
     EventFilter::BlockAdded,
     EventFilter::DeployProcessed,
     EventFilter::DeployExpired,
+    EventFilter::ContractMessage,
     EventFilter::Fault,
     EventFilter::Step,
 ];
@@ -96,11 +114,22 @@ pub enum SseData {
         ttl: TimeDiff,
         dependencies: Vec<DeployHash>,
         block_hash: Box<BlockHash>,
+        /// The stored contracts invoked by hash from the deploy's payment or session code.
+        #[serde(default)]
+        invoked_contracts: Vec<ContractHash>,
         #[data_size(skip)]
         execution_result: Box<ExecutionResult>,
     },
     /// The given deploy has expired.
     DeployExpired { deploy_hash: DeployHash },
+    /// A message was emitted by a contract while executing the given deploy, which forms part of
+    /// the given block.
+    ContractMessage {
+        deploy_hash: Box<DeployHash>,
+        block_hash: Box<BlockHash>,
+        #[data_size(skip)]
+        message: Box<ContractMessage>,
+    },
     /// Generic representation of validator's fault in an era.
     Fault {
         era_id: EraId,
@@ -120,18 +149,68 @@ pub enum SseData {
 }
 
 impl SseData {
-    pub(super) fn should_include(&self, filter: &[EventFilter]) -> bool {
+    pub(crate) fn should_include(&self, filter: &[EventFilter]) -> bool {
         match self {
             SseData::ApiVersion(_) | SseData::Shutdown => true,
             SseData::BlockAdded { .. } => filter.contains(&EventFilter::BlockAdded),
             SseData::DeployAccepted { .. } => filter.contains(&EventFilter::DeployAccepted),
             SseData::DeployProcessed { .. } => filter.contains(&EventFilter::DeployProcessed),
             SseData::DeployExpired { .. } => filter.contains(&EventFilter::DeployExpired),
+            SseData::ContractMessage { .. } => filter.contains(&EventFilter::ContractMessage),
             SseData::Fault { .. } => filter.contains(&EventFilter::Fault),
             SseData::FinalitySignature(_) => filter.contains(&EventFilter::FinalitySignature),
             SseData::Step { .. } => filter.contains(&EventFilter::Step),
         }
     }
+
+    /// Returns `true` if this event relates to the given account.
+    ///
+    /// Deploy events relate to the account which sent the deploy, `BlockAdded` to the block's
+    /// proposer, and `Fault` and `FinalitySignature` events to the given validator.  `ApiVersion`
+    /// and `Shutdown` relate to all accounts.
+    pub(crate) fn involves_account(&self, account: &PublicKey) -> bool {
+        match self {
+            SseData::ApiVersion(_) | SseData::Shutdown => true,
+            SseData::BlockAdded { block, .. } => block.body.proposer() == account,
+            SseData::DeployAccepted { deploy } => deploy.header().account() == account,
+            SseData::DeployProcessed {
+                account: deploy_account,
+                ..
+            } => **deploy_account == *account,
+            SseData::Fault { public_key, .. } => public_key == account,
+            SseData::FinalitySignature(signature) => signature.public_key == *account,
+            SseData::DeployExpired { .. }
+            | SseData::ContractMessage { .. }
+            | SseData::Step { .. } => false,
+        }
+    }
+
+    /// Returns `true` if this event relates to the given deploy.
+    ///
+    /// Deploy events relate to the deploy itself, and `BlockAdded` to all deploys and transfers
+    /// included in the block.  `ApiVersion` and `Shutdown` relate to all deploys.
+    pub(crate) fn involves_deploy(&self, deploy_hash: &DeployHash) -> bool {
+        match self {
+            SseData::ApiVersion(_) | SseData::Shutdown => true,
+            SseData::BlockAdded { block, .. } => {
+                block.body.deploy_hashes().contains(deploy_hash)
+                    || block.body.transfer_hashes().contains(deploy_hash)
+            }
+            SseData::DeployAccepted { deploy } => deploy.id() == deploy_hash,
+            SseData::DeployProcessed {
+                deploy_hash: processed_deploy_hash,
+                ..
+            } => **processed_deploy_hash == *deploy_hash,
+            SseData::DeployExpired {
+                deploy_hash: expired_deploy_hash,
+            } => expired_deploy_hash == deploy_hash,
+            SseData::ContractMessage {
+                deploy_hash: emitting_deploy_hash,
+                ..
+            } => **emitting_deploy_hash == *deploy_hash,
+            SseData::Fault { .. } | SseData::FinalitySignature(_) | SseData::Step { .. } => false,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -175,6 +254,7 @@ impl SseData {
             dependencies: deploy.header().dependencies().clone(),
             block_hash: Box::new(BlockHash::random(rng)),
             execution_result: Box::new(rng.gen()),
+            invoked_contracts: deploy.invoked_contracts(),
         }
     }
 
@@ -186,6 +266,17 @@ impl SseData {
         }
     }
 
+    /// Returns a random `SseData::ContractMessage`.
+    pub(super) fn random_contract_message(rng: &mut TestRng) -> Self {
+        let topic = format!("topic-{}", rng.gen::<u8>());
+        let payload = CLValue::from_t(rng.gen::<u64>()).unwrap();
+        SseData::ContractMessage {
+            deploy_hash: Box::new(DeployHash::random(rng)),
+            block_hash: Box::new(BlockHash::random(rng)),
+            message: Box::new(ContractMessage::new(Key::Hash(rng.gen()), topic, payload)),
+        }
+    }
+
     /// Returns a random `SseData::Fault`.
     pub(super) fn random_fault(rng: &mut TestRng) -> Self {
         SseData::Fault {
@@ -225,7 +316,7 @@ pub(super) struct DeployAccepted {
 
 /// The components of a single SSE.
 #[derive(Clone, PartialEq, Eq, Debug)]
-pub(super) struct ServerSentEvent {
+pub(crate) struct ServerSentEvent {
     /// The ID should only be `None` where the `data` is `SseData::ApiVersion`.
     pub(super) id: Option<Id>,
     pub(super) data: SseData,
@@ -244,7 +335,7 @@ impl ServerSentEvent {
 /// The messages sent via the tokio broadcast channel to the handler of each client's SSE stream.
 #[derive(Clone, PartialEq, Eq, Debug)]
 #[allow(clippy::large_enum_variant)]
-pub(super) enum BroadcastChannelMessage {
+pub(crate) enum BroadcastChannelMessage {
     /// The message should be sent to the client as an SSE with an optional ID.  The ID should only
     /// be `None` where the `data` is `SseData::ApiVersion`.
     ServerSentEvent(ServerSentEvent),
@@ -261,27 +352,173 @@ pub(super) struct NewSubscriberInfo {
     pub(super) start_from: Option<Id>,
     /// A channel to send the initial events to the client's handler.  This will always send the
     /// ApiVersion as the first event, and then any buffered events as indicated by `start_from`.
-    pub(super) initial_events_sender: mpsc::UnboundedSender<ServerSentEvent>,
+    pub(super) initial_events_sender: mpsc::Sender<ServerSentEvent>,
 }
 
 /// A filter for event types a client has subscribed to receive.
-#[derive(Clone, Copy, Eq, PartialEq, Debug)]
-pub(super) enum EventFilter {
+#[derive(Clone, Copy, Eq, PartialEq, Serialize, Deserialize, Debug)]
+pub(crate) enum EventFilter {
     BlockAdded,
     DeployAccepted,
     DeployProcessed,
     DeployExpired,
+    ContractMessage,
     Fault,
     FinalitySignature,
     Step,
 }
 
+/// A filter on the content of events a client has subscribed to receive.
+///
+/// Each populated criterion must be satisfied for a deploy-related event to be included, where a
+/// criterion is satisfied if the event matches any one of its values.  Events which don't relate
+/// to deploys (e.g. `BlockAdded` or `Fault`) are never filtered out by content.  Topics only apply
+/// to `ContractMessage` events, so if any are set, other deploy-related events are filtered out.
+///
+/// A `DeployAccepted` or `DeployProcessed` event matches a contract hash if the deploy's payment or
+/// session code invokes that stored contract by hash, as listed by [`Deploy::invoked_contracts`],
+/// regardless of whether execution succeeded or which other contracts were called in turn.  A
+/// `ContractMessage` event matches the contract hash of its emitter.
+#[derive(Clone, Default, PartialEq, Eq, Debug)]
+pub(super) struct ContentFilter {
+    /// The accounts which sent the deploy.
+    accounts: HashSet<PublicKey>,
+    /// The formatted strings of keys touched during execution.
+    keys: HashSet<String>,
+    /// The contracts invoked by hash from the deploy's payment or session code.
+    contract_hashes: HashSet<ContractHash>,
+    /// The deploy hashes.
+    deploy_hashes: HashSet<DeployHash>,
+    /// The topics of contract messages.
+    topics: HashSet<String>,
+}
+
+impl ContentFilter {
+    /// Returns `true` if no content criteria are set.
+    fn is_empty(&self) -> bool {
+        self.accounts.is_empty()
+            && self.keys.is_empty()
+            && self.contract_hashes.is_empty()
+            && self.deploy_hashes.is_empty()
+            && self.topics.is_empty()
+    }
+
+    /// Returns `true` if the given event should be sent to the client.
+    pub(super) fn should_include(&self, data: &SseData) -> bool {
+        if self.is_empty() {
+            return true;
+        }
+
+        match data {
+            SseData::ApiVersion(_)
+            | SseData::BlockAdded { .. }
+            | SseData::Fault { .. }
+            | SseData::FinalitySignature(_)
+            | SseData::Shutdown => true,
+            SseData::DeployAccepted { deploy } => {
+                // The deploy has not been executed yet, so no keys have been touched.
+                self.keys.is_empty()
+                    && self.topics.is_empty()
+                    && self.matches_account(deploy.header().account())
+                    && self.matches_deploy_hash(deploy.id())
+                    && self.matches_contract_hash(&deploy.invoked_contracts())
+            }
+            SseData::DeployProcessed {
+                deploy_hash,
+                account,
+                execution_result,
+                invoked_contracts,
+                ..
+            } => {
+                let effect = match &**execution_result {
+                    ExecutionResult::Success { effect, .. }
+                    | ExecutionResult::Failure { effect, .. } => effect,
+                };
+                self.topics.is_empty()
+                    && self.matches_account(account)
+                    && self.matches_deploy_hash(deploy_hash)
+                    && self.matches_keys(effect)
+                    && self.matches_contract_hash(invoked_contracts)
+            }
+            SseData::DeployExpired { deploy_hash } => {
+                self.accounts.is_empty()
+                    && self.keys.is_empty()
+                    && self.contract_hashes.is_empty()
+                    && self.topics.is_empty()
+                    && self.matches_deploy_hash(deploy_hash)
+            }
+            SseData::ContractMessage {
+                deploy_hash,
+                message,
+                ..
+            } => {
+                // Messages are attributed to the emitting contract or, for session code, account.
+                let emitter = message.emitter();
+                self.matches_account_key(emitter)
+                    && self.matches_deploy_hash(deploy_hash)
+                    && (self.keys.is_empty() || self.keys.contains(&emitter.to_formatted_string()))
+                    && (self.contract_hashes.is_empty()
+                        || self
+                            .contract_hashes
+                            .iter()
+                            .any(|contract_hash| *emitter == Key::from(*contract_hash)))
+                    && (self.topics.is_empty() || self.topics.contains(message.topic()))
+            }
+            SseData::Step {
+                execution_effect, ..
+            } => {
+                self.accounts.is_empty()
+                    && self.contract_hashes.is_empty()
+                    && self.deploy_hashes.is_empty()
+                    && self.topics.is_empty()
+                    && self.matches_keys(execution_effect)
+            }
+        }
+    }
+
+    fn matches_account(&self, account: &PublicKey) -> bool {
+        self.accounts.is_empty() || self.accounts.contains(account)
+    }
+
+    fn matches_account_key(&self, key: &Key) -> bool {
+        self.accounts.is_empty()
+            || self
+                .accounts
+                .iter()
+                .any(|account| *key == Key::Account(account.to_account_hash()))
+    }
+
+    fn matches_deploy_hash(&self, deploy_hash: &DeployHash) -> bool {
+        self.deploy_hashes.is_empty() || self.deploy_hashes.contains(deploy_hash)
+    }
+
+    fn matches_keys(&self, effect: &ExecutionEffect) -> bool {
+        self.keys.is_empty() || self.keys.iter().any(|key| touches_key(effect, key))
+    }
+
+    fn matches_contract_hash(&self, invoked_contracts: &[ContractHash]) -> bool {
+        self.contract_hashes.is_empty()
+            || invoked_contracts
+                .iter()
+                .any(|contract_hash| self.contract_hashes.contains(contract_hash))
+    }
+}
+
+/// Returns `true` if the given formatted key appears in the execution effect's transforms.
+fn touches_key(effect: &ExecutionEffect, formatted_key: &str) -> bool {
+    effect
+        .transforms
+        .iter()
+        .any(|transform_entry| transform_entry.key == formatted_key)
+}
+
 /// Filters the `event`, mapping it to a warp event, or `None` if it should be filtered out.
 async fn filter_map_server_sent_event(
     event: &ServerSentEvent,
     event_filter: &[EventFilter],
+    content_filter: &ContentFilter,
 ) -> Option<Result<WarpServerSentEvent, RecvError>> {
-    if !event.data.should_include(event_filter) {
+    if !event.data.should_include(event_filter) || !content_filter.should_include(&event.data) {
         return None;
     }
 
@@ -313,6 +550,7 @@ async fn filter_map_server_sent_event(
         &SseData::BlockAdded { .. }
         | &SseData::DeployProcessed { .. }
         | &SseData::DeployExpired { .. }
+        | &SseData::ContractMessage { .. }
         | &SseData::Fault { .. }
         | &SseData::Step { .. }
         | &SseData::FinalitySignature(_)
@@ -346,26 +584,63 @@ pub(super) fn get_filter(path_param: &str) -> Option<&'static [EventFilter]> {
     }
 }
 
-/// Extracts the starting event ID from the provided query, or `None` if `query` is empty.
+/// Extracts the starting event ID and the content filter from the provided query.
 ///
-/// If `query` is not empty, returns a 422 response if `query` doesn't have exactly one entry,
-/// "starts_from" mapped to a value representing an event ID.
-fn parse_query(query: HashMap<String, String>) -> Result<Option<Id>, Response> {
-    if query.is_empty() {
-        return Ok(None);
+/// The starting event ID is `None` if `query` has no "start_from" entry.  Returns a 422 response
+/// if `query` has any unknown fields, or if any of its values fail to parse.
+fn parse_query(query: HashMap<String, String>) -> Result<(Option<Id>, ContentFilter), Response> {
+    let mut start_from = None;
+    let mut content_filter = ContentFilter::default();
+
+    for (field, value) in query {
+        match field.as_str() {
+            QUERY_FIELD => {
+                start_from = Some(value.parse::<Id>().map_err(|_| create_422())?);
+            }
+            ACCOUNT_QUERY_FIELD => {
+                content_filter.accounts =
+                    parse_query_values(&value, |account| PublicKey::from_hex(account).ok())?;
+            }
+            KEY_QUERY_FIELD => {
+                content_filter.keys = parse_query_values(&value, |key| {
+                    Key::from_formatted_str(key)
+                        .map(Key::to_formatted_string)
+                        .ok()
+                })?;
+            }
+            CONTRACT_HASH_QUERY_FIELD => {
+                content_filter.contract_hashes = parse_query_values(&value, |contract_hash| {
+                    ContractHash::from_formatted_str(contract_hash).ok()
+                })?;
+            }
+            DEPLOY_HASH_QUERY_FIELD => {
+                content_filter.deploy_hashes = parse_query_values(&value, |deploy_hash| {
+                    Digest::from_hex(deploy_hash).map(DeployHash::new).ok()
+                })?;
+            }
+            TOPIC_QUERY_FIELD => {
+                content_filter.topics = parse_query_values(&value, |topic| {
+                    (!topic.is_empty()).then(|| topic.to_string())
+                })?;
+            }
+            _ => return Err(create_422()),
+        }
     }
 
-    if query.len() > 1 {
-        return Err(create_422());
-    }
+    Ok((start_from, content_filter))
+}
 
-    match query
-        .get(QUERY_FIELD)
-        .and_then(|id_str| id_str.parse::<Id>().ok())
-    {
-        Some(id) => Ok(Some(id)),
-        None => Err(create_422()),
-    }
+/// Parses each of the comma-separated values of a single query field, returning a 422 response if
+/// any of them fail to parse.
+fn parse_query_values<T, F>(value: &str, parse: F) -> Result<HashSet<T>, Response>
+where
+    T: Eq + std::hash::Hash,
+    F: Fn(&str) -> Option<T>,
+{
+    value
+        .split(QUERY_VALUE_SEPARATOR)
+        .map(|item| parse(item.trim()).ok_or_else(create_422))
+        .collect()
 }
 
 /// Creates a 404 response with a useful error message in the body.
@@ -385,13 +660,35 @@ fn create_404() -> Response {
 /// string.
 fn create_422() -> Response {
     let mut response = Response::new(Body::from(format!(
-        "invalid query: expected single field '{}=<EVENT ID>'\n",
-        QUERY_FIELD
+        "invalid query: expected only the optional fields '{}=<EVENT ID>', \
+        '{}=<PUBLIC KEYS>', '{}=<KEYS>', '{}=<CONTRACT HASHES>', '{}=<DEPLOY HASHES>' and \
+        '{}=<TOPICS>', with multiple values separated by '{}'\n",
+        QUERY_FIELD,
+        ACCOUNT_QUERY_FIELD,
+        KEY_QUERY_FIELD,
+        CONTRACT_HASH_QUERY_FIELD,
+        DEPLOY_HASH_QUERY_FIELD,
+        TOPIC_QUERY_FIELD,
+        QUERY_VALUE_SEPARATOR
     )));
     *response.status_mut() = StatusCode::UNPROCESSABLE_ENTITY;
     response
 }
 
+/// Creates a 416 response (Range Not Satisfiable) with the oldest retained event ID in the body,
+/// for use in case of a request to start from an event which is not retained in the event log.
+fn create_416(start_from: Id, retained_range: RetainedRange) -> Response {
+    let mut response = Response::new(Body::from(format!(
+        "invalid query: event ID {} is not retained: the oldest retained event ID is {} and the \
+        next event ID is {}\n",
+        start_from,
+        retained_range.oldest(),
+        retained_range.next()
+    )));
+    *response.status_mut() = StatusCode::RANGE_NOT_SATISFIABLE;
+    response
+}
+
 /// Creates a 503 response (Service Unavailable) to be returned if the server has too many
 /// subscribers.
 fn create_503() -> Response {
@@ -409,7 +706,14 @@ pub(super) struct ChannelsAndFilter {
 impl ChannelsAndFilter {
     /// Creates the message-passing channels required to run the event-stream server and the warp
     /// filter for the event-stream server.
-    pub(super) fn new(broadcast_channel_size: usize, max_concurrent_subscribers: u32) -> Self {
+    ///
+    /// If the on-disk event log is enabled, `retained_range` is used to reject requests to start
+    /// from an event ID which is not retained in the log.
+    pub(super) fn new(
+        broadcast_channel_size: usize,
+        max_concurrent_subscribers: u32,
+        retained_range: Option<Arc<RwLock<RetainedRange>>>,
+    ) -> Self {
         // Create a channel to broadcast new events to all subscribed clients' streams.
         let (event_broadcaster, _) = broadcast::channel(broadcast_channel_size);
         let cloned_broadcaster = event_broadcaster.clone();
@@ -442,13 +746,23 @@ impl ChannelsAndFilter {
                 None => return create_404(),
             };
 
-            let start_from = match parse_query(query) {
-                Ok(maybe_id) => maybe_id,
+            let (start_from, content_filter) = match parse_query(query) {
+                Ok(parsed) => parsed,
                 Err(error_response) => return error_response,
             };
 
+            // If the event log is enabled and doesn't retain the requested starting event, return a
+            // 416.
+            if let (Some(start_id), Some(retained_range)) = (start_from, retained_range.as_ref()) {
+                let retained_range = *retained_range.read().unwrap();
+                if !retained_range.contains(start_id) {
+                    return create_416(start_id, retained_range);
+                }
+            }
+
             // Create a channel for the client's handler to receive the stream of initial events.
-            let (initial_events_sender, initial_events_receiver) = mpsc::unbounded_channel();
+            let (initial_events_sender, initial_events_receiver) =
+                mpsc::channel(INITIAL_EVENTS_CHANNEL_SIZE);
 
             // Supply the server with the sender part of the channel along with the client's
             // requested starting point.
@@ -470,6 +784,7 @@ impl ChannelsAndFilter {
                 initial_events_receiver,
                 ongoing_events_receiver,
                 event_filter,
+                content_filter,
                 remote_address,
             )))
             .into_response()
@@ -505,12 +820,13 @@ impl ChannelsAndFilter {
 /// variant via the channel).  This channel will receive all SSEs created from the moment the client
 /// subscribed to the server's event stream.
 ///
-/// It also takes an `EventFilter` which causes events to which the client didn't subscribe to be
-/// skipped.
+/// It also takes an `EventFilter` and a `ContentFilter` which cause events to which the client
+/// didn't subscribe to be skipped.
 fn stream_to_client(
-    initial_events: mpsc::UnboundedReceiver<ServerSentEvent>,
+    initial_events: mpsc::Receiver<ServerSentEvent>,
     ongoing_events: broadcast::Receiver<BroadcastChannelMessage>,
     event_filter: &'static [EventFilter],
+    content_filter: ContentFilter,
     remote_address: String,
 ) -> impl Stream<Item = Result<WarpServerSentEvent, RecvError>> + 'static {
     // Keep a record of the IDs of the events delivered via the `initial_events` receiver.
@@ -549,8 +865,9 @@ fn stream_to_client(
         .take_while(|result| future::ready(!matches!(result, Err(RecvError::Closed))));
 
     // Serve the initial events followed by the ongoing ones, filtering as dictated by the
-    // `event_filter`.
-    UnboundedReceiverStream::new(initial_events)
+    // `event_filter` and `content_filter`.
+    let content_filter = Arc::new(content_filter);
+    ReceiverStream::new(initial_events)
         .map(move |event| {
             if let Some(id) = event.id {
                 let _ = initial_stream_ids.write().unwrap().insert(id);
@@ -558,10 +875,15 @@ fn stream_to_client(
             Ok(event)
         })
         .chain(ongoing_stream)
-        .filter_map(move |result| async move {
-            match result {
-                Ok(event) => filter_map_server_sent_event(&event, event_filter).await,
-                Err(error) => Some(Err(error)),
+        .filter_map(move |result| {
+            let content_filter = Arc::clone(&content_filter);
+            async move {
+                match result {
+                    Ok(event) => {
+                        filter_map_server_sent_event(&event, event_filter, &content_filter).await
+                    }
+                    Err(error) => Some(Err(error)),
+                }
             }
         })
 }
@@ -570,14 +892,16 @@ fn stream_to_client(
 mod tests {
     use std::iter;
 
-    use casper_types::testing::TestRng;
+    use casper_types::{testing::TestRng, Transform, TransformEntry};
 
     use super::*;
     use crate::logging;
 
     async fn should_filter_out(event: &ServerSentEvent, filter: &'static [EventFilter]) {
         assert!(
-            filter_map_server_sent_event(event, filter).await.is_none(),
+            filter_map_server_sent_event(event, filter, &ContentFilter::default())
+                .await
+                .is_none(),
             "should filter out {:?} with {:?}",
             event,
             filter
@@ -586,7 +910,9 @@ mod tests {
 
     async fn should_not_filter_out(event: &ServerSentEvent, filter: &'static [EventFilter]) {
         assert!(
-            filter_map_server_sent_event(event, filter).await.is_some(),
+            filter_map_server_sent_event(event, filter, &ContentFilter::default())
+                .await
+                .is_some(),
             "should not filter out {:?} with {:?}",
             event,
             filter
@@ -623,6 +949,10 @@ mod tests {
             id: Some(rng.gen()),
             data: SseData::random_deploy_expired(&mut rng),
         };
+        let contract_message = ServerSentEvent {
+            id: Some(rng.gen()),
+            data: SseData::random_contract_message(&mut rng),
+        };
         let fault = ServerSentEvent {
             id: Some(rng.gen()),
             data: SseData::random_fault(&mut rng),
@@ -645,6 +975,7 @@ mod tests {
         should_not_filter_out(&block_added, &MAIN_FILTER[..]).await;
         should_not_filter_out(&deploy_processed, &MAIN_FILTER[..]).await;
         should_not_filter_out(&deploy_expired, &MAIN_FILTER[..]).await;
+        should_not_filter_out(&contract_message, &MAIN_FILTER[..]).await;
         should_not_filter_out(&fault, &MAIN_FILTER[..]).await;
         should_not_filter_out(&step, &MAIN_FILTER[..]).await;
         should_not_filter_out(&shutdown, &MAIN_FILTER).await;
@@ -661,6 +992,7 @@ mod tests {
         should_filter_out(&block_added, &DEPLOYS_FILTER[..]).await;
         should_filter_out(&deploy_processed, &DEPLOYS_FILTER[..]).await;
         should_filter_out(&deploy_expired, &DEPLOYS_FILTER[..]).await;
+        should_filter_out(&contract_message, &DEPLOYS_FILTER[..]).await;
         should_filter_out(&fault, &DEPLOYS_FILTER[..]).await;
         should_filter_out(&finality_signature, &DEPLOYS_FILTER[..]).await;
         should_filter_out(&step, &DEPLOYS_FILTER[..]).await;
@@ -675,6 +1007,7 @@ mod tests {
         should_filter_out(&deploy_accepted, &SIGNATURES_FILTER[..]).await;
         should_filter_out(&deploy_processed, &SIGNATURES_FILTER[..]).await;
         should_filter_out(&deploy_expired, &SIGNATURES_FILTER[..]).await;
+        should_filter_out(&contract_message, &SIGNATURES_FILTER[..]).await;
         should_filter_out(&fault, &SIGNATURES_FILTER[..]).await;
         should_filter_out(&step, &SIGNATURES_FILTER[..]).await;
     }
@@ -709,6 +1042,10 @@ mod tests {
             id: None,
             data: SseData::random_deploy_expired(&mut rng),
         };
+        let malformed_contract_message = ServerSentEvent {
+            id: None,
+            data: SseData::random_contract_message(&mut rng),
+        };
         let malformed_fault = ServerSentEvent {
             id: None,
             data: SseData::random_fault(&mut rng),
@@ -736,6 +1073,7 @@ mod tests {
             should_filter_out(&malformed_deploy_accepted, filter).await;
             should_filter_out(&malformed_deploy_processed, filter).await;
             should_filter_out(&malformed_deploy_expired, filter).await;
+            should_filter_out(&malformed_contract_message, filter).await;
             should_filter_out(&malformed_fault, filter).await;
             should_filter_out(&malformed_finality_signature, filter).await;
             should_filter_out(&malformed_step, filter).await;
@@ -834,13 +1172,14 @@ mod tests {
                 &mut deploys,
             );
 
-            let (initial_events_sender, initial_events_receiver) = mpsc::unbounded_channel();
+            let (initial_events_sender, initial_events_receiver) =
+                mpsc::channel(initial_events.len());
             let (ongoing_events_sender, ongoing_events_receiver) =
                 broadcast::channel(NUM_INITIAL_EVENTS + NUM_ONGOING_EVENTS + 1);
 
             // Send all the events.
             for event in initial_events.iter().cloned() {
-                initial_events_sender.send(event).unwrap();
+                initial_events_sender.try_send(event).unwrap();
             }
             for event in ongoing_events.iter().cloned() {
                 let _ = ongoing_events_sender
@@ -856,6 +1195,7 @@ mod tests {
                 initial_events_receiver,
                 ongoing_events_receiver,
                 get_filter(path_filter).unwrap(),
+                ContentFilter::default(),
                 "127.0.0.1:3456".to_string(),
             )
             .collect()
@@ -921,4 +1261,225 @@ mod tests {
     async fn should_filter_duplicate_signature_events() {
         should_filter_duplicate_events(SSE_API_SIGNATURES_PATH).await
     }
+
+    /// This test checks that events are correctly identified as relating to a given account or
+    /// deploy.
+    #[test]
+    fn should_identify_involved_accounts_and_deploys() {
+        let mut rng = crate::new_rng();
+        let other_account = PublicKey::random(&mut rng);
+        let other_deploy_hash = DeployHash::random(&mut rng);
+
+        let (deploy_accepted, deploy) = SseData::random_deploy_accepted(&mut rng);
+        assert!(deploy_accepted.involves_account(deploy.header().account()));
+        assert!(deploy_accepted.involves_deploy(deploy.id()));
+        assert!(!deploy_accepted.involves_account(&other_account));
+        assert!(!deploy_accepted.involves_deploy(&other_deploy_hash));
+
+        let deploy_processed = SseData::random_deploy_processed(&mut rng);
+        if let SseData::DeployProcessed {
+            deploy_hash,
+            account,
+            ..
+        } = &deploy_processed
+        {
+            assert!(deploy_processed.involves_account(account));
+            assert!(deploy_processed.involves_deploy(deploy_hash));
+        }
+        assert!(!deploy_processed.involves_account(&other_account));
+        assert!(!deploy_processed.involves_deploy(&other_deploy_hash));
+
+        let block_added = SseData::random_block_added(&mut rng);
+        if let SseData::BlockAdded { block, .. } = &block_added {
+            assert!(block_added.involves_account(block.body.proposer()));
+            for deploy_hash in block
+                .body
+                .deploy_hashes()
+                .iter()
+                .chain(block.body.transfer_hashes())
+            {
+                assert!(block_added.involves_deploy(deploy_hash));
+            }
+        }
+        assert!(!block_added.involves_account(&other_account));
+        assert!(!block_added.involves_deploy(&other_deploy_hash));
+
+        let contract_message = SseData::random_contract_message(&mut rng);
+        if let SseData::ContractMessage { deploy_hash, .. } = &contract_message {
+            assert!(contract_message.involves_deploy(deploy_hash));
+        }
+        assert!(!contract_message.involves_account(&other_account));
+        assert!(!contract_message.involves_deploy(&other_deploy_hash));
+
+        let step = SseData::random_step(&mut rng);
+        assert!(!step.involves_account(&other_account));
+        assert!(!step.involves_deploy(&other_deploy_hash));
+
+        assert!(SseData::Shutdown.involves_account(&other_account));
+        assert!(SseData::Shutdown.involves_deploy(&other_deploy_hash));
+    }
+
+    fn parse(query: &[(&str, &str)]) -> ContentFilter {
+        let query = query
+            .iter()
+            .map(|(field, value)| (field.to_string(), value.to_string()))
+            .collect();
+        parse_query(query).unwrap().1
+    }
+
+    /// This test checks that events are filtered by content as per the query string.
+    #[test]
+    fn should_filter_by_content() {
+        let mut rng = crate::new_rng();
+        let contract_hash = ContractHash::new(rng.gen());
+        let touched_contract_hash = ContractHash::new(rng.gen());
+        let touched_key = Key::from(touched_contract_hash).to_formatted_string();
+
+        let mut deploy_processed = SseData::random_deploy_processed(&mut rng);
+        let (account, deploy_hash) = match &mut deploy_processed {
+            SseData::DeployProcessed {
+                deploy_hash,
+                account,
+                execution_result,
+                invoked_contracts,
+                ..
+            } => {
+                *invoked_contracts = vec![contract_hash];
+                let effect = match &mut **execution_result {
+                    ExecutionResult::Success { effect, .. }
+                    | ExecutionResult::Failure { effect, .. } => effect,
+                };
+                effect.transforms.push(TransformEntry {
+                    key: touched_key.clone(),
+                    transform: Transform::Identity,
+                });
+                (account.to_hex(), format!("{:x}", deploy_hash.inner()))
+            }
+            _ => unreachable!(),
+        };
+        let other_account = PublicKey::random(&mut rng).to_hex();
+        let other_key = Key::Hash(rng.gen()).to_formatted_string();
+
+        let contract_hash = contract_hash.to_formatted_string();
+        let touched_contract_hash = touched_contract_hash.to_formatted_string();
+
+        // An empty filter includes everything.
+        assert!(ContentFilter::default().should_include(&deploy_processed));
+
+        // Each matching criterion includes the event.
+        let matching_queries = [
+            vec![(ACCOUNT_QUERY_FIELD, account.as_str())],
+            vec![(DEPLOY_HASH_QUERY_FIELD, deploy_hash.as_str())],
+            vec![(KEY_QUERY_FIELD, touched_key.as_str())],
+            vec![(CONTRACT_HASH_QUERY_FIELD, contract_hash.as_str())],
+        ];
+        for query in &matching_queries {
+            assert!(parse(query).should_include(&deploy_processed));
+        }
+
+        // Any one of multiple values within a single criterion matches.
+        let accounts = format!("{},{}", other_account, account);
+        assert!(parse(&[(ACCOUNT_QUERY_FIELD, &accounts)]).should_include(&deploy_processed));
+
+        // All criteria must match.
+        let filter = parse(&[
+            (ACCOUNT_QUERY_FIELD, &account),
+            (KEY_QUERY_FIELD, &other_key),
+        ]);
+        assert!(!filter.should_include(&deploy_processed));
+        assert!(!parse(&[(ACCOUNT_QUERY_FIELD, &other_account)]).should_include(&deploy_processed));
+        // Touching a contract's key during execution doesn't count as invoking it.
+        assert!(
+            !parse(&[(CONTRACT_HASH_QUERY_FIELD, &touched_contract_hash)])
+                .should_include(&deploy_processed)
+        );
+
+        // Events not relating to deploys are unaffected.
+        let block_added = SseData::random_block_added(&mut rng);
+        assert!(filter.should_include(&block_added));
+        assert!(filter.should_include(&SseData::Shutdown));
+
+        // Deploy expiry events can only match on deploy hash.
+        let deploy_expired = SseData::random_deploy_expired(&mut rng);
+        assert!(!filter.should_include(&deploy_expired));
+
+        // Topics only apply to contract messages.
+        assert!(!parse(&[(TOPIC_QUERY_FIELD, "transfer")]).should_include(&deploy_processed));
+    }
+
+    /// This test checks that contract messages are filtered by emitter, deploy hash and topic.
+    #[test]
+    fn should_filter_contract_messages_by_content() {
+        let mut rng = crate::new_rng();
+        let contract_hash = ContractHash::new(rng.gen());
+        let deploy_hash = DeployHash::random(&mut rng);
+        let contract_message = SseData::ContractMessage {
+            deploy_hash: Box::new(deploy_hash),
+            block_hash: Box::new(BlockHash::random(&mut rng)),
+            message: Box::new(ContractMessage::new(
+                Key::from(contract_hash),
+                "transfer".to_string(),
+                CLValue::from_t(rng.gen::<u64>()).unwrap(),
+            )),
+        };
+        let contract_key = Key::from(contract_hash).to_formatted_string();
+        let contract_hash = contract_hash.to_formatted_string();
+        let deploy_hash = format!("{:x}", deploy_hash.inner());
+        let other_contract_hash = ContractHash::new(rng.gen()).to_formatted_string();
+
+        let matching_queries = [
+            vec![(CONTRACT_HASH_QUERY_FIELD, contract_hash.as_str())],
+            vec![(KEY_QUERY_FIELD, contract_key.as_str())],
+            vec![(DEPLOY_HASH_QUERY_FIELD, deploy_hash.as_str())],
+            vec![(TOPIC_QUERY_FIELD, "transfer")],
+            vec![(TOPIC_QUERY_FIELD, "mint,transfer")],
+            vec![
+                (CONTRACT_HASH_QUERY_FIELD, contract_hash.as_str()),
+                (TOPIC_QUERY_FIELD, "transfer"),
+            ],
+        ];
+        for query in &matching_queries {
+            assert!(
+                parse(query).should_include(&contract_message),
+                "{:?}",
+                query
+            );
+        }
+
+        let account = PublicKey::random(&mut rng).to_hex();
+        let non_matching_queries = [
+            vec![(CONTRACT_HASH_QUERY_FIELD, other_contract_hash.as_str())],
+            vec![(TOPIC_QUERY_FIELD, "mint")],
+            vec![(ACCOUNT_QUERY_FIELD, account.as_str())],
+            vec![
+                (CONTRACT_HASH_QUERY_FIELD, contract_hash.as_str()),
+                (TOPIC_QUERY_FIELD, "mint"),
+            ],
+        ];
+        for query in &non_matching_queries {
+            assert!(
+                !parse(query).should_include(&contract_message),
+                "{:?}",
+                query
+            );
+        }
+    }
+
+    /// This test checks that invalid query strings are rejected.
+    #[test]
+    fn should_reject_invalid_query() {
+        let invalid_queries = [
+            (QUERY_FIELD, "not-integer"),
+            (ACCOUNT_QUERY_FIELD, "01"),
+            (KEY_QUERY_FIELD, "not-a-key"),
+            (CONTRACT_HASH_QUERY_FIELD, "hash-0000"),
+            (DEPLOY_HASH_QUERY_FIELD, ""),
+            (TOPIC_QUERY_FIELD, "transfer,"),
+            ("unknown", "0"),
+        ];
+        for (field, value) in invalid_queries {
+            let query = iter::once((field.to_string(), value.to_string())).collect();
+            assert!(parse_query(query).is_err(), "{}={}", field, value);
+        }
+    }
 }
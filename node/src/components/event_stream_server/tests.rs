@@ -17,7 +17,7 @@ use reqwest::Response;
 use schemars::schema_for;
 use tempfile::TempDir;
 use tokio::{
-    sync::{Barrier, Notify},
+    sync::{broadcast::error::RecvError, Barrier, Notify},
     task::{self, JoinHandle},
     time,
 };
@@ -26,9 +26,10 @@ use tracing::debug;
 use super::*;
 use crate::{logging, testing::assert_schema};
 use sse_server::{
-    DeployAccepted, Id, QUERY_FIELD, SSE_API_DEPLOYS_PATH as DEPLOYS_PATH,
+    DeployAccepted, Id, ACCOUNT_QUERY_FIELD, CONTRACT_HASH_QUERY_FIELD, DEPLOY_HASH_QUERY_FIELD,
+    KEY_QUERY_FIELD, QUERY_FIELD, SSE_API_DEPLOYS_PATH as DEPLOYS_PATH,
     SSE_API_MAIN_PATH as MAIN_PATH, SSE_API_ROOT_PATH as ROOT_PATH,
-    SSE_API_SIGNATURES_PATH as SIGS_PATH,
+    SSE_API_SIGNATURES_PATH as SIGS_PATH, TOPIC_QUERY_FIELD,
 };
 
 /// The total number of random events each `EventStreamServer` will emit by default, excluding the
@@ -81,6 +82,8 @@ struct ServerBehavior {
     /// If `Some`, sets the `max_concurrent_subscribers` server config value, otherwise uses the
     /// config default.
     max_concurrent_subscribers: Option<u32>,
+    /// Whether the server should enable the on-disk event log.
+    enable_event_log: bool,
     clients: Vec<ClientSyncBehavior>,
 }
 
@@ -93,6 +96,7 @@ impl ServerBehavior {
             has_delay_between_events: true,
             repeat_events: false,
             max_concurrent_subscribers: None,
+            enable_event_log: false,
             clients: Vec::new(),
         }
     }
@@ -106,6 +110,7 @@ impl ServerBehavior {
             has_delay_between_events: false,
             repeat_events: true,
             max_concurrent_subscribers: None,
+            enable_event_log: false,
             clients: Vec::new(),
         }
     }
@@ -123,6 +128,11 @@ impl ServerBehavior {
         self.max_concurrent_subscribers = Some(count);
     }
 
+    /// Enables the on-disk event log in the server config.
+    fn enable_event_log(&mut self) {
+        self.enable_event_log = true;
+    }
+
     /// Waits for all clients which specified they wanted to join just before the given event ID.
     async fn wait_for_clients(&self, id: Id) {
         for client_behavior in &self.clients {
@@ -198,7 +208,7 @@ struct TestFixture {
 impl TestFixture {
     /// Constructs a new `TestFixture` including `EVENT_COUNT` random events ready to be served.
     fn new(rng: &mut TestRng) -> Self {
-        const DISTINCT_EVENTS_COUNT: u32 = 7;
+        const DISTINCT_EVENTS_COUNT: u32 = 8;
 
         let _ = logging::init();
         let storage_dir = tempfile::tempdir().unwrap();
@@ -219,6 +229,7 @@ impl TestFixture {
                 4 => SseData::random_fault(rng),
                 5 => SseData::random_step(rng),
                 6 => SseData::random_finality_signature(rng),
+                7 => SseData::random_contract_message(rng),
                 _ => unreachable!(),
             })
             .collect();
@@ -261,6 +272,7 @@ impl TestFixture {
             max_concurrent_subscribers: server_behavior
                 .max_concurrent_subscribers
                 .unwrap_or(Config::default().max_concurrent_subscribers),
+            enable_event_log: server_behavior.enable_event_log,
             ..Default::default()
         };
         let mut server = EventStreamServer::new(
@@ -296,10 +308,35 @@ impl TestFixture {
                 server_behavior.sleep_if_required().await;
             }
 
+            // Allow clients to sync before the `Shutdown` event which is sent when the server is
+            // dropped.
+            server_behavior
+                .wait_for_clients(event_count.wrapping_add(first_event_id))
+                .await;
+
             // Keep the server running until told to stop.  Clients connecting from now will only
             // receive keepalives.
             debug!("server finished sending all events");
             server_stopper.wait().await;
+
+            // Dropping the server sends the `Shutdown` event, which is appended to the event log by
+            // the HTTP server task.  Wait for that task to exit so that a server started after this
+            // one opens the complete log.
+            let mut receiver = server
+                .inner
+                .as_ref()
+                .unwrap()
+                .handle
+                .event_broadcaster
+                .subscribe();
+            drop(server);
+            loop {
+                match receiver.recv().await {
+                    Ok(BroadcastChannelMessage::Shutdown) | Err(RecvError::Closed) => break,
+                    Ok(BroadcastChannelMessage::ServerSentEvent(_)) | Err(RecvError::Lagged(_)) => {
+                    }
+                }
+            }
             debug!("server stopped");
         });
 
@@ -944,11 +981,29 @@ async fn should_handle_bad_url_query() {
         format!("{}?{}=0&extra=1", main_url, QUERY_FIELD),
         format!("{}?{}=0&extra=1", deploys_url, QUERY_FIELD),
         format!("{}?{}=0&extra=1", sigs_url, QUERY_FIELD),
+        format!("{}?{}=not-a-public-key", main_url, ACCOUNT_QUERY_FIELD),
+        format!("{}?{}=hash-00", main_url, KEY_QUERY_FIELD),
+        format!(
+            "{}?{}=not-a-contract-hash",
+            deploys_url, CONTRACT_HASH_QUERY_FIELD
+        ),
+        format!(
+            "{}?{}=0&{}=00,",
+            main_url, QUERY_FIELD, DEPLOY_HASH_QUERY_FIELD
+        ),
+        format!("{}?{}=transfer,", main_url, TOPIC_QUERY_FIELD),
     ];
 
     let expected_body = format!(
-        "invalid query: expected single field '{}=<EVENT ID>'",
-        QUERY_FIELD
+        "invalid query: expected only the optional fields '{}=<EVENT ID>', '{}=<PUBLIC KEYS>', \
+        '{}=<KEYS>', '{}=<CONTRACT HASHES>', '{}=<DEPLOY HASHES>' and '{}=<TOPICS>', with \
+        multiple values separated by ','",
+        QUERY_FIELD,
+        ACCOUNT_QUERY_FIELD,
+        KEY_QUERY_FIELD,
+        CONTRACT_HASH_QUERY_FIELD,
+        DEPLOY_HASH_QUERY_FIELD,
+        TOPIC_QUERY_FIELD
     );
     for url in &urls {
         let response = reqwest::get(url).await.unwrap();
@@ -1034,6 +1089,90 @@ async fn should_persist_signature_event_ids() {
     should_persist_event_ids(SIGS_PATH).await;
 }
 
+/// Check that a server with the event log enabled replays events sent before it restarted, and
+/// rejects requests to start from events which are not retained.
+async fn should_replay_from_event_log(path: &str) {
+    let mut rng = crate::new_rng();
+    let mut fixture = TestFixture::new(&mut rng);
+
+    let mut expected_events = {
+        // Run the first server to emit the 100 events.
+        let mut server_behavior = ServerBehavior::new();
+        server_behavior.enable_event_log();
+        let barrier = server_behavior.add_client_sync_before_event(0);
+        let shutdown_barrier = server_behavior.add_client_sync_before_event(EVENT_COUNT);
+        let server_address = fixture.run_server(server_behavior).await;
+
+        // Consume these, and stop the server once it has emitted all of them, not just the final
+        // one on this path.
+        let url = url(server_address, path, None);
+        let (expected_events, final_id) = fixture.all_filtered_events(path);
+        let _ = subscribe(&url, barrier, final_id, "client 1")
+            .await
+            .unwrap();
+        shutdown_barrier.wait().await;
+        shutdown_barrier.wait().await;
+        fixture.stop_server().await;
+        expected_events
+    };
+
+    // The first server also logged a `Shutdown` event when it stopped.
+    expected_events.push(ReceivedEvent {
+        id: Some(EVENT_COUNT),
+        data: serde_json::to_string(&SseData::Shutdown).unwrap(),
+    });
+
+    // Start a new server and once it has emitted all its events, request all events from the first
+    // run.  Connecting after the second run's events avoids the client lagging behind them while
+    // the first run's events are replayed.
+    let mut server_behavior = ServerBehavior::new();
+    server_behavior.enable_event_log();
+    let barrier = server_behavior.add_client_sync_before_event(EVENT_COUNT + 1 + EVENT_COUNT);
+    let server_address = fixture.run_server(server_behavior).await;
+
+    let url_from_start = url(server_address, path, Some(0));
+    let received_events = subscribe(&url_from_start, barrier, EVENT_COUNT, "client 2")
+        .await
+        .unwrap();
+
+    // The first run's events are replayed before any from the second run.
+    assert!(received_events.len() >= expected_events.len());
+    assert_eq!(received_events[..expected_events.len()], expected_events);
+
+    // A request for an event which isn't retained should be rejected.
+    let unretained_id = EVENT_COUNT * 10;
+    let response = reqwest::get(url(server_address, path, Some(unretained_id)))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::RANGE_NOT_SATISFIABLE);
+    let expected_body_start = format!(
+        "invalid query: event ID {} is not retained: the oldest retained event ID is 0",
+        unretained_id
+    );
+    assert!(response
+        .text()
+        .await
+        .unwrap()
+        .starts_with(&expected_body_start));
+
+    fixture.stop_server().await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn should_replay_main_events_from_event_log() {
+    should_replay_from_event_log(MAIN_PATH).await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn should_replay_deploy_accepted_events_from_event_log() {
+    should_replay_from_event_log(DEPLOYS_PATH).await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn should_replay_signature_events_from_event_log() {
+    should_replay_from_event_log(SIGS_PATH).await;
+}
+
 /// Check that a server handles wrapping round past the maximum value for event IDs.
 async fn should_handle_wrapping_past_max_event_id(path: &str) {
     let mut rng = crate::new_rng();
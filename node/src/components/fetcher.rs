@@ -33,7 +33,7 @@ use crate::{
     FetcherConfig, NodeRng,
 };
 
-use crate::effect::announcements::BlocklistAnnouncement;
+use crate::effect::announcements::{Offense, PeerBehaviorAnnouncement};
 pub(crate) use config::Config;
 pub(crate) use event::{Event, FetchResult, FetchedData, FetcherError};
 use metrics::Metrics;
@@ -44,7 +44,7 @@ pub(crate) trait ReactorEventT<T>:
     + From<NetworkRequest<Message>>
     + From<StorageRequest>
     + From<ContractRuntimeRequest>
-    + From<BlocklistAnnouncement>
+    + From<PeerBehaviorAnnouncement>
     // Won't be needed when we implement "get block by height" feature in storage.
     + Send
     + 'static
@@ -62,7 +62,7 @@ where
         + From<NetworkRequest<Message>>
         + From<StorageRequest>
         + From<ContractRuntimeRequest>
-        + From<BlocklistAnnouncement>
+        + From<PeerBehaviorAnnouncement>
         + Send
         + 'static,
 {
@@ -752,9 +752,23 @@ where
                         self.metrics().found_on_peer.inc();
                         if let Err(err) = item.validate() {
                             warn!(?peer, ?err, ?item, "Peer sent invalid item, banning peer");
-                            effect_builder.announce_disconnect_from_peer(peer).ignore()
+                            effect_builder
+                                .announce_offense(peer, Offense::InvalidItem)
+                                .ignore()
                         } else {
-                            self.signal(item.id(), Ok(*item), peer)
+                            // Only reward the peer for responses we asked it for, so it cannot
+                            // raise its score by replaying valid items.
+                            let id = item.id();
+                            let requested = self
+                                .responders()
+                                .get(&id)
+                                .map_or(false, |peers| peers.contains_key(&peer));
+                            let mut effects = self.signal(id, Ok(*item), peer);
+                            if requested {
+                                effects
+                                    .extend(effect_builder.announce_useful_response(peer).ignore());
+                            }
+                            effects
                         }
                     }
                     Source::Client | Source::Ourself => {
@@ -769,7 +783,16 @@ where
                 self.signal(id, Err(FetcherError::Absent { id, peer }), peer)
             }
             Event::TimeoutPeer { id, peer } => {
-                self.signal(id, Err(FetcherError::TimedOut { id, peer }), peer)
+                // Only hold the timeout against the peer if it has not responded in the meantime.
+                let still_waiting = self
+                    .responders()
+                    .get(&id)
+                    .map_or(false, |peers| peers.contains_key(&peer));
+                let mut effects = self.signal(id, Err(FetcherError::TimedOut { id, peer }), peer);
+                if still_waiting {
+                    effects.extend(effect_builder.announce_peer_timed_out(peer).ignore());
+                }
+                effects
             }
         }
     }
@@ -818,7 +841,8 @@ async fn has_enough_block_signatures<REv>(
     fault_tolerance_fraction: Ratio<u64>,
 ) -> bool
 where
-    REv: From<StorageRequest> + From<ContractRuntimeRequest> + From<BlocklistAnnouncement> + Send,
+    REv:
+        From<StorageRequest> + From<ContractRuntimeRequest> + From<PeerBehaviorAnnouncement> + Send,
 {
     let validator_weights =
         match linear_chain::era_validator_weights_for_block(block_header, effect_builder).await {
@@ -132,7 +132,9 @@ reactor!(Reactor {
         // No consensus component.
         ConsensusMessageIncoming -> [!];
         FinalitySignatureIncoming -> [!];
-        BlocklistAnnouncement -> [!];
+
+        // Peer behavior is not scored, as there is no networking component.
+        PeerBehaviorAnnouncement -> [#];
     }
 });
 
@@ -470,7 +470,7 @@ impl<T: Item + 'static, REv: ReactorEventT<T>> Gossiper<T, REv> {
         requester: NodeId,
     ) -> Effects<Event<T>> {
         match NodeMessage::new_get_response(&FetchedOrNotFound::Fetched(item)) {
-            Ok(message) => effect_builder.send_message(requester, message).ignore(),
+            Ok(message) => effect_builder.enqueue_message(requester, message).ignore(),
             Err(error) => {
                 error!("failed to create get-response: {}", error);
                 Effects::new()
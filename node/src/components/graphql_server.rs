@@ -0,0 +1,128 @@
+//! GraphQL server
+//!
+//! The GraphQL server provides clients with a read-only GraphQL API over blocks, deploys and their
+//! execution results, and selected global state (accounts, contracts, bids and era info).  It is
+//! disabled by default.
+//!
+//! Unlike the RPC and REST servers, this component handles no events of its own: queries are
+//! resolved directly via requests to the storage and contract runtime components, which lets a
+//! single GraphQL query traverse e.g. from a block to its deploys and their execution results.
+//!
+//! To protect the node, each query is subject to configurable depth and complexity limits, and the
+//! server as a whole is subject to its own rate limit, independent of those of the other servers.
+//!
+//! The single endpoint is `/graphql`, accepting both GET and POST requests.
+//!     example: curl -X POST -H 'Content-Type: application/json' \
+//!         -d '{"query": "{ block { height deploys { hash } } }"}' 'http://<ip>:7780/graphql'
+
+mod config;
+mod http_server;
+mod schema;
+#[cfg(test)]
+mod tests;
+
+use std::sync::Arc;
+
+use datasize::DataSize;
+use futures::{future::BoxFuture, FutureExt};
+use tokio::{sync::oneshot, task::JoinHandle};
+use tracing::{debug, error, info, warn};
+
+use crate::{
+    effect::{
+        requests::{ContractRuntimeRequest, StorageRequest},
+        EffectBuilder,
+    },
+    reactor::Finalize,
+    utils::{self, ListeningError},
+};
+pub use config::Config;
+
+/// A helper trait capturing all of this components Request type dependencies.
+pub(crate) trait ReactorEventT:
+    From<StorageRequest> + From<ContractRuntimeRequest> + Send
+{
+}
+
+impl<REv> ReactorEventT for REv where
+    REv: From<StorageRequest> + From<ContractRuntimeRequest> + Send + 'static
+{
+}
+
+#[derive(DataSize, Debug)]
+pub(crate) struct InnerGraphqlServer {
+    /// When the message is sent, it signals the server loop to exit cleanly.
+    #[data_size(skip)]
+    shutdown_sender: oneshot::Sender<()>,
+    /// The task handle which will only join once the server loop has exited.
+    #[data_size(skip)]
+    server_join_handle: Option<JoinHandle<()>>,
+}
+
+#[derive(DataSize, Debug)]
+pub(crate) struct GraphqlServer {
+    /// Inner server is present only when enabled in the config.
+    inner_graphql: Option<InnerGraphqlServer>,
+}
+
+impl GraphqlServer {
+    pub(crate) fn new<REv>(
+        config: Config,
+        effect_builder: EffectBuilder<REv>,
+    ) -> Result<Self, ListeningError>
+    where
+        REv: ReactorEventT,
+    {
+        if !config.enable_server {
+            return Ok(GraphqlServer {
+                inner_graphql: None,
+            });
+        }
+
+        let (shutdown_sender, shutdown_receiver) = oneshot::channel::<()>();
+
+        let schema = schema::build_schema(
+            Arc::new(effect_builder),
+            config.max_query_depth,
+            config.max_query_complexity,
+        );
+
+        let builder = utils::start_listening(&config.address)?;
+        let server_join_handle = Some(tokio::spawn(http_server::run(
+            builder,
+            schema,
+            shutdown_receiver,
+            config.qps_limit,
+        )));
+
+        Ok(GraphqlServer {
+            inner_graphql: Some(InnerGraphqlServer {
+                shutdown_sender,
+                server_join_handle,
+            }),
+        })
+    }
+}
+
+impl Finalize for GraphqlServer {
+    fn finalize(self) -> BoxFuture<'static, ()> {
+        async {
+            if let Some(mut graphql_server) = self.inner_graphql {
+                let _ = graphql_server.shutdown_sender.send(());
+
+                // Wait for the server to exit cleanly.
+                if let Some(join_handle) = graphql_server.server_join_handle.take() {
+                    match join_handle.await {
+                        Ok(_) => debug!("graphql server exited cleanly"),
+                        Err(error) => error!(%error, "could not join graphql server task cleanly"),
+                    }
+                } else {
+                    warn!("graphql server shutdown while already shut down")
+                }
+            } else {
+                info!("graphql server was disabled in config, no shutdown performed")
+            }
+        }
+        .boxed()
+    }
+}
@@ -0,0 +1,54 @@
+use datasize::DataSize;
+use serde::{Deserialize, Serialize};
+
+/// Default binding address for the GraphQL HTTP server.
+///
+/// Uses a fixed port per node, but binds on any interface.
+const DEFAULT_ADDRESS: &str = "0.0.0.0:0";
+/// Default rate limit in qps.
+const DEFAULT_QPS_LIMIT: u64 = 100;
+/// Default maximum nesting depth of a query.
+const DEFAULT_MAX_QUERY_DEPTH: usize = 8;
+/// Default maximum complexity of a query.
+const DEFAULT_MAX_QUERY_COMPLEXITY: usize = 1_000;
+
+/// GraphQL HTTP server configuration.
+#[derive(Clone, DataSize, Debug, Deserialize, Serialize)]
+// Disallow unknown fields to ensure config files and command-line overrides contain valid keys.
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// Setting to enable the HTTP server.
+    pub enable_server: bool,
+
+    /// Address to bind GraphQL HTTP server to.
+    pub address: String,
+
+    /// Max rate limit in qps.
+    pub qps_limit: u64,
+
+    /// Maximum nesting depth of a single query.
+    pub max_query_depth: usize,
+
+    /// Maximum complexity of a single query, where the fields selected from each item of a list
+    /// resolved via the backend count once per item the list may return.
+    pub max_query_complexity: usize,
+}
+
+impl Config {
+    /// Creates a default instance for `GraphqlServer`.
+    pub fn new() -> Self {
+        Config {
+            enable_server: false,
+            address: DEFAULT_ADDRESS.to_string(),
+            qps_limit: DEFAULT_QPS_LIMIT,
+            max_query_depth: DEFAULT_MAX_QUERY_DEPTH,
+            max_query_complexity: DEFAULT_MAX_QUERY_COMPLEXITY,
+        }
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config::new()
+    }
+}
@@ -0,0 +1,59 @@
+use std::convert::Infallible;
+
+use async_graphql_warp::GraphQLResponse;
+use futures::{future, TryFutureExt};
+use hyper::server::{conn::AddrIncoming, Builder};
+use tokio::sync::oneshot;
+use tracing::{info, warn};
+use warp::Filter;
+
+use super::schema::GraphqlSchema;
+use crate::utils::rate_limit::{self, QpsLimiter};
+
+/// The URL path of the GraphQL endpoint.
+const GRAPHQL_API_PATH: &str = "graphql";
+
+/// Run the GraphQL HTTP server.
+///
+/// A message received on `shutdown_receiver` will cause the server to exit cleanly.
+pub(super) async fn run(
+    builder: Builder<AddrIncoming>,
+    schema: GraphqlSchema,
+    shutdown_receiver: oneshot::Receiver<()>,
+    qps_limit: u64,
+) {
+    // Each request takes up a slot, regardless of how many are sent over the same connection.
+    let qps_limiter = QpsLimiter::new(qps_limit);
+    let graphql = warp::path(GRAPHQL_API_PATH)
+        .and(warp::path::end())
+        .and(qps_limiter.filter())
+        .and(async_graphql_warp::graphql(schema))
+        .and_then(
+            |(schema, request): (GraphqlSchema, async_graphql::Request)| async move {
+                Ok::<_, Infallible>(GraphQLResponse::from(schema.execute(request).await))
+            },
+        );
+
+    let service = warp::service(
+        graphql
+            .recover(rate_limit::handle_rejection)
+            .with(warp::cors().allow_any_origin()),
+    );
+
+    // Start the server, passing a oneshot receiver to allow the server to be shut down gracefully.
+    let make_svc =
+        hyper::service::make_service_fn(move |_| future::ok::<_, Infallible>(service.clone()));
+
+    let server = builder.serve(make_svc);
+    info!(address = %server.local_addr(), "started GraphQL server");
+
+    // Shutdown the server gracefully.
+    let _ = server
+        .with_graceful_shutdown(async {
+            shutdown_receiver.await.ok();
+        })
+        .map_err(|error| {
+            warn!(%error, "error running GraphQL server");
+        })
+        .await;
+}
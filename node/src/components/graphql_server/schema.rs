@@ -0,0 +1,702 @@
+//! The GraphQL schema served by the GraphQL server, along with the resolvers backing it.
+//!
+//! All hashes, keys and public keys are rendered as strings in the same formats as used by the
+//! JSON-RPC API, and all motes amounts as decimal strings.
+//!
+//! Lists of objects which are resolved via the backend take an optional `limit` argument, and count
+//! towards the complexity of a query once per item they may return, so that nesting such lists
+//! can't fan out beyond the configured complexity limit.
+
+use std::sync::Arc;
+
+use async_graphql::{
+    Context, EmptyMutation, EmptySubscription, Error, Object, Result, Schema, SimpleObject,
+};
+use async_trait::async_trait;
+
+use casper_execution_engine::core::engine_state::{
+    GetBidsRequest, GetBidsResult, QueryRequest, QueryResult,
+};
+use casper_hashing::Digest;
+use casper_types::{
+    account::{Account as DomainAccount, AccountHash},
+    contracts::NamedKeys,
+    system::auction::{Bid as DomainBid, EraInfo as DomainEraInfo, SeigniorageAllocation},
+    AsymmetricType, Contract as DomainContract, ContractHash, EraId,
+    ExecutionResult as DomainExecutionResult, Key, PublicKey, StoredValue,
+};
+
+use super::ReactorEventT;
+use crate::{
+    effect::EffectBuilder,
+    types::{
+        Block as DomainBlock, BlockHash, Deploy as DomainDeploy, DeployHash, DeployMetadataExt,
+    },
+};
+
+/// The maximum number of items returned by a list field taking a `limit` argument, and the number
+/// returned if no `limit` is given.
+const MAX_LIST_LENGTH: usize = 100;
+
+/// The GraphQL schema served by the GraphQL server.
+pub(super) type GraphqlSchema = Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+
+/// Builds the schema, with queries resolved via `backend` and limited as specified.
+pub(super) fn build_schema(
+    backend: Arc<dyn Backend>,
+    max_query_depth: usize,
+    max_query_complexity: usize,
+) -> GraphqlSchema {
+    Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+        .data(backend)
+        .limit_depth(max_query_depth)
+        .limit_complexity(max_query_complexity)
+        .finish()
+}
+
+/// The source of the data from which queries are resolved.
+///
+/// This is implemented by `EffectBuilder`, and exists so that the GraphQL types need not be generic
+/// over the reactor event type.
+#[async_trait]
+pub(super) trait Backend: Send + Sync {
+    /// Returns the block with the given hash, if it is stored.
+    async fn block(&self, block_hash: BlockHash) -> Option<DomainBlock>;
+
+    /// Returns the block at the given height, if it is stored.
+    async fn block_at_height(&self, height: u64) -> Option<DomainBlock>;
+
+    /// Returns the highest stored block, if any.
+    async fn highest_block(&self) -> Option<DomainBlock>;
+
+    /// Returns the deploy with the given hash and its metadata, if it is stored.
+    async fn deploy(&self, deploy_hash: DeployHash) -> Option<(DomainDeploy, DeployMetadataExt)>;
+
+    /// Returns the value stored under `key` in the given global state, if any.
+    async fn query(&self, state_root_hash: Digest, key: Key)
+        -> Result<Option<StoredValue>, String>;
+
+    /// Returns the bids in the given global state.
+    async fn bids(&self, state_root_hash: Digest) -> Result<Vec<DomainBid>, String>;
+
+    /// Returns the switch block of the given era, if it is stored.
+    async fn switch_block(&self, era_id: EraId) -> Option<DomainBlock>;
+}
+
+#[async_trait]
+impl<REv: ReactorEventT> Backend for EffectBuilder<REv> {
+    async fn block(&self, block_hash: BlockHash) -> Option<DomainBlock> {
+        self.get_block_from_storage(block_hash).await
+    }
+
+    async fn block_at_height(&self, height: u64) -> Option<DomainBlock> {
+        self.get_block_at_height_with_metadata_from_storage(height, false)
+            .await
+            .map(|block_with_metadata| block_with_metadata.block)
+    }
+
+    async fn highest_block(&self) -> Option<DomainBlock> {
+        self.get_highest_block_from_storage().await
+    }
+
+    async fn deploy(&self, deploy_hash: DeployHash) -> Option<(DomainDeploy, DeployMetadataExt)> {
+        self.get_deploy_and_metadata_from_storage(deploy_hash)
+            .await
+            .map(|(deploy, metadata)| (deploy.into_naive(), metadata))
+    }
+
+    async fn query(
+        &self,
+        state_root_hash: Digest,
+        key: Key,
+    ) -> Result<Option<StoredValue>, String> {
+        let query_request = QueryRequest::new(state_root_hash, key, vec![]);
+        match self.query_global_state(query_request).await {
+            Ok(QueryResult::Success { value, .. }) => Ok(Some(*value)),
+            Ok(QueryResult::ValueNotFound(_)) => Ok(None),
+            Ok(QueryResult::RootNotFound) => Err(format!(
+                "global state not found for state root hash {}",
+                state_root_hash
+            )),
+            Ok(query_result) => Err(format!("failed to query global state: {:?}", query_result)),
+            Err(error) => Err(format!("failed to query global state: {}", error)),
+        }
+    }
+
+    async fn bids(&self, state_root_hash: Digest) -> Result<Vec<DomainBid>, String> {
+        match self.get_bids(GetBidsRequest::new(state_root_hash)).await {
+            Ok(GetBidsResult::Success { bids }) => Ok(bids.into_values().collect()),
+            Ok(GetBidsResult::RootNotFound) => Err(format!(
+                "global state not found for state root hash {}",
+                state_root_hash
+            )),
+            Err(error) => Err(format!("failed to get bids: {}", error)),
+        }
+    }
+
+    async fn switch_block(&self, era_id: EraId) -> Option<DomainBlock> {
+        let header = self
+            .get_switch_block_header_at_era_id_from_storage(era_id)
+            .await?;
+        self.get_block_from_storage(header.hash()).await
+    }
+}
+
+/// Returns the backend registered with the schema.
+fn backend<'a>(ctx: &Context<'a>) -> &'a Arc<dyn Backend> {
+    ctx.data_unchecked::<Arc<dyn Backend>>()
+}
+
+/// Returns the state root hash of the block at `block_height`, or of the highest block if `None`.
+async fn state_root_hash(backend: &Arc<dyn Backend>, block_height: Option<u64>) -> Result<Digest> {
+    let maybe_block = match block_height {
+        Some(height) => backend.block_at_height(height).await,
+        None => backend.highest_block().await,
+    };
+    maybe_block
+        .map(|block| *block.header().state_root_hash())
+        .ok_or_else(|| Error::new("block not found"))
+}
+
+/// Returns the number of items to be returned by a list field given its `limit` argument.
+fn list_length(limit: Option<usize>) -> usize {
+    limit.map_or(MAX_LIST_LENGTH, |limit| limit.min(MAX_LIST_LENGTH))
+}
+
+/// Returns the complexity of a list field given its `limit` argument and the complexity of the
+/// fields selected from each item.
+fn list_complexity(limit: Option<usize>, child_complexity: usize) -> usize {
+    list_length(limit).saturating_mul(child_complexity.max(1))
+}
+
+fn parse_digest(hash: &str) -> Result<Digest> {
+    Digest::from_hex(hash).map_err(|error| Error::new(format!("invalid hash: {}", error)))
+}
+
+fn named_keys(named_keys: &NamedKeys) -> Vec<NamedKey> {
+    named_keys
+        .iter()
+        .map(|(name, key)| NamedKey {
+            name: name.clone(),
+            key: key.to_formatted_string(),
+        })
+        .collect()
+}
+
+/// The root of all queries.
+pub(super) struct QueryRoot;
+
+#[Object(name = "Query")]
+impl QueryRoot {
+    /// Returns the block with the given hash or at the given height, or the highest block if
+    /// neither is given.
+    async fn block(
+        &self,
+        ctx: &Context<'_>,
+        hash: Option<String>,
+        height: Option<u64>,
+    ) -> Result<Option<Block>> {
+        let backend = backend(ctx);
+        let maybe_block = match (hash, height) {
+            (Some(_), Some(_)) => {
+                return Err(Error::new("only one of hash or height may be given"))
+            }
+            (Some(hash), None) => backend.block(BlockHash::new(parse_digest(&hash)?)).await,
+            (None, Some(height)) => backend.block_at_height(height).await,
+            (None, None) => backend.highest_block().await,
+        };
+        Ok(maybe_block.map(Block))
+    }
+
+    /// Returns the deploy with the given hash.
+    async fn deploy(&self, ctx: &Context<'_>, hash: String) -> Result<Option<Deploy>> {
+        let deploy_hash = DeployHash::new(parse_digest(&hash)?);
+        Ok(backend(ctx)
+            .deploy(deploy_hash)
+            .await
+            .map(|(deploy, metadata)| Deploy { deploy, metadata }))
+    }
+
+    /// Returns the account identified by the given account hash or public key, as at the given
+    /// block height or the highest block if not given.
+    async fn account(
+        &self,
+        ctx: &Context<'_>,
+        account_hash: Option<String>,
+        public_key: Option<String>,
+        block_height: Option<u64>,
+    ) -> Result<Option<Account>> {
+        let account_hash = match (account_hash, public_key) {
+            (Some(account_hash), None) => AccountHash::from_formatted_str(&account_hash)
+                .map_err(|error| Error::new(format!("invalid account hash: {}", error)))?,
+            (None, Some(public_key)) => PublicKey::from_hex(&public_key)
+                .map_err(|error| Error::new(format!("invalid public key: {}", error)))?
+                .to_account_hash(),
+            _ => {
+                return Err(Error::new(
+                    "exactly one of accountHash or publicKey must be given",
+                ))
+            }
+        };
+        let backend = backend(ctx);
+        let state_root_hash = state_root_hash(backend, block_height).await?;
+        match backend
+            .query(state_root_hash, Key::Account(account_hash))
+            .await?
+        {
+            Some(StoredValue::Account(account)) => Ok(Some(Account::from(&account))),
+            Some(_) => Err(Error::new("stored value is not an account")),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the contract with the given hash, as at the given block height or the highest block
+    /// if not given.
+    async fn contract(
+        &self,
+        ctx: &Context<'_>,
+        hash: String,
+        block_height: Option<u64>,
+    ) -> Result<Option<Contract>> {
+        let contract_hash = ContractHash::from_formatted_str(&hash)
+            .map_err(|error| Error::new(format!("invalid contract hash: {}", error)))?;
+        let backend = backend(ctx);
+        let state_root_hash = state_root_hash(backend, block_height).await?;
+        match backend
+            .query(state_root_hash, Key::from(contract_hash))
+            .await?
+        {
+            Some(StoredValue::Contract(contract)) => {
+                Ok(Some(Contract::new(contract_hash, &contract)))
+            }
+            Some(_) => Err(Error::new("stored value is not a contract")),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns up to `limit` bids, or 100 if not given, as at the given block height, or the
+    /// highest block if not given.
+    #[graphql(complexity = "list_complexity(limit, child_complexity)")]
+    async fn bids(
+        &self,
+        ctx: &Context<'_>,
+        block_height: Option<u64>,
+        limit: Option<usize>,
+    ) -> Result<Vec<Bid>> {
+        let backend = backend(ctx);
+        let state_root_hash = state_root_hash(backend, block_height).await?;
+        let bids = backend.bids(state_root_hash).await?;
+        Ok(bids
+            .iter()
+            .take(list_length(limit))
+            .map(Bid::from)
+            .collect())
+    }
+
+    /// Returns the era info of the given era, as recorded at its switch block.
+    async fn era_info(&self, ctx: &Context<'_>, era_id: u64) -> Result<Option<EraInfo>> {
+        let era_id = EraId::from(era_id);
+        let backend = backend(ctx);
+        let switch_block = match backend.switch_block(era_id).await {
+            Some(switch_block) => switch_block,
+            None => return Ok(None),
+        };
+        match backend
+            .query(
+                *switch_block.header().state_root_hash(),
+                Key::EraInfo(era_id),
+            )
+            .await?
+        {
+            Some(StoredValue::EraInfo(era_info)) => Ok(Some(EraInfo::new(era_id, &era_info))),
+            Some(_) => Err(Error::new("stored value is not era info")),
+            None => Ok(None),
+        }
+    }
+}
+
+/// A block.
+pub(super) struct Block(DomainBlock);
+
+#[Object]
+impl Block {
+    /// The block hash.
+    async fn hash(&self) -> String {
+        self.0.hash().inner().to_string()
+    }
+
+    /// The hash of the parent block.
+    async fn parent_hash(&self) -> String {
+        self.0.header().parent_hash().inner().to_string()
+    }
+
+    /// The global state root hash after executing the block.
+    async fn state_root_hash(&self) -> String {
+        self.0.header().state_root_hash().to_string()
+    }
+
+    /// The block height.
+    async fn height(&self) -> u64 {
+        self.0.header().height()
+    }
+
+    /// The era in which the block was created.
+    async fn era_id(&self) -> u64 {
+        self.0.header().era_id().value()
+    }
+
+    /// The block timestamp, in RFC 3339 format.
+    async fn timestamp(&self) -> String {
+        self.0.header().timestamp().to_string()
+    }
+
+    /// The protocol version under which the block was created.
+    async fn protocol_version(&self) -> String {
+        self.0.header().protocol_version().to_string()
+    }
+
+    /// Whether the block is the last one of its era.
+    async fn is_switch_block(&self) -> bool {
+        self.0.header().is_switch_block()
+    }
+
+    /// The public key of the validator which proposed the block.
+    async fn proposer(&self) -> String {
+        self.0.body().proposer().to_hex()
+    }
+
+    /// The hashes of the non-transfer deploys included in the block.
+    async fn deploy_hashes(&self) -> Vec<String> {
+        self.0
+            .deploy_hashes()
+            .iter()
+            .map(|deploy_hash| deploy_hash.inner().to_string())
+            .collect()
+    }
+
+    /// The hashes of the transfers included in the block.
+    async fn transfer_hashes(&self) -> Vec<String> {
+        self.0
+            .transfer_hashes()
+            .iter()
+            .map(|deploy_hash| deploy_hash.inner().to_string())
+            .collect()
+    }
+
+    /// Up to `limit` deploys, or 100 if not given, included in the block, non-transfer deploys
+    /// first.
+    #[graphql(complexity = "list_complexity(limit, child_complexity)")]
+    async fn deploys(&self, ctx: &Context<'_>, limit: Option<usize>) -> Vec<Deploy> {
+        let backend = backend(ctx);
+        let mut deploys = vec![];
+        for deploy_hash in self
+            .0
+            .deploy_hashes()
+            .iter()
+            .chain(self.0.transfer_hashes())
+            .take(list_length(limit))
+        {
+            if let Some((deploy, metadata)) = backend.deploy(*deploy_hash).await {
+                deploys.push(Deploy { deploy, metadata });
+            }
+        }
+        deploys
+    }
+}
+
+/// A deploy, along with its execution results.
+pub(super) struct Deploy {
+    deploy: DomainDeploy,
+    metadata: DeployMetadataExt,
+}
+
+#[Object]
+impl Deploy {
+    /// The deploy hash.
+    async fn hash(&self) -> String {
+        self.deploy.id().inner().to_string()
+    }
+
+    /// The public key of the account which created the deploy.
+    async fn account(&self) -> String {
+        self.deploy.header().account().to_hex()
+    }
+
+    /// The deploy timestamp, in RFC 3339 format.
+    async fn timestamp(&self) -> String {
+        self.deploy.header().timestamp().to_string()
+    }
+
+    /// How long the deploy remains valid after its timestamp, e.g. "30m".
+    async fn ttl(&self) -> String {
+        self.deploy.header().ttl().to_string()
+    }
+
+    /// The gas price specified by the deploy.
+    async fn gas_price(&self) -> u64 {
+        self.deploy.header().gas_price()
+    }
+
+    /// The name of the chain on which the deploy is valid.
+    async fn chain_name(&self) -> String {
+        self.deploy.header().chain_name().to_string()
+    }
+
+    /// The hashes of the deploys on which this deploy depends.
+    async fn dependencies(&self) -> Vec<String> {
+        self.deploy
+            .header()
+            .dependencies()
+            .iter()
+            .map(|deploy_hash| deploy_hash.inner().to_string())
+            .collect()
+    }
+
+    /// Whether the deploy is a native transfer.
+    async fn is_transfer(&self) -> bool {
+        self.deploy.session().is_transfer()
+    }
+
+    /// The results of executing the deploy, one per block in which it was executed.
+    async fn execution_results(&self) -> Vec<ExecutionResult> {
+        match &self.metadata {
+            DeployMetadataExt::Metadata(metadata) => metadata
+                .execution_results
+                .iter()
+                .map(|(block_hash, execution_result)| {
+                    ExecutionResult::new(block_hash, execution_result)
+                })
+                .collect(),
+            DeployMetadataExt::BlockInfo(_) | DeployMetadataExt::Empty => vec![],
+        }
+    }
+
+    /// Up to `limit` blocks, or 100 if not given, in which the deploy was executed.
+    #[graphql(complexity = "list_complexity(limit, child_complexity)")]
+    async fn blocks(&self, ctx: &Context<'_>, limit: Option<usize>) -> Vec<Block> {
+        let block_hashes: Vec<BlockHash> = match &self.metadata {
+            DeployMetadataExt::Metadata(metadata) => {
+                metadata.execution_results.keys().copied().collect()
+            }
+            DeployMetadataExt::BlockInfo(block_hash_and_height) => {
+                vec![block_hash_and_height.block_hash]
+            }
+            DeployMetadataExt::Empty => vec![],
+        };
+        let backend = backend(ctx);
+        let mut blocks = vec![];
+        for block_hash in block_hashes.into_iter().take(list_length(limit)) {
+            if let Some(block) = backend.block(block_hash).await {
+                blocks.push(Block(block));
+            }
+        }
+        blocks
+    }
+}
+
+/// The result of executing a deploy in a given block.
+#[derive(SimpleObject)]
+pub(super) struct ExecutionResult {
+    /// The hash of the block in which the deploy was executed.
+    block_hash: String,
+    /// Whether execution succeeded.
+    success: bool,
+    /// The cost of execution, in motes.
+    cost: String,
+    /// The error message, if execution failed.
+    error_message: Option<String>,
+    /// The addresses of the transfers performed during execution.
+    transfers: Vec<String>,
+}
+
+impl ExecutionResult {
+    fn new(block_hash: &BlockHash, execution_result: &DomainExecutionResult) -> Self {
+        let (cost, transfers, error_message) = match execution_result {
+            DomainExecutionResult::Success {
+                cost, transfers, ..
+            } => (cost, transfers, None),
+            DomainExecutionResult::Failure {
+                cost,
+                transfers,
+                error_message,
+                ..
+            } => (cost, transfers, Some(error_message.clone())),
+        };
+        ExecutionResult {
+            block_hash: block_hash.inner().to_string(),
+            success: error_message.is_none(),
+            cost: cost.to_string(),
+            error_message,
+            transfers: transfers
+                .iter()
+                .map(|transfer_addr| transfer_addr.to_formatted_string())
+                .collect(),
+        }
+    }
+}
+
+/// A named key.
+#[derive(SimpleObject)]
+pub(super) struct NamedKey {
+    /// The name of the entry.
+    name: String,
+    /// The value of the entry: a casper `Key` type.
+    key: String,
+}
+
+/// An account.
+#[derive(SimpleObject)]
+pub(super) struct Account {
+    /// The account hash.
+    account_hash: String,
+    /// The main purse of the account.
+    main_purse: String,
+    /// The named keys of the account.
+    named_keys: Vec<NamedKey>,
+}
+
+impl From<&DomainAccount> for Account {
+    fn from(account: &DomainAccount) -> Self {
+        Account {
+            account_hash: account.account_hash().to_formatted_string(),
+            main_purse: account.main_purse().to_formatted_string(),
+            named_keys: named_keys(account.named_keys()),
+        }
+    }
+}
+
+/// A contract.
+#[derive(SimpleObject)]
+pub(super) struct Contract {
+    /// The contract hash.
+    hash: String,
+    /// The hash of the contract package to which the contract belongs.
+    contract_package_hash: String,
+    /// The hash of the contract's Wasm.
+    contract_wasm_hash: String,
+    /// The protocol version under which the contract was installed.
+    protocol_version: String,
+    /// The names of the contract's entry points.
+    entry_points: Vec<String>,
+    /// The named keys of the contract.
+    named_keys: Vec<NamedKey>,
+}
+
+impl Contract {
+    fn new(contract_hash: ContractHash, contract: &DomainContract) -> Self {
+        Contract {
+            hash: contract_hash.to_formatted_string(),
+            contract_package_hash: contract.contract_package_hash().to_formatted_string(),
+            contract_wasm_hash: contract.contract_wasm_hash().to_formatted_string(),
+            protocol_version: contract.protocol_version().to_string(),
+            entry_points: contract.entry_points().keys().cloned().collect(),
+            named_keys: named_keys(contract.named_keys()),
+        }
+    }
+}
+
+/// A validator's bid.
+#[derive(SimpleObject)]
+pub(super) struct Bid {
+    /// The validator's public key.
+    validator_public_key: String,
+    /// The purse holding the validator's stake.
+    bonding_purse: String,
+    /// The validator's own stake, in motes.
+    staked_amount: String,
+    /// The validator's delegation rate.
+    delegation_rate: u8,
+    /// Whether the validator has been evicted.
+    inactive: bool,
+    /// The delegators to the validator.
+    delegators: Vec<Delegator>,
+}
+
+impl From<&DomainBid> for Bid {
+    fn from(bid: &DomainBid) -> Self {
+        Bid {
+            validator_public_key: bid.validator_public_key().to_hex(),
+            bonding_purse: bid.bonding_purse().to_formatted_string(),
+            staked_amount: bid.staked_amount().to_string(),
+            delegation_rate: *bid.delegation_rate(),
+            inactive: bid.inactive(),
+            delegators: bid
+                .delegators()
+                .values()
+                .map(|delegator| Delegator {
+                    public_key: delegator.delegator_public_key().to_hex(),
+                    bonding_purse: delegator.bonding_purse().to_formatted_string(),
+                    staked_amount: delegator.staked_amount().to_string(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// A delegator to a validator.
+#[derive(SimpleObject)]
+pub(super) struct Delegator {
+    /// The delegator's public key.
+    public_key: String,
+    /// The purse holding the delegator's stake.
+    bonding_purse: String,
+    /// The delegator's stake, in motes.
+    staked_amount: String,
+}
+
+/// Auction information recorded for an era.
+#[derive(SimpleObject)]
+pub(super) struct EraInfo {
+    /// The era ID.
+    era_id: u64,
+    /// The seigniorage allocated in the era.
+    seigniorage_allocations: Vec<Allocation>,
+}
+
+impl EraInfo {
+    fn new(era_id: EraId, era_info: &DomainEraInfo) -> Self {
+        EraInfo {
+            era_id: era_id.value(),
+            seigniorage_allocations: era_info
+                .seigniorage_allocations()
+                .iter()
+                .map(Allocation::from)
+                .collect(),
+        }
+    }
+}
+
+/// A seigniorage allocation to a validator or delegator.
+#[derive(SimpleObject)]
+pub(super) struct Allocation {
+    /// The public key of the validator to which, or through which, seigniorage was allocated.
+    validator_public_key: String,
+    /// The public key of the delegator to which seigniorage was allocated, if any.
+    delegator_public_key: Option<String>,
+    /// The allocated amount, in motes.
+    amount: String,
+}
+
+impl From<&SeigniorageAllocation> for Allocation {
+    fn from(allocation: &SeigniorageAllocation) -> Self {
+        match allocation {
+            SeigniorageAllocation::Validator {
+                validator_public_key,
+                amount,
+            } => Allocation {
+                validator_public_key: validator_public_key.to_hex(),
+                delegator_public_key: None,
+                amount: amount.to_string(),
+            },
+            SeigniorageAllocation::Delegator {
+                delegator_public_key,
+                validator_public_key,
+                amount,
+            } => Allocation {
+                validator_public_key: validator_public_key.to_hex(),
+                delegator_public_key: Some(delegator_public_key.to_hex()),
+                amount: amount.to_string(),
+            },
+        }
+    }
+}
@@ -0,0 +1,322 @@
+use std::{collections::HashMap, sync::Arc};
+
+use async_graphql::Response;
+use async_trait::async_trait;
+use serde_json::{json, Value};
+
+use casper_hashing::Digest;
+use casper_types::{
+    account::{Account as DomainAccount, AccountHash},
+    contracts::NamedKeys,
+    system::auction::Bid as DomainBid,
+    testing::TestRng,
+    AccessRights, EraId, ExecutionResult as DomainExecutionResult, Key, ProtocolVersion,
+    StoredValue, URef,
+};
+use rand::Rng;
+
+use super::schema::{self, Backend};
+use crate::types::{
+    Block as DomainBlock, BlockHash, Deploy as DomainDeploy, DeployHash, DeployMetadata,
+    DeployMetadataExt,
+};
+
+const MAX_QUERY_DEPTH: usize = 10;
+const MAX_QUERY_COMPLEXITY: usize = 1000;
+
+/// A backend serving a fixed set of blocks, deploys and global state values.
+struct MockBackend {
+    blocks: Vec<DomainBlock>,
+    deploys: HashMap<DeployHash, (DomainDeploy, DeployMetadataExt)>,
+    global_state: HashMap<(Digest, Key), StoredValue>,
+}
+
+impl MockBackend {
+    /// Creates a backend holding `block_count` blocks in era 0, each containing `deploy_count`
+    /// deploys which were executed in every one of the blocks.
+    fn new(rng: &mut TestRng, block_count: u64, deploy_count: usize) -> Self {
+        let deploys: Vec<DomainDeploy> = (0..deploy_count)
+            .map(|_| DomainDeploy::random(rng))
+            .collect();
+        let blocks: Vec<DomainBlock> = (0..block_count)
+            .map(|height| {
+                DomainBlock::random_with_specifics(
+                    rng,
+                    EraId::new(0),
+                    height,
+                    ProtocolVersion::V1_0_0,
+                    false,
+                    deploys.iter(),
+                )
+            })
+            .collect();
+        let deploys = deploys
+            .into_iter()
+            .map(|deploy| {
+                let execution_results = blocks
+                    .iter()
+                    .map(|block| (*block.hash(), rng.gen::<DomainExecutionResult>()))
+                    .collect();
+                let metadata = DeployMetadataExt::Metadata(DeployMetadata { execution_results });
+                (*deploy.id(), (deploy, metadata))
+            })
+            .collect();
+        MockBackend {
+            blocks,
+            deploys,
+            global_state: HashMap::new(),
+        }
+    }
+
+    fn last_block(&self) -> &DomainBlock {
+        self.blocks.last().expect("should have blocks")
+    }
+
+    fn schema(self) -> schema::GraphqlSchema {
+        schema::build_schema(Arc::new(self), MAX_QUERY_DEPTH, MAX_QUERY_COMPLEXITY)
+    }
+}
+
+#[async_trait]
+impl Backend for MockBackend {
+    async fn block(&self, block_hash: BlockHash) -> Option<DomainBlock> {
+        self.blocks
+            .iter()
+            .find(|block| *block.hash() == block_hash)
+            .cloned()
+    }
+
+    async fn block_at_height(&self, height: u64) -> Option<DomainBlock> {
+        self.blocks
+            .iter()
+            .find(|block| block.height() == height)
+            .cloned()
+    }
+
+    async fn highest_block(&self) -> Option<DomainBlock> {
+        self.blocks.last().cloned()
+    }
+
+    async fn deploy(&self, deploy_hash: DeployHash) -> Option<(DomainDeploy, DeployMetadataExt)> {
+        self.deploys.get(&deploy_hash).cloned()
+    }
+
+    async fn query(
+        &self,
+        state_root_hash: Digest,
+        key: Key,
+    ) -> Result<Option<StoredValue>, String> {
+        Ok(self.global_state.get(&(state_root_hash, key)).cloned())
+    }
+
+    async fn bids(&self, _state_root_hash: Digest) -> Result<Vec<DomainBid>, String> {
+        Ok(vec![])
+    }
+
+    async fn switch_block(&self, era_id: EraId) -> Option<DomainBlock> {
+        self.blocks
+            .iter()
+            .find(|block| block.header().era_id() == era_id && block.header().is_switch_block())
+            .cloned()
+    }
+}
+
+/// Returns the data of a response which is expected to have no errors.
+fn data(response: Response) -> Value {
+    assert!(
+        response.errors.is_empty(),
+        "unexpected errors: {:?}",
+        response.errors
+    );
+    response.data.into_json().expect("should convert to JSON")
+}
+
+fn hash_string(block: &DomainBlock) -> String {
+    block.hash().inner().to_string()
+}
+
+#[tokio::test]
+async fn should_get_block_by_hash_height_or_highest() {
+    let mut rng = TestRng::new();
+    let backend = MockBackend::new(&mut rng, 3, 0);
+    let first_hash = hash_string(&backend.blocks[0]);
+    let highest_hash = hash_string(backend.last_block());
+    let schema = backend.schema();
+
+    let query = format!(r#"{{ block(hash: "{}") {{ hash height }} }}"#, first_hash);
+    let response = schema.execute(query.as_str()).await;
+    assert_eq!(
+        data(response),
+        json!({ "block": { "hash": first_hash, "height": 0 } })
+    );
+
+    let response = schema.execute("{ block(height: 0) { hash } }").await;
+    assert_eq!(data(response), json!({ "block": { "hash": first_hash } }));
+
+    let response = schema.execute("{ block { hash height } }").await;
+    assert_eq!(
+        data(response),
+        json!({ "block": { "hash": highest_hash, "height": 2 } })
+    );
+
+    let response = schema.execute("{ block(height: 3) { hash } }").await;
+    assert_eq!(data(response), json!({ "block": null }));
+}
+
+#[tokio::test]
+async fn should_reject_block_by_both_hash_and_height() {
+    let mut rng = TestRng::new();
+    let backend = MockBackend::new(&mut rng, 1, 0);
+    let hash = hash_string(&backend.blocks[0]);
+    let schema = backend.schema();
+
+    let query = format!(r#"{{ block(hash: "{}", height: 0) {{ hash }} }}"#, hash);
+    let response = schema.execute(query.as_str()).await;
+    assert_eq!(response.errors.len(), 1);
+    assert_eq!(
+        response.errors[0].message,
+        "only one of hash or height may be given"
+    );
+}
+
+#[tokio::test]
+async fn should_resolve_deploys_of_block_and_blocks_of_deploy() {
+    let mut rng = TestRng::new();
+    let backend = MockBackend::new(&mut rng, 2, 3);
+    let block_hash = hash_string(backend.last_block());
+    let mut deploy_hashes: Vec<String> = backend
+        .deploys
+        .keys()
+        .map(|deploy_hash| deploy_hash.inner().to_string())
+        .collect();
+    deploy_hashes.sort();
+    let mut block_hashes: Vec<String> = backend.blocks.iter().map(hash_string).collect();
+    block_hashes.sort();
+    let deploy_hash = deploy_hashes[0].clone();
+    let schema = backend.schema();
+
+    let response = schema.execute("{ block { hash deploys { hash } } }").await;
+    let block_data = data(response);
+    assert_eq!(block_data["block"]["hash"], json!(block_hash));
+    let mut returned_deploy_hashes: Vec<String> = block_data["block"]["deploys"]
+        .as_array()
+        .expect("should be an array")
+        .iter()
+        .map(|deploy| {
+            deploy["hash"]
+                .as_str()
+                .expect("should be a string")
+                .to_string()
+        })
+        .collect();
+    returned_deploy_hashes.sort();
+    assert_eq!(returned_deploy_hashes, deploy_hashes);
+
+    let query = format!(
+        r#"{{ deploy(hash: "{}") {{ blocks {{ hash }} executionResults {{ blockHash }} }} }}"#,
+        deploy_hash
+    );
+    let deploy_data = data(schema.execute(query.as_str()).await);
+    let sorted_strings = |field: &str, key: &str| {
+        let mut strings: Vec<String> = deploy_data["deploy"][field]
+            .as_array()
+            .expect("should be an array")
+            .iter()
+            .map(|item| item[key].as_str().expect("should be a string").to_string())
+            .collect();
+        strings.sort();
+        strings
+    };
+    assert_eq!(sorted_strings("blocks", "hash"), block_hashes);
+    assert_eq!(
+        sorted_strings("executionResults", "blockHash"),
+        block_hashes
+    );
+}
+
+#[tokio::test]
+async fn should_limit_list_length() {
+    let mut rng = TestRng::new();
+    let backend = MockBackend::new(&mut rng, 3, 5);
+    let schema = backend.schema();
+
+    let response = schema
+        .execute("{ block { deploys(limit: 2) { hash } } }")
+        .await;
+    let data = data(response);
+    assert_eq!(data["block"]["deploys"].as_array().unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn should_get_account_at_highest_block() {
+    let mut rng = TestRng::new();
+    let mut backend = MockBackend::new(&mut rng, 2, 0);
+    let account_hash = AccountHash::new(rng.gen());
+    let main_purse = URef::new(rng.gen(), AccessRights::READ_ADD_WRITE);
+    let account = DomainAccount::create(account_hash, NamedKeys::new(), main_purse);
+    let state_root_hash = *backend.last_block().header().state_root_hash();
+    backend.global_state.insert(
+        (state_root_hash, Key::Account(account_hash)),
+        StoredValue::Account(account),
+    );
+    let schema = backend.schema();
+
+    let query = format!(
+        r#"{{ account(accountHash: "{}") {{ mainPurse namedKeys {{ name }} }} }}"#,
+        account_hash.to_formatted_string()
+    );
+    let response = schema.execute(query.as_str()).await;
+    assert_eq!(
+        data(response),
+        json!({
+            "account": { "mainPurse": main_purse.to_formatted_string(), "namedKeys": [] }
+        })
+    );
+
+    // The account isn't stored in the global state of the first block.
+    let query = format!(
+        r#"{{ account(accountHash: "{}", blockHeight: 0) {{ mainPurse }} }}"#,
+        account_hash.to_formatted_string()
+    );
+    let response = schema.execute(query.as_str()).await;
+    assert_eq!(data(response), json!({ "account": null }));
+}
+
+#[tokio::test]
+async fn should_reject_query_exceeding_depth_limit() {
+    let mut rng = TestRng::new();
+    let backend = MockBackend::new(&mut rng, 1, 1);
+    let schema = schema::build_schema(Arc::new(backend), 3, MAX_QUERY_COMPLEXITY);
+
+    let response = schema.execute("{ block { deploys { hash } } }").await;
+    assert!(response.errors.is_empty());
+
+    let response = schema
+        .execute("{ block { deploys { blocks { hash } } } }")
+        .await;
+    assert_eq!(response.errors.len(), 1);
+}
+
+#[tokio::test]
+async fn should_weight_nested_lists_by_their_limit() {
+    let mut rng = TestRng::new();
+    let backend = MockBackend::new(&mut rng, 2, 2);
+    let schema = backend.schema();
+
+    // Without limits, each list counts as 100 items, so nesting two lists exceeds the complexity
+    // limit even though only a few items would actually be returned.
+    let response = schema
+        .execute("{ block { deploys { blocks { hash } } } }")
+        .await;
+    assert_eq!(response.errors.len(), 1);
+
+    let response = schema
+        .execute("{ block { deploys(limit: 5) { blocks(limit: 5) { hash } } } }")
+        .await;
+    let data = data(response);
+    let deploys = data["block"]["deploys"].as_array().unwrap();
+    assert_eq!(deploys.len(), 2);
+    for deploy in deploys {
+        assert_eq!(deploy["blocks"].as_array().unwrap().len(), 2);
+    }
+}
@@ -119,6 +119,7 @@ impl RestServer {
             api_version,
             shutdown_receiver,
             config.qps_limit,
+            config.rate_limit,
         )));
 
         Ok(RestServer {
@@ -161,12 +162,14 @@ where
                         chainspec_info,
                         consensus_status,
                         node_state,
+                        available_block_range,
                     ) = join!(
                         effect_builder.get_highest_block_from_storage(),
                         effect_builder.network_peers(),
                         effect_builder.get_chainspec_info(),
                         effect_builder.consensus_status(),
-                        effect_builder.get_node_state()
+                        effect_builder.get_node_state(),
+                        effect_builder.get_available_block_range_from_storage()
                     );
 
                     let status_feed = StatusFeed::new(
@@ -176,6 +179,7 @@ where
                         consensus_status,
                         node_uptime,
                         node_state,
+                        available_block_range,
                     );
                     responder.respond(status_feed).await;
                 }
@@ -10,7 +10,10 @@ use warp::Filter;
 use casper_types::ProtocolVersion;
 
 use super::{filters, ReactorEventT};
-use crate::effect::EffectBuilder;
+use crate::{
+    effect::EffectBuilder,
+    utils::rate_limit::{self, RateLimitConfig, RateLimiter},
+};
 
 /// Run the REST HTTP server.
 ///
@@ -21,6 +24,7 @@ pub(super) async fn run<REv: ReactorEventT>(
     api_version: ProtocolVersion,
     shutdown_receiver: oneshot::Receiver<()>,
     qps_limit: u64,
+    rate_limit_config: RateLimitConfig,
 ) {
     // REST filters.
     let rest_status = filters::create_status_filter(effect_builder, api_version);
@@ -30,12 +34,19 @@ pub(super) async fn run<REv: ReactorEventT>(
         filters::create_validator_changes_filter(effect_builder, api_version);
     let rest_chainspec_filter = filters::create_chainspec_filter(effect_builder, api_version);
 
+    let rate_limiter = RateLimiter::new(rate_limit_config);
+
     let service = warp::service(
-        rest_status
-            .or(rest_metrics)
-            .or(rest_open_rpc)
-            .or(rest_validator_changes)
-            .or(rest_chainspec_filter)
+        rate_limiter
+            .rest_filter()
+            .and(
+                rest_status
+                    .or(rest_metrics)
+                    .or(rest_open_rpc)
+                    .or(rest_validator_changes)
+                    .or(rest_chainspec_filter),
+            )
+            .recover(rate_limit::handle_rejection)
             .with(warp::cors().allow_any_origin()),
     );
 
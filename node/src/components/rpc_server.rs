@@ -11,6 +11,9 @@
 //! reactor, and an external facing http server that exposes various uri routes and converts
 //! JSON-RPC requests into the appropriate component events.
 //!
+//! The JSON-RPC methods are also available over a WebSocket connection, along with methods to
+//! subscribe to events from the event stream server.
+//!
 //! For the list of supported RPC methods, see:
 //! <https://github.com/CasperLabs/ceps/blob/master/text/0009-client-api.md#rpcs>
 
@@ -20,6 +23,7 @@ mod http_server;
 pub mod rpcs;
 mod speculative_exec_config;
 mod speculative_exec_server;
+mod ws_server;
 
 use std::{convert::Infallible, fmt::Debug, time::Instant};
 
@@ -37,8 +41,11 @@ use casper_types::{system::auction::EraValidators, ExecutionResult, Key, Protoco
 use self::rpcs::chain::BlockIdentifier;
 use super::Component;
 use crate::{
-    components::contract_runtime::EraValidatorsRequest,
-    contract_runtime::SpeculativeExecutionState,
+    components::{
+        contract_runtime::{EraValidatorsRequest, SpeculativeExecutionResults},
+        event_stream_server::EventStreamHandle,
+    },
+    contract_runtime::{SpeculativeExecutionOverrides, SpeculativeExecutionState},
     effect::{
         announcements::RpcServerAnnouncement,
         requests::{
@@ -47,7 +54,7 @@ use crate::{
         },
         EffectBuilder, EffectExt, Effects, Responder,
     },
-    types::{BlockHeader, Deploy, StatusFeed},
+    types::{Block, Deploy, PeersMap, StatusFeed},
     utils::{self, ListeningError},
     NodeRng,
 };
@@ -119,6 +126,7 @@ impl RpcServer {
         effect_builder: EffectBuilder<REv>,
         api_version: ProtocolVersion,
         node_startup_instant: Instant,
+        event_stream: Option<EventStreamHandle>,
     ) -> Result<Self, ListeningError>
     where
         REv: ReactorEventT,
@@ -134,6 +142,8 @@ impl RpcServer {
                 api_version,
                 speculative_exec_config.qps_limit,
                 speculative_exec_config.max_body_bytes,
+                speculative_exec_config.max_batch_size,
+                speculative_exec_config.rate_limit,
             ));
             Some(())
         } else {
@@ -152,8 +162,11 @@ impl RpcServer {
             builder,
             effect_builder,
             api_version,
+            event_stream,
             config.qps_limit,
             config.max_body_bytes,
+            config.max_batch_size,
+            config.rate_limit,
         ));
 
         let inner_rpc = Some(InnerRpcServer {
@@ -220,18 +233,33 @@ impl RpcServer {
     fn handle_execute_deploy<REv: ReactorEventT>(
         &mut self,
         effect_builder: EffectBuilder<REv>,
-        block_header: BlockHeader,
+        block: Block,
         deploy: Deploy,
+        overrides: SpeculativeExecutionOverrides,
         responder: Responder<Result<Option<ExecutionResult>, engine_state::Error>>,
     ) -> Effects<Event> {
         async move {
-            let execution_prestate = SpeculativeExecutionState {
-                state_root_hash: *block_header.state_root_hash(),
-                block_time: block_header.timestamp(),
-                protocol_version: block_header.protocol_version(),
-            };
+            let execution_prestate = SpeculativeExecutionState::from(&block);
+            let result = effect_builder
+                .speculative_execute_deploy(execution_prestate, deploy, overrides)
+                .await;
+            responder.respond(result).await
+        }
+        .ignore()
+    }
+
+    fn handle_execute_deploys<REv: ReactorEventT>(
+        &mut self,
+        effect_builder: EffectBuilder<REv>,
+        block: Block,
+        deploys: Vec<Deploy>,
+        overrides: SpeculativeExecutionOverrides,
+        responder: Responder<Result<SpeculativeExecutionResults, engine_state::Error>>,
+    ) -> Effects<Event> {
+        async move {
+            let execution_prestate = SpeculativeExecutionState::from(&block);
             let result = effect_builder
-                .speculative_execute_deploy(execution_prestate, deploy)
+                .speculative_execute_deploys(execution_prestate, deploys, overrides)
                 .await;
             responder.respond(result).await
         }
@@ -255,8 +283,9 @@ where
         // Handle the special case where we need to route the request to
         // the speculative execution JSON-RPC server.
         if let Event::RpcRequest(RpcRequest::SpeculativeDeployExecute {
-            block_header,
+            block,
             deploy,
+            overrides,
             responder,
         }) = event
         {
@@ -264,8 +293,32 @@ where
                 Some(_) => {
                     return self.handle_execute_deploy(
                         effect_builder,
-                        block_header,
+                        *block,
                         *deploy,
+                        overrides,
+                        responder,
+                    );
+                }
+                None => {
+                    return Effects::new();
+                }
+            }
+        }
+
+        if let Event::RpcRequest(RpcRequest::SpeculativeDeploysExecute {
+            block,
+            deploys,
+            overrides,
+            responder,
+        }) = event
+        {
+            match self.speculative_exec {
+                Some(_) => {
+                    return self.handle_execute_deploys(
+                        effect_builder,
+                        *block,
+                        deploys,
+                        overrides,
                         responder,
                     );
                 }
@@ -275,7 +328,8 @@ where
             }
         }
 
-        // For all requests other than `SpeculativeDeployExecute`, we return
+        // For all requests other than `SpeculativeDeployExecute` and `SpeculativeDeploysExecute`,
+        // we return
         // empty effects if the JSON-RPC server is disabled.
         let rpc_server = match &self.inner_rpc {
             Some(rpc_server) => rpc_server,
@@ -390,21 +444,34 @@ where
                     )),
                     main_responder: responder,
                 }),
-            Event::RpcRequest(RpcRequest::GetPeers { responder }) => effect_builder
-                .network_peers()
-                .event(move |peers| Event::GetPeersResult {
-                    peers,
-                    main_responder: responder,
-                }),
+            Event::RpcRequest(RpcRequest::GetPeers { responder }) => async move {
+                join!(
+                    effect_builder.network_peers(),
+                    effect_builder.get_peer_scores()
+                )
+            }
+            .event(move |(peers, scores)| Event::GetPeersResult {
+                peers,
+                scores,
+                main_responder: responder,
+            }),
             Event::RpcRequest(RpcRequest::GetStatus { responder }) => {
                 let node_uptime = rpc_server.node_startup_instant().elapsed();
                 async move {
-                    let (last_added_block, peers, chainspec_info, consensus_status, node_state) = join!(
+                    let (
+                        last_added_block,
+                        peers,
+                        chainspec_info,
+                        consensus_status,
+                        node_state,
+                        available_block_range,
+                    ) = join!(
                         effect_builder.get_highest_block_from_storage(),
                         effect_builder.network_peers(),
                         effect_builder.get_chainspec_info(),
                         effect_builder.consensus_status(),
-                        effect_builder.get_node_state()
+                        effect_builder.get_node_state(),
+                        effect_builder.get_available_block_range_from_storage()
                     );
                     let status_feed = StatusFeed::new(
                         last_added_block,
@@ -413,6 +480,7 @@ where
                         consensus_status,
                         node_uptime,
                         node_state,
+                        available_block_range,
                     );
                     responder.respond(status_feed).await;
                 }
@@ -428,7 +496,8 @@ where
                     .await
             }
             .ignore(),
-            Event::RpcRequest(RpcRequest::SpeculativeDeployExecute { .. }) => {
+            Event::RpcRequest(RpcRequest::SpeculativeDeployExecute { .. })
+            | Event::RpcRequest(RpcRequest::SpeculativeDeploysExecute { .. }) => {
                 // Handled above by the speculative execution JSON-RPC server.
                 error!(
                     "Received spurious speculative exec event in JSON-RPC server, \
@@ -469,8 +538,11 @@ where
             } => main_responder.respond(*result).ignore(),
             Event::GetPeersResult {
                 peers,
+                scores,
                 main_responder,
-            } => main_responder.respond(peers).ignore(),
+            } => main_responder
+                .respond(PeersMap::with_scores(peers, &scores))
+                .ignore(),
         }
     }
 }
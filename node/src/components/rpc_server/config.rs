@@ -1,6 +1,8 @@
 use datasize::DataSize;
 use serde::{Deserialize, Serialize};
 
+use crate::utils::rate_limit::RateLimitConfig;
+
 /// Default binding address for the JSON-RPC HTTP server.
 ///
 /// Uses a fixed port per node, but binds on any interface.
@@ -10,6 +12,8 @@ const DEFAULT_QPS_LIMIT: u64 = 100;
 /// Default max body bytes.  This is 2.5MB which should be able to accommodate the largest valid
 /// JSON-RPC request, which would be an "account_put_deploy".
 const DEFAULT_MAX_BODY_BYTES: u32 = 2_621_440;
+/// Default max batch size.
+const DEFAULT_MAX_BATCH_SIZE: u32 = 100;
 
 /// JSON-RPC HTTP server configuration.
 #[derive(Clone, DataSize, Debug, Deserialize, Serialize)]
@@ -20,10 +24,16 @@ pub struct Config {
     pub enable_server: bool,
     /// Address to bind JSON-RPC HTTP server to.
     pub address: String,
-    /// Maximum rate limit in queries per second.
+    /// Maximum rate limit in queries per second, where each request in a batch counts separately.
     pub qps_limit: u64,
     /// Maximum number of bytes to accept in a single request body.
     pub max_body_bytes: u32,
+    /// Maximum number of JSON-RPC requests to accept in a single batch.  If 0, batches are
+    /// rejected.
+    pub max_batch_size: u32,
+    /// Per-client and per-method rate limiting, applied in addition to `qps_limit`.
+    #[serde(default)]
+    pub rate_limit: RateLimitConfig,
 }
 
 impl Config {
@@ -34,6 +44,8 @@ impl Config {
             address: DEFAULT_ADDRESS.to_string(),
             qps_limit: DEFAULT_QPS_LIMIT,
             max_body_bytes: DEFAULT_MAX_BODY_BYTES,
+            max_batch_size: DEFAULT_MAX_BATCH_SIZE,
+            rate_limit: RateLimitConfig::default(),
         }
     }
 }
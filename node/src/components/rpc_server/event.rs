@@ -11,9 +11,12 @@ use casper_execution_engine::core::engine_state::{
 use casper_types::{system::auction::EraValidators, Transfer};
 
 use crate::{
+    components::small_network::PeerScore,
     effect::{requests::RpcRequest, Responder},
     rpcs::chain::BlockIdentifier,
-    types::{BlockHash, BlockWithMetadata, Deploy, DeployHash, DeployMetadataExt, NodeId},
+    types::{
+        BlockHash, BlockWithMetadata, Deploy, DeployHash, DeployMetadataExt, NodeId, PeersMap,
+    },
 };
 
 #[derive(Debug, From)]
@@ -49,7 +52,8 @@ pub(crate) enum Event {
     },
     GetPeersResult {
         peers: BTreeMap<NodeId, String>,
-        main_responder: Responder<BTreeMap<NodeId, String>>,
+        scores: Vec<PeerScore>,
+        main_responder: Responder<PeersMap>,
     },
     GetBalanceResult {
         result: Result<BalanceResult, engine_state::Error>,
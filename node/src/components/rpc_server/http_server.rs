@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use hyper::server::{conn::AddrIncoming, Builder};
 
 use casper_json_rpc::RequestHandlersBuilder;
@@ -6,59 +8,104 @@ use casper_types::ProtocolVersion;
 use super::{
     rpcs::{
         account::PutDeploy,
-        chain::{GetBlock, GetBlockTransfers, GetEraInfoBySwitchBlock, GetStateRootHash},
+        chain::{
+            GetBlock, GetBlockTransfers, GetBlocks, GetEraInfoBySwitchBlock, GetEraSummaries,
+            GetStateRootHash,
+        },
         docs::ListRpcs,
-        info::{GetChainspec, GetDeploy, GetPeers, GetStatus, GetValidatorChanges},
+        info::{
+            GetAccountHistory, GetChainspec, GetDeploy, GetDeployProof, GetPeers, GetStatus,
+            GetValidatorChanges,
+        },
         state::{
-            GetAccountInfo, GetAuctionInfo, GetBalance, GetDictionaryItem, GetItem, GetTrie,
-            QueryBalance, QueryGlobalState,
+            GetAccountInfo, GetAuctionInfo, GetBalance, GetDictionaryItem, GetItem,
+            GetKeysByPrefix, GetTrie, QueryBalance, QueryBalanceDiff, QueryGlobalState,
+            QueryNamedKeysDiff,
         },
         RpcWithOptionalParams, RpcWithParams, RpcWithoutParams,
     },
-    ReactorEventT,
+    ws_server, ReactorEventT,
+};
+use crate::{
+    components::event_stream_server::EventStreamHandle,
+    effect::EffectBuilder,
+    utils::rate_limit::{QpsLimiter, RateLimitConfig, RateLimiter},
 };
-use crate::effect::EffectBuilder;
 
 /// The URL path for all JSON-RPC requests.
 pub const RPC_API_PATH: &str = "rpc";
 
 pub const RPC_API_SERVER_NAME: &str = "JSON RPC";
 
+/// Registers the handlers for all RPCs served by the JSON-RPC server.
+pub(super) fn register_handlers<REv: ReactorEventT>(
+    effect_builder: EffectBuilder<REv>,
+    api_version: ProtocolVersion,
+    handlers: &mut RequestHandlersBuilder,
+) {
+    PutDeploy::register_as_handler(effect_builder, api_version, handlers);
+    GetBlock::register_as_handler(effect_builder, api_version, handlers);
+    GetBlocks::register_as_handler(effect_builder, api_version, handlers);
+    GetBlockTransfers::register_as_handler(effect_builder, api_version, handlers);
+    GetStateRootHash::register_as_handler(effect_builder, api_version, handlers);
+    GetItem::register_as_handler(effect_builder, api_version, handlers);
+    QueryGlobalState::register_as_handler(effect_builder, api_version, handlers);
+    GetBalance::register_as_handler(effect_builder, api_version, handlers);
+    GetAccountInfo::register_as_handler(effect_builder, api_version, handlers);
+    GetDeploy::register_as_handler(effect_builder, api_version, handlers);
+    GetDeployProof::register_as_handler(effect_builder, api_version, handlers);
+    GetAccountHistory::register_as_handler(effect_builder, api_version, handlers);
+    GetPeers::register_as_handler(effect_builder, api_version, handlers);
+    GetStatus::register_as_handler(effect_builder, api_version, handlers);
+    GetEraInfoBySwitchBlock::register_as_handler(effect_builder, api_version, handlers);
+    GetEraSummaries::register_as_handler(effect_builder, api_version, handlers);
+    GetAuctionInfo::register_as_handler(effect_builder, api_version, handlers);
+    GetTrie::register_as_handler(effect_builder, api_version, handlers);
+    GetValidatorChanges::register_as_handler(effect_builder, api_version, handlers);
+    ListRpcs::register_as_handler(effect_builder, api_version, handlers);
+    GetDictionaryItem::register_as_handler(effect_builder, api_version, handlers);
+    GetChainspec::register_as_handler(effect_builder, api_version, handlers);
+    QueryBalance::register_as_handler(effect_builder, api_version, handlers);
+    QueryBalanceDiff::register_as_handler(effect_builder, api_version, handlers);
+    QueryNamedKeysDiff::register_as_handler(effect_builder, api_version, handlers);
+    GetKeysByPrefix::register_as_handler(effect_builder, api_version, handlers);
+}
+
 /// Run the JSON-RPC server.
+#[allow(clippy::too_many_arguments)]
 pub(super) async fn run<REv: ReactorEventT>(
     builder: Builder<AddrIncoming>,
     effect_builder: EffectBuilder<REv>,
     api_version: ProtocolVersion,
+    event_stream: Option<EventStreamHandle>,
     qps_limit: u64,
     max_body_bytes: u32,
+    max_batch_size: u32,
+    rate_limit_config: RateLimitConfig,
 ) {
     let mut handlers = RequestHandlersBuilder::new();
-    PutDeploy::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetBlock::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetBlockTransfers::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetStateRootHash::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetItem::register_as_handler(effect_builder, api_version, &mut handlers);
-    QueryGlobalState::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetBalance::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetAccountInfo::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetDeploy::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetPeers::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetStatus::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetEraInfoBySwitchBlock::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetAuctionInfo::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetTrie::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetValidatorChanges::register_as_handler(effect_builder, api_version, &mut handlers);
-    ListRpcs::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetDictionaryItem::register_as_handler(effect_builder, api_version, &mut handlers);
-    GetChainspec::register_as_handler(effect_builder, api_version, &mut handlers);
-    QueryBalance::register_as_handler(effect_builder, api_version, &mut handlers);
+    register_handlers(effect_builder, api_version, &mut handlers);
     let handlers = handlers.build();
 
+    let qps_limiter = QpsLimiter::new(qps_limit);
+    let rate_limiter = RateLimiter::new(rate_limit_config);
+    let ws_route = ws_server::route(
+        effect_builder,
+        api_version,
+        event_stream,
+        max_batch_size,
+        Arc::clone(&qps_limiter),
+        Arc::clone(&rate_limiter),
+    );
+
     super::rpcs::run(
         builder,
         handlers,
-        qps_limit,
+        Some(ws_route),
+        qps_limiter,
+        rate_limiter,
         max_body_bytes,
+        max_batch_size,
         RPC_API_PATH,
         RPC_API_SERVER_NAME,
     )
@@ -11,24 +11,31 @@ pub mod info;
 pub mod speculative_exec;
 pub mod state;
 
-use std::{convert::Infallible, str, sync::Arc, time::Duration};
+use std::{convert::Infallible, str, sync::Arc};
 
 use async_trait::async_trait;
-use http::header::ACCEPT_ENCODING;
+use http::{
+    header::{ACCEPT_ENCODING, CONTENT_TYPE},
+    Method,
+};
 use hyper::server::{conn::AddrIncoming, Builder};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use tokio::sync::oneshot;
-use tower::ServiceBuilder;
 use tracing::info;
-use warp::Filter;
+use warp::{filters::BoxedFilter, reply::Response, Filter, Reply};
 
-use casper_json_rpc::{Error, Params, RequestHandlers, RequestHandlersBuilder, ReservedErrorCode};
+use casper_json_rpc::{
+    filters, Error, Params, RequestHandlers, RequestHandlersBuilder, ReservedErrorCode,
+};
 use casper_types::ProtocolVersion;
 
 use super::{ReactorEventT, RpcRequest};
-use crate::effect::EffectBuilder;
+use crate::{
+    effect::EffectBuilder,
+    utils::rate_limit::{self, QpsLimiter, RateLimiter},
+};
 pub use common::ErrorData;
 use docs::DocExample;
 pub use error_code::ErrorCode;
@@ -37,7 +44,7 @@ pub use error_code::ErrorCode;
 /// standard 'id', 'jsonrpc', 'method', and 'params' fields.
 ///
 /// It will be changed to `false` for casper-node v2.0.0.
-const ALLOW_UNKNOWN_FIELDS_IN_JSON_RPC_REQUEST: bool = true;
+pub(super) const ALLOW_UNKNOWN_FIELDS_IN_JSON_RPC_REQUEST: bool = true;
 
 /// A JSON-RPC requiring the "params" field to be present.
 #[async_trait]
@@ -253,21 +260,45 @@ pub(super) trait RpcWithOptionalParams {
 }
 
 /// Start JSON RPC server in a background.
+///
+/// If provided, `ws_route` is served alongside the JSON-RPC route, and is tried first.  It is
+/// expected to apply `qps_limiter` and `rate_limiter` to the requests it handles itself.
+#[allow(clippy::too_many_arguments)]
 pub(super) async fn run(
     builder: Builder<AddrIncoming>,
     handlers: RequestHandlers,
-    qps_limit: u64,
+    ws_route: Option<BoxedFilter<(Response,)>>,
+    qps_limiter: Arc<QpsLimiter>,
+    rate_limiter: Arc<RateLimiter>,
     max_body_bytes: u32,
+    max_batch_size: u32,
     api_path: &'static str,
     server_name: &'static str,
 ) {
     let make_svc = hyper::service::make_service_fn(move |_| {
-        let service_routes = casper_json_rpc::route(
-            api_path,
-            max_body_bytes,
-            handlers.clone(),
-            ALLOW_UNKNOWN_FIELDS_IN_JSON_RPC_REQUEST,
-        );
+        // Equivalent to `casper_json_rpc::route`, but with the body of each request inspected by
+        // the rate limiters before being handled, so that every request in a batch is charged for.
+        let service_routes = filters::base_filter(api_path, max_body_bytes)
+            .and(filters::main_filter_with_body_source(
+                qps_limiter.json_rpc_body_filter(
+                    rate_limiter.json_rpc_body_filter(max_batch_size),
+                    max_batch_size,
+                ),
+                handlers.clone(),
+                ALLOW_UNKNOWN_FIELDS_IN_JSON_RPC_REQUEST,
+                max_batch_size,
+            ))
+            .recover(rate_limit::handle_json_rpc_rejection)
+            .unify()
+            .recover(filters::handle_rejection)
+            .unify()
+            .with(
+                warp::cors()
+                    .allow_any_origin()
+                    .allow_header(CONTENT_TYPE)
+                    .allow_method(Method::POST),
+            )
+            .boxed();
 
         // Supports content negotiation for gzip responses. This is an interim fix until
         // https://github.com/seanmonstar/warp/pull/513 moves forward.
@@ -275,14 +306,22 @@ pub(super) async fn run(
             .and(service_routes.clone())
             .with(warp::compression::gzip());
 
-        let service = warp::service(service_routes_gzip.or(service_routes));
+        let json_rpc_routes = service_routes_gzip
+            .or(service_routes)
+            .map(Reply::into_response)
+            .boxed();
+
+        // The JSON-RPC routes recover from all rejections, so any WebSocket route needs to be
+        // tried first.
+        let routes = match ws_route.clone() {
+            Some(ws_route) => ws_route.or(json_rpc_routes).unify().boxed(),
+            None => json_rpc_routes,
+        };
+
+        let service = warp::service(routes);
         async move { Ok::<_, Infallible>(service.clone()) }
     });
 
-    let make_svc = ServiceBuilder::new()
-        .rate_limit(qps_limit, Duration::from_secs(1))
-        .service(make_svc);
-
     let server = builder.serve(make_svc);
     info!(address = %server.local_addr(), "started {} server", server_name);
 
@@ -308,6 +347,8 @@ mod tests {
     use super::*;
     use crate::types::DeployHash;
 
+    const MAX_BATCH_SIZE: u32 = 10;
+
     async fn send_request(
         method: &str,
         maybe_params: Option<&str>,
@@ -344,9 +385,13 @@ mod tests {
             GetDeploy::register_as_test_handler(&mut handlers);
             let handlers = handlers.build();
 
-            filters::main_filter(handlers, ALLOW_UNKNOWN_FIELDS_IN_JSON_RPC_REQUEST)
-                .recover(filters::handle_rejection)
-                .boxed()
+            filters::main_filter(
+                handlers,
+                ALLOW_UNKNOWN_FIELDS_IN_JSON_RPC_REQUEST,
+                MAX_BATCH_SIZE,
+            )
+            .recover(filters::handle_rejection)
+            .boxed()
         }
 
         #[tokio::test]
@@ -411,9 +456,13 @@ mod tests {
             GetPeers::register_as_test_handler(&mut handlers);
             let handlers = handlers.build();
 
-            filters::main_filter(handlers, ALLOW_UNKNOWN_FIELDS_IN_JSON_RPC_REQUEST)
-                .recover(filters::handle_rejection)
-                .boxed()
+            filters::main_filter(
+                handlers,
+                ALLOW_UNKNOWN_FIELDS_IN_JSON_RPC_REQUEST,
+                MAX_BATCH_SIZE,
+            )
+            .recover(filters::handle_rejection)
+            .boxed()
         }
 
         #[tokio::test]
@@ -465,9 +514,13 @@ mod tests {
             GetBlock::register_as_test_handler(&mut handlers);
             let handlers = handlers.build();
 
-            filters::main_filter(handlers, ALLOW_UNKNOWN_FIELDS_IN_JSON_RPC_REQUEST)
-                .recover(filters::handle_rejection)
-                .boxed()
+            filters::main_filter(
+                handlers,
+                ALLOW_UNKNOWN_FIELDS_IN_JSON_RPC_REQUEST,
+                MAX_BATCH_SIZE,
+            )
+            .recover(filters::handle_rejection)
+            .boxed()
         }
 
         #[tokio::test]
@@ -11,22 +11,32 @@ use async_trait::async_trait;
 use once_cell::sync::Lazy;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use tracing::info;
 
 use casper_hashing::Digest;
-use casper_types::{Key, ProtocolVersion, Transfer};
+use casper_types::{EraId, Key, ProtocolVersion, Transfer};
 
 use super::{
     docs::{DocExample, DOCS_EXAMPLE_PROTOCOL_VERSION},
     Error, ErrorCode, ReactorEventT, ReservedErrorCode, RpcRequest, RpcWithOptionalParams,
+    RpcWithParams,
 };
 use crate::{
     effect::EffectBuilder,
     reactor::QueueKind,
     rpcs::common,
-    types::{Block, BlockHash, BlockWithMetadata, JsonBlock},
+    types::{Block, BlockHash, BlockHeader, BlockWithMetadata, JsonBlock, JsonBlockHeader},
 };
-pub use era_summary::EraSummary;
-use era_summary::ERA_SUMMARY;
+pub use era_summary::{EraSummary, EraSummaryEntry};
+use era_summary::{ERA_SUMMARY, ERA_SUMMARY_ENTRY};
+
+/// The maximum number of blocks returned by a single "chain_get_blocks" request.
+const MAX_BLOCKS_PAGE_SIZE: u64 = 100;
+/// The maximum number of era summaries returned by a single "chain_get_era_summaries" request.
+const MAX_ERA_SUMMARIES_PAGE_SIZE: u64 = 100;
+/// The maximum number of era summaries returned by a single "chain_get_era_summaries" request
+/// which includes the eras' transfers.
+const MAX_ERA_SUMMARIES_WITH_TRANSFERS_PAGE_SIZE: u64 = 10;
 
 static GET_BLOCK_PARAMS: Lazy<GetBlockParams> = Lazy::new(|| GetBlockParams {
     block_identifier: BlockIdentifier::Hash(*Block::doc_example().hash()),
@@ -35,6 +45,18 @@ static GET_BLOCK_RESULT: Lazy<GetBlockResult> = Lazy::new(|| GetBlockResult {
     api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
     block: Some(JsonBlock::doc_example().clone()),
 });
+static GET_BLOCKS_PARAMS: Lazy<GetBlocksParams> = Lazy::new(|| GetBlocksParams {
+    from_height: Block::doc_example().header().height(),
+    to_height: Some(Block::doc_example().header().height()),
+    limit: Some(1),
+    headers_only: false,
+});
+static GET_BLOCKS_RESULT: Lazy<GetBlocksResult> = Lazy::new(|| GetBlocksResult {
+    api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+    blocks: Some(vec![JsonBlock::doc_example().clone()]),
+    block_headers: None,
+    next_from_height: None,
+});
 static GET_BLOCK_TRANSFERS_PARAMS: Lazy<GetBlockTransfersParams> =
     Lazy::new(|| GetBlockTransfersParams {
         block_identifier: BlockIdentifier::Hash(*Block::doc_example().hash()),
@@ -61,6 +83,19 @@ static GET_ERA_INFO_RESULT: Lazy<GetEraInfoResult> = Lazy::new(|| GetEraInfoResu
     api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
     era_summary: Some(ERA_SUMMARY.clone()),
 });
+static GET_ERA_SUMMARIES_PARAMS: Lazy<GetEraSummariesParams> =
+    Lazy::new(|| GetEraSummariesParams {
+        from_era_id: ERA_SUMMARY.era_id,
+        to_era_id: Some(ERA_SUMMARY.era_id),
+        limit: Some(1),
+        include_transfers: true,
+    });
+static GET_ERA_SUMMARIES_RESULT: Lazy<GetEraSummariesResult> =
+    Lazy::new(|| GetEraSummariesResult {
+        api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+        era_summaries: vec![ERA_SUMMARY_ENTRY.clone()],
+        next_from_era_id: None,
+    });
 
 /// Identifier for possible ways to retrieve a block.
 #[derive(Serialize, Deserialize, Debug, Clone, Copy, JsonSchema)]
@@ -177,6 +212,139 @@ impl RpcWithOptionalParams for GetBlock {
     }
 }
 
+/// Params for "chain_get_blocks" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetBlocksParams {
+    /// The height of the lowest block to return.
+    pub from_height: u64,
+    /// The height of the highest block to return.  If omitted, blocks up to the highest available
+    /// block are returned.
+    pub to_height: Option<u64>,
+    /// The maximum number of blocks to return.  If omitted or greater than 100, at most 100 blocks
+    /// are returned.
+    pub limit: Option<u64>,
+    /// Whether to return only the block headers rather than the full blocks along with their
+    /// finality signatures.
+    #[serde(default)]
+    pub headers_only: bool,
+}
+
+impl DocExample for GetBlocksParams {
+    fn doc_example() -> &'static Self {
+        &*GET_BLOCKS_PARAMS
+    }
+}
+
+/// Result for "chain_get_blocks" RPC response.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetBlocksResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The available blocks in the requested range along with their finality signatures, in order
+    /// of height, unless only headers were requested.
+    pub blocks: Option<Vec<JsonBlock>>,
+    /// The headers of the available blocks in the requested range, in order of height, if only
+    /// headers were requested.
+    pub block_headers: Option<Vec<JsonBlockHeader>>,
+    /// The `from_height` with which to request the next page of blocks, or `None` if there are no
+    /// more available blocks in the requested range.
+    pub next_from_height: Option<u64>,
+}
+
+impl DocExample for GetBlocksResult {
+    fn doc_example() -> &'static Self {
+        &*GET_BLOCKS_RESULT
+    }
+}
+
+/// "chain_get_blocks" RPC.
+pub struct GetBlocks {}
+
+#[async_trait]
+impl RpcWithParams for GetBlocks {
+    const METHOD: &'static str = "chain_get_blocks";
+    type RequestParams = GetBlocksParams;
+    type ResponseResult = GetBlocksResult;
+
+    async fn do_handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        api_version: ProtocolVersion,
+        params: Self::RequestParams,
+    ) -> Result<Self::ResponseResult, Error> {
+        let from_height = params.from_height;
+        let to_height = params.to_height.unwrap_or(u64::MAX);
+        if from_height > to_height {
+            let message = format!(
+                "from_height {} exceeds to_height {}",
+                from_height, to_height
+            );
+            return Err(Error::new(ReservedErrorCode::InvalidParams, message));
+        }
+        let limit = params
+            .limit
+            .unwrap_or(MAX_BLOCKS_PAGE_SIZE)
+            .min(MAX_BLOCKS_PAGE_SIZE) as usize;
+
+        // The storage requests are restricted by the completed block range, so they include blocks
+        // whose global state has been pruned.  We request one more block than the limit to find
+        // where the next page starts, if anywhere.
+        let result = if params.headers_only {
+            let mut block_headers = effect_builder
+                .get_block_headers_in_range_from_storage(from_height, to_height, limit + 1)
+                .await;
+            let next_from_height = if block_headers.len() > limit {
+                block_headers
+                    .pop()
+                    .map(|block_header| block_header.height())
+            } else {
+                None
+            };
+            Self::ResponseResult {
+                api_version,
+                blocks: None,
+                block_headers: Some(
+                    block_headers
+                        .into_iter()
+                        .map(JsonBlockHeader::from)
+                        .collect(),
+                ),
+                next_from_height,
+            }
+        } else {
+            let mut blocks_with_metadata = effect_builder
+                .get_blocks_with_metadata_in_range_from_storage(from_height, to_height, limit + 1)
+                .await;
+            let next_from_height = if blocks_with_metadata.len() > limit {
+                blocks_with_metadata
+                    .pop()
+                    .map(|block_with_metadata| block_with_metadata.block.height())
+            } else {
+                None
+            };
+            Self::ResponseResult {
+                api_version,
+                blocks: Some(
+                    blocks_with_metadata
+                        .into_iter()
+                        .map(|block_with_metadata| {
+                            JsonBlock::new(
+                                block_with_metadata.block,
+                                Some(block_with_metadata.block_signatures),
+                            )
+                        })
+                        .collect(),
+                ),
+                block_headers: None,
+                next_from_height,
+            }
+        };
+        Ok(result)
+    }
+}
+
 /// Params for "chain_get_block_transfers" RPC request.
 #[derive(Serialize, Deserialize, Debug, JsonSchema)]
 #[serde(deny_unknown_fields)]
@@ -398,28 +566,209 @@ impl RpcWithOptionalParams for GetEraInfoBySwitchBlock {
             });
         }
 
-        let state_root_hash = block.state_root_hash().to_owned();
-        let era_id = block.header().era_id();
-        let base_key = Key::EraInfo(era_id);
-        let path = Vec::new();
-
-        let (stored_value, merkle_proof) =
-            common::run_query_and_encode(effect_builder, state_root_hash, base_key, path).await?;
+        let era_summary = get_era_summary(effect_builder, *block.hash(), block.header()).await?;
 
         let result = Self::ResponseResult {
             api_version,
-            era_summary: Some(EraSummary {
-                block_hash: *block.hash(),
+            era_summary: Some(era_summary),
+        };
+        Ok(result)
+    }
+}
+
+/// Params for "chain_get_era_summaries" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetEraSummariesParams {
+    /// The ID of the lowest era to return the summary of.
+    pub from_era_id: EraId,
+    /// The ID of the highest era to return the summary of.  If omitted, summaries up to the era
+    /// ended by the highest available switch block are returned.
+    pub to_era_id: Option<EraId>,
+    /// The maximum number of era summaries to return.  If omitted or greater than 100, at most 100
+    /// era summaries are returned, or at most 10 if transfers are included.
+    pub limit: Option<u64>,
+    /// Whether to return all transfers made in each era, i.e. the transfers of every block from the
+    /// one following the previous switch block up to and including the era's switch block.
+    #[serde(default)]
+    pub include_transfers: bool,
+}
+
+impl DocExample for GetEraSummariesParams {
+    fn doc_example() -> &'static Self {
+        &*GET_ERA_SUMMARIES_PARAMS
+    }
+}
+
+/// Result for "chain_get_era_summaries" RPC response.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetEraSummariesResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// An entry for each era in the requested range whose switch block is available, in order of
+    /// era ID.
+    pub era_summaries: Vec<EraSummaryEntry>,
+    /// The `from_era_id` with which to request the next page of era summaries, or `None` if there
+    /// are no more available in the requested range.
+    pub next_from_era_id: Option<EraId>,
+}
+
+impl DocExample for GetEraSummariesResult {
+    fn doc_example() -> &'static Self {
+        &*GET_ERA_SUMMARIES_RESULT
+    }
+}
+
+/// "chain_get_era_summaries" RPC.
+pub struct GetEraSummaries {}
+
+#[async_trait]
+impl RpcWithParams for GetEraSummaries {
+    const METHOD: &'static str = "chain_get_era_summaries";
+    type RequestParams = GetEraSummariesParams;
+    type ResponseResult = GetEraSummariesResult;
+
+    async fn do_handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        api_version: ProtocolVersion,
+        params: Self::RequestParams,
+    ) -> Result<Self::ResponseResult, Error> {
+        let from_era_id = params.from_era_id;
+        let to_era_id = params.to_era_id.unwrap_or(EraId::MAX);
+        if from_era_id > to_era_id {
+            let message = format!(
+                "from_era_id {} exceeds to_era_id {}",
+                from_era_id, to_era_id
+            );
+            return Err(Error::new(ReservedErrorCode::InvalidParams, message));
+        }
+        let max_page_size = if params.include_transfers {
+            MAX_ERA_SUMMARIES_WITH_TRANSFERS_PAGE_SIZE
+        } else {
+            MAX_ERA_SUMMARIES_PAGE_SIZE
+        };
+        let limit = params.limit.unwrap_or(max_page_size).min(max_page_size) as usize;
+
+        // The storage request is restricted by the completed block range, so it includes switch
+        // blocks whose global state has been pruned.  We request one more switch block than the
+        // limit to find where the next page starts, if anywhere.
+        let mut switch_block_headers = effect_builder
+            .get_switch_block_headers_in_era_range_from_storage(from_era_id, to_era_id, limit + 1)
+            .await;
+        let next_from_era_id = if switch_block_headers.len() > limit {
+            switch_block_headers
+                .pop()
+                .map(|switch_block_header| switch_block_header.era_id())
+        } else {
+            None
+        };
+
+        let mut era_summaries = Vec::with_capacity(switch_block_headers.len());
+        for switch_block_header in &switch_block_headers {
+            let era_id = switch_block_header.era_id();
+            let switch_block_hash = switch_block_header.hash();
+            // A failure to query one era, e.g. as its global state has been pruned, only makes
+            // that entry's summary unavailable rather than failing the whole page.
+            let era_summary =
+                match get_era_summary(effect_builder, switch_block_hash, switch_block_header).await
+                {
+                    Ok(era_summary) => Some(era_summary),
+                    Err(error) => {
+                        info!(%era_id, ?error, "era summary unavailable");
+                        None
+                    }
+                };
+            let transfers = if params.include_transfers {
+                get_era_transfers(effect_builder, switch_block_header).await
+            } else {
+                None
+            };
+            era_summaries.push(EraSummaryEntry {
                 era_id,
-                stored_value,
-                state_root_hash,
-                merkle_proof,
-            }),
+                switch_block_hash,
+                era_summary,
+                transfers,
+            });
+        }
+
+        let result = Self::ResponseResult {
+            api_version,
+            era_summaries,
+            next_from_era_id,
         };
         Ok(result)
     }
 }
 
+/// Queries global state for the `EraInfo` of the era ended by the given switch block.
+async fn get_era_summary<REv: ReactorEventT>(
+    effect_builder: EffectBuilder<REv>,
+    block_hash: BlockHash,
+    switch_block_header: &BlockHeader,
+) -> Result<EraSummary, Error> {
+    let state_root_hash = *switch_block_header.state_root_hash();
+    let era_id = switch_block_header.era_id();
+    let base_key = Key::EraInfo(era_id);
+    let path = Vec::new();
+
+    let (stored_value, merkle_proof) =
+        common::run_query_and_encode(effect_builder, state_root_hash, base_key, path).await?;
+
+    Ok(EraSummary {
+        block_hash,
+        era_id,
+        stored_value,
+        state_root_hash,
+        merkle_proof,
+    })
+}
+
+/// Collects the transfers of every block in the era ended by the given switch block, in order of
+/// block height.
+///
+/// Returns `None` if any of the era's blocks or their transfers are not stored.
+async fn get_era_transfers<REv: ReactorEventT>(
+    effect_builder: EffectBuilder<REv>,
+    switch_block_header: &BlockHeader,
+) -> Option<Vec<Transfer>> {
+    let from_height = match switch_block_header.era_id().checked_sub(1) {
+        Some(previous_era_id) => {
+            effect_builder
+                .get_switch_block_header_at_era_id_from_storage(previous_era_id)
+                .await?
+                .height()
+                + 1
+        }
+        None => 0,
+    };
+    let to_height = switch_block_header.height();
+    let block_count = to_height.checked_sub(from_height)? + 1;
+    let block_headers = effect_builder
+        .get_block_headers_in_range_from_storage(from_height, to_height, block_count as usize)
+        .await;
+    if block_headers.len() as u64 != block_count {
+        return None;
+    }
+
+    let mut transfers = vec![];
+    for block_header in block_headers {
+        let block_hash = block_header.hash();
+        let block_transfers = effect_builder
+            .make_request(
+                |responder| RpcRequest::GetBlockTransfers {
+                    block_hash,
+                    responder,
+                },
+                QueueKind::Api,
+            )
+            .await?;
+        transfers.extend(block_transfers);
+    }
+    Some(transfers)
+}
+
 pub(super) async fn get_block_with_metadata<REv: ReactorEventT>(
     maybe_id: Option<BlockIdentifier>,
     only_from_available_block_range: bool,
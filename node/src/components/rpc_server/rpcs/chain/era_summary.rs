@@ -5,7 +5,7 @@ use serde::{Deserialize, Serialize};
 use casper_hashing::Digest;
 use casper_types::{
     system::auction::{EraInfo, SeigniorageAllocation},
-    AsymmetricType, EraId, PublicKey, U512,
+    AsymmetricType, EraId, PublicKey, Transfer, U512,
 };
 
 use crate::{
@@ -44,6 +44,13 @@ pub(super) static ERA_SUMMARY: Lazy<EraSummary> = Lazy::new(|| {
     }
 });
 
+pub(super) static ERA_SUMMARY_ENTRY: Lazy<EraSummaryEntry> = Lazy::new(|| EraSummaryEntry {
+    era_id: ERA_SUMMARY.era_id,
+    switch_block_hash: ERA_SUMMARY.block_hash,
+    era_summary: Some(ERA_SUMMARY.clone()),
+    transfers: Some(vec![Transfer::default()]),
+});
+
 /// The summary of an era
 #[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
 #[serde(deny_unknown_fields)]
@@ -59,3 +66,19 @@ pub struct EraSummary {
     /// The merkle proof
     pub merkle_proof: String,
 }
+
+/// An era's entry in the "chain_get_era_summaries" RPC response.
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct EraSummaryEntry {
+    /// The era id
+    pub era_id: EraId,
+    /// The hash of the switch block ending the era
+    pub switch_block_hash: BlockHash,
+    /// The summary of the era, or `None` if it is unavailable, e.g. as the global state of the
+    /// switch block has been pruned
+    pub era_summary: Option<EraSummary>,
+    /// All transfers made in the era, in order of block height, or `None` if not requested or if
+    /// any of the era's blocks are unavailable
+    pub transfers: Option<Vec<Transfer>>,
+}
@@ -17,11 +17,17 @@ use casper_types::ProtocolVersion;
 
 use super::{
     account::PutDeploy,
-    chain::{GetBlock, GetBlockTransfers, GetEraInfoBySwitchBlock, GetStateRootHash},
-    info::{GetChainspec, GetDeploy, GetPeers, GetStatus, GetValidatorChanges},
+    chain::{
+        GetBlock, GetBlockTransfers, GetBlocks, GetEraInfoBySwitchBlock, GetEraSummaries,
+        GetStateRootHash,
+    },
+    info::{
+        GetAccountHistory, GetChainspec, GetDeploy, GetDeployProof, GetPeers, GetStatus,
+        GetValidatorChanges,
+    },
     state::{
-        GetAccountInfo, GetAuctionInfo, GetBalance, GetDictionaryItem, GetItem, QueryBalance,
-        QueryGlobalState,
+        GetAccountInfo, GetAuctionInfo, GetBalance, GetDictionaryItem, GetItem, GetKeysByPrefix,
+        QueryBalance, QueryBalanceDiff, QueryGlobalState, QueryNamedKeysDiff,
     },
     Error, ReactorEventT, RpcWithOptionalParams, RpcWithParams, RpcWithoutParams,
 };
@@ -68,6 +74,12 @@ pub(crate) static OPEN_RPC_SCHEMA: Lazy<OpenRpcSchema> = Lazy::new(|| {
 
     schema.push_with_params::<PutDeploy>("receives a Deploy to be executed by the network");
     schema.push_with_params::<GetDeploy>("returns a Deploy from the network");
+    schema.push_with_params::<GetDeployProof>(
+        "returns a Deploy's block and execution result, with a proof of their inclusion",
+    );
+    schema.push_with_params::<GetAccountHistory>(
+        "returns the executed Deploys an Account sent or transferred to or from",
+    );
     schema.push_with_params::<GetAccountInfo>("returns an Account from the network");
     schema.push_with_params::<GetDictionaryItem>("returns an item from a Dictionary");
     schema.push_with_params::<QueryGlobalState>(
@@ -76,6 +88,17 @@ pub(crate) static OPEN_RPC_SCHEMA: Lazy<OpenRpcSchema> = Lazy::new(|| {
     schema.push_with_params::<QueryBalance>(
         "query for a balance using a purse identifier and a state identifier",
     );
+    schema.push_with_params::<QueryBalanceDiff>(
+        "query for the difference of a purse's balance between two state identifiers",
+    );
+    schema.push_with_params::<QueryNamedKeysDiff>(
+        "query for the difference of an Account's or contract's named keys between two state \
+        identifiers",
+    );
+    schema.push_with_params::<GetKeysByPrefix>(
+        "returns a page of the keys, and optionally the values, of global state entries sharing a \
+        key prefix",
+    );
     schema.push_without_params::<GetPeers>("returns a list of peers connected to the node");
     schema.push_without_params::<GetStatus>("returns the current status of the node");
     schema
@@ -85,6 +108,9 @@ pub(crate) static OPEN_RPC_SCHEMA: Lazy<OpenRpcSchema> = Lazy::new(|| {
         global_state.toml files",
     );
     schema.push_with_optional_params::<GetBlock>("returns a Block from the network");
+    schema.push_with_params::<GetBlocks>(
+        "returns the Blocks or Block headers within a range of heights from the network",
+    );
     schema.push_with_optional_params::<GetBlockTransfers>(
         "returns all transfers for a Block from the network",
     );
@@ -99,6 +125,10 @@ pub(crate) static OPEN_RPC_SCHEMA: Lazy<OpenRpcSchema> = Lazy::new(|| {
     schema.push_with_optional_params::<GetEraInfoBySwitchBlock>(
         "returns an EraInfo from the network",
     );
+    schema.push_with_params::<GetEraSummaries>(
+        "returns the EraInfo, and optionally all transfers, of each era within a range of era IDs \
+        from the network",
+    );
     schema.push_with_optional_params::<GetAuctionInfo>(
         "returns the bids and validators as of either a specific block (by height or hash), or \
         the most recently added block",
@@ -35,6 +35,20 @@ pub enum ErrorCode {
     FailedToGetTrie = -32011,
     /// The requested state root hash was not found.
     NoSuchStateRoot = -32012,
+    /// The event stream server is not enabled, so subscriptions are unavailable.
+    EventStreamUnavailable = -32013,
+    /// The limit of subscriptions has been reached.
+    TooManySubscriptions = -32014,
+    /// The requested global state has been pruned from this node.
+    StateRootPruned = -32015,
+    /// The execution result of the requested Deploy was not found.
+    NoSuchExecutionResult = -32016,
+    /// The account history index is not enabled on this node.
+    AccountHistoryUnavailable = -32017,
+    /// A state override given for speculative execution could not be parsed.
+    InvalidStateOverride = -32018,
+    /// The request was rejected by a rate limit.
+    RateLimited = -32019,
 }
 
 impl From<ErrorCode> for (i64, &'static str) {
@@ -59,6 +73,15 @@ impl From<ErrorCode> for (i64, &'static str) {
             }
             ErrorCode::FailedToGetTrie => (error_code as i64, "Failed to get trie"),
             ErrorCode::NoSuchStateRoot => (error_code as i64, "No such state root"),
+            ErrorCode::EventStreamUnavailable => (error_code as i64, "Event stream unavailable"),
+            ErrorCode::TooManySubscriptions => (error_code as i64, "Too many subscriptions"),
+            ErrorCode::StateRootPruned => (error_code as i64, "State root pruned"),
+            ErrorCode::NoSuchExecutionResult => (error_code as i64, "No such execution result"),
+            ErrorCode::AccountHistoryUnavailable => {
+                (error_code as i64, "Account history unavailable")
+            }
+            ErrorCode::InvalidStateOverride => (error_code as i64, "Invalid state override"),
+            ErrorCode::RateLimited => (error_code as i64, "Rate limited"),
         }
     }
 }
@@ -9,11 +9,19 @@ use async_trait::async_trait;
 use once_cell::sync::Lazy;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use tracing::info;
-
-use casper_types::{EraId, ExecutionResult, ProtocolVersion, PublicKey};
+use tracing::{info, warn};
+
+use casper_hashing::ChunkWithProof;
+use casper_json_rpc::ReservedErrorCode;
+use casper_types::{
+    account::AccountHash,
+    bytesrepr::{ToBytes, U32_SERIALIZED_LENGTH},
+    EraId, ExecutionResult, Key, ProtocolVersion, PublicKey, SecretKey,
+};
 
 use super::{
+    chain::{self, BlockIdentifier},
+    common::{self, MERKLE_PROOF},
     docs::{DocExample, DOCS_EXAMPLE_PROTOCOL_VERSION},
     Error, ErrorCode, ReactorEventT, RpcRequest, RpcWithParams, RpcWithoutParams,
 };
@@ -22,11 +30,14 @@ use crate::{
     effect::EffectBuilder,
     reactor::QueueKind,
     types::{
-        Block, BlockHash, BlockHashAndHeight, ChainspecRawBytes, Deploy, DeployHash,
-        DeployMetadataExt, GetStatusResult, PeersMap,
+        AccountHistoryEntry, Block, BlockHash, BlockHashAndHeight, ChainspecRawBytes, Deploy,
+        DeployHash, DeployMetadataExt, GetStatusResult, JsonBlock, PeersMap,
     },
 };
 
+/// The maximum number of entries returned by a single "info_get_account_history" request.
+const MAX_ACCOUNT_HISTORY_PAGE_SIZE: u64 = 1_000;
+
 static GET_DEPLOY_PARAMS: Lazy<GetDeployParams> = Lazy::new(|| GetDeployParams {
     deploy_hash: *Deploy::doc_example().id(),
     finalized_approvals: true,
@@ -40,6 +51,37 @@ static GET_DEPLOY_RESULT: Lazy<GetDeployResult> = Lazy::new(|| GetDeployResult {
     }],
     block_hash_and_height: None,
 });
+static GET_DEPLOY_PROOF_PARAMS: Lazy<GetDeployProofParams> = Lazy::new(|| GetDeployProofParams {
+    deploy_hash: *Deploy::doc_example().id(),
+});
+static GET_DEPLOY_PROOF_RESULT: Lazy<GetDeployProofResult> = Lazy::new(|| GetDeployProofResult {
+    api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+    block: JsonBlock::doc_example().clone(),
+    execution_result_index: 0,
+    execution_result: ExecutionResult::example().clone(),
+    execution_results_chunks: EXECUTION_RESULTS_CHUNKS.clone(),
+    merkle_proof: MERKLE_PROOF.clone(),
+});
+static EXECUTION_RESULTS_CHUNKS: Lazy<String> = Lazy::new(|| {
+    String::from(
+        "01000000000000000000000001000000000000000100000099bd72c73bea193f7040ac279bd656cdec7fd35e\
+        097a657b6c03b4fa967223ed0c000000000102030405060708090a0b",
+    )
+});
+static GET_ACCOUNT_HISTORY_PARAMS: Lazy<GetAccountHistoryParams> = Lazy::new(|| {
+    let secret_key = SecretKey::ed25519_from_bytes([0; 32]).unwrap();
+    GetAccountHistoryParams {
+        account_hash: PublicKey::from(&secret_key).to_account_hash(),
+        cursor: None,
+        limit: Some(10),
+    }
+});
+static GET_ACCOUNT_HISTORY_RESULT: Lazy<GetAccountHistoryResult> =
+    Lazy::new(|| GetAccountHistoryResult {
+        api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+        entries: vec![AccountHistoryEntry::doc_example().clone()],
+        next_cursor: None,
+    });
 static GET_PEERS_RESULT: Lazy<GetPeersResult> = Lazy::new(|| GetPeersResult {
     api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
     peers: GetStatusResult::doc_example().peers.clone(),
@@ -177,6 +219,286 @@ impl RpcWithParams for GetDeploy {
     }
 }
 
+/// Params for "info_get_deploy_proof" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetDeployProofParams {
+    /// The deploy hash.
+    pub deploy_hash: DeployHash,
+}
+
+impl DocExample for GetDeployProofParams {
+    fn doc_example() -> &'static Self {
+        &*GET_DEPLOY_PROOF_PARAMS
+    }
+}
+
+/// Result for "info_get_deploy_proof" RPC response.
+///
+/// The block's hash commits to its header, and the header's body hash to its body, which lists the
+/// block's deploy hashes followed by its transfer hashes in execution order.  The execution
+/// results of these deploys, serialized as a `Vec<ExecutionResult>` and hashed with
+/// `Digest::hash_bytes_into_chunks_if_necessary`, yield the value stored under
+/// `Key::BlockEffectsRootHash` for the block's height, which is proven under the header's state
+/// root hash by `merkle_proof`.
+///
+/// Only the chunks of the serialized execution results up to the one holding the end of the
+/// deploy's execution result are returned, each with its proof against that value.  The preceding
+/// chunks are needed to locate the deploy's execution result by its index.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetDeployProofResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The block in which the deploy was executed, along with its finality signatures.
+    pub block: JsonBlock,
+    /// The index of the deploy's execution result among those of the block's deploys and
+    /// transfers, in execution order.
+    pub execution_result_index: u64,
+    /// The deploy's execution result.
+    pub execution_result: ExecutionResult,
+    /// The chunks of the block's serialized execution results, up to the one holding the end of
+    /// the deploy's execution result, as a hex-encoded, bytesrepr-encoded `Vec<ChunkWithProof>`.
+    pub execution_results_chunks: String,
+    /// The merkle proof of the block's `Key::BlockEffectsRootHash` entry.
+    pub merkle_proof: String,
+}
+
+impl DocExample for GetDeployProofResult {
+    fn doc_example() -> &'static Self {
+        &*GET_DEPLOY_PROOF_RESULT
+    }
+}
+
+/// "info_get_deploy_proof" RPC.
+pub struct GetDeployProof {}
+
+#[async_trait]
+impl RpcWithParams for GetDeployProof {
+    const METHOD: &'static str = "info_get_deploy_proof";
+    type RequestParams = GetDeployProofParams;
+    type ResponseResult = GetDeployProofResult;
+
+    async fn do_handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        api_version: ProtocolVersion,
+        params: Self::RequestParams,
+    ) -> Result<Self::ResponseResult, Error> {
+        let deploy_hash = params.deploy_hash;
+        let maybe_deploy_and_metadata = effect_builder
+            .make_request(
+                |responder| RpcRequest::GetDeploy {
+                    hash: deploy_hash,
+                    finalized_approvals: false,
+                    responder,
+                },
+                QueueKind::Api,
+            )
+            .await;
+
+        let maybe_block_hash = match maybe_deploy_and_metadata {
+            Some((_, DeployMetadataExt::Metadata(metadata))) => {
+                // On the linear chain a deploy is executed in at most one block.
+                metadata.execution_results.into_keys().next()
+            }
+            Some((_, DeployMetadataExt::BlockInfo(_) | DeployMetadataExt::Empty)) => None,
+            None => {
+                let message = format!("failed to get {} and metadata from storage", deploy_hash);
+                info!("{}", message);
+                return Err(Error::new(ErrorCode::NoSuchDeploy, message));
+            }
+        };
+        let block_hash = match maybe_block_hash {
+            Some(block_hash) => block_hash,
+            None => {
+                let message = format!("no execution result for {} on this node", deploy_hash);
+                info!("{}", message);
+                return Err(Error::new(ErrorCode::NoSuchExecutionResult, message));
+            }
+        };
+
+        let block_with_metadata = chain::get_block_with_metadata(
+            Some(BlockIdentifier::Hash(block_hash)),
+            true,
+            effect_builder,
+        )
+        .await?;
+
+        let execution_results = effect_builder
+            .get_block_execution_results_from_storage(block_hash)
+            .await
+            .unwrap_or_default();
+        let maybe_index = execution_results
+            .iter()
+            .position(|(executed_deploy_hash, _)| *executed_deploy_hash == deploy_hash);
+        let execution_result_index = match maybe_index {
+            Some(index) => index,
+            None => {
+                let message = format!(
+                    "execution results of {} not all stored on this node",
+                    block_hash
+                );
+                info!("{}", message);
+                return Err(Error::new(ErrorCode::NoSuchExecutionResult, message));
+            }
+        };
+
+        let block_header = block_with_metadata.block.header();
+        let (_, merkle_proof) = common::run_query_and_encode(
+            effect_builder,
+            *block_header.state_root_hash(),
+            Key::BlockEffectsRootHash {
+                block_height: block_header.height(),
+            },
+            vec![],
+        )
+        .await?;
+
+        let mut execution_results: Vec<ExecutionResult> = execution_results
+            .into_iter()
+            .map(|(_, execution_result)| execution_result)
+            .collect();
+        let serialized_execution_results = execution_results.to_bytes().map_err(|error| {
+            let message = format!("failed to encode execution results: {}", error);
+            warn!("{}", message);
+            Error::new(ReservedErrorCode::InternalError, message)
+        })?;
+        let execution_result_end = U32_SERIALIZED_LENGTH
+            + execution_results[..=execution_result_index]
+                .iter()
+                .map(ToBytes::serialized_length)
+                .sum::<usize>();
+        let execution_results_chunks =
+            encode_execution_results_chunks(&serialized_execution_results, execution_result_end)?;
+
+        let result = Self::ResponseResult {
+            api_version,
+            block: JsonBlock::new(
+                block_with_metadata.block,
+                Some(block_with_metadata.block_signatures),
+            ),
+            execution_result_index: execution_result_index as u64,
+            execution_result: execution_results.swap_remove(execution_result_index),
+            execution_results_chunks,
+            merkle_proof,
+        };
+        Ok(result)
+    }
+}
+
+/// Returns the chunks of `serialized_execution_results` from the first up to the one holding the
+/// byte preceding `end`, each with its proof.
+///
+/// The chunks are bytesrepr-encoded, and then hex-encoded.
+fn encode_execution_results_chunks(
+    serialized_execution_results: &[u8],
+    end: usize,
+) -> Result<String, Error> {
+    let last_chunk_index = end.saturating_sub(1) / ChunkWithProof::CHUNK_SIZE_BYTES;
+    let encoded_chunks = (0..=last_chunk_index as u64)
+        .map(|chunk_index| ChunkWithProof::new(serialized_execution_results, chunk_index))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|error| error.to_string())
+        .and_then(|chunks| chunks.to_bytes().map_err(|error| error.to_string()))
+        .map_err(|error| {
+            let message = format!("failed to encode execution results chunks: {}", error);
+            warn!("{}", message);
+            Error::new(ReservedErrorCode::InternalError, message)
+        })?;
+    Ok(base16::encode_lower(&encoded_chunks))
+}
+
+/// Params for "info_get_account_history" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetAccountHistoryParams {
+    /// The account hash.
+    pub account_hash: AccountHash,
+    /// The `next_cursor` returned by the previous request, if continuing from a previous page.
+    pub cursor: Option<String>,
+    /// The maximum number of entries to return.  If omitted, zero or greater than 1000, at most
+    /// 1000 entries are returned.
+    pub limit: Option<u64>,
+}
+
+impl DocExample for GetAccountHistoryParams {
+    fn doc_example() -> &'static Self {
+        &*GET_ACCOUNT_HISTORY_PARAMS
+    }
+}
+
+/// Result for "info_get_account_history" RPC response.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetAccountHistoryResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The executed deploys the account was involved in, in order of block height.
+    pub entries: Vec<AccountHistoryEntry>,
+    /// The cursor to pass to retrieve the next page of entries, or `None` if there are no more.
+    pub next_cursor: Option<String>,
+}
+
+impl DocExample for GetAccountHistoryResult {
+    fn doc_example() -> &'static Self {
+        &*GET_ACCOUNT_HISTORY_RESULT
+    }
+}
+
+/// "info_get_account_history" RPC.
+pub struct GetAccountHistory {}
+
+#[async_trait]
+impl RpcWithParams for GetAccountHistory {
+    const METHOD: &'static str = "info_get_account_history";
+    type RequestParams = GetAccountHistoryParams;
+    type ResponseResult = GetAccountHistoryResult;
+
+    async fn do_handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        api_version: ProtocolVersion,
+        params: Self::RequestParams,
+    ) -> Result<Self::ResponseResult, Error> {
+        let cursor = match params.cursor {
+            Some(cursor) => match base16::decode(cursor.as_bytes()) {
+                Ok(cursor) => Some(cursor),
+                Err(error) => {
+                    let message = format!("failed to decode cursor: {}", error);
+                    info!("{}", message);
+                    return Err(Error::new(ReservedErrorCode::InvalidParams, message));
+                }
+            },
+            None => None,
+        };
+        let limit = params
+            .limit
+            .filter(|limit| *limit > 0)
+            .unwrap_or(MAX_ACCOUNT_HISTORY_PAGE_SIZE)
+            .min(MAX_ACCOUNT_HISTORY_PAGE_SIZE) as usize;
+
+        let (entries, next_cursor) = match effect_builder
+            .get_account_history_from_storage(params.account_hash, cursor, limit)
+            .await
+        {
+            Some(entries_and_next_cursor) => entries_and_next_cursor,
+            None => {
+                let message = "the account history index is not enabled on this node".to_string();
+                info!("{}", message);
+                return Err(Error::new(ErrorCode::AccountHistoryUnavailable, message));
+            }
+        };
+
+        let result = Self::ResponseResult {
+            api_version,
+            entries,
+            next_cursor: next_cursor.map(|cursor| base16::encode_lower(&cursor)),
+        };
+        Ok(result)
+    }
+}
+
 /// Result for "info_get_peers" RPC response.
 #[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
 #[serde(deny_unknown_fields)]
@@ -213,10 +535,7 @@ impl RpcWithoutParams for GetPeers {
             )
             .await;
 
-        let result = Self::ResponseResult {
-            api_version,
-            peers: PeersMap::from(peers),
-        };
+        let result = Self::ResponseResult { api_version, peers };
         Ok(result)
     }
 }
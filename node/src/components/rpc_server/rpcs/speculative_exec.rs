@@ -12,7 +12,11 @@ use once_cell::sync::Lazy;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use casper_types::{ExecutionResult, ProtocolVersion};
+use casper_hashing::Digest;
+use casper_types::{
+    bytesrepr, EraId, ExecutionEffect, ExecutionResult, Key, ProtocolVersion, StoredValue,
+    Timestamp,
+};
 
 use super::{
     chain::BlockIdentifier,
@@ -21,20 +25,47 @@ use super::{
     Error, ErrorCode, ReactorEventT, RpcWithParams,
 };
 use crate::{
+    components::contract_runtime::{SpeculativeExecutionOverrides, SpeculativeExecutionResults},
     effect::{requests::RpcRequest, EffectBuilder},
     reactor::QueueKind,
-    types::{Block, BlockHash, Deploy},
+    types::{Block, BlockHash, Deploy, DeployHash},
 };
 
 static SPECULATIVE_EXEC_PARAMS: Lazy<SpeculativeExecParams> = Lazy::new(|| SpeculativeExecParams {
     block_identifier: Some(BlockIdentifier::Hash(*Block::doc_example().hash())),
     deploy: Deploy::doc_example().clone(),
+    state_overrides: Vec::new(),
+    block_time: None,
+    era_id: None,
 });
 static SPECULATIVE_EXEC_RESULT: Lazy<SpeculativeExecResult> = Lazy::new(|| SpeculativeExecResult {
     api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
     block_hash: *Block::doc_example().hash(),
     execution_result: ExecutionResult::example().clone(),
 });
+static SPECULATIVE_EXEC_DEPLOYS_PARAMS: Lazy<SpeculativeExecDeploysParams> =
+    Lazy::new(|| SpeculativeExecDeploysParams {
+        block_identifier: Some(BlockIdentifier::Hash(*Block::doc_example().hash())),
+        deploys: vec![Deploy::doc_example().clone()],
+        state_overrides: Vec::new(),
+        block_time: None,
+        era_id: None,
+    });
+static SPECULATIVE_EXEC_DEPLOYS_RESULT: Lazy<SpeculativeExecDeploysResult> =
+    Lazy::new(|| SpeculativeExecDeploysResult {
+        api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+        block_hash: *Block::doc_example().hash(),
+        execution_results: vec![SpeculativeDeployExecutionResult {
+            deploy_hash: *Deploy::doc_example().id(),
+            execution_result: ExecutionResult::example().clone(),
+        }],
+        effect: ExecutionEffect::default(),
+        post_state_hash: *Block::doc_example().header().state_root_hash(),
+    });
+
+/// The maximum number of deploys which can be executed in a single "speculative_exec_deploys"
+/// request.
+const MAX_SPECULATIVE_EXEC_DEPLOYS: usize = 10;
 
 /// Params for "speculative_exec" RPC request.
 #[derive(Serialize, Deserialize, Debug, JsonSchema)]
@@ -44,6 +75,51 @@ pub struct SpeculativeExecParams {
     pub block_identifier: Option<BlockIdentifier>,
     /// Deploy to execute.
     pub deploy: Deploy,
+    /// Values to write to a scratch copy of global state before executing the deploy.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub state_overrides: Vec<StateOverride>,
+    /// Block time to execute the deploy with, in place of that of the block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_time: Option<Timestamp>,
+    /// Era in which to execute the deploy, in place of that of the block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub era_id: Option<EraId>,
+}
+
+/// A value to write to global state before speculatively executing a deploy.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct StateOverride {
+    /// The formatted key under which to write the value, e.g. "account-hash-<hex>".
+    pub key: String,
+    /// The hex-encoded bytesrepr serialization of the `StoredValue` to write.
+    pub stored_value: String,
+}
+
+impl StateOverride {
+    fn parse(self) -> Result<(Key, StoredValue), Error> {
+        let key = Key::from_formatted_str(&self.key).map_err(|error| {
+            Error::new(
+                ErrorCode::InvalidStateOverride,
+                format!("failed to parse key {}: {}", self.key, error),
+            )
+        })?;
+        let stored_value = base16::decode(self.stored_value.as_bytes())
+            .map_err(|error| error.to_string())
+            .and_then(|bytes| {
+                bytesrepr::deserialize::<StoredValue>(bytes).map_err(|error| error.to_string())
+            })
+            .map_err(|error| {
+                Error::new(
+                    ErrorCode::InvalidStateOverride,
+                    format!(
+                        "failed to parse stored value for key {}: {}",
+                        self.key, error
+                    ),
+                )
+            })?;
+        Ok((key, stored_value))
+    }
 }
 
 impl DocExample for SpeculativeExecParams {
@@ -88,7 +164,11 @@ impl RpcWithParams for SpeculativeExec {
         let SpeculativeExecParams {
             block_identifier: maybe_block_id,
             deploy,
+            state_overrides,
+            block_time,
+            era_id,
         } = params;
+        let overrides = parse_overrides(state_overrides, block_time, era_id)?;
         // This RPC request is restricted by the block availability index.
         let only_from_available_block_range = true;
 
@@ -102,8 +182,9 @@ impl RpcWithParams for SpeculativeExec {
         let result = effect_builder
             .make_request(
                 |responder| RpcRequest::SpeculativeDeployExecute {
-                    block_header: block.take_header(),
+                    block: Box::new(block),
                     deploy: Box::new(deploy),
+                    overrides,
                     responder,
                 },
                 QueueKind::Api,
@@ -123,50 +204,421 @@ impl RpcWithParams for SpeculativeExec {
                 ErrorCode::NoSuchBlock,
                 "block hash not found".to_string(),
             )),
-            Err(error) => {
-                let rpc_error = match error {
-                    EngineStateError::RootNotFound(_) => Error::new(ErrorCode::NoSuchStateRoot, ""),
-                    EngineStateError::WasmPreprocessing(error) => {
-                        Error::new(ErrorCode::InvalidDeploy, &format!("{}", error))
-                    }
-                    EngineStateError::InvalidDeployItemVariant(error) => {
-                        Error::new(ErrorCode::InvalidDeploy, &error)
-                    }
-                    EngineStateError::InvalidProtocolVersion(_) => Error::new(
-                        ErrorCode::InvalidDeploy,
-                        &format!("deploy used invalid protocol version {}", error),
-                    ),
-                    EngineStateError::Deploy => Error::new(ErrorCode::InvalidDeploy, ""),
-                    EngineStateError::Genesis(_)
-                    | EngineStateError::WasmSerialization(_)
-                    | EngineStateError::Exec(_)
-                    | EngineStateError::Storage(_)
-                    | EngineStateError::Authorization
-                    | EngineStateError::InsufficientPayment
-                    | EngineStateError::GasConversionOverflow
-                    | EngineStateError::Finalization
-                    | EngineStateError::Bytesrepr(_)
-                    | EngineStateError::Mint(_)
-                    | EngineStateError::InvalidKeyVariant
-                    | EngineStateError::ProtocolUpgrade(_)
-                    | EngineStateError::CommitError(_)
-                    | EngineStateError::MissingSystemContractRegistry
-                    | EngineStateError::MissingSystemContractHash(_)
-                    | EngineStateError::RuntimeStackOverflow
-                    | EngineStateError::FailedToGetWithdrawKeys
-                    | EngineStateError::FailedToGetStoredWithdraws
-                    | EngineStateError::FailedToGetWithdrawPurses
-                    | EngineStateError::FailedToRetrieveUnbondingDelay
-                    | EngineStateError::FailedToRetrieveEraId => {
-                        Error::new(ReservedErrorCode::InternalError, &format!("{}", error))
-                    }
-                    _ => Error::new(
-                        ReservedErrorCode::InternalError,
-                        &format!("Unhandled engine state error: {}", error),
-                    ),
-                };
-                Err(rpc_error)
+            Err(error) => Err(engine_state_error_to_rpc_error(error)),
+        }
+    }
+}
+
+/// Params for "speculative_exec_deploys" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct SpeculativeExecDeploysParams {
+    /// Block hash on top of which to execute the deploys.
+    pub block_identifier: Option<BlockIdentifier>,
+    /// Deploys to execute, in order.
+    pub deploys: Vec<Deploy>,
+    /// Values to write to a scratch copy of global state before executing the deploys.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub state_overrides: Vec<StateOverride>,
+    /// Block time to execute the deploys with, in place of that of the block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_time: Option<Timestamp>,
+    /// Era in which to execute the deploys, in place of that of the block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub era_id: Option<EraId>,
+}
+
+impl DocExample for SpeculativeExecDeploysParams {
+    fn doc_example() -> &'static Self {
+        &*SPECULATIVE_EXEC_DEPLOYS_PARAMS
+    }
+}
+
+/// The result of executing a single deploy of a "speculative_exec_deploys" RPC request.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct SpeculativeDeployExecutionResult {
+    /// Hash of the deploy.
+    pub deploy_hash: DeployHash,
+    /// Result of the execution.
+    pub execution_result: ExecutionResult,
+}
+
+/// Result for "speculative_exec_deploys" RPC response.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct SpeculativeExecDeploysResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// Hash of the block on top of which the deploys were executed.
+    pub block_hash: BlockHash,
+    /// Result of executing each deploy, in execution order.
+    pub execution_results: Vec<SpeculativeDeployExecutionResult>,
+    /// Combined effects of executing all of the deploys.
+    pub effect: ExecutionEffect,
+    /// Global state root hash after executing all of the deploys.  It is not committed to by any
+    /// block, nor stored by the node, so it can't be queried.
+    pub post_state_hash: Digest,
+}
+
+impl DocExample for SpeculativeExecDeploysResult {
+    fn doc_example() -> &'static Self {
+        &*SPECULATIVE_EXEC_DEPLOYS_RESULT
+    }
+}
+
+/// "speculative_exec_deploys" RPC
+pub struct SpeculativeExecDeploys {}
+
+#[async_trait]
+impl RpcWithParams for SpeculativeExecDeploys {
+    const METHOD: &'static str = "speculative_exec_deploys";
+    type RequestParams = SpeculativeExecDeploysParams;
+    type ResponseResult = SpeculativeExecDeploysResult;
+
+    async fn do_handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        api_version: ProtocolVersion,
+        params: Self::RequestParams,
+    ) -> Result<Self::ResponseResult, Error> {
+        let SpeculativeExecDeploysParams {
+            block_identifier: maybe_block_id,
+            deploys,
+            state_overrides,
+            block_time,
+            era_id,
+        } = params;
+
+        if deploys.is_empty() || deploys.len() > MAX_SPECULATIVE_EXEC_DEPLOYS {
+            return Err(Error::new(
+                ErrorCode::InvalidDeploy,
+                format!(
+                    "expected between 1 and {} deploys, got {}",
+                    MAX_SPECULATIVE_EXEC_DEPLOYS,
+                    deploys.len()
+                ),
+            ));
+        }
+        let overrides = parse_overrides(state_overrides, block_time, era_id)?;
+
+        // This RPC request is restricted by the block availability index.
+        let only_from_available_block_range = true;
+
+        let block = common::get_block(
+            maybe_block_id,
+            only_from_available_block_range,
+            effect_builder,
+        )
+        .await?;
+        let block_hash = *block.hash();
+        let result = effect_builder
+            .make_request(
+                |responder| RpcRequest::SpeculativeDeploysExecute {
+                    block: Box::new(block),
+                    deploys,
+                    overrides,
+                    responder,
+                },
+                QueueKind::Api,
+            )
+            .await;
+
+        match result {
+            Ok(SpeculativeExecutionResults {
+                execution_results,
+                effect,
+                post_state_hash,
+            }) => Ok(Self::ResponseResult {
+                api_version,
+                block_hash,
+                execution_results: execution_results
+                    .into_iter()
+                    .map(
+                        |(deploy_hash, execution_result)| SpeculativeDeployExecutionResult {
+                            deploy_hash,
+                            execution_result,
+                        },
+                    )
+                    .collect(),
+                effect,
+                post_state_hash,
+            }),
+            Err(error) => Err(engine_state_error_to_rpc_error(error)),
+        }
+    }
+}
+
+fn parse_overrides(
+    state_overrides: Vec<StateOverride>,
+    block_time: Option<Timestamp>,
+    era_id: Option<EraId>,
+) -> Result<SpeculativeExecutionOverrides, Error> {
+    Ok(SpeculativeExecutionOverrides {
+        state: state_overrides
+            .into_iter()
+            .map(StateOverride::parse)
+            .collect::<Result<_, _>>()?,
+        block_time,
+        era_id,
+    })
+}
+
+fn engine_state_error_to_rpc_error(error: EngineStateError) -> Error {
+    match error {
+        EngineStateError::RootNotFound(_) => Error::new(ErrorCode::NoSuchStateRoot, ""),
+        EngineStateError::WasmPreprocessing(error) => {
+            Error::new(ErrorCode::InvalidDeploy, &format!("{}", error))
+        }
+        EngineStateError::InvalidDeployItemVariant(error) => {
+            Error::new(ErrorCode::InvalidDeploy, &error)
+        }
+        EngineStateError::InvalidProtocolVersion(_) => Error::new(
+            ErrorCode::InvalidDeploy,
+            &format!("deploy used invalid protocol version {}", error),
+        ),
+        EngineStateError::Deploy => Error::new(ErrorCode::InvalidDeploy, ""),
+        EngineStateError::Genesis(_)
+        | EngineStateError::WasmSerialization(_)
+        | EngineStateError::Exec(_)
+        | EngineStateError::Storage(_)
+        | EngineStateError::Authorization
+        | EngineStateError::InsufficientPayment
+        | EngineStateError::GasConversionOverflow
+        | EngineStateError::Finalization
+        | EngineStateError::Bytesrepr(_)
+        | EngineStateError::Mint(_)
+        | EngineStateError::InvalidKeyVariant
+        | EngineStateError::ProtocolUpgrade(_)
+        | EngineStateError::CommitError(_)
+        | EngineStateError::MissingSystemContractRegistry
+        | EngineStateError::MissingSystemContractHash(_)
+        | EngineStateError::RuntimeStackOverflow
+        | EngineStateError::FailedToGetWithdrawKeys
+        | EngineStateError::FailedToGetStoredWithdraws
+        | EngineStateError::FailedToGetWithdrawPurses
+        | EngineStateError::FailedToRetrieveUnbondingDelay
+        | EngineStateError::FailedToRetrieveEraId => {
+            Error::new(ReservedErrorCode::InternalError, &format!("{}", error))
+        }
+        _ => Error::new(
+            ReservedErrorCode::InternalError,
+            &format!("Unhandled engine state error: {}", error),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use derive_more::From;
+    use rand::Rng;
+
+    use casper_types::{bytesrepr::ToBytes, testing::TestRng, CLValue};
+
+    use super::*;
+    use crate::{
+        components::rpc_server::Event as RpcServerEvent,
+        effect::{
+            announcements::RpcServerAnnouncement,
+            requests::{
+                ChainspecLoaderRequest, ConsensusRequest, ContractRuntimeRequest, MetricsRequest,
+                NetworkInfoRequest, NodeStateRequest, StorageRequest,
+            },
+        },
+        reactor::{EventQueueHandle, Scheduler},
+        types::{BlockSignatures, BlockWithMetadata},
+        utils,
+    };
+
+    #[derive(Debug, From)]
+    enum ReactorEvent {
+        #[from]
+        RpcServer(RpcServerEvent),
+        #[from]
+        Rpc(RpcRequest),
+        #[from]
+        RpcServerAnnouncement(RpcServerAnnouncement),
+        #[from]
+        ChainspecLoader(ChainspecLoaderRequest),
+        #[from]
+        ContractRuntime(ContractRuntimeRequest),
+        #[from]
+        Consensus(ConsensusRequest),
+        #[from]
+        Metrics(MetricsRequest),
+        #[from]
+        NetworkInfo(NetworkInfoRequest),
+        #[from]
+        Storage(StorageRequest),
+        #[from]
+        NodeState(NodeStateRequest),
+    }
+
+    struct MockReactor {
+        scheduler: &'static Scheduler<ReactorEvent>,
+    }
+
+    impl MockReactor {
+        fn new() -> Self {
+            MockReactor {
+                scheduler: utils::leak(Scheduler::new(QueueKind::weights())),
+            }
+        }
+
+        fn effect_builder(&self) -> EffectBuilder<ReactorEvent> {
+            EffectBuilder::new(EventQueueHandle::without_shutdown(self.scheduler))
+        }
+
+        async fn expect_rpc_request(&self) -> RpcRequest {
+            let ((_ancestor, reactor_event), _) = self.scheduler.pop().await;
+            if let ReactorEvent::Rpc(request) = reactor_event {
+                request
+            } else {
+                panic!("unexpected event: {:?}", reactor_event);
+            }
+        }
+
+        /// Responds to a request for the highest block with `block`.
+        async fn expect_get_block(&self, block: Block) {
+            match self.expect_rpc_request().await {
+                RpcRequest::GetBlock {
+                    maybe_id: None,
+                    only_from_available_block_range: true,
+                    responder,
+                } => {
+                    let block_signatures =
+                        BlockSignatures::new(*block.hash(), block.header().era_id());
+                    responder
+                        .respond(Some(BlockWithMetadata {
+                            block,
+                            block_signatures,
+                        }))
+                        .await
+                }
+                request => panic!("unexpected request: {:?}", request),
             }
         }
     }
+
+    fn new_params(deploys: Vec<Deploy>) -> SpeculativeExecDeploysParams {
+        SpeculativeExecDeploysParams {
+            block_identifier: None,
+            deploys,
+            state_overrides: Vec::new(),
+            block_time: None,
+            era_id: None,
+        }
+    }
+
+    fn error_code(error: &Error) -> i64 {
+        let json = serde_json::to_value(error).expect("should serialize");
+        json["code"].as_i64().expect("should have a code")
+    }
+
+    #[tokio::test]
+    async fn should_execute_deploys_with_overrides_on_block() {
+        let mut rng = TestRng::new();
+        let reactor = MockReactor::new();
+        let block = Block::random(&mut rng);
+        let deploys = vec![Deploy::random(&mut rng), Deploy::random(&mut rng)];
+        let key = Key::Hash(rng.gen());
+        let stored_value = StoredValue::CLValue(CLValue::from_t(rng.gen::<u64>()).unwrap());
+        let block_time = Timestamp::from(u64::from(rng.gen::<u32>()));
+        let era_id = EraId::new(u64::from(rng.gen::<u32>()));
+        let mut params = new_params(deploys.clone());
+        params.state_overrides = vec![StateOverride {
+            key: key.to_formatted_string(),
+            stored_value: base16::encode_lower(&stored_value.to_bytes().unwrap()),
+        }];
+        params.block_time = Some(block_time);
+        params.era_id = Some(era_id);
+        let results = SpeculativeExecutionResults {
+            execution_results: deploys
+                .iter()
+                .map(|deploy| (*deploy.id(), rng.gen()))
+                .collect(),
+            effect: ExecutionEffect::default(),
+            post_state_hash: Digest::hash(rng.gen::<[u8; 32]>()),
+        };
+
+        let handle_request = SpeculativeExecDeploys::do_handle_request(
+            reactor.effect_builder(),
+            ProtocolVersion::V1_0_0,
+            params,
+        );
+        let expected_block = block.clone();
+        let expected_results = results.clone();
+        let respond = async {
+            reactor.expect_get_block(block).await;
+            match reactor.expect_rpc_request().await {
+                RpcRequest::SpeculativeDeploysExecute {
+                    block,
+                    deploys: requested_deploys,
+                    overrides,
+                    responder,
+                } => {
+                    assert_eq!(*block, expected_block);
+                    assert_eq!(requested_deploys, deploys);
+                    assert_eq!(overrides.state, vec![(key, stored_value)]);
+                    assert_eq!(overrides.block_time, Some(block_time));
+                    assert_eq!(overrides.era_id, Some(era_id));
+                    responder.respond(Ok(expected_results)).await
+                }
+                request => panic!("unexpected request: {:?}", request),
+            }
+        };
+        let (result, ()) = tokio::join!(handle_request, respond);
+
+        let result = result.expect("should execute");
+        assert_eq!(result.block_hash, *expected_block.hash());
+        assert_eq!(
+            result
+                .execution_results
+                .into_iter()
+                .map(|result| (result.deploy_hash, result.execution_result))
+                .collect::<Vec<_>>(),
+            results.execution_results
+        );
+        assert_eq!(result.post_state_hash, results.post_state_hash);
+    }
+
+    #[tokio::test]
+    async fn should_reject_too_few_or_too_many_deploys() {
+        let mut rng = TestRng::new();
+        let reactor = MockReactor::new();
+
+        for deploy_count in [0, MAX_SPECULATIVE_EXEC_DEPLOYS + 1] {
+            let deploys = (0..deploy_count)
+                .map(|_| Deploy::random(&mut rng))
+                .collect();
+            let error = SpeculativeExecDeploys::do_handle_request(
+                reactor.effect_builder(),
+                ProtocolVersion::V1_0_0,
+                new_params(deploys),
+            )
+            .await
+            .expect_err("should reject deploys");
+            assert_eq!(error_code(&error), ErrorCode::InvalidDeploy as i64);
+        }
+    }
+
+    #[tokio::test]
+    async fn should_reject_invalid_state_override() {
+        let mut rng = TestRng::new();
+        let reactor = MockReactor::new();
+        let mut params = new_params(vec![Deploy::random(&mut rng)]);
+        params.state_overrides = vec![StateOverride {
+            key: Key::Hash(rng.gen()).to_formatted_string(),
+            stored_value: "not a stored value".to_string(),
+        }];
+
+        let error = SpeculativeExecDeploys::do_handle_request(
+            reactor.effect_builder(),
+            ProtocolVersion::V1_0_0,
+            params,
+        )
+        .await
+        .expect_err("should reject state override");
+        assert_eq!(error_code(&error), ErrorCode::InvalidStateOverride as i64);
+    }
 }
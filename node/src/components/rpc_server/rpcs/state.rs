@@ -3,7 +3,7 @@
 // TODO - remove once schemars stops causing warning.
 #![allow(clippy::field_reassign_with_default)]
 
-use std::str;
+use std::{convert::TryFrom, str};
 
 use async_trait::async_trait;
 use once_cell::sync::Lazy;
@@ -12,7 +12,10 @@ use serde::{Deserialize, Serialize};
 use tracing::{error, info, warn};
 
 use casper_execution_engine::{
-    core::engine_state::{BalanceResult, GetBidsResult, QueryResult},
+    core::engine_state::{
+        keys_by_prefix::{self, GetKeysByPrefixRequest, KeyPrefix},
+        BalanceResult, GetBidsResult, QueryResult,
+    },
     storage::trie::merkle_proof::TrieMerkleProof,
 };
 use casper_hashing::Digest;
@@ -20,8 +23,9 @@ use casper_json_rpc::ReservedErrorCode;
 use casper_types::{
     account::AccountHash,
     bytesrepr::{Bytes, ToBytes},
-    CLValue, Key, ProtocolVersion, PublicKey, SecretKey, StoredValue as DomainStoredValue, URef,
-    U512,
+    contracts::NamedKeys,
+    CLValue, ContractHash, Key, KeyTag, NamedKey, ProtocolVersion, PublicKey, SecretKey,
+    StoredValue as DomainStoredValue, URef, U512,
 };
 
 use crate::{
@@ -34,11 +38,14 @@ use crate::{
         Error, ErrorCode, ReactorEventT, RpcRequest, RpcWithOptionalParams, RpcWithParams,
     },
     types::{
-        json_compatibility::{Account as JsonAccount, AuctionState, StoredValue},
-        Block, BlockHash, JsonBlockHeader,
+        json_compatibility::{self, Account as JsonAccount, AuctionState, StoredValue},
+        Block, BlockHash, BlockHeader, JsonBlockHeader,
     },
 };
 
+/// The maximum number of entries returned by a single "state_get_keys_by_prefix" request.
+const MAX_KEYS_BY_PREFIX_PAGE_SIZE: u64 = 100;
+
 static GET_ITEM_PARAMS: Lazy<GetItemParams> = Lazy::new(|| GetItemParams {
     state_root_hash: *Block::doc_example().header().state_root_hash(),
     key: "deploy-af684263911154d26fa05be9963171802801a0b6aff8f199b7391eacb8edc9e1".to_string(),
@@ -127,6 +134,66 @@ static QUERY_BALANCE_RESULT: Lazy<QueryBalanceResult> = Lazy::new(|| QueryBalanc
     api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
     balance: U512::from(123_456),
 });
+static QUERY_BALANCE_DIFF_PARAMS: Lazy<QueryBalanceDiffParams> =
+    Lazy::new(|| QueryBalanceDiffParams {
+        from_state_identifier: GlobalStateIdentifier::BlockHeight(1),
+        to_state_identifier: GlobalStateIdentifier::BlockHash(*Block::doc_example().hash()),
+        purse_identifier: PurseIdentifier::MainPurseUnderAccountHash(AccountHash::new([9u8; 32])),
+    });
+static QUERY_BALANCE_DIFF_RESULT: Lazy<QueryBalanceDiffResult> =
+    Lazy::new(|| QueryBalanceDiffResult {
+        api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+        from_balance: U512::from(100_000),
+        to_balance: U512::from(123_456),
+        balance_change: "23456".to_string(),
+    });
+static QUERY_NAMED_KEYS_DIFF_PARAMS: Lazy<QueryNamedKeysDiffParams> =
+    Lazy::new(|| QueryNamedKeysDiffParams {
+        from_state_identifier: GlobalStateIdentifier::BlockHeight(1),
+        to_state_identifier: GlobalStateIdentifier::BlockHash(*Block::doc_example().hash()),
+        named_keys_identifier: NamedKeysIdentifier::AccountUnderAccountHash(AccountHash::new(
+            [9u8; 32],
+        )),
+    });
+static QUERY_NAMED_KEYS_DIFF_RESULT: Lazy<QueryNamedKeysDiffResult> =
+    Lazy::new(|| QueryNamedKeysDiffResult {
+        api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+        added: vec![NamedKey {
+            name: "counter".to_string(),
+            key: "uref-09480c3248ef76b603d386f3f4f8a5f87f597d4eaffd475433f861af187ab5db-007"
+                .to_string(),
+        }],
+        removed: vec![],
+        changed: vec![NamedKeyChange {
+            name: "owner".to_string(),
+            from_key:
+                "account-hash-0909090909090909090909090909090909090909090909090909090909090909"
+                    .to_string(),
+            to_key: "account-hash-e94daaff79c2ab8d9c31d9c3058d7d0a0dd31204a5638dc1451fa67b2e3fb88c"
+                .to_string(),
+        }],
+    });
+static GET_KEYS_BY_PREFIX_PARAMS: Lazy<GetKeysByPrefixParams> =
+    Lazy::new(|| GetKeysByPrefixParams {
+        state_identifier: Some(GlobalStateIdentifier::BlockHash(
+            *Block::doc_example().hash(),
+        )),
+        key_prefix: KeyPrefixIdentifier::AllBids,
+        cursor: None,
+        limit: Some(1),
+        include_values: false,
+    });
+static GET_KEYS_BY_PREFIX_RESULT: Lazy<GetKeysByPrefixResult> = Lazy::new(|| {
+    let key = "bid-e94daaff79c2ab8d9c31d9c3058d7d0a0dd31204a5638dc1451fa67b2e3fb88c";
+    GetKeysByPrefixResult {
+        api_version: DOCS_EXAMPLE_PROTOCOL_VERSION,
+        entries: vec![GlobalStateEntry {
+            key: key.to_string(),
+            stored_value: None,
+        }],
+        next_cursor: Some(key.to_string()),
+    }
+});
 
 /// Params for "state_get_item" RPC request.
 #[derive(Serialize, Deserialize, Debug, JsonSchema)]
@@ -927,63 +994,232 @@ impl RpcWithParams for QueryBalance {
             }
         };
 
-        let purse_uref = match params.purse_identifier {
-            PurseIdentifier::MainPurseUnderPublicKey(account_public_key) => {
-                let account = get_account(
-                    effect_builder,
-                    state_root_hash,
-                    account_public_key.to_account_hash(),
-                )
+        let purse_uref =
+            get_purse_uref(effect_builder, state_root_hash, params.purse_identifier).await?;
+        let balance_value = get_balance(effect_builder, state_root_hash, purse_uref).await?;
+
+        let result = Self::ResponseResult {
+            api_version,
+            balance: balance_value,
+        };
+        Ok(result)
+    }
+}
+
+/// Params for "query_balance_diff" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct QueryBalanceDiffParams {
+    /// The state identifier of the earlier global state.
+    pub from_state_identifier: GlobalStateIdentifier,
+    /// The state identifier of the later global state.
+    pub to_state_identifier: GlobalStateIdentifier,
+    /// The identifier to obtain the purse, which is resolved in each global state.  A purse, or an
+    /// account, missing from a global state is treated as having a zero balance there.
+    pub purse_identifier: PurseIdentifier,
+}
+
+impl DocExample for QueryBalanceDiffParams {
+    fn doc_example() -> &'static Self {
+        &*QUERY_BALANCE_DIFF_PARAMS
+    }
+}
+
+/// Result for "query_balance_diff" RPC response.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct QueryBalanceDiffResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The balance in the earlier global state, represented in motes.
+    pub from_balance: U512,
+    /// The balance in the later global state, represented in motes.
+    pub to_balance: U512,
+    /// The signed decimal difference of the later and the earlier balances, in motes.
+    pub balance_change: String,
+}
+
+impl DocExample for QueryBalanceDiffResult {
+    fn doc_example() -> &'static Self {
+        &*QUERY_BALANCE_DIFF_RESULT
+    }
+}
+
+/// "query_balance_diff" RPC.
+pub struct QueryBalanceDiff {}
+
+#[async_trait]
+impl RpcWithParams for QueryBalanceDiff {
+    const METHOD: &'static str = "query_balance_diff";
+    type RequestParams = QueryBalanceDiffParams;
+    type ResponseResult = QueryBalanceDiffResult;
+
+    async fn do_handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        api_version: ProtocolVersion,
+        params: Self::RequestParams,
+    ) -> Result<Self::ResponseResult, Error> {
+        let (from_state_root_hash, _) =
+            get_state_root_hash_and_optional_header(effect_builder, params.from_state_identifier)
                 .await?;
-                account.main_purse()
-            }
-            PurseIdentifier::MainPurseUnderAccountHash(account_hash) => {
-                let account = get_account(effect_builder, state_root_hash, account_hash).await?;
-                account.main_purse()
-            }
-            PurseIdentifier::PurseUref(purse_uref) => purse_uref,
+        let (to_state_root_hash, _) =
+            get_state_root_hash_and_optional_header(effect_builder, params.to_state_identifier)
+                .await?;
+
+        // The purse is resolved in each state, as e.g. the account may not exist in the earlier one.
+        let from_balance = get_balance_or_zero(
+            effect_builder,
+            from_state_root_hash,
+            params.purse_identifier.clone(),
+        )
+        .await?;
+        let to_balance =
+            get_balance_or_zero(effect_builder, to_state_root_hash, params.purse_identifier)
+                .await?;
+
+        let balance_change = if to_balance >= from_balance {
+            (to_balance - from_balance).to_string()
+        } else {
+            format!("-{}", from_balance - to_balance)
         };
 
-        // Get the balance.
-        let balance_result = effect_builder
-            .make_request(
-                |responder| RpcRequest::GetBalance {
-                    state_root_hash,
-                    purse_uref,
-                    responder,
-                },
-                QueueKind::Api,
-            )
-            .await;
+        let result = Self::ResponseResult {
+            api_version,
+            from_balance,
+            to_balance,
+            balance_change,
+        };
+        Ok(result)
+    }
+}
 
-        let balance_value = match balance_result {
-            Ok(BalanceResult::Success { motes, .. }) => motes,
-            Ok(BalanceResult::RootNotFound) => {
-                info!(
-                    %state_root_hash,
-                    %purse_uref,
-                    "query-balance failed: root not found"
-                );
-                return Err(Error::new(
-                    ErrorCode::FailedToGetBalance,
-                    format!(
-                        "root hash {} not found when querying for purse {}",
-                        state_root_hash, purse_uref
-                    ),
-                ));
+/// Identifier of an account or contract holding named keys.
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+#[serde(deny_unknown_fields, rename_all = "snake_case")]
+pub enum NamedKeysIdentifier {
+    /// The named keys of the account identified by this public key.
+    AccountUnderPublicKey(PublicKey),
+    /// The named keys of the account identified by this account hash.
+    AccountUnderAccountHash(AccountHash),
+    /// The named keys of the contract identified by this contract hash.
+    Contract(ContractHash),
+}
+
+impl NamedKeysIdentifier {
+    fn key(&self) -> Key {
+        match self {
+            NamedKeysIdentifier::AccountUnderPublicKey(public_key) => {
+                Key::Account(public_key.to_account_hash())
             }
-            Err(error) => {
-                info!("query-balance failed to execute: {}", error);
-                return Err(Error::new(
-                    ErrorCode::GetBalanceFailedToExecute,
-                    error.to_string(),
-                ));
+            NamedKeysIdentifier::AccountUnderAccountHash(account_hash) => {
+                Key::Account(*account_hash)
             }
-        };
+            NamedKeysIdentifier::Contract(contract_hash) => Key::Hash(contract_hash.value()),
+        }
+    }
+}
+
+/// Params for "query_named_keys_diff" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct QueryNamedKeysDiffParams {
+    /// The state identifier of the earlier global state.
+    pub from_state_identifier: GlobalStateIdentifier,
+    /// The state identifier of the later global state.
+    pub to_state_identifier: GlobalStateIdentifier,
+    /// The identifier of the account or contract.  An account or contract missing from a global
+    /// state is treated as having no named keys there.
+    pub named_keys_identifier: NamedKeysIdentifier,
+}
+
+impl DocExample for QueryNamedKeysDiffParams {
+    fn doc_example() -> &'static Self {
+        &*QUERY_NAMED_KEYS_DIFF_PARAMS
+    }
+}
+
+/// A named key whose value differs between two global states.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct NamedKeyChange {
+    /// The name of the entry.
+    pub name: String,
+    /// The value of the entry in the earlier global state, as a formatted string.
+    pub from_key: String,
+    /// The value of the entry in the later global state, as a formatted string.
+    pub to_key: String,
+}
+
+/// Result for "query_named_keys_diff" RPC response.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct QueryNamedKeysDiffResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The named keys present only in the later global state.
+    pub added: Vec<NamedKey>,
+    /// The named keys present only in the earlier global state.
+    pub removed: Vec<NamedKey>,
+    /// The named keys present in both global states with differing values.
+    pub changed: Vec<NamedKeyChange>,
+}
+
+impl DocExample for QueryNamedKeysDiffResult {
+    fn doc_example() -> &'static Self {
+        &*QUERY_NAMED_KEYS_DIFF_RESULT
+    }
+}
+
+/// "query_named_keys_diff" RPC.
+pub struct QueryNamedKeysDiff {}
+
+#[async_trait]
+impl RpcWithParams for QueryNamedKeysDiff {
+    const METHOD: &'static str = "query_named_keys_diff";
+    type RequestParams = QueryNamedKeysDiffParams;
+    type ResponseResult = QueryNamedKeysDiffResult;
+
+    async fn do_handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        api_version: ProtocolVersion,
+        params: Self::RequestParams,
+    ) -> Result<Self::ResponseResult, Error> {
+        let (from_state_root_hash, _) =
+            get_state_root_hash_and_optional_header(effect_builder, params.from_state_identifier)
+                .await?;
+        let (to_state_root_hash, _) =
+            get_state_root_hash_and_optional_header(effect_builder, params.to_state_identifier)
+                .await?;
+
+        let key = params.named_keys_identifier.key();
+        let mut from_named_keys = get_named_keys(effect_builder, from_state_root_hash, key).await?;
+        let to_named_keys = get_named_keys(effect_builder, to_state_root_hash, key).await?;
+
+        let mut added = vec![];
+        let mut changed = vec![];
+        for (name, to_key) in to_named_keys {
+            match from_named_keys.remove(&name) {
+                None => added.push(NamedKey {
+                    name,
+                    key: to_key.to_formatted_string(),
+                }),
+                Some(from_key) if from_key != to_key => changed.push(NamedKeyChange {
+                    name,
+                    from_key: from_key.to_formatted_string(),
+                    to_key: to_key.to_formatted_string(),
+                }),
+                Some(_) => (),
+            }
+        }
+        let removed = json_compatibility::vectorize(&from_named_keys);
 
         let result = Self::ResponseResult {
             api_version,
-            balance: balance_value,
+            added,
+            removed,
+            changed,
         };
         Ok(result)
     }
@@ -1058,6 +1294,184 @@ impl RpcWithParams for GetTrie {
     }
 }
 
+/// Identifier of a set of global state entries sharing a key prefix.
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+#[serde(deny_unknown_fields, rename_all = "snake_case")]
+pub enum KeyPrefixIdentifier {
+    /// All `Key::Bid` entries.
+    AllBids,
+    /// All `Key::Withdraw` entries.
+    AllWithdraws,
+    /// All `Key::Unbond` entries.
+    AllUnbonds,
+}
+
+impl From<KeyPrefixIdentifier> for KeyPrefix {
+    fn from(key_prefix: KeyPrefixIdentifier) -> Self {
+        match key_prefix {
+            KeyPrefixIdentifier::AllBids => KeyPrefix::Tag(KeyTag::Bid),
+            KeyPrefixIdentifier::AllWithdraws => KeyPrefix::Tag(KeyTag::Withdraw),
+            KeyPrefixIdentifier::AllUnbonds => KeyPrefix::Tag(KeyTag::Unbond),
+        }
+    }
+}
+
+/// Params for "state_get_keys_by_prefix" RPC request.
+#[derive(Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetKeysByPrefixParams {
+    /// The state identifier used for the query, if none is passed
+    /// the tip of the chain will be used.
+    pub state_identifier: Option<GlobalStateIdentifier>,
+    /// The identifier of the entries to be listed.
+    pub key_prefix: KeyPrefixIdentifier,
+    /// The `next_cursor` returned by the previous request, if continuing from a previous page.
+    pub cursor: Option<String>,
+    /// The maximum number of entries to return.  If omitted, zero or greater than 100, at most 100
+    /// entries are returned.
+    pub limit: Option<u64>,
+    /// Whether to return the stored value of each entry along with its key.  The values are not
+    /// read from global state otherwise.
+    #[serde(default)]
+    pub include_values: bool,
+}
+
+impl DocExample for GetKeysByPrefixParams {
+    fn doc_example() -> &'static Self {
+        &*GET_KEYS_BY_PREFIX_PARAMS
+    }
+}
+
+/// An entry of global state.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GlobalStateEntry {
+    /// `casper_types::Key` as formatted string.
+    pub key: String,
+    /// The stored value, if requested.
+    pub stored_value: Option<StoredValue>,
+}
+
+/// Result for "state_get_keys_by_prefix" RPC response.
+#[derive(PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct GetKeysByPrefixResult {
+    /// The RPC API version.
+    #[schemars(with = "String")]
+    pub api_version: ProtocolVersion,
+    /// The entries found, in ascending order of their serialized keys.
+    pub entries: Vec<GlobalStateEntry>,
+    /// The cursor to pass to retrieve the next page of entries, or `None` if there are no more.
+    pub next_cursor: Option<String>,
+}
+
+impl DocExample for GetKeysByPrefixResult {
+    fn doc_example() -> &'static Self {
+        &*GET_KEYS_BY_PREFIX_RESULT
+    }
+}
+
+/// "state_get_keys_by_prefix" RPC.
+pub struct GetKeysByPrefix {}
+
+#[async_trait]
+impl RpcWithParams for GetKeysByPrefix {
+    const METHOD: &'static str = "state_get_keys_by_prefix";
+    type RequestParams = GetKeysByPrefixParams;
+    type ResponseResult = GetKeysByPrefixResult;
+
+    async fn do_handle_request<REv: ReactorEventT>(
+        effect_builder: EffectBuilder<REv>,
+        api_version: ProtocolVersion,
+        params: Self::RequestParams,
+    ) -> Result<Self::ResponseResult, Error> {
+        let start_after = match params.cursor {
+            Some(cursor) => match Key::from_formatted_str(&cursor) {
+                Ok(key) => Some(key),
+                Err(error) => {
+                    let message = format!("failed to parse cursor: {}", error);
+                    info!("{}", message);
+                    return Err(Error::new(ReservedErrorCode::InvalidParams, message));
+                }
+            },
+            None => None,
+        };
+        let limit = params
+            .limit
+            .filter(|limit| *limit > 0)
+            .unwrap_or(MAX_KEYS_BY_PREFIX_PAGE_SIZE)
+            .min(MAX_KEYS_BY_PREFIX_PAGE_SIZE) as usize;
+
+        let state_root_hash = match params.state_identifier {
+            None => match effect_builder.get_highest_block_header_from_storage().await {
+                None => {
+                    return Err(Error::new(
+                        ErrorCode::NoSuchBlock,
+                        "get-keys-by-prefix failed to retrieve highest block header",
+                    ))
+                }
+                Some(block_header) => *block_header.state_root_hash(),
+            },
+            Some(state_identifier) => {
+                let (state_root_hash, _) =
+                    get_state_root_hash_and_optional_header(effect_builder, state_identifier)
+                        .await?;
+                state_root_hash
+            }
+        };
+
+        let request = GetKeysByPrefixRequest::new(
+            state_root_hash,
+            params.key_prefix.into(),
+            start_after,
+            limit,
+            params.include_values,
+        );
+        let (entries, continue_after) = match effect_builder.get_keys_by_prefix(request).await {
+            Ok(keys_by_prefix::GetKeysByPrefixResult::Success {
+                entries,
+                continue_after,
+            }) => (entries, continue_after),
+            Ok(keys_by_prefix::GetKeysByPrefixResult::RootNotFound) => {
+                info!(%state_root_hash, "get-keys-by-prefix failed: root not found");
+                return Err(state_root_not_found_error(effect_builder, state_root_hash).await);
+            }
+            Err(error) => {
+                info!(?error, "get-keys-by-prefix failed to execute");
+                return Err(Error::new(
+                    ErrorCode::QueryFailedToExecute,
+                    format!("{:?}", error),
+                ));
+            }
+        };
+
+        let mut json_entries = Vec::with_capacity(entries.len());
+        for (key, maybe_value) in entries {
+            let stored_value = match maybe_value.map(StoredValue::try_from).transpose() {
+                Ok(maybe_value_compat) => maybe_value_compat,
+                Err(error) => {
+                    warn!(?error, "failed to encode stored value");
+                    return Err(Error::new(
+                        ReservedErrorCode::InternalError,
+                        format!("failed to encode stored value: {}", error),
+                    ));
+                }
+            };
+            json_entries.push(GlobalStateEntry {
+                key: key.to_formatted_string(),
+                stored_value,
+            });
+        }
+
+        let result = Self::ResponseResult {
+            api_version,
+            entries: json_entries,
+            next_cursor: continue_after.map(|key| key.to_formatted_string()),
+        };
+        Ok(result)
+    }
+}
+
 type QuerySuccess = (
     DomainStoredValue,
     Vec<TrieMerkleProof<Key, DomainStoredValue>>,
@@ -1089,13 +1503,7 @@ pub(super) async fn run_query<REv: ReactorEventT>(
         Ok(QueryResult::Success { value, proofs }) => Ok((*value, proofs)),
         Ok(QueryResult::RootNotFound) => {
             info!("query failed: root not found");
-            let error = common::missing_block_or_state_root_error(
-                effect_builder,
-                ErrorCode::NoSuchStateRoot,
-                format!("failed to get state root at {:?}", state_root_hash),
-            )
-            .await;
-            Err(error)
+            Err(state_root_not_found_error(effect_builder, state_root_hash).await)
         }
         Ok(query_result) => {
             info!(?query_result, "query failed");
@@ -1114,6 +1522,82 @@ pub(super) async fn run_query<REv: ReactorEventT>(
     }
 }
 
+/// Runs a global state query and returns the domain stored value, or `None` if there is no value
+/// under `base_key`.
+async fn run_optional_query<REv: ReactorEventT>(
+    effect_builder: EffectBuilder<REv>,
+    state_root_hash: Digest,
+    base_key: Key,
+) -> Result<Option<DomainStoredValue>, Error> {
+    let query_result = effect_builder
+        .make_request(
+            |responder| RpcRequest::QueryGlobalState {
+                state_root_hash,
+                base_key,
+                path: vec![],
+                responder,
+            },
+            QueueKind::Api,
+        )
+        .await;
+
+    match query_result {
+        Ok(QueryResult::Success { value, .. }) => Ok(Some(*value)),
+        Ok(QueryResult::ValueNotFound(_)) => Ok(None),
+        Ok(QueryResult::RootNotFound) => {
+            info!("query failed: root not found");
+            Err(state_root_not_found_error(effect_builder, state_root_hash).await)
+        }
+        Ok(query_result) => {
+            info!(?query_result, "query failed");
+            Err(Error::new(
+                ErrorCode::QueryFailed,
+                format!("{:?}", query_result),
+            ))
+        }
+        Err(error) => {
+            info!(?error, "query failed to execute");
+            Err(Error::new(
+                ErrorCode::QueryFailedToExecute,
+                format!("{:?}", error),
+            ))
+        }
+    }
+}
+
+/// Returns the error to report when `state_root_hash` is not present in global state, which is
+/// `StateRootPruned` only if it is the state root of a block whose global state has been pruned.
+async fn state_root_not_found_error<REv: ReactorEventT>(
+    effect_builder: EffectBuilder<REv>,
+    state_root_hash: Digest,
+) -> Error {
+    match effect_builder
+        .get_pruned_state_root_height_from_storage(state_root_hash)
+        .await
+    {
+        Some(pruned_block_height) => {
+            common::missing_block_or_state_root_error(
+                effect_builder,
+                ErrorCode::StateRootPruned,
+                format!(
+                    "failed to get state root at {:?}; global state of the block at height {} \
+                    has been pruned",
+                    state_root_hash, pruned_block_height
+                ),
+            )
+            .await
+        }
+        None => {
+            common::missing_block_or_state_root_error(
+                effect_builder,
+                ErrorCode::NoSuchStateRoot,
+                format!("failed to get state root at {:?}", state_root_hash),
+            )
+            .await
+        }
+    }
+}
+
 async fn get_account<REv: ReactorEventT>(
     effect_builder: EffectBuilder<REv>,
     state_root_hash: Digest,
@@ -1136,6 +1620,174 @@ async fn get_account<REv: ReactorEventT>(
     }
 }
 
+/// Returns the named keys of the account or contract stored under `key` at `state_root_hash`, or
+/// no named keys if nothing is stored under `key`.
+async fn get_named_keys<REv: ReactorEventT>(
+    effect_builder: EffectBuilder<REv>,
+    state_root_hash: Digest,
+    key: Key,
+) -> Result<NamedKeys, Error> {
+    match run_optional_query(effect_builder, state_root_hash, key).await? {
+        None => Ok(NamedKeys::new()),
+        Some(DomainStoredValue::Account(account)) => Ok(account.named_keys().clone()),
+        Some(DomainStoredValue::Contract(contract)) => Ok(contract.named_keys().clone()),
+        Some(stored_value) => {
+            let error_msg = format!("no account or contract under {}", key);
+            info!(?stored_value, "{}", error_msg);
+            Err(Error::new(ErrorCode::QueryFailed, error_msg))
+        }
+    }
+}
+
+/// Returns the purse identified by `purse_identifier` at `state_root_hash`.
+async fn get_purse_uref<REv: ReactorEventT>(
+    effect_builder: EffectBuilder<REv>,
+    state_root_hash: Digest,
+    purse_identifier: PurseIdentifier,
+) -> Result<URef, Error> {
+    let purse_uref = match purse_identifier {
+        PurseIdentifier::MainPurseUnderPublicKey(account_public_key) => {
+            let account = get_account(
+                effect_builder,
+                state_root_hash,
+                account_public_key.to_account_hash(),
+            )
+            .await?;
+            account.main_purse()
+        }
+        PurseIdentifier::MainPurseUnderAccountHash(account_hash) => {
+            let account = get_account(effect_builder, state_root_hash, account_hash).await?;
+            account.main_purse()
+        }
+        PurseIdentifier::PurseUref(purse_uref) => purse_uref,
+    };
+    Ok(purse_uref)
+}
+
+/// Returns the balance at `state_root_hash` of the purse identified by `purse_identifier`, or zero
+/// if the purse, or the account owning it, doesn't exist there.
+async fn get_balance_or_zero<REv: ReactorEventT>(
+    effect_builder: EffectBuilder<REv>,
+    state_root_hash: Digest,
+    purse_identifier: PurseIdentifier,
+) -> Result<U512, Error> {
+    let account_hash = match purse_identifier {
+        PurseIdentifier::MainPurseUnderPublicKey(account_public_key) => {
+            account_public_key.to_account_hash()
+        }
+        PurseIdentifier::MainPurseUnderAccountHash(account_hash) => account_hash,
+        PurseIdentifier::PurseUref(purse_uref) => {
+            return get_optional_balance(effect_builder, state_root_hash, purse_uref).await;
+        }
+    };
+    let account_key = Key::Account(account_hash);
+    match run_optional_query(effect_builder, state_root_hash, account_key).await? {
+        None => Ok(U512::zero()),
+        Some(DomainStoredValue::Account(account)) => {
+            get_optional_balance(effect_builder, state_root_hash, account.main_purse()).await
+        }
+        Some(stored_value) => {
+            let error_msg = format!("failed to get account {}", account_hash);
+            info!(?stored_value, "{}", error_msg);
+            Err(Error::new(ErrorCode::NoSuchAccount, error_msg))
+        }
+    }
+}
+
+/// Returns the balance of `purse_uref` at `state_root_hash`, or zero if the purse doesn't exist
+/// there.
+async fn get_optional_balance<REv: ReactorEventT>(
+    effect_builder: EffectBuilder<REv>,
+    state_root_hash: Digest,
+    purse_uref: URef,
+) -> Result<U512, Error> {
+    let balance_key = Key::Balance(purse_uref.addr());
+    match run_optional_query(effect_builder, state_root_hash, balance_key).await? {
+        None => Ok(U512::zero()),
+        Some(DomainStoredValue::CLValue(cl_value)) => cl_value.into_t().map_err(|error| {
+            let error_msg = format!("failed to parse balance of purse {}", purse_uref);
+            info!(?error, "{}", error_msg);
+            Error::new(ErrorCode::FailedToGetBalance, error_msg)
+        }),
+        Some(stored_value) => {
+            let error_msg = format!("no balance under purse {}", purse_uref);
+            info!(?stored_value, "{}", error_msg);
+            Err(Error::new(ErrorCode::FailedToGetBalance, error_msg))
+        }
+    }
+}
+
+/// Returns the balance of `purse_uref` at `state_root_hash`.
+async fn get_balance<REv: ReactorEventT>(
+    effect_builder: EffectBuilder<REv>,
+    state_root_hash: Digest,
+    purse_uref: URef,
+) -> Result<U512, Error> {
+    let balance_result = effect_builder
+        .make_request(
+            |responder| RpcRequest::GetBalance {
+                state_root_hash,
+                purse_uref,
+                responder,
+            },
+            QueueKind::Api,
+        )
+        .await;
+
+    match balance_result {
+        Ok(BalanceResult::Success { motes, .. }) => Ok(motes),
+        Ok(BalanceResult::RootNotFound) => {
+            info!(
+                %state_root_hash,
+                %purse_uref,
+                "query-balance failed: root not found"
+            );
+            Err(Error::new(
+                ErrorCode::FailedToGetBalance,
+                format!(
+                    "root hash {} not found when querying for purse {}",
+                    state_root_hash, purse_uref
+                ),
+            ))
+        }
+        Err(error) => {
+            info!("query-balance failed to execute: {}", error);
+            Err(Error::new(
+                ErrorCode::GetBalanceFailedToExecute,
+                error.to_string(),
+            ))
+        }
+    }
+}
+
+/// Returns the error for a block which is not in the available block range: `StateRootPruned` if
+/// the block is in storage but its global state has been pruned, otherwise `NoSuchBlock`.
+async fn unavailable_block_error<REv: ReactorEventT>(
+    effect_builder: EffectBuilder<REv>,
+    maybe_block_header: Option<BlockHeader>,
+    error_msg: String,
+) -> Error {
+    let maybe_lowest_retained_height = effect_builder
+        .get_lowest_retained_state_height_from_storage()
+        .await;
+    match (maybe_block_header, maybe_lowest_retained_height) {
+        (Some(block_header), Some(lowest_retained_height))
+            if block_header.height() < lowest_retained_height =>
+        {
+            common::missing_block_or_state_root_error(
+                effect_builder,
+                ErrorCode::StateRootPruned,
+                format!(
+                    "global state of block at height {} has been pruned",
+                    block_header.height()
+                ),
+            )
+            .await
+        }
+        _ => Error::new(ErrorCode::NoSuchBlock, error_msg),
+    }
+}
+
 pub(super) async fn get_state_root_hash_and_optional_header<REv: ReactorEventT>(
     effect_builder: EffectBuilder<REv>,
     state_identifier: GlobalStateIdentifier,
@@ -1151,7 +1803,13 @@ pub(super) async fn get_state_root_hash_and_optional_header<REv: ReactorEventT>(
                 None => {
                     let error_msg =
                         format!("failed to retrieve specified block header {}", block_hash);
-                    Err(Error::new(ErrorCode::NoSuchBlock, error_msg))
+                    let maybe_block_header = effect_builder
+                        .get_block_header_from_storage(block_hash, false)
+                        .await;
+                    Err(
+                        unavailable_block_error(effect_builder, maybe_block_header, error_msg)
+                            .await,
+                    )
                 }
                 Some(block_header) => {
                     let json_block_header = JsonBlockHeader::from(block_header.clone());
@@ -1170,7 +1828,13 @@ pub(super) async fn get_state_root_hash_and_optional_header<REv: ReactorEventT>(
                 None => {
                     let error_msg =
                         format!("failed to retrieve block header at height {}", block_height);
-                    Err(Error::new(ErrorCode::NoSuchBlock, error_msg))
+                    let maybe_block_header = effect_builder
+                        .get_block_header_at_height_from_storage(block_height, false)
+                        .await;
+                    Err(
+                        unavailable_block_error(effect_builder, maybe_block_header, error_msg)
+                            .await,
+                    )
                 }
                 Some(block_header) => {
                     let json_block_header = JsonBlockHeader::from(block_header.clone());
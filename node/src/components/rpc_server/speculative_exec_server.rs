@@ -6,7 +6,11 @@ use casper_types::ProtocolVersion;
 use super::ReactorEventT;
 use crate::{
     effect::EffectBuilder,
-    rpcs::{speculative_exec::SpeculativeExec, RpcWithParams},
+    rpcs::{
+        speculative_exec::{SpeculativeExec, SpeculativeExecDeploys},
+        RpcWithParams,
+    },
+    utils::rate_limit::{QpsLimiter, RateLimitConfig, RateLimiter},
 };
 
 /// The URL path for all JSON-RPC requests.
@@ -21,16 +25,22 @@ pub(super) async fn run<REv: ReactorEventT>(
     api_version: ProtocolVersion,
     qps_limit: u64,
     max_body_bytes: u32,
+    max_batch_size: u32,
+    rate_limit_config: RateLimitConfig,
 ) {
     let mut handlers = RequestHandlersBuilder::new();
     SpeculativeExec::register_as_handler(effect_builder, api_version, &mut handlers);
+    SpeculativeExecDeploys::register_as_handler(effect_builder, api_version, &mut handlers);
     let handlers = handlers.build();
 
     super::rpcs::run(
         builder,
         handlers,
-        qps_limit,
+        None,
+        QpsLimiter::new(qps_limit),
+        RateLimiter::new(rate_limit_config),
         max_body_bytes,
+        max_batch_size,
         SPECULATIVE_EXEC_API_PATH,
         SPECULATIVE_EXEC_SERVER_NAME,
     )
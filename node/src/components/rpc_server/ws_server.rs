@@ -0,0 +1,439 @@
+//! WebSocket transport for the JSON-RPC server.
+//!
+//! Clients connected via WebSocket can send any JSON-RPC request (or batch of requests) handled by
+//! the JSON-RPC HTTP server as a single message, and will receive the response as a single message.
+//!
+//! Additionally, clients can call `subscribe` to be sent events from the event stream server as
+//! JSON-RPC notifications over the same connection, and `unsubscribe` to stop receiving them.  If
+//! a subscription ends otherwise, because the client fell too far behind the events or the event
+//! stream server shut down, the client is sent a `subscription_ended` notification.
+//!
+//! Requests received over WebSocket are subject to the same `qps_limit` and per-client and
+//! per-method rate limits as those received over HTTP, and each connection only handles a bounded
+//! number of requests concurrently.  Since every subscription takes up one of the event stream
+//! server's subscriber slots, the number of subscriptions across all connections is capped too.
+
+use std::{
+    collections::HashMap,
+    net::IpAddr,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use bytes::Bytes;
+use futures::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tokio::{
+    select,
+    sync::{mpsc, Semaphore},
+    task::JoinHandle,
+};
+use tracing::{debug, info};
+use warp::{
+    filters::BoxedFilter,
+    reply::Response,
+    ws::{Message, WebSocket, Ws},
+    Filter, Reply,
+};
+
+use casper_json_rpc::{Error, Params, RequestHandlersBuilder, ReservedErrorCode};
+use casper_types::{ProtocolVersion, PublicKey};
+
+use super::{
+    http_server,
+    rpcs::{ErrorCode, ALLOW_UNKNOWN_FIELDS_IN_JSON_RPC_REQUEST},
+    ReactorEventT,
+};
+use crate::{
+    components::event_stream_server::{EventFilter, EventStreamHandle, SseData},
+    effect::EffectBuilder,
+    types::DeployHash,
+    utils::rate_limit::{QpsLimiter, RateLimiter},
+};
+
+/// The URL path for WebSocket connections.
+pub const WS_API_PATH: &str = "ws";
+
+/// The JSON-RPC version field of notifications.
+const JSON_RPC_VERSION: &str = "2.0";
+
+/// The JSON-RPC method name for subscribing to events.
+const SUBSCRIBE_METHOD: &str = "subscribe";
+/// The JSON-RPC method name for cancelling a subscription.
+const UNSUBSCRIBE_METHOD: &str = "unsubscribe";
+/// The JSON-RPC method name of the notifications sent for subscribed events.
+const SUBSCRIPTION_NOTIFICATION_METHOD: &str = "subscription";
+/// The JSON-RPC method name of the notification sent when a subscription ends without having been
+/// cancelled.
+const SUBSCRIPTION_ENDED_NOTIFICATION_METHOD: &str = "subscription_ended";
+
+/// The maximum number of concurrent subscriptions on a single WebSocket connection.
+const MAX_SUBSCRIPTIONS_PER_CONNECTION: usize = 16;
+/// The maximum number of concurrent subscriptions summed over all WebSocket connections.
+const MAX_SUBSCRIPTIONS: usize = 64;
+/// The maximum number of requests handled concurrently for a single WebSocket connection.  Further
+/// messages are not read from the connection until one of these has been responded to.
+const MAX_IN_FLIGHT_REQUESTS_PER_CONNECTION: usize = 16;
+/// The maximum number of messages buffered for sending on a single WebSocket connection.  Once
+/// full, responses and notifications wait until the client has read earlier ones.
+const OUTGOING_BUFFER_SIZE: usize = 64;
+
+/// The identifier of a subscription, unique within a single WebSocket connection.
+type SubscriptionId = u64;
+
+/// Params for "subscribe" RPC request.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct SubscribeParams {
+    /// The kinds of event to be sent.
+    events: Vec<EventFilter>,
+    /// If set, only events relating to the given account are sent.
+    #[serde(default)]
+    account: Option<PublicKey>,
+    /// If set, only events relating to the given deploy are sent.
+    #[serde(default)]
+    deploy_hash: Option<DeployHash>,
+}
+
+impl SubscribeParams {
+    /// Returns `true` if the given event should be sent to the subscriber.
+    fn matches(&self, event: &SseData) -> bool {
+        event.should_include(&self.events)
+            && self
+                .account
+                .as_ref()
+                .map_or(true, |account| event.involves_account(account))
+            && self
+                .deploy_hash
+                .as_ref()
+                .map_or(true, |deploy_hash| event.involves_deploy(deploy_hash))
+    }
+}
+
+/// Params for "unsubscribe" RPC request.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct UnsubscribeParams {
+    /// The ID of the subscription to cancel, as returned by "subscribe".
+    subscription: SubscriptionId,
+}
+
+/// Tries to parse the incoming JSON-RPC request's "params" field as `T`.
+fn try_parse_params<T: for<'de> Deserialize<'de>>(
+    maybe_params: Option<Params>,
+) -> Result<T, Error> {
+    let params = match maybe_params {
+        Some(params) => Value::from(params),
+        None => {
+            return Err(Error::new(
+                ReservedErrorCode::InvalidParams,
+                "Missing 'params' field",
+            ))
+        }
+    };
+    serde_json::from_value::<T>(params).map_err(|error| {
+        Error::new(
+            ReservedErrorCode::InvalidParams,
+            format!("Failed to parse 'params' field: {}", error),
+        )
+    })
+}
+
+/// The subscriptions held by a single WebSocket connection.
+struct Subscriptions {
+    event_stream: Option<EventStreamHandle>,
+    outgoing_sender: mpsc::Sender<Message>,
+    /// Permits for subscriptions, shared by all connections.
+    subscription_permits: Arc<Semaphore>,
+    next_id: AtomicU64,
+    tasks: Mutex<HashMap<SubscriptionId, JoinHandle<()>>>,
+}
+
+impl Subscriptions {
+    fn new(
+        event_stream: Option<EventStreamHandle>,
+        outgoing_sender: mpsc::Sender<Message>,
+        subscription_permits: Arc<Semaphore>,
+    ) -> Self {
+        Subscriptions {
+            event_stream,
+            outgoing_sender,
+            subscription_permits,
+            next_id: AtomicU64::new(0),
+            tasks: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Starts forwarding matching events from the event stream to the client as notifications.
+    fn subscribe(&self, maybe_params: Option<Params>) -> Result<SubscriptionId, Error> {
+        let params = try_parse_params::<SubscribeParams>(maybe_params)?;
+
+        let event_stream = self.event_stream.as_ref().ok_or_else(|| {
+            Error::new(
+                ErrorCode::EventStreamUnavailable,
+                "the event stream server is not enabled on this node",
+            )
+        })?;
+
+        let mut tasks = self.tasks.lock().unwrap();
+        tasks.retain(|_, task| !task.is_finished());
+        if tasks.len() >= MAX_SUBSCRIPTIONS_PER_CONNECTION {
+            return Err(Error::new(
+                ErrorCode::TooManySubscriptions,
+                format!(
+                    "limit of {} subscriptions per connection reached",
+                    MAX_SUBSCRIPTIONS_PER_CONNECTION
+                ),
+            ));
+        }
+
+        let permit = Arc::clone(&self.subscription_permits)
+            .try_acquire_owned()
+            .map_err(|_| {
+                Error::new(
+                    ErrorCode::TooManySubscriptions,
+                    format!(
+                        "limit of {} subscriptions across all connections reached",
+                        MAX_SUBSCRIPTIONS
+                    ),
+                )
+            })?;
+
+        let mut events = event_stream.subscribe().ok_or_else(|| {
+            Error::new(
+                ErrorCode::TooManySubscriptions,
+                "the event stream server has reached its limit of subscribers",
+            )
+        })?;
+
+        let subscription_id = self.next_id.fetch_add(1, Ordering::Relaxed);
+
+        let outgoing_sender = self.outgoing_sender.clone();
+        let task = tokio::spawn(async move {
+            // Hold the permit for as long as the subscription is active.
+            let _permit = permit;
+            while let Some(event) = events.next().await {
+                if !params.matches(&event) {
+                    continue;
+                }
+                let notification = json!({
+                    "jsonrpc": JSON_RPC_VERSION,
+                    "method": SUBSCRIPTION_NOTIFICATION_METHOD,
+                    "params": {
+                        "subscription": subscription_id,
+                        "event": event,
+                    }
+                });
+                if outgoing_sender
+                    .send(Message::text(notification.to_string()))
+                    .await
+                    .is_err()
+                {
+                    return;
+                }
+            }
+            // The stream only ends if events were dropped or the event stream server shut down, so
+            // the client must not keep waiting for further events.
+            debug!(subscription_id, "websocket subscription ended");
+            let notification = json!({
+                "jsonrpc": JSON_RPC_VERSION,
+                "method": SUBSCRIPTION_ENDED_NOTIFICATION_METHOD,
+                "params": {
+                    "subscription": subscription_id,
+                    "reason": "fell too far behind the events, or the event stream server shut \
+                        down",
+                }
+            });
+            let _ = outgoing_sender
+                .send(Message::text(notification.to_string()))
+                .await;
+        });
+        let _ = tasks.insert(subscription_id, task);
+
+        Ok(subscription_id)
+    }
+
+    /// Cancels the given subscription, returning `true` if it existed.
+    fn unsubscribe(&self, maybe_params: Option<Params>) -> Result<bool, Error> {
+        let params = try_parse_params::<UnsubscribeParams>(maybe_params)?;
+        match self.tasks.lock().unwrap().remove(&params.subscription) {
+            Some(task) => {
+                task.abort();
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Cancels all subscriptions.
+    fn clear(&self) {
+        for (_, task) in self.tasks.lock().unwrap().drain() {
+            task.abort();
+        }
+    }
+}
+
+/// Handles a single WebSocket connection until it is closed by either side.
+#[allow(clippy::too_many_arguments)]
+async fn handle_connection<REv: ReactorEventT>(
+    websocket: WebSocket,
+    (client_ip, api_key): (IpAddr, Option<String>),
+    effect_builder: EffectBuilder<REv>,
+    api_version: ProtocolVersion,
+    event_stream: Option<EventStreamHandle>,
+    max_batch_size: u32,
+    qps_limiter: Arc<QpsLimiter>,
+    rate_limiter: Arc<RateLimiter>,
+    subscription_permits: Arc<Semaphore>,
+) {
+    let (mut ws_sender, mut ws_receiver) = websocket.split();
+    let (outgoing_sender, mut outgoing_receiver) = mpsc::channel(OUTGOING_BUFFER_SIZE);
+    let subscriptions = Arc::new(Subscriptions::new(
+        event_stream,
+        outgoing_sender.clone(),
+        subscription_permits,
+    ));
+    let in_flight_permits = Arc::new(Semaphore::new(MAX_IN_FLIGHT_REQUESTS_PER_CONNECTION));
+
+    let mut handlers = RequestHandlersBuilder::new();
+    http_server::register_handlers(effect_builder, api_version, &mut handlers);
+    let cloned_subscriptions = Arc::clone(&subscriptions);
+    let subscribe = move |maybe_params| {
+        let subscriptions = Arc::clone(&cloned_subscriptions);
+        async move { subscriptions.subscribe(maybe_params) }
+    };
+    handlers.register_handler(SUBSCRIBE_METHOD, Arc::new(subscribe));
+    let cloned_subscriptions = Arc::clone(&subscriptions);
+    let unsubscribe = move |maybe_params| {
+        let subscriptions = Arc::clone(&cloned_subscriptions);
+        async move { subscriptions.unsubscribe(maybe_params) }
+    };
+    handlers.register_handler(UNSUBSCRIBE_METHOD, Arc::new(unsubscribe));
+    let handlers = handlers.build();
+
+    loop {
+        select! {
+            // Stop reading from the connection while the maximum number of requests is in flight.
+            maybe_message = ws_receiver.next(),
+                if in_flight_permits.available_permits() > 0 => {
+                let message = match maybe_message {
+                    Some(Ok(message)) => message,
+                    Some(Err(error)) => {
+                        debug!(%error, "websocket connection failed");
+                        break;
+                    }
+                    None => break,
+                };
+                if message.is_close() {
+                    break;
+                }
+                if !message.is_text() && !message.is_binary() {
+                    continue;
+                }
+
+                let permit = match Arc::clone(&in_flight_permits).try_acquire_owned() {
+                    Ok(permit) => permit,
+                    Err(_) => unreachable!("only reading while permits are available"),
+                };
+
+                // Handle each request concurrently so that slow requests don't hold up others or
+                // the sending of notifications.
+                let body = Bytes::copy_from_slice(message.as_bytes());
+                let handlers = handlers.clone();
+                let outgoing_sender = outgoing_sender.clone();
+                let qps_limiter = Arc::clone(&qps_limiter);
+                let rate_limiter = Arc::clone(&rate_limiter);
+                let api_key = api_key.clone();
+                tokio::spawn(async move {
+                    let _permit = permit;
+                    let limited = match rate_limiter.check_json_rpc(
+                        client_ip,
+                        api_key.as_deref(),
+                        &body,
+                        max_batch_size,
+                    ) {
+                        Ok(()) => qps_limiter.wait_for_json_rpc(&body, max_batch_size).await,
+                        Err(rejection) => Err(rejection),
+                    };
+                    let maybe_response = match limited {
+                        Ok(()) => {
+                            casper_json_rpc::handle_message(
+                                body,
+                                handlers,
+                                ALLOW_UNKNOWN_FIELDS_IN_JSON_RPC_REQUEST,
+                                max_batch_size,
+                            )
+                            .await
+                        }
+                        Err(rejection) => {
+                            debug!(%rejection, "rate limited websocket request");
+                            serde_json::to_string(&rejection.json_rpc_response()).ok()
+                        }
+                    };
+                    if let Some(response) = maybe_response {
+                        let _ = outgoing_sender.send(Message::text(response)).await;
+                    }
+                });
+            }
+
+            maybe_outgoing = outgoing_receiver.recv() => {
+                if let Some(message) = maybe_outgoing {
+                    if let Err(error) = ws_sender.send(message).await {
+                        debug!(%error, "failed to send on websocket connection");
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    subscriptions.clear();
+    let _ = ws_sender.close().await;
+}
+
+/// Returns a warp filter which upgrades requests on the WebSocket path to WebSocket connections.
+///
+/// Requests received on the connections are subject to the given limiters, which should be shared
+/// with the HTTP transport.
+pub(super) fn route<REv: ReactorEventT>(
+    effect_builder: EffectBuilder<REv>,
+    api_version: ProtocolVersion,
+    event_stream: Option<EventStreamHandle>,
+    max_batch_size: u32,
+    qps_limiter: Arc<QpsLimiter>,
+    rate_limiter: Arc<RateLimiter>,
+) -> BoxedFilter<(Response,)> {
+    if event_stream.is_none() {
+        info!("event stream server not enabled: websocket subscriptions will be unavailable");
+    }
+    let subscription_permits = Arc::new(Semaphore::new(MAX_SUBSCRIPTIONS));
+
+    warp::path(WS_API_PATH)
+        .and(warp::path::end())
+        .and(rate_limiter.client_filter())
+        .and(warp::ws())
+        .map(move |client: (IpAddr, Option<String>), ws: Ws| {
+            let event_stream = event_stream.clone();
+            let qps_limiter = Arc::clone(&qps_limiter);
+            let rate_limiter = Arc::clone(&rate_limiter);
+            let subscription_permits = Arc::clone(&subscription_permits);
+            ws.on_upgrade(move |websocket| {
+                handle_connection(
+                    websocket,
+                    client,
+                    effect_builder,
+                    api_version,
+                    event_stream,
+                    max_batch_size,
+                    qps_limiter,
+                    rate_limiter,
+                    subscription_permits,
+                )
+            })
+            .into_response()
+        })
+        .boxed()
+}
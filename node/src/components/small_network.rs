@@ -25,6 +25,9 @@
 
 mod bincode_format;
 mod chain_info;
+mod channel;
+mod chunking;
+mod compression;
 mod config;
 mod counting_format;
 mod error;
@@ -35,6 +38,7 @@ mod message;
 mod message_pack_format;
 mod metrics;
 mod outgoing;
+mod peer_scores;
 mod symmetry;
 pub(crate) mod tasks;
 #[cfg(test)]
@@ -54,20 +58,17 @@ use std::{
     time::{Duration, Instant},
 };
 
+use bincode::Options;
 use datasize::DataSize;
-use futures::{future::BoxFuture, FutureExt};
+use futures::{future::BoxFuture, FutureExt, StreamExt};
 use openssl::{error::ErrorStack as OpenSslErrorStack, pkey};
 use pkey::{PKey, Private};
 use prometheus::Registry;
 use rand::{prelude::SliceRandom, seq::IteratorRandom};
-use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use tokio::{
     net::TcpStream,
-    sync::{
-        mpsc::{self, UnboundedSender},
-        watch,
-    },
+    sync::watch,
     task::JoinHandle,
 };
 use tokio_openssl::SslStream;
@@ -78,14 +79,19 @@ use casper_types::{EraId, PublicKey};
 
 pub(crate) use self::{
     bincode_format::BincodeFormat,
+    channel::Channel,
     config::Config,
     error::Error,
     event::Event,
     gossiped_address::GossipedAddress,
     message::{EstimatorWeights, FromIncoming, Message, MessageKind, Payload},
+    peer_scores::PeerScore,
 };
 use self::{
     chain_info::ChainInfo,
+    channel::{ChannelSenders, MessageQueueItem, QueueError},
+    chunking::{ChunkSink, ChunkStream},
+    compression::{CompressionConfig, CompressionFormat},
     config::IdentityConfig,
     counting_format::{ConnectionId, CountingFormat, Role},
     error::{ConnectionError, Result},
@@ -94,15 +100,16 @@ use self::{
     message::ConsensusKeyPair,
     metrics::Metrics,
     outgoing::{DialOutcome, DialRequest, OutgoingConfig, OutgoingManager},
+    peer_scores::{PeerBehavior, PeerScores},
     symmetry::ConnectionSymmetry,
-    tasks::{MessageQueueItem, NetworkContext},
+    tasks::NetworkContext,
 };
 
 use crate::{
     components::{consensus, Component},
     effect::{
         announcements::{
-            BlocklistAnnouncement, ChainSynchronizerAnnouncement, ContractRuntimeAnnouncement,
+            ChainSynchronizerAnnouncement, ContractRuntimeAnnouncement, PeerBehaviorAnnouncement,
         },
         requests::{BeginGossipRequest, NetworkInfoRequest, NetworkRequest, StorageRequest},
         AutoClosingResponder, EffectBuilder, EffectExt, Effects,
@@ -135,7 +142,7 @@ const OUTGOING_MANAGER_SWEEP_INTERVAL: Duration = Duration::from_secs(1);
 #[derive(Clone, DataSize, Debug)]
 pub(crate) struct OutgoingHandle<P> {
     #[data_size(skip)] // Unfortunately, there is no way to inspect an `UnboundedSender`.
-    sender: UnboundedSender<MessageQueueItem<P>>,
+    senders: ChannelSenders<P>,
     peer_addr: SocketAddr,
 }
 
@@ -164,6 +171,9 @@ where
     /// Tracks nodes that have announced themselves as nodes that are syncing.
     syncing_nodes: HashSet<NodeId>,
 
+    /// Scores of peers, based on their past behavior.
+    peer_scores: PeerScores,
+
     /// Channel signaling a shutdown of the small network.
     // Note: This channel is closed when `SmallNetwork` is dropped, signalling the receivers that
     // they should cease operation.
@@ -295,11 +305,11 @@ where
         let consensus_keys = consensus_cfg
             .map(|cfg| {
                 let root = cfg.dir();
-                cfg.value().load_keys(root)
+                cfg.value().load_handshake_signer(root)
             })
             .transpose()
-            .map_err(Error::LoadConsensusKeys)?
-            .map(|(secret_key, public_key)| ConsensusKeyPair::new(secret_key, public_key));
+            .map_err(|error| Error::LoadConsensusKeys(Box::new(error)))?
+            .map(ConsensusKeyPair::new);
 
         // Set the demand max from configuration, regarding `0` as "unlimited".
         let demand_max = if cfg.max_in_flight_demands == 0 {
@@ -347,6 +357,7 @@ where
             tarpit_chance: cfg.tarpit_chance,
             max_in_flight_demands: demand_max,
             is_syncing: AtomicBool::new(true),
+            compression: cfg.compression.clone(),
         });
 
         // Run the server task.
@@ -366,12 +377,15 @@ where
             .in_current_span(),
         );
 
+        let peer_scores = PeerScores::new(cfg.peer_scoring.clone());
+
         let mut component = SmallNetwork {
             cfg,
             context,
             outgoing_manager,
             connection_symmetries: HashMap::new(),
             syncing_nodes: HashSet::new(),
+            peer_scores,
             shutdown_sender: Some(server_shutdown_sender),
             close_incoming_sender: Some(close_incoming_sender),
             close_incoming_receiver,
@@ -421,8 +435,12 @@ where
     /// Queues a message to be sent to all nodes.
     fn broadcast_message(&self, msg: Arc<Message<P>>) {
         self.net_metrics.broadcast_requests.inc();
+        let estimated_wire_size = match estimate_wire_size(&msg) {
+            Some(estimated_wire_size) => estimated_wire_size,
+            None => return,
+        };
         for peer_id in self.outgoing_manager.connected_peers() {
-            self.send_message(peer_id, msg.clone(), None);
+            self.send_message(peer_id, msg.clone(), estimated_wire_size, None);
         }
     }
 
@@ -434,6 +452,10 @@ where
         count: usize,
         exclude: HashSet<NodeId>,
     ) -> HashSet<NodeId> {
+        let estimated_wire_size = match estimate_wire_size(&msg) {
+            Some(estimated_wire_size) => estimated_wire_size,
+            None => return HashSet::new(),
+        };
         let peer_ids = self
             .outgoing_manager
             .connected_peers()
@@ -453,17 +475,20 @@ where
         }
 
         for &peer_id in &peer_ids {
-            self.send_message(peer_id, msg.clone(), None);
+            self.send_message(peer_id, msg.clone(), estimated_wire_size, None);
         }
 
         peer_ids.into_iter().collect()
     }
 
     /// Queues a message to be sent to a specific node.
+    ///
+    /// If the message's channel is over budget, the message is dropped.
     fn send_message(
         &self,
         dest: NodeId,
         msg: Arc<Message<P>>,
+        estimated_wire_size: u32,
         opt_responder: Option<AutoClosingResponder<()>>,
     ) {
         // Try to send the message.
@@ -475,11 +500,24 @@ where
                 error!(kind=%msg.classify(), node_id=%dest, "sending unsafe message to syncing node");
             }
 
-            if let Err(msg) = connection.sender.send((msg, opt_responder)) {
-                // We lost the connection, but that fact has not reached us yet.
-                warn!(our_id=%self.context.our_id, %dest, ?msg, "dropped outgoing message, lost connection");
-            } else {
-                self.net_metrics.queued_messages.inc();
+            let channel = msg.channel();
+            let item = MessageQueueItem {
+                message: msg,
+                responder: opt_responder,
+                estimated_wire_size,
+            };
+
+            match connection.senders.send(channel, item) {
+                Ok(()) => self.net_metrics.queued_messages.inc(),
+                Err(QueueError::OverBudget) => {
+                    // The peer is not keeping up with the traffic on this channel.
+                    self.net_metrics.out_dropped_over_budget.inc();
+                    debug!(our_id=%self.context.our_id, %dest, %channel, "dropped outgoing message, channel over budget");
+                }
+                Err(QueueError::Closed) => {
+                    // We lost the connection, but that fact has not reached us yet.
+                    warn!(our_id=%self.context.our_id, %dest, %channel, "dropped outgoing message, lost connection");
+                }
             }
         } else {
             // We are not connected, so the reconnection is likely already in progress.
@@ -580,7 +618,7 @@ where
                 effects.extend(
                     tasks::message_reader(
                         self.context.clone(),
-                        stream,
+                        *stream,
                         self.incoming_limiter
                             .create_handle(peer_id, peer_consensus_public_key),
                         self.close_incoming_receiver.clone(),
@@ -644,8 +682,10 @@ where
             | ConnectionError::HandshakeRecv(_)
             | ConnectionError::IncompatibleVersion(_) => false,
 
-            // These errors are potential bugs on our side.
-            ConnectionError::HandshakeSenderCrashed(_)
+            // These errors are on our side, and mostly potential bugs.
+            ConnectionError::CouldNotSignOurHandshake(_)
+            | ConnectionError::HandshakeSignerCrashed(_)
+            | ConnectionError::HandshakeSenderCrashed(_)
             | ConnectionError::FailedToReuniteHandshakeSinkAndStream
             | ConnectionError::CouldNotEncodeOurHandshake(_) => false,
 
@@ -718,8 +758,8 @@ where
             } => {
                 info!("new outgoing connection established");
 
-                let (sender, receiver) = mpsc::unbounded_channel();
-                let handle = OutgoingHandle { sender, peer_addr };
+                let (senders, receivers) = channel::outgoing_channels(&self.cfg.channel_budgets);
+                let handle = OutgoingHandle { senders, peer_addr };
 
                 let request = self
                     .outgoing_manager
@@ -744,8 +784,8 @@ where
 
                 effects.extend(
                     tasks::message_sender(
-                        receiver,
-                        sink,
+                        receivers,
+                        *sink,
                         self.outgoing_limiter
                             .create_handle(peer_id, peer_consensus_public_key),
                         self.net_metrics.queued_messages.clone(),
@@ -964,16 +1004,23 @@ where
                         // can later be given once the message has actually been buffered.
                         self.net_metrics.direct_message_requests.inc();
 
-                        if respond_after_queueing {
-                            self.send_message(*dest, Arc::new(Message::Payload(*payload)), None);
-                            auto_closing_responder.respond(()).ignore()
-                        } else {
-                            self.send_message(
-                                *dest,
-                                Arc::new(Message::Payload(*payload)),
-                                Some(auto_closing_responder),
-                            );
-                            Effects::new()
+                        let msg = Arc::new(Message::Payload(*payload));
+                        match estimate_wire_size(&msg) {
+                            Some(estimated_wire_size) if respond_after_queueing => {
+                                self.send_message(*dest, msg, estimated_wire_size, None);
+                                auto_closing_responder.respond(()).ignore()
+                            }
+                            Some(estimated_wire_size) => {
+                                self.send_message(
+                                    *dest,
+                                    msg,
+                                    estimated_wire_size,
+                                    Some(auto_closing_responder),
+                                );
+                                Effects::new()
+                            }
+                            // The message can't be sent, which closes the responder.
+                            None => Effects::new(),
                         }
                     }
                     NetworkRequest::Broadcast {
@@ -1013,6 +1060,8 @@ where
                         .collect();
 
                     symmetric_peers.shuffle(rng);
+                    self.peer_scores
+                        .sort_penalized_last(&mut symmetric_peers, Instant::now());
 
                     responder.respond(symmetric_peers).ignore()
                 }
@@ -1027,9 +1076,14 @@ where
                         .collect();
 
                     symmetric_validator_peers.shuffle(rng);
+                    self.peer_scores
+                        .sort_penalized_last(&mut symmetric_validator_peers, Instant::now());
 
                     responder.respond(symmetric_validator_peers).ignore()
                 }
+                NetworkInfoRequest::PeerScores { responder } => responder
+                    .respond(self.peer_scores.snapshot(Instant::now()))
+                    .ignore(),
             },
             Event::PeerAddressReceived(gossiped_address) => {
                 let requests = self.outgoing_manager.learn_addr(
@@ -1039,66 +1093,84 @@ where
                 );
                 self.process_dial_requests(requests)
             }
-            Event::BlocklistAnnouncement(BlocklistAnnouncement::OffenseCommitted(peer_id)) => {
+            Event::PeerBehaviorAnnouncement(announcement) => {
+                let (peer_id, behavior) = match announcement {
+                    PeerBehaviorAnnouncement::OffenseCommitted { offender, offense } => {
+                        (*offender, PeerBehavior::Offense(offense))
+                    }
+                    PeerBehaviorAnnouncement::TimedOut(peer_id) => {
+                        (*peer_id, PeerBehavior::TimedOut)
+                    }
+                    PeerBehaviorAnnouncement::UsefulResponse(peer_id) => {
+                        (*peer_id, PeerBehavior::UsefulResponse)
+                    }
+                };
+
+                let now = Instant::now();
+                if !self.peer_scores.record(peer_id, behavior, now) {
+                    trace!(%peer_id, %behavior, "updated peer score");
+                    return Effects::new();
+                }
+
                 // TODO: We do not have a proper by-node-ID blocklist, but rather only block the
                 // current outgoing address of a peer.
-                warn!(%peer_id, "adding peer to blocklist after transgression");
+                warn!(%peer_id, %behavior, "adding peer to blocklist after its score dropped");
 
-                if let Some(addr) = self.outgoing_manager.get_addr(*peer_id) {
-                    let requests = self.outgoing_manager.block_addr(addr, Instant::now());
+                if let Some(addr) = self.outgoing_manager.get_addr(peer_id) {
+                    let requests = self.outgoing_manager.block_addr(addr, now);
                     self.process_dial_requests(requests)
                 } else {
                     // Peer got away with it, no longer an outgoing connection.
                     Effects::new()
                 }
             }
-            Event::ContractRuntimeAnnouncement(
+            Event::ContractRuntimeAnnouncement(ann) => match *ann {
                 ContractRuntimeAnnouncement::LinearChainBlock { .. }
-                | ContractRuntimeAnnouncement::CommitStepSuccess { .. },
-            ) => Effects::new(),
-            Event::ContractRuntimeAnnouncement(
+                | ContractRuntimeAnnouncement::CommitStepSuccess { .. } => Effects::new(),
                 ContractRuntimeAnnouncement::UpcomingEraValidators {
                     era_that_is_ending,
                     mut upcoming_era_validators,
-                },
-            ) => {
-                if era_that_is_ending < self.active_era {
-                    debug!("ignoring past era end announcement");
-                } else {
-                    // We have a new `active_era`, even if we may have skipped some, as this one
-                    // is the highest seen.
-                    self.active_era = era_that_is_ending + 1;
-
-                    let active_validators: HashSet<PublicKey> = upcoming_era_validators
-                        .remove(&self.active_era)
-                        .unwrap_or_default()
-                        .into_keys()
-                        .collect();
+                } => {
+                    if era_that_is_ending < self.active_era {
+                        debug!("ignoring past era end announcement");
+                    } else {
+                        // We have a new `active_era`, even if we may have skipped some, as this one
+                        // is the highest seen.
+                        self.active_era = era_that_is_ending + 1;
+
+                        let active_validators: HashSet<PublicKey> = upcoming_era_validators
+                            .remove(&self.active_era)
+                            .unwrap_or_default()
+                            .into_keys()
+                            .collect();
+
+                        if active_validators.is_empty() {
+                            error!("received an empty set of active era validators");
+                        }
 
-                    if active_validators.is_empty() {
-                        error!("received an empty set of active era validators");
+                        let upcoming_validators: HashSet<PublicKey> = upcoming_era_validators
+                            .remove(&(self.active_era + 1))
+                            .unwrap_or_default()
+                            .into_keys()
+                            .collect();
+
+                        debug!(
+                            %era_that_is_ending,
+                            active = active_validators.len(),
+                            upcoming = upcoming_validators.len(),
+                            "updating active and upcoming validators"
+                        );
+                        self.incoming_limiter.update_validators(
+                            active_validators.clone(),
+                            upcoming_validators.clone(),
+                        );
+                        self.outgoing_limiter
+                            .update_validators(active_validators, upcoming_validators);
                     }
 
-                    let upcoming_validators: HashSet<PublicKey> = upcoming_era_validators
-                        .remove(&(self.active_era + 1))
-                        .unwrap_or_default()
-                        .into_keys()
-                        .collect();
-
-                    debug!(
-                        %era_that_is_ending,
-                        active = active_validators.len(),
-                        upcoming = upcoming_validators.len(),
-                        "updating active and upcoming validators"
-                    );
-                    self.incoming_limiter
-                        .update_validators(active_validators.clone(), upcoming_validators.clone());
-                    self.outgoing_limiter
-                        .update_validators(active_validators, upcoming_validators);
+                    Effects::new()
                 }
-
-                Effects::new()
-            }
+            },
 
             Event::GossipOurAddress => {
                 let our_address = GossipedAddress::new(self.context.public_addr);
@@ -1116,6 +1188,7 @@ where
             Event::SweepOutgoing => {
                 let now = Instant::now();
                 let requests = self.outgoing_manager.perform_housekeeping(now);
+                self.peer_scores.prune(now);
 
                 let mut effects = self.process_dial_requests(requests);
 
@@ -1215,41 +1288,84 @@ impl From<&SmallNetworkIdentity> for NodeId {
 /// Transport type alias for base encrypted connections.
 type Transport = SslStream<TcpStream>;
 
-/// A framed transport for `Message`s.
-pub(crate) type FullTransport<P> = tokio_serde::Framed<
-    FramedTransport,
-    Message<P>,
-    Arc<Message<P>>,
-    CountingFormat<BincodeFormat>,
->;
+/// The format `Message`s are serialized with, before being split into chunks.
+pub(crate) type MessageFormat = CountingFormat<CompressionFormat<BincodeFormat>>;
 
 pub(crate) type FramedTransport = tokio_util::codec::Framed<Transport, LengthDelimitedCodec>;
 
-/// Constructs a new full transport on a stream.
+/// Constructs a new full transport on a stream, returning its sending and receiving halves.
 ///
-/// A full transport contains the framing as well as the encoding scheme used to send messages.
+/// A full transport contains the framing, the splitting of messages into chunks and the encoding
+/// scheme used to send messages. Messages are compressed according to `compression`, if it was
+/// negotiated for the connection, and split into chunks if `chunking` was.
 fn full_transport<P>(
     metrics: Weak<Metrics>,
     connection_id: ConnectionId,
     framed: FramedTransport,
     role: Role,
-) -> FullTransport<P>
+    compression: Option<CompressionConfig>,
+    chunking: bool,
+    maximum_net_message_size: u32,
+) -> (ChunkSink<P>, ChunkStream<P>)
 where
-    for<'de> P: Serialize + Deserialize<'de>,
-    for<'de> Message<P>: Serialize + Deserialize<'de>,
+    P: Payload,
 {
-    tokio_serde::Framed::new(
-        framed,
-        CountingFormat::new(metrics, connection_id, role, BincodeFormat::default()),
+    let format = || {
+        CountingFormat::new(
+            metrics.clone(),
+            connection_id,
+            role,
+            CompressionFormat::new(
+                metrics.clone(),
+                compression.clone(),
+                maximum_net_message_size,
+                BincodeFormat::default(),
+            ),
+        )
+    };
+    let compression_threshold = compression.as_ref().map(|config| config.threshold);
+    let (sink, stream) = framed.split();
+    (
+        ChunkSink::new(sink, format(), compression_threshold, chunking),
+        ChunkStream::new(
+            stream,
+            format(),
+            compression_threshold,
+            chunking,
+            maximum_serialized_message_size(maximum_net_message_size),
+        ),
     )
 }
 
+/// Returns the maximum size of a serialized message, including the tag byte prepended to it if
+/// compression was negotiated.
+fn maximum_serialized_message_size(maximum_net_message_size: u32) -> usize {
+    maximum_net_message_size as usize + 1
+}
+
+/// Returns the estimated size of the serialized message, counted towards its channel's budget.
+///
+/// Returns `None` if the message cannot be serialized, in which case it should be dropped.
+fn estimate_wire_size<P: Payload>(msg: &Message<P>) -> Option<u32> {
+    match BincodeFormat::default().0.serialized_size(msg) {
+        Ok(size) => Some(size as u32),
+        Err(error) => {
+            error!(
+                error = display_error(&error),
+                ?msg,
+                "failed to get serialized size of outgoing message, dropping it"
+            );
+            None
+        }
+    }
+}
+
 /// Constructs a framed transport.
 fn framed_transport(transport: Transport, maximum_net_message_size: u32) -> FramedTransport {
     tokio_util::codec::Framed::new(
         transport,
         LengthDelimitedCodec::builder()
-            .max_frame_length(maximum_net_message_size as usize)
+            .max_frame_length(maximum_serialized_message_size(maximum_net_message_size))
             .new_codec(),
     )
 }
@@ -0,0 +1,405 @@
+//! Prioritized channels for outgoing messages.
+//!
+//! All messages to a peer are sent over a single connection, but they are queued on separate
+//! channels depending on their kind. The sender always sends the next chunk of a message from the
+//! channel with the highest priority that has one queued, interrupting any message of lower
+//! priority, so a burst of bulk transfers during a peer's sync delays a consensus message by at
+//! most the one chunk currently being written.
+//!
+//! Every channel has its own budget for the number of bytes queued on it. A message exceeding it is
+//! dropped, unless its sender waits for it to be sent, which already provides backpressure. That
+//! way a congested channel can neither exhaust memory nor affect the other channels.
+
+use std::{
+    fmt::{self, Display, Formatter},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+use datasize::DataSize;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+
+use super::{Message, MessageKind};
+use crate::effect::AutoClosingResponder;
+
+/// Default budget for the gossip channel: 16 MiB.
+const DEFAULT_GOSSIP_BUDGET: u32 = 16 * 1024 * 1024;
+
+/// Default budget for the bulk transfer channel: 64 MiB.
+const DEFAULT_BULK_TRANSFER_BUDGET: u32 = 64 * 1024 * 1024;
+
+/// A channel for outgoing messages.
+///
+/// The variants are listed in order of decreasing priority.
+#[derive(Copy, Clone, DataSize, Debug, Eq, PartialEq, Hash)]
+pub(crate) enum Channel {
+    /// Consensus messages and protocol overhead.
+    Consensus,
+    /// Finality signatures.
+    FinalitySignatures,
+    /// Gossiped deploys and addresses, and anything not classified otherwise.
+    Gossip,
+    /// Deploys, finalized approvals, blocks and tries transferred on request.
+    BulkTransfer,
+}
+
+impl Channel {
+    /// The number of channels.
+    pub(super) const COUNT: usize = 4;
+
+    /// Returns the index of the channel, which is lower for higher priorities.
+    #[inline]
+    pub(super) fn index(self) -> u8 {
+        self as u8
+    }
+}
+
+impl From<MessageKind> for Channel {
+    fn from(kind: MessageKind) -> Self {
+        match kind {
+            MessageKind::Protocol | MessageKind::Consensus => Channel::Consensus,
+            MessageKind::DeployGossip | MessageKind::AddressGossip | MessageKind::Other => {
+                Channel::Gossip
+            }
+            MessageKind::DeployTransfer
+            | MessageKind::FinalizedApprovalsTransfer
+            | MessageKind::BlockTransfer
+            | MessageKind::TrieTransfer => Channel::BulkTransfer,
+        }
+    }
+}
+
+impl Display for Channel {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Channel::Consensus => f.write_str("consensus"),
+            Channel::FinalitySignatures => f.write_str("finality_signatures"),
+            Channel::Gossip => f.write_str("gossip"),
+            Channel::BulkTransfer => f.write_str("bulk_transfer"),
+        }
+    }
+}
+
+/// Maximum number of bytes queued for a single peer on each outgoing channel.
+///
+/// A value of `0` means unlimited.
+#[derive(DataSize, Debug, Clone, Deserialize, Serialize)]
+// Disallow unknown fields to ensure config files and command-line overrides contain valid keys.
+#[serde(deny_unknown_fields)]
+pub struct ChannelBudgets {
+    /// Budget for consensus messages.
+    pub consensus: u32,
+    /// Budget for finality signatures.
+    pub finality_signatures: u32,
+    /// Budget for gossiped deploys and addresses.
+    pub gossip: u32,
+    /// Budget for deploys, blocks and tries transferred on request.
+    pub bulk_transfer: u32,
+}
+
+impl Default for ChannelBudgets {
+    fn default() -> Self {
+        ChannelBudgets {
+            consensus: 0,
+            finality_signatures: 0,
+            gossip: DEFAULT_GOSSIP_BUDGET,
+            bulk_transfer: DEFAULT_BULK_TRANSFER_BUDGET,
+        }
+    }
+}
+
+/// An item on one of the internal outgoing message queues.
+#[derive(Debug)]
+pub(super) struct MessageQueueItem<P> {
+    /// The reference counted message.
+    pub(super) message: Arc<Message<P>>,
+    /// An optional responder to call once the message has been successfully handed over to the
+    /// kernel for sending.
+    pub(super) responder: Option<AutoClosingResponder<()>>,
+    /// The estimated size of the serialized message, counted towards the channel's budget.
+    pub(super) estimated_wire_size: u32,
+}
+
+/// The reason a message could not be queued.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub(super) enum QueueError {
+    /// Queueing the message would exceed the channel's budget.
+    OverBudget,
+    /// The connection was closed.
+    Closed,
+}
+
+/// Creates the outgoing channels for a connection to a peer.
+pub(super) fn outgoing_channels<P>(
+    budgets: &ChannelBudgets,
+) -> (ChannelSenders<P>, ChannelReceivers<P>) {
+    let (consensus_sender, consensus_receiver) = channel(budgets.consensus);
+    let (finality_signatures_sender, finality_signatures_receiver) =
+        channel(budgets.finality_signatures);
+    let (gossip_sender, gossip_receiver) = channel(budgets.gossip);
+    let (bulk_transfer_sender, bulk_transfer_receiver) = channel(budgets.bulk_transfer);
+    let senders = ChannelSenders {
+        consensus: consensus_sender,
+        finality_signatures: finality_signatures_sender,
+        gossip: gossip_sender,
+        bulk_transfer: bulk_transfer_sender,
+    };
+    let receivers = ChannelReceivers {
+        consensus: consensus_receiver,
+        finality_signatures: finality_signatures_receiver,
+        gossip: gossip_receiver,
+        bulk_transfer: bulk_transfer_receiver,
+    };
+    (senders, receivers)
+}
+
+/// Creates a single channel with the given budget.
+fn channel<P>(budget: u32) -> (ChannelSender<P>, ChannelReceiver<P>) {
+    let (sender, receiver) = mpsc::unbounded_channel();
+    let queued_bytes = Arc::new(AtomicUsize::new(0));
+    let budget = if budget == 0 {
+        usize::MAX
+    } else {
+        budget as usize
+    };
+    let sender = ChannelSender {
+        sender,
+        queued_bytes: Arc::clone(&queued_bytes),
+        budget,
+    };
+    let receiver = ChannelReceiver {
+        receiver,
+        queued_bytes,
+    };
+    (sender, receiver)
+}
+
+/// The sending end of a single outgoing channel.
+#[derive(Debug)]
+struct ChannelSender<P> {
+    sender: UnboundedSender<MessageQueueItem<P>>,
+    /// The number of bytes currently queued, shared with the receiver.
+    queued_bytes: Arc<AtomicUsize>,
+    /// The maximum number of bytes queued.
+    budget: usize,
+}
+
+// Implemented manually, since deriving it would require `P: Clone`.
+impl<P> Clone for ChannelSender<P> {
+    fn clone(&self) -> Self {
+        ChannelSender {
+            sender: self.sender.clone(),
+            queued_bytes: Arc::clone(&self.queued_bytes),
+            budget: self.budget,
+        }
+    }
+}
+
+impl<P> ChannelSender<P> {
+    fn send(&self, item: MessageQueueItem<P>) -> Result<(), QueueError> {
+        let size = item.estimated_wire_size as usize;
+        let queued_bytes = self.queued_bytes.fetch_add(size, Ordering::SeqCst);
+        if item.responder.is_none() && queued_bytes.saturating_add(size) > self.budget {
+            self.queued_bytes.fetch_sub(size, Ordering::SeqCst);
+            return Err(QueueError::OverBudget);
+        }
+        self.sender.send(item).map_err(|_| {
+            self.queued_bytes.fetch_sub(size, Ordering::SeqCst);
+            QueueError::Closed
+        })
+    }
+}
+
+/// The receiving end of a single outgoing channel.
+#[derive(Debug)]
+struct ChannelReceiver<P> {
+    receiver: UnboundedReceiver<MessageQueueItem<P>>,
+    /// The number of bytes currently queued, shared with the sender.
+    queued_bytes: Arc<AtomicUsize>,
+}
+
+impl<P> ChannelReceiver<P> {
+    async fn recv(&mut self) -> Option<MessageQueueItem<P>> {
+        let item = self.receiver.recv().await?;
+        self.queued_bytes
+            .fetch_sub(item.estimated_wire_size as usize, Ordering::SeqCst);
+        Some(item)
+    }
+
+    fn try_recv(&mut self) -> Option<MessageQueueItem<P>> {
+        let item = self.receiver.try_recv().ok()?;
+        self.queued_bytes
+            .fetch_sub(item.estimated_wire_size as usize, Ordering::SeqCst);
+        Some(item)
+    }
+}
+
+/// The sending ends of all outgoing channels for a connection to a peer.
+#[derive(Debug)]
+pub(super) struct ChannelSenders<P> {
+    consensus: ChannelSender<P>,
+    finality_signatures: ChannelSender<P>,
+    gossip: ChannelSender<P>,
+    bulk_transfer: ChannelSender<P>,
+}
+
+// Implemented manually, since deriving it would require `P: Clone`.
+impl<P> Clone for ChannelSenders<P> {
+    fn clone(&self) -> Self {
+        ChannelSenders {
+            consensus: self.consensus.clone(),
+            finality_signatures: self.finality_signatures.clone(),
+            gossip: self.gossip.clone(),
+            bulk_transfer: self.bulk_transfer.clone(),
+        }
+    }
+}
+
+impl<P> ChannelSenders<P> {
+    /// Queues a message on the given channel.
+    ///
+    /// If it cannot be queued, the message is dropped, closing its responder.
+    pub(super) fn send(
+        &self,
+        channel: Channel,
+        item: MessageQueueItem<P>,
+    ) -> Result<(), QueueError> {
+        match channel {
+            Channel::Consensus => self.consensus.send(item),
+            Channel::FinalitySignatures => self.finality_signatures.send(item),
+            Channel::Gossip => self.gossip.send(item),
+            Channel::BulkTransfer => self.bulk_transfer.send(item),
+        }
+    }
+}
+
+/// The receiving ends of all outgoing channels for a connection to a peer.
+#[derive(Debug)]
+pub(super) struct ChannelReceivers<P> {
+    consensus: ChannelReceiver<P>,
+    finality_signatures: ChannelReceiver<P>,
+    gossip: ChannelReceiver<P>,
+    bulk_transfer: ChannelReceiver<P>,
+}
+
+impl<P> ChannelReceivers<P> {
+    /// Returns the next message from the channel with the highest priority that has one queued.
+    ///
+    /// Returns `None` once all channels are closed and empty.
+    pub(super) async fn recv(&mut self) -> Option<MessageQueueItem<P>> {
+        tokio::select! {
+            biased;
+            Some(item) = self.consensus.recv() => Some(item),
+            Some(item) = self.finality_signatures.recv() => Some(item),
+            Some(item) = self.gossip.recv() => Some(item),
+            Some(item) = self.bulk_transfer.recv() => Some(item),
+            else => None,
+        }
+    }
+
+    /// Returns the next message from the channel with the highest priority that has one queued, if
+    /// that channel has a higher priority than `channel`. Does not wait for messages to be queued.
+    pub(super) fn try_recv_above(&mut self, channel: Channel) -> Option<MessageQueueItem<P>> {
+        let mut receivers = [
+            &mut self.consensus,
+            &mut self.finality_signatures,
+            &mut self.gossip,
+            &mut self.bulk_transfer,
+        ];
+        for receiver in receivers.iter_mut().take(channel.index() as usize) {
+            if let Some(item) = receiver.try_recv() {
+                return Some(item);
+            }
+        }
+        None
+    }
+
+    /// Closes all channels, while still allowing the messages already queued to be received.
+    pub(super) fn close(&mut self) {
+        self.consensus.receiver.close();
+        self.finality_signatures.receiver.close();
+        self.gossip.receiver.close();
+        self.bulk_transfer.receiver.close();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(value: u32, estimated_wire_size: u32) -> MessageQueueItem<u32> {
+        MessageQueueItem {
+            message: Arc::new(Message::Payload(value)),
+            responder: None,
+            estimated_wire_size,
+        }
+    }
+
+    fn value(item: Option<MessageQueueItem<u32>>) -> u32 {
+        match *item.expect("expected a queued message").message {
+            Message::Payload(value) => value,
+            Message::Handshake { .. } => panic!("unexpected handshake"),
+        }
+    }
+
+    #[tokio::test]
+    async fn should_send_highest_priority_first() {
+        let (senders, mut receivers) = outgoing_channels(&ChannelBudgets::default());
+
+        senders.send(Channel::BulkTransfer, item(1, 10)).unwrap();
+        senders.send(Channel::Gossip, item(2, 10)).unwrap();
+        senders.send(Channel::BulkTransfer, item(3, 10)).unwrap();
+        senders
+            .send(Channel::FinalitySignatures, item(4, 10))
+            .unwrap();
+        senders.send(Channel::Consensus, item(5, 10)).unwrap();
+
+        // Only messages of higher priority than the given channel are taken.
+        assert!(receivers.try_recv_above(Channel::Consensus).is_none());
+        assert_eq!(value(receivers.try_recv_above(Channel::Gossip)), 5);
+        senders.send(Channel::Consensus, item(5, 10)).unwrap();
+
+        assert_eq!(value(receivers.recv().await), 5);
+        assert_eq!(value(receivers.recv().await), 4);
+        assert_eq!(value(receivers.recv().await), 2);
+        assert_eq!(value(receivers.recv().await), 1);
+        assert_eq!(value(receivers.recv().await), 3);
+
+        drop(senders);
+        assert!(receivers.recv().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn should_drop_messages_over_budget() {
+        let budgets = ChannelBudgets {
+            consensus: 0,
+            finality_signatures: 0,
+            gossip: 0,
+            bulk_transfer: 100,
+        };
+        let (senders, mut receivers) = outgoing_channels(&budgets);
+
+        senders.send(Channel::BulkTransfer, item(1, 60)).unwrap();
+        assert_eq!(
+            senders.send(Channel::BulkTransfer, item(2, 60)),
+            Err(QueueError::OverBudget)
+        );
+        // Other channels are unaffected.
+        senders.send(Channel::Gossip, item(3, 1_000)).unwrap();
+
+        assert_eq!(value(receivers.recv().await), 3);
+        assert_eq!(value(receivers.recv().await), 1);
+        // Receiving the message frees up the budget.
+        senders.send(Channel::BulkTransfer, item(4, 60)).unwrap();
+        assert_eq!(value(receivers.recv().await), 4);
+
+        receivers.close();
+        assert_eq!(
+            senders.send(Channel::Consensus, item(5, 1)),
+            Err(QueueError::Closed)
+        );
+    }
+}
@@ -0,0 +1,431 @@
+//! Splitting of messages into chunks, interleaving messages on different channels.
+//!
+//! Every message is serialized as a whole, then sent as a sequence of frames carrying at most
+//! [`CHUNK_SIZE`] bytes of it each. Every chunk starts with a header byte holding the index of the
+//! message's channel, with the highest bit set on the last chunk of a message. At most one message
+//! per channel is sent at a time, so the sender can interrupt a message between any two chunks to
+//! send one of higher priority, and the receiver reassembles the messages of each channel
+//! separately.
+//!
+//! A consensus message is thus delayed by at most a single chunk of a bulk transfer, regardless of
+//! the size of the latter.
+//!
+//! Chunking is only used if both peers offered it in their handshakes. Otherwise every message is
+//! sent as a single frame without a header byte, as by nodes not supporting chunking.
+//!
+//! If compression was negotiated, messages of at least the compression threshold are serialized
+//! and deserialized on a blocking thread, as compressing a large message can take hundreds of
+//! milliseconds.
+
+use std::{
+    fmt::{self, Debug, Formatter},
+    io,
+    marker::PhantomData,
+    mem,
+    pin::Pin,
+    sync::{Arc, Mutex},
+};
+
+use bytes::{BufMut, Bytes, BytesMut};
+use futures::{
+    stream::{SplitSink, SplitStream},
+    SinkExt, StreamExt,
+};
+use tokio::task;
+use tokio_serde::{Deserializer, Serializer};
+
+use super::{Channel, FramedTransport, Message, MessageFormat, Payload};
+use crate::effect::AutoClosingResponder;
+
+/// Maximum number of bytes of a message carried by a single chunk: 64 KiB.
+pub(super) const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Flag set in the header byte of the last chunk of a message.
+const LAST_CHUNK_FLAG: u8 = 0x80;
+
+/// A serialized message being sent in chunks.
+pub(super) struct OutgoingMessage {
+    /// The channel the message is sent over.
+    channel: Channel,
+    /// The part of the serialized message not sent yet.
+    remaining: Bytes,
+    /// Whether the last chunk has been taken.
+    finished: bool,
+    /// An optional responder to call once the message has been sent.
+    responder: Option<AutoClosingResponder<()>>,
+}
+
+impl OutgoingMessage {
+    /// Creates a new message to be sent in chunks.
+    pub(super) fn new(
+        channel: Channel,
+        serialized: Bytes,
+        responder: Option<AutoClosingResponder<()>>,
+    ) -> Self {
+        OutgoingMessage {
+            channel,
+            remaining: serialized,
+            finished: false,
+            responder,
+        }
+    }
+
+    /// Returns the channel the message is sent over.
+    pub(super) fn channel(&self) -> Channel {
+        self.channel
+    }
+
+    /// Returns whether all chunks of the message have been taken.
+    pub(super) fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Consumes the message, returning its responder.
+    pub(super) fn into_responder(self) -> Option<AutoClosingResponder<()>> {
+        self.responder
+    }
+
+    /// Returns the next chunk of the message, including its header byte.
+    fn next_chunk(&mut self) -> Bytes {
+        let data = self
+            .remaining
+            .split_to(self.remaining.len().min(CHUNK_SIZE));
+        self.finished = self.remaining.is_empty();
+
+        let mut header = self.channel.index();
+        if self.finished {
+            header |= LAST_CHUNK_FLAG;
+        }
+        let mut chunk = BytesMut::with_capacity(data.len() + 1);
+        chunk.put_u8(header);
+        chunk.put_slice(&data);
+        chunk.freeze()
+    }
+
+    /// Returns the whole remaining message, without a header byte.
+    fn take_remaining(&mut self) -> Bytes {
+        self.finished = true;
+        mem::take(&mut self.remaining)
+    }
+}
+
+impl Debug for OutgoingMessage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OutgoingMessage")
+            .field("channel", &self.channel)
+            .field("remaining", &self.remaining.len())
+            .field("finished", &self.finished)
+            .finish()
+    }
+}
+
+/// Reassembles messages from the chunks received on each channel.
+#[derive(Debug)]
+struct Reassembler {
+    /// The received part of the message currently being received on each channel.
+    partial: [BytesMut; Channel::COUNT],
+    /// Maximum size of a reassembled message.
+    maximum_message_size: usize,
+}
+
+impl Reassembler {
+    fn new(maximum_message_size: usize) -> Self {
+        Reassembler {
+            partial: Default::default(),
+            maximum_message_size,
+        }
+    }
+
+    /// Adds a received chunk, returning the complete message if it was the last chunk of one.
+    fn push(&mut self, mut chunk: BytesMut) -> io::Result<Option<BytesMut>> {
+        if chunk.is_empty() {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "empty chunk"));
+        }
+        let header = chunk.split_to(1)[0];
+        let index = header & !LAST_CHUNK_FLAG;
+        let partial = self.partial.get_mut(index as usize).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("chunk for unknown channel {}", index),
+            )
+        })?;
+
+        if partial.len() + chunk.len() > self.maximum_message_size {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "chunked message exceeds maximum message size",
+            ));
+        }
+        // Most messages fit into a single chunk, which can then be used without copying.
+        if partial.is_empty() {
+            *partial = chunk;
+        } else {
+            partial.extend_from_slice(&chunk);
+        }
+
+        if header & LAST_CHUNK_FLAG == 0 {
+            Ok(None)
+        } else {
+            Ok(Some(mem::take(partial)))
+        }
+    }
+}
+
+/// Runs `f` with exclusive access to the given format, on a blocking thread if `blocking` is set.
+async fn with_format<F, T>(
+    format: &Arc<Mutex<MessageFormat>>,
+    blocking: bool,
+    f: F,
+) -> io::Result<T>
+where
+    F: FnOnce(Pin<&mut MessageFormat>) -> io::Result<T> + Send + 'static,
+    T: Send + 'static,
+{
+    let format = Arc::clone(format);
+    let run = move || {
+        let mut format = format
+            .lock()
+            .map_err(|_| io::Error::new(io::ErrorKind::Other, "message format lock poisoned"))?;
+        f(Pin::new(&mut *format))
+    };
+    if blocking {
+        task::spawn_blocking(run)
+            .await
+            .map_err(|error| io::Error::new(io::ErrorKind::Other, error))?
+    } else {
+        run()
+    }
+}
+
+/// Returns whether a message of the given size is possibly compressed, and should thus be
+/// serialized or deserialized on a blocking thread.
+fn is_compressible(compression_threshold: Option<u32>, size: usize) -> bool {
+    compression_threshold.map_or(false, |threshold| size >= threshold as usize)
+}
+
+/// The sending half of a connection, sending messages in chunks.
+#[derive(Debug)]
+pub(crate) struct ChunkSink<P> {
+    /// The sink chunks are written to.
+    sink: SplitSink<FramedTransport, Bytes>,
+    /// The format messages are serialized with.
+    format: Arc<Mutex<MessageFormat>>,
+    /// The compression threshold, if compression was negotiated for the connection.
+    compression_threshold: Option<u32>,
+    /// Whether chunking was negotiated for the connection.
+    chunking: bool,
+    _payload: PhantomData<P>,
+}
+
+impl<P> ChunkSink<P>
+where
+    P: Payload,
+{
+    /// Creates a new chunk sink.
+    pub(super) fn new(
+        sink: SplitSink<FramedTransport, Bytes>,
+        format: MessageFormat,
+        compression_threshold: Option<u32>,
+        chunking: bool,
+    ) -> Self {
+        ChunkSink {
+            sink,
+            format: Arc::new(Mutex::new(format)),
+            compression_threshold,
+            chunking,
+            _payload: PhantomData,
+        }
+    }
+
+    /// Serializes a message of the given estimated size, to be sent in chunks.
+    pub(super) async fn serialize(
+        &mut self,
+        message: &Arc<Message<P>>,
+        estimated_wire_size: u32,
+        responder: Option<AutoClosingResponder<()>>,
+    ) -> io::Result<OutgoingMessage> {
+        let channel = message.channel();
+        let message = Arc::clone(message);
+        let blocking = is_compressible(self.compression_threshold, estimated_wire_size as usize);
+        let serialized = with_format(&self.format, blocking, move |format| {
+            format.serialize(&message)
+        })
+        .await?;
+        Ok(OutgoingMessage::new(channel, serialized, responder))
+    }
+
+    /// Sends the next chunk of the given message, or all of it if chunking was not negotiated.
+    pub(super) async fn send_chunk(&mut self, message: &mut OutgoingMessage) -> io::Result<()> {
+        let frame = if self.chunking {
+            message.next_chunk()
+        } else {
+            message.take_remaining()
+        };
+        self.sink.send(frame).await
+    }
+
+    /// Flushes all chunks sent so far to the kernel.
+    pub(super) async fn flush(&mut self) -> io::Result<()> {
+        self.sink.flush().await
+    }
+}
+
+/// The receiving half of a connection, reassembling messages from chunks.
+#[derive(Debug)]
+pub(crate) struct ChunkStream<P> {
+    /// The stream chunks are read from.
+    stream: SplitStream<FramedTransport>,
+    /// The format messages are deserialized with.
+    format: Arc<Mutex<MessageFormat>>,
+    /// The compression threshold, if compression was negotiated for the connection.
+    compression_threshold: Option<u32>,
+    /// Whether chunking was negotiated for the connection.
+    chunking: bool,
+    /// The messages being received.
+    reassembler: Reassembler,
+    _payload: PhantomData<P>,
+}
+
+impl<P> ChunkStream<P>
+where
+    P: Payload,
+{
+    /// Creates a new chunk stream, accepting serialized messages of up to `maximum_message_size`
+    /// bytes.
+    pub(super) fn new(
+        stream: SplitStream<FramedTransport>,
+        format: MessageFormat,
+        compression_threshold: Option<u32>,
+        chunking: bool,
+        maximum_message_size: usize,
+    ) -> Self {
+        ChunkStream {
+            stream,
+            format: Arc::new(Mutex::new(format)),
+            compression_threshold,
+            chunking,
+            reassembler: Reassembler::new(maximum_message_size),
+            _payload: PhantomData,
+        }
+    }
+
+    /// Receives the next complete message.
+    ///
+    /// Returns `None` once the stream is closed.
+    pub(super) async fn recv(&mut self) -> Option<io::Result<Message<P>>> {
+        while let Some(frame) = self.stream.next().await {
+            let reassembled = if self.chunking {
+                frame.and_then(|chunk| self.reassembler.push(chunk))
+            } else {
+                // The frame holds a whole message, its size limited by the framing.
+                frame.map(Some)
+            };
+            match reassembled {
+                Ok(Some(serialized)) => {
+                    let blocking = is_compressible(self.compression_threshold, serialized.len());
+                    return Some(
+                        with_format(&self.format, blocking, move |format| {
+                            format.deserialize(&serialized)
+                        })
+                        .await,
+                    );
+                }
+                Ok(None) => (),
+                Err(error) => return Some(Err(error)),
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Maximum message size used in tests.
+    const MAXIMUM_MESSAGE_SIZE: usize = 4 * CHUNK_SIZE;
+
+    fn chunks(message: &mut OutgoingMessage) -> Vec<BytesMut> {
+        let mut chunks = vec![];
+        while !message.is_finished() {
+            chunks.push(BytesMut::from(&message.next_chunk()[..]));
+        }
+        chunks
+    }
+
+    #[test]
+    fn should_reassemble_interleaved_messages() {
+        let bulk = vec![1; 2 * CHUNK_SIZE + 10];
+        let consensus = vec![2; 10];
+        let mut bulk_chunks = chunks(&mut OutgoingMessage::new(
+            Channel::BulkTransfer,
+            Bytes::from(bulk.clone()),
+            None,
+        ));
+        let mut consensus_chunks = chunks(&mut OutgoingMessage::new(
+            Channel::Consensus,
+            Bytes::from(consensus.clone()),
+            None,
+        ));
+        assert_eq!(bulk_chunks.len(), 3);
+        assert_eq!(consensus_chunks.len(), 1);
+        assert!(bulk_chunks
+            .iter()
+            .all(|chunk| chunk.len() <= CHUNK_SIZE + 1));
+
+        // The consensus message is sent between the chunks of the bulk transfer.
+        let mut reassembler = Reassembler::new(MAXIMUM_MESSAGE_SIZE);
+        assert_eq!(reassembler.push(bulk_chunks.remove(0)).unwrap(), None);
+        assert_eq!(
+            reassembler.push(consensus_chunks.remove(0)).unwrap(),
+            Some(BytesMut::from(&consensus[..]))
+        );
+        assert_eq!(reassembler.push(bulk_chunks.remove(0)).unwrap(), None);
+        assert_eq!(
+            reassembler.push(bulk_chunks.remove(0)).unwrap(),
+            Some(BytesMut::from(&bulk[..]))
+        );
+
+        // Empty messages are sent as a single chunk as well.
+        let mut empty_chunks = chunks(&mut OutgoingMessage::new(
+            Channel::Gossip,
+            Bytes::new(),
+            None,
+        ));
+        assert_eq!(empty_chunks.len(), 1);
+        assert_eq!(
+            reassembler.push(empty_chunks.remove(0)).unwrap(),
+            Some(BytesMut::new())
+        );
+    }
+
+    #[test]
+    fn should_take_whole_message_without_header() {
+        let serialized = vec![3; 2 * CHUNK_SIZE];
+        let mut message =
+            OutgoingMessage::new(Channel::BulkTransfer, Bytes::from(serialized.clone()), None);
+        assert_eq!(message.take_remaining(), Bytes::from(serialized));
+        assert!(message.is_finished());
+    }
+
+    #[test]
+    fn should_reject_invalid_chunks() {
+        let mut reassembler = Reassembler::new(MAXIMUM_MESSAGE_SIZE);
+        assert!(reassembler.push(BytesMut::new()).is_err());
+        assert!(reassembler
+            .push(BytesMut::from(&[Channel::COUNT as u8, 0][..]))
+            .is_err());
+
+        let oversized = vec![0; MAXIMUM_MESSAGE_SIZE + 1];
+        let mut reassembler = Reassembler::new(MAXIMUM_MESSAGE_SIZE);
+        let results: Vec<_> = chunks(&mut OutgoingMessage::new(
+            Channel::BulkTransfer,
+            Bytes::from(oversized),
+            None,
+        ))
+        .into_iter()
+        .map(|chunk| reassembler.push(chunk))
+        .collect();
+        assert!(results.last().unwrap().is_err());
+    }
+}
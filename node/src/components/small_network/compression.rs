@@ -0,0 +1,292 @@
+//! Per-frame compression of network messages.
+//!
+//! Compression is negotiated during the handshake: it is used on a connection only if both peers
+//! advertise it, otherwise frames are passed through unchanged, keeping the wire format compatible
+//! with nodes that do not support compression.
+//!
+//! Once negotiated, every frame is prefixed with a single tag byte indicating whether the remainder
+//! is compressed. Only frames of at least the configured threshold size are compressed, and only if
+//! compressing them actually saves space. An uncompressed frame of maximum message size thus
+//! exceeds the latter by the tag byte, which the framing allows for.
+//!
+//! Compressing a large frame can take hundreds of milliseconds, so frames of at least the threshold
+//! size are serialized and deserialized on a blocking thread rather than the connection's task.
+
+use std::{
+    io::{self, Read, Write},
+    pin::Pin,
+    sync::{Arc, Weak},
+    time::Instant,
+};
+
+use bytes::{BufMut, Bytes, BytesMut};
+use datasize::DataSize;
+use flate2::{read::DeflateDecoder, write::DeflateEncoder};
+use pin_project::pin_project;
+use serde::{Deserialize, Serialize};
+use tokio_serde::{Deserializer, Serializer};
+
+use super::{Message, Metrics};
+
+/// Default minimum size of a frame to be compressed: 4 KiB.
+const DEFAULT_THRESHOLD: u32 = 4 * 1024;
+
+/// Default compression level, a balance between speed and compression ratio.
+const DEFAULT_LEVEL: u32 = 6;
+
+/// Highest compression level supported.
+const MAX_LEVEL: u32 = 9;
+
+/// Tag byte of a frame sent as-is.
+const TAG_UNCOMPRESSED: u8 = 0;
+
+/// Tag byte of a deflate-compressed frame.
+const TAG_DEFLATE: u8 = 1;
+
+/// Compression configuration.
+#[derive(DataSize, Debug, Clone, Deserialize, Serialize)]
+// Disallow unknown fields to ensure config files and command-line overrides contain valid keys.
+#[serde(deny_unknown_fields)]
+pub struct CompressionConfig {
+    /// Whether to offer compression to peers. It is only used if the peer offers it as well.
+    pub enabled: bool,
+    /// Minimum size in bytes of a frame to be compressed.
+    pub threshold: u32,
+    /// Compression level, from 0 (fastest) to 9 (smallest). Higher values are treated as 9.
+    pub level: u32,
+}
+
+impl Default for CompressionConfig {
+    fn default() -> Self {
+        CompressionConfig {
+            enabled: true,
+            threshold: DEFAULT_THRESHOLD,
+            level: DEFAULT_LEVEL,
+        }
+    }
+}
+
+/// A compressing serializer/deserializer wrapper for network messages.
+///
+/// Compresses the frames produced by the wrapped format and decompresses frames before handing them
+/// to it, if compression was negotiated for the connection.
+#[pin_project]
+#[derive(Debug)]
+pub struct CompressionFormat<F> {
+    /// The actual serializer performing the work.
+    #[pin]
+    inner: F,
+    /// The compression configuration, if compression was negotiated for the connection.
+    config: Option<CompressionConfig>,
+    /// Maximum size of a decompressed frame.
+    maximum_frame_size: usize,
+    /// Metrics to update.
+    metrics: Weak<Metrics>,
+}
+
+impl<F> CompressionFormat<F> {
+    /// Creates a new compression format.
+    ///
+    /// If `config` is `None`, frames are passed through unchanged.
+    #[inline]
+    pub(super) fn new(
+        metrics: Weak<Metrics>,
+        config: Option<CompressionConfig>,
+        maximum_frame_size: u32,
+        inner: F,
+    ) -> Self {
+        Self {
+            inner,
+            config,
+            maximum_frame_size: maximum_frame_size as usize,
+            metrics,
+        }
+    }
+}
+
+/// Creates a frame with the given tag byte, followed by `data`.
+fn tagged(tag: u8, data: &[u8]) -> Bytes {
+    let mut frame = BytesMut::with_capacity(data.len() + 1);
+    frame.put_u8(tag);
+    frame.put_slice(data);
+    frame.freeze()
+}
+
+impl<F, P> Serializer<Arc<Message<P>>> for CompressionFormat<F>
+where
+    F: Serializer<Arc<Message<P>>, Error = io::Error>,
+{
+    type Error = io::Error;
+
+    fn serialize(self: Pin<&mut Self>, item: &Arc<Message<P>>) -> Result<Bytes, Self::Error> {
+        let this = self.project();
+        let serialized = F::serialize(this.inner, item)?;
+
+        let config = match this.config {
+            Some(config) => config,
+            None => return Ok(serialized),
+        };
+
+        if serialized.len() < config.threshold as usize {
+            return Ok(tagged(TAG_UNCOMPRESSED, &serialized));
+        }
+
+        let start = Instant::now();
+        let mut buffer = Vec::with_capacity(serialized.len() / 2 + 1);
+        buffer.push(TAG_DEFLATE);
+        let mut encoder = DeflateEncoder::new(
+            buffer,
+            flate2::Compression::new(config.level.min(MAX_LEVEL)),
+        );
+        encoder.write_all(&serialized)?;
+        let compressed = encoder.finish()?;
+
+        // Frames that do not shrink, e.g. because they contain already compressed data, are sent
+        // uncompressed instead.
+        let frame = if compressed.len() <= serialized.len() {
+            Bytes::from(compressed)
+        } else {
+            tagged(TAG_UNCOMPRESSED, &serialized)
+        };
+        Metrics::record_compression(
+            this.metrics,
+            serialized.len() as u64,
+            frame.len() as u64 - 1,
+            start.elapsed(),
+        );
+
+        Ok(frame)
+    }
+}
+
+impl<F, P> Deserializer<Message<P>> for CompressionFormat<F>
+where
+    F: Deserializer<Message<P>, Error = io::Error>,
+{
+    type Error = io::Error;
+
+    fn deserialize(self: Pin<&mut Self>, src: &BytesMut) -> Result<Message<P>, Self::Error> {
+        let this = self.project();
+
+        if this.config.is_none() {
+            return F::deserialize(this.inner, src);
+        }
+
+        let (tag, data) = src
+            .split_first()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "empty frame"))?;
+
+        match *tag {
+            TAG_UNCOMPRESSED => F::deserialize(this.inner, &BytesMut::from(data)),
+            TAG_DEFLATE => {
+                let start = Instant::now();
+                // We read at most one byte more than allowed, to detect oversized frames without
+                // decompressing all of them.
+                let mut decoder =
+                    DeflateDecoder::new(data).take(*this.maximum_frame_size as u64 + 1);
+                let mut writer = BytesMut::with_capacity(data.len() * 2).writer();
+                io::copy(&mut decoder, &mut writer)?;
+                let decompressed = writer.into_inner();
+
+                if decompressed.len() > *this.maximum_frame_size {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        "decompressed frame exceeds maximum message size",
+                    ));
+                }
+                Metrics::record_decompression(
+                    this.metrics,
+                    data.len() as u64,
+                    decompressed.len() as u64,
+                    start.elapsed(),
+                );
+
+                F::deserialize(this.inner, &decompressed)
+            }
+            other => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("unknown frame compression tag {}", other),
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bincode::Options;
+
+    use super::*;
+    use crate::components::small_network::BincodeFormat;
+
+    /// Maximum frame size used in tests.
+    const MAXIMUM_FRAME_SIZE: u32 = 1024 * 1024;
+
+    fn format(config: Option<CompressionConfig>) -> CompressionFormat<BincodeFormat> {
+        CompressionFormat::new(
+            Weak::new(),
+            config,
+            MAXIMUM_FRAME_SIZE,
+            BincodeFormat::default(),
+        )
+    }
+
+    fn roundtrip(
+        format: &mut CompressionFormat<BincodeFormat>,
+        payload: Vec<u8>,
+    ) -> (Bytes, Vec<u8>) {
+        let frame = Pin::new(&mut *format)
+            .serialize(&Arc::new(Message::Payload(payload)))
+            .expect("could not serialize");
+        let message: Message<Vec<u8>> = Pin::new(format)
+            .deserialize(&BytesMut::from(&frame[..]))
+            .expect("could not deserialize");
+        match message {
+            Message::Payload(payload) => (frame, payload),
+            Message::Handshake { .. } => panic!("unexpected handshake"),
+        }
+    }
+
+    #[test]
+    fn should_compress_large_frames_only() {
+        let mut format = format(Some(CompressionConfig::default()));
+
+        let small = vec![0xAB; 16];
+        let (frame, payload) = roundtrip(&mut format, small.clone());
+        assert_eq!(frame[0], TAG_UNCOMPRESSED);
+        assert_eq!(payload, small);
+
+        let large = vec![0xAB; 64 * 1024];
+        let (frame, payload) = roundtrip(&mut format, large.clone());
+        assert_eq!(frame[0], TAG_DEFLATE);
+        assert!(frame.len() < large.len() / 10);
+        assert_eq!(payload, large);
+    }
+
+    #[test]
+    fn should_pass_frames_through_if_not_negotiated() {
+        let mut format = format(None);
+        let large = vec![0xAB; 64 * 1024];
+
+        let expected = BincodeFormat::default()
+            .0
+            .serialize(&Message::Payload(large.clone()))
+            .unwrap();
+        let (frame, payload) = roundtrip(&mut format, large.clone());
+        assert_eq!(&frame[..], &expected[..]);
+        assert_eq!(payload, large);
+    }
+
+    #[test]
+    fn should_reject_oversized_decompressed_frames() {
+        let mut encoder = DeflateEncoder::new(vec![TAG_DEFLATE], flate2::Compression::best());
+        encoder
+            .write_all(&vec![0; MAXIMUM_FRAME_SIZE as usize + 1])
+            .unwrap();
+        let frame = encoder.finish().unwrap();
+
+        let mut format = format(Some(CompressionConfig::default()));
+        let result: Result<Message<Vec<u8>>, _> =
+            Pin::new(&mut format).deserialize(&BytesMut::from(&frame[..]));
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::InvalidData);
+    }
+}
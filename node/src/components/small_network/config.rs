@@ -6,7 +6,10 @@ use casper_types::{ProtocolVersion, TimeDiff};
 use datasize::DataSize;
 use serde::{Deserialize, Serialize};
 
-use super::EstimatorWeights;
+use super::{
+    channel::ChannelBudgets, compression::CompressionConfig, peer_scores::PeerScoringConfig,
+    EstimatorWeights,
+};
 
 /// Default binding address.
 ///
@@ -45,6 +48,9 @@ impl Default for Config {
             max_outgoing_byte_rate_non_validators: 0,
             max_incoming_message_rate_non_validators: 0,
             estimator_weights: Default::default(),
+            channel_budgets: Default::default(),
+            compression: Default::default(),
+            peer_scoring: Default::default(),
             tarpit_version_threshold: None,
             tarpit_duration: TimeDiff::from_seconds(600),
             tarpit_chance: 0.2,
@@ -97,6 +103,15 @@ pub struct Config {
     pub max_incoming_message_rate_non_validators: u32,
     /// Weight distribution for the payload impact estimator.
     pub estimator_weights: EstimatorWeights,
+    /// Maximum number of bytes queued for a single peer on each outgoing channel.
+    #[serde(default)]
+    pub channel_budgets: ChannelBudgets,
+    /// Compression of messages sent to peers supporting it.
+    #[serde(default)]
+    pub compression: CompressionConfig,
+    /// Scoring of peers based on their behavior.
+    #[serde(default)]
+    pub peer_scoring: PeerScoringConfig,
     /// The protocol version at which (or under) tarpitting is enabled.
     pub tarpit_version_threshold: Option<ProtocolVersion>,
     /// If tarpitting is enabled, duration for which connections should be kept open.
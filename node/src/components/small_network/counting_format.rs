@@ -220,6 +220,12 @@ impl TlsRandomData {
     }
 }
 
+impl From<ConnectionId> for Digest {
+    fn from(connection_id: ConnectionId) -> Self {
+        Digest::from(connection_id.0)
+    }
+}
+
 impl ConnectionId {
     /// Creates a new connection ID, based on random values from server and client, as well as
     /// node IDs.
@@ -1,7 +1,7 @@
-use std::{error, io, net::SocketAddr, result, sync::Arc};
+use std::{error, io, net::SocketAddr, result};
 
 use casper_hashing::Digest;
-use casper_types::{crypto, ProtocolVersion, SecretKey};
+use casper_types::{crypto, ProtocolVersion};
 use datasize::DataSize;
 use openssl::{error::ErrorStack, ssl};
 use serde::Serialize;
@@ -9,7 +9,7 @@ use thiserror::Error;
 
 use crate::{
     tls::{LoadCertError, ValidationError},
-    utils::{LoadError, Loadable, ResolveAddressError},
+    utils::ResolveAddressError,
 };
 
 pub(super) type Result<T> = result::Result<T, Error>;
@@ -25,7 +25,7 @@ pub enum Error {
     LoadConsensusKeys(
         #[serde(skip_serializing)]
         #[source]
-        LoadError<<Arc<SecretKey> as Loadable>::Error>,
+        Box<dyn error::Error + Send + Sync>,
     ),
     /// Failed to create a TCP listener.
     #[error("failed to create listener on {1}")]
@@ -175,6 +175,22 @@ pub enum ConnectionError {
     /// Peer did not send any message, or a non-handshake as its first message.
     #[error("peer did not send handshake")]
     DidNotSendHandshake,
+    /// Failed to sign our handshake with the validator's key.
+    #[error("could not sign our handshake: {0}")]
+    CouldNotSignOurHandshake(
+        #[serde(skip_serializing)]
+        #[source]
+        Box<dyn error::Error + Send + Sync>,
+    ),
+    /// The background task signing our handshake panicked or crashed.
+    ///
+    /// This is usually a bug.
+    #[error("handshake signer crashed")]
+    HandshakeSignerCrashed(
+        #[serde(skip_serializing)]
+        #[source]
+        tokio::task::JoinError,
+    ),
     /// Failed to encode our handshake.
     #[error("could not encode our handshake")]
     CouldNotEncodeOurHandshake(
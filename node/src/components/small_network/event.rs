@@ -2,21 +2,23 @@ use std::{
     fmt::{self, Debug, Display, Formatter},
     io, mem,
     net::SocketAddr,
-    sync::Arc,
 };
 
 use casper_types::PublicKey;
 use derive_more::From;
-use futures::stream::{SplitSink, SplitStream};
 use serde::Serialize;
 use static_assertions::const_assert;
 use tracing::Span;
 
-use super::{error::ConnectionError, FullTransport, GossipedAddress, Message, NodeId};
+use super::{
+    chunking::{ChunkSink, ChunkStream},
+    error::ConnectionError,
+    GossipedAddress, Message, NodeId,
+};
 use crate::{
     effect::{
         announcements::{
-            BlocklistAnnouncement, ChainSynchronizerAnnouncement, ContractRuntimeAnnouncement,
+            ChainSynchronizerAnnouncement, ContractRuntimeAnnouncement, PeerBehaviorAnnouncement,
         },
         requests::{NetworkInfoRequest, NetworkRequest},
     },
@@ -92,11 +94,11 @@ pub(crate) enum Event<P> {
 
     /// Blocklist announcement.
     #[from]
-    BlocklistAnnouncement(BlocklistAnnouncement),
+    PeerBehaviorAnnouncement(PeerBehaviorAnnouncement),
 
     /// Contract runtime announcement.
     #[from]
-    ContractRuntimeAnnouncement(ContractRuntimeAnnouncement),
+    ContractRuntimeAnnouncement(Box<ContractRuntimeAnnouncement>),
 
     /// Chain synchronizer announcement.
     #[from]
@@ -109,6 +111,12 @@ impl From<NetworkRequest<ProtocolMessage>> for Event<ProtocolMessage> {
     }
 }
 
+impl From<ContractRuntimeAnnouncement> for Event<ProtocolMessage> {
+    fn from(ann: ContractRuntimeAnnouncement) -> Self {
+        Self::ContractRuntimeAnnouncement(Box::new(ann))
+    }
+}
+
 impl From<NetworkInfoRequest> for Event<ProtocolMessage> {
     fn from(req: NetworkInfoRequest) -> Self {
         Self::NetworkInfoRequest { req: Box::new(req) }
@@ -141,8 +149,8 @@ impl<P: Display> Display for Event<P> {
             Event::PeerAddressReceived(gossiped_address) => {
                 write!(f, "received gossiped peer address {}", gossiped_address)
             }
-            Event::BlocklistAnnouncement(ann) => {
-                write!(f, "handling blocklist announcement: {}", ann)
+            Event::PeerBehaviorAnnouncement(ann) => {
+                write!(f, "handling peer behavior announcement: {}", ann)
             }
             Event::ContractRuntimeAnnouncement(ann) => {
                 write!(f, "handling contract runtime announcement: {}", ann)
@@ -190,7 +198,7 @@ pub(crate) enum IncomingConnection<P> {
         peer_consensus_public_key: Option<PublicKey>,
         /// Stream of incoming messages. for incoming connections.
         #[serde(skip_serializing)]
-        stream: SplitStream<FullTransport<P>>,
+        stream: Box<ChunkStream<P>>,
     },
 }
 
@@ -260,7 +268,7 @@ pub(crate) enum OutgoingConnection<P> {
         peer_consensus_public_key: Option<PublicKey>,
         /// Sink for outgoing messages.
         #[serde(skip_serializing)]
-        sink: SplitSink<FullTransport<P>, Arc<Message<P>>>,
+        sink: Box<ChunkSink<P>>,
         /// Holds the information whether the remote node is syncing.
         is_syncing: bool,
     },
@@ -7,7 +7,9 @@ use std::{
 use casper_hashing::Digest;
 #[cfg(test)]
 use casper_types::testing::TestRng;
-use casper_types::{crypto, AsymmetricType, ProtocolVersion, PublicKey, SecretKey, Signature};
+#[cfg(test)]
+use casper_types::SecretKey;
+use casper_types::{crypto, AsymmetricType, ProtocolVersion, PublicKey, Signature};
 use datasize::DataSize;
 use futures::future::BoxFuture;
 use serde::{
@@ -15,9 +17,19 @@ use serde::{
     Deserialize, Deserializer, Serialize, Serializer,
 };
 
-use crate::{effect::EffectBuilder, types::NodeId, utils::opt_display::OptDisplay};
+#[cfg(test)]
+use crate::components::consensus::signer::LocalSigner;
+use crate::{
+    components::consensus::{
+        signer::{SignerError, SigningRequest},
+        Signer,
+    },
+    effect::EffectBuilder,
+    types::NodeId,
+    utils::opt_display::OptDisplay,
+};
 
-use super::counting_format::ConnectionId;
+use super::{channel::Channel, counting_format::ConnectionId};
 
 /// The default protocol version to use in absence of one in the protocol version field.
 #[inline]
@@ -45,6 +57,12 @@ pub(crate) enum Message<P> {
         /// Hash of the chainspec the node is running.
         #[serde(default)]
         chainspec_hash: Option<Digest>,
+        /// True if the node offers to compress messages on the connection.
+        #[serde(default)]
+        compression: bool,
+        /// True if the node offers to split messages into chunks on the connection.
+        #[serde(default)]
+        chunking: bool,
     },
     Payload(P),
 }
@@ -59,6 +77,15 @@ impl<P: Payload> Message<P> {
         }
     }
 
+    /// Returns the outgoing channel the message is sent over.
+    #[inline]
+    pub(super) fn channel(&self) -> Channel {
+        match self {
+            Message::Handshake { .. } => Channel::Consensus,
+            Message::Payload(payload) => payload.channel(),
+        }
+    }
+
     /// Determines whether or not a message is low priority.
     #[inline]
     pub(super) fn is_low_priority(&self) -> bool {
@@ -108,24 +135,24 @@ impl<P: Payload> Message<P> {
     }
 }
 
-/// A pair of secret keys used by consensus.
+/// The validator's consensus key, used to sign handshakes.
+#[derive(Clone)]
 pub(super) struct ConsensusKeyPair {
-    secret_key: Arc<SecretKey>,
-    public_key: PublicKey,
+    signer: Arc<dyn Signer>,
 }
 
 impl ConsensusKeyPair {
     /// Creates a new key pair for consensus signing.
-    pub(super) fn new(secret_key: Arc<SecretKey>, public_key: PublicKey) -> Self {
-        Self {
-            secret_key,
-            public_key,
-        }
+    pub(super) fn new(signer: Arc<dyn Signer>) -> Self {
+        Self { signer }
     }
 
-    /// Sign a value using this keypair.
-    fn sign<T: AsRef<[u8]>>(&self, value: T) -> Signature {
-        crypto::sign(value, &self.secret_key, &self.public_key)
+    /// Signs the connection ID.
+    fn sign(&self, connection_id: ConnectionId) -> Result<Signature, SignerError> {
+        let request = SigningRequest::Handshake {
+            connection_id: connection_id.into(),
+        };
+        self.signer.sign(&request)
     }
 }
 
@@ -142,12 +169,18 @@ pub(crate) struct ConsensusCertificate {
 
 impl ConsensusCertificate {
     /// Creates a new consensus certificate from a connection ID and key pair.
-    pub(super) fn create(connection_id: ConnectionId, key_pair: &ConsensusKeyPair) -> Self {
-        let signature = key_pair.sign(connection_id.as_bytes());
-        ConsensusCertificate {
-            public_key: key_pair.public_key.clone(),
+    ///
+    /// Signing may block while waiting for a remote signer, so this must not be called on an
+    /// async executor thread.
+    pub(super) fn create(
+        connection_id: ConnectionId,
+        key_pair: &ConsensusKeyPair,
+    ) -> Result<Self, SignerError> {
+        let signature = key_pair.sign(connection_id)?;
+        Ok(ConsensusCertificate {
+            public_key: key_pair.signer.public_key().clone(),
             signature,
-        }
+        })
     }
 
     /// Validates a certificate, returning a `PublicKey` if valid.
@@ -160,11 +193,12 @@ impl ConsensusCertificate {
     #[cfg(test)]
     fn random(rng: &mut TestRng) -> Self {
         let secret_key = SecretKey::random(rng);
-        let public_key = PublicKey::from(&secret_key);
+        let signer = LocalSigner::new(Arc::new(secret_key));
         ConsensusCertificate::create(
             ConnectionId::random(rng),
-            &ConsensusKeyPair::new(Arc::new(secret_key), public_key),
+            &ConsensusKeyPair::new(Arc::new(signer)),
         )
+        .expect("local signer should not fail")
     }
 }
 
@@ -265,16 +299,20 @@ impl<P: Display> Display for Message<P> {
                 consensus_certificate,
                 is_syncing,
                 chainspec_hash,
+                compression,
+                chunking,
             } => {
                 write!(
                     f,
-                    "handshake: {}, public addr: {}, protocol_version: {}, consensus_certificate: {}, is_syncing: {}, chainspec_hash: {}",
+                    "handshake: {}, public addr: {}, protocol_version: {}, consensus_certificate: {}, is_syncing: {}, chainspec_hash: {}, compression: {}, chunking: {}",
                     network_name,
                     public_addr,
                     protocol_version,
                     OptDisplay::new(consensus_certificate.as_ref(), "none"),
                     is_syncing,
-                    OptDisplay::new(chainspec_hash.as_ref(), "none")
+                    OptDisplay::new(chainspec_hash.as_ref(), "none"),
+                    compression,
+                    chunking
                 )
             }
             Message::Payload(payload) => write!(f, "payload: {}", payload),
@@ -331,6 +369,11 @@ pub(crate) trait Payload:
     /// Classifies the payload based on its contents.
     fn classify(&self) -> MessageKind;
 
+    /// Returns the outgoing channel the payload is sent over.
+    fn channel(&self) -> Channel {
+        self.classify().into()
+    }
+
     /// The penalty for resource usage of a message to be applied when processed as incoming.
     fn incoming_resource_estimate(&self, _weights: &EstimatorWeights) -> u32;
 
@@ -562,6 +605,8 @@ mod tests {
             consensus_certificate: Some(ConsensusCertificate::random(&mut rng)),
             is_syncing: false,
             chainspec_hash: Some(Digest::hash("example-chainspec")),
+            compression: true,
+            chunking: true,
         };
 
         let legacy_handshake: V1_0_0_Message = roundtrip_message(&modern_handshake);
@@ -597,13 +642,17 @@ mod tests {
                 consensus_certificate,
                 is_syncing,
                 chainspec_hash,
+                compression,
+                chunking,
             } => {
                 assert_eq!(network_name, "example-handshake");
                 assert_eq!(public_addr, ([12, 34, 56, 78], 12346).into());
                 assert_eq!(protocol_version, ProtocolVersion::V1_0_0);
                 assert!(consensus_certificate.is_none());
                 assert!(!is_syncing);
-                assert!(chainspec_hash.is_none())
+                assert!(chainspec_hash.is_none());
+                assert!(!compression);
+                assert!(!chunking);
             }
             Message::Payload(_) => {
                 panic!("did not expect modern handshake to deserialize to payload")
@@ -623,6 +672,8 @@ mod tests {
                 consensus_certificate,
                 is_syncing,
                 chainspec_hash,
+                compression,
+                chunking,
             } => {
                 assert!(!is_syncing);
                 assert_eq!(network_name, "serialization-test");
@@ -630,7 +681,9 @@ mod tests {
                 assert_eq!(protocol_version, ProtocolVersion::V1_0_0);
                 assert!(consensus_certificate.is_none());
                 assert!(!is_syncing);
-                assert!(chainspec_hash.is_none())
+                assert!(chainspec_hash.is_none());
+                assert!(!compression);
+                assert!(!chunking);
             }
             Message::Payload(_) => {
                 panic!("did not expect modern handshake to deserialize to payload")
@@ -650,6 +703,8 @@ mod tests {
                 consensus_certificate,
                 is_syncing,
                 chainspec_hash,
+                compression,
+                chunking,
             } => {
                 assert_eq!(network_name, "example-handshake");
                 assert_eq!(public_addr, ([12, 34, 56, 78], 12346).into());
@@ -676,7 +731,9 @@ mod tests {
                     .unwrap()
                 );
                 assert!(!is_syncing);
-                assert!(chainspec_hash.is_none())
+                assert!(chainspec_hash.is_none());
+                assert!(!compression);
+                assert!(!chunking);
             }
             Message::Payload(_) => {
                 panic!("did not expect modern handshake to deserialize to payload")
@@ -696,6 +753,8 @@ mod tests {
                 consensus_certificate,
                 is_syncing,
                 chainspec_hash,
+                compression,
+                chunking,
             } => {
                 assert!(!is_syncing);
                 assert_eq!(network_name, "example-handshake");
@@ -722,7 +781,9 @@ mod tests {
                     .unwrap()
                 );
                 assert!(!is_syncing);
-                assert!(chainspec_hash.is_none())
+                assert!(chainspec_hash.is_none());
+                assert!(!compression);
+                assert!(!chunking);
             }
             Message::Payload(_) => {
                 panic!("did not expect modern handshake to deserialize to payload")
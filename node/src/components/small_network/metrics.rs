@@ -1,4 +1,4 @@
-use std::sync::Weak;
+use std::{sync::Weak, time::Duration};
 
 use prometheus::{Counter, IntCounter, IntGauge, Registry};
 use tracing::debug;
@@ -17,6 +17,8 @@ pub(super) struct Metrics {
     pub(super) open_connections: IntGauge,
     /// Number of messages still waiting to be sent out (broadcast and direct).
     pub(super) queued_messages: IntGauge,
+    /// Number of outgoing messages dropped because their channel exceeded its budget.
+    pub(super) out_dropped_over_budget: IntCounter,
     /// Number of connected peers.
     pub(super) peers: IntGauge,
 
@@ -117,6 +119,19 @@ pub(super) struct Metrics {
     /// Total time spent delaying incoming traffic from non-validators due to limiter, in seconds.
     pub(super) accumulated_incoming_limiter_delay: Counter,
 
+    /// Volume in bytes of outgoing frames eligible for compression, before compressing them.
+    pub(super) out_bytes_before_compression: IntCounter,
+    /// Volume in bytes of outgoing frames eligible for compression, after compressing them.
+    pub(super) out_bytes_after_compression: IntCounter,
+    /// Total time spent compressing outgoing frames, in seconds.
+    pub(super) accumulated_compression_time: Counter,
+    /// Volume in bytes of incoming compressed frames, before decompressing them.
+    pub(super) in_bytes_before_decompression: IntCounter,
+    /// Volume in bytes of incoming compressed frames, after decompressing them.
+    pub(super) in_bytes_after_decompression: IntCounter,
+    /// Total time spent decompressing incoming frames, in seconds.
+    pub(super) accumulated_decompression_time: Counter,
+
     /// Registry instance.
     registry: Registry,
 }
@@ -136,6 +151,10 @@ impl Metrics {
             "net_queued_direct_messages",
             "number of messages waiting to be sent out",
         )?;
+        let out_dropped_over_budget = IntCounter::new(
+            "net_out_dropped_over_budget",
+            "number of outgoing messages dropped because their channel exceeded its budget",
+        )?;
         let peers = IntGauge::new("peers", "number of connected peers")?;
 
         let out_count_protocol = IntCounter::new(
@@ -325,10 +344,36 @@ impl Metrics {
             "seconds spent delaying incoming traffic from non-validators due to limiter, in seconds."
         )?;
 
+        let out_bytes_before_compression = IntCounter::new(
+            "net_out_bytes_before_compression",
+            "volume in bytes of outgoing frames eligible for compression, before compressing them",
+        )?;
+        let out_bytes_after_compression = IntCounter::new(
+            "net_out_bytes_after_compression",
+            "volume in bytes of outgoing frames eligible for compression, after compressing them",
+        )?;
+        let accumulated_compression_time = Counter::new(
+            "net_accumulated_compression_time",
+            "seconds spent compressing outgoing frames",
+        )?;
+        let in_bytes_before_decompression = IntCounter::new(
+            "net_in_bytes_before_decompression",
+            "volume in bytes of incoming compressed frames, before decompressing them",
+        )?;
+        let in_bytes_after_decompression = IntCounter::new(
+            "net_in_bytes_after_decompression",
+            "volume in bytes of incoming compressed frames, after decompressing them",
+        )?;
+        let accumulated_decompression_time = Counter::new(
+            "net_accumulated_decompression_time",
+            "seconds spent decompressing incoming frames",
+        )?;
+
         registry.register(Box::new(broadcast_requests.clone()))?;
         registry.register(Box::new(direct_message_requests.clone()))?;
         registry.register(Box::new(open_connections.clone()))?;
         registry.register(Box::new(queued_messages.clone()))?;
+        registry.register(Box::new(out_dropped_over_budget.clone()))?;
         registry.register(Box::new(peers.clone()))?;
 
         registry.register(Box::new(out_count_protocol.clone()))?;
@@ -383,11 +428,19 @@ impl Metrics {
         registry.register(Box::new(accumulated_outgoing_limiter_delay.clone()))?;
         registry.register(Box::new(accumulated_incoming_limiter_delay.clone()))?;
 
+        registry.register(Box::new(out_bytes_before_compression.clone()))?;
+        registry.register(Box::new(out_bytes_after_compression.clone()))?;
+        registry.register(Box::new(accumulated_compression_time.clone()))?;
+        registry.register(Box::new(in_bytes_before_decompression.clone()))?;
+        registry.register(Box::new(in_bytes_after_decompression.clone()))?;
+        registry.register(Box::new(accumulated_decompression_time.clone()))?;
+
         Ok(Metrics {
             broadcast_requests,
             direct_message_requests,
             open_connections,
             queued_messages,
+            out_dropped_over_budget,
             peers,
             out_count_protocol,
             out_count_consensus,
@@ -434,6 +487,12 @@ impl Metrics {
             requests_for_trie_finished,
             accumulated_outgoing_limiter_delay,
             accumulated_incoming_limiter_delay,
+            out_bytes_before_compression,
+            out_bytes_after_compression,
+            accumulated_compression_time,
+            in_bytes_before_decompression,
+            in_bytes_after_decompression,
+            accumulated_decompression_time,
             registry: registry.clone(),
         })
     }
@@ -558,6 +617,48 @@ impl Metrics {
             debug!("not recording metrics, component already shut down");
         }
     }
+
+    /// Records the compression of an outgoing frame.
+    pub(super) fn record_compression(
+        this: &Weak<Self>,
+        uncompressed_size: u64,
+        compressed_size: u64,
+        duration: Duration,
+    ) {
+        if let Some(metrics) = this.upgrade() {
+            metrics
+                .out_bytes_before_compression
+                .inc_by(uncompressed_size);
+            metrics.out_bytes_after_compression.inc_by(compressed_size);
+            metrics
+                .accumulated_compression_time
+                .inc_by(duration.as_secs_f64());
+        } else {
+            debug!("not recording metrics, component already shut down");
+        }
+    }
+
+    /// Records the decompression of an incoming frame.
+    pub(super) fn record_decompression(
+        this: &Weak<Self>,
+        compressed_size: u64,
+        uncompressed_size: u64,
+        duration: Duration,
+    ) {
+        if let Some(metrics) = this.upgrade() {
+            metrics
+                .in_bytes_before_decompression
+                .inc_by(compressed_size);
+            metrics
+                .in_bytes_after_decompression
+                .inc_by(uncompressed_size);
+            metrics
+                .accumulated_decompression_time
+                .inc_by(duration.as_secs_f64());
+        } else {
+            debug!("not recording metrics, component already shut down");
+        }
+    }
 }
 
 impl Drop for Metrics {
@@ -566,6 +667,7 @@ impl Drop for Metrics {
         unregister_metric!(self.registry, self.direct_message_requests);
         unregister_metric!(self.registry, self.open_connections);
         unregister_metric!(self.registry, self.queued_messages);
+        unregister_metric!(self.registry, self.out_dropped_over_budget);
         unregister_metric!(self.registry, self.peers);
 
         unregister_metric!(self.registry, self.out_count_protocol);
@@ -619,5 +721,12 @@ impl Drop for Metrics {
 
         unregister_metric!(self.registry, self.accumulated_outgoing_limiter_delay);
         unregister_metric!(self.registry, self.accumulated_incoming_limiter_delay);
+
+        unregister_metric!(self.registry, self.out_bytes_before_compression);
+        unregister_metric!(self.registry, self.out_bytes_after_compression);
+        unregister_metric!(self.registry, self.accumulated_compression_time);
+        unregister_metric!(self.registry, self.in_bytes_before_decompression);
+        unregister_metric!(self.registry, self.in_bytes_after_decompression);
+        unregister_metric!(self.registry, self.accumulated_decompression_time);
     }
 }
@@ -0,0 +1,322 @@
+//! Peer scoring.
+//!
+//! Every peer is scored based on its observed behavior: useful responses raise its score, while
+//! timeouts, invalid items and bad consensus messages lower it. Scores decay towards zero with a
+//! configurable half-life, so both good and bad behavior is eventually forgotten.
+//!
+//! A peer whose score drops to the configured threshold is blocked, while the remaining peers are
+//! handed out in random order, with penalized peers last, when components ask for peers to fetch
+//! data from.
+
+use std::{
+    cmp::Ordering,
+    collections::HashMap,
+    fmt::{self, Display, Formatter},
+    time::{Duration, Instant},
+};
+
+use casper_types::TimeDiff;
+use datasize::DataSize;
+use serde::{Deserialize, Serialize};
+
+use crate::{effect::announcements::Offense, types::NodeId};
+
+/// Scores closer to zero than this are considered decayed entirely.
+const NEGLIGIBLE_SCORE: f64 = 0.01;
+
+/// Peer scoring configuration.
+#[derive(DataSize, Debug, Clone, Deserialize, Serialize)]
+// Disallow unknown fields to ensure config files and command-line overrides contain valid keys.
+#[serde(deny_unknown_fields)]
+pub struct PeerScoringConfig {
+    /// Time after which a score has decayed to half its value. Scores do not decay if `0`.
+    pub half_life: TimeDiff,
+    /// Maximum score a peer can reach.
+    pub max_score: f64,
+    /// Score at or below which a peer is blocked.
+    pub block_threshold: f64,
+    /// Score added for a valid response to one of our requests.
+    pub useful_response_reward: f64,
+    /// Score subtracted for a request timing out.
+    pub timeout_penalty: f64,
+    /// Score subtracted for an invalid item or a response we could not use.
+    pub invalid_item_penalty: f64,
+    /// Score subtracted for an invalid consensus message.
+    pub bad_consensus_message_penalty: f64,
+}
+
+impl Default for PeerScoringConfig {
+    fn default() -> Self {
+        PeerScoringConfig {
+            half_life: TimeDiff::from_seconds(600),
+            max_score: 100.0,
+            block_threshold: -100.0,
+            useful_response_reward: 1.0,
+            timeout_penalty: 5.0,
+            invalid_item_penalty: 200.0,
+            bad_consensus_message_penalty: 200.0,
+        }
+    }
+}
+
+/// Observed behavior of a peer.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub(super) enum PeerBehavior {
+    /// The peer committed an offense.
+    Offense(Offense),
+    /// A request to the peer timed out.
+    TimedOut,
+    /// The peer sent a valid response to one of our requests.
+    UsefulResponse,
+}
+
+impl Display for PeerBehavior {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            PeerBehavior::Offense(offense) => Display::fmt(offense, f),
+            PeerBehavior::TimedOut => f.write_str("timed out"),
+            PeerBehavior::UsefulResponse => f.write_str("useful response"),
+        }
+    }
+}
+
+/// The score of a single peer, along with the behavior it is based on.
+#[derive(Clone, DataSize, Debug, Serialize)]
+pub(crate) struct PeerScore {
+    /// The peer.
+    pub(crate) node_id: NodeId,
+    /// The current score.
+    pub(crate) score: f64,
+    /// Number of valid responses to our requests received from the peer.
+    pub(crate) useful_responses: u64,
+    /// Number of requests to the peer that timed out.
+    pub(crate) timeouts: u64,
+    /// Number of invalid items received from the peer.
+    pub(crate) invalid_items: u64,
+    /// Number of invalid consensus messages received from the peer.
+    pub(crate) bad_consensus_messages: u64,
+}
+
+impl Display for PeerScore {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: score {:.2}, useful responses: {}, timeouts: {}, invalid items: {}, bad consensus \
+            messages: {}",
+            self.node_id,
+            self.score,
+            self.useful_responses,
+            self.timeouts,
+            self.invalid_items,
+            self.bad_consensus_messages
+        )
+    }
+}
+
+/// The scores of all peers we have observed the behavior of.
+#[derive(DataSize, Debug)]
+pub(super) struct PeerScores {
+    /// Scoring configuration.
+    config: PeerScoringConfig,
+    /// Score of each peer, along with the time it was last updated.
+    scores: HashMap<NodeId, (PeerScore, Instant)>,
+}
+
+impl PeerScores {
+    /// Creates a new, empty set of peer scores.
+    pub(super) fn new(config: PeerScoringConfig) -> Self {
+        PeerScores {
+            config,
+            scores: HashMap::new(),
+        }
+    }
+
+    /// Applies the decay of `elapsed` to the given score.
+    fn decay(&self, score: f64, elapsed: Duration) -> f64 {
+        let half_life = Duration::from(self.config.half_life).as_secs_f64();
+        if half_life <= 0.0 {
+            return score;
+        }
+        score * 0.5f64.powf(elapsed.as_secs_f64() / half_life)
+    }
+
+    /// Records the behavior of a peer, updating its score.
+    ///
+    /// Returns `true` if the peer's score dropped to the block threshold, in which case its score
+    /// is reset, the time spent on the block list being its punishment.
+    pub(super) fn record(&mut self, peer: NodeId, behavior: PeerBehavior, now: Instant) -> bool {
+        let delta = match behavior {
+            PeerBehavior::Offense(Offense::InvalidItem) => -self.config.invalid_item_penalty,
+            PeerBehavior::Offense(Offense::BadConsensusMessage) => {
+                -self.config.bad_consensus_message_penalty
+            }
+            PeerBehavior::TimedOut => -self.config.timeout_penalty,
+            PeerBehavior::UsefulResponse => self.config.useful_response_reward,
+        };
+
+        let (mut peer_score, updated) = self.scores.remove(&peer).unwrap_or((
+            PeerScore {
+                node_id: peer,
+                score: 0.0,
+                useful_responses: 0,
+                timeouts: 0,
+                invalid_items: 0,
+                bad_consensus_messages: 0,
+            },
+            now,
+        ));
+
+        match behavior {
+            PeerBehavior::Offense(Offense::InvalidItem) => peer_score.invalid_items += 1,
+            PeerBehavior::Offense(Offense::BadConsensusMessage) => {
+                peer_score.bad_consensus_messages += 1
+            }
+            PeerBehavior::TimedOut => peer_score.timeouts += 1,
+            PeerBehavior::UsefulResponse => peer_score.useful_responses += 1,
+        }
+
+        let decayed = self.decay(peer_score.score, now.saturating_duration_since(updated));
+        peer_score.score = (decayed + delta).min(self.config.max_score);
+
+        let block = peer_score.score <= self.config.block_threshold;
+        if block {
+            peer_score.score = 0.0;
+        }
+
+        self.scores.insert(peer, (peer_score, now));
+        block
+    }
+
+    /// Returns the current score of a peer, `0` if its behavior was not observed yet.
+    pub(super) fn score(&self, peer: &NodeId, now: Instant) -> f64 {
+        self.scores
+            .get(peer)
+            .map(|(peer_score, updated)| {
+                self.decay(peer_score.score, now.saturating_duration_since(*updated))
+            })
+            .unwrap_or_default()
+    }
+
+    /// Moves the peers with a negative current score to the back, least penalized first.
+    ///
+    /// The sort is stable, so the other peers keep their relative order, and requests are spread
+    /// across them rather than all going to the best scored peer.
+    pub(super) fn sort_penalized_last(&self, peers: &mut [NodeId], now: Instant) {
+        let penalty = |peer: &NodeId| {
+            let score = self.score(peer, now);
+            if score <= -NEGLIGIBLE_SCORE {
+                -score
+            } else {
+                0.0
+            }
+        };
+        peers.sort_by(|a, b| {
+            penalty(a)
+                .partial_cmp(&penalty(b))
+                .unwrap_or(Ordering::Equal)
+        });
+    }
+
+    /// Returns the current scores of all peers, best scored first.
+    pub(super) fn snapshot(&self, now: Instant) -> Vec<PeerScore> {
+        let mut snapshot: Vec<PeerScore> = self
+            .scores
+            .values()
+            .map(|(peer_score, updated)| PeerScore {
+                score: self.decay(peer_score.score, now.saturating_duration_since(*updated)),
+                ..peer_score.clone()
+            })
+            .collect();
+        snapshot.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(Ordering::Equal));
+        snapshot
+    }
+
+    /// Forgets all peers whose score has decayed entirely.
+    pub(super) fn prune(&mut self, now: Instant) {
+        let config = &self.config;
+        let half_life = Duration::from(config.half_life).as_secs_f64();
+        if half_life <= 0.0 {
+            return;
+        }
+        self.scores.retain(|_, (peer_score, updated)| {
+            let elapsed = now.saturating_duration_since(*updated).as_secs_f64();
+            (peer_score.score * 0.5f64.powf(elapsed / half_life)).abs() >= NEGLIGIBLE_SCORE
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use casper_types::testing::TestRng;
+
+    use super::*;
+
+    #[test]
+    fn should_block_after_offense() {
+        let mut rng = TestRng::new();
+        let mut scores = PeerScores::new(PeerScoringConfig::default());
+        let peer = NodeId::random(&mut rng);
+        let now = Instant::now();
+
+        // Good behavior cannot make up for an offense.
+        for _ in 0..1_000 {
+            assert!(!scores.record(peer, PeerBehavior::UsefulResponse, now));
+        }
+        assert_eq!(scores.score(&peer, now), 100.0);
+        assert!(scores.record(peer, PeerBehavior::Offense(Offense::InvalidItem), now));
+
+        // The score is reset after blocking, but the behavior is retained.
+        assert_eq!(scores.score(&peer, now), 0.0);
+        let snapshot = scores.snapshot(now);
+        assert_eq!(snapshot.len(), 1);
+        assert_eq!(snapshot[0].useful_responses, 1_000);
+        assert_eq!(snapshot[0].invalid_items, 1);
+    }
+
+    #[test]
+    fn should_decay_scores() {
+        let mut rng = TestRng::new();
+        let mut scores = PeerScores::new(PeerScoringConfig::default());
+        let peer = NodeId::random(&mut rng);
+        let start = Instant::now();
+
+        for _ in 0..10 {
+            assert!(!scores.record(peer, PeerBehavior::TimedOut, start));
+        }
+        assert_eq!(scores.score(&peer, start), -50.0);
+
+        let later = start + Duration::from_secs(600);
+        assert!((scores.score(&peer, later) + 25.0).abs() < 1e-9);
+
+        // Timeouts spread out over time do not lead to the peer being blocked.
+        for minutes in 0..100 {
+            let now = later + Duration::from_secs(60 * minutes);
+            assert!(!scores.record(peer, PeerBehavior::TimedOut, now));
+        }
+
+        // Eventually, the peer is forgotten.
+        scores.prune(later + Duration::from_secs(6000 + 600 * 20));
+        assert!(scores.snapshot(start).is_empty());
+    }
+
+    #[test]
+    fn should_sort_penalized_last() {
+        let mut rng = TestRng::new();
+        let mut scores = PeerScores::new(PeerScoringConfig::default());
+        let good = NodeId::random(&mut rng);
+        let unknown = NodeId::random(&mut rng);
+        let bad = NodeId::random(&mut rng);
+        let worse = NodeId::random(&mut rng);
+        let now = Instant::now();
+
+        scores.record(good, PeerBehavior::UsefulResponse, now);
+        scores.record(bad, PeerBehavior::TimedOut, now);
+        scores.record(worse, PeerBehavior::TimedOut, now);
+        scores.record(worse, PeerBehavior::TimedOut, now);
+
+        // Peers which are not penalized keep their order, regardless of their scores.
+        let mut peers = vec![worse, unknown, bad, good];
+        scores.sort_penalized_last(&mut peers, now);
+        assert_eq!(peers, vec![unknown, good, bad, worse]);
+    }
+}
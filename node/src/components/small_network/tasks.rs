@@ -13,10 +13,8 @@ use std::{
     time::Duration,
 };
 
-use bincode::Options;
 use futures::{
     future::{self, Either},
-    stream::{SplitSink, SplitStream},
     Future, SinkExt, StreamExt,
 };
 use openssl::{
@@ -29,7 +27,7 @@ use rand::Rng;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use tokio::{
     net::TcpStream,
-    sync::{mpsc::UnboundedReceiver, watch, Semaphore},
+    sync::{watch, Semaphore},
 };
 use tokio_openssl::SslStream;
 use tokio_serde::{Deserializer, Serializer};
@@ -43,30 +41,27 @@ use casper_types::{ProtocolVersion, PublicKey, TimeDiff};
 
 use super::{
     chain_info::ChainInfo,
+    channel::{Channel, ChannelReceivers, MessageQueueItem},
+    chunking::{ChunkSink, ChunkStream, OutgoingMessage},
+    compression::CompressionConfig,
     counting_format::{ConnectionId, Role},
     error::{ConnectionError, IoError},
     event::{IncomingConnection, OutgoingConnection},
     full_transport,
     limiter::LimiterHandle,
-    message::ConsensusKeyPair,
+    message::{ConsensusCertificate, ConsensusKeyPair},
     message_pack_format::MessagePackFormat,
-    EstimatorWeights, Event, FramedTransport, FullTransport, Message, Metrics, Payload, Transport,
+    EstimatorWeights, Event, FramedTransport, Message, Metrics, Payload, Transport,
 };
 use crate::{
-    components::small_network::{framed_transport, BincodeFormat, FromIncoming},
-    effect::{requests::NetworkRequest, AutoClosingResponder, EffectBuilder},
+    components::small_network::{framed_transport, FromIncoming},
+    effect::{requests::NetworkRequest, EffectBuilder},
     reactor::{EventQueueHandle, QueueKind},
     tls::{self, TlsCert, ValidationError},
     types::NodeId,
     utils::display_error,
 };
 
-/// An item on the internal outgoing message queue.
-///
-/// Contains a reference counted message and an optional responder to call once the message has been
-/// successfully handed over to the kernel for sending.
-pub(super) type MessageQueueItem<P> = (Arc<Message<P>>, Option<AutoClosingResponder<()>>);
-
 /// The outcome of the handshake process.
 struct HandshakeOutcome {
     /// A framed transport for peer.
@@ -77,6 +72,10 @@ struct HandshakeOutcome {
     peer_consensus_public_key: Option<PublicKey>,
     /// Holds the information whether the remote node is syncing.
     is_peer_syncing: bool,
+    /// Whether both peers offered compression, enabling it on the connection.
+    use_compression: bool,
+    /// Whether both peers offered chunking, enabling it on the connection.
+    use_chunking: bool,
 }
 
 /// Low-level TLS connection function.
@@ -159,6 +158,8 @@ where
             public_addr,
             peer_consensus_public_key,
             is_peer_syncing: is_syncing,
+            use_compression,
+            use_chunking,
         }) => {
             if let Some(ref public_key) = peer_consensus_public_key {
                 Span::current().record("validator_id", &field::display(public_key));
@@ -170,19 +171,21 @@ where
             }
 
             // Setup full framed transport, then close down receiving end of the transport.
-            let full_transport = full_transport::<P>(
+            let (sink, _stream) = full_transport::<P>(
                 context.net_metrics.clone(),
                 connection_id,
                 framed_transport,
                 Role::Dialer,
+                context.negotiated_compression(use_compression),
+                use_chunking,
+                context.chain_info.maximum_net_message_size,
             );
-            let (sink, _stream) = full_transport.split();
 
             OutgoingConnection::Established {
                 peer_addr,
                 peer_id,
                 peer_consensus_public_key,
-                sink,
+                sink: Box::new(sink),
                 is_syncing,
             }
         }
@@ -231,6 +234,8 @@ where
     pub(super) max_in_flight_demands: usize,
     /// Flag indicating whether this node is syncing.
     pub(super) is_syncing: AtomicBool,
+    /// Compression configuration.
+    pub(super) compression: CompressionConfig,
 }
 
 impl<REv> NetworkContext<REv> {
@@ -240,6 +245,16 @@ impl<REv> NetworkContext<REv> {
             None => tls::validate_self_signed_cert(peer_cert),
         }
     }
+
+    /// Returns the compression configuration to use on a connection, if compression was
+    /// negotiated for it.
+    fn negotiated_compression(&self, use_compression: bool) -> Option<CompressionConfig> {
+        if use_compression {
+            Some(self.compression.clone())
+        } else {
+            None
+        }
+    }
 }
 
 /// Handles an incoming connection.
@@ -284,27 +299,30 @@ where
             public_addr,
             peer_consensus_public_key,
             is_peer_syncing: _,
+            use_compression,
+            use_chunking,
         }) => {
             if let Some(ref public_key) = peer_consensus_public_key {
                 Span::current().record("validator_id", &field::display(public_key));
             }
 
             // Establish full transport and close the receiving end.
-            let full_transport = full_transport::<P>(
+            let (_sink, stream) = full_transport::<P>(
                 context.net_metrics.clone(),
                 connection_id,
                 framed_transport,
                 Role::Listener,
+                context.negotiated_compression(use_compression),
+                use_chunking,
+                context.chain_info.maximum_net_message_size,
             );
 
-            let (_sink, stream) = full_transport.split();
-
             IncomingConnection::Established {
                 peer_addr,
                 public_addr,
                 peer_id,
                 peer_consensus_public_key,
-                stream,
+                stream: Box::new(stream),
             }
         }
         Err(error) => IncomingConnection::Failed {
@@ -390,12 +408,33 @@ where
 {
     let mut encoder = MessagePackFormat;
 
-    // Manually encode a handshake.
+    // Signing may block while waiting for a remote signer, so it is done off the executor. If it
+    // fails, we don't connect rather than silently connect as a non-validator.
+    let consensus_certificate = match context.consensus_keys.clone() {
+        Some(key_pair) => {
+            let certificate = tokio::task::spawn_blocking(move || {
+                ConsensusCertificate::create(connection_id, &key_pair)
+            })
+            .await
+            .map_err(ConnectionError::HandshakeSignerCrashed)?
+            .map_err(|error| {
+                // Unlike most connection errors, this one is on our side and affects all peers.
+                error!(%error, "failed to sign handshake");
+                ConnectionError::CouldNotSignOurHandshake(Box::new(error))
+            })?;
+            Some(certificate)
+        }
+        None => None,
+    };
+
+    // Manually encode a handshake. We always offer chunking, but fall back to sending every
+    // message as a single frame if the peer doesn't offer it too.
     let handshake_message = context.chain_info.create_handshake::<P>(
         context.public_addr,
-        context.consensus_keys.as_ref(),
-        connection_id,
+        consensus_certificate,
         context.is_syncing.load(Ordering::SeqCst),
+        context.compression.enabled,
+        true,
     );
 
     let serialized_handshake_message = Pin::new(&mut encoder)
@@ -435,6 +474,8 @@ where
         consensus_certificate,
         is_syncing,
         chainspec_hash,
+        compression,
+        chunking,
     } = remote_message
     {
         debug!(%protocol_version, "handshake received");
@@ -493,6 +534,8 @@ where
             public_addr,
             peer_consensus_public_key,
             is_peer_syncing: is_syncing,
+            use_compression: context.compression.enabled && compression,
+            use_chunking: chunking,
         })
     } else {
         // Received a non-handshake, this is an error.
@@ -578,7 +621,7 @@ pub(super) async fn server<P, REv>(
 /// Schedules all received messages until the stream is closed or an error occurs.
 pub(super) async fn message_reader<REv, P>(
     context: Arc<NetworkContext<REv>>,
-    mut stream: SplitStream<FullTransport<P>>,
+    mut stream: ChunkStream<P>,
     limiter: Box<dyn LimiterHandle>,
     mut close_incoming_receiver: watch::Receiver<()>,
     peer_id: NodeId,
@@ -591,7 +634,7 @@ where
     let demands_in_flight = Arc::new(Semaphore::new(context.max_in_flight_demands));
 
     let read_messages = async move {
-        while let Some(msg_result) = stream.next().await {
+        while let Some(msg_result) = stream.recv().await {
             match msg_result {
                 Ok(msg) => {
                     trace!(%msg, "message received");
@@ -712,38 +755,66 @@ where
 
 /// Network message sender.
 ///
-/// Reads from a channel and sends all messages, until the stream is closed or an error occurs.
+/// Reads from the outgoing channels and sends all messages in chunks, until the channels are closed
+/// or an error occurs. The next chunk is always taken from the message with the highest priority,
+/// so a message is interrupted whenever one of higher priority is queued.
 pub(super) async fn message_sender<P>(
-    mut queue: UnboundedReceiver<MessageQueueItem<P>>,
-    mut sink: SplitSink<FullTransport<P>, Arc<Message<P>>>,
+    mut queues: ChannelReceivers<P>,
+    mut sink: ChunkSink<P>,
     limiter: Box<dyn LimiterHandle>,
     counter: IntGauge,
 ) where
     P: Payload,
 {
-    while let Some((message, opt_responder)) = queue.recv().await {
-        counter.dec();
-
-        let estimated_wire_size = match BincodeFormat::default().0.serialized_size(&*message) {
-            Ok(size) => size as u32,
-            Err(error) => {
-                error!(
-                    error = display_error(&error),
-                    "failed to get serialized size of outgoing message, closing outgoing connection"
-                );
-                break;
-            }
+    // The messages being sent, at most one per channel, ordered by decreasing priority.
+    let mut in_progress: Vec<OutgoingMessage> = Vec::with_capacity(Channel::COUNT);
+
+    loop {
+        let next_item = match in_progress.first() {
+            Some(current) => queues.try_recv_above(current.channel()),
+            None => match queues.recv().await {
+                Some(item) => Some(item),
+                None => break,
+            },
         };
-        limiter.request_allowance(estimated_wire_size).await;
 
-        let mut outcome = sink.send(message).await;
+        let outcome = match next_item {
+            Some(MessageQueueItem {
+                message,
+                responder: opt_responder,
+                estimated_wire_size,
+            }) => {
+                counter.dec();
 
-        // Notify via responder that the message has been buffered by the kernel.
-        if let Some(auto_closing_responder) = opt_responder {
-            // Since someone is interested in the message, flush the socket to ensure it was sent.
-            outcome = outcome.and(sink.flush().await);
-            auto_closing_responder.respond(()).await;
-        }
+                limiter.request_allowance(estimated_wire_size).await;
+
+                // The new message has a higher priority than all those already being sent.
+                match sink
+                    .serialize(&message, estimated_wire_size, opt_responder)
+                    .await
+                {
+                    Ok(outgoing_message) => {
+                        in_progress.insert(0, outgoing_message);
+                        Ok(())
+                    }
+                    Err(err) => Err(err),
+                }
+            }
+            None => {
+                let mut outcome = sink.send_chunk(&mut in_progress[0]).await;
+
+                if in_progress[0].is_finished() {
+                    // Notify via responder that the message has been buffered by the kernel.
+                    if let Some(auto_closing_responder) = in_progress.remove(0).into_responder() {
+                        // Since someone is interested in the message, flush the socket to ensure
+                        // it was sent.
+                        outcome = outcome.and(sink.flush().await);
+                        auto_closing_responder.respond(()).await;
+                    }
+                }
+                outcome
+            }
+        };
 
         // We simply error-out if the sink fails, it means that our connection broke.
         if let Err(ref err) = outcome {
@@ -753,8 +824,8 @@ pub(super) async fn message_sender<P>(
             );
 
             // To ensure, metrics are up to date, we close the queue and drain it.
-            queue.close();
-            while queue.recv().await.is_some() {
+            queues.close();
+            while queues.recv().await.is_some() {
                 counter.dec();
             }
 
@@ -26,13 +26,15 @@ use crate::{
         Component,
     },
     effect::{
-        announcements::{ControlAnnouncement, GossiperAnnouncement},
+        announcements::{
+            ControlAnnouncement, GossiperAnnouncement, Offense, PeerBehaviorAnnouncement,
+        },
         incoming::GossiperIncoming,
         requests::{
             BeginGossipRequest, ChainspecLoaderRequest, ContractRuntimeRequest, NetworkRequest,
             StorageRequest,
         },
-        EffectBuilder, Effects,
+        EffectBuilder, EffectExt, Effects,
     },
     protocol,
     reactor::{self, EventQueueHandle, Finalize, Reactor, Runner},
@@ -62,6 +64,8 @@ enum Event {
     BeginAddressGossipRequest(BeginGossipRequest<GossipedAddress>),
     /// An incoming network message with an address gossiper protocol message.
     AddressGossiperIncoming(GossiperIncoming<GossipedAddress>),
+    #[from]
+    PeerBehaviorAnnouncement(PeerBehaviorAnnouncement),
 }
 
 impl ReactorEvent for Event {
@@ -255,6 +259,11 @@ impl Reactor for TestReactor {
                 self.address_gossiper
                     .handle_event(effect_builder, rng, incoming.into()),
             ),
+            Event::PeerBehaviorAnnouncement(announcement) => reactor::wrap_effects(
+                Event::SmallNet,
+                self.net
+                    .handle_event(effect_builder, rng, announcement.into()),
+            ),
         }
     }
 
@@ -378,6 +387,126 @@ async fn run_two_node_network_five_times() {
     }
 }
 
+/// Check that nodes connect if only one of them enables compression.
+///
+/// Compression is negotiated during the handshake, so neither node may tag its frames.
+#[tokio::test]
+async fn connect_if_only_one_node_enables_compression() {
+    init_logging();
+
+    let mut rng = crate::new_rng();
+
+    let mut net = Network::new();
+
+    let first_node_port = testing::unused_port_on_localhost();
+
+    let mut first_node_config = Config::default_local_net_first_node(first_node_port);
+    first_node_config.compression.enabled = true;
+    net.add_node_with_config(first_node_config, &mut rng)
+        .await
+        .unwrap();
+
+    let mut second_node_config = Config::default_local_net(first_node_port);
+    second_node_config.compression.enabled = false;
+    net.add_node_with_config(second_node_config, &mut rng)
+        .await
+        .unwrap();
+
+    let timeout = Duration::from_secs(20);
+    let blocklist = HashSet::new();
+    net.settle_on(
+        &mut rng,
+        |nodes| network_is_complete(&blocklist, nodes),
+        timeout,
+    )
+    .await;
+
+    // Address gossip keeps flowing over both connections, which would fail on misread frames.
+    let quiet_for = Duration::from_millis(25);
+    let timeout = Duration::from_secs(2);
+    net.settle(&mut rng, quiet_for, timeout).await;
+
+    assert!(
+        network_is_complete(&blocklist, net.nodes()),
+        "network did not stay connected"
+    );
+
+    net.finalize().await;
+}
+
+/// Check that a peer is blocked once announcements of its misbehavior drop its score far enough.
+#[tokio::test]
+async fn block_peer_after_its_score_dropped() {
+    init_logging();
+
+    let mut rng = crate::new_rng();
+
+    let mut net = Network::<TestReactor>::new();
+
+    let first_node_port = testing::unused_port_on_localhost();
+
+    let node_id = net
+        .add_node_with_config(
+            Config::default_local_net_first_node(first_node_port),
+            &mut rng,
+        )
+        .await
+        .unwrap()
+        .0;
+    let peer_id = net
+        .add_node_with_config(Config::default_local_net(first_node_port), &mut rng)
+        .await
+        .unwrap()
+        .0;
+
+    let outgoing_addr = |nodes: &Nodes<TestReactor>| {
+        nodes[&node_id]
+            .reactor()
+            .inner()
+            .net
+            .outgoing_manager
+            .get_addr(peer_id)
+    };
+    let is_blocked = |nodes: &Nodes<TestReactor>, addr| {
+        nodes[&node_id]
+            .reactor()
+            .inner()
+            .net
+            .outgoing_manager
+            .is_blocked(addr)
+    };
+
+    let timeout = Duration::from_secs(20);
+    net.settle_on(&mut rng, |nodes| outgoing_addr(nodes).is_some(), timeout)
+        .await;
+    let addr = outgoing_addr(net.nodes()).unwrap();
+
+    // A single timeout is not enough to block the peer.
+    net.process_injected_effect_on(&node_id, |effect_builder| {
+        effect_builder.announce_peer_timed_out(peer_id).ignore()
+    })
+    .await;
+    let quiet_for = Duration::from_millis(25);
+    let timeout = Duration::from_secs(2);
+    net.settle(&mut rng, quiet_for, timeout).await;
+    assert!(
+        !is_blocked(net.nodes(), addr),
+        "peer blocked after a single timeout"
+    );
+
+    // A single invalid item is.
+    net.process_injected_effect_on(&node_id, |effect_builder| {
+        effect_builder
+            .announce_offense(peer_id, Offense::InvalidItem)
+            .ignore()
+    })
+    .await;
+    net.settle_on(&mut rng, |nodes| is_blocked(nodes, addr), timeout)
+        .await;
+
+    net.finalize().await;
+}
+
 /// Sanity check that we can bind to a real network.
 ///
 /// Very unlikely to ever fail on a real machine.
@@ -41,7 +41,7 @@ mod tests;
 use std::collections::BTreeSet;
 use std::{
     borrow::Cow,
-    collections::{btree_map::Entry, BTreeMap, HashSet},
+    collections::{btree_map::Entry, BTreeMap, HashMap, HashSet},
     convert::TryFrom,
     fmt::{self, Display, Formatter},
     fs, mem,
@@ -55,6 +55,7 @@ use lmdb::{
     Cursor, Database, DatabaseFlags, Environment, EnvironmentFlags, RwTransaction, Transaction,
     WriteFlags,
 };
+use lmdb_sys::MDB_SET_RANGE;
 use serde::{Deserialize, Serialize};
 use smallvec::SmallVec;
 use static_assertions::const_assert;
@@ -64,7 +65,8 @@ use tracing::{debug, error, info, warn};
 
 use casper_hashing::Digest;
 use casper_types::{
-    bytesrepr::{FromBytes, ToBytes},
+    account::{AccountHash, ACCOUNT_HASH_LENGTH},
+    bytesrepr::{FromBytes, ToBytes, U64_SERIALIZED_LENGTH},
     EraId, ExecutionResult, ProtocolVersion, TimeDiff, Transfer, Transform,
 };
 
@@ -81,10 +83,11 @@ use crate::{
     protocol::Message,
     reactor::ReactorEvent,
     types::{
-        AvailableBlockRange, Block, BlockAndDeploys, BlockBody, BlockHash, BlockHashAndHeight,
-        BlockHeader, BlockHeaderWithMetadata, BlockHeadersBatch, BlockHeadersBatchId,
-        BlockSignatures, BlockWithMetadata, Deploy, DeployHash, DeployMetadata, DeployMetadataExt,
-        DeployWithFinalizedApprovals, FinalizedApprovals, FinalizedApprovalsWithId, Item, NodeId,
+        AccountHistoryEntry, AccountHistoryRole, AvailableBlockRange, Block, BlockAndDeploys,
+        BlockBody, BlockHash, BlockHashAndHeight, BlockHeader, BlockHeaderWithMetadata,
+        BlockHeadersBatch, BlockHeadersBatchId, BlockSignatures, BlockWithMetadata, Deploy,
+        DeployHash, DeployMetadata, DeployMetadataExt, DeployWithFinalizedApprovals,
+        FinalizedApprovals, FinalizedApprovalsWithId, Item, NodeId,
     },
     utils::{display_error, WithDir},
     NodeRng,
@@ -114,9 +117,14 @@ const DEFAULT_MAX_DEPLOY_METADATA_STORE_SIZE: usize = 300 * GIB;
 /// Default max state store size.
 const DEFAULT_MAX_STATE_STORE_SIZE: usize = 10 * GIB;
 /// Maximum number of allowed dbs.
-const MAX_DB_COUNT: u32 = 8;
+const MAX_DB_COUNT: u32 = 10;
+/// Length of the keys in the account history database.
+const ACCOUNT_HISTORY_KEY_LENGTH: usize =
+    ACCOUNT_HASH_LENGTH + U64_SERIALIZED_LENGTH + Digest::LENGTH + 1;
 /// Key under which completed blocks are to be stored.
 const COMPLETED_BLOCKS_STORAGE_KEY: &[u8] = b"completed_blocks_disjoint_sequences";
+/// Key under which the height of the lowest block with retained global state is to be stored.
+const LOWEST_RETAINED_STATE_HEIGHT_STORAGE_KEY: &[u8] = b"lowest_retained_state_height";
 
 /// OS-specific lmdb flags.
 #[cfg(not(target_os = "macos"))]
@@ -170,6 +178,18 @@ pub struct Storage {
     /// The finalized approvals database.
     #[data_size(skip)]
     finalized_approvals_db: Database,
+    /// The account history database, if enabled.
+    ///
+    /// Keyed by account hash followed by block height, deploy hash and role, see
+    /// [`account_history_key`].
+    #[data_size(skip)]
+    account_history_db: Option<Database>,
+    /// The pruned state roots database.
+    ///
+    /// Keyed by the state root hash of a block whose global state has been pruned, contains the
+    /// height of the highest such block.
+    #[data_size(skip)]
+    pruned_state_root_db: Database,
     /// A map of block height to block ID.
     block_height_index: BTreeMap<u64, BlockHash>,
     /// A map of era ID to switch block ID.
@@ -178,6 +198,8 @@ pub struct Storage {
     deploy_hash_index: BTreeMap<DeployHash, BlockHashAndHeight>,
     /// Runs of completed blocks known in storage.
     completed_blocks: DisjointSequences,
+    /// Height of the lowest block whose global state has not been pruned, if any has been.
+    lowest_retained_state_height: Option<u64>,
     /// Whether or not memory deduplication is enabled.
     enable_mem_deduplication: bool,
     /// An in-memory pool of already loaded serialized items.
@@ -327,6 +349,13 @@ impl Storage {
         let finalized_approvals_db =
             env.create_db(Some("finalized_approvals"), DatabaseFlags::empty())?;
         let block_body_db = env.create_db(Some("block_body"), DatabaseFlags::empty())?;
+        let account_history_db = if config.enable_account_history_index {
+            Some(env.create_db(Some("account_history"), DatabaseFlags::empty())?)
+        } else {
+            None
+        };
+        let pruned_state_root_db =
+            env.create_db(Some("pruned_state_roots"), DatabaseFlags::empty())?;
 
         // We now need to restore the block-height index. Log messages allow timing here.
         info!("indexing block store");
@@ -413,10 +442,13 @@ impl Storage {
             transfer_db,
             state_store_db,
             finalized_approvals_db,
+            account_history_db,
+            pruned_state_root_db,
             block_height_index,
             switch_block_era_id_index,
             deploy_hash_index,
             completed_blocks: Default::default(),
+            lowest_retained_state_height: None,
             enable_mem_deduplication: config.enable_mem_deduplication,
             serialized_item_pool: ObjectPool::new(config.mem_pool_prune_interval),
         };
@@ -454,6 +486,14 @@ impl Storage {
             }
         }
 
+        if let Some(raw) =
+            component.read_state_store(&Cow::Borrowed(LOWEST_RETAINED_STATE_HEIGHT_STORAGE_KEY))?
+        {
+            let (block_height, _) =
+                u64::from_vec(raw).map_err(FatalStorageError::UnexpectedDeserializationFailure)?;
+            component.lowest_retained_state_height = Some(block_height);
+        }
+
         Ok(component)
     }
 
@@ -543,7 +583,9 @@ impl Storage {
                     incoming.message.tag(),
                     serialized_item,
                 );
-                return Ok(effect_builder.send_message(incoming.sender, found).ignore());
+                return Ok(effect_builder
+                    .enqueue_message(incoming.sender, found)
+                    .ignore());
             }
         }
 
@@ -776,6 +818,8 @@ impl Storage {
             } => {
                 let mut txn = self.env.begin_rw_txn()?;
 
+                self.put_account_history(&mut txn, &block_hash, &execution_results)?;
+
                 let mut transfers: Vec<Transfer> = vec![];
 
                 for (deploy_hash, execution_result) in execution_results {
@@ -828,6 +872,31 @@ impl Storage {
                 txn.commit()?;
                 responder.respond(()).ignore()
             }
+            StorageRequest::GetAccountHistory {
+                account_hash,
+                cursor,
+                limit,
+                responder,
+            } => {
+                let mut txn = self.env.begin_ro_txn()?;
+                responder
+                    .respond(self.get_account_history(
+                        &mut txn,
+                        &account_hash,
+                        cursor.as_deref(),
+                        limit,
+                    )?)
+                    .ignore()
+            }
+            StorageRequest::GetBlockExecutionResults {
+                block_hash,
+                responder,
+            } => {
+                let mut txn = self.env.begin_ro_txn()?;
+                responder
+                    .respond(self.get_block_execution_results(&mut txn, &block_hash)?)
+                    .ignore()
+            }
             StorageRequest::GetDeployAndMetadata {
                 deploy_hash,
                 responder,
@@ -996,6 +1065,69 @@ impl Storage {
                     }))
                     .ignore()
             }
+            StorageRequest::GetBlocksAndMetadataInRange {
+                from_height,
+                to_height,
+                limit,
+                responder,
+            } => {
+                let mut txn = self.env.begin_ro_txn()?;
+                let mut blocks_with_metadata = vec![];
+                for block_hash in self.completed_block_hashes_in_range(from_height, to_height) {
+                    if blocks_with_metadata.len() >= limit {
+                        break;
+                    }
+                    let block = match self.get_single_block(&mut txn, block_hash)? {
+                        Some(block) => block,
+                        None => continue,
+                    };
+                    let block_signatures = match self.get_block_signatures(&mut txn, block_hash)? {
+                        Some(signatures) => signatures,
+                        None => BlockSignatures::new(*block_hash, block.header().era_id()),
+                    };
+                    blocks_with_metadata.push(BlockWithMetadata {
+                        block,
+                        block_signatures,
+                    });
+                }
+                responder.respond(blocks_with_metadata).ignore()
+            }
+            StorageRequest::GetBlockHeadersInRange {
+                from_height,
+                to_height,
+                limit,
+                responder,
+            } => {
+                let mut txn = self.env.begin_ro_txn()?;
+                let mut block_headers = vec![];
+                for block_hash in self.completed_block_hashes_in_range(from_height, to_height) {
+                    if block_headers.len() >= limit {
+                        break;
+                    }
+                    if let Some(block_header) =
+                        self.get_single_block_header(&mut txn, block_hash)?
+                    {
+                        block_headers.push(block_header);
+                    }
+                }
+                responder.respond(block_headers).ignore()
+            }
+            StorageRequest::GetSwitchBlockHeadersInEraRange {
+                from_era_id,
+                to_era_id,
+                limit,
+                responder,
+            } => {
+                let mut txn = self.env.begin_ro_txn()?;
+                responder
+                    .respond(self.get_switch_block_headers_in_era_range(
+                        &mut txn,
+                        from_era_id,
+                        to_era_id,
+                        limit,
+                    )?)
+                    .ignore()
+            }
             StorageRequest::GetHighestBlockWithMetadata { responder } => {
                 let mut txn = self.env.begin_ro_txn()?;
 
@@ -1103,6 +1235,29 @@ impl Storage {
             StorageRequest::GetAvailableBlockRange { responder } => {
                 responder.respond(self.get_available_block_range()).ignore()
             }
+            StorageRequest::PutLowestRetainedStateHeight {
+                block_height,
+                responder,
+            } => {
+                self.put_lowest_retained_state_height(block_height)?;
+                responder.respond(()).ignore()
+            }
+            StorageRequest::PutPrunedStateRoots {
+                pruned_state_roots,
+                responder,
+            } => {
+                self.put_pruned_state_roots(&pruned_state_roots)?;
+                responder.respond(()).ignore()
+            }
+            StorageRequest::GetPrunedStateRootHeight {
+                state_root_hash,
+                responder,
+            } => responder
+                .respond(self.get_pruned_state_root_height(&state_root_hash)?)
+                .ignore(),
+            StorageRequest::GetLowestRetainedStateHeight { responder } => responder
+                .respond(self.lowest_retained_state_height)
+                .ignore(),
             StorageRequest::StoreFinalizedApprovals {
                 ref deploy_hash,
                 ref finalized_approvals,
@@ -1151,6 +1306,56 @@ impl Storage {
         self.write_state_store(Cow::Borrowed(COMPLETED_BLOCKS_STORAGE_KEY), &serialized)
     }
 
+    /// Records and persists the height of the lowest block whose global state is retained.
+    fn put_lowest_retained_state_height(
+        &mut self,
+        block_height: u64,
+    ) -> Result<(), FatalStorageError> {
+        let serialized = block_height
+            .to_bytes()
+            .map_err(FatalStorageError::UnexpectedSerializationFailure)?;
+        self.write_state_store(
+            Cow::Borrowed(LOWEST_RETAINED_STATE_HEIGHT_STORAGE_KEY),
+            &serialized,
+        )?;
+        self.lowest_retained_state_height = Some(block_height);
+        Ok(())
+    }
+
+    /// Records the state roots of blocks whose global state has been pruned, along with the
+    /// height of the respective block.  Where a state root is shared by several blocks, the
+    /// highest height is kept.
+    fn put_pruned_state_roots(
+        &self,
+        pruned_state_roots: &[(Digest, u64)],
+    ) -> Result<(), FatalStorageError> {
+        let mut txn = self.env.begin_rw_txn()?;
+        for (state_root_hash, block_height) in pruned_state_roots {
+            let existing: Option<u64> =
+                txn.get_value_bytesrepr(self.pruned_state_root_db, state_root_hash)?;
+            if existing.map_or(true, |height| height < *block_height) {
+                let _ = txn.put_value_bytesrepr(
+                    self.pruned_state_root_db,
+                    state_root_hash,
+                    block_height,
+                    true,
+                )?;
+            }
+        }
+        txn.commit()?;
+        Ok(())
+    }
+
+    /// Returns the height of the highest block whose global state under the given state root has
+    /// been pruned, or `None` if that state root is not known to have been pruned.
+    fn get_pruned_state_root_height(
+        &self,
+        state_root_hash: &Digest,
+    ) -> Result<Option<u64>, FatalStorageError> {
+        let mut txn = self.env.begin_ro_txn()?;
+        Ok(txn.get_value_bytesrepr(self.pruned_state_root_db, state_root_hash)?)
+    }
+
     /// Put a single deploy into storage.
     pub fn put_deploy(&self, deploy: &Deploy) -> Result<bool, FatalStorageError> {
         let mut txn = self.env.begin_rw_txn()?;
@@ -1392,6 +1597,37 @@ impl Storage {
             .transpose()
     }
 
+    /// Retrieves the headers of the switch blocks ending the eras within the given range, in order
+    /// of era ID, omitting any outside the completed block range.
+    fn get_switch_block_headers_in_era_range<Tx: Transaction>(
+        &self,
+        txn: &mut Tx,
+        from_era_id: EraId,
+        to_era_id: EraId,
+        limit: usize,
+    ) -> Result<Vec<BlockHeader>, FatalStorageError> {
+        let mut switch_block_headers = vec![];
+        if from_era_id > to_era_id {
+            return Ok(switch_block_headers);
+        }
+        let completed_block_range = self.get_completed_block_range();
+        for block_hash in self
+            .switch_block_era_id_index
+            .range(from_era_id..=to_era_id)
+            .map(|(_, block_hash)| block_hash)
+        {
+            if switch_block_headers.len() >= limit {
+                break;
+            }
+            if let Some(block_header) = self.get_single_block_header(txn, block_hash)? {
+                if completed_block_range.contains(block_header.height()) {
+                    switch_block_headers.push(block_header);
+                }
+            }
+        }
+        Ok(switch_block_headers)
+    }
+
     /// Retrieves a single block header by deploy hash by looking it up in the index and returning
     /// it.
     fn get_block_header_by_deploy_hash<Tx: Transaction>(
@@ -1684,6 +1920,153 @@ impl Storage {
         Ok(txn.get_value(self.deploy_metadata_db, deploy_hash)?)
     }
 
+    /// Retrieves the execution results of all deploys and transfers in a block, in the order in
+    /// which they were executed.
+    ///
+    /// Returns `None` if the block or any of its execution results are not stored.
+    fn get_block_execution_results<Tx: Transaction>(
+        &self,
+        txn: &mut Tx,
+        block_hash: &BlockHash,
+    ) -> Result<Option<Vec<(DeployHash, ExecutionResult)>>, FatalStorageError> {
+        let block = match self.get_single_block(txn, block_hash)? {
+            Some(block) => block,
+            None => return Ok(None),
+        };
+        let mut execution_results =
+            Vec::with_capacity(block.deploy_hashes().len() + block.transfer_hashes().len());
+        for deploy_hash in block.deploy_hashes().iter().chain(block.transfer_hashes()) {
+            let maybe_execution_result = self
+                .get_deploy_metadata(txn, deploy_hash)?
+                .and_then(|mut metadata| metadata.execution_results.remove(block_hash));
+            match maybe_execution_result {
+                Some(execution_result) => execution_results.push((*deploy_hash, execution_result)),
+                None => return Ok(None),
+            }
+        }
+        Ok(Some(execution_results))
+    }
+
+    /// Records the accounts involved in the given execution results of a block in the account
+    /// history database, if enabled.
+    ///
+    /// The sender of each deploy, as well as the source and target accounts of any transfers it
+    /// made, are recorded.  Transfers from the sender's own account are only recorded in its
+    /// history as sent by it.
+    fn put_account_history(
+        &self,
+        txn: &mut RwTransaction,
+        block_hash: &BlockHash,
+        execution_results: &HashMap<DeployHash, ExecutionResult>,
+    ) -> Result<(), FatalStorageError> {
+        let account_history_db = match self.account_history_db {
+            Some(account_history_db) => account_history_db,
+            None => return Ok(()),
+        };
+        let block_height =
+            match txn.get_value::<_, BlockHeader>(self.block_header_db, block_hash)? {
+                Some(block_header) => block_header.height(),
+                None => {
+                    warn!(%block_hash, "not indexing account history for unknown block");
+                    return Ok(());
+                }
+            };
+
+        for (deploy_hash, execution_result) in execution_results {
+            let mut involved_accounts = vec![];
+            let maybe_sender = match txn.get_value::<_, Deploy>(self.deploy_db, deploy_hash)? {
+                Some(deploy) => Some(deploy.header().account().to_account_hash()),
+                None => {
+                    warn!(%deploy_hash, "not indexing account history of unknown deploy sender");
+                    None
+                }
+            };
+            if let Some(sender) = maybe_sender {
+                involved_accounts.push((sender, AccountHistoryRole::Sender));
+            }
+            let success = match execution_result {
+                ExecutionResult::Success { effect, .. } => {
+                    for transform_entry in &effect.transforms {
+                        if let Transform::WriteTransfer(transfer) = &transform_entry.transform {
+                            if maybe_sender != Some(transfer.from) {
+                                involved_accounts
+                                    .push((transfer.from, AccountHistoryRole::TransferSource));
+                            }
+                            if let Some(to) = transfer.to {
+                                involved_accounts.push((to, AccountHistoryRole::TransferTarget));
+                            }
+                        }
+                    }
+                    true
+                }
+                ExecutionResult::Failure { .. } => false,
+            };
+
+            for (account_hash, role) in involved_accounts {
+                let key = account_history_key(&account_hash, block_height, deploy_hash, role);
+                let entry = AccountHistoryEntry {
+                    deploy_hash: *deploy_hash,
+                    block_hash: *block_hash,
+                    block_height,
+                    role,
+                    success,
+                };
+                let _ = txn.put_value(account_history_db, &key, &entry, true)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Retrieves up to `limit` entries of the history of the given account, in order of block
+    /// height, starting after the entry identified by `cursor`.
+    ///
+    /// Along with the entries, returns the cursor from which to continue if more entries exist.
+    /// Returns `None` if the account history database is not enabled.
+    #[allow(clippy::type_complexity)]
+    fn get_account_history<Tx: Transaction>(
+        &self,
+        txn: &mut Tx,
+        account_hash: &AccountHash,
+        cursor: Option<&[u8]>,
+        limit: usize,
+    ) -> Result<Option<(Vec<AccountHistoryEntry>, Option<Vec<u8>>)>, FatalStorageError> {
+        let account_history_db = match self.account_history_db {
+            Some(account_history_db) => account_history_db,
+            None => return Ok(None),
+        };
+        let prefix = account_hash.as_bytes();
+        let mut start_key = prefix.to_vec();
+        if let Some(cursor) = cursor {
+            start_key.extend_from_slice(cursor);
+        }
+
+        let mut entries = vec![];
+        let mut last_key: Option<&[u8]> = None;
+        let mut next_cursor = None;
+        let mut lmdb_cursor = txn.open_ro_cursor(account_history_db)?;
+        // `iter_from` panics if no key is greater than or equal to `start_key`, so check first.
+        match lmdb_cursor.get(Some(&start_key), None, MDB_SET_RANGE) {
+            Ok(_) => (),
+            Err(lmdb::Error::NotFound) => return Ok(Some((entries, next_cursor))),
+            Err(error) => return Err(error.into()),
+        }
+        for (raw_key, raw_value) in lmdb_cursor.iter_from(&start_key) {
+            if !raw_key.starts_with(prefix) {
+                break;
+            }
+            if cursor.is_some() && raw_key == start_key.as_slice() {
+                continue;
+            }
+            if entries.len() >= limit {
+                next_cursor = last_key.map(|key| key[prefix.len()..].to_vec());
+                break;
+            }
+            entries.push(lmdb_ext::deserialize(raw_value)?);
+            last_key = Some(raw_key);
+        }
+        Ok(Some((entries, next_cursor)))
+    }
+
     /// Retrieves transfers associated with block.
     ///
     /// If no transfers are stored for the block, an empty transfers instance will be
@@ -1825,7 +2208,7 @@ impl Storage {
         }
 
         let message = Message::new_get_response_from_serialized(<T as Item>::TAG, shared);
-        Ok(effect_builder.send_message(sender, message).ignore())
+        Ok(effect_builder.enqueue_message(sender, message).ignore())
     }
 
     /// Returns `true` if the storage should attempt to return a block. Depending on the
@@ -1843,12 +2226,44 @@ impl Storage {
         }
     }
 
-    fn get_available_block_range(&self) -> AvailableBlockRange {
+    /// Returns the hashes of the blocks within the given height range, in order of height,
+    /// restricted to the completed block range.
+    ///
+    /// Blocks whose global state has been pruned are still included, as their data is retained.
+    fn completed_block_hashes_in_range(
+        &self,
+        from_height: u64,
+        to_height: u64,
+    ) -> impl Iterator<Item = &BlockHash> {
+        let completed_block_range = self.get_completed_block_range();
+        let from_height = from_height.max(completed_block_range.low());
+        let to_height = to_height.min(completed_block_range.high());
+        // `BTreeMap::range` panics if the start of the range exceeds its end.
+        let maybe_range = (from_height <= to_height).then(|| from_height..=to_height);
+        maybe_range
+            .into_iter()
+            .flat_map(move |range| self.block_height_index.range(range))
+            .map(|(_, block_hash)| block_hash)
+    }
+
+    /// Returns the height range of the highest contiguous sequence of completed blocks, regardless
+    /// of whether their global state has been pruned.
+    fn get_completed_block_range(&self) -> AvailableBlockRange {
         match self.completed_blocks.highest_sequence() {
-            Some(&seq) => seq.into(),
+            Some(&seq) => AvailableBlockRange::from(seq),
             None => AvailableBlockRange::RANGE_0_0,
         }
     }
+
+    /// Returns the height range of fully available blocks, excluding those whose global state has
+    /// been pruned.
+    fn get_available_block_range(&self) -> AvailableBlockRange {
+        let available_block_range = self.get_completed_block_range();
+        match self.lowest_retained_state_height {
+            Some(block_height) => available_block_range.with_low_at_least(block_height),
+            None => available_block_range,
+        }
+    }
 }
 
 /// Decodes an item's ID, typically from an incoming request.
@@ -1997,6 +2412,24 @@ fn move_storage_files_to_network_subdir(
     Ok(())
 }
 
+/// Constructs the key under which an entry is stored in the account history database.
+///
+/// Keys consist of the account hash followed by the big-endian block height, the deploy hash and
+/// the role, so that an account's entries are contiguous and ordered by block height.
+fn account_history_key(
+    account_hash: &AccountHash,
+    block_height: u64,
+    deploy_hash: &DeployHash,
+    role: AccountHistoryRole,
+) -> Vec<u8> {
+    let mut key = Vec::with_capacity(ACCOUNT_HISTORY_KEY_LENGTH);
+    key.extend_from_slice(account_hash.as_bytes());
+    key.extend_from_slice(&block_height.to_be_bytes());
+    key.extend_from_slice(deploy_hash.as_ref());
+    key.push(role as u8);
+    key
+}
+
 /// On-disk storage configuration.
 #[derive(Clone, DataSize, Debug, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
@@ -2025,6 +2458,11 @@ pub struct Config {
     enable_mem_deduplication: bool,
     /// How many loads before memory duplication checks for dead references.
     mem_pool_prune_interval: u16,
+    /// Whether or not to maintain an index of the deploys each account was involved in.
+    ///
+    /// Only deploys whose execution results are stored after enabling the index are recorded.
+    #[serde(default)]
+    enable_account_history_index: bool,
 }
 
 impl Default for Config {
@@ -2038,6 +2476,7 @@ impl Default for Config {
             max_state_store_size: DEFAULT_MAX_STATE_STORE_SIZE,
             enable_mem_deduplication: true,
             mem_pool_prune_interval: 4096,
+            enable_account_history_index: false,
         }
     }
 }
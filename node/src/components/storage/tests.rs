@@ -10,9 +10,11 @@ use rand::{prelude::SliceRandom, Rng};
 use serde::{Deserialize, Serialize};
 use smallvec::smallvec;
 
+use casper_hashing::Digest;
 use casper_types::{
-    system::auction::UnbondingPurse, testing::TestRng, AccessRights, EraId, ExecutionResult,
-    ProtocolVersion, PublicKey, SecretKey, URef, U512,
+    account::AccountHash, system::auction::UnbondingPurse, testing::TestRng, AccessRights, EraId,
+    ExecutionEffect, ExecutionResult, ProtocolVersion, PublicKey, SecretKey, Transfer, Transform,
+    TransformEntry, URef, U512,
 };
 
 use super::{
@@ -24,8 +26,9 @@ use crate::{
     storage::lmdb_ext::{deserialize_internal, serialize_internal},
     testing::{ComponentHarness, UnitTestEvent},
     types::{
-        Block, BlockHash, BlockHashAndHeight, BlockHeader, BlockSignatures, Deploy, DeployHash,
-        DeployMetadata, DeployMetadataExt, DeployWithFinalizedApprovals, FinalitySignature,
+        AccountHistoryEntry, AccountHistoryRole, AvailableBlockRange, Block, BlockHash,
+        BlockHashAndHeight, BlockHeader, BlockSignatures, Deploy, DeployHash, DeployMetadata,
+        DeployMetadataExt, DeployWithFinalizedApprovals, FinalitySignature,
     },
     utils::WithDir,
 };
@@ -42,6 +45,7 @@ fn new_config(harness: &ComponentHarness<UnitTestEvent>) -> Config {
         max_state_store_size: 50 * MIB,
         enable_mem_deduplication: true,
         mem_pool_prune_interval: 4,
+        enable_account_history_index: true,
     }
 }
 
@@ -309,6 +313,45 @@ fn put_execution_results(
     assert!(harness.is_idle());
 }
 
+/// Loads the execution results of all deploys in a block from a storage component.
+fn get_block_execution_results(
+    harness: &mut ComponentHarness<UnitTestEvent>,
+    storage: &mut Storage,
+    block_hash: BlockHash,
+) -> Option<Vec<(DeployHash, ExecutionResult)>> {
+    let response = harness.send_request(storage, move |responder| {
+        StorageRequest::GetBlockExecutionResults {
+            block_hash,
+            responder,
+        }
+        .into()
+    });
+    assert!(harness.is_idle());
+    response
+}
+
+/// Loads a page of the history of an account from a storage component.
+#[allow(clippy::type_complexity)]
+fn get_account_history(
+    harness: &mut ComponentHarness<UnitTestEvent>,
+    storage: &mut Storage,
+    account_hash: AccountHash,
+    cursor: Option<Vec<u8>>,
+    limit: usize,
+) -> Option<(Vec<AccountHistoryEntry>, Option<Vec<u8>>)> {
+    let response = harness.send_request(storage, move |responder| {
+        StorageRequest::GetAccountHistory {
+            account_hash: Box::new(account_hash),
+            cursor,
+            limit,
+            responder,
+        }
+        .into()
+    });
+    assert!(harness.is_idle());
+    response
+}
+
 #[test]
 fn get_block_of_non_existing_block_returns_none() {
     let mut harness = ComponentHarness::default();
@@ -837,6 +880,189 @@ fn store_identical_execution_results() {
     put_execution_results(&mut harness, &mut storage, block_hash, exec_result);
 }
 
+#[test]
+fn should_get_block_execution_results_in_execution_order() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+
+    let deploys: Vec<Deploy> = iter::repeat_with(|| Deploy::random(&mut harness.rng))
+        .take(3)
+        .collect();
+    let block = Block::random_with_specifics(
+        &mut harness.rng,
+        EraId::from(1),
+        10,
+        ProtocolVersion::V1_0_0,
+        false,
+        deploys.iter(),
+    );
+    let block_hash = *block.hash();
+    put_block(&mut harness, &mut storage, Box::new(block.clone()));
+
+    // Store results for all but the last deploy.
+    let mut execution_results = HashMap::new();
+    for deploy in &deploys[..2] {
+        execution_results.insert(*deploy.id(), harness.rng.gen());
+    }
+    put_execution_results(
+        &mut harness,
+        &mut storage,
+        block_hash,
+        execution_results.clone(),
+    );
+    assert!(get_block_execution_results(&mut harness, &mut storage, block_hash).is_none());
+
+    execution_results.insert(*deploys[2].id(), harness.rng.gen());
+    put_execution_results(
+        &mut harness,
+        &mut storage,
+        block_hash,
+        execution_results.clone(),
+    );
+    let expected: Vec<(DeployHash, ExecutionResult)> = block
+        .deploy_hashes()
+        .iter()
+        .chain(block.transfer_hashes())
+        .map(|deploy_hash| (*deploy_hash, execution_results[deploy_hash].clone()))
+        .collect();
+    assert_eq!(expected.len(), 3);
+    assert_eq!(
+        get_block_execution_results(&mut harness, &mut storage, block_hash),
+        Some(expected)
+    );
+
+    // Results are unknown for a block which is not stored.
+    let unknown_block_hash = BlockHash::random(&mut harness.rng);
+    assert!(get_block_execution_results(&mut harness, &mut storage, unknown_block_hash).is_none());
+}
+
+#[test]
+fn should_index_account_history() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+
+    let deploys: Vec<Deploy> = iter::repeat_with(|| Deploy::random(&mut harness.rng))
+        .take(4)
+        .collect();
+    let block = Block::random_with_specifics(
+        &mut harness.rng,
+        EraId::from(1),
+        10,
+        ProtocolVersion::V1_0_0,
+        false,
+        deploys.iter(),
+    );
+    let block_hash = *block.hash();
+    put_block(&mut harness, &mut storage, Box::new(block));
+    for deploy in &deploys {
+        put_deploy(&mut harness, &mut storage, Box::new(deploy.clone()));
+    }
+
+    // The first two deploys each transfer from `source` to `target`, the third one fails and the
+    // last one transfers from its own sender.
+    let source = AccountHash::new(harness.rng.gen());
+    let target = AccountHash::new(harness.rng.gen());
+    let mut execution_results = HashMap::new();
+    for (index, deploy) in deploys.iter().enumerate().filter(|(index, _)| *index != 2) {
+        let (from, to) = if index < 2 {
+            (source, Some(target))
+        } else {
+            (deploy.header().account().to_account_hash(), None)
+        };
+        let transfer = Transfer::new(
+            (*deploy.id()).into(),
+            from,
+            to,
+            URef::new(harness.rng.gen(), AccessRights::READ_ADD_WRITE),
+            URef::new(harness.rng.gen(), AccessRights::READ_ADD_WRITE),
+            U512::one(),
+            U512::zero(),
+            None,
+        );
+        let effect = ExecutionEffect::new(vec![TransformEntry {
+            key: String::new(),
+            transform: Transform::WriteTransfer(transfer),
+        }]);
+        let execution_result = ExecutionResult::Success {
+            effect,
+            transfers: vec![],
+            cost: U512::zero(),
+            messages: vec![],
+        };
+        execution_results.insert(*deploy.id(), execution_result);
+    }
+    let failure = ExecutionResult::Failure {
+        effect: ExecutionEffect::default(),
+        transfers: vec![],
+        cost: U512::zero(),
+        error_message: String::new(),
+    };
+    execution_results.insert(*deploys[2].id(), failure);
+    put_execution_results(&mut harness, &mut storage, block_hash, execution_results);
+
+    // Each deploy is recorded in the history of its sender, only once even if the sender is also
+    // the source of a transfer.
+    for (index, deploy) in deploys.iter().enumerate() {
+        let sender = deploy.header().account().to_account_hash();
+        let (entries, next_cursor) =
+            get_account_history(&mut harness, &mut storage, sender, None, 10)
+                .expect("account history should be enabled");
+        let expected = AccountHistoryEntry {
+            deploy_hash: *deploy.id(),
+            block_hash,
+            block_height: 10,
+            role: AccountHistoryRole::Sender,
+            success: index != 2,
+        };
+        assert_eq!(entries, vec![expected]);
+        assert!(next_cursor.is_none());
+    }
+
+    // The transfers are recorded in the history of both the source and target, one page at a
+    // time.
+    for (account_hash, role) in [
+        (source, AccountHistoryRole::TransferSource),
+        (target, AccountHistoryRole::TransferTarget),
+    ] {
+        let (first_page, cursor) =
+            get_account_history(&mut harness, &mut storage, account_hash, None, 1)
+                .expect("account history should be enabled");
+        assert_eq!(first_page.len(), 1);
+        assert!(cursor.is_some());
+        let (second_page, cursor) =
+            get_account_history(&mut harness, &mut storage, account_hash, cursor, 1)
+                .expect("account history should be enabled");
+        assert_eq!(second_page.len(), 1);
+        assert!(cursor.is_none());
+
+        let mut deploy_hashes: Vec<DeployHash> = first_page
+            .iter()
+            .chain(second_page.iter())
+            .map(|entry| {
+                assert_eq!(entry.role, role);
+                assert!(entry.success);
+                entry.deploy_hash
+            })
+            .collect();
+        deploy_hashes.sort();
+        let mut expected: Vec<DeployHash> =
+            deploys[..2].iter().map(|deploy| *deploy.id()).collect();
+        expected.sort();
+        assert_eq!(deploy_hashes, expected);
+    }
+
+    // An unrelated account has no history, including one which sorts after every stored entry.
+    for unrelated in [
+        AccountHash::new(harness.rng.gen()),
+        AccountHash::new([u8::MAX; 32]),
+    ] {
+        assert_eq!(
+            get_account_history(&mut harness, &mut storage, unrelated, None, 10),
+            Some((vec![], None))
+        );
+    }
+}
+
 /// Example state used in storage.
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 struct StateData {
@@ -1260,6 +1486,186 @@ fn should_restrict_returned_blocks() {
         .expect("should return block failed"));
 }
 
+#[test]
+fn should_get_blocks_and_switch_block_headers_in_range() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+
+    // Create blocks 0-5, where blocks 1, 3 and 5 are the switch blocks of eras 0, 1 and 2, and
+    // only 2-5 are stored completely.
+    for height in 0..=5 {
+        let block = Block::random_with_specifics(
+            &mut harness.rng,
+            EraId::from(height / 2),
+            height,
+            ProtocolVersion::from_parts(1, 5, 0),
+            height % 2 == 1,
+            None,
+        );
+        storage.write_block(&block).unwrap();
+        if height >= 2 {
+            storage.completed_blocks.insert(height);
+        }
+    }
+
+    // Pruning the global state of blocks 2 and 3 shouldn't affect which blocks are returned.
+    harness.send_request(&mut storage, |responder| {
+        StorageRequest::PutLowestRetainedStateHeight {
+            block_height: 4,
+            responder,
+        }
+        .into()
+    });
+
+    let mut get_block_header_heights = |from_height: u64, to_height: u64, limit: usize| {
+        let block_headers = harness.send_request(&mut storage, |responder| {
+            StorageRequest::GetBlockHeadersInRange {
+                from_height,
+                to_height,
+                limit,
+                responder,
+            }
+            .into()
+        });
+        block_headers
+            .iter()
+            .map(|block_header| block_header.height())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(get_block_header_heights(0, u64::MAX, 10), vec![2, 3, 4, 5]);
+    assert_eq!(get_block_header_heights(0, u64::MAX, 2), vec![2, 3]);
+    assert_eq!(get_block_header_heights(3, 4, 10), vec![3, 4]);
+    assert!(get_block_header_heights(0, 1, 10).is_empty());
+    assert!(get_block_header_heights(6, u64::MAX, 10).is_empty());
+
+    let blocks_with_metadata = harness.send_request(&mut storage, |responder| {
+        StorageRequest::GetBlocksAndMetadataInRange {
+            from_height: 3,
+            to_height: 4,
+            limit: 10,
+            responder,
+        }
+        .into()
+    });
+    let block_heights: Vec<u64> = blocks_with_metadata
+        .iter()
+        .map(|block_with_metadata| block_with_metadata.block.height())
+        .collect();
+    assert_eq!(block_heights, vec![3, 4]);
+
+    // The switch block of era 0 is not available.
+    let mut get_switch_block_era_ids = |from_era_id: EraId, to_era_id: EraId, limit: usize| {
+        let switch_block_headers = harness.send_request(&mut storage, |responder| {
+            StorageRequest::GetSwitchBlockHeadersInEraRange {
+                from_era_id,
+                to_era_id,
+                limit,
+                responder,
+            }
+            .into()
+        });
+        switch_block_headers
+            .iter()
+            .map(|block_header| block_header.era_id())
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        get_switch_block_era_ids(EraId::from(0), EraId::MAX, 10),
+        vec![EraId::from(1), EraId::from(2)]
+    );
+    assert_eq!(
+        get_switch_block_era_ids(EraId::from(0), EraId::MAX, 1),
+        vec![EraId::from(1)]
+    );
+    assert!(get_switch_block_era_ids(EraId::from(2), EraId::from(1), 10).is_empty());
+}
+
+#[test]
+fn should_exclude_pruned_state_from_available_block_range() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+
+    for height in 0..=5 {
+        storage.completed_blocks.insert(height);
+    }
+    assert_eq!(
+        storage.get_available_block_range(),
+        AvailableBlockRange::new(0, 5)
+    );
+    let response = harness.send_request(&mut storage, |responder| {
+        StorageRequest::GetLowestRetainedStateHeight { responder }.into()
+    });
+    assert_eq!(response, None);
+
+    harness.send_request(&mut storage, |responder| {
+        StorageRequest::PutLowestRetainedStateHeight {
+            block_height: 3,
+            responder,
+        }
+        .into()
+    });
+    assert_eq!(
+        storage.get_available_block_range(),
+        AvailableBlockRange::new(3, 5)
+    );
+    assert!(!storage
+        .should_return_block(2, true)
+        .expect("should return block failed"));
+    assert!(storage
+        .should_return_block(2, false)
+        .expect("should return block failed"));
+
+    // The height should be persisted across instantiations.
+    let (on_disk, rng) = harness.into_parts();
+    let mut harness = ComponentHarness::builder()
+        .on_disk(on_disk)
+        .rng(rng)
+        .build();
+    let mut storage = storage_fixture(&harness);
+    let response = harness.send_request(&mut storage, |responder| {
+        StorageRequest::GetLowestRetainedStateHeight { responder }.into()
+    });
+    assert_eq!(response, Some(3));
+}
+
+#[test]
+fn should_record_pruned_state_roots() {
+    let mut harness = ComponentHarness::default();
+    let mut storage = storage_fixture(&harness);
+
+    let shared_state_root = Digest::hash(b"shared");
+    let other_state_root = Digest::hash(b"other");
+    let unknown_state_root = Digest::hash(b"unknown");
+    harness.send_request(&mut storage, |responder| {
+        StorageRequest::PutPrunedStateRoots {
+            pruned_state_roots: vec![(shared_state_root, 2), (other_state_root, 3)],
+            responder,
+        }
+        .into()
+    });
+    // A state root shared by several blocks should keep the highest height.
+    harness.send_request(&mut storage, |responder| {
+        StorageRequest::PutPrunedStateRoots {
+            pruned_state_roots: vec![(shared_state_root, 5), (other_state_root, 1)],
+            responder,
+        }
+        .into()
+    });
+
+    let mut get_height = |state_root_hash| {
+        harness.send_request(&mut storage, |responder| {
+            StorageRequest::GetPrunedStateRootHeight {
+                state_root_hash,
+                responder,
+            }
+            .into()
+        })
+    };
+    assert_eq!(get_height(shared_state_root), Some(5));
+    assert_eq!(get_height(other_state_root), Some(3));
+    assert_eq!(get_height(unknown_state_root), None);
+}
+
 #[test]
 fn should_get_block_header_by_height() {
     let mut harness = ComponentHarness::default();
@@ -16,9 +16,9 @@
 //!
 //! * (unbound) events: These events are not associated with a particular reactor or component and
 //!   represent information or requests by themselves. An example is the
-//!   [`BlocklistAnnouncement`](`crate::effect::announcements::BlocklistAnnouncement`), it can be
-//!   emitted through an effect by different components and contains the ID of a peer that should be
-//!   shunned. It is not associated with a particular reactor or component though.
+//!   [`PeerBehaviorAnnouncement`](`crate::effect::announcements::PeerBehaviorAnnouncement`), it
+//!   can be emitted through an effect by different components and contains the ID of a peer that
+//!   misbehaved. It is not associated with a particular reactor or component though.
 //!
 //!   While the node is running, these unbound events cannot exist on their own, instead they are
 //!   typically converted into a concrete reactor event by the effect builder as soon as they are
@@ -117,17 +117,19 @@ use tracing::{debug, error, warn};
 use casper_execution_engine::{
     core::engine_state::{
         self, era_validators::GetEraValidatorsError, genesis::GenesisSuccess, BalanceRequest,
-        BalanceResult, GetBidsRequest, GetBidsResult, QueryRequest, QueryResult, UpgradeConfig,
-        UpgradeSuccess,
+        BalanceResult, GetBidsRequest, GetBidsResult, GetKeysByPrefixRequest,
+        GetKeysByPrefixResult, QueryRequest, QueryResult, UpgradeConfig, UpgradeSuccess,
     },
     shared::execution_journal::ExecutionJournal,
     storage::trie::{TrieOrChunk, TrieOrChunkId},
 };
 use casper_hashing::Digest;
 use casper_types::{
-    account::Account, bytesrepr::Bytes, system::auction::EraValidators, Contract, ContractPackage,
-    EraId, ExecutionEffect, ExecutionResult, Key, ProtocolVersion, PublicKey, TimeDiff, Timestamp,
-    Transfer, URef, U512,
+    account::{Account, AccountHash},
+    bytesrepr::Bytes,
+    system::auction::EraValidators,
+    Contract, ContractHash, ContractMessage, ContractPackage, EraId, ExecutionEffect,
+    ExecutionResult, Key, ProtocolVersion, PublicKey, TimeDiff, Timestamp, Transfer, URef, U512,
 };
 
 use crate::{
@@ -137,16 +139,17 @@ use crate::{
         consensus::{BlockContext, ClContext, EraDump, ValidatorChange},
         contract_runtime::{
             BlockAndExecutionEffects, BlockExecutionError, EraValidatorsRequest, ExecutionPreState,
+            SpeculativeExecutionResults,
         },
         deploy_acceptor,
         fetcher::FetchResult,
-        small_network::FromIncoming,
+        small_network::{FromIncoming, PeerScore},
     },
-    contract_runtime::SpeculativeExecutionState,
+    contract_runtime::{SpeculativeExecutionOverrides, SpeculativeExecutionState},
     effect::announcements::ChainSynchronizerAnnouncement,
     reactor::{EventQueueHandle, QueueKind},
     types::{
-        AvailableBlockRange, Block, BlockAndDeploys, BlockHash, BlockHeader,
+        AccountHistoryEntry, AvailableBlockRange, Block, BlockAndDeploys, BlockHash, BlockHeader,
         BlockHeaderWithMetadata, BlockHeadersBatch, BlockHeadersBatchId, BlockPayload,
         BlockSignatures, BlockWithMetadata, Chainspec, ChainspecInfo, ChainspecRawBytes, Deploy,
         DeployHash, DeployHeader, DeployMetadataExt, DeployWithFinalizedApprovals,
@@ -155,10 +158,10 @@ use crate::{
     utils::{fmt_limit::FmtLimit, SharedFlag, Source},
 };
 use announcements::{
-    BlockProposerAnnouncement, BlocklistAnnouncement, ChainspecLoaderAnnouncement,
-    ConsensusAnnouncement, ContractRuntimeAnnouncement, ControlAnnouncement,
-    DeployAcceptorAnnouncement, GossiperAnnouncement, LinearChainAnnouncement, QueueDumpFormat,
-    RpcServerAnnouncement,
+    BlockProposerAnnouncement, ChainspecLoaderAnnouncement, ConsensusAnnouncement,
+    ContractRuntimeAnnouncement, ControlAnnouncement, DeployAcceptorAnnouncement,
+    GossiperAnnouncement, LinearChainAnnouncement, Offense, PeerBehaviorAnnouncement,
+    QueueDumpFormat, RpcServerAnnouncement,
 };
 use diagnostics_port::DumpConsensusStateRequest;
 use requests::{
@@ -754,7 +757,20 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
-    /// Gets the current network peers in random order.
+    /// Gets the scores of all peers we have observed the behavior of, best scored first.
+    pub(crate) async fn get_peer_scores(self) -> Vec<PeerScore>
+    where
+        REv: From<NetworkInfoRequest>,
+    {
+        self.make_request(
+            |responder| NetworkInfoRequest::PeerScores { responder },
+            QueueKind::Api,
+        )
+        .await
+    }
+
+    /// Gets the current network peers in random order, with peers penalized for their behavior
+    /// last.
     pub async fn get_fully_connected_peers(self) -> Vec<NodeId>
     where
         REv: From<NetworkInfoRequest>,
@@ -766,7 +782,8 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
-    /// Gets the current network non-syncing peers in random order.
+    /// Gets the current network non-syncing peers in random order, with peers penalized for their
+    /// behavior last.
     pub async fn get_fully_connected_non_syncing_peers(self) -> Vec<NodeId>
     where
         REv: From<NetworkInfoRequest>,
@@ -938,6 +955,8 @@ impl<REv> EffectBuilder<REv> {
         self,
         block: Box<Block>,
         execution_results: Vec<(DeployHash, DeployHeader, ExecutionResult)>,
+        contract_messages: Vec<(DeployHash, Vec<ContractMessage>)>,
+        invoked_contracts: Vec<(DeployHash, Vec<ContractHash>)>,
     ) where
         REv: From<ContractRuntimeAnnouncement>,
     {
@@ -946,6 +965,8 @@ impl<REv> EffectBuilder<REv> {
                 ContractRuntimeAnnouncement::LinearChainBlock {
                     block,
                     execution_results,
+                    contract_messages,
+                    invoked_contracts,
                 },
                 QueueKind::Regular,
             )
@@ -1237,6 +1258,71 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
+    /// Records that the global state of every block below `block_height` has been pruned.
+    pub(crate) async fn put_lowest_retained_state_height_to_storage(self, block_height: u64)
+    where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::PutLowestRetainedStateHeight {
+                block_height,
+                responder,
+            },
+            QueueKind::Regular,
+        )
+        .await
+    }
+
+    /// Requests the height of the lowest block whose global state is retained, if global state
+    /// has ever been pruned.
+    pub(crate) async fn get_lowest_retained_state_height_from_storage(self) -> Option<u64>
+    where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::GetLowestRetainedStateHeight { responder },
+            QueueKind::Regular,
+        )
+        .await
+    }
+
+    /// Records the state roots of blocks whose global state has been pruned, each with the height
+    /// of the respective block.
+    pub(crate) async fn put_pruned_state_roots_to_storage(
+        self,
+        pruned_state_roots: Vec<(Digest, u64)>,
+    ) where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::PutPrunedStateRoots {
+                pruned_state_roots,
+                responder,
+            },
+            QueueKind::Regular,
+        )
+        .await
+    }
+
+    /// Requests the height of the highest block whose global state under the given state root
+    /// has been pruned, if any.
+    pub(crate) async fn get_pruned_state_root_height_from_storage(
+        self,
+        state_root_hash: Digest,
+    ) -> Option<u64>
+    where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::GetPrunedStateRootHeight {
+                state_root_hash,
+                responder,
+            },
+            QueueKind::Regular,
+        )
+        .await
+    }
+
     /// Get a trie or chunk by its ID.
     pub(crate) async fn get_trie(
         self,
@@ -1362,6 +1448,51 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
+    /// Gets the execution results of all deploys and transfers in the given block, in execution
+    /// order.
+    pub(crate) async fn get_block_execution_results_from_storage(
+        self,
+        block_hash: BlockHash,
+    ) -> Option<Vec<(DeployHash, ExecutionResult)>>
+    where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::GetBlockExecutionResults {
+                block_hash,
+                responder,
+            },
+            QueueKind::Regular,
+        )
+        .await
+    }
+
+    /// Gets up to `limit` entries of the history of deploys the given account was involved in,
+    /// continuing from `cursor` if given.
+    ///
+    /// Returns `None` if the account history index is not enabled.
+    #[allow(clippy::type_complexity)]
+    pub(crate) async fn get_account_history_from_storage(
+        self,
+        account_hash: AccountHash,
+        cursor: Option<Vec<u8>>,
+        limit: usize,
+    ) -> Option<(Vec<AccountHistoryEntry>, Option<Vec<u8>>)>
+    where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::GetAccountHistory {
+                account_hash: Box::new(account_hash),
+                cursor,
+                limit,
+                responder,
+            },
+            QueueKind::Regular,
+        )
+        .await
+    }
+
     /// Gets the requested deploys from the deploy store.
     pub(crate) async fn get_deploy_and_metadata_from_storage(
         self,
@@ -1480,6 +1611,75 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
+    /// Gets up to `limit` blocks with their associated metadata within the given height range,
+    /// restricted to the completed block range.
+    pub(crate) async fn get_blocks_with_metadata_in_range_from_storage(
+        self,
+        from_height: u64,
+        to_height: u64,
+        limit: usize,
+    ) -> Vec<BlockWithMetadata>
+    where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::GetBlocksAndMetadataInRange {
+                from_height,
+                to_height,
+                limit,
+                responder,
+            },
+            QueueKind::Regular,
+        )
+        .await
+    }
+
+    /// Gets up to `limit` block headers within the given height range, restricted to the completed
+    /// block range.
+    pub(crate) async fn get_block_headers_in_range_from_storage(
+        self,
+        from_height: u64,
+        to_height: u64,
+        limit: usize,
+    ) -> Vec<BlockHeader>
+    where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::GetBlockHeadersInRange {
+                from_height,
+                to_height,
+                limit,
+                responder,
+            },
+            QueueKind::Regular,
+        )
+        .await
+    }
+
+    /// Gets up to `limit` headers of the switch blocks ending the eras within the given range,
+    /// restricted to the completed block range.
+    pub(crate) async fn get_switch_block_headers_in_era_range_from_storage(
+        self,
+        from_era_id: EraId,
+        to_era_id: EraId,
+        limit: usize,
+    ) -> Vec<BlockHeader>
+    where
+        REv: From<StorageRequest>,
+    {
+        self.make_request(
+            |responder| StorageRequest::GetSwitchBlockHeadersInEraRange {
+                from_era_id,
+                to_era_id,
+                limit,
+                responder,
+            },
+            QueueKind::Regular,
+        )
+        .await
+    }
+
     /// Gets the highest block with its associated metadata.
     pub(crate) async fn get_highest_block_with_metadata_from_storage(
         self,
@@ -1657,14 +1857,44 @@ impl<REv> EffectBuilder<REv> {
             .await
     }
 
-    /// Announce the intent to disconnect from a specific peer, which consensus thinks is faulty.
-    pub(crate) async fn announce_disconnect_from_peer(self, peer: NodeId)
+    /// Announce that a specific peer committed an offense, lowering its score and likely causing
+    /// us to disconnect from it.
+    pub(crate) async fn announce_offense(self, peer: NodeId, offense: Offense)
+    where
+        REv: From<PeerBehaviorAnnouncement>,
+    {
+        self.event_queue
+            .schedule(
+                PeerBehaviorAnnouncement::OffenseCommitted {
+                    offender: Box::new(peer),
+                    offense,
+                },
+                QueueKind::Regular,
+            )
+            .await
+    }
+
+    /// Announce that a request to a specific peer timed out.
+    pub(crate) async fn announce_peer_timed_out(self, peer: NodeId)
     where
-        REv: From<BlocklistAnnouncement>,
+        REv: From<PeerBehaviorAnnouncement>,
     {
         self.event_queue
             .schedule(
-                BlocklistAnnouncement::OffenseCommitted(Box::new(peer)),
+                PeerBehaviorAnnouncement::TimedOut(Box::new(peer)),
+                QueueKind::Regular,
+            )
+            .await
+    }
+
+    /// Announce that a specific peer responded to a request with a valid item.
+    pub(crate) async fn announce_useful_response(self, peer: NodeId)
+    where
+        REv: From<PeerBehaviorAnnouncement>,
+    {
+        self.event_queue
+            .schedule(
+                PeerBehaviorAnnouncement::UsefulResponse(Box::new(peer)),
                 QueueKind::Regular,
             )
             .await
@@ -1978,6 +2208,25 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
+    /// Requests a page of the global state entries matching a key prefix from the Contract Runtime
+    /// component.
+    pub(crate) async fn get_keys_by_prefix(
+        self,
+        get_keys_by_prefix_request: GetKeysByPrefixRequest,
+    ) -> Result<GetKeysByPrefixResult, engine_state::Error>
+    where
+        REv: From<ContractRuntimeRequest>,
+    {
+        self.make_request(
+            |responder| ContractRuntimeRequest::GetKeysByPrefix {
+                get_keys_by_prefix_request,
+                responder,
+            },
+            QueueKind::Regular,
+        )
+        .await
+    }
+
     /// Gets the correct era validators set for the given era.
     /// Takes emergency restarts into account based on the information in the immediate switch
     /// block after a restart.
@@ -2175,12 +2424,14 @@ impl<REv> EffectBuilder<REv> {
         .await
     }
 
-    /// Requests execution of a single deploy, without commiting its effects.
+    /// Requests execution of a single deploy, with the given overrides applied to the prestate,
+    /// without commiting its effects.
     /// Inteded to be used for debugging & discovery purposes.
     pub(crate) async fn speculative_execute_deploy(
         self,
         execution_prestate: SpeculativeExecutionState,
         deploy: Deploy,
+        overrides: SpeculativeExecutionOverrides,
     ) -> Result<Option<ExecutionResult>, engine_state::Error>
     where
         REv: From<ContractRuntimeRequest>,
@@ -2189,6 +2440,30 @@ impl<REv> EffectBuilder<REv> {
             |responder| ContractRuntimeRequest::SpeculativeDeployExecution {
                 execution_prestate,
                 deploy: Box::new(deploy),
+                overrides,
+                responder,
+            },
+            QueueKind::Regular,
+        )
+        .await
+    }
+
+    /// Requests execution of a sequence of deploys, each on top of the effects of those before it,
+    /// with the given overrides applied to the prestate, without commiting their effects.
+    pub(crate) async fn speculative_execute_deploys(
+        self,
+        execution_prestate: SpeculativeExecutionState,
+        deploys: Vec<Deploy>,
+        overrides: SpeculativeExecutionOverrides,
+    ) -> Result<SpeculativeExecutionResults, engine_state::Error>
+    where
+        REv: From<ContractRuntimeRequest>,
+    {
+        self.make_request(
+            |responder| ContractRuntimeRequest::SpeculativeDeploysExecution {
+                execution_prestate,
+                deploys,
+                overrides,
                 responder,
             },
             QueueKind::Regular,
@@ -12,7 +12,10 @@ use std::{
 use itertools::Itertools;
 use serde::Serialize;
 
-use casper_types::{EraId, ExecutionEffect, ExecutionResult, PublicKey, Timestamp, U512};
+use casper_types::{
+    ContractHash, ContractMessage, EraId, ExecutionEffect, ExecutionResult, PublicKey, Timestamp,
+    U512,
+};
 
 use crate::{
     components::{
@@ -221,18 +224,49 @@ impl Display for ConsensusAnnouncement {
     }
 }
 
-/// A block-list related announcement.
+/// An offense committed by a peer.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+pub(crate) enum Offense {
+    /// The peer sent an invalid item or a response we could not use.
+    InvalidItem,
+    /// The peer sent an invalid consensus message.
+    BadConsensusMessage,
+}
+
+impl Display for Offense {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Offense::InvalidItem => f.write_str("invalid item"),
+            Offense::BadConsensusMessage => f.write_str("bad consensus message"),
+        }
+    }
+}
+
+/// An announcement about the behavior of a peer, used to score it.
 #[derive(Debug, Serialize)]
-pub(crate) enum BlocklistAnnouncement {
-    /// A given peer committed a blockable offense.
-    OffenseCommitted(Box<NodeId>),
+pub(crate) enum PeerBehaviorAnnouncement {
+    /// A given peer committed an offense.
+    OffenseCommitted {
+        /// The offending peer.
+        offender: Box<NodeId>,
+        /// The offense committed.
+        offense: Offense,
+    },
+    /// A given peer did not respond to one of our requests in time.
+    TimedOut(Box<NodeId>),
+    /// A given peer responded to one of our requests with a valid item.
+    UsefulResponse(Box<NodeId>),
 }
 
-impl Display for BlocklistAnnouncement {
+impl Display for PeerBehaviorAnnouncement {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
-            BlocklistAnnouncement::OffenseCommitted(peer) => {
-                write!(f, "peer {} committed offense", peer)
+            PeerBehaviorAnnouncement::OffenseCommitted { offender, offense } => {
+                write!(f, "peer {} committed offense: {}", offender, offense)
+            }
+            PeerBehaviorAnnouncement::TimedOut(peer) => write!(f, "peer {} timed out", peer),
+            PeerBehaviorAnnouncement::UsefulResponse(peer) => {
+                write!(f, "peer {} sent useful response", peer)
             }
         }
     }
@@ -308,6 +342,10 @@ pub(crate) enum ContractRuntimeAnnouncement {
         /// The results of executing the deploys in this block.
         // #[serde(skip_serializing)]
         execution_results: Vec<(DeployHash, DeployHeader, ExecutionResult)>,
+        /// The messages emitted by contracts while executing the deploys in this block.
+        contract_messages: Vec<(DeployHash, Vec<ContractMessage>)>,
+        /// The stored contracts invoked by hash from each deploy in this block, if any.
+        invoked_contracts: Vec<(DeployHash, Vec<ContractHash>)>,
     },
     /// A step was committed successfully and has altered global state.
     CommitStepSuccess {
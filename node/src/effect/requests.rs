@@ -23,6 +23,7 @@ use casper_execution_engine::{
         era_validators::GetEraValidatorsError,
         genesis::GenesisSuccess,
         get_bids::{GetBidsRequest, GetBidsResult},
+        keys_by_prefix::{GetKeysByPrefixRequest, GetKeysByPrefixResult},
         query::{QueryRequest, QueryResult},
         UpgradeConfig, UpgradeSuccess,
     },
@@ -30,8 +31,8 @@ use casper_execution_engine::{
 };
 use casper_hashing::Digest;
 use casper_types::{
-    bytesrepr::Bytes, system::auction::EraValidators, EraId, ExecutionResult, Key, ProtocolVersion,
-    PublicKey, TimeDiff, Transfer, URef,
+    account::AccountHash, bytesrepr::Bytes, system::auction::EraValidators, EraId, ExecutionResult,
+    Key, ProtocolVersion, PublicKey, TimeDiff, Transfer, URef,
 };
 
 use crate::{
@@ -40,19 +41,21 @@ use crate::{
         consensus::{BlockContext, ClContext, ValidatorChange},
         contract_runtime::{
             BlockAndExecutionEffects, BlockExecutionError, EraValidatorsRequest, ExecutionPreState,
+            SpeculativeExecutionResults,
         },
         deploy_acceptor::Error,
         fetcher::FetchResult,
+        small_network::PeerScore,
     },
-    contract_runtime::SpeculativeExecutionState,
+    contract_runtime::{SpeculativeExecutionOverrides, SpeculativeExecutionState},
     effect::{AutoClosingResponder, Responder},
     rpcs::{chain::BlockIdentifier, docs::OpenRpcSchema},
     types::{
-        AvailableBlockRange, Block, BlockAndDeploys, BlockHash, BlockHeader,
+        AccountHistoryEntry, AvailableBlockRange, Block, BlockAndDeploys, BlockHash, BlockHeader,
         BlockHeaderWithMetadata, BlockHeadersBatch, BlockHeadersBatchId, BlockPayload,
         BlockSignatures, BlockWithMetadata, Chainspec, ChainspecInfo, ChainspecRawBytes, Deploy,
         DeployHash, DeployMetadataExt, DeployWithFinalizedApprovals, FinalizedApprovals,
-        FinalizedBlock, Item, NodeId, NodeState, StatusFeed,
+        FinalizedBlock, Item, NodeId, NodeState, PeersMap, StatusFeed,
     },
     utils::{DisplayIter, Source},
 };
@@ -187,7 +190,7 @@ where
 }
 
 /// A networking info request.
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub(crate) enum NetworkInfoRequest {
     /// Get incoming and outgoing peers.
     Peers {
@@ -195,16 +198,24 @@ pub(crate) enum NetworkInfoRequest {
         /// Responds with a map from [NodeId]s to a socket address, represented as a string.
         responder: Responder<BTreeMap<NodeId, String>>,
     },
-    /// Get the peers in random order.
+    /// Get the peers, penalized peers last.
     FullyConnectedPeers {
-        /// Responder to be called with all connected in random order peers.
+        /// Responder to be called with all connected peers in random order, with peers penalized
+        /// for their behavior last.
         responder: Responder<Vec<NodeId>>,
     },
-    /// Get only non-syncing peers in random order.
+    /// Get only non-syncing peers, penalized peers last.
     FullyConnectedNonSyncingPeers {
-        /// Responder to be called with all connected non-syncing peers in random order.
+        /// Responder to be called with all connected non-syncing peers in random order, with peers
+        /// penalized for their behavior last.
         responder: Responder<Vec<NodeId>>,
     },
+    /// Get the scores of all peers.
+    PeerScores {
+        /// Responder to be called with the scores of all peers we have observed the behavior of,
+        /// best scored first.
+        responder: Responder<Vec<PeerScore>>,
+    },
 }
 
 impl Display for NetworkInfoRequest {
@@ -219,6 +230,9 @@ impl Display for NetworkInfoRequest {
             NetworkInfoRequest::FullyConnectedNonSyncingPeers { responder: _ } => {
                 write!(formatter, "get fully connected non-syncing peers")
             }
+            NetworkInfoRequest::PeerScores { responder: _ } => {
+                write!(formatter, "get peer scores")
+            }
         }
     }
 }
@@ -385,6 +399,28 @@ pub(crate) enum StorageRequest {
         /// Responder to call when done storing.
         responder: Responder<()>,
     },
+    /// Retrieve the execution results of all deploys and transfers in a block, in the order in
+    /// which they were executed.
+    GetBlockExecutionResults {
+        /// Hash of block.
+        block_hash: BlockHash,
+        /// Responder to call with the results.  Returns `None` if the block or any of its
+        /// execution results do not exist in local storage.
+        responder: Responder<Option<Vec<(DeployHash, ExecutionResult)>>>,
+    },
+    /// Retrieve a page of the history of deploys an account was involved in.
+    GetAccountHistory {
+        /// The account hash.
+        account_hash: Box<AccountHash>,
+        /// The cursor returned with the previous page, if any.
+        cursor: Option<Vec<u8>>,
+        /// The maximum number of entries to return.
+        limit: usize,
+        /// Responder to call with the entries and the cursor of the next page, if any.  Returns
+        /// `None` if the account history index is not enabled.
+        #[allow(clippy::type_complexity)]
+        responder: Responder<Option<(Vec<AccountHistoryEntry>, Option<Vec<u8>>)>>,
+    },
     /// Retrieve deploy and its metadata.
     GetDeployAndMetadata {
         /// Hash of deploy to be retrieved.
@@ -432,6 +468,42 @@ pub(crate) enum StorageRequest {
         /// The responder to call with the results.
         responder: Responder<Option<BlockHeaderWithMetadata>>,
     },
+    /// Retrieve the blocks and their metadata within a height range, restricted to the completed
+    /// block range, including blocks whose global state has been pruned.
+    GetBlocksAndMetadataInRange {
+        /// The height of the lowest block to retrieve.
+        from_height: BlockHeight,
+        /// The height of the highest block to retrieve.
+        to_height: BlockHeight,
+        /// The maximum number of blocks to retrieve.
+        limit: usize,
+        /// The responder to call with the results, in order of height.
+        responder: Responder<Vec<BlockWithMetadata>>,
+    },
+    /// Retrieve the block headers within a height range, restricted to the completed block range,
+    /// including blocks whose global state has been pruned.
+    GetBlockHeadersInRange {
+        /// The height of the lowest block header to retrieve.
+        from_height: BlockHeight,
+        /// The height of the highest block header to retrieve.
+        to_height: BlockHeight,
+        /// The maximum number of block headers to retrieve.
+        limit: usize,
+        /// The responder to call with the results, in order of height.
+        responder: Responder<Vec<BlockHeader>>,
+    },
+    /// Retrieve the headers of the switch blocks ending the eras within a range, restricted to the
+    /// completed block range, including blocks whose global state has been pruned.
+    GetSwitchBlockHeadersInEraRange {
+        /// The ID of the lowest era to retrieve the switch block header of.
+        from_era_id: EraId,
+        /// The ID of the highest era to retrieve the switch block header of.
+        to_era_id: EraId,
+        /// The maximum number of switch block headers to retrieve.
+        limit: usize,
+        /// The responder to call with the results, in order of era ID.
+        responder: Responder<Vec<BlockHeader>>,
+    },
     /// Get the highest block and its metadata.
     GetHighestBlockWithMetadata {
         /// The responder to call the results with.
@@ -479,6 +551,34 @@ pub(crate) enum StorageRequest {
         /// Responder to call with the result.
         responder: Responder<AvailableBlockRange>,
     },
+    /// Record that the global state of every block below the given height has been pruned.
+    PutLowestRetainedStateHeight {
+        /// Height of the lowest block whose global state is retained.
+        block_height: u64,
+        /// Responder to call once the height has been recorded.
+        responder: Responder<()>,
+    },
+    /// Retrieve the height of the lowest block whose global state is retained. Returns `None` if
+    /// global state has never been pruned.
+    GetLowestRetainedStateHeight {
+        /// Responder to call with the result.
+        responder: Responder<Option<u64>>,
+    },
+    /// Record the state roots of blocks whose global state has been pruned.
+    PutPrunedStateRoots {
+        /// State root hashes of the pruned blocks, each with the height of the respective block.
+        pruned_state_roots: Vec<(Digest, u64)>,
+        /// Responder to call once the state roots have been recorded.
+        responder: Responder<()>,
+    },
+    /// Retrieve the height of the highest block whose global state under the given state root
+    /// has been pruned.  Returns `None` if the state root is not known to have been pruned.
+    GetPrunedStateRootHeight {
+        /// The state root hash.
+        state_root_hash: Digest,
+        /// Responder to call with the result.
+        responder: Responder<Option<u64>>,
+    },
     /// Store a set of finalized approvals for a specific deploy.
     StoreFinalizedApprovals {
         /// The deploy hash to store the finalized approvals for.
@@ -537,6 +637,12 @@ impl Display for StorageRequest {
             StorageRequest::PutExecutionResults { block_hash, .. } => {
                 write!(formatter, "put execution results for {}", block_hash)
             }
+            StorageRequest::GetBlockExecutionResults { block_hash, .. } => {
+                write!(formatter, "get execution results for {}", block_hash)
+            }
+            StorageRequest::GetAccountHistory { account_hash, .. } => {
+                write!(formatter, "get account history for {}", account_hash)
+            }
             StorageRequest::GetDeployAndMetadata { deploy_hash, .. } => {
                 write!(formatter, "get deploy and metadata for {}", deploy_hash)
             }
@@ -571,6 +677,39 @@ impl Display for StorageRequest {
             StorageRequest::GetHighestBlockWithMetadata { .. } => {
                 write!(formatter, "get highest block with metadata")
             }
+            StorageRequest::GetBlocksAndMetadataInRange {
+                from_height,
+                to_height,
+                ..
+            } => {
+                write!(
+                    formatter,
+                    "get blocks and metadata for heights {} to {}",
+                    from_height, to_height
+                )
+            }
+            StorageRequest::GetBlockHeadersInRange {
+                from_height,
+                to_height,
+                ..
+            } => {
+                write!(
+                    formatter,
+                    "get block headers for heights {} to {}",
+                    from_height, to_height
+                )
+            }
+            StorageRequest::GetSwitchBlockHeadersInEraRange {
+                from_era_id,
+                to_era_id,
+                ..
+            } => {
+                write!(
+                    formatter,
+                    "get switch block headers for eras {} to {}",
+                    from_era_id, to_era_id
+                )
+            }
             StorageRequest::GetBlockSignatures { block_hash, .. } => {
                 write!(
                     formatter,
@@ -590,6 +729,34 @@ impl Display for StorageRequest {
             StorageRequest::GetAvailableBlockRange { .. } => {
                 write!(formatter, "get available block range",)
             }
+            StorageRequest::PutLowestRetainedStateHeight { block_height, .. } => {
+                write!(
+                    formatter,
+                    "put lowest retained state height: {}",
+                    block_height
+                )
+            }
+            StorageRequest::GetLowestRetainedStateHeight { .. } => {
+                write!(formatter, "get lowest retained state height")
+            }
+            StorageRequest::PutPrunedStateRoots {
+                pruned_state_roots, ..
+            } => {
+                write!(
+                    formatter,
+                    "put {} pruned state roots",
+                    pruned_state_roots.len()
+                )
+            }
+            StorageRequest::GetPrunedStateRootHeight {
+                state_root_hash, ..
+            } => {
+                write!(
+                    formatter,
+                    "get pruned state root height: {}",
+                    state_root_hash
+                )
+            }
             StorageRequest::StoreFinalizedApprovals { deploy_hash, .. } => {
                 write!(formatter, "finalized approvals for deploy {}", deploy_hash)
             }
@@ -795,10 +962,10 @@ pub(crate) enum RpcRequest {
         /// Responder to call with the result.
         responder: Responder<Option<(Deploy, DeployMetadataExt)>>,
     },
-    /// Return the connected peers.
+    /// Return the connected peers and their scores.
     GetPeers {
         /// Responder to call with the result.
-        responder: Responder<BTreeMap<NodeId, String>>,
+        responder: Responder<PeersMap>,
     },
     /// Return string formatted status or `None` if an error occurred.
     GetStatus {
@@ -813,13 +980,27 @@ pub(crate) enum RpcRequest {
     /// Executs a deploy against a specified block, returning the effects.
     /// Does not commit the effects. This is a "read-only" action.
     SpeculativeDeployExecute {
-        /// Block header representing the state on top of which we will run the deploy.
-        block_header: BlockHeader,
+        /// Block on top of which we will run the deploy.
+        block: Box<Block>,
         /// Deploy to execute.
         deploy: Box<Deploy>,
+        /// Overrides applied to the state on top of which the deploy is run.
+        overrides: SpeculativeExecutionOverrides,
         /// Responder.
         responder: Responder<Result<Option<ExecutionResult>, engine_state::Error>>,
     },
+    /// Executes a sequence of deploys against a specified block, each on top of the effects of
+    /// those before it, returning the effects.  Does not commit the effects.
+    SpeculativeDeploysExecute {
+        /// Block on top of which we will run the deploys.
+        block: Box<Block>,
+        /// Deploys to execute, in order.
+        deploys: Vec<Deploy>,
+        /// Overrides applied to the state on top of which the deploys are run.
+        overrides: SpeculativeExecutionOverrides,
+        /// Responder.
+        responder: Responder<Result<SpeculativeExecutionResults, engine_state::Error>>,
+    },
 }
 
 impl Display for RpcRequest {
@@ -881,6 +1062,9 @@ impl Display for RpcRequest {
                 write!(formatter, "get available block range")
             }
             RpcRequest::SpeculativeDeployExecute { .. } => write!(formatter, "execute deploy"),
+            RpcRequest::SpeculativeDeploysExecute { deploys, .. } => {
+                write!(formatter, "execute {} deploys", deploys.len())
+            }
         }
     }
 }
@@ -981,6 +1165,14 @@ pub(crate) enum ContractRuntimeRequest {
         /// Responder to call with the result.
         responder: Responder<Result<GetBidsResult, engine_state::Error>>,
     },
+    /// Return a page of the global state entries matching a key prefix at a given state root hash.
+    GetKeysByPrefix {
+        /// Get keys by prefix request.
+        #[serde(skip_serializing)]
+        get_keys_by_prefix_request: GetKeysByPrefixRequest,
+        /// Responder to call with the result.
+        responder: Responder<Result<GetKeysByPrefixResult, engine_state::Error>>,
+    },
     /// Check if validator is bonded in the future era (identified by `era_id`).
     IsBonded {
         /// State root hash of the LFB.
@@ -1047,9 +1239,23 @@ pub(crate) enum ContractRuntimeRequest {
         execution_prestate: SpeculativeExecutionState,
         /// Deploy to execute.
         deploy: Box<Deploy>,
+        /// Overrides applied to the prestate before executing.
+        overrides: SpeculativeExecutionOverrides,
         /// Results
         responder: Responder<Result<Option<ExecutionResult>, engine_state::Error>>,
     },
+    /// Execute a sequence of deploys without commiting results, each on top of the effects of
+    /// those before it.
+    SpeculativeDeploysExecution {
+        /// Hash of a block on top of which to execute the deploys.
+        execution_prestate: SpeculativeExecutionState,
+        /// Deploys to execute, in order.
+        deploys: Vec<Deploy>,
+        /// Overrides applied to the prestate before executing.
+        overrides: SpeculativeExecutionOverrides,
+        /// Results
+        responder: Responder<Result<SpeculativeExecutionResults, engine_state::Error>>,
+    },
 }
 
 impl Display for ContractRuntimeRequest {
@@ -1093,6 +1299,17 @@ impl Display for ContractRuntimeRequest {
                 write!(formatter, "get bids request: {:?}", get_bids_request)
             }
 
+            ContractRuntimeRequest::GetKeysByPrefix {
+                get_keys_by_prefix_request,
+                ..
+            } => {
+                write!(
+                    formatter,
+                    "get keys by prefix request: {:?}",
+                    get_keys_by_prefix_request
+                )
+            }
+
             ContractRuntimeRequest::IsBonded {
                 public_key, era_id, ..
             } => {
@@ -1129,6 +1346,18 @@ impl Display for ContractRuntimeRequest {
                     execution_prestate.state_root_hash
                 )
             }
+            ContractRuntimeRequest::SpeculativeDeploysExecution {
+                execution_prestate,
+                deploys,
+                ..
+            } => {
+                write!(
+                    formatter,
+                    "Execute {} deploys on {}",
+                    deploys.len(),
+                    execution_prestate.state_root_hash
+                )
+            }
         }
     }
 }
@@ -59,6 +59,7 @@ pub(crate) use components::{
     event_stream_server::Config as EventStreamServerConfig,
     fetcher::Config as FetcherConfig,
     gossiper::Config as GossipConfig,
+    graphql_server::Config as GraphqlServerConfig,
     rest_server::Config as RestServerConfig,
     rpc_server::{Config as RpcServerConfig, SpeculativeExecConfig},
     small_network::Config as SmallNetworkConfig,
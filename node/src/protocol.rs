@@ -16,7 +16,9 @@ use crate::{
         consensus,
         fetcher::FetchedOrNotFound,
         gossiper,
-        small_network::{EstimatorWeights, FromIncoming, GossipedAddress, MessageKind, Payload},
+        small_network::{
+            Channel, EstimatorWeights, FromIncoming, GossipedAddress, MessageKind, Payload,
+        },
     },
     effect::{
         incoming::{
@@ -87,6 +89,13 @@ impl Payload for Message {
         }
     }
 
+    fn channel(&self) -> Channel {
+        match self {
+            Message::FinalitySignature(_) => Channel::FinalitySignatures,
+            _ => self.classify().into(),
+        }
+    }
+
     fn is_low_priority(&self) -> bool {
         // We only deprioritize requested trie nodes, as they are the most commonly requested item
         // during fast sync.
@@ -63,7 +63,7 @@ use tracing_futures::Instrument;
 use crate::{
     components::{deploy_acceptor, fetcher, fetcher::FetchedOrNotFound},
     effect::{
-        announcements::{BlocklistAnnouncement, ControlAnnouncement, QueueDumpFormat},
+        announcements::{ControlAnnouncement, Offense, PeerBehaviorAnnouncement, QueueDumpFormat},
         incoming::NetResponse,
         Effect, EffectBuilder, EffectExt, Effects,
     },
@@ -932,7 +932,7 @@ fn handle_fetch_response<R, I>(
 where
     I: Item,
     R: Reactor,
-    <R as Reactor>::Event: From<fetcher::Event<I>> + From<BlocklistAnnouncement>,
+    <R as Reactor>::Event: From<fetcher::Event<I>> + From<PeerBehaviorAnnouncement>,
 {
     match fetcher::Event::<I>::from_get_response_serialized_item(sender, serialized_item) {
         Some(fetcher_event) => {
@@ -945,7 +945,7 @@ where
                 I::TAG
             );
             effect_builder
-                .announce_disconnect_from_peer(sender)
+                .announce_offense(sender, Offense::InvalidItem)
                 .ignore()
         }
     }
@@ -970,7 +970,7 @@ where
         + From<fetcher::Event<BlockHeadersBatch>>
         + From<fetcher::Event<BlockSignatures>>
         + From<fetcher::Event<Deploy>>
-        + From<BlocklistAnnouncement>,
+        + From<PeerBehaviorAnnouncement>,
 {
     match message {
         NetResponse::Deploy(ref serialized_item) => {
@@ -1000,7 +1000,7 @@ where
                         "received a deploy item we couldn't parse, banning peer",
                     );
                     return effect_builder
-                        .announce_disconnect_from_peer(sender)
+                        .announce_offense(sender, Offense::InvalidItem)
                         .ignore();
                 }
             };
@@ -1026,7 +1026,7 @@ where
                 "gossiped addresses are never fetched, banning peer",
             );
             effect_builder
-                .announce_disconnect_from_peer(sender)
+                .announce_offense(sender, Offense::InvalidItem)
                 .ignore()
         }
         NetResponse::BlockAndMetadataByHeight(ref serialized_item) => {
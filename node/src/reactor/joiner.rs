@@ -41,9 +41,9 @@ use crate::{
     contract_runtime,
     effect::{
         announcements::{
-            BlocklistAnnouncement, ChainSynchronizerAnnouncement, ChainspecLoaderAnnouncement,
+            ChainSynchronizerAnnouncement, ChainspecLoaderAnnouncement,
             ContractRuntimeAnnouncement, ControlAnnouncement, DeployAcceptorAnnouncement,
-            GossiperAnnouncement, LinearChainAnnouncement,
+            GossiperAnnouncement, LinearChainAnnouncement, PeerBehaviorAnnouncement,
         },
         diagnostics_port::DumpConsensusStateRequest,
         incoming::{
@@ -166,7 +166,7 @@ pub(crate) enum JoinerEvent {
     #[from]
     ControlAnnouncement(ControlAnnouncement),
     #[from]
-    BlocklistAnnouncement(#[serde(skip_serializing)] BlocklistAnnouncement),
+    PeerBehaviorAnnouncement(#[serde(skip_serializing)] PeerBehaviorAnnouncement),
     #[from]
     ContractRuntimeAnnouncement(#[serde(skip_serializing)] ContractRuntimeAnnouncement),
     #[from]
@@ -266,7 +266,7 @@ impl ReactorEvent for JoinerEvent {
             }
             JoinerEvent::BlockAndDeploysFetcher(_) => "BlockAndDeploysFetcher",
             JoinerEvent::BlockAndDeploysFetcherRequest(_) => "BlockAndDeploysFetcherRequest",
-            JoinerEvent::BlocklistAnnouncement(_) => "BlocklistAnnouncement",
+            JoinerEvent::PeerBehaviorAnnouncement(_) => "PeerBehaviorAnnouncement",
             JoinerEvent::StorageRequest(_) => "StorageRequest",
             JoinerEvent::MarkBlockCompletedRequest(_) => "MarkBlockCompletedRequest",
             JoinerEvent::BeginAddressGossipRequest(_) => "BeginAddressGossipRequest",
@@ -324,8 +324,8 @@ impl Display for JoinerEvent {
                 write!(f, "chain synchronizer: {}", event)
             }
             JoinerEvent::SmallNetwork(event) => write!(f, "small network: {}", event),
-            JoinerEvent::BlocklistAnnouncement(event) => {
-                write!(f, "blocklist announcement: {}", event)
+            JoinerEvent::PeerBehaviorAnnouncement(event) => {
+                write!(f, "peer behavior announcement: {}", event)
             }
             JoinerEvent::Storage(request) => write!(f, "storage: {}", request),
             JoinerEvent::RestServer(event) => write!(f, "rest server: {}", event),
@@ -666,7 +666,7 @@ impl reactor::Reactor for Reactor {
                 error!("unhandled control announcement: {}", ctrl_ann);
                 Effects::new()
             }
-            JoinerEvent::BlocklistAnnouncement(ann) => {
+            JoinerEvent::PeerBehaviorAnnouncement(ann) => {
                 self.dispatch_event(effect_builder, rng, JoinerEvent::SmallNetwork(ann.into()))
             }
             JoinerEvent::DeployAcceptorAnnouncement(
@@ -9,6 +9,7 @@ mod memory_metrics;
 mod tests;
 
 use std::{
+    collections::HashMap,
     fmt::{self, Debug, Display, Formatter},
     path::PathBuf,
     sync::Arc,
@@ -37,6 +38,7 @@ use crate::{
         event_stream_server::{self, EventStreamServer},
         fetcher::{self, Fetcher, FetcherBuilder},
         gossiper::{self, Gossiper},
+        graphql_server::GraphqlServer,
         linear_chain::{self, LinearChainComponent},
         metrics::Metrics,
         rest_server::{self, RestServer},
@@ -48,10 +50,10 @@ use crate::{
     contract_runtime,
     effect::{
         announcements::{
-            BlockProposerAnnouncement, BlocklistAnnouncement, ChainSynchronizerAnnouncement,
-            ChainspecLoaderAnnouncement, ConsensusAnnouncement, ContractRuntimeAnnouncement,
-            ControlAnnouncement, DeployAcceptorAnnouncement, GossiperAnnouncement,
-            LinearChainAnnouncement, RpcServerAnnouncement,
+            BlockProposerAnnouncement, ChainSynchronizerAnnouncement, ChainspecLoaderAnnouncement,
+            ConsensusAnnouncement, ContractRuntimeAnnouncement, ControlAnnouncement,
+            DeployAcceptorAnnouncement, GossiperAnnouncement, LinearChainAnnouncement,
+            PeerBehaviorAnnouncement, RpcServerAnnouncement,
         },
         diagnostics_port::DumpConsensusStateRequest,
         incoming::{
@@ -72,8 +74,7 @@ use crate::{
     reactor::{self, event_queue_metrics::EventQueueMetrics, EventQueueHandle, ReactorExit},
     types::{
         Block, BlockAndDeploys, BlockHeader, BlockHeaderWithMetadata, BlockHeadersBatch,
-        BlockSignatures, BlockWithMetadata, Deploy, ExitCode, FinalitySignature,
-        FinalizedApprovalsWithId,
+        BlockSignatures, BlockWithMetadata, Deploy, ExitCode, FinalizedApprovalsWithId,
     },
     utils::{Source, WithDir},
     NodeRng,
@@ -219,7 +220,7 @@ pub(crate) enum ParticipatingEvent {
     #[from]
     ChainSynchronizerAnnouncement(#[serde(skip_serializing)] ChainSynchronizerAnnouncement),
     #[from]
-    BlocklistAnnouncement(BlocklistAnnouncement),
+    PeerBehaviorAnnouncement(PeerBehaviorAnnouncement),
     #[from]
     ConsensusMessageIncoming(ConsensusMessageIncoming),
     #[from]
@@ -326,7 +327,7 @@ impl ReactorEvent for ParticipatingEvent {
             ParticipatingEvent::AddressGossiperAnnouncement(_) => "AddressGossiperAnnouncement",
             ParticipatingEvent::LinearChainAnnouncement(_) => "LinearChainAnnouncement",
             ParticipatingEvent::ChainspecLoaderAnnouncement(_) => "ChainspecLoaderAnnouncement",
-            ParticipatingEvent::BlocklistAnnouncement(_) => "BlocklistAnnouncement",
+            ParticipatingEvent::PeerBehaviorAnnouncement(_) => "PeerBehaviorAnnouncement",
             ParticipatingEvent::BlockProposerAnnouncement(_) => "BlockProposerAnnouncement",
             ParticipatingEvent::BeginAddressGossipRequest(_) => "BeginAddressGossipRequest",
             ParticipatingEvent::ConsensusMessageIncoming(_) => "ConsensusMessageIncoming",
@@ -517,8 +518,8 @@ impl Display for ParticipatingEvent {
             ParticipatingEvent::ChainspecLoaderAnnouncement(ann) => {
                 write!(f, "chainspec loader announcement: {}", ann)
             }
-            ParticipatingEvent::BlocklistAnnouncement(ann) => {
-                write!(f, "blocklist announcement: {}", ann)
+            ParticipatingEvent::PeerBehaviorAnnouncement(ann) => {
+                write!(f, "peer behavior announcement: {}", ann)
             }
             ParticipatingEvent::ChainSynchronizerAnnouncement(ann) => {
                 write!(f, "chain synchronizer announcement: {}", ann)
@@ -580,6 +581,7 @@ pub(crate) struct Reactor {
     contract_runtime: ContractRuntime,
     rpc_server: RpcServer,
     rest_server: RestServer,
+    graphql_server: GraphqlServer,
     event_stream_server: EventStreamServer,
     chainspec_loader: ChainspecLoader,
     consensus: EraSupervisor,
@@ -653,7 +655,7 @@ impl reactor::Reactor for Reactor {
             node_startup_instant,
         } = config;
 
-        let (our_secret_key, our_public_key) = config.consensus.load_keys(&root)?;
+        let signer = config.consensus.load_signer(&root, storage.root_path())?;
 
         let effect_builder = EffectBuilder::new(event_queue);
         let mut effects = Effects::new();
@@ -669,6 +671,8 @@ impl reactor::Reactor for Reactor {
                 if let Some(BlockAndExecutionEffects {
                     block,
                     execution_results,
+                    contract_messages,
+                    invoked_contracts,
                     maybe_step_effect_and_upcoming_era_validators,
                 }) = chainspec_loader
                     .maybe_immediate_switch_block_data()
@@ -680,7 +684,12 @@ impl reactor::Reactor for Reactor {
                     // don't get dropped as the joining reactor shuts down.
                     effects.extend(
                         effect_builder
-                            .announce_new_linear_chain_block(block.clone(), execution_results)
+                            .announce_new_linear_chain_block(
+                                block.clone(),
+                                execution_results,
+                                contract_messages,
+                                invoked_contracts,
+                            )
                             .ignore(),
                     );
 
@@ -706,8 +715,7 @@ impl reactor::Reactor for Reactor {
                         );
                     }
 
-                    let secret_key = our_secret_key.clone();
-                    let public_key = our_public_key.clone();
+                    let signer = Arc::clone(&signer);
                     let block_hash = *block.hash();
                     effects.extend(
                         async move {
@@ -731,13 +739,24 @@ impl reactor::Reactor for Reactor {
 
                             // We're responsible for signing the new block if we're in the provided
                             // list.
-                            if validator_weights.contains_key(&public_key) {
-                                let signature = FinalitySignature::new(
-                                    block_hash,
-                                    current_era_id,
-                                    &secret_key,
-                                    public_key.clone(),
-                                );
+                            if validator_weights.contains_key(signer.public_key()) {
+                                let block_height = block.height();
+                                let signature =
+                                    match consensus::spawn_signing(signer, move |signer| {
+                                        signer.sign_finality_signature(
+                                            block_hash,
+                                            block_height,
+                                            current_era_id,
+                                        )
+                                    })
+                                    .await
+                                    {
+                                        Ok(signature) => signature,
+                                        Err(error) => {
+                                            error!(%error, %block_hash, "failed to sign block");
+                                            return;
+                                        }
+                                    };
 
                                 effect_builder
                                     .announce_created_finality_signature(signature.clone())
@@ -784,6 +803,7 @@ impl reactor::Reactor for Reactor {
             effect_builder,
             protocol_version,
             node_startup_instant,
+            event_stream_server.handle(),
         )?;
         let rest_server = RestServer::new(
             config.rest_server.clone(),
@@ -791,6 +811,7 @@ impl reactor::Reactor for Reactor {
             protocol_version,
             node_startup_instant,
         )?;
+        let graphql_server = GraphqlServer::new(config.graphql_server.clone(), effect_builder)?;
 
         let fetcher_builder = FetcherBuilder::new(
             config.fetcher,
@@ -837,8 +858,7 @@ impl reactor::Reactor for Reactor {
         let (consensus, init_consensus_effects) = EraSupervisor::new(
             highest_block_header.next_block_era_id(),
             storage.root_path(),
-            our_secret_key,
-            our_public_key,
+            signer,
             config.consensus,
             effect_builder,
             chainspec.clone(),
@@ -909,6 +929,7 @@ impl reactor::Reactor for Reactor {
                 contract_runtime,
                 rpc_server,
                 rest_server,
+                graphql_server,
                 event_stream_server,
                 chainspec_loader,
                 consensus,
@@ -1284,6 +1305,8 @@ impl reactor::Reactor for Reactor {
                 ContractRuntimeAnnouncement::LinearChainBlock {
                     block,
                     execution_results,
+                    contract_messages,
+                    invoked_contracts,
                 },
             ) => {
                 let mut effects = Effects::new();
@@ -1301,6 +1324,7 @@ impl reactor::Reactor for Reactor {
                 effects.extend(self.dispatch_event(effect_builder, rng, reactor_event));
 
                 // send to event stream
+                let mut invoked_contracts: HashMap<_, _> = invoked_contracts.into_iter().collect();
                 for (deploy_hash, deploy_header, execution_result) in execution_results {
                     let reactor_event = ParticipatingEvent::EventStreamServer(
                         event_stream_server::Event::DeployProcessed {
@@ -1308,11 +1332,27 @@ impl reactor::Reactor for Reactor {
                             deploy_header: Box::new(deploy_header),
                             block_hash,
                             execution_result: Box::new(execution_result),
+                            invoked_contracts: invoked_contracts
+                                .remove(&deploy_hash)
+                                .unwrap_or_default(),
                         },
                     );
                     effects.extend(self.dispatch_event(effect_builder, rng, reactor_event));
                 }
 
+                for (deploy_hash, messages) in contract_messages {
+                    for message in messages {
+                        let reactor_event = ParticipatingEvent::EventStreamServer(
+                            event_stream_server::Event::ContractMessage {
+                                deploy_hash,
+                                block_hash,
+                                message: Box::new(message),
+                            },
+                        );
+                        effects.extend(self.dispatch_event(effect_builder, rng, reactor_event));
+                    }
+                }
+
                 effects
             }
             ParticipatingEvent::ContractRuntimeAnnouncement(
@@ -1418,7 +1458,7 @@ impl reactor::Reactor for Reactor {
                 effects.extend(self.dispatch_event(effect_builder, rng, reactor_event));
                 effects
             }
-            ParticipatingEvent::BlocklistAnnouncement(ann) => self.dispatch_event(
+            ParticipatingEvent::PeerBehaviorAnnouncement(ann) => self.dispatch_event(
                 effect_builder,
                 rng,
                 ParticipatingEvent::SmallNetwork(ann.into()),
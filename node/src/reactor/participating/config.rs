@@ -1,11 +1,12 @@
 use datasize::DataSize;
 use serde::Deserialize;
+use thiserror::Error;
 
 use crate::{
     logging::LoggingConfig, types::NodeConfig, BlockProposerConfig, ConsensusConfig,
     ContractRuntimeConfig, DiagnosticsPortConfig, EventStreamServerConfig, FetcherConfig,
-    GossipConfig, RestServerConfig, RpcServerConfig, SmallNetworkConfig, SpeculativeExecConfig,
-    StorageConfig,
+    GossipConfig, GraphqlServerConfig, RestServerConfig, RpcServerConfig, SmallNetworkConfig,
+    SpeculativeExecConfig, StorageConfig,
 };
 
 /// Root configuration.
@@ -29,6 +30,9 @@ pub(crate) struct Config {
     pub(crate) rpc_server: RpcServerConfig,
     /// Speculative execution server configuration.
     pub(crate) speculative_exec_server: SpeculativeExecConfig,
+    /// GraphQL API server configuration.
+    #[serde(default)]
+    pub(crate) graphql_server: GraphqlServerConfig,
     /// On-disk storage configuration.
     pub(crate) storage: StorageConfig,
     /// Gossip protocol configuration.
@@ -43,3 +47,24 @@ pub(crate) struct Config {
     /// Diagnostics port configuration.
     pub(crate) diagnostics_port: DiagnosticsPortConfig,
 }
+
+impl Config {
+    /// Checks that the settings of the different sections are compatible with each other.
+    pub(crate) fn validate(&self) -> Result<(), InvalidConfigError> {
+        if self.node.sync_to_genesis && self.contract_runtime.retained_eras().is_some() {
+            return Err(InvalidConfigError::PruningWithSyncToGenesis);
+        }
+        Ok(())
+    }
+}
+
+/// Error returned if the settings of different config sections are incompatible.
+#[derive(Debug, Error)]
+pub(crate) enum InvalidConfigError {
+    /// Historical global state pruning is enabled along with syncing to genesis.
+    #[error(
+        "`contract_runtime.retained_eras` cannot be set when `node.sync_to_genesis` is enabled, \
+         as the historical global state being synced would be pruned"
+    )]
+    PruningWithSyncToGenesis,
+}
@@ -2,13 +2,13 @@ use thiserror::Error;
 
 use crate::{
     components::{
-        chain_synchronizer, contract_runtime, contract_runtime::BlockExecutionError,
-        diagnostics_port, small_network, storage,
+        chain_synchronizer, consensus::LoadSignerError, contract_runtime,
+        contract_runtime::BlockExecutionError, diagnostics_port, small_network, storage,
     },
-    utils::{ListeningError, LoadError},
+    utils::ListeningError,
 };
 use casper_execution_engine::core::engine_state;
-use casper_types::{bytesrepr, crypto::ErrorExt as CryptoError};
+use casper_types::bytesrepr;
 
 /// Error type returned by the validator reactor.
 #[derive(Debug, Error)]
@@ -61,9 +61,9 @@ pub(crate) enum Error {
     #[error("diagnostics port: {0}")]
     DiagnosticsPort(#[from] diagnostics_port::Error),
 
-    /// Error while loading the signing key pair.
+    /// Error while loading the secret key or connecting to the remote signer.
     #[error("signing key pair load error: {0}")]
-    LoadSigningKeyPair(#[from] LoadError<CryptoError>),
+    LoadSigningKeyPair(#[from] LoadSignerError),
 }
 
 impl From<bytesrepr::Error> for Error {
@@ -1,5 +1,6 @@
 //! Common types used across multiple components.
 
+mod account_history;
 pub(crate) mod appendable_block;
 mod available_block_range;
 mod block;
@@ -20,6 +21,7 @@ use rand::{CryptoRng, RngCore};
 #[cfg(not(test))]
 use rand_chacha::ChaCha20Rng;
 
+pub use account_history::{AccountHistoryEntry, AccountHistoryRole};
 pub use available_block_range::AvailableBlockRange;
 pub use block::{
     json_compatibility::{JsonBlock, JsonBlockHeader},
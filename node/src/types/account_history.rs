@@ -0,0 +1,65 @@
+use datasize::DataSize;
+use once_cell::sync::Lazy;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::{Block, BlockHash, Deploy, DeployHash};
+use crate::rpcs::docs::DocExample;
+
+static ACCOUNT_HISTORY_ENTRY: Lazy<AccountHistoryEntry> = Lazy::new(|| {
+    let block = Block::doc_example();
+    AccountHistoryEntry {
+        deploy_hash: *Deploy::doc_example().id(),
+        block_hash: *block.hash(),
+        block_height: block.height(),
+        role: AccountHistoryRole::Sender,
+        success: true,
+    }
+});
+
+/// The way in which an account was involved in an executed deploy.
+#[derive(
+    Clone,
+    Copy,
+    DataSize,
+    Debug,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    JsonSchema,
+)]
+#[repr(u8)]
+pub enum AccountHistoryRole {
+    /// The account sent the deploy.
+    Sender = 0,
+    /// The account was the source of a transfer made by the deploy.
+    TransferSource = 1,
+    /// The account was the target of a transfer made by the deploy.
+    TransferTarget = 2,
+}
+
+/// An executed deploy in which an account was involved, as recorded in the account history index.
+#[derive(Clone, DataSize, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct AccountHistoryEntry {
+    /// The hash of the deploy.
+    pub deploy_hash: DeployHash,
+    /// The hash of the block in which the deploy was executed.
+    pub block_hash: BlockHash,
+    /// The height of the block in which the deploy was executed.
+    pub block_height: u64,
+    /// The way in which the account was involved in the deploy.
+    pub role: AccountHistoryRole,
+    /// Whether the deploy was executed successfully.
+    pub success: bool,
+}
+
+impl DocExample for AccountHistoryEntry {
+    fn doc_example() -> &'static Self {
+        &*ACCOUNT_HISTORY_ENTRY
+    }
+}
@@ -34,7 +34,6 @@ impl AvailableBlockRange {
     pub const RANGE_0_0: AvailableBlockRange = AvailableBlockRange { low: 0, high: 0 };
 
     /// Constructs a new `AvailableBlockRange` with the given limits.
-    #[cfg(test)]
     pub fn new(low: u64, high: u64) -> Self {
         assert!(
             low <= high,
@@ -43,6 +42,15 @@ impl AvailableBlockRange {
         AvailableBlockRange { low, high }
     }
 
+    /// Returns a copy of this range with its lower bound raised to `low`, unless that would exceed
+    /// the upper bound, in which case the range is reduced to the upper bound alone.
+    pub(crate) fn with_low_at_least(self, low: u64) -> Self {
+        AvailableBlockRange {
+            low: self.low.max(low).min(self.high),
+            high: self.high,
+        }
+    }
+
     /// Returns `true` if `height` is within the range.
     pub fn contains(&self, height: u64) -> bool {
         height >= self.low && height <= self.high
@@ -1953,6 +1953,23 @@ pub(crate) mod json_compatibility {
         transfer_hashes: Vec<DeployHash>,
     }
 
+    impl JsonBlockBody {
+        /// Block proposer.
+        pub fn proposer(&self) -> &PublicKey {
+            &self.proposer
+        }
+
+        /// Retrieves the deploy hashes within the block.
+        pub fn deploy_hashes(&self) -> &Vec<DeployHash> {
+            &self.deploy_hashes
+        }
+
+        /// Retrieves the transfer hashes within the block.
+        pub fn transfer_hashes(&self) -> &Vec<DeployHash> {
+            &self.transfer_hashes
+        }
+    }
+
     impl From<BlockBody> for JsonBlockBody {
         fn from(body: BlockBody) -> Self {
             JsonBlockBody {
@@ -2094,8 +2111,7 @@ impl FinalitySignature {
         secret_key: &SecretKey,
         public_key: PublicKey,
     ) -> Self {
-        let mut bytes = block_hash.inner().into_vec();
-        bytes.extend_from_slice(&era_id.to_le_bytes());
+        let bytes = Self::bytes_to_sign(&block_hash, era_id);
         let signature = crypto::sign(bytes, secret_key, &public_key);
         FinalitySignature {
             block_hash,
@@ -2107,12 +2123,17 @@ impl FinalitySignature {
 
     /// Verifies whether the signature is correct.
     pub fn verify(&self) -> Result<(), crypto::Error> {
-        // NOTE: This needs to be in sync with the `new` constructor.
-        let mut bytes = self.block_hash.inner().into_vec();
-        bytes.extend_from_slice(&self.era_id.to_le_bytes());
+        let bytes = Self::bytes_to_sign(&self.block_hash, self.era_id);
         crypto::verify(bytes, &self.signature, &self.public_key)
     }
 
+    /// Returns the bytes which are signed to create a finality signature for the given block.
+    pub(crate) fn bytes_to_sign(block_hash: &BlockHash, era_id: EraId) -> Vec<u8> {
+        let mut bytes = block_hash.inner().into_vec();
+        bytes.extend_from_slice(&era_id.to_le_bytes());
+        bytes
+    }
+
     /// Returns a random `FinalitySignature` for the provided `block_hash` and `era_id`.
     #[cfg(any(feature = "testing", test))]
     pub fn random_for_block(block_hash: BlockHash, era_id: u64) -> Self {
@@ -282,6 +282,15 @@ mod tests {
             print: HostFunction::new(123, [0, 1]),
             blake2b: HostFunction::new(133, [0, 1, 2, 3]),
             random_bytes: HostFunction::new(123, [0, 1]),
+            emit_message: HostFunction::new(142, [0, 1, 2, 3]),
+            verify_signature: HostFunction::new(143, [0, 1, 2, 3, 4, 5]),
+            get_block_height: HostFunction::new(144, [0]),
+            get_era_id: HostFunction::new(145, [0]),
+            get_protocol_version: HostFunction::new(146, [0]),
+            get_parent_block_hash: HostFunction::new(147, [0]),
+            get_state_root_hash: HostFunction::new(148, [0]),
+            remove_uref: HostFunction::new(149, [0, 1]),
+            remove_dictionary_item: HostFunction::new(150, [0, 1, 2, 3]),
         });
     static EXPECTED_GENESIS_WASM_COSTS: Lazy<WasmConfig> = Lazy::new(|| {
         WasmConfig::new(
@@ -36,8 +36,8 @@ use casper_types::{
     bytesrepr::{self, FromBytes, ToBytes},
     crypto, runtime_args,
     system::standard_payment::ARG_AMOUNT,
-    ExecutionResult, Motes, PublicKey, RuntimeArgs, SecretKey, Signature, TimeDiff, Timestamp,
-    U512,
+    ContractHash, ExecutionResult, Motes, PublicKey, RuntimeArgs, SecretKey, Signature, TimeDiff,
+    Timestamp, U512,
 };
 
 use super::{BlockHash, BlockHashAndHeight, Item, Tag};
@@ -866,6 +866,22 @@ impl Deploy {
         &self.session
     }
 
+    /// Returns the stored contracts invoked by hash from this deploy's payment or session code.
+    ///
+    /// Contracts invoked by name, via a contract package, or indirectly from other contracts are
+    /// not included.
+    pub fn invoked_contracts(&self) -> Vec<ContractHash> {
+        let mut invoked_contracts = vec![];
+        for item in [&self.payment, &self.session] {
+            if let ExecutableDeployItem::StoredContractByHash { hash, .. } = item {
+                if !invoked_contracts.contains(hash) {
+                    invoked_contracts.push(*hash);
+                }
+            }
+        }
+        invoked_contracts
+    }
+
     /// Returns the `Approval`s for this deploy.
     pub fn approvals(&self) -> &BTreeSet<Approval> {
         &self.approvals
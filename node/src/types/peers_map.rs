@@ -6,7 +6,7 @@ use std::collections::BTreeMap;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use crate::types::NodeId;
+use crate::{components::small_network::PeerScore, types::NodeId};
 
 /// Node peer entry.
 #[derive(Clone, PartialEq, Eq, Serialize, Deserialize, Debug, JsonSchema)]
@@ -16,6 +16,9 @@ pub struct PeerEntry {
     pub node_id: String,
     /// Node address.
     pub address: String,
+    /// Score of the node, based on its past behavior. Omitted if not known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub score: Option<i64>,
 }
 
 /// Map of peer IDs to network addresses.
@@ -28,6 +31,23 @@ impl PeersMap {
     pub fn into_inner(self) -> Vec<PeerEntry> {
         self.0
     }
+
+    /// Creates a `PeersMap` from peer addresses, including each peer's score, if known.
+    pub(crate) fn with_scores(peers: BTreeMap<NodeId, String>, scores: &[PeerScore]) -> Self {
+        let scores: BTreeMap<NodeId, i64> = scores
+            .iter()
+            .map(|peer_score| (peer_score.node_id, peer_score.score.round() as i64))
+            .collect();
+        let ret = peers
+            .into_iter()
+            .map(|(node_id, address)| PeerEntry {
+                node_id: node_id.to_string(),
+                address,
+                score: scores.get(&node_id).copied(),
+            })
+            .collect();
+        PeersMap(ret)
+    }
 }
 
 impl From<BTreeMap<NodeId, String>> for PeersMap {
@@ -37,6 +57,7 @@ impl From<BTreeMap<NodeId, String>> for PeersMap {
             .map(|(node_id, address)| PeerEntry {
                 node_id: node_id.to_string(),
                 address,
+                score: None,
             })
             .collect();
         PeersMap(ret)
@@ -21,7 +21,7 @@ use crate::{
         chainspec_loader::NextUpgrade,
         rpc_server::rpcs::docs::{DocExample, DOCS_EXAMPLE_PROTOCOL_VERSION},
     },
-    types::{ActivationPoint, Block, BlockHash, NodeId, PeersMap},
+    types::{ActivationPoint, AvailableBlockRange, Block, BlockHash, NodeId, PeersMap},
 };
 
 static CHAINSPEC_INFO: Lazy<ChainspecInfo> = Lazy::new(|| {
@@ -49,6 +49,7 @@ static GET_STATUS_RESULT: Lazy<GetStatusResult> = Lazy::new(|| {
         version: crate::VERSION_STRING.as_str(),
         node_uptime: Duration::from_secs(13),
         node_state: NodeState::Participating,
+        available_block_range: AvailableBlockRange::new(0, 10),
     };
     GetStatusResult::new(status_feed, DOCS_EXAMPLE_PROTOCOL_VERSION)
 });
@@ -110,6 +111,8 @@ pub struct StatusFeed {
     pub node_uptime: Duration,
     /// The current state of node.
     pub node_state: NodeState,
+    /// The height range of fully available blocks.
+    pub available_block_range: AvailableBlockRange,
 }
 
 impl StatusFeed {
@@ -120,6 +123,7 @@ impl StatusFeed {
         consensus_status: Option<(PublicKey, Option<TimeDiff>)>,
         node_uptime: Duration,
         node_state: NodeState,
+        available_block_range: AvailableBlockRange,
     ) -> Self {
         let (our_public_signing_key, round_length) = match consensus_status {
             Some((public_key, round_length)) => (Some(public_key), round_length),
@@ -134,6 +138,7 @@ impl StatusFeed {
             version: crate::VERSION_STRING.as_str(),
             node_uptime,
             node_state,
+            available_block_range,
         }
     }
 }
@@ -191,6 +196,9 @@ pub struct GetStatusResult {
     pub uptime: TimeDiff,
     /// The current state of node.
     pub node_state: NodeState,
+    /// The height range of blocks for which this node holds the full data, including global state.
+    /// Global state can only be queried at blocks within this range.
+    pub available_block_range: AvailableBlockRange,
 }
 
 impl GetStatusResult {
@@ -207,6 +215,7 @@ impl GetStatusResult {
             next_upgrade: status_feed.chainspec_info.next_upgrade,
             uptime: status_feed.node_uptime.into(),
             node_state: status_feed.node_state,
+            available_block_range: status_feed.available_block_range,
             #[cfg(not(test))]
             build_version: crate::VERSION_STRING.clone(),
 
@@ -0,0 +1,768 @@
+//! Per-client and per-method rate limiting for the node's HTTP servers.
+//!
+//! Each HTTP server applies a global `qps_limit` to all requests it receives.  The JSON-RPC and
+//! GraphQL servers enforce it using a [`QpsLimiter`], which takes up one slot for every request,
+//! including each request in a JSON-RPC batch.  On top of that, a [`RateLimiter`] can be
+//! configured to
+//!   * weight each method (a JSON-RPC method name, or a REST endpoint path) by a cost,
+//!   * limit the rate at which each method is called, summed over all clients,
+//!   * limit the total cost incurred by each client, where a client is identified by its API key if
+//!     it provides one, or else by its IPv4 address or the /64 prefix of its IPv6 address, and
+//!   * exempt an allowlist of IP addresses from all of the above.
+//!
+//! All limits are enforced using token buckets which hold up to one second's worth of tokens.
+//! Requests exceeding a limit are rejected with an HTTP 429 (too many requests) response, while
+//! requests providing an unknown API key are rejected with an HTTP 401 (unauthorized) response.
+//! The body of the response is a JSON-RPC error object for JSON-RPC requests.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::{self, Display, Formatter},
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use bytes::Bytes;
+use datasize::DataSize;
+use http::{HeaderMap, StatusCode};
+use linked_hash_map::LinkedHashMap;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tracing::debug;
+use warp::{
+    filters::BoxedFilter,
+    reject::{self, Reject, Rejection},
+    reply::{self, WithStatus},
+    Filter,
+};
+
+use casper_json_rpc::{Error, Response};
+
+use crate::components::rpc_server::rpcs::ErrorCode;
+
+/// Default maximum cost per second for a client identified by its IP address.
+const DEFAULT_CLIENT_COST_LIMIT: u64 = 50;
+/// Default maximum cost per second for a client identified by its API key.
+const DEFAULT_API_KEY_COST_LIMIT: u64 = 200;
+/// Default name of the HTTP header carrying a client's API key.
+const DEFAULT_API_KEY_HEADER: &str = "x-api-key";
+/// The cost of a method which has no entry in `method_costs`.
+const DEFAULT_METHOD_COST: u64 = 1;
+/// The maximum number of tracked clients, above which the least recently seen one is forgotten.
+const MAX_TRACKED_CLIENTS: usize = 10_000;
+/// The longest a request is delayed by a [`QpsLimiter`] before being rejected instead.
+const MAX_QPS_DELAY: Duration = Duration::from_secs(1);
+
+/// Per-client and per-method rate limiting configuration for an HTTP server.
+#[derive(Clone, DataSize, Debug, Deserialize, Serialize)]
+// Disallow unknown fields to ensure config files and command-line overrides contain valid keys.
+#[serde(deny_unknown_fields)]
+pub struct RateLimitConfig {
+    /// Whether per-client and per-method rate limiting is enabled.
+    pub enabled: bool,
+    /// Maximum cost per second incurred by a single client identified by its IP address.
+    pub client_cost_limit: u64,
+    /// Maximum cost per second incurred by a single client identified by its API key.
+    pub api_key_cost_limit: u64,
+    /// Name of the HTTP header from which a client's API key is read.
+    pub api_key_header: String,
+    /// The accepted API keys.  Requests providing any other API key are rejected.
+    pub api_keys: Vec<String>,
+    /// IP addresses of clients exempt from all per-client and per-method limits.
+    #[data_size(skip)]
+    pub allowlist: Vec<IpAddr>,
+    /// Cost of each method, by name.  Methods with no entry cost 1.
+    pub method_costs: BTreeMap<String, u64>,
+    /// Maximum number of calls per second of each method, summed over all clients, by name.
+    /// Methods with no entry are not limited.
+    pub method_limits: BTreeMap<String, u64>,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        RateLimitConfig {
+            enabled: false,
+            client_cost_limit: DEFAULT_CLIENT_COST_LIMIT,
+            api_key_cost_limit: DEFAULT_API_KEY_COST_LIMIT,
+            api_key_header: DEFAULT_API_KEY_HEADER.to_string(),
+            api_keys: vec![],
+            allowlist: vec![],
+            method_costs: BTreeMap::new(),
+            method_limits: BTreeMap::new(),
+        }
+    }
+}
+
+/// Identifies the client of a request.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+enum ClientId {
+    /// A client providing a valid API key.
+    ApiKey(String),
+    /// A client not providing an API key, identified by its IPv4 address or the /64 prefix of its
+    /// IPv6 address.
+    Ip(IpAddr),
+}
+
+impl ClientId {
+    /// Returns the ID of a client not providing an API key.
+    ///
+    /// A /64 is the smallest IPv6 block routinely assigned to a single host or site, so all of its
+    /// addresses are treated as one client, which can't evade its limit by rotating through them.
+    fn from_ip(client_ip: IpAddr) -> Self {
+        match client_ip {
+            IpAddr::V4(_) => ClientId::Ip(client_ip),
+            IpAddr::V6(ipv6) => match ipv6.to_ipv4_mapped() {
+                Some(ipv4) => ClientId::Ip(IpAddr::V4(ipv4)),
+                None => {
+                    let prefix = u128::from(ipv6) & !u128::from(u64::MAX);
+                    ClientId::Ip(IpAddr::V6(Ipv6Addr::from(prefix)))
+                }
+            },
+        }
+    }
+}
+
+/// A token bucket holding up to one second's worth of tokens.
+#[derive(Debug)]
+struct TokenBucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(limit: u64, now: Instant) -> Self {
+        TokenBucket {
+            tokens: limit as f64,
+            last_refill: now,
+        }
+    }
+
+    /// Adds the tokens accrued since the last refill at a rate of `limit` per second.
+    fn refill(&mut self, limit: u64, now: Instant) {
+        let elapsed = now
+            .saturating_duration_since(self.last_refill)
+            .as_secs_f64();
+        self.tokens = (self.tokens + elapsed * limit as f64).min(limit as f64);
+        self.last_refill = now;
+    }
+
+    /// Returns `true` if `cost` tokens are available.
+    ///
+    /// A cost higher than the bucket's capacity is affordable once the bucket is full, so that such
+    /// requests are throttled rather than rejected outright.
+    fn can_afford(&self, cost: u64, limit: u64) -> bool {
+        self.tokens >= cost.min(limit) as f64
+    }
+}
+
+#[derive(Debug, Default)]
+struct State {
+    /// The buckets of the tracked clients, least recently seen first.
+    clients: LinkedHashMap<ClientId, TokenBucket>,
+    methods: HashMap<String, TokenBucket>,
+}
+
+/// Enforces the per-client and per-method limits of a single HTTP server.
+#[derive(Debug)]
+pub(crate) struct RateLimiter {
+    config: RateLimitConfig,
+    state: Mutex<State>,
+}
+
+impl RateLimiter {
+    /// Creates a new rate limiter.
+    pub(crate) fn new(config: RateLimitConfig) -> Arc<Self> {
+        Arc::new(RateLimiter {
+            config,
+            state: Mutex::new(State::default()),
+        })
+    }
+
+    /// Returns the cost of calling `method`.
+    fn method_cost(&self, method: &str) -> u64 {
+        self.config
+            .method_costs
+            .get(method)
+            .copied()
+            .unwrap_or(DEFAULT_METHOD_COST)
+    }
+
+    /// Checks whether a request from `client_ip`, optionally providing `api_key`, calling each of
+    /// `methods` is allowed and, if so, charges for it.
+    fn check(
+        &self,
+        client_ip: IpAddr,
+        api_key: Option<&str>,
+        methods: &[String],
+        now: Instant,
+    ) -> Result<(), RateLimitRejection> {
+        let (client_id, client_limit) = match api_key {
+            Some(api_key) if self.config.api_keys.iter().any(|key| key == api_key) => (
+                ClientId::ApiKey(api_key.to_string()),
+                self.config.api_key_cost_limit,
+            ),
+            Some(_) => return Err(RateLimitRejection::InvalidApiKey),
+            None => (ClientId::from_ip(client_ip), self.config.client_cost_limit),
+        };
+
+        if self.config.allowlist.contains(&client_ip) {
+            return Ok(());
+        }
+
+        let mut method_counts: BTreeMap<&str, u64> = BTreeMap::new();
+        for method in methods {
+            *method_counts.entry(method.as_str()).or_default() += 1;
+        }
+        let total_cost = methods
+            .iter()
+            .map(|method| self.method_cost(method))
+            .fold(0_u64, u64::saturating_add);
+
+        let mut state = self.state.lock().expect("rate limiter lock poisoned");
+        let State {
+            clients,
+            methods: method_buckets,
+        } = &mut *state;
+
+        for (method, count) in &method_counts {
+            if let Some(limit) = self.config.method_limits.get(*method) {
+                let bucket = method_buckets
+                    .entry(method.to_string())
+                    .or_insert_with(|| TokenBucket::new(*limit, now));
+                bucket.refill(*limit, now);
+                if !bucket.can_afford(*count, *limit) {
+                    return Err(RateLimitRejection::MethodLimited(method.to_string()));
+                }
+            }
+        }
+
+        // Forget the least recently seen client to make room for a new one.  Its bucket will
+        // usually have refilled by the time it returns anyway.
+        if clients.get_refresh(&client_id).is_none() && clients.len() >= MAX_TRACKED_CLIENTS {
+            let _ = clients.pop_front();
+        }
+        let client_bucket = clients
+            .entry(client_id)
+            .or_insert_with(|| TokenBucket::new(client_limit, now));
+        client_bucket.refill(client_limit, now);
+        if !client_bucket.can_afford(total_cost, client_limit) {
+            return Err(RateLimitRejection::ClientLimited);
+        }
+        client_bucket.tokens -= total_cost as f64;
+
+        for (method, count) in &method_counts {
+            if let Some(bucket) = method_buckets.get_mut(*method) {
+                bucket.tokens -= *count as f64;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks whether the JSON-RPC request, or batch of requests, in `body` sent by `client_ip`,
+    /// optionally providing `api_key`, is allowed and, if so, charges for it.
+    ///
+    /// Every request in a batch is charged for, unless the batch has more than `max_batch_size`
+    /// entries, in which case it is charged as a single request.
+    pub(crate) fn check_json_rpc(
+        &self,
+        client_ip: IpAddr,
+        api_key: Option<&str>,
+        body: &[u8],
+        max_batch_size: u32,
+    ) -> Result<(), RateLimitRejection> {
+        if !self.config.enabled {
+            return Ok(());
+        }
+        let methods = json_rpc_methods(body, max_batch_size);
+        self.check(client_ip, api_key, &methods, Instant::now())
+    }
+
+    /// Returns a filter extracting the client's IP address and, if provided, its API key.
+    pub(crate) fn client_filter(self: &Arc<Self>) -> BoxedFilter<((IpAddr, Option<String>),)> {
+        let api_key_header = self.config.api_key_header.clone();
+        warp::addr::remote()
+            .and(warp::header::headers_cloned())
+            .map(
+                move |maybe_address: Option<SocketAddr>, headers: HeaderMap| {
+                    let client_ip = maybe_address
+                        .map(|address| address.ip())
+                        .unwrap_or(IpAddr::V4(Ipv4Addr::UNSPECIFIED));
+                    let api_key = headers
+                        .get(api_key_header.as_str())
+                        .and_then(|value| value.to_str().ok())
+                        .map(str::to_string);
+                    (client_ip, api_key)
+                },
+            )
+            .boxed()
+    }
+
+    /// Returns a filter extracting the body of a JSON-RPC request, rejecting the request if it
+    /// exceeds any applicable limit.
+    ///
+    /// Every request in a batch is charged for, unless the batch has more than `max_batch_size`
+    /// entries, in which case it is charged as a single request.
+    pub(crate) fn json_rpc_body_filter(
+        self: &Arc<Self>,
+        max_batch_size: u32,
+    ) -> BoxedFilter<(Bytes,)> {
+        if !self.config.enabled {
+            return warp::body::bytes().boxed();
+        }
+        let rate_limiter = Arc::clone(self);
+        self.client_filter()
+            .and(warp::body::bytes())
+            .and_then(
+                move |(client_ip, api_key): (IpAddr, Option<String>), body: Bytes| {
+                    let rate_limiter = Arc::clone(&rate_limiter);
+                    async move {
+                        rate_limiter
+                            .check_json_rpc(client_ip, api_key.as_deref(), &body, max_batch_size)
+                            .map(|()| body)
+                            .map_err(reject::custom)
+                    }
+                },
+            )
+            .boxed()
+    }
+
+    /// Returns a filter rejecting a REST request if it exceeds any applicable limit.
+    ///
+    /// The method of a REST request is its path without the leading `/`, e.g. `status`.
+    pub(crate) fn rest_filter(self: &Arc<Self>) -> BoxedFilter<()> {
+        if !self.config.enabled {
+            return warp::any().boxed();
+        }
+        let rate_limiter = Arc::clone(self);
+        self.client_filter()
+            .and(warp::path::full())
+            .and_then(
+                move |(client_ip, api_key): (IpAddr, Option<String>),
+                      path: warp::path::FullPath| {
+                    let rate_limiter = Arc::clone(&rate_limiter);
+                    async move {
+                        let methods = [path.as_str().trim_start_matches('/').to_string()];
+                        rate_limiter
+                            .check(client_ip, api_key.as_deref(), &methods, Instant::now())
+                            .map_err(reject::custom)
+                    }
+                },
+            )
+            .untuple_one()
+            .boxed()
+    }
+}
+
+/// Paces the requests handled by a server to at most `qps_limit` per second, summed over all
+/// clients.
+///
+/// Requests exceeding the limit are delayed until a slot is available, unless their slots would
+/// not all be available within [`MAX_QPS_DELAY`], in which case they are rejected.  Hence a batch
+/// of more than `qps_limit` requests is always rejected.
+#[derive(Debug)]
+pub(crate) struct QpsLimiter {
+    qps_limit: u64,
+    /// The time from which the next request may proceed.
+    next_slot: Mutex<Instant>,
+}
+
+impl QpsLimiter {
+    /// Creates a new QPS limiter.  A `qps_limit` of 0 is treated as 1.
+    pub(crate) fn new(qps_limit: u64) -> Arc<Self> {
+        Arc::new(QpsLimiter {
+            qps_limit: qps_limit.max(1),
+            next_slot: Mutex::new(Instant::now()),
+        })
+    }
+
+    /// Reserves one slot for each of `count` requests, returning the time from which they may
+    /// proceed, or `None` if the last of the slots would end later than `MAX_QPS_DELAY` after
+    /// `now`.
+    fn reserve(&self, count: u64, now: Instant) -> Option<Instant> {
+        let mut next_slot = self.next_slot.lock().expect("QPS limiter lock poisoned");
+        let start = (*next_slot).max(now);
+        let end = start + Duration::from_secs_f64(count as f64 / self.qps_limit as f64);
+        if end > now + MAX_QPS_DELAY {
+            return None;
+        }
+        *next_slot = end;
+        Some(start)
+    }
+
+    /// Waits until `count` requests may proceed, or rejects them if that would take too long.
+    async fn wait(&self, count: u64) -> Result<(), RateLimitRejection> {
+        let start = self
+            .reserve(count, Instant::now())
+            .ok_or(RateLimitRejection::ServerBusy)?;
+        tokio::time::sleep_until(start.into()).await;
+        Ok(())
+    }
+
+    /// Waits until the JSON-RPC request, or every request in the batch, in `body` may proceed, or
+    /// rejects it if that would take too long.
+    ///
+    /// A batch of more than `max_batch_size` entries takes up a single slot.
+    pub(crate) async fn wait_for_json_rpc(
+        &self,
+        body: &[u8],
+        max_batch_size: u32,
+    ) -> Result<(), RateLimitRejection> {
+        self.wait(json_rpc_methods(body, max_batch_size).len() as u64)
+            .await
+    }
+
+    /// Returns a filter passing on a request once a slot is available for it.
+    pub(crate) fn filter(self: &Arc<Self>) -> BoxedFilter<()> {
+        let qps_limiter = Arc::clone(self);
+        warp::any()
+            .and_then(move || {
+                let qps_limiter = Arc::clone(&qps_limiter);
+                async move { qps_limiter.wait(1).await.map_err(reject::custom) }
+            })
+            .untuple_one()
+            .boxed()
+    }
+
+    /// Returns a filter passing on the body of a JSON-RPC request extracted by `body_source` once
+    /// a slot is available for every request in it, treating a batch of more than `max_batch_size`
+    /// entries as a single request.
+    pub(crate) fn json_rpc_body_filter(
+        self: &Arc<Self>,
+        body_source: BoxedFilter<(Bytes,)>,
+        max_batch_size: u32,
+    ) -> BoxedFilter<(Bytes,)> {
+        let qps_limiter = Arc::clone(self);
+        body_source
+            .and_then(move |body: Bytes| {
+                let qps_limiter = Arc::clone(&qps_limiter);
+                async move {
+                    qps_limiter
+                        .wait_for_json_rpc(&body, max_batch_size)
+                        .await
+                        .map(|()| body)
+                        .map_err(reject::custom)
+                }
+            })
+            .boxed()
+    }
+}
+
+/// Returns the method names of the JSON-RPC request, or of each request in the batch, in `body`.
+///
+/// Entries without a method name, or a body which cannot be parsed, yield an empty name, which is
+/// charged the default cost.  So does a batch of more than `max_batch_size` entries, as the server
+/// rejects it as a whole without handling any of its requests.
+fn json_rpc_methods(body: &[u8], max_batch_size: u32) -> Vec<String> {
+    fn method(request: &Value) -> String {
+        request
+            .get("method")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string()
+    }
+
+    match serde_json::from_slice::<Value>(body) {
+        Ok(Value::Array(batch)) if batch.len() > max_batch_size as usize => vec![String::new()],
+        Ok(Value::Array(batch)) if !batch.is_empty() => batch.iter().map(method).collect(),
+        Ok(request) => vec![method(&request)],
+        Err(_) => vec![String::new()],
+    }
+}
+
+/// Indicates a request was rejected by a [`RateLimiter`].
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum RateLimitRejection {
+    /// The request provided an API key which is not accepted.
+    InvalidApiKey,
+    /// The client has exceeded its cost limit.
+    ClientLimited,
+    /// The named method has exceeded its call limit.
+    MethodLimited(String),
+    /// The server has exceeded its `qps_limit`.
+    ServerBusy,
+}
+
+impl Display for RateLimitRejection {
+    fn fmt(&self, formatter: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            RateLimitRejection::InvalidApiKey => formatter.write_str("The API key is not valid"),
+            RateLimitRejection::ClientLimited => {
+                formatter.write_str("Too many requests from this client")
+            }
+            RateLimitRejection::MethodLimited(method) => {
+                write!(formatter, "Too many requests for '{}'", method)
+            }
+            RateLimitRejection::ServerBusy => {
+                formatter.write_str("Too many requests to this server")
+            }
+        }
+    }
+}
+
+impl RateLimitRejection {
+    /// Returns the HTTP status code of the response to a request rejected this way.
+    fn status_code(&self) -> StatusCode {
+        match self {
+            RateLimitRejection::InvalidApiKey => StatusCode::UNAUTHORIZED,
+            RateLimitRejection::ClientLimited
+            | RateLimitRejection::MethodLimited(_)
+            | RateLimitRejection::ServerBusy => StatusCode::TOO_MANY_REQUESTS,
+        }
+    }
+
+    /// Returns the JSON-RPC response to a request rejected this way.
+    pub(crate) fn json_rpc_response(&self) -> Response {
+        Response::new_failure(
+            Value::Null,
+            Error::new(ErrorCode::RateLimited, self.to_string()),
+        )
+    }
+}
+
+impl Reject for RateLimitRejection {}
+
+/// Handler for rejections by a [`RateLimiter`] or [`QpsLimiter`], passing on all other rejections.
+///
+/// The HTTP response body will be a JSON object of the form:
+/// ```json
+/// { "message": <String> }
+/// ```
+pub(crate) async fn handle_rejection(
+    error: Rejection,
+) -> Result<WithStatus<reply::Json>, Rejection> {
+    let rejection = match error.find::<RateLimitRejection>() {
+        Some(rejection) => rejection,
+        None => return Err(error),
+    };
+    debug!(%rejection, "rate limited request");
+    Ok(reply::with_status(
+        reply::json(&json!({ "message": rejection.to_string() })),
+        rejection.status_code(),
+    ))
+}
+
+/// Handler for rejections of JSON-RPC requests by a [`RateLimiter`] or [`QpsLimiter`], passing on
+/// all other rejections.
+///
+/// The HTTP response body will be a JSON-RPC response carrying an error with code
+/// `ErrorCode::RateLimited` and a `null` ID, as the request is rejected before it is parsed.
+pub(crate) async fn handle_json_rpc_rejection(
+    error: Rejection,
+) -> Result<WithStatus<reply::Json>, Rejection> {
+    let rejection = match error.find::<RateLimitRejection>() {
+        Some(rejection) => rejection,
+        None => return Err(error),
+    };
+    debug!(%rejection, "rate limited JSON-RPC request");
+    Ok(reply::with_status(
+        reply::json(&rejection.json_rpc_response()),
+        rejection.status_code(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    const IP: IpAddr = IpAddr::V4(Ipv4Addr::LOCALHOST);
+    const OTHER_IP: IpAddr = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+
+    fn config() -> RateLimitConfig {
+        RateLimitConfig {
+            enabled: true,
+            client_cost_limit: 10,
+            api_key_cost_limit: 20,
+            api_keys: vec!["key".to_string()],
+            allowlist: vec![OTHER_IP],
+            method_costs: vec![("expensive".to_string(), 5)].into_iter().collect(),
+            method_limits: vec![("limited".to_string(), 2)].into_iter().collect(),
+            ..Default::default()
+        }
+    }
+
+    fn methods(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn should_charge_clients_by_method_cost() {
+        let rate_limiter = RateLimiter::new(config());
+        let now = Instant::now();
+        let expensive = methods(&["expensive"]);
+
+        assert!(rate_limiter.check(IP, None, &expensive, now).is_ok());
+        assert!(rate_limiter.check(IP, None, &expensive, now).is_ok());
+        assert_eq!(
+            rate_limiter.check(IP, None, &expensive, now),
+            Err(RateLimitRejection::ClientLimited)
+        );
+
+        // Tokens are refilled over time.
+        let later = now + Duration::from_millis(500);
+        assert!(rate_limiter.check(IP, None, &expensive, later).is_ok());
+    }
+
+    #[test]
+    fn should_charge_every_request_in_batch() {
+        let rate_limiter = RateLimiter::new(config());
+        let now = Instant::now();
+
+        let batch = methods(&["cheap"; 10]);
+        assert!(rate_limiter.check(IP, None, &batch, now).is_ok());
+        let batch = methods(&["cheap"]);
+        assert_eq!(
+            rate_limiter.check(IP, None, &batch, now),
+            Err(RateLimitRejection::ClientLimited)
+        );
+    }
+
+    #[test]
+    fn should_limit_methods_across_clients() {
+        let rate_limiter = RateLimiter::new(config());
+        let now = Instant::now();
+        let limited = methods(&["limited"]);
+
+        assert!(rate_limiter.check(IP, None, &limited, now).is_ok());
+        assert!(rate_limiter.check(IP, Some("key"), &limited, now).is_ok());
+        assert_eq!(
+            rate_limiter.check(IP, None, &limited, now),
+            Err(RateLimitRejection::MethodLimited("limited".to_string()))
+        );
+        // A rejected request is not charged.
+        assert!(rate_limiter
+            .check(IP, None, &methods(&["cheap"; 9]), now)
+            .is_ok());
+    }
+
+    #[test]
+    fn should_bucket_api_key_clients_separately() {
+        let rate_limiter = RateLimiter::new(config());
+        let now = Instant::now();
+
+        assert!(rate_limiter
+            .check(IP, None, &methods(&["cheap"; 10]), now)
+            .is_ok());
+        assert!(rate_limiter
+            .check(IP, Some("key"), &methods(&["cheap"; 20]), now)
+            .is_ok());
+        assert_eq!(
+            rate_limiter.check(IP, Some("other key"), &methods(&["cheap"]), now),
+            Err(RateLimitRejection::InvalidApiKey)
+        );
+    }
+
+    #[test]
+    fn should_bucket_ipv6_clients_by_64_bit_prefix() {
+        let rate_limiter = RateLimiter::new(config());
+        let now = Instant::now();
+        let ip =
+            |last_segment| IpAddr::V6(Ipv6Addr::new(0x2001, 0xdb8, 0, 1, 0, 0, 0, last_segment));
+
+        assert!(rate_limiter
+            .check(ip(1), None, &methods(&["cheap"; 10]), now)
+            .is_ok());
+        assert_eq!(
+            rate_limiter.check(ip(2), None, &methods(&["cheap"]), now),
+            Err(RateLimitRejection::ClientLimited)
+        );
+        let other_prefix = IpAddr::V6(Ipv6Addr::new(0x2001, 0xdb8, 0, 2, 0, 0, 0, 1));
+        assert!(rate_limiter
+            .check(other_prefix, None, &methods(&["cheap"]), now)
+            .is_ok());
+
+        // IPv4-mapped IPv6 addresses are bucketed with the IPv4 address.
+        assert!(rate_limiter
+            .check(IP, None, &methods(&["cheap"; 10]), now)
+            .is_ok());
+        let mapped = IpAddr::V6(Ipv4Addr::LOCALHOST.to_ipv6_mapped());
+        assert_eq!(
+            rate_limiter.check(mapped, None, &methods(&["cheap"]), now),
+            Err(RateLimitRejection::ClientLimited)
+        );
+    }
+
+    #[test]
+    fn should_forget_least_recently_seen_client() {
+        let rate_limiter = RateLimiter::new(config());
+        let now = Instant::now();
+        let cheap = methods(&["cheap"]);
+        let ip = |index: usize| IpAddr::V4(Ipv4Addr::from(0x0b00_0000 + index as u32));
+
+        assert!(rate_limiter
+            .check(IP, None, &methods(&["cheap"; 10]), now)
+            .is_ok());
+        // Track one client more than the maximum, keeping `ip(0)` recently seen throughout, whether
+        // or not its requests are allowed.
+        for index in 0..MAX_TRACKED_CLIENTS {
+            let _ = rate_limiter.check(ip(0), None, &cheap, now);
+            assert!(rate_limiter.check(ip(index + 1), None, &cheap, now).is_ok());
+        }
+        assert_eq!(
+            rate_limiter.state.lock().unwrap().clients.len(),
+            MAX_TRACKED_CLIENTS
+        );
+
+        // `IP` was the least recently seen client, so it was forgotten along with its exhausted
+        // bucket, while `ip(0)` was kept.
+        assert!(rate_limiter.check(IP, None, &cheap, now).is_ok());
+        assert_eq!(
+            rate_limiter.check(ip(0), None, &cheap, now),
+            Err(RateLimitRejection::ClientLimited)
+        );
+    }
+
+    #[test]
+    fn should_exempt_allowlisted_clients() {
+        let rate_limiter = RateLimiter::new(config());
+        let now = Instant::now();
+
+        for _ in 0..10 {
+            assert!(rate_limiter
+                .check(OTHER_IP, None, &methods(&["expensive", "limited"]), now)
+                .is_ok());
+        }
+    }
+
+    #[test]
+    fn should_pace_every_request_in_batch() {
+        let qps_limiter = QpsLimiter::new(10);
+        let now = Instant::now();
+
+        // A batch of 10 requests takes up a second's worth of slots.
+        assert_eq!(qps_limiter.reserve(10, now), Some(now));
+        // Requests whose slots would end more than `MAX_QPS_DELAY` from now are rejected.
+        assert_eq!(qps_limiter.reserve(1, now), None);
+        assert_eq!(
+            qps_limiter.reserve(1, now + Duration::from_millis(500)),
+            Some(now + Duration::from_secs(1))
+        );
+        // A batch exceeding a second's worth of slots is rejected even when the limiter is idle.
+        let later = now + Duration::from_secs(10);
+        assert_eq!(qps_limiter.reserve(11, later), None);
+        assert_eq!(qps_limiter.reserve(10, later), Some(later));
+    }
+
+    #[test]
+    fn should_parse_json_rpc_methods() {
+        assert_eq!(
+            json_rpc_methods(br#"{"jsonrpc":"2.0","id":1,"method":"a"}"#, 3),
+            methods(&["a"])
+        );
+        assert_eq!(
+            json_rpc_methods(br#"[{"method":"a"},{"method":"b"},{}]"#, 3),
+            methods(&["a", "b", ""])
+        );
+        assert_eq!(json_rpc_methods(b"not json", 3), methods(&[""]));
+    }
+
+    #[test]
+    fn should_charge_oversized_batch_as_single_request() {
+        let batch = br#"[{"method":"a"},{"method":"b"},{"method":"c"}]"#;
+        assert_eq!(json_rpc_methods(batch, 2), methods(&[""]));
+        assert_eq!(json_rpc_methods(batch, 0), methods(&[""]));
+    }
+}
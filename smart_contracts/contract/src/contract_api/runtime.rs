@@ -6,11 +6,12 @@ use core::mem::MaybeUninit;
 use casper_types::{
     account::AccountHash,
     api_error,
-    bytesrepr::{self, FromBytes},
+    bytesrepr::{self, FromBytes, ToBytes, U64_SERIALIZED_LENGTH},
     contracts::{ContractVersion, NamedKeys},
     system::CallStackElement,
-    ApiError, BlockTime, CLTyped, CLValue, ContractHash, ContractPackageHash, Key, Phase,
-    RuntimeArgs, URef, BLAKE2B_DIGEST_LENGTH, BLOCKTIME_SERIALIZED_LENGTH, PHASE_SERIALIZED_LENGTH,
+    ApiError, BlockTime, CLTyped, CLValue, ContractHash, ContractPackageHash, EraId, Key, Phase,
+    ProtocolVersion, PublicKey, RuntimeArgs, Signature, URef, BLAKE2B_DIGEST_LENGTH,
+    BLOCKTIME_SERIALIZED_LENGTH, PHASE_SERIALIZED_LENGTH, SEM_VER_SERIALIZED_LENGTH,
 };
 
 use crate::{contract_api, ext_ffi, unwrap_or_revert::UnwrapOrRevert};
@@ -221,6 +222,56 @@ pub fn get_phase() -> Phase {
     bytesrepr::deserialize(bytes).unwrap_or_revert()
 }
 
+/// Returns the height of the block in which the deploy is executed.
+pub fn get_block_height() -> u64 {
+    read_fixed_size_value(U64_SERIALIZED_LENGTH, ext_ffi::casper_get_block_height)
+}
+
+/// Returns the [`EraId`] of the era in which the block containing the deploy was created.
+pub fn get_era_id() -> EraId {
+    read_fixed_size_value(U64_SERIALIZED_LENGTH, ext_ffi::casper_get_era_id)
+}
+
+/// Returns the [`ProtocolVersion`] under which the deploy is executed.
+pub fn get_protocol_version() -> ProtocolVersion {
+    read_fixed_size_value(
+        SEM_VER_SERIALIZED_LENGTH,
+        ext_ffi::casper_get_protocol_version,
+    )
+}
+
+/// Returns the hash of the parent of the block in which the deploy is executed.
+pub fn get_parent_block_hash() -> [u8; BLAKE2B_DIGEST_LENGTH] {
+    read_fixed_size_value(BLAKE2B_DIGEST_LENGTH, ext_ffi::casper_get_parent_block_hash)
+}
+
+/// Returns the root hash of the global state on top of which the deploy is executed.
+///
+/// This is the deploy's own pre-state, i.e. the state after all preceding deploys in the same block
+/// have been applied.  It only matches the state root hash of the parent block for the first deploy
+/// in a block, and never matches the state root hash of the block containing the deploy.
+pub fn get_state_root_hash() -> [u8; BLAKE2B_DIGEST_LENGTH] {
+    read_fixed_size_value(BLAKE2B_DIGEST_LENGTH, ext_ffi::casper_get_state_root_hash)
+}
+
+/// Calls a host function which writes a value of the given serialized length to the provided
+/// pointer, and deserializes the result.
+fn read_fixed_size_value<T: FromBytes>(
+    serialized_length: usize,
+    host_function: unsafe extern "C" fn(*mut u8),
+) -> T {
+    let dest_non_null_ptr = contract_api::alloc_bytes(serialized_length);
+    let bytes = unsafe {
+        host_function(dest_non_null_ptr.as_ptr());
+        Vec::from_raw_parts(
+            dest_non_null_ptr.as_ptr(),
+            serialized_length,
+            serialized_length,
+        )
+    };
+    bytesrepr::deserialize(bytes).unwrap_or_revert()
+}
+
 /// Returns the requested named [`Key`] from the current context.
 ///
 /// The current context is either the caller's account or a stored contract depending on whether the
@@ -354,6 +405,49 @@ pub fn random_bytes() -> [u8; RANDOM_BYTES_COUNT] {
     ret
 }
 
+/// Emits a message with the given `payload` under `topic`.
+///
+/// The message is attributed to the current context, i.e. either the caller's account or a stored
+/// contract depending on whether the currently-executing module is a direct call or a sub-call
+/// respectively.  Messages are only published if the deploy executes successfully.
+///
+/// The `topic` must not be empty and must be no longer than
+/// [`CONTRACT_MESSAGE_TOPIC_MAX_LENGTH`](casper_types::CONTRACT_MESSAGE_TOPIC_MAX_LENGTH) bytes,
+/// otherwise execution is reverted with [`ApiError::InvalidArgument`].
+pub fn emit_message<T: CLTyped + ToBytes>(topic: &str, payload: T) {
+    let payload = CLValue::from_t(payload).unwrap_or_revert();
+    let (topic_ptr, topic_size, _bytes) = contract_api::to_ptr(topic);
+    let (payload_ptr, payload_size, _bytes2) = contract_api::to_ptr(payload);
+    let result =
+        unsafe { ext_ffi::casper_emit_message(topic_ptr, topic_size, payload_ptr, payload_size) };
+    api_error::result_from(result).unwrap_or_revert();
+}
+
+/// Verifies that `signature` was produced by signing `message` with the secret key corresponding
+/// to `public_key`.
+///
+/// Both Ed25519 and secp256k1 keys are supported.  Returns [`ApiError::InvalidSignature`] if the
+/// signature is invalid, or if the signature and public key are of different types.
+pub fn verify_signature(
+    message: &[u8],
+    signature: &Signature,
+    public_key: &PublicKey,
+) -> Result<(), ApiError> {
+    let signature_bytes = signature.to_bytes().unwrap_or_revert();
+    let public_key_bytes = public_key.to_bytes().unwrap_or_revert();
+    let result = unsafe {
+        ext_ffi::casper_verify_signature(
+            message.as_ptr(),
+            message.len(),
+            signature_bytes.as_ptr(),
+            signature_bytes.len(),
+            public_key_bytes.as_ptr(),
+            public_key_bytes.len(),
+        )
+    };
+    api_error::result_from(result)
+}
+
 fn read_host_buffer_into(dest: &mut [u8]) -> Result<usize, ApiError> {
     let mut bytes_written = MaybeUninit::uninit();
     let ret = unsafe {
@@ -75,6 +75,18 @@ pub fn add<T: CLTyped + ToBytes>(uref: URef, value: T) {
     }
 }
 
+/// Removes the value stored under `uref` from the global state.
+///
+/// Reverts with [`ApiError::PermissionDenied`] if `uref` is not writeable by the caller.
+pub fn remove(uref: URef) {
+    let (uref_ptr, uref_size, _bytes) = contract_api::to_ptr(uref);
+    let result = unsafe {
+        let ret = ext_ffi::casper_remove_uref(uref_ptr, uref_size);
+        api_error::result_from(ret)
+    };
+    result.unwrap_or_revert()
+}
+
 /// Returns a new unforgeable pointer, where the value is initialized to `init`.
 pub fn new_uref<T: CLTyped + ToBytes>(init: T) -> URef {
     let uref_non_null_ptr = contract_api::alloc_bytes(UREF_SERIALIZED_LENGTH);
@@ -428,6 +440,30 @@ pub fn dictionary_put<V: CLTyped + ToBytes>(
     result.unwrap_or_revert()
 }
 
+/// Removes the value under `dictionary_item_key` in the dictionary accessed by
+/// `dictionary_seed_uref`.
+pub fn dictionary_remove(dictionary_seed_uref: URef, dictionary_item_key: &str) {
+    let (uref_ptr, uref_size, _bytes) = contract_api::to_ptr(dictionary_seed_uref);
+    let (dictionary_item_key_ptr, dictionary_item_key_size) =
+        contract_api::dictionary_item_key_to_ptr(dictionary_item_key);
+
+    if dictionary_item_key_size > DICTIONARY_ITEM_KEY_MAX_LENGTH {
+        revert(ApiError::DictionaryItemKeyExceedsLength)
+    }
+
+    let result = unsafe {
+        let ret = ext_ffi::casper_remove_dictionary_item(
+            uref_ptr,
+            uref_size,
+            dictionary_item_key_ptr,
+            dictionary_item_key_size,
+        );
+        api_error::result_from(ret)
+    };
+
+    result.unwrap_or_revert()
+}
+
 /// Reads value under `dictionary_key` in the global state.
 pub fn dictionary_read<T: CLTyped + FromBytes>(dictionary_key: Key) -> Result<Option<T>, ApiError> {
     if !dictionary_key.is_dictionary_key() {
@@ -790,4 +790,107 @@ extern "C" {
     /// * `out_ptr` - pointer to the location where argument bytes will be copied from the host side
     /// * `out_size` - size of output pointer
     pub fn casper_random_bytes(out_ptr: *mut u8, out_size: usize) -> i32;
+    /// Emits a message under the given topic, to be published once the deploy has been executed.
+    ///
+    /// # Arguments
+    /// * `topic_ptr` - pointer to bytes representing the serialized topic
+    /// * `topic_size` - size of the serialized topic (in bytes)
+    /// * `payload_ptr` - pointer to bytes representing the serialized `CLValue` payload
+    /// * `payload_size` - size of the serialized payload (in bytes)
+    pub fn casper_emit_message(
+        topic_ptr: *const u8,
+        topic_size: usize,
+        payload_ptr: *const u8,
+        payload_size: usize,
+    ) -> i32;
+    /// Verifies that the given signature was produced by signing the message with the secret key
+    /// corresponding to the given public key.
+    ///
+    /// # Arguments
+    /// * `message_ptr` - pointer to bytes representing the signed message
+    /// * `message_size` - size of the message (in bytes)
+    /// * `signature_ptr` - pointer to bytes representing the serialized `Signature`
+    /// * `signature_size` - size of the serialized signature (in bytes)
+    /// * `public_key_ptr` - pointer to bytes representing the serialized `PublicKey`
+    /// * `public_key_size` - size of the serialized public key (in bytes)
+    pub fn casper_verify_signature(
+        message_ptr: *const u8,
+        message_size: usize,
+        signature_ptr: *const u8,
+        signature_size: usize,
+        public_key_ptr: *const u8,
+        public_key_size: usize,
+    ) -> i32;
+    /// This function gets the height of the block this deploy is included in. The return value
+    /// is always a 64-bit unsigned integer. It is up to the caller to ensure there are 8 bytes
+    /// allocated at `dest_ptr`, otherwise data corruption in the wasm memory may occur.
+    ///
+    /// # Arguments
+    ///
+    /// * `dest_ptr` - pointer in wasm memory where to write the result
+    pub fn casper_get_block_height(dest_ptr: *mut u8);
+    /// This function gets the id of the era in which the block this deploy is included in was
+    /// created. The return value is always a 64-bit unsigned integer. It is up to the caller to
+    /// ensure there are 8 bytes allocated at `dest_ptr`, otherwise data corruption in the wasm
+    /// memory may occur.
+    ///
+    /// # Arguments
+    ///
+    /// * `dest_ptr` - pointer in wasm memory where to write the result
+    pub fn casper_get_era_id(dest_ptr: *mut u8);
+    /// This function gets the protocol version under which this deploy is executed. The return
+    /// value is always three 32-bit unsigned integers representing the major, minor and patch
+    /// versions. It is up to the caller to ensure there are 12 bytes allocated at `dest_ptr`,
+    /// otherwise data corruption in the wasm memory may occur.
+    ///
+    /// # Arguments
+    ///
+    /// * `dest_ptr` - pointer in wasm memory where to write the result
+    pub fn casper_get_protocol_version(dest_ptr: *mut u8);
+    /// This function gets the hash of the parent of the block this deploy is included in. The
+    /// return value is always a 32-byte BLAKE2b hash digest. It is up to the caller to ensure
+    /// there are 32 bytes allocated at `dest_ptr`, otherwise data corruption in the wasm memory
+    /// may occur.
+    ///
+    /// # Arguments
+    ///
+    /// * `dest_ptr` - pointer in wasm memory where to write the result
+    pub fn casper_get_parent_block_hash(dest_ptr: *mut u8);
+    /// This function gets the root hash of the global state on top of which this deploy is
+    /// executed, i.e. the state after all preceding deploys in the same block have been applied.
+    /// The return value is always a 32-byte BLAKE2b hash digest. It is up to the caller to ensure
+    /// there are 32 bytes allocated at `dest_ptr`, otherwise data corruption in the wasm memory may
+    /// occur.
+    ///
+    /// # Arguments
+    ///
+    /// * `dest_ptr` - pointer in wasm memory where to write the result
+    pub fn casper_get_state_root_hash(dest_ptr: *mut u8);
+    /// This function removes the value stored under the provided [`URef`] (read via
+    /// de-serializing the bytes in wasm memory from offset `uref_ptr` to `uref_ptr + uref_size`)
+    /// from the global state. This function will cause a `Trap` if the uref fails to de-serialize,
+    /// and returns [`ApiError::PermissionDenied`](crate::ApiError::PermissionDenied) if writing to
+    /// that uref is not permitted.
+    ///
+    /// # Arguments
+    ///
+    /// * `uref_ptr` - pointer to bytes representing the uref to remove
+    /// * `uref_size` - size of the uref (in bytes)
+    pub fn casper_remove_uref(uref_ptr: *const u8, uref_size: usize) -> i32;
+    /// This function removes the item stored under the provided dictionary item key (read via
+    /// de-serializing the bytes in wasm memory from offset `key_ptr` to `key_ptr + key_size`)
+    /// from the dictionary referenced by the provided seed [`URef`].
+    ///
+    /// # Arguments
+    ///
+    /// * `uref_ptr` - pointer to bytes representing the dictionary seed uref
+    /// * `uref_size` - size of the uref (in bytes)
+    /// * `key_ptr` - pointer to bytes representing the dictionary item key to remove
+    /// * `key_size` - size of the key (in bytes)
+    pub fn casper_remove_dictionary_item(
+        uref_ptr: *const u8,
+        uref_size: usize,
+        key_ptr: *const u8,
+        key_size: usize,
+    ) -> i32;
 }
@@ -0,0 +1,18 @@
+#![no_std]
+#![no_main]
+
+extern crate alloc;
+
+use alloc::string::String;
+
+use casper_contract::contract_api::runtime;
+
+const ARG_TOPIC: &str = "topic";
+const ARG_VALUE: &str = "value";
+
+#[no_mangle]
+pub extern "C" fn call() {
+    let topic: String = runtime::get_named_arg(ARG_TOPIC);
+    let value: u64 = runtime::get_named_arg(ARG_VALUE);
+    runtime::emit_message(&topic, value);
+}
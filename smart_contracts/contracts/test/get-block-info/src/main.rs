@@ -0,0 +1,32 @@
+#![no_std]
+#![no_main]
+
+extern crate alloc;
+
+use alloc::string::ToString;
+
+use casper_contract::contract_api::{runtime, storage};
+use casper_types::{bytesrepr::ToBytes, CLTyped};
+
+const BLOCK_HEIGHT_KEY: &str = "block_height";
+const ERA_ID_KEY: &str = "era_id";
+const PROTOCOL_VERSION_KEY: &str = "protocol_version";
+const PARENT_BLOCK_HASH_KEY: &str = "parent_block_hash";
+const STATE_ROOT_HASH_KEY: &str = "state_root_hash";
+
+fn store<T: CLTyped + ToBytes>(name: &str, value: T) {
+    let uref = storage::new_uref(value);
+    runtime::put_key(name, uref.into());
+}
+
+#[no_mangle]
+pub extern "C" fn call() {
+    store(BLOCK_HEIGHT_KEY, runtime::get_block_height());
+    store(ERA_ID_KEY, runtime::get_era_id());
+    store(
+        PROTOCOL_VERSION_KEY,
+        runtime::get_protocol_version().to_string(),
+    );
+    store(PARENT_BLOCK_HASH_KEY, runtime::get_parent_block_hash());
+    store(STATE_ROOT_HASH_KEY, runtime::get_state_root_hash());
+}
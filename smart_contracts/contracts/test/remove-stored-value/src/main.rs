@@ -0,0 +1,75 @@
+#![no_std]
+#![no_main]
+
+extern crate alloc;
+
+use alloc::string::String;
+
+use casper_contract::{
+    contract_api::{runtime, storage},
+    unwrap_or_revert::UnwrapOrRevert,
+};
+use casper_types::ApiError;
+
+const ARG_ACTION: &str = "action";
+const ACTION_STORE: &str = "store";
+const ACTION_REMOVE: &str = "remove";
+const ACTION_REMOVE_READ_ONLY: &str = "remove_read_only";
+
+const UREF_NAME: &str = "value";
+const DICTIONARY_NAME: &str = "dictionary";
+const DICTIONARY_ITEM_KEY: &str = "item";
+const VALUE: u64 = 42;
+
+#[repr(u16)]
+enum Error {
+    UnknownAction = 0,
+    ValueNotRemoved = 1,
+    DictionaryItemNotRemoved = 2,
+}
+
+impl From<Error> for ApiError {
+    fn from(error: Error) -> Self {
+        ApiError::User(error as u16)
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn call() {
+    let action: String = runtime::get_named_arg(ARG_ACTION);
+
+    match action.as_str() {
+        ACTION_STORE => {
+            let uref = storage::new_uref(VALUE);
+            runtime::put_key(UREF_NAME, uref.into());
+
+            let dictionary_uref = storage::new_dictionary(DICTIONARY_NAME).unwrap_or_revert();
+            storage::dictionary_put(dictionary_uref, DICTIONARY_ITEM_KEY, VALUE);
+        }
+        ACTION_REMOVE => {
+            let uref = runtime::get_key(UREF_NAME)
+                .and_then(|key| key.into_uref())
+                .unwrap_or_revert_with(ApiError::MissingKey);
+            storage::remove(uref);
+            if storage::read::<u64>(uref).unwrap_or_revert().is_some() {
+                runtime::revert(Error::ValueNotRemoved);
+            }
+
+            let dictionary_uref = runtime::get_key(DICTIONARY_NAME)
+                .and_then(|key| key.into_uref())
+                .unwrap_or_revert_with(ApiError::MissingKey);
+            storage::dictionary_remove(dictionary_uref, DICTIONARY_ITEM_KEY);
+            if storage::dictionary_get::<u64>(dictionary_uref, DICTIONARY_ITEM_KEY)
+                .unwrap_or_revert()
+                .is_some()
+            {
+                runtime::revert(Error::DictionaryItemNotRemoved);
+            }
+        }
+        ACTION_REMOVE_READ_ONLY => {
+            let uref = storage::new_uref(VALUE);
+            storage::remove(uref.into_read());
+        }
+        _ => runtime::revert(Error::UnknownAction),
+    }
+}
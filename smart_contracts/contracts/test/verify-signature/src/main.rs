@@ -0,0 +1,22 @@
+#![no_std]
+#![no_main]
+
+use casper_contract::{contract_api::runtime, unwrap_or_revert::UnwrapOrRevert};
+use casper_types::{
+    bytesrepr::{self, Bytes},
+    PublicKey, Signature,
+};
+
+const ARG_MESSAGE: &str = "message";
+const ARG_SIGNATURE: &str = "signature";
+const ARG_PUBLIC_KEY: &str = "public_key";
+
+#[no_mangle]
+pub extern "C" fn call() {
+    let message: Bytes = runtime::get_named_arg(ARG_MESSAGE);
+    let signature_bytes: Bytes = runtime::get_named_arg(ARG_SIGNATURE);
+    let public_key: PublicKey = runtime::get_named_arg(ARG_PUBLIC_KEY);
+
+    let signature: Signature = bytesrepr::deserialize(signature_bytes.into()).unwrap_or_revert();
+    runtime::verify_signature(&message, &signature, &public_key).unwrap_or_revert();
+}
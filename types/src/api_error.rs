@@ -350,6 +350,12 @@ pub enum ApiError {
     /// assert_eq!(ApiError::from(40), ApiError::NonRepresentableSerialization);
     /// ```
     NonRepresentableSerialization,
+    /// A signature could not be verified against the given message and public key.
+    /// ```
+    /// # use casper_types::ApiError;
+    /// assert_eq!(ApiError::from(41), ApiError::InvalidSignature);
+    /// ```
+    InvalidSignature,
     /// Error specific to Auction contract. See
     /// [casper_types::system::auction::Error](crate::system::auction::Error).
     /// ```
@@ -541,6 +547,7 @@ impl From<ApiError> for u32 {
             ApiError::MissingSystemContractHash => 38,
             ApiError::ExceededRecursionDepth => 39,
             ApiError::NonRepresentableSerialization => 40,
+            ApiError::InvalidSignature => 41,
             ApiError::AuctionError(value) => AUCTION_ERROR_OFFSET + u32::from(value),
             ApiError::ContractHeader(value) => HEADER_ERROR_OFFSET + u32::from(value),
             ApiError::Mint(value) => MINT_ERROR_OFFSET + u32::from(value),
@@ -593,6 +600,7 @@ impl From<u32> for ApiError {
             38 => ApiError::MissingSystemContractHash,
             39 => ApiError::ExceededRecursionDepth,
             40 => ApiError::NonRepresentableSerialization,
+            41 => ApiError::InvalidSignature,
             USER_ERROR_MIN..=USER_ERROR_MAX => ApiError::User(value as u16),
             HP_ERROR_MIN..=HP_ERROR_MAX => ApiError::HandlePayment(value as u8),
             MINT_ERROR_MIN..=MINT_ERROR_MAX => ApiError::Mint(value as u8),
@@ -652,6 +660,7 @@ impl Debug for ApiError {
                 write!(f, "ApiError::NonRepresentableSerialization")?
             }
             ApiError::ExceededRecursionDepth => write!(f, "ApiError::ExceededRecursionDepth")?,
+            ApiError::InvalidSignature => write!(f, "ApiError::InvalidSignature")?,
             ApiError::AuctionError(value) => write!(
                 f,
                 "ApiError::AuctionError({:?})",
@@ -860,6 +869,7 @@ mod tests {
         round_trip(Err(ApiError::HostBufferFull));
         round_trip(Err(ApiError::AllocLayout));
         round_trip(Err(ApiError::NonRepresentableSerialization));
+        round_trip(Err(ApiError::InvalidSignature));
         round_trip(Err(ApiError::ContractHeader(0)));
         round_trip(Err(ApiError::ContractHeader(u8::MAX)));
         round_trip(Err(ApiError::Mint(0)));
@@ -0,0 +1,157 @@
+// TODO - remove once schemars stops causing warning.
+#![allow(clippy::field_reassign_with_default)]
+
+use alloc::{string::String, vec::Vec};
+
+#[cfg(feature = "datasize")]
+use datasize::DataSize;
+#[cfg(feature = "json-schema")]
+use schemars::JsonSchema;
+use serde::{de::Error as SerdeError, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{
+    bytesrepr::{self, FromBytes, ToBytes},
+    CLValue, Key,
+};
+
+/// The maximum length in bytes of the topic of a [`ContractMessage`].
+pub const CONTRACT_MESSAGE_TOPIC_MAX_LENGTH: usize = 128;
+
+/// A message emitted by a contract or session code during the execution of a deploy.
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "datasize", derive(DataSize))]
+#[cfg_attr(feature = "json-schema", derive(JsonSchema))]
+#[serde(deny_unknown_fields)]
+pub struct ContractMessage {
+    /// The key of the contract or account which emitted the message.
+    #[serde(
+        serialize_with = "serialize_emitter",
+        deserialize_with = "deserialize_emitter"
+    )]
+    #[cfg_attr(feature = "json-schema", schemars(with = "String"))]
+    emitter: Key,
+    /// The topic under which the message was emitted.
+    topic: String,
+    /// The message payload.
+    payload: CLValue,
+}
+
+impl ContractMessage {
+    /// Creates a [`ContractMessage`].
+    pub fn new(emitter: Key, topic: String, payload: CLValue) -> Self {
+        ContractMessage {
+            emitter,
+            topic,
+            payload,
+        }
+    }
+
+    /// Returns the key of the contract or account which emitted the message.
+    pub fn emitter(&self) -> &Key {
+        &self.emitter
+    }
+
+    /// Returns the topic under which the message was emitted.
+    pub fn topic(&self) -> &str {
+        &self.topic
+    }
+
+    /// Returns the message payload.
+    pub fn payload(&self) -> &CLValue {
+        &self.payload
+    }
+}
+
+/// Serializes the emitter as its formatted string in human-readable formats, consistent with the
+/// keys of `TransformEntry`s.
+fn serialize_emitter<S: Serializer>(emitter: &Key, serializer: S) -> Result<S::Ok, S::Error> {
+    if serializer.is_human_readable() {
+        emitter.to_formatted_string().serialize(serializer)
+    } else {
+        emitter.serialize(serializer)
+    }
+}
+
+fn deserialize_emitter<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Key, D::Error> {
+    if deserializer.is_human_readable() {
+        let formatted_key = String::deserialize(deserializer)?;
+        Key::from_formatted_str(&formatted_key).map_err(SerdeError::custom)
+    } else {
+        Key::deserialize(deserializer)
+    }
+}
+
+impl ToBytes for ContractMessage {
+    fn to_bytes(&self) -> Result<Vec<u8>, bytesrepr::Error> {
+        let mut result = bytesrepr::allocate_buffer(self)?;
+        self.write_bytes(&mut result)?;
+        Ok(result)
+    }
+
+    fn serialized_length(&self) -> usize {
+        self.emitter.serialized_length()
+            + self.topic.serialized_length()
+            + self.payload.serialized_length()
+    }
+
+    fn write_bytes(&self, writer: &mut Vec<u8>) -> Result<(), bytesrepr::Error> {
+        self.emitter.write_bytes(writer)?;
+        self.topic.write_bytes(writer)?;
+        self.payload.write_bytes(writer)?;
+        Ok(())
+    }
+}
+
+impl FromBytes for ContractMessage {
+    fn from_bytes(bytes: &[u8]) -> Result<(Self, &[u8]), bytesrepr::Error> {
+        let (emitter, rem) = Key::from_bytes(bytes)?;
+        let (topic, rem) = String::from_bytes(rem)?;
+        let (payload, rem) = CLValue::from_bytes(rem)?;
+        Ok((
+            ContractMessage {
+                emitter,
+                topic,
+                payload,
+            },
+            rem,
+        ))
+    }
+}
+
+/// Generators for a `ContractMessage`
+#[cfg(any(feature = "testing", test))]
+pub(crate) mod gens {
+    use proptest::prelude::Strategy;
+
+    use super::ContractMessage;
+    use crate::gens::{cl_value_arb, key_arb};
+
+    /// Creates an arbitrary `ContractMessage`
+    pub fn contract_message_arb() -> impl Strategy<Value = ContractMessage> {
+        (key_arb(), "[a-z_]{1,128}", cl_value_arb())
+            .prop_map(|(emitter, topic, payload)| ContractMessage::new(emitter, topic, payload))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use crate::bytesrepr;
+
+    use super::gens;
+
+    proptest! {
+        #[test]
+        fn test_serialization_roundtrip(message in gens::contract_message_arb()) {
+            bytesrepr::test_serialization_roundtrip(&message)
+        }
+
+        #[test]
+        fn json_roundtrip(message in gens::contract_message_arb()) {
+            let json_string = serde_json::to_string_pretty(&message).unwrap();
+            let decoded: super::ContractMessage = serde_json::from_str(&json_string).unwrap();
+            assert_eq!(message, decoded);
+        }
+    }
+}
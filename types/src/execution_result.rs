@@ -3,6 +3,11 @@
 //!
 //! It is stored as metadata related to a given deploy, and made available to clients via the
 //! JSON-RPC API.
+//!
+//! The messages emitted by contracts during a successful execution were added after the original
+//! binary format was fixed.  A successful result without messages keeps the original encoding in
+//! both `bytesrepr` and non-human-readable serde formats, so that previously-stored results, and
+//! the hashes computed over them, are unaffected.
 
 // TODO - remove once schemars stops causing warning.
 #![allow(clippy::field_reassign_with_default)]
@@ -30,7 +35,7 @@ use rand::{
 };
 #[cfg(feature = "json-schema")]
 use schemars::JsonSchema;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 #[cfg(feature = "json-schema")]
 use crate::KEY_HASH_LENGTH;
@@ -38,7 +43,7 @@ use crate::{
     account::AccountHash,
     bytesrepr::{self, FromBytes, ToBytes, U8_SERIALIZED_LENGTH},
     system::auction::{Bid, EraInfo, UnbondingPurse},
-    CLValue, DeployInfo, NamedKey, Transfer, TransferAddr, U128, U256, U512,
+    CLValue, ContractMessage, DeployInfo, Key, NamedKey, Transfer, TransferAddr, U128, U256, U512,
 };
 
 #[derive(FromPrimitive, ToPrimitive, Debug)]
@@ -46,6 +51,8 @@ use crate::{
 enum ExecutionResultTag {
     Failure = 0,
     Success = 1,
+    /// A successful result followed by the messages emitted during execution.
+    SuccessWithMessages = 2,
 }
 
 impl TryFrom<u8> for ExecutionResultTag {
@@ -94,6 +101,7 @@ enum TransformTag {
     AddUInt512 = 15,
     AddKeys = 16,
     Failure = 17,
+    Prune = 18,
 }
 
 impl TryFrom<u8> for TransformTag {
@@ -146,6 +154,7 @@ static EXECUTION_RESULT: Lazy<ExecutionResult> = Lazy::new(|| {
         effect,
         transfers,
         cost: U512::from(123_456),
+        messages: vec![],
     }
 });
 
@@ -153,7 +162,7 @@ static EXECUTION_RESULT: Lazy<ExecutionResult> = Lazy::new(|| {
 #[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Debug)]
 #[cfg_attr(feature = "datasize", derive(DataSize))]
 #[cfg_attr(feature = "json-schema", derive(JsonSchema))]
-#[serde(deny_unknown_fields)]
+#[serde(remote = "Self", deny_unknown_fields)]
 pub enum ExecutionResult {
     /// The result of a failed execution.
     Failure {
@@ -174,6 +183,9 @@ pub enum ExecutionResult {
         transfers: Vec<TransferAddr>,
         /// The cost of executing the deploy.
         cost: U512,
+        /// The messages emitted by contracts during execution.
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        messages: Vec<ContractMessage>,
     },
 }
 
@@ -193,10 +205,14 @@ impl ExecutionResult {
                 cost: _,
                 error_message: _,
             } => ExecutionResultTag::Failure,
+            ExecutionResult::Success { messages, .. } if !messages.is_empty() => {
+                ExecutionResultTag::SuccessWithMessages
+            }
             ExecutionResult::Success {
                 effect: _,
                 transfers: _,
                 cost: _,
+                messages: _,
             } => ExecutionResultTag::Success,
         }
     }
@@ -241,10 +257,20 @@ impl Distribution<ExecutionResult> for Standard {
                 error_message: format!("Error message {}", rng.gen::<u64>()),
             }
         } else {
+            let message_count = rng.gen_range(0..3);
+            let mut messages = vec![];
+            for _ in 0..message_count {
+                messages.push(ContractMessage::new(
+                    Key::Hash(rng.gen()),
+                    format!("topic {}", rng.gen::<u64>()),
+                    CLValue::from_t(rng.gen::<u64>()).unwrap(),
+                ))
+            }
             ExecutionResult::Success {
                 effect: execution_effect,
                 transfers,
                 cost: rng.gen::<u64>().into(),
+                messages,
             }
         }
     }
@@ -271,10 +297,14 @@ impl ToBytes for ExecutionResult {
                 effect,
                 transfers,
                 cost,
+                messages,
             } => {
                 buffer.extend(effect.to_bytes()?);
                 buffer.extend(transfers.to_bytes()?);
                 buffer.extend(cost.to_bytes()?);
+                if !messages.is_empty() {
+                    buffer.extend(messages.to_bytes()?);
+                }
             }
         }
         Ok(buffer)
@@ -298,10 +328,17 @@ impl ToBytes for ExecutionResult {
                     effect: execution_effect,
                     transfers,
                     cost,
+                    messages,
                 } => {
+                    let messages_length = if messages.is_empty() {
+                        0
+                    } else {
+                        messages.serialized_length()
+                    };
                     execution_effect.serialized_length()
                         + transfers.serialized_length()
                         + cost.serialized_length()
+                        + messages_length
                 }
             }
     }
@@ -332,6 +369,24 @@ impl FromBytes for ExecutionResult {
                     effect: execution_effect,
                     transfers,
                     cost,
+                    messages: vec![],
+                };
+                Ok((execution_result, remainder))
+            }
+            ExecutionResultTag::SuccessWithMessages => {
+                let (execution_effect, remainder) = ExecutionEffect::from_bytes(remainder)?;
+                let (transfers, remainder) = Vec::<TransferAddr>::from_bytes(remainder)?;
+                let (cost, remainder) = U512::from_bytes(remainder)?;
+                let (messages, remainder) = Vec::<ContractMessage>::from_bytes(remainder)?;
+                // Only results with messages are encoded this way, so anything else is malformed.
+                if messages.is_empty() {
+                    return Err(bytesrepr::Error::Formatting);
+                }
+                let execution_result = ExecutionResult::Success {
+                    effect: execution_effect,
+                    transfers,
+                    cost,
+                    messages,
                 };
                 Ok((execution_result, remainder))
             }
@@ -339,6 +394,139 @@ impl FromBytes for ExecutionResult {
     }
 }
 
+impl Serialize for ExecutionResult {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            return ExecutionResult::serialize(self, serializer);
+        }
+        let binary_result = match self {
+            ExecutionResult::Failure {
+                effect,
+                transfers,
+                cost,
+                error_message,
+            } => BinaryExecutionResultRef::Failure {
+                effect,
+                transfers,
+                cost,
+                error_message,
+            },
+            ExecutionResult::Success {
+                effect,
+                transfers,
+                cost,
+                messages,
+            } if messages.is_empty() => BinaryExecutionResultRef::Success {
+                effect,
+                transfers,
+                cost,
+            },
+            ExecutionResult::Success {
+                effect,
+                transfers,
+                cost,
+                messages,
+            } => BinaryExecutionResultRef::SuccessWithMessages {
+                effect,
+                transfers,
+                cost,
+                messages,
+            },
+        };
+        binary_result.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ExecutionResult {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            return ExecutionResult::deserialize(deserializer);
+        }
+        let execution_result = match BinaryExecutionResult::deserialize(deserializer)? {
+            BinaryExecutionResult::Failure {
+                effect,
+                transfers,
+                cost,
+                error_message,
+            } => ExecutionResult::Failure {
+                effect,
+                transfers,
+                cost,
+                error_message,
+            },
+            BinaryExecutionResult::Success {
+                effect,
+                transfers,
+                cost,
+            } => ExecutionResult::Success {
+                effect,
+                transfers,
+                cost,
+                messages: vec![],
+            },
+            BinaryExecutionResult::SuccessWithMessages {
+                effect,
+                transfers,
+                cost,
+                messages,
+            } => ExecutionResult::Success {
+                effect,
+                transfers,
+                cost,
+                messages,
+            },
+        };
+        Ok(execution_result)
+    }
+}
+
+/// The representation of an [`ExecutionResult`] in non-human-readable serde formats, mirroring its
+/// `bytesrepr` encoding by only using a distinct variant for a successful result with messages.
+#[derive(Serialize)]
+#[serde(rename = "ExecutionResult")]
+enum BinaryExecutionResultRef<'a> {
+    Failure {
+        effect: &'a ExecutionEffect,
+        transfers: &'a Vec<TransferAddr>,
+        cost: &'a U512,
+        error_message: &'a String,
+    },
+    Success {
+        effect: &'a ExecutionEffect,
+        transfers: &'a Vec<TransferAddr>,
+        cost: &'a U512,
+    },
+    SuccessWithMessages {
+        effect: &'a ExecutionEffect,
+        transfers: &'a Vec<TransferAddr>,
+        cost: &'a U512,
+        messages: &'a Vec<ContractMessage>,
+    },
+}
+
+/// The owned counterpart of [`BinaryExecutionResultRef`].
+#[derive(Deserialize)]
+#[serde(rename = "ExecutionResult")]
+enum BinaryExecutionResult {
+    Failure {
+        effect: ExecutionEffect,
+        transfers: Vec<TransferAddr>,
+        cost: U512,
+        error_message: String,
+    },
+    Success {
+        effect: ExecutionEffect,
+        transfers: Vec<TransferAddr>,
+        cost: U512,
+    },
+    SuccessWithMessages {
+        effect: ExecutionEffect,
+        transfers: Vec<TransferAddr>,
+        cost: U512,
+        messages: Vec<ContractMessage>,
+    },
+}
+
 /// The journal of execution transforms from a single deploy.
 #[derive(Clone, Eq, PartialEq, Serialize, Deserialize, Default, Debug)]
 #[cfg_attr(feature = "datasize", derive(DataSize))]
@@ -546,6 +734,8 @@ pub enum Transform {
     AddKeys(Vec<NamedKey>),
     /// A failed transformation, containing an error message.
     Failure(String),
+    /// Removes the value from global state.
+    Prune,
 }
 
 impl Transform {
@@ -569,6 +759,7 @@ impl Transform {
             Transform::AddUInt512(_) => TransformTag::AddUInt512,
             Transform::AddKeys(_) => TransformTag::AddKeys,
             Transform::Failure(_) => TransformTag::Failure,
+            Transform::Prune => TransformTag::Prune,
         }
     }
 }
@@ -625,6 +816,7 @@ impl ToBytes for Transform {
             Transform::Failure(value) => {
                 buffer.extend(value.to_bytes()?);
             }
+            Transform::Prune => {}
         }
         Ok(buffer)
     }
@@ -646,7 +838,8 @@ impl ToBytes for Transform {
             Transform::Identity
             | Transform::WriteContractWasm
             | Transform::WriteContract
-            | Transform::WriteContractPackage => 0,
+            | Transform::WriteContractPackage
+            | Transform::Prune => 0,
             Transform::WriteBid(value) => value.serialized_length(),
             Transform::WriteWithdraw(value) => value.serialized_length(),
         };
@@ -719,6 +912,7 @@ impl FromBytes for Transform {
                     <Vec<UnbondingPurse> as FromBytes>::from_bytes(remainder)?;
                 Ok((Transform::WriteWithdraw(unbonding_purses), remainder))
             }
+            TransformTag::Prune => Ok((Transform::Prune, remainder)),
         }
     }
 }
@@ -726,7 +920,7 @@ impl FromBytes for Transform {
 impl Distribution<Transform> for Standard {
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Transform {
         // TODO - include WriteDeployInfo and WriteTransfer as options
-        match rng.gen_range(0..13) {
+        match rng.gen_range(0..14) {
             0 => Transform::Identity,
             1 => Transform::WriteCLValue(CLValue::from_t(true).unwrap()),
             2 => Transform::WriteAccount(AccountHash::new(rng.gen())),
@@ -749,6 +943,7 @@ impl Distribution<Transform> for Standard {
                 Transform::AddKeys(named_keys)
             }
             12 => Transform::Failure(rng.gen::<u64>().to_string()),
+            13 => Transform::Prune,
             _ => unreachable!(),
         }
     }
@@ -779,4 +974,84 @@ mod tests {
         let execution_result: ExecutionResult = rng.gen();
         bytesrepr::test_serialization_roundtrip(&execution_result);
     }
+
+    #[test]
+    fn should_keep_original_encoding_without_messages() {
+        let mut rng = get_rng();
+        let effect = ExecutionEffect::default();
+        let transfers = vec![TransferAddr::new(rng.gen())];
+        let cost = U512::from(rng.gen::<u64>());
+
+        let mut original_bytes = vec![ExecutionResultTag::Success as u8];
+        original_bytes.extend(effect.to_bytes().unwrap());
+        original_bytes.extend(transfers.to_bytes().unwrap());
+        original_bytes.extend(cost.to_bytes().unwrap());
+
+        let mut execution_result = ExecutionResult::Success {
+            effect,
+            transfers,
+            cost,
+            messages: vec![],
+        };
+        assert_eq!(execution_result.to_bytes().unwrap(), original_bytes);
+        assert_eq!(
+            bytesrepr::deserialize::<ExecutionResult>(original_bytes).unwrap(),
+            execution_result
+        );
+
+        if let ExecutionResult::Success { messages, .. } = &mut execution_result {
+            messages.push(ContractMessage::new(
+                Key::Hash(rng.gen()),
+                "topic".to_string(),
+                CLValue::from_t(1_u8).unwrap(),
+            ));
+        }
+        let bytes = execution_result.to_bytes().unwrap();
+        assert_eq!(bytes[0], ExecutionResultTag::SuccessWithMessages as u8);
+        bytesrepr::test_serialization_roundtrip(&execution_result);
+    }
+
+    #[test]
+    fn should_keep_original_bincode_encoding_without_messages() {
+        let mut rng = get_rng();
+        let effect = ExecutionEffect::default();
+        let transfers = vec![TransferAddr::new(rng.gen())];
+        let cost = U512::from(rng.gen::<u64>());
+
+        // The original derived encoding of the `Success` variant: its index then its three fields.
+        let original_bytes = bincode::serialize(&(1_u32, &effect, &transfers, &cost)).unwrap();
+
+        let mut execution_result = ExecutionResult::Success {
+            effect,
+            transfers,
+            cost,
+            messages: vec![],
+        };
+        assert_eq!(
+            bincode::serialize(&execution_result).unwrap(),
+            original_bytes
+        );
+        assert_eq!(
+            bincode::deserialize::<ExecutionResult>(&original_bytes).unwrap(),
+            execution_result
+        );
+
+        if let ExecutionResult::Success { messages, .. } = &mut execution_result {
+            messages.push(ContractMessage::new(
+                Key::Hash(rng.gen()),
+                "topic".to_string(),
+                CLValue::from_t(1_u8).unwrap(),
+            ));
+        }
+        let bytes = bincode::serialize(&execution_result).unwrap();
+        assert_eq!(
+            bincode::deserialize::<ExecutionResult>(&bytes).unwrap(),
+            execution_result
+        );
+        let json = serde_json::to_string(&execution_result).unwrap();
+        assert_eq!(
+            serde_json::from_str::<ExecutionResult>(&json).unwrap(),
+            execution_result
+        );
+    }
 }
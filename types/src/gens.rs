@@ -29,7 +29,10 @@ use crate::{
 };
 
 use crate::deploy_info::gens::{deploy_hash_arb, transfer_addr_arb};
-pub use crate::{deploy_info::gens::deploy_info_arb, transfer::gens::transfer_arb};
+pub use crate::{
+    contract_message::gens::contract_message_arb, deploy_info::gens::deploy_info_arb,
+    transfer::gens::transfer_arb,
+};
 
 pub fn u8_slice_32() -> impl Strategy<Value = [u8; 32]> {
     collection::vec(any::<u8>(), 32).prop_map(|b| {
@@ -29,6 +29,7 @@ pub mod bytesrepr;
 pub mod checksummed_hex;
 mod cl_type;
 mod cl_value;
+mod contract_message;
 mod contract_wasm;
 pub mod contracts;
 pub mod crypto;
@@ -67,6 +68,7 @@ pub use api_error::ApiError;
 pub use block_time::{BlockTime, BLOCKTIME_SERIALIZED_LENGTH};
 pub use cl_type::{named_key_type, CLType, CLTyped};
 pub use cl_value::{CLTypeMismatch, CLValue, CLValueError};
+pub use contract_message::{ContractMessage, CONTRACT_MESSAGE_TOPIC_MAX_LENGTH};
 pub use contract_wasm::{ContractWasm, ContractWasmHash};
 #[doc(inline)]
 pub use contracts::{
@@ -0,0 +1,205 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::{bail, Context};
+use lmdb::DatabaseFlags;
+use structopt::StructOpt;
+
+use casper_execution_engine::storage::{
+    global_state::lmdb::{LmdbGlobalState, TrieGcMarks, TrieGcProgress, TrieGcSummary},
+    transaction_source::lmdb::LmdbEnvironment,
+    trie_store::lmdb::LmdbTrieStore,
+};
+use casper_hashing::Digest;
+
+/// Filename of the global state database, as created by the execution engine.
+const EE_DB_FILENAME: &str = "data.lmdb";
+const DEFAULT_MAX_DB_SIZE: usize = 483_183_820_800; // 450 gb
+const DEFAULT_MAX_READERS: u32 = 512;
+
+#[derive(Debug, StructOpt)]
+#[structopt(
+    about = "Removes all tries which are not reachable from the given state root hashes from a \
+        global state database"
+)]
+struct Opts {
+    #[structopt(
+        long,
+        about = "Path to the directory containing the global state database to be garbage \
+            collected."
+    )]
+    source_path: PathBuf,
+
+    #[structopt(
+        long,
+        required_unless = "dry_run",
+        about = "Path to the directory in which the compacted global state database will be \
+            created."
+    )]
+    dest_path: Option<PathBuf>,
+
+    #[structopt(
+        long = "state-root-hash",
+        required = true,
+        number_of_values = 1,
+        parse(try_from_str = parse_digest),
+        about = "A state root hash whose tries should be retained. May be given multiple times."
+    )]
+    state_root_hashes: Vec<Digest>,
+
+    #[structopt(
+        long,
+        about = "Path to the directory in which the marks of the reachable tries are held while \
+            the tool runs. Defaults to a temporary directory within the source path, which is \
+            removed on completion."
+    )]
+    marks_path: Option<PathBuf>,
+
+    #[structopt(
+        long,
+        about = "Specify the max size (in bytes) that the underlying LMDB can grow to."
+    )]
+    max_db_size: Option<usize>,
+
+    #[structopt(
+        long,
+        about = "Only estimate the number and size of the tries to be removed, without writing \
+            a new database."
+    )]
+    dry_run: bool,
+}
+
+fn parse_digest(hex: &str) -> Result<Digest, String> {
+    Digest::from_hex(hex).map_err(|error| error.to_string())
+}
+
+fn open_global_state(
+    path: &Path,
+    max_db_size: usize,
+    state_root_hash: Digest,
+) -> Result<LmdbGlobalState, anyhow::Error> {
+    let environment = Arc::new(LmdbEnvironment::new(
+        path,
+        max_db_size,
+        DEFAULT_MAX_READERS,
+        false,
+    )?);
+    let trie_store = Arc::new(LmdbTrieStore::open(&environment, None)?);
+    Ok(LmdbGlobalState::new(
+        environment,
+        trie_store,
+        state_root_hash,
+    ))
+}
+
+fn create_global_state(path: &Path, max_db_size: usize) -> Result<LmdbGlobalState, anyhow::Error> {
+    fs::create_dir_all(path)?;
+    let environment = Arc::new(LmdbEnvironment::new(
+        path,
+        max_db_size,
+        DEFAULT_MAX_READERS,
+        false,
+    )?);
+    let trie_store = Arc::new(LmdbTrieStore::new(
+        &environment,
+        None,
+        DatabaseFlags::empty(),
+    )?);
+    Ok(LmdbGlobalState::empty(environment, trie_store)?)
+}
+
+fn report_progress(progress: TrieGcProgress) {
+    match progress {
+        TrieGcProgress::Marked {
+            state_root_hash,
+            marked,
+        } => println!(
+            "marked tries reachable from {}: {} tries marked in total",
+            state_root_hash, marked
+        ),
+        TrieGcProgress::Swept { scanned, retained } => {
+            println!("swept {} tries: {} retained", scanned, retained)
+        }
+    }
+}
+
+fn report_summary(summary: &TrieGcSummary, dry_run: bool) {
+    let (retain, remove) = if dry_run {
+        ("would be retained", "would be removed")
+    } else {
+        ("retained", "removed")
+    };
+    println!(
+        "{} of {} tries ({} of {} bytes) {}",
+        summary.retained_tries,
+        summary.total_tries,
+        summary.retained_bytes,
+        summary.total_bytes,
+        retain
+    );
+    println!(
+        "{} tries ({} bytes) {}",
+        summary.removed_tries(),
+        summary.removed_bytes(),
+        remove
+    );
+}
+
+fn main() -> Result<(), anyhow::Error> {
+    let opts = Opts::from_args();
+    let max_db_size = opts.max_db_size.unwrap_or(DEFAULT_MAX_DB_SIZE);
+
+    if !opts.source_path.join(EE_DB_FILENAME).exists() {
+        bail!(
+            "global state database not found at {}",
+            opts.source_path.join(EE_DB_FILENAME).display()
+        );
+    }
+    let source = open_global_state(&opts.source_path, max_db_size, opts.state_root_hashes[0])
+        .context("failed to open source global state")?;
+
+    // Keep the temporary directory alive until the tool completes.
+    let (_maybe_temp_dir, marks_path) = match opts.marks_path {
+        Some(marks_path) => {
+            fs::create_dir_all(&marks_path)?;
+            (None, marks_path)
+        }
+        None => {
+            let temp_dir = tempfile::Builder::new()
+                .prefix("trie-gc-marks")
+                .tempdir_in(&opts.source_path)?;
+            let marks_path = temp_dir.path().to_path_buf();
+            (Some(temp_dir), marks_path)
+        }
+    };
+    let marks =
+        TrieGcMarks::new(&marks_path, max_db_size).context("failed to create trie marks")?;
+
+    let summary = if opts.dry_run {
+        source.estimate_trie_gc(&opts.state_root_hashes, &marks, report_progress)?
+    } else {
+        let dest_path = opts
+            .dest_path
+            .expect("dest path is required unless dry run");
+        if dest_path.join(EE_DB_FILENAME).exists() {
+            bail!(
+                "refusing to overwrite existing global state database at {}",
+                dest_path.join(EE_DB_FILENAME).display()
+            );
+        }
+        let destination = create_global_state(&dest_path, max_db_size)
+            .context("failed to create destination global state")?;
+        source.compact_tries_into(
+            &opts.state_root_hashes,
+            &marks,
+            &destination,
+            report_progress,
+        )?
+    };
+
+    report_summary(&summary, opts.dry_run);
+    Ok(())
+}
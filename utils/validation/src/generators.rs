@@ -246,6 +246,10 @@ pub fn make_abi_test_fixtures() -> Result<TestFixtures, Error> {
             )
             .into()])?,
         );
+        transform.insert(
+            "Prune".to_string(),
+            ABITestCase::from_inputs(vec![Transform::Prune.into()])?,
+        );
         Fixture::ABI {
             name: "transform".to_string(),
             fixture: ABIFixture::from(transform),